@@ -5,7 +5,15 @@ use goose::config::{Config, ExtensionConfig};
 
 use crate::commands::acp::run_acp_agent;
 use crate::commands::bench::agent_generator;
+use crate::commands::config::{
+    handle_config_get, handle_config_set, handle_config_trust, handle_config_unset, ValueType,
+};
 use crate::commands::configure::handle_configure;
+use crate::commands::doctor::handle_doctor;
+use crate::commands::extensions::{
+    handle_extensions_add, handle_extensions_enable, handle_extensions_list,
+    handle_extensions_remove, handle_extensions_test, ExtensionTransport, NewExtension,
+};
 use crate::commands::info::handle_info;
 use crate::commands::project::{handle_project_default, handle_projects_interactive};
 use crate::commands::recipe::{handle_deeplink, handle_list, handle_validate};
@@ -15,10 +23,13 @@ use crate::commands::schedule::{
     handle_schedule_run_now, handle_schedule_services_status, handle_schedule_services_stop,
     handle_schedule_sessions,
 };
-use crate::commands::session::{handle_session_list, handle_session_remove};
+use crate::commands::session::{handle_session_list, handle_session_remove, ExportOptions};
 use crate::recipes::extract_from_cli::extract_recipe_info_from_cli;
+use crate::recipes::prompt_file::extract_prompt_file_from_cli;
 use crate::recipes::recipe::{explain_recipe, render_recipe_as_yaml};
-use crate::session::{build_session, SessionBuilderConfig, SessionSettings};
+use crate::session::{
+    build_session, OutputFormat, RunStatus, SessionBuilderConfig, SessionSettings,
+};
 use goose::session::SessionManager;
 use goose_bench::bench_config::BenchRunConfig;
 use goose_bench::runners::bench_runner::BenchRunner;
@@ -148,6 +159,170 @@ enum SessionCommand {
             default_value = "markdown"
         )]
         format: String,
+
+        #[arg(
+            long,
+            help = "Export the most recently updated session",
+            conflicts_with_all = ["name", "session_id", "path"]
+        )]
+        latest: bool,
+
+        #[arg(
+            long = "last",
+            value_name = "N",
+            help = "Only export the last N turns",
+            long_help = "Only export the last N turns. A turn starts with a user prompt and includes every assistant reply and tool call that followed it."
+        )]
+        last_turns: Option<usize>,
+
+        #[arg(
+            long,
+            help = "Replace tool call arguments with a placeholder",
+            long_help = "Replace the value of every tool call argument with a placeholder, keeping the argument names. Useful when sharing sessions that passed secrets or private paths to tools."
+        )]
+        redact_args: bool,
+    },
+    #[command(about = "Show the log of a session")]
+    Logs {
+        #[arg(
+            value_name = "SESSION",
+            help = "Session ID or name (default: the most recently updated session)"
+        )]
+        session: Option<String>,
+
+        #[arg(short, long, help = "Keep printing log lines as they are written")]
+        follow: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    #[command(
+        about = "Print a config value",
+        long_about = "Print the value in effect for a key. Its source (environment, project config, config file or secret store) is printed to stderr, so stdout holds only the value."
+    )]
+    Get {
+        #[arg(help = "Config key, e.g. GOOSE_MODEL")]
+        key: String,
+        #[arg(long, help = "Read the key from the secret store")]
+        secret: bool,
+        #[arg(
+            long,
+            help = "Print secret values instead of masking them",
+            requires = "secret"
+        )]
+        reveal: bool,
+    },
+    #[command(about = "Set a config value in the config file or secret store")]
+    Set {
+        #[arg(help = "Config key, e.g. GOOSE_MODEL")]
+        key: String,
+        #[arg(help = "Value to store")]
+        value: String,
+        #[arg(long, help = "Store the value in the secret store")]
+        secret: bool,
+        #[arg(
+            long = "type",
+            value_enum,
+            help = "How to read the value; by default JSON is tried, then a plain string"
+        )]
+        value_type: Option<ValueType>,
+    },
+    #[command(about = "Remove a key from the config file or secret store")]
+    Unset {
+        #[arg(help = "Config key, e.g. GOOSE_MODEL")]
+        key: String,
+        #[arg(long, help = "Remove the key from the secret store")]
+        secret: bool,
+    },
+    #[command(
+        about = "Trust a project config with extensions, hosts and other sensitive keys",
+        long_about = "Until a project's .goose/config.yaml is trusted, keys in it that can run commands or send requests elsewhere (extensions, GOOSE_MODE, security and keys ending in _HOST, _ENDPOINT, _URL and the like) are ignored. Trust is recorded in the global config file."
+    )]
+    Trust {
+        #[arg(help = "Project directory, or the current directory if omitted")]
+        path: Option<PathBuf>,
+        #[arg(long, help = "Stop trusting the project config")]
+        revoke: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ExtensionsCommand {
+    #[command(about = "List configured extensions")]
+    List {},
+    #[command(about = "Enable an extension")]
+    Enable {
+        #[arg(help = "Name of the extension")]
+        name: String,
+    },
+    #[command(about = "Disable an extension")]
+    Disable {
+        #[arg(help = "Name of the extension")]
+        name: String,
+    },
+    #[command(about = "Add a stdio, SSE or streamable HTTP extension")]
+    #[command(group(clap::ArgGroup::new("transport").required(true)))]
+    Add {
+        #[arg(help = "Name for the extension")]
+        name: String,
+        #[arg(
+            long,
+            value_name = "COMMAND",
+            group = "transport",
+            help = "Command line that starts a stdio extension (e.g., 'npx -y @acme/mcp')"
+        )]
+        stdio: Option<String>,
+        #[arg(
+            long,
+            value_name = "URL",
+            group = "transport",
+            help = "SSE endpoint of a remote extension"
+        )]
+        sse: Option<String>,
+        #[arg(
+            long = "streamable-http",
+            value_name = "URL",
+            group = "transport",
+            help = "Streamable HTTP endpoint of a remote extension"
+        )]
+        streamable_http: Option<String>,
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value_t = goose::config::DEFAULT_EXTENSION_TIMEOUT,
+            help = "Timeout for the extension in seconds"
+        )]
+        timeout: u64,
+        #[arg(long, help = "Description of the extension")]
+        description: Option<String>,
+        #[arg(
+            long = "env-key",
+            value_name = "NAME",
+            help = "Environment variable the extension needs (can be specified multiple times)",
+            long_help = "Environment variable the extension needs. Its value is prompted for and stored as a secret unless it is already set. Can be specified multiple times.",
+            action = clap::ArgAction::Append
+        )]
+        env_keys: Vec<String>,
+        #[arg(
+            long = "header",
+            value_name = "KEY=VALUE",
+            help = "HTTP header sent to a streamable HTTP extension (can be specified multiple times)",
+            value_parser = parse_key_val,
+            requires = "streamable_http",
+            action = clap::ArgAction::Append
+        )]
+        headers: Vec<(String, String)>,
+    },
+    #[command(about = "Remove an extension")]
+    Remove {
+        #[arg(help = "Name of the extension")]
+        name: String,
+    },
+    #[command(about = "Start an extension and report the tools it offers")]
+    Test {
+        #[arg(help = "Name of the extension")]
+        name: String,
     },
 }
 
@@ -306,6 +481,13 @@ enum Command {
     #[command(about = "Configure goose settings")]
     Configure {},
 
+    /// Read and write single config values
+    #[command(about = "Get, set or unset a single config value")]
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
     /// Display goose configuration information
     #[command(about = "Display goose information")]
     Info {
@@ -314,6 +496,21 @@ enum Command {
         verbose: bool,
     },
 
+    /// Diagnose common setup problems
+    #[command(about = "Check the goose setup for common problems")]
+    Doctor {
+        /// Print the results as JSON
+        #[arg(long, help = "Print the results as JSON")]
+        json: bool,
+    },
+
+    /// Manage configured extensions
+    #[command(about = "List, enable, disable, add, remove or test extensions")]
+    Extensions {
+        #[command(subcommand)]
+        command: ExtensionsCommand,
+    },
+
     /// Manage system prompts and behaviors
     #[command(about = "Run one of the mcp servers bundled with goose")]
     Mcp { name: String },
@@ -351,6 +548,25 @@ enum Command {
         )]
         history: bool,
 
+        /// Resume into a new session holding only the trailing messages
+        #[arg(
+            long = "last-n",
+            value_name = "MESSAGES",
+            help = "Resume with only the last N messages, as a new session",
+            long_help = "Load only the last N messages of the resumed session. The trimmed history is saved as a new session so the original is left untouched.",
+            requires = "resume"
+        )]
+        last_n: Option<usize>,
+
+        /// Summarize the messages dropped by --last-n
+        #[arg(
+            long = "summarize-older",
+            help = "Summarize the messages older than --last-n into the resumed session",
+            long_help = "Ask the provider for a one-shot summary of the messages dropped by --last-n and start the resumed session with it.",
+            requires = "last_n"
+        )]
+        summarize_older: bool,
+
         /// Enable debug output mode
         #[arg(
             long,
@@ -436,7 +652,8 @@ enum Command {
             value_name = "FILE",
             help = "Path to instruction file containing commands. Use - for stdin.",
             conflicts_with = "input_text",
-            conflicts_with = "recipe"
+            conflicts_with = "recipe",
+            conflicts_with = "prompt_file"
         )]
         instructions: Option<String>,
 
@@ -448,7 +665,8 @@ enum Command {
             help = "Input text to provide to goose directly",
             long_help = "Input text containing commands for goose. Use this in lieu of the instructions argument.",
             conflicts_with = "instructions",
-            conflicts_with = "recipe"
+            conflicts_with = "recipe",
+            conflicts_with = "prompt_file"
         )]
         input_text: Option<String>,
 
@@ -470,15 +688,28 @@ enum Command {
             help = "Recipe name to get recipe file or the full path of the recipe file (use --explain to see recipe details)",
             long_help = "Recipe name to get recipe file or the full path of the recipe file that defines a custom agent configuration. Use --explain to see the recipe's title, description, and parameters.",
             conflicts_with = "instructions",
-            conflicts_with = "input_text"
+            conflicts_with = "input_text",
+            conflicts_with = "prompt_file"
         )]
         recipe: Option<String>,
 
+        /// Path to a prompt file with parameters declared in its front matter
+        #[arg(
+            long = "prompt-file",
+            value_name = "FILE",
+            help = "Path to a prompt file to run, filled in with --params",
+            long_help = "Path to a markdown or text prompt file. A YAML front-matter block between '---' lines can declare parameters (name, type, required, default), which are substituted for {{name}} in the prompt, and configured extensions to enable for the run.",
+            conflicts_with = "instructions",
+            conflicts_with = "input_text",
+            conflicts_with = "recipe"
+        )]
+        prompt_file: Option<PathBuf>,
+
         #[arg(
             long,
             value_name = "KEY=VALUE",
             help = "Dynamic parameters (e.g., --params username=alice --params channel_name=goose-channel)",
-            long_help = "Key-value parameters to pass to the recipe file. Can be specified multiple times.",
+            long_help = "Key-value parameters to pass to the recipe or prompt file. Can be specified multiple times.",
             action = clap::ArgAction::Append,
             value_parser = parse_key_val,
         )]
@@ -603,6 +834,18 @@ enum Command {
         )]
         quiet: bool,
 
+        /// Output format for headless runs
+        #[arg(
+            long = "output",
+            value_name = "FORMAT",
+            value_enum,
+            default_value = "text",
+            conflicts_with = "interactive",
+            help = "Output format: text, json or json-stream",
+            long_help = "Output format for headless runs. 'json' prints a single JSON document when the run finishes with the final assistant message, tool calls, token usage and exit status. 'json-stream' prints one JSON event per line as the run progresses, ending with the same document. Tool calls that need confirmation are denied in both JSON formats."
+        )]
+        output_format: OutputFormat,
+
         /// Scheduled job ID (used internally for scheduled executions)
         #[arg(
             long = "scheduled-job-id",
@@ -728,6 +971,19 @@ pub struct RecipeInfo {
     pub retry_config: Option<goose::agents::types::RetryConfig>,
 }
 
+/// Returned from [`cli`] to end the process with this status. The exit itself is left to
+/// the caller, so telemetry can be flushed first.
+#[derive(Debug)]
+pub struct CliExit(pub i32);
+
+impl std::fmt::Display for CliExit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "exiting with status {}", self.0)
+    }
+}
+
+impl std::error::Error for CliExit {}
+
 pub async fn cli() -> Result<()> {
     let cli = Cli::parse();
 
@@ -736,9 +992,19 @@ pub async fn cli() -> Result<()> {
         eprintln!("Warning: Failed to update project tracker: {}", e);
     }
 
+    // Surface misconfigurations up front instead of as opaque errors on first use
+    if let Ok(problems) = goose::config::Config::global().validate() {
+        for problem in problems {
+            eprintln!("Warning: Invalid configuration value {}", problem);
+        }
+    }
+
     let command_name = match &cli.command {
         Some(Command::Configure {}) => "configure",
+        Some(Command::Config { .. }) => "config",
         Some(Command::Info { .. }) => "info",
+        Some(Command::Doctor { .. }) => "doctor",
+        Some(Command::Extensions { .. }) => "extensions",
         Some(Command::Mcp { .. }) => "mcp",
         Some(Command::Acp {}) => "acp",
         Some(Command::Session { .. }) => "session",
@@ -768,8 +1034,70 @@ pub async fn cli() -> Result<()> {
             handle_info(verbose)?;
             return Ok(());
         }
+        Some(Command::Doctor { json }) => {
+            if !handle_doctor(json).await? {
+                return Err(CliExit(1).into());
+            }
+            return Ok(());
+        }
+        Some(Command::Config { command }) => {
+            match command {
+                ConfigCommand::Get {
+                    key,
+                    secret,
+                    reveal,
+                } => handle_config_get(&key, secret, reveal)?,
+                ConfigCommand::Set {
+                    key,
+                    value,
+                    secret,
+                    value_type,
+                } => handle_config_set(&key, &value, value_type, secret)?,
+                ConfigCommand::Unset { key, secret } => handle_config_unset(&key, secret)?,
+                ConfigCommand::Trust { path, revoke } => {
+                    handle_config_trust(path.as_deref(), revoke)?
+                }
+            }
+            return Ok(());
+        }
+        Some(Command::Extensions { command }) => {
+            match command {
+                ExtensionsCommand::List {} => handle_extensions_list()?,
+                ExtensionsCommand::Enable { name } => handle_extensions_enable(&name, true)?,
+                ExtensionsCommand::Disable { name } => handle_extensions_enable(&name, false)?,
+                ExtensionsCommand::Add {
+                    name,
+                    stdio,
+                    sse,
+                    streamable_http,
+                    timeout,
+                    description,
+                    env_keys,
+                    headers,
+                } => {
+                    let (transport, target) = match (stdio, sse, streamable_http) {
+                        (Some(command), _, _) => (ExtensionTransport::Stdio, command),
+                        (_, Some(uri), _) => (ExtensionTransport::Sse, uri),
+                        (_, _, Some(uri)) => (ExtensionTransport::StreamableHttp, uri),
+                        _ => unreachable!("clap requires one transport"),
+                    };
+                    handle_extensions_add(NewExtension {
+                        name,
+                        transport,
+                        target,
+                        timeout,
+                        description,
+                        env_keys,
+                        headers,
+                    })?;
+                }
+                ExtensionsCommand::Remove { name } => handle_extensions_remove(&name)?,
+                ExtensionsCommand::Test { name } => handle_extensions_test(&name).await?,
+            }
+            return Ok(());
+        }
         Some(Command::Mcp { name }) => {
-            crate::logging::setup_logging(Some(&format!("mcp-{name}")), None)?;
+            crate::logging::setup_logging(Some(&format!("mcp-{name}")), None, None)?;
             let _ = goose_mcp::mcp_server_runner::run_mcp_server(&name).await;
         }
         Some(Command::Acp {}) => {
@@ -781,6 +1109,8 @@ pub async fn cli() -> Result<()> {
             identifier,
             resume,
             history,
+            last_n,
+            summarize_older,
             debug,
             max_tool_repetitions,
             max_turns,
@@ -802,13 +1132,22 @@ pub async fn cli() -> Result<()> {
                     handle_session_remove(id, regex).await?;
                     return Ok(());
                 }
+                Some(SessionCommand::Logs { session, follow }) => {
+                    crate::commands::session::handle_session_logs(session, follow).await?;
+                    Ok(())
+                }
                 Some(SessionCommand::Export {
                     identifier,
                     output,
                     format,
+                    latest,
+                    last_turns,
+                    redact_args,
                 }) => {
                     let session_identifier = if let Some(id) = identifier {
                         get_session_id(id).await?
+                    } else if latest {
+                        crate::commands::session::most_recent_session_id().await?
                     } else {
                         // If no identifier is provided, prompt for interactive selection
                         match crate::commands::session::prompt_interactive_session_selection().await
@@ -825,6 +1164,10 @@ pub async fn cli() -> Result<()> {
                         session_identifier,
                         output,
                         format,
+                        ExportOptions {
+                            last_turns,
+                            redact_args,
+                        },
                     )
                     .await?;
                     Ok(())
@@ -850,6 +1193,8 @@ pub async fn cli() -> Result<()> {
                     let mut session: crate::CliSession = build_session(SessionBuilderConfig {
                         session_id,
                         resume,
+                        resume_last_n: last_n,
+                        summarize_older,
                         no_session: false,
                         extensions,
                         remote_extensions,
@@ -866,6 +1211,7 @@ pub async fn cli() -> Result<()> {
                         scheduled_job_id: None,
                         interactive: true,
                         quiet: false,
+                        output_format: OutputFormat::Text,
                         sub_recipes: None,
                         final_output_response: None,
                         retry_config: None,
@@ -878,6 +1224,7 @@ pub async fn cli() -> Result<()> {
                     }
 
                     let result = session.interactive(None).await;
+                    session.shutdown().await;
 
                     let session_duration = session_start.elapsed();
                     let exit_type = if result.is_ok() { "normal" } else { "error" };
@@ -931,6 +1278,7 @@ pub async fn cli() -> Result<()> {
             instructions,
             input_text,
             recipe,
+            prompt_file,
             system,
             interactive,
             identifier,
@@ -948,12 +1296,14 @@ pub async fn cli() -> Result<()> {
             render_recipe,
             scheduled_job_id,
             quiet,
+            output_format,
             additional_sub_recipes,
             provider,
             model,
         }) => {
-            let (input_config, recipe_info) = match (instructions, input_text, recipe) {
-                (Some(file), _, _) if file == "-" => {
+            let (input_config, recipe_info) = match (instructions, input_text, recipe, prompt_file)
+            {
+                (Some(file), _, _, _) if file == "-" => {
                     let mut input = String::new();
                     std::io::stdin()
                         .read_to_string(&mut input)
@@ -966,14 +1316,17 @@ pub async fn cli() -> Result<()> {
                     };
                     (input_config, None)
                 }
-                (Some(file), _, _) => {
-                    let contents = std::fs::read_to_string(&file).unwrap_or_else(|err| {
-                        eprintln!(
-                            "Instruction file not found — did you mean to use goose run --text?\n{}",
-                            err
-                        );
-                        std::process::exit(1);
-                    });
+                (Some(file), _, _, _) => {
+                    let contents = match std::fs::read_to_string(&file) {
+                        Ok(contents) => contents,
+                        Err(err) => {
+                            eprintln!(
+                                "Instruction file not found — did you mean to use goose run --text?\n{}",
+                                err
+                            );
+                            return Err(CliExit(1).into());
+                        }
+                    };
                     let input_config = InputConfig {
                         contents: Some(contents),
                         extensions_override: None,
@@ -981,7 +1334,7 @@ pub async fn cli() -> Result<()> {
                     };
                     (input_config, None)
                 }
-                (_, Some(text), _) => {
+                (_, Some(text), _, _) => {
                     let input_config = InputConfig {
                         contents: Some(text),
                         extensions_override: None,
@@ -989,7 +1342,7 @@ pub async fn cli() -> Result<()> {
                     };
                     (input_config, None)
                 }
-                (_, _, Some(recipe_name)) => {
+                (_, _, Some(recipe_name), _) => {
                     let recipe_display_name = std::path::Path::new(&recipe_name)
                         .file_name()
                         .and_then(|name| name.to_str())
@@ -1015,7 +1368,7 @@ pub async fn cli() -> Result<()> {
                     if render_recipe {
                         if let Err(err) = render_recipe_as_yaml(&recipe_name, params) {
                             eprintln!("{}: {}", console::style("Error").red().bold(), err);
-                            std::process::exit(1);
+                            return Err(CliExit(1).into());
                         }
                         return Ok(());
                     }
@@ -1033,9 +1386,18 @@ pub async fn cli() -> Result<()> {
                         extract_recipe_info_from_cli(recipe_name, params, additional_sub_recipes)?;
                     (input_config, Some(recipe_info))
                 }
-                (None, None, None) => {
-                    eprintln!("Error: Must provide either --instructions (-i), --text (-t), or --recipe. Use -i - for stdin.");
-                    std::process::exit(1);
+                (_, _, _, Some(path)) => {
+                    match extract_prompt_file_from_cli(&path, &params, system) {
+                        Ok(input_config) => (input_config, None),
+                        Err(err) => {
+                            eprintln!("{}: {}", console::style("Error").red().bold(), err);
+                            return Err(CliExit(1).into());
+                        }
+                    }
+                }
+                (None, None, None, None) => {
+                    eprintln!("Error: Must provide either --instructions (-i), --text (-t), --recipe, or --prompt-file. Use -i - for stdin.");
+                    return Err(CliExit(1).into());
                 }
             };
             let session_id = if let Some(id) = identifier {
@@ -1047,6 +1409,8 @@ pub async fn cli() -> Result<()> {
             let mut session = build_session(SessionBuilderConfig {
                 session_id,
                 resume,
+                resume_last_n: None,
+                summarize_older: false,
                 no_session,
                 extensions,
                 remote_extensions,
@@ -1065,6 +1429,7 @@ pub async fn cli() -> Result<()> {
                 scheduled_job_id,
                 interactive, // Use the interactive flag from the Run command
                 quiet,
+                output_format,
                 sub_recipes: recipe_info.as_ref().and_then(|r| r.sub_recipes.clone()),
                 final_output_response: recipe_info
                     .as_ref()
@@ -1075,6 +1440,7 @@ pub async fn cli() -> Result<()> {
 
             if interactive {
                 let _ = session.interactive(input_config.contents).await;
+                session.shutdown().await;
             } else if let Some(contents) = input_config.contents {
                 let session_start = std::time::Instant::now();
                 let session_type = if recipe_info.is_some() {
@@ -1091,6 +1457,7 @@ pub async fn cli() -> Result<()> {
                 );
 
                 let result = session.headless(contents).await;
+                session.shutdown().await;
 
                 let session_duration = session_start.elapsed();
                 let exit_type = if result.is_ok() { "normal" } else { "error" };
@@ -1126,10 +1493,18 @@ pub async fn cli() -> Result<()> {
                     );
                 }
 
+                // Errors returned from the run are reported below in text mode
+                if let Some(report) = session.finish_run(&result).await {
+                    if report.status != RunStatus::Completed
+                        && (result.is_ok() || output_format != OutputFormat::Text)
+                    {
+                        return Err(CliExit(report.exit_code).into());
+                    }
+                }
                 result?;
             } else {
                 eprintln!("Error: no text provided for prompt in headless mode");
-                std::process::exit(1);
+                return Err(CliExit(1).into());
             }
 
             return Ok(());
@@ -1224,6 +1599,8 @@ pub async fn cli() -> Result<()> {
                 let mut session = build_session(SessionBuilderConfig {
                     session_id: None,
                     resume: false,
+                    resume_last_n: None,
+                    summarize_older: false,
                     no_session: false,
                     extensions: Vec::new(),
                     remote_extensions: Vec::new(),
@@ -1240,6 +1617,7 @@ pub async fn cli() -> Result<()> {
                     scheduled_job_id: None,
                     interactive: true,
                     quiet: false,
+                    output_format: OutputFormat::Text,
                     sub_recipes: None,
                     final_output_response: None,
                     retry_config: None,
@@ -1248,6 +1626,7 @@ pub async fn cli() -> Result<()> {
                 if let Err(e) = session.interactive(None).await {
                     eprintln!("Session ended with error: {}", e);
                 }
+                session.shutdown().await;
                 Ok(())
             };
         }
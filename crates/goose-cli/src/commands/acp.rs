@@ -3,17 +3,17 @@ use agent_client_protocol::{
     ToolCallContent,
 };
 use anyhow::Result;
+use futures::StreamExt;
 use goose::agents::Agent;
 use goose::config::{Config, ExtensionConfigManager};
 use goose::conversation::message::{Message, MessageContent};
 use goose::conversation::Conversation;
 use goose::providers::create;
 use rmcp::model::{RawContent, ResourceContents};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::fs;
 use std::sync::Arc;
 use tokio::sync::{mpsc, oneshot, Mutex};
-use tokio::task::JoinSet;
 use tokio_util::compat::{TokioAsyncReadCompatExt as _, TokioAsyncWriteCompatExt as _};
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
@@ -131,42 +131,15 @@ impl GooseAcpAgent {
             .map(|ext| ext.config)
             .collect();
 
-        // Add extensions to the agent in parallel
-        let agent_ptr = Arc::new(agent);
-        let mut set = JoinSet::new();
-        let mut waiting_on = HashSet::new();
-
-        for extension in extensions_to_run {
-            waiting_on.insert(extension.name());
-            let agent_ptr_clone = agent_ptr.clone();
-            set.spawn(async move {
-                (
-                    extension.name(),
-                    agent_ptr_clone.add_extension(extension.clone()).await,
-                )
-            });
-        }
-
-        // Wait for all extensions to load
-        while let Some(result) = set.join_next().await {
+        // Add extensions to the agent, each after the ones it depends on
+        let mut results = agent.add_extensions(extensions_to_run);
+        while let Some((name, result)) = results.next().await {
             match result {
-                Ok((name, Ok(_))) => {
-                    waiting_on.remove(&name);
-                    info!("Loaded extension: {}", name);
-                }
-                Ok((name, Err(e))) => {
-                    warn!("Failed to load extension '{}': {}", name, e);
-                    waiting_on.remove(&name);
-                }
-                Err(e) => {
-                    error!("Task error while loading extension: {}", e);
-                }
+                Ok(_) => info!("Loaded extension: {}", name),
+                Err(e) => warn!("Failed to load extension '{}': {}", name, e),
             }
         }
-
-        // Unwrap the Arc to get the agent back
-        let agent = Arc::try_unwrap(agent_ptr)
-            .map_err(|_| anyhow::anyhow!("Failed to unwrap agent Arc"))?;
+        drop(results);
 
         Ok(Self {
             session_update_tx,
@@ -187,7 +160,8 @@ impl GooseAcpAgent {
                 acp::ContentBlock::Image(image) => {
                     // Goose supports images via base64 encoded data
                     // The ACP ImageContent has data as a String directly
-                    user_message = user_message.with_image(&image.data, &image.mime_type);
+                    user_message = user_message
+                        .with_content(MessageContent::image(&image.data, &image.mime_type));
                 }
                 acp::ContentBlock::Resource(resource) => {
                     // Embed resource content as text with context
@@ -569,8 +543,6 @@ impl acp::Agent for GooseAcpAgent {
                 acp::Error::internal_error()
             })?;
 
-        use futures::StreamExt;
-
         // Track if we were cancelled
         let mut was_cancelled = false;
 
@@ -1,5 +1,5 @@
 use crate::session::build_session;
-use crate::session::SessionBuilderConfig;
+use crate::session::{OutputFormat, SessionBuilderConfig};
 use crate::{logging, CliSession};
 use async_trait::async_trait;
 use goose::conversation::Conversation;
@@ -38,6 +38,8 @@ pub async fn agent_generator(
     let base_session = build_session(SessionBuilderConfig {
         session_id: Some(session_id),
         resume: false,
+        resume_last_n: None,
+        summarize_older: false,
         no_session: false,
         extensions: requirements.external,
         remote_extensions: requirements.remote,
@@ -54,6 +56,7 @@ pub async fn agent_generator(
         scheduled_job_id: None,
         max_turns: None,
         quiet: false,
+        output_format: OutputFormat::Text,
         sub_recipes: None,
         final_output_response: None,
         retry_config: None,
@@ -63,7 +66,7 @@ pub async fn agent_generator(
     let bench_agent = BenchAgent::new(Box::new(base_session));
 
     let errors = Some(Arc::new(Mutex::new(bench_agent.get_errors().await)));
-    logging::setup_logging(Some("bench"), errors).expect("Failed to initialize logging");
+    logging::setup_logging(Some("bench"), None, errors).expect("Failed to initialize logging");
 
     bench_agent
 }
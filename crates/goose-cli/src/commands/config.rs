@@ -0,0 +1,365 @@
+//! Non-interactive access to single config values (`goose config get/set/unset`), so setup
+//! can be scripted without editing config.yaml by hand, and to project config trust
+//! (`goose config trust`).
+
+use anyhow::{anyhow, bail, Result};
+use console::style;
+use goose::config::base::{find_project_config, requires_project_trust};
+use goose::config::{Config, ConfigSource};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const MASKED: &str = "********";
+
+/// How `goose config set` reads its value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ValueType {
+    Json,
+    String,
+    Bool,
+    Int,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigValue {
+    pub value: Value,
+    pub source: ConfigSource,
+    /// The stored value hidden by an environment override
+    pub overridden: Option<Value>,
+}
+
+/// Parse a value given on the command line. Without a type, JSON is tried first (so `true`,
+/// `42` and `[1, 2]` keep their types) and anything else is taken as a string.
+pub fn parse_value(raw: &str, value_type: Option<ValueType>) -> Result<Value> {
+    match value_type {
+        None => Ok(serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))),
+        Some(ValueType::Json) => {
+            serde_json::from_str(raw).map_err(|e| anyhow!("'{}' is not valid JSON: {}", raw, e))
+        }
+        Some(ValueType::String) => Ok(Value::String(raw.to_string())),
+        Some(ValueType::Bool) => match raw.trim().to_lowercase().as_str() {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            _ => bail!("'{}' is not a boolean, use true or false", raw),
+        },
+        Some(ValueType::Int) => raw
+            .trim()
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| anyhow!("'{}' is not an integer", raw)),
+    }
+}
+
+fn env_override(key: &str) -> Option<String> {
+    let var = key.to_uppercase();
+    std::env::var_os(&var).map(|_| var)
+}
+
+/// The value in effect for `key` and where it comes from
+pub fn get_value(config: &Config, key: &str, secret: bool) -> Result<ConfigValue> {
+    if !secret {
+        let (value, source) = config
+            .get_with_provenance(key)
+            .map_err(|_| anyhow!("'{}' is not set", key))?;
+        let overridden = match source {
+            ConfigSource::Environment(_) => config
+                .get_stored_with_provenance(key)?
+                .map(|(stored, _)| stored),
+            _ => None,
+        };
+        return Ok(ConfigValue {
+            value,
+            source,
+            overridden,
+        });
+    }
+
+    let stored = config.load_secrets()?.remove(key);
+    if let Some(var) = env_override(key) {
+        return Ok(ConfigValue {
+            value: config.get(key, secret)?,
+            source: ConfigSource::Environment(var),
+            overridden: stored,
+        });
+    }
+    stored
+        .map(|value| ConfigValue {
+            value,
+            source: ConfigSource::SecretStore,
+            overridden: None,
+        })
+        .ok_or_else(|| anyhow!("'{}' is not set", key))
+}
+
+/// Store `value` under `key`, returning the environment variable that overrides it, if any
+pub fn set_value(config: &Config, key: &str, value: Value, secret: bool) -> Result<Option<String>> {
+    config.set(key, value, secret)?;
+    Ok(env_override(key))
+}
+
+/// Remove `key` from the config file or secret store, returning whether it was there
+pub fn unset_value(config: &Config, key: &str, secret: bool) -> Result<bool> {
+    if secret {
+        if !config.load_secrets()?.contains_key(key) {
+            return Ok(false);
+        }
+        config.delete_secret(key)?;
+    } else {
+        if !config.load_values()?.contains_key(key) {
+            return Ok(false);
+        }
+        config.delete(key)?;
+    }
+    Ok(true)
+}
+
+/// Strings are printed bare so the output can be used directly in scripts
+fn render(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+pub fn handle_config_get(key: &str, secret: bool, reveal: bool) -> Result<()> {
+    let found = get_value(Config::global(), key, secret)?;
+    let show = |value: &Value| {
+        if secret && !reveal {
+            MASKED.to_string()
+        } else {
+            render(value)
+        }
+    };
+
+    println!("{}", show(&found.value));
+    // The source goes to stderr so stdout holds only the value
+    eprintln!("{} {}", style("source:").dim(), found.source);
+    if let Some(overridden) = &found.overridden {
+        eprintln!(
+            "{} {} (overridden)",
+            style("stored:").dim(),
+            show(overridden)
+        );
+    }
+    Ok(())
+}
+
+pub fn handle_config_set(
+    key: &str,
+    raw: &str,
+    value_type: Option<ValueType>,
+    secret: bool,
+) -> Result<()> {
+    let value = parse_value(raw, value_type)?;
+    let config = Config::global();
+    let overridden_by = set_value(config, key, value, secret)?;
+    let location = if secret {
+        ConfigSource::SecretStore
+    } else {
+        ConfigSource::ConfigFile(config.path().into())
+    };
+    println!("Set {} in {}", style(key).green(), location);
+    if let Some(var) = overridden_by {
+        eprintln!(
+            "{} {} is set in the environment and takes precedence",
+            style("Warning:").yellow(),
+            var
+        );
+    }
+    Ok(())
+}
+
+pub fn handle_config_unset(key: &str, secret: bool) -> Result<()> {
+    let config = Config::global();
+    if unset_value(config, key, secret)? {
+        println!("Removed {}", style(key).green());
+    } else {
+        let location = if secret {
+            "the secret store"
+        } else {
+            "the config file"
+        };
+        println!("{} is not set in {}", style(key).green(), location);
+    }
+    Ok(())
+}
+
+/// Trust or stop trusting the project config that applies to `dir`, returning its path and the
+/// keys in it that only a trusted project can set
+pub fn trust_project(config: &Config, dir: &Path, trusted: bool) -> Result<(PathBuf, Vec<String>)> {
+    let path = find_project_config(dir).ok_or_else(|| {
+        anyhow!(
+            "No project config found in {} or its parents",
+            dir.display()
+        )
+    })?;
+    let values: HashMap<String, Value> = serde_yaml::from_str(&std::fs::read_to_string(&path)?)
+        .map_err(|e| anyhow!("{} is not valid YAML: {}", path.display(), e))?;
+    let mut keys: Vec<String> = values
+        .into_keys()
+        .filter(|key| requires_project_trust(key))
+        .collect();
+    keys.sort();
+    config.set_project_trusted(&path, trusted)?;
+    Ok((path, keys))
+}
+
+pub fn handle_config_trust(dir: Option<&Path>, revoke: bool) -> Result<()> {
+    let dir = match dir {
+        Some(dir) => dir.to_path_buf(),
+        None => std::env::current_dir()?,
+    };
+    let (path, keys) = trust_project(Config::global(), &dir, !revoke)?;
+    if revoke {
+        println!("No longer trusting {}", style(path.display()).green());
+        return Ok(());
+    }
+    println!("Trusted {}", style(path.display()).green());
+    if !keys.is_empty() {
+        println!(
+            "It can now set {}. Editing it will need trusting it again.",
+            keys.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn temp_config(dir: &TempDir) -> Config {
+        Config::new_with_file_secrets(
+            dir.path().join("config.yaml"),
+            dir.path().join("secrets.yaml"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("gpt-4o", None).unwrap(), json!("gpt-4o"));
+        assert_eq!(parse_value("true", None).unwrap(), json!(true));
+        assert_eq!(parse_value("42", None).unwrap(), json!(42));
+        assert_eq!(
+            parse_value(r#"{"enabled": true}"#, None).unwrap(),
+            json!({"enabled": true})
+        );
+        assert_eq!(
+            parse_value("42", Some(ValueType::String)).unwrap(),
+            json!("42")
+        );
+        assert_eq!(
+            parse_value("TRUE", Some(ValueType::Bool)).unwrap(),
+            json!(true)
+        );
+        assert!(parse_value("yes", Some(ValueType::Bool)).is_err());
+        assert!(parse_value("4.5", Some(ValueType::Int)).is_err());
+        assert!(parse_value("{oops", Some(ValueType::Json)).is_err());
+    }
+
+    #[test]
+    fn test_set_get_unset_json_value() {
+        let dir = TempDir::new().unwrap();
+        let config = temp_config(&dir);
+        let value = parse_value(r#"{"enabled": true, "threshold": 0.8}"#, None).unwrap();
+
+        set_value(&config, "security", value.clone(), false).unwrap();
+        let found = get_value(&config, "security", false).unwrap();
+        assert_eq!(found.value, value);
+        assert_eq!(found.source, ConfigSource::ConfigFile(config.path().into()));
+
+        assert!(unset_value(&config, "security", false).unwrap());
+        assert!(!config.load_values().unwrap().contains_key("security"));
+        let written = std::fs::read_to_string(dir.path().join("config.yaml")).unwrap();
+        assert!(!written.contains("security"));
+        assert!(get_value(&config, "security", false).is_err());
+        assert!(!unset_value(&config, "security", false).unwrap());
+    }
+
+    #[test]
+    fn test_secrets_use_the_secret_store() {
+        let dir = TempDir::new().unwrap();
+        let config = temp_config(&dir);
+
+        set_value(&config, "GOOSE_TEST_CLI_API_KEY", json!("sk-123"), true).unwrap();
+        assert!(!config
+            .load_values()
+            .unwrap()
+            .contains_key("GOOSE_TEST_CLI_API_KEY"));
+        let found = get_value(&config, "GOOSE_TEST_CLI_API_KEY", true).unwrap();
+        assert_eq!(found.value, json!("sk-123"));
+        assert_eq!(found.source, ConfigSource::SecretStore);
+        // A secret is not found among the plain values
+        assert!(get_value(&config, "GOOSE_TEST_CLI_API_KEY", false).is_err());
+
+        assert!(unset_value(&config, "GOOSE_TEST_CLI_API_KEY", true).unwrap());
+        assert!(get_value(&config, "GOOSE_TEST_CLI_API_KEY", true).is_err());
+    }
+
+    #[test]
+    fn test_trust_project() {
+        let dir = TempDir::new().unwrap();
+        let config = temp_config(&dir);
+        let project = TempDir::new().unwrap();
+        let nested = project.path().join("src");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert!(trust_project(&config, &nested, true).is_err());
+
+        let project_file = project.path().join(".goose/config.yaml");
+        std::fs::create_dir_all(project_file.parent().unwrap()).unwrap();
+        std::fs::write(
+            &project_file,
+            "extensions: {}\nOPENAI_HOST: https://proxy.example\nGOOSE_MODEL: gpt-4o\n",
+        )
+        .unwrap();
+
+        let (path, keys) = trust_project(&config, &nested, true).unwrap();
+        assert_eq!(path, project_file);
+        assert_eq!(keys, vec!["OPENAI_HOST", "extensions"]);
+        assert!(config.is_project_trusted(&project_file).unwrap());
+
+        // An edit after trusting needs to be trusted again
+        std::fs::write(
+            &project_file,
+            "extensions: {}\nOPENAI_HOST: https://other.example\n",
+        )
+        .unwrap();
+        assert!(!config.is_project_trusted(&project_file).unwrap());
+        trust_project(&config, &nested, true).unwrap();
+        assert!(config.is_project_trusted(&project_file).unwrap());
+
+        trust_project(&config, &nested, false).unwrap();
+        assert!(!config.is_project_trusted(&project_file).unwrap());
+    }
+
+    #[test]
+    fn test_environment_override_is_reported() {
+        let dir = TempDir::new().unwrap();
+        let config = temp_config(&dir);
+
+        let overridden_by = temp_env::with_var("GOOSE_TEST_CLI_MODEL", Some("env-model"), || {
+            set_value(&config, "GOOSE_TEST_CLI_MODEL", json!("file-model"), false).unwrap()
+        });
+        assert_eq!(overridden_by.as_deref(), Some("GOOSE_TEST_CLI_MODEL"));
+
+        let found = temp_env::with_var("GOOSE_TEST_CLI_MODEL", Some("env-model"), || {
+            get_value(&config, "GOOSE_TEST_CLI_MODEL", false).unwrap()
+        });
+        assert_eq!(found.value, json!("env-model"));
+        assert_eq!(
+            found.source,
+            ConfigSource::Environment("GOOSE_TEST_CLI_MODEL".to_string())
+        );
+        assert_eq!(found.overridden, Some(json!("file-model")));
+
+        let found = temp_env::with_var_unset("GOOSE_TEST_CLI_MODEL", || {
+            get_value(&config, "GOOSE_TEST_CLI_MODEL", false).unwrap()
+        });
+        assert_eq!(found.value, json!("file-model"));
+        assert_eq!(found.overridden, None);
+    }
+}
@@ -8,11 +8,11 @@ use goose::agents::platform_tools::{
 };
 use goose::agents::Agent;
 use goose::agents::{extension::Envs, ExtensionConfig};
-use goose::config::custom_providers::CustomProviderConfig;
+use goose::config::custom_providers::{discover_models, CustomProviderConfig};
 use goose::config::extensions::name_to_key;
 use goose::config::permission::PermissionLevel;
 use goose::config::{
-    Config, ConfigError, ExperimentManager, ExtensionConfigManager, ExtensionEntry,
+    Config, ConfigError, ConfigSource, ExperimentManager, ExtensionConfigManager, ExtensionEntry,
     PermissionManager,
 };
 use goose::conversation::message::Message;
@@ -78,6 +78,11 @@ pub async fn handle_configure() -> Result<(), Box<dyn Error>> {
                 "Tetrate Agent Router Service Login",
                 "Sign in with Tetrate Agent Router Service to automatically configure models",
             )
+            .item(
+                "anthropic",
+                "Anthropic API Key",
+                "Create an Anthropic API key in your browser and paste it in",
+            )
             .item(
                 "manual",
                 "Manual Configuration",
@@ -116,6 +121,21 @@ pub async fn handle_configure() -> Result<(), Box<dyn Error>> {
                     }
                 }
             }
+            "anthropic" => {
+                match handle_anthropic_auth().await {
+                    Ok(_) => {
+                        // Anthropic signup already handles everything including enabling developer extension
+                    }
+                    Err(e) => {
+                        let _ = config.clear();
+                        println!(
+                            "\n  {} Anthropic signup failed: {} \n  Please try again or use manual configuration",
+                            style("Error").red().italic(),
+                            e,
+                        );
+                    }
+                }
+            }
             "manual" => {
                 match configure_provider_dialog().await {
                     Ok(true) => {
@@ -135,6 +155,8 @@ pub async fn handle_configure() -> Result<(), Box<dyn Error>> {
                                 bundled: Some(true),
                                 description: None,
                                 available_tools: Vec::new(),
+                                depends_on: Vec::new(),
+                                response_limits: None,
                             },
                         })?;
                     }
@@ -268,7 +290,7 @@ pub async fn handle_configure() -> Result<(), Box<dyn Error>> {
             "remove" => remove_extension_dialog(),
             "settings" => configure_settings_dialog().await.and(Ok(())),
             "providers" => configure_provider_dialog().await.and(Ok(())),
-            "custom_providers" => configure_custom_provider_dialog(),
+            "custom_providers" => configure_custom_provider_dialog().await,
             _ => unreachable!(),
         }
     }
@@ -655,6 +677,17 @@ pub async fn configure_provider_dialog() -> Result<bool, Box<dyn Error>> {
             // Update config with new values only if the test succeeds
             config.set_param("GOOSE_PROVIDER", Value::String(provider_name.to_string()))?;
             config.set_param("GOOSE_MODEL", Value::String(model.clone()))?;
+            // A saved value is not used while an environment variable overrides it
+            for key in ["GOOSE_PROVIDER", "GOOSE_MODEL"] {
+                if let Ok((value, source @ ConfigSource::Environment(_))) =
+                    config.get_with_provenance(key)
+                {
+                    cliclack::log::warning(format!(
+                        "{} is set to {} by the {}, which takes precedence over the saved value",
+                        key, value, source
+                    ))?;
+                }
+            }
             cliclack::outro("Configuration saved successfully")?;
             Ok(true)
         }
@@ -796,6 +829,8 @@ pub fn configure_extensions_dialog() -> Result<(), Box<dyn Error>> {
                     bundled: Some(true),
                     description: None,
                     available_tools: Vec::new(),
+                    depends_on: Vec::new(),
+                    response_limits: None,
                 },
             })?;
 
@@ -904,6 +939,8 @@ pub fn configure_extensions_dialog() -> Result<(), Box<dyn Error>> {
                     timeout: Some(timeout),
                     bundled: None,
                     available_tools: Vec::new(),
+                    depends_on: Vec::new(),
+                    response_limits: None,
                 },
             })?;
 
@@ -1007,6 +1044,9 @@ pub fn configure_extensions_dialog() -> Result<(), Box<dyn Error>> {
                     timeout: Some(timeout),
                     bundled: None,
                     available_tools: Vec::new(),
+                    depends_on: Vec::new(),
+                    response_limits: None,
+                    keep_alive_interval: None,
                 },
             })?;
 
@@ -1135,6 +1175,9 @@ pub fn configure_extensions_dialog() -> Result<(), Box<dyn Error>> {
                     timeout: Some(timeout),
                     bundled: None,
                     available_tools: Vec::new(),
+                    depends_on: Vec::new(),
+                    response_limits: None,
+                    keep_alive_interval: None,
                 },
             })?;
 
@@ -1505,6 +1548,8 @@ pub async fn configure_tool_permissions_dialog() -> Result<(), Box<dyn Error>> {
             )
         })
         .collect::<Vec<ToolInfo>>();
+    // The extension was only started to list its tools
+    agent.shutdown().await;
 
     let tool_name = cliclack::select("Choose a tool to update permission")
         .items(
@@ -1692,11 +1737,40 @@ pub fn configure_max_turns_dialog() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Pick the model to start with after signing up, the provider's default unless another is chosen
+fn select_signup_model(
+    known_models: &[&str],
+    default_model: &str,
+) -> Result<String, Box<dyn Error>> {
+    let mut items = vec![(default_model, default_model, "Default")];
+    items.extend(
+        known_models
+            .iter()
+            .filter(|model| **model != default_model)
+            .map(|model| (*model, *model, "")),
+    );
+    items.push(("__other__", "Another model...", "Enter a model name"));
+
+    let selection = cliclack::select("Which model would you like to use?")
+        .items(&items)
+        .interact()?;
+    if selection == "__other__" {
+        Ok(cliclack::input("Enter a model name:")
+            .default_input(default_model)
+            .interact()?)
+    } else {
+        Ok(selection.to_string())
+    }
+}
+
 /// Handle OpenRouter authentication
 pub async fn handle_openrouter_auth() -> Result<(), Box<dyn Error>> {
-    use goose::config::{configure_openrouter, signup_openrouter::OpenRouterAuth};
+    use goose::config::{
+        configure_openrouter, signup_openrouter::OpenRouterAuth, validate_signup_model,
+    };
     use goose::conversation::message::Message;
     use goose::providers::create;
+    use goose::providers::openrouter::{OPENROUTER_DEFAULT_MODEL, OPENROUTER_KNOWN_MODELS};
 
     // Use the OpenRouter authentication flow
     let mut auth_flow = OpenRouterAuth::new()?;
@@ -1709,10 +1783,15 @@ pub async fn handle_openrouter_auth() -> Result<(), Box<dyn Error>> {
 
             // Use the existing configure_openrouter function to set everything up
             println!("\nConfiguring OpenRouter...");
-            if let Err(e) = configure_openrouter(config, api_key) {
+            let model = select_signup_model(OPENROUTER_KNOWN_MODELS, OPENROUTER_DEFAULT_MODEL)?;
+            if let Err(e) = configure_openrouter(config, api_key.clone(), Some(&model)) {
                 eprintln!("Failed to configure OpenRouter: {}", e);
                 return Err(e.into());
             }
+            if let Err(e) = validate_signup_model("openrouter", &model).await {
+                eprintln!("⚠️  {}, using {} instead", e, OPENROUTER_DEFAULT_MODEL);
+                configure_openrouter(config, api_key, None)?;
+            }
 
             println!("✓ OpenRouter configuration complete");
             println!("✓ Models configured successfully");
@@ -1764,6 +1843,8 @@ pub async fn handle_openrouter_auth() -> Result<(), Box<dyn Error>> {
                                         bundled: Some(true),
                                         description: None,
                                         available_tools: Vec::new(),
+                                        depends_on: Vec::new(),
+                                        response_limits: None,
                                     },
                                 }) {
                                     Ok(_) => println!("✓ Developer extension enabled"),
@@ -1798,9 +1879,11 @@ pub async fn handle_openrouter_auth() -> Result<(), Box<dyn Error>> {
 
 /// Handle Tetrate Agent Router Service authentication
 pub async fn handle_tetrate_auth() -> Result<(), Box<dyn Error>> {
-    use goose::config::{configure_tetrate, signup_tetrate::TetrateAuth};
+    use goose::config::signup_tetrate::{TetrateAuth, TETRATE_DEFAULT_MODEL};
+    use goose::config::{configure_tetrate, validate_signup_model};
     use goose::conversation::message::Message;
     use goose::providers::create;
+    use goose::providers::tetrate::TETRATE_KNOWN_MODELS;
 
     // Use the Tetrate Agent Router Service authentication flow
     let mut auth_flow = TetrateAuth::new()?;
@@ -1812,10 +1895,15 @@ pub async fn handle_tetrate_auth() -> Result<(), Box<dyn Error>> {
 
             // Use the existing configure_tetrate function to set everything up
             println!("\nConfiguring Tetrate Agent Router Service...");
-            if let Err(e) = configure_tetrate(config, api_key) {
+            let model = select_signup_model(TETRATE_KNOWN_MODELS, TETRATE_DEFAULT_MODEL)?;
+            if let Err(e) = configure_tetrate(config, api_key.clone(), Some(&model)) {
                 eprintln!("Failed to configure Tetrate Agent Router Service: {}", e);
                 return Err(e.into());
             }
+            if let Err(e) = validate_signup_model("tetrate", &model).await {
+                eprintln!("⚠️  {}, using {} instead", e, TETRATE_DEFAULT_MODEL);
+                configure_tetrate(config, api_key, None)?;
+            }
 
             println!("✓ Tetrate Agent Router Service configuration complete");
             println!("✓ Models configured successfully");
@@ -1867,6 +1955,8 @@ pub async fn handle_tetrate_auth() -> Result<(), Box<dyn Error>> {
                                         bundled: Some(true),
                                         description: None,
                                         available_tools: Vec::new(),
+                                        depends_on: Vec::new(),
+                                        response_limits: None,
                                     },
                                 }) {
                                     Ok(_) => println!("✓ Developer extension enabled"),
@@ -1899,7 +1989,124 @@ pub async fn handle_tetrate_auth() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn add_provider() -> Result<(), Box<dyn Error>> {
+/// Handle signup with an Anthropic API key
+pub async fn handle_anthropic_auth() -> Result<(), Box<dyn Error>> {
+    use goose::config::{
+        configure_anthropic, signup_anthropic::AnthropicAuth, validate_signup_model,
+    };
+    use goose::conversation::message::Message;
+    use goose::providers::anthropic::{ANTHROPIC_DEFAULT_MODEL, ANTHROPIC_KNOWN_MODELS};
+    use goose::providers::create;
+
+    // Open the console to create a key, then check the one pasted in
+    let mut auth_flow = AnthropicAuth::new()?;
+    let read_key = || -> anyhow::Result<String> {
+        Ok(cliclack::password("Paste your Anthropic API key:")
+            .mask('▪')
+            .interact()?)
+    };
+    match auth_flow.complete_flow(read_key).await {
+        Ok(api_key) => {
+            println!("\nAPI key verified!");
+
+            let config = Config::global();
+
+            println!("\nConfiguring Anthropic...");
+            let model = select_signup_model(ANTHROPIC_KNOWN_MODELS, ANTHROPIC_DEFAULT_MODEL)?;
+            if let Err(e) = configure_anthropic(config, api_key.clone(), Some(&model)) {
+                eprintln!("Failed to configure Anthropic: {}", e);
+                return Err(e.into());
+            }
+            if let Err(e) = validate_signup_model("anthropic", &model).await {
+                eprintln!("⚠️  {}, using {} instead", e, ANTHROPIC_DEFAULT_MODEL);
+                configure_anthropic(config, api_key, None)?;
+            }
+
+            println!("✓ Anthropic configuration complete");
+            println!("✓ Models configured successfully");
+
+            // Test configuration - get the model that was configured
+            println!("\nTesting configuration...");
+            let configured_model: String = config.get_param("GOOSE_MODEL")?;
+            let model_config = match goose::model::ModelConfig::new(&configured_model) {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("⚠️  Invalid model configuration: {}", e);
+                    eprintln!(
+                        "Your settings have been saved. Please check your model configuration."
+                    );
+                    return Ok(());
+                }
+            };
+
+            match create("anthropic", model_config) {
+                Ok(provider) => {
+                    // Simple test request
+                    let test_result = provider
+                        .complete(
+                            "You are goose, an AI assistant.",
+                            &[Message::user().with_text("Say 'Configuration test successful!'")],
+                            &[],
+                        )
+                        .await;
+
+                    match test_result {
+                        Ok(_) => {
+                            println!("✓ Configuration test passed!");
+
+                            // Enable the developer extension by default if not already enabled
+                            let entries = ExtensionConfigManager::get_all()?;
+                            let has_developer = entries
+                                .iter()
+                                .any(|e| e.config.name() == "developer" && e.enabled);
+
+                            if !has_developer {
+                                match ExtensionConfigManager::set(ExtensionEntry {
+                                    enabled: true,
+                                    config: ExtensionConfig::Builtin {
+                                        name: "developer".to_string(),
+                                        display_name: Some(
+                                            goose::config::DEFAULT_DISPLAY_NAME.to_string(),
+                                        ),
+                                        timeout: Some(goose::config::DEFAULT_EXTENSION_TIMEOUT),
+                                        bundled: Some(true),
+                                        description: None,
+                                        available_tools: Vec::new(),
+                                        depends_on: Vec::new(),
+                                        response_limits: None,
+                                    },
+                                }) {
+                                    Ok(_) => println!("✓ Developer extension enabled"),
+                                    Err(e) => {
+                                        eprintln!("⚠️  Failed to enable developer extension: {}", e)
+                                    }
+                                }
+                            }
+
+                            cliclack::outro("Anthropic setup complete! You can now use goose.")?;
+                        }
+                        Err(e) => {
+                            eprintln!("⚠️  Configuration test failed: {}", e);
+                            eprintln!("Your settings have been saved, but there may be an issue with the connection.");
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("⚠️  Failed to create provider for testing: {}", e);
+                    eprintln!("Your settings have been saved. Please check your configuration.");
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!("Anthropic signup failed: {}", e);
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
+
+async fn add_provider() -> Result<(), Box<dyn Error>> {
     let provider_type = cliclack::select("What type of API is this?")
         .item(
             "openai_compatible",
@@ -1942,36 +2149,95 @@ fn add_provider() -> Result<(), Box<dyn Error>> {
 
     let api_key: String = cliclack::password("API key:").mask('▪').interact()?;
 
-    let models_input: String = cliclack::input("Available models (seperate with commas):")
-        .placeholder("model-a, model-b, model-c")
-        .validate(|input: &String| {
-            if input.trim().is_empty() {
-                Err("Please enter at least one model name")
-            } else {
-                Ok(())
+    let discovered = if provider_type == "openai_compatible" {
+        let spin = spinner();
+        spin.start("Checking the endpoint for available models...");
+        match discover_models(&api_url, &api_key, None).await {
+            Ok(models) => {
+                spin.stop(style("Model discovery complete").green());
+                models
             }
-        })
-        .interact()?;
+            Err(e) => {
+                spin.stop(style(format!("Could not list models: {}", e)).yellow());
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
 
-    let models: Vec<String> = models_input
-        .split(',')
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
+    let models: Vec<String> = if discovered.is_empty() {
+        let models_input: String = cliclack::input("Available models (seperate with commas):")
+            .placeholder("model-a, model-b, model-c")
+            .validate(|input: &String| {
+                if input.trim().is_empty() {
+                    Err("Please enter at least one model name")
+                } else {
+                    Ok(())
+                }
+            })
+            .interact()?;
+
+        models_input
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    } else {
+        cliclack::multiselect(
+            "Select models to use: (use \"space\" to toggle and \"enter\" to submit)",
+        )
+        .required(true)
+        .items(
+            &discovered
+                .iter()
+                .map(|m| (m.clone(), m.as_str(), ""))
+                .collect::<Vec<_>>(),
+        )
+        .interact()?
+    };
 
     let supports_streaming = cliclack::confirm("Does this provider support streaming responses?")
         .initial_value(true)
         .interact()?;
 
-    CustomProviderConfig::create_and_save(
+    let provider = CustomProviderConfig::create_and_save(
         provider_type,
         display_name.clone(),
         api_url,
-        api_key,
+        api_key.clone(),
         models,
         Some(supports_streaming),
     )?;
 
+    if provider_type == "openai_compatible" {
+        let spin = spinner();
+        spin.start("Checking the endpoint...");
+        match provider.validate_with_key(&api_key).await {
+            Ok(validation) => {
+                let yes_no = |supported: bool| if supported { "yes" } else { "no" };
+                spin.stop(format!(
+                    "Endpoint check passed (streaming: {}, tool calls: {})",
+                    yes_no(validation.capabilities.streaming),
+                    yes_no(validation.capabilities.tool_calls)
+                ));
+                if supports_streaming && !validation.capabilities.streaming {
+                    let _ = cliclack::log::warning(
+                        "The endpoint did not stream a test response; streaming may not work.",
+                    );
+                }
+                if !validation.capabilities.tool_calls {
+                    let _ = cliclack::log::warning(
+                        "The endpoint did not return a tool call; extensions will not work with this provider.",
+                    );
+                }
+            }
+            Err(e) => {
+                spin.stop(style(format!("Endpoint check failed: {}", e)).yellow());
+            }
+        }
+    }
+
     cliclack::outro(format!("Custom provider added: {}", display_name))?;
     Ok(())
 }
@@ -2003,7 +2269,7 @@ fn remove_provider() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-pub fn configure_custom_provider_dialog() -> Result<(), Box<dyn Error>> {
+pub async fn configure_custom_provider_dialog() -> Result<(), Box<dyn Error>> {
     let action = cliclack::select("What would you like to do?")
         .item(
             "add",
@@ -2018,7 +2284,7 @@ pub fn configure_custom_provider_dialog() -> Result<(), Box<dyn Error>> {
         .interact()?;
 
     match action {
-        "add" => add_provider(),
+        "add" => add_provider().await,
         "remove" => remove_provider(),
         _ => unreachable!(),
     }
@@ -0,0 +1,667 @@
+//! `goose doctor`: checks the usual causes of a broken setup (config, provider credentials,
+//! extensions, directories and telemetry) and reports each as pass, warn or fail.
+
+use anyhow::Result;
+use console::style;
+use etcetera::{choose_app_strategy, AppStrategy};
+use goose::config::validation::validate_values;
+use goose::config::{Config, ExtensionConfig, ExtensionConfigManager};
+use goose::model::ModelConfig;
+use goose::providers::base::ProviderMetadata;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use url::Url;
+
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(5);
+const PROVIDER_PING_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub category: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl CheckResult {
+    fn new(category: &'static str, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            category,
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// Everything the checks look at, so tests can point them at a stubbed environment
+pub struct DoctorEnv<'a> {
+    pub config: &'a Config,
+    /// Directories goose needs to write to, with a label for each
+    pub directories: Vec<(&'static str, PathBuf)>,
+    /// Search path for extension commands; `None` uses `PATH`
+    pub search_path: Option<OsString>,
+    /// Whether to make a request to the provider to check its credentials
+    pub ping_provider: bool,
+}
+
+impl DoctorEnv<'static> {
+    pub fn from_system() -> Result<Self> {
+        let strategy = choose_app_strategy(crate::APP_STRATEGY.clone())?;
+        let config = Config::global();
+        let config_dir = Path::new(&config.path())
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| strategy.config_dir());
+        Ok(Self {
+            config,
+            directories: vec![
+                ("config", config_dir),
+                ("data", strategy.data_dir()),
+                (
+                    "logs",
+                    strategy
+                        .in_state_dir("logs")
+                        .unwrap_or_else(|| strategy.in_data_dir("logs")),
+                ),
+                ("cache", strategy.cache_dir()),
+            ],
+            search_path: None,
+            ping_provider: true,
+        })
+    }
+}
+
+pub fn check_version() -> Vec<CheckResult> {
+    vec![CheckResult::new(
+        "version",
+        CheckStatus::Pass,
+        format!(
+            "goose {} ({} {})",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ),
+    )]
+}
+
+pub fn check_config_file(env: &DoctorEnv<'_>) -> Vec<CheckResult> {
+    let path = env.config.path();
+    if !env.config.exists() {
+        return vec![CheckResult::new(
+            "config",
+            CheckStatus::Warn,
+            format!(
+                "No config file at {}. Run 'goose configure' to create one",
+                path
+            ),
+        )];
+    }
+
+    // Parse the file directly: loading it through Config would quietly replace a corrupt file
+    let parsed = std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            if content.trim().is_empty() {
+                return Ok(HashMap::new());
+            }
+            serde_yaml::from_str::<HashMap<String, Value>>(&content).map_err(|e| e.to_string())
+        });
+    match parsed.map(|values| validate_values(&values)) {
+        Ok(problems) if problems.is_empty() => vec![CheckResult::new(
+            "config",
+            CheckStatus::Pass,
+            format!("{} is valid", path),
+        )],
+        Ok(problems) => problems
+            .into_iter()
+            .map(|problem| CheckResult::new("config", CheckStatus::Warn, problem.to_string()))
+            .collect(),
+        Err(e) => vec![CheckResult::new(
+            "config",
+            CheckStatus::Fail,
+            format!("Could not parse {}: {}", path, e),
+        )],
+    }
+}
+
+fn configured_provider(env: &DoctorEnv<'_>) -> Option<(String, ProviderMetadata)> {
+    let name = env.config.get_param::<String>("GOOSE_PROVIDER").ok()?;
+    let metadata = goose::providers::providers()
+        .into_iter()
+        .find(|metadata| metadata.name == name)?;
+    Some((name, metadata))
+}
+
+fn has_value(config: &Config, key: &str, secret: bool) -> bool {
+    if secret {
+        config.get_secret::<Value>(key).is_ok() || config.get_param::<Value>(key).is_ok()
+    } else {
+        config.get_param::<Value>(key).is_ok()
+    }
+}
+
+pub fn check_provider_keys(env: &DoctorEnv<'_>) -> Vec<CheckResult> {
+    let Ok(name) = env.config.get_param::<String>("GOOSE_PROVIDER") else {
+        return vec![CheckResult::new(
+            "provider",
+            CheckStatus::Fail,
+            "No provider configured. Run 'goose configure' to choose one",
+        )];
+    };
+    let Some((_, metadata)) = configured_provider(env) else {
+        return vec![CheckResult::new(
+            "provider",
+            CheckStatus::Fail,
+            format!("Unknown provider '{}'", name),
+        )];
+    };
+
+    let mut results = Vec::new();
+    if env.config.get_param::<String>("GOOSE_MODEL").is_err() {
+        results.push(CheckResult::new(
+            "provider",
+            CheckStatus::Fail,
+            "No model configured. Set GOOSE_MODEL or run 'goose configure'",
+        ));
+    }
+
+    let missing: Vec<&str> = metadata
+        .config_keys
+        .iter()
+        .filter(|key| key.required && key.default.is_none() && !key.oauth_flow)
+        .filter(|key| !has_value(env.config, &key.name, key.secret))
+        .map(|key| key.name.as_str())
+        .collect();
+    if missing.is_empty() {
+        results.push(CheckResult::new(
+            "provider",
+            CheckStatus::Pass,
+            format!("{} credentials are set", name),
+        ));
+    } else {
+        results.push(CheckResult::new(
+            "provider",
+            CheckStatus::Fail,
+            format!(
+                "{} is missing {}. Set it in the environment or run 'goose configure'",
+                name,
+                missing.join(", ")
+            ),
+        ));
+    }
+    results
+}
+
+pub async fn check_provider_auth(env: &DoctorEnv<'_>) -> Vec<CheckResult> {
+    if !env.ping_provider {
+        return Vec::new();
+    }
+    let Some((name, _)) = configured_provider(env) else {
+        return Vec::new();
+    };
+    let Ok(model) = env.config.get_param::<String>("GOOSE_MODEL") else {
+        return Vec::new();
+    };
+
+    let provider = match ModelConfig::new(&model)
+        .map_err(anyhow::Error::from)
+        .and_then(|model_config| goose::providers::create(&name, model_config))
+    {
+        Ok(provider) => provider,
+        Err(e) => {
+            return vec![CheckResult::new(
+                "provider",
+                CheckStatus::Fail,
+                format!("Could not set up {}: {}", name, e),
+            )]
+        }
+    };
+
+    let result = match tokio::time::timeout(
+        PROVIDER_PING_TIMEOUT,
+        provider.fetch_supported_models(),
+    )
+    .await
+    {
+        Err(_) => CheckResult::new(
+            "provider",
+            CheckStatus::Fail,
+            format!(
+                "{} did not respond within {}s",
+                name,
+                PROVIDER_PING_TIMEOUT.as_secs()
+            ),
+        ),
+        Ok(Err(e)) => CheckResult::new(
+            "provider",
+            CheckStatus::Fail,
+            format!("{} rejected the request: {}", name, e),
+        ),
+        Ok(Ok(Some(_))) => CheckResult::new(
+            "provider",
+            CheckStatus::Pass,
+            format!("{} accepted the credentials", name),
+        ),
+        Ok(Ok(None)) => CheckResult::new(
+            "provider",
+            CheckStatus::Warn,
+            format!(
+                "{} has no lightweight check; credentials were not verified",
+                name
+            ),
+        ),
+    };
+    vec![result]
+}
+
+/// Resolve `command` the way a process spawn would: paths are checked directly, bare names
+/// are looked up on the search path
+fn resolve_command(command: &str, search_path: Option<&OsString>) -> Option<PathBuf> {
+    let path = Path::new(command);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    let search_path = search_path.cloned().or_else(|| std::env::var_os("PATH"))?;
+    let extensions: &[&str] = if cfg!(windows) {
+        &["", ".exe", ".cmd", ".bat"]
+    } else {
+        &[""]
+    };
+    std::env::split_paths(&search_path).find_map(|dir| {
+        extensions
+            .iter()
+            .map(|extension| dir.join(format!("{}{}", command, extension)))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Whether something accepts TCP connections at the URL's host and port
+async fn url_reachable(uri: &str) -> Result<(), String> {
+    let url = Url::parse(uri).map_err(|e| format!("invalid URL: {}", e))?;
+    let host = url.host_str().ok_or("URL has no host")?;
+    let port = url.port_or_known_default().ok_or("URL has no port")?;
+    match tokio::time::timeout(
+        NETWORK_TIMEOUT,
+        tokio::net::TcpStream::connect((host.trim_matches(['[', ']']), port)),
+    )
+    .await
+    {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("timed out after {}s", NETWORK_TIMEOUT.as_secs())),
+    }
+}
+
+pub async fn check_extensions(env: &DoctorEnv<'_>) -> Vec<CheckResult> {
+    let entries = match ExtensionConfigManager::get_all_in(env.config) {
+        Ok(entries) => entries,
+        Err(e) => {
+            return vec![CheckResult::new(
+                "extensions",
+                CheckStatus::Fail,
+                format!("Could not read extensions: {}", e),
+            )]
+        }
+    };
+
+    let mut enabled: Vec<ExtensionConfig> = entries
+        .into_iter()
+        .filter(|entry| entry.enabled)
+        .map(|entry| entry.config)
+        .collect();
+    enabled.sort_by_key(|config| config.name());
+
+    let mut results = Vec::new();
+    for config in enabled {
+        let name = config.name();
+        let command = match &config {
+            ExtensionConfig::Stdio { cmd, .. } => Some(cmd.as_str()),
+            ExtensionConfig::InlinePython { .. } => Some("uvx"),
+            _ => None,
+        };
+        let uri = match &config {
+            ExtensionConfig::Sse { uri, .. } | ExtensionConfig::StreamableHttp { uri, .. } => {
+                Some(uri.as_str())
+            }
+            _ => None,
+        };
+
+        let result = if let Some(command) = command {
+            match resolve_command(command, env.search_path.as_ref()) {
+                Some(path) => CheckResult::new(
+                    "extensions",
+                    CheckStatus::Pass,
+                    format!("{}: {} found at {}", name, command, path.display()),
+                ),
+                None => CheckResult::new(
+                    "extensions",
+                    CheckStatus::Fail,
+                    format!("{}: command '{}' was not found on PATH", name, command),
+                ),
+            }
+        } else if let Some(uri) = uri {
+            match url_reachable(uri).await {
+                Ok(()) => CheckResult::new(
+                    "extensions",
+                    CheckStatus::Pass,
+                    format!("{}: {} is reachable", name, uri),
+                ),
+                Err(e) => CheckResult::new(
+                    "extensions",
+                    CheckStatus::Fail,
+                    format!("{}: {} is unreachable ({})", name, uri, e),
+                ),
+            }
+        } else {
+            CheckResult::new(
+                "extensions",
+                CheckStatus::Pass,
+                format!("{}: bundled with goose", name),
+            )
+        };
+        results.push(result);
+    }
+
+    if results.is_empty() {
+        results.push(CheckResult::new(
+            "extensions",
+            CheckStatus::Warn,
+            "No extensions are enabled",
+        ));
+    }
+    results
+}
+
+pub fn check_directories(env: &DoctorEnv<'_>) -> Vec<CheckResult> {
+    env.directories
+        .iter()
+        .map(|(label, dir)| {
+            let writable = std::fs::create_dir_all(dir)
+                .and_then(|_| tempfile::NamedTempFile::new_in(dir).map(|_| ()));
+            match writable {
+                Ok(()) => CheckResult::new(
+                    "directories",
+                    CheckStatus::Pass,
+                    format!("{} directory {} is writable", label, dir.display()),
+                ),
+                Err(e) => CheckResult::new(
+                    "directories",
+                    CheckStatus::Fail,
+                    format!(
+                        "{} directory {} is not writable: {}",
+                        label,
+                        dir.display(),
+                        e
+                    ),
+                ),
+            }
+        })
+        .collect()
+}
+
+pub async fn check_telemetry(env: &DoctorEnv<'_>) -> Vec<CheckResult> {
+    let Ok(endpoint) = env
+        .config
+        .get_param::<String>("otel_exporter_otlp_endpoint")
+    else {
+        return Vec::new();
+    };
+    let result = match url_reachable(&endpoint).await {
+        Ok(()) => CheckResult::new(
+            "telemetry",
+            CheckStatus::Pass,
+            format!("OTLP endpoint {} is reachable", endpoint),
+        ),
+        Err(e) => CheckResult::new(
+            "telemetry",
+            CheckStatus::Fail,
+            format!("OTLP endpoint {} is unreachable ({})", endpoint, e),
+        ),
+    };
+    vec![result]
+}
+
+pub async fn run_checks(env: &DoctorEnv<'_>) -> Vec<CheckResult> {
+    let mut results = check_version();
+    let config_results = check_config_file(env);
+    let config_broken = config_results
+        .iter()
+        .any(|result| result.status == CheckStatus::Fail);
+    results.extend(config_results);
+    results.extend(check_directories(env));
+    if config_broken {
+        // The remaining checks read the config, which would replace the unparsable file
+        return results;
+    }
+    let provider_results = check_provider_keys(env);
+    let provider_ready = provider_results
+        .iter()
+        .all(|result| result.status != CheckStatus::Fail);
+    results.extend(provider_results);
+    if provider_ready {
+        results.extend(check_provider_auth(env).await);
+    }
+    results.extend(check_extensions(env).await);
+    results.extend(check_telemetry(env).await);
+    results
+}
+
+fn print_results(results: &[CheckResult]) {
+    for result in results {
+        let label = match result.status {
+            CheckStatus::Pass => style("PASS").green(),
+            CheckStatus::Warn => style("WARN").yellow(),
+            CheckStatus::Fail => style("FAIL").red(),
+        };
+        println!(
+            "[{}] {:<11} {}",
+            label.bold(),
+            result.category,
+            result.message
+        );
+    }
+}
+
+/// Run every check and print the results. Returns whether all checks passed or only warned.
+pub async fn handle_doctor(json: bool) -> Result<bool> {
+    let env = DoctorEnv::from_system()?;
+    let results = run_checks(&env).await;
+    let ok = results
+        .iter()
+        .all(|result| result.status != CheckStatus::Fail);
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "ok": ok,
+                "checks": results,
+            }))?
+        );
+    } else {
+        print_results(&results);
+    }
+    Ok(ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config_with(dir: &TempDir, yaml: &str) -> Config {
+        let config_path = dir.path().join("config.yaml");
+        std::fs::write(&config_path, yaml).unwrap();
+        Config::new_with_file_secrets(config_path, dir.path().join("secrets.yaml")).unwrap()
+    }
+
+    fn env_for(config: &Config) -> DoctorEnv<'_> {
+        DoctorEnv {
+            config,
+            directories: Vec::new(),
+            search_path: Some(OsString::new()),
+            ping_provider: false,
+        }
+    }
+
+    fn statuses(results: &[CheckResult]) -> Vec<CheckStatus> {
+        results.iter().map(|result| result.status).collect()
+    }
+
+    #[test]
+    fn test_unparsable_config_fails() {
+        let dir = TempDir::new().unwrap();
+        let config = config_with(&dir, "GOOSE_PROVIDER: [unclosed");
+        let results = check_config_file(&env_for(&config));
+        assert_eq!(statuses(&results), vec![CheckStatus::Fail]);
+        assert!(results[0].message.contains("Could not parse"));
+    }
+
+    #[tokio::test]
+    async fn test_run_checks_leaves_broken_config_alone() {
+        let dir = TempDir::new().unwrap();
+        let config = config_with(&dir, "GOOSE_PROVIDER: [unclosed");
+        let results = run_checks(&env_for(&config)).await;
+
+        let categories: Vec<&str> = results.iter().map(|result| result.category).collect();
+        assert_eq!(categories, vec!["version", "config"]);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("config.yaml")).unwrap(),
+            "GOOSE_PROVIDER: [unclosed"
+        );
+    }
+
+    #[test]
+    fn test_missing_provider_key_fails() {
+        let dir = TempDir::new().unwrap();
+        let config = config_with(&dir, "GOOSE_PROVIDER: openai\nGOOSE_MODEL: gpt-4o\n");
+        temp_env::with_var_unset("OPENAI_API_KEY", || {
+            let results = check_provider_keys(&env_for(&config));
+            assert_eq!(statuses(&results), vec![CheckStatus::Fail]);
+            assert!(results[0].message.contains("missing OPENAI_API_KEY"));
+
+            config
+                .set_secret("OPENAI_API_KEY", Value::String("sk-test".to_string()))
+                .unwrap();
+            let results = check_provider_keys(&env_for(&config));
+            assert_eq!(statuses(&results), vec![CheckStatus::Pass]);
+        });
+
+        let unconfigured = config_with(&dir, "{}");
+        temp_env::with_var_unset("GOOSE_PROVIDER", || {
+            let results = check_provider_keys(&env_for(&unconfigured));
+            assert_eq!(statuses(&results), vec![CheckStatus::Fail]);
+        });
+    }
+
+    #[tokio::test]
+    async fn test_extension_checks() {
+        let dir = TempDir::new().unwrap();
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let live = format!("http://{}/mcp", listener.local_addr().unwrap());
+        let closed = {
+            let unused = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/sse", unused.local_addr().unwrap())
+        };
+        let config = config_with(
+            &dir,
+            &format!(
+                r#"extensions:
+  developer:
+    enabled: true
+    type: builtin
+    name: developer
+  missingcmd:
+    enabled: true
+    type: stdio
+    name: missingcmd
+    cmd: goose-doctor-missing-command
+    args: []
+  disabled:
+    enabled: false
+    type: stdio
+    name: disabled
+    cmd: also-missing
+    args: []
+  live:
+    enabled: true
+    type: streamable_http
+    name: live
+    uri: {}
+  down:
+    enabled: true
+    type: sse
+    name: down
+    uri: {}
+"#,
+                live, closed
+            ),
+        );
+
+        let results = check_extensions(&env_for(&config)).await;
+        let summary: Vec<(CheckStatus, &str)> = results
+            .iter()
+            .map(|result| (result.status, result.message.split(':').next().unwrap()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (CheckStatus::Pass, "developer"),
+                (CheckStatus::Fail, "down"),
+                (CheckStatus::Pass, "live"),
+                (CheckStatus::Fail, "missingcmd"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unwritable_directory_fails() {
+        let dir = TempDir::new().unwrap();
+        let config = config_with(&dir, "{}");
+        let blocker = dir.path().join("not-a-dir");
+        std::fs::write(&blocker, "").unwrap();
+
+        let mut env = env_for(&config);
+        env.directories = vec![
+            ("data", dir.path().join("data")),
+            ("cache", blocker.join("cache")),
+        ];
+        let results = check_directories(&env);
+        assert_eq!(
+            statuses(&results),
+            vec![CheckStatus::Pass, CheckStatus::Fail]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_otlp_endpoint_fails() {
+        let dir = TempDir::new().unwrap();
+        let unused = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", unused.local_addr().unwrap());
+        drop(unused);
+        let config = config_with(
+            &dir,
+            &format!("otel_exporter_otlp_endpoint: {}\n", endpoint),
+        );
+
+        let results = temp_env::async_with_vars(
+            [("OTEL_EXPORTER_OTLP_ENDPOINT", None::<&str>)],
+            check_telemetry(&env_for(&config)),
+        )
+        .await;
+        assert_eq!(statuses(&results), vec![CheckStatus::Fail]);
+        assert!(results[0].message.contains("unreachable"));
+    }
+}
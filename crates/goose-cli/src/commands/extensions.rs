@@ -0,0 +1,442 @@
+//! Non-interactive extension management (`goose extensions ...`), so extensions can be
+//! listed, toggled, added and checked without going through `goose configure`.
+
+use anyhow::{anyhow, bail, Result};
+use console::style;
+use goose::agents::extension::Envs;
+use goose::agents::ExtensionManager;
+use goose::config::extensions::name_to_key;
+use goose::config::{
+    Config, ExtensionConfig, ExtensionConfigManager, ExtensionEntry, DEFAULT_EXTENSION_TIMEOUT,
+};
+use rmcp::model::Tool;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtensionTransport {
+    Stdio,
+    Sse,
+    StreamableHttp,
+}
+
+/// Everything needed to add an extension from the command line
+#[derive(Debug, Clone)]
+pub struct NewExtension {
+    pub name: String,
+    pub transport: ExtensionTransport,
+    /// The command line for stdio extensions, the endpoint URI otherwise
+    pub target: String,
+    pub timeout: u64,
+    pub description: Option<String>,
+    /// Env vars the extension needs; values are prompted for unless already set
+    pub env_keys: Vec<String>,
+    pub headers: Vec<(String, String)>,
+}
+
+fn extension_type(config: &ExtensionConfig) -> &'static str {
+    match config {
+        ExtensionConfig::Sse { .. } => "sse",
+        ExtensionConfig::Stdio { .. } => "stdio",
+        ExtensionConfig::Builtin { .. } => "builtin",
+        ExtensionConfig::StreamableHttp { .. } => "streamable_http",
+        ExtensionConfig::Frontend { .. } => "frontend",
+        ExtensionConfig::InlinePython { .. } => "inline_python",
+    }
+}
+
+fn extension_timeout(config: &ExtensionConfig) -> Option<u64> {
+    match config {
+        ExtensionConfig::Sse { timeout, .. }
+        | ExtensionConfig::Stdio { timeout, .. }
+        | ExtensionConfig::Builtin { timeout, .. }
+        | ExtensionConfig::StreamableHttp { timeout, .. }
+        | ExtensionConfig::InlinePython { timeout, .. } => {
+            Some(timeout.unwrap_or(DEFAULT_EXTENSION_TIMEOUT))
+        }
+        ExtensionConfig::Frontend { .. } => None,
+    }
+}
+
+/// Find a configured extension by its name or its config key
+fn find_extension(config: &Config, name: &str) -> Result<ExtensionEntry> {
+    let key = name_to_key(name);
+    ExtensionConfigManager::get_all_in(config)?
+        .into_iter()
+        .find(|entry| entry.config.key() == key || entry.config.name() == name)
+        .ok_or_else(|| {
+            anyhow!(
+                "No extension named '{}'. Run 'goose extensions list' to see configured extensions",
+                name
+            )
+        })
+}
+
+pub fn render_extension_list(entries: &[ExtensionEntry]) -> String {
+    if entries.is_empty() {
+        return "No extensions configured.\n".to_string();
+    }
+
+    let mut rows: Vec<[String; 4]> = entries
+        .iter()
+        .map(|entry| {
+            [
+                entry.config.name(),
+                extension_type(&entry.config).to_string(),
+                if entry.enabled { "yes" } else { "no" }.to_string(),
+                extension_timeout(&entry.config)
+                    .map(|timeout| format!("{}s", timeout))
+                    .unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+    rows.sort_by(|a, b| a[0].to_lowercase().cmp(&b[0].to_lowercase()));
+    rows.insert(
+        0,
+        ["NAME", "TYPE", "ENABLED", "TIMEOUT"].map(|header| header.to_string()),
+    );
+
+    let widths: Vec<usize> = (0..4)
+        .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+        .collect();
+    let mut output = String::new();
+    for row in rows {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+pub fn set_extension_enabled(config: &Config, name: &str, enabled: bool) -> Result<()> {
+    let entry = find_extension(config, name)?;
+    ExtensionConfigManager::set_enabled_in(config, &entry.config.key(), enabled)
+}
+
+pub fn remove_extension(config: &Config, name: &str) -> Result<()> {
+    let entry = find_extension(config, name)?;
+    ExtensionConfigManager::remove_in(config, &entry.config.key())
+}
+
+/// Add a new, enabled extension. Env keys that have no value in the environment or the secret
+/// store are filled in with `prompt` and stored as secrets, falling back to the extension's own
+/// envs when the secret store is unavailable.
+pub fn add_extension(
+    config: &Config,
+    extension: NewExtension,
+    mut prompt: impl FnMut(&str) -> Result<String>,
+) -> Result<ExtensionEntry> {
+    if extension.name.trim().is_empty() {
+        bail!("Extension name cannot be empty");
+    }
+    if find_extension(config, &extension.name).is_ok() {
+        bail!("An extension named '{}' already exists", extension.name);
+    }
+
+    let mut envs = HashMap::new();
+    let mut env_keys = Vec::new();
+    for key in extension.env_keys {
+        if config.get_secret::<String>(&key).is_ok() {
+            env_keys.push(key);
+            continue;
+        }
+        let value = prompt(&key)?;
+        match config.set_secret(&key, Value::String(value.clone())) {
+            Ok(_) => env_keys.push(key),
+            Err(_) => {
+                envs.insert(key, value);
+            }
+        }
+    }
+
+    let name = extension.name;
+    let timeout = Some(extension.timeout);
+    let description = extension.description;
+    let entry_config = match extension.transport {
+        ExtensionTransport::Stdio => {
+            let mut parts = shlex::split(&extension.target)
+                .ok_or_else(|| anyhow!("Could not parse command: {}", extension.target))?
+                .into_iter();
+            let cmd = parts
+                .next()
+                .ok_or_else(|| anyhow!("A command is required for stdio extensions"))?;
+            ExtensionConfig::Stdio {
+                name,
+                cmd,
+                args: parts.collect(),
+                envs: Envs::new(envs),
+                env_keys,
+                timeout,
+                description,
+                bundled: None,
+                available_tools: Vec::new(),
+                depends_on: Vec::new(),
+                response_limits: None,
+            }
+        }
+        ExtensionTransport::Sse | ExtensionTransport::StreamableHttp => {
+            if !extension.target.starts_with("http") {
+                bail!("URI should start with http:// or https://");
+            }
+            if extension.transport == ExtensionTransport::Sse {
+                ExtensionConfig::Sse {
+                    name,
+                    uri: extension.target,
+                    envs: Envs::new(envs),
+                    env_keys,
+                    description,
+                    timeout,
+                    bundled: None,
+                    available_tools: Vec::new(),
+                    depends_on: Vec::new(),
+                    response_limits: None,
+                    keep_alive_interval: None,
+                }
+            } else {
+                ExtensionConfig::StreamableHttp {
+                    name,
+                    uri: extension.target,
+                    envs: Envs::new(envs),
+                    env_keys,
+                    headers: extension.headers.into_iter().collect(),
+                    description,
+                    timeout,
+                    bundled: None,
+                    available_tools: Vec::new(),
+                    depends_on: Vec::new(),
+                    response_limits: None,
+                    keep_alive_interval: None,
+                }
+            }
+        }
+    };
+
+    let entry = ExtensionEntry {
+        enabled: true,
+        config: entry_config,
+    };
+    ExtensionConfigManager::set_in(config, entry.clone())?;
+    Ok(entry)
+}
+
+/// Start the extension the same way a session would and return the tools it offers
+pub async fn test_extension(extension: ExtensionConfig) -> Result<Vec<Tool>> {
+    let name = extension.name();
+    let manager = ExtensionManager::default();
+    manager
+        .add_extension(extension)
+        .await
+        .map_err(|e| anyhow!("Extension '{}' failed to start: {}", name, e))?;
+    Ok(manager.get_prefixed_tools(None).await?)
+}
+
+pub fn handle_extensions_list() -> Result<()> {
+    let entries = ExtensionConfigManager::get_all_in(Config::global())?;
+    print!("{}", render_extension_list(&entries));
+    Ok(())
+}
+
+pub fn handle_extensions_enable(name: &str, enabled: bool) -> Result<()> {
+    set_extension_enabled(Config::global(), name, enabled)?;
+    let action = if enabled { "Enabled" } else { "Disabled" };
+    println!("{} extension {}", action, style(name).green());
+    Ok(())
+}
+
+pub fn handle_extensions_add(extension: NewExtension) -> Result<()> {
+    let entry = add_extension(Config::global(), extension, |key| {
+        Ok(cliclack::password(format!("Value for {}:", key))
+            .mask('▪')
+            .interact()?)
+    })?;
+    println!(
+        "Added extension {}. Run 'goose extensions test {}' to check that it starts",
+        style(entry.config.name()).green(),
+        entry.config.name()
+    );
+    Ok(())
+}
+
+pub fn handle_extensions_remove(name: &str) -> Result<()> {
+    remove_extension(Config::global(), name)?;
+    println!("Removed extension {}", style(name).green());
+    Ok(())
+}
+
+pub async fn handle_extensions_test(name: &str) -> Result<()> {
+    let entry = find_extension(Config::global(), name)?;
+    let tools = test_extension(entry.config).await?;
+    println!(
+        "{} {} started and offers {} tool(s)",
+        style("✓").green(),
+        style(name).green(),
+        tools.len()
+    );
+    for tool in tools {
+        println!("  - {}", tool.name);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const MOCK_CONFIG: &str = r#"extensions:
+  mockbuiltin:
+    enabled: true
+    type: builtin
+    name: mockbuiltin
+    display_name: Mock Builtin
+    timeout: 120
+    bundled: true
+"#;
+
+    fn temp_config(dir: &TempDir) -> Config {
+        let config_path = dir.path().join("config.yaml");
+        std::fs::write(&config_path, MOCK_CONFIG).unwrap();
+        Config::new_with_file_secrets(config_path, dir.path().join("secrets.yaml")).unwrap()
+    }
+
+    fn stdio_extension(name: &str, command: &str) -> NewExtension {
+        NewExtension {
+            name: name.to_string(),
+            transport: ExtensionTransport::Stdio,
+            target: command.to_string(),
+            timeout: 60,
+            description: None,
+            env_keys: Vec::new(),
+            headers: Vec::new(),
+        }
+    }
+
+    fn no_prompt(key: &str) -> Result<String> {
+        panic!("unexpected prompt for {}", key)
+    }
+
+    #[test]
+    fn test_list_shows_configured_extensions() {
+        let dir = TempDir::new().unwrap();
+        let config = temp_config(&dir);
+        let entries = ExtensionConfigManager::get_all_in(&config).unwrap();
+
+        assert_eq!(
+            render_extension_list(&entries),
+            "NAME         TYPE     ENABLED  TIMEOUT\nmockbuiltin  builtin  yes      120s\n"
+        );
+        assert_eq!(render_extension_list(&[]), "No extensions configured.\n");
+    }
+
+    #[test]
+    fn test_enable_and_disable_persist() {
+        let dir = TempDir::new().unwrap();
+        let config = temp_config(&dir);
+
+        set_extension_enabled(&config, "mockbuiltin", false).unwrap();
+        let saved = std::fs::read_to_string(dir.path().join("config.yaml")).unwrap();
+        assert!(saved.contains("enabled: false"));
+        assert!(!dir.path().join("config.tmp").exists());
+
+        set_extension_enabled(&config, "MockBuiltin", true).unwrap();
+        let entries = ExtensionConfigManager::get_all_in(&config).unwrap();
+        assert!(entries[0].enabled);
+
+        let err = set_extension_enabled(&config, "missing", true).unwrap_err();
+        assert!(err.to_string().contains("No extension named 'missing'"));
+    }
+
+    #[test]
+    fn test_add_and_remove_stdio_extension() {
+        let dir = TempDir::new().unwrap();
+        let config = temp_config(&dir);
+
+        let mut extension = stdio_extension("My Tools", "npx -y \"@acme/mcp server\"");
+        extension.env_keys = vec!["ACME_TOKEN".to_string()];
+        let mut prompted = Vec::new();
+        add_extension(&config, extension.clone(), |key| {
+            prompted.push(key.to_string());
+            Ok("secret-value".to_string())
+        })
+        .unwrap();
+        assert_eq!(prompted, vec!["ACME_TOKEN"]);
+        assert_eq!(
+            config.get_secret::<String>("ACME_TOKEN").unwrap(),
+            "secret-value"
+        );
+
+        match ExtensionConfigManager::get_all_in(&config)
+            .unwrap()
+            .into_iter()
+            .find(|entry| entry.config.key() == "mytools")
+            .unwrap()
+            .config
+        {
+            ExtensionConfig::Stdio {
+                cmd,
+                args,
+                env_keys,
+                timeout,
+                ..
+            } => {
+                assert_eq!(cmd, "npx");
+                assert_eq!(args, vec!["-y", "@acme/mcp server"]);
+                assert_eq!(env_keys, vec!["ACME_TOKEN"]);
+                assert_eq!(timeout, Some(60));
+            }
+            other => panic!("unexpected config: {:?}", other),
+        }
+
+        let err = add_extension(&config, extension, no_prompt).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+
+        remove_extension(&config, "My Tools").unwrap();
+        let names: Vec<String> = ExtensionConfigManager::get_all_in(&config)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.config.name())
+            .collect();
+        assert_eq!(names, vec!["mockbuiltin"]);
+    }
+
+    #[test]
+    fn test_add_remote_extension_requires_http_uri() {
+        let dir = TempDir::new().unwrap();
+        let config = temp_config(&dir);
+
+        let mut extension = stdio_extension("remote", "ftp://example.com");
+        extension.transport = ExtensionTransport::StreamableHttp;
+        assert!(add_extension(&config, extension.clone(), no_prompt).is_err());
+
+        extension.target = "https://example.com/mcp".to_string();
+        extension.headers = vec![("Authorization".to_string(), "Bearer x".to_string())];
+        let entry = add_extension(&config, extension, no_prompt).unwrap();
+        match entry.config {
+            ExtensionConfig::StreamableHttp { uri, headers, .. } => {
+                assert_eq!(uri, "https://example.com/mcp");
+                assert_eq!(headers["Authorization"], "Bearer x");
+            }
+            other => panic!("unexpected config: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reports_startup_error() {
+        let err = test_extension(ExtensionConfig::stdio(
+            "broken",
+            "goose-test-command-that-does-not-exist",
+            "",
+            5u64,
+        ))
+        .await
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Extension 'broken' failed to start:"));
+    }
+}
@@ -8,6 +8,14 @@ fn print_aligned(label: &str, value: &str, width: usize) {
     println!("  {:<width$} {}", label, value, width = width);
 }
 
+/// Strings are shown without quotes
+fn render(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
 pub fn handle_info(verbose: bool) -> Result<()> {
     let data_dir = choose_app_strategy(crate::APP_STRATEGY.clone())?;
     let logs_dir = data_dir
@@ -20,11 +28,22 @@ pub fn handle_info(verbose: bool) -> Result<()> {
     let config_file = config.path();
 
     // Define the labels and their corresponding path values once.
-    let paths = [
-        ("Config file:", config_file.to_string()),
+    let mut paths = vec![("Config file:", config_file.to_string())];
+    if let Some(project_file) = config.project_path() {
+        let trust = if config.is_project_trusted(project_file).unwrap_or(false) {
+            "trusted"
+        } else {
+            "not trusted"
+        };
+        paths.push((
+            "Project config:",
+            format!("{} ({})", project_file.display(), trust),
+        ));
+    }
+    paths.extend([
         ("Sessions dir:", sessions_dir.display().to_string()),
         ("Logs dir:", logs_dir.display().to_string()),
-    ];
+    ]);
 
     // Calculate padding: use the max length of the label plus extra space.
     let basic_padding = paths.iter().map(|(l, _)| l.len()).max().unwrap_or(0) + 4;
@@ -40,10 +59,25 @@ pub fn handle_info(verbose: bool) -> Result<()> {
         print_aligned(label, path, basic_padding);
     }
 
+    // Print the provider and model in effect, and where each one comes from
+    println!("\n{}", style("goose Provider:").cyan().bold());
+    for (label, key) in [("Provider:", "GOOSE_PROVIDER"), ("Model:", "GOOSE_MODEL")] {
+        let line = match config.get_with_provenance(key) {
+            Ok((value, source)) => format!(
+                "{} {}",
+                render(&value),
+                style(format!("(from {})", source)).dim()
+            ),
+            Err(_) => style("not set").dim().to_string(),
+        };
+        print_aligned(label, &line, basic_padding);
+    }
+
     // Print verbose info if requested
     if verbose {
         println!("\n{}", style("goose Configuration:").cyan().bold());
-        match config.load_values() {
+        // The project config is layered over the global one
+        match config.load_effective_values() {
             Ok(values) => {
                 if values.is_empty() {
                     println!("  No configuration values set");
@@ -60,6 +94,32 @@ pub fn handle_info(verbose: bool) -> Result<()> {
                             println!("  {}", line);
                         }
                     }
+
+                    let mut env_keys: Vec<_> = values
+                        .keys()
+                        .filter(|key| std::env::var_os(key.to_uppercase()).is_some())
+                        .cloned()
+                        .collect();
+                    if !env_keys.is_empty() {
+                        env_keys.sort();
+                        println!(
+                            "\n  {} {}",
+                            style("Overridden by environment variables:").dim(),
+                            env_keys.join(", ")
+                        );
+                    }
+
+                    if let Ok(project_values) = config.load_project_values() {
+                        let mut project_keys: Vec<_> = project_values.keys().cloned().collect();
+                        if !project_keys.is_empty() {
+                            project_keys.sort();
+                            println!(
+                                "\n  {} {}",
+                                style("Set by project config:").dim(),
+                                project_keys.join(", ")
+                            );
+                        }
+                    }
                 }
             }
             Err(e) => println!("  Error loading configuration: {}", e),
@@ -1,6 +1,9 @@
 pub mod acp;
 pub mod bench;
+pub mod config;
 pub mod configure;
+pub mod doctor;
+pub mod extensions;
 pub mod info;
 pub mod project;
 pub mod recipe;
@@ -2,13 +2,21 @@ use crate::session::message_to_markdown;
 use anyhow::{Context, Result};
 
 use cliclack::{confirm, multiselect, select};
+use goose::conversation::message::{Message, MessageContent};
+use goose::conversation::Conversation;
 use goose::session::{Session, SessionManager};
 use goose::utils::safe_truncate;
 use regex::Regex;
+use rmcp::model::Role;
+use serde_json::Value;
 use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::PathBuf;
+use std::time::Duration;
 
 const TRUNCATED_DESC_LENGTH: usize = 60;
+const LOG_TAIL_LINES: usize = 100;
+const LOG_FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
 
 pub async fn remove_sessions(sessions: Vec<Session>) -> Result<()> {
     println!("The following sessions will be removed:");
@@ -149,10 +157,22 @@ pub async fn handle_session_list(verbose: bool, format: String, ascending: bool)
     Ok(())
 }
 
+/// Options that narrow down what `goose session export` writes
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Only export the last N turns
+    pub last_turns: Option<usize>,
+    /// Replace tool call argument values with a placeholder
+    pub redact_args: bool,
+}
+
+const REDACTED_ARGUMENT: &str = "[redacted]";
+
 pub async fn handle_session_export(
     session_id: String,
     output_path: Option<PathBuf>,
     format: String,
+    options: ExportOptions,
 ) -> Result<()> {
     let session = match SessionManager::get_session(&session_id, true).await {
         Ok(session) => session,
@@ -165,7 +185,97 @@ pub async fn handle_session_export(
         }
     };
 
-    let output = match format.as_str() {
+    let output = render_session_export(session, &format, &options)?;
+
+    if let Some(output_path) = output_path {
+        fs::write(&output_path, output).with_context(|| {
+            format!("Failed to write to output file: {}", output_path.display())
+        })?;
+        println!("Session exported to {}", output_path.display());
+    } else {
+        println!("{}", output);
+    }
+
+    Ok(())
+}
+
+/// The id of the most recently updated session
+pub async fn most_recent_session_id() -> Result<String> {
+    SessionManager::list_sessions()
+        .await?
+        .into_iter()
+        .next()
+        .map(|session| session.id)
+        .ok_or_else(|| anyhow::anyhow!("No sessions found"))
+}
+
+/// Print the end of a session's log, then with `follow` keep printing what gets appended
+pub async fn handle_session_logs(session: Option<String>, follow: bool) -> Result<()> {
+    let session_id = match session {
+        Some(session) => SessionManager::list_sessions()
+            .await?
+            .into_iter()
+            .find(|s| s.id == session || s.description == session)
+            .map_or(session, |s| s.id),
+        None => most_recent_session_id().await?,
+    };
+    let path = crate::logging::find_session_log(&session_id)?
+        .ok_or_else(|| anyhow::anyhow!("No log found for session '{}'", session_id))?;
+
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let lines: Vec<&str> = content.lines().collect();
+    let mut stdout = std::io::stdout();
+    for line in &lines[lines.len().saturating_sub(LOG_TAIL_LINES)..] {
+        writeln!(stdout, "{}", line)?;
+    }
+    if !follow {
+        return Ok(());
+    }
+
+    let mut offset = content.len() as u64;
+    loop {
+        tokio::time::sleep(LOG_FOLLOW_INTERVAL).await;
+        let mut file = match fs::File::open(&path) {
+            Ok(file) => file,
+            // The log is briefly missing while it is rotated
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let len = file.metadata()?.len();
+        if len < offset {
+            offset = 0;
+        }
+        if len == offset {
+            continue;
+        }
+        file.seek(SeekFrom::Start(offset))?;
+        let mut appended = Vec::new();
+        file.read_to_end(&mut appended)?;
+        offset += appended.len() as u64;
+        stdout.write_all(&appended)?;
+        stdout.flush()?;
+    }
+}
+
+fn render_session_export(
+    mut session: Session,
+    format: &str,
+    options: &ExportOptions,
+) -> Result<String> {
+    if let Some(conversation) = session.conversation.take() {
+        let mut messages = conversation.messages().to_vec();
+        if let Some(turns) = options.last_turns {
+            messages = last_turns(messages, turns);
+        }
+        if options.redact_args {
+            messages.iter_mut().for_each(redact_tool_arguments);
+        }
+        session.message_count = messages.len();
+        session.conversation = Some(Conversation::new_unvalidated(messages));
+    }
+
+    Ok(match format {
         "json" => serde_json::to_string_pretty(&session)?,
         "yaml" => serde_yaml::to_string(&session)?,
         "markdown" => {
@@ -175,19 +285,49 @@ pub async fn handle_session_export(
             export_session_to_markdown(conversation.messages().to_vec(), &session.description)
         }
         _ => return Err(anyhow::anyhow!("Unsupported format: {}", format)),
-    };
+    })
+}
 
-    if let Some(output_path) = output_path {
-        fs::write(&output_path, output).with_context(|| {
-            format!("Failed to write to output file: {}", output_path.display())
-        })?;
-        println!("Session exported to {}", output_path.display());
-    } else {
-        println!("{}", output);
+/// A user message that is not just carrying tool results back starts a new turn
+fn starts_turn(message: &Message) -> bool {
+    message.role == Role::User
+        && !message
+            .content
+            .iter()
+            .all(|content| matches!(content, MessageContent::ToolResponse(_)))
+}
+
+/// Keep the messages belonging to the last `turns` turns
+fn last_turns(messages: Vec<Message>, turns: usize) -> Vec<Message> {
+    if turns == 0 {
+        return Vec::new();
     }
+    let start = messages
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, message)| starts_turn(message))
+        .nth(turns - 1)
+        .map(|(index, _)| index)
+        .unwrap_or(0);
+    messages.into_iter().skip(start).collect()
+}
 
-    Ok(())
+fn redact_tool_arguments(message: &mut Message) {
+    for content in message.content.iter_mut() {
+        if let MessageContent::ToolRequest(request) = content {
+            if let Ok(call) = request.tool_call.as_mut() {
+                match call.arguments.as_object_mut() {
+                    Some(arguments) => arguments
+                        .values_mut()
+                        .for_each(|value| *value = Value::from(REDACTED_ARGUMENT)),
+                    None => call.arguments = Value::from(REDACTED_ARGUMENT),
+                }
+            }
+        }
+    }
 }
+
 /// Convert a list of messages to markdown format for session export
 ///
 /// This function handles the formatting of a complete session including headers,
@@ -312,3 +452,126 @@ pub async fn prompt_interactive_session_selection() -> Result<String> {
         Err(anyhow::anyhow!("Invalid selection"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goose::session::extension_data::ExtensionData;
+    use mcp_core::tool::ToolCall;
+    use rmcp::model::Content;
+    use serde_json::json;
+    use std::path::Path;
+
+    const CREATED: i64 = 1735732800;
+
+    fn user(text: &str) -> Message {
+        Message::new(Role::User, CREATED, vec![]).with_text(text)
+    }
+
+    fn assistant(text: &str) -> Message {
+        Message::new(Role::Assistant, CREATED, vec![]).with_text(text)
+    }
+
+    fn shell_call(id: &str, arguments: Value) -> Message {
+        Message::new(Role::Assistant, CREATED, vec![])
+            .with_tool_request(id, Ok(ToolCall::new("developer__shell", arguments)))
+    }
+
+    fn shell_output(id: &str, output: &str) -> Message {
+        Message::new(Role::User, CREATED, vec![])
+            .with_tool_response(id, Ok(vec![Content::text(output)]))
+    }
+
+    fn fixture_session() -> Session {
+        let test_output: String = (1..=60)
+            .map(|n| format!("test build::case_{} ... ok\n", n))
+            .collect();
+        let messages = vec![
+            user("What's failing in the build?"),
+            shell_call("call_1", json!({"command": "cargo test"})),
+            shell_output("call_1", &test_output),
+            assistant("All 60 tests pass, the build is green."),
+            user("Commit it, and push with my token."),
+            shell_call(
+                "call_2",
+                json!({"command": "git commit -am fix && git push", "token": "ghp_secret"}),
+            ),
+            shell_output("call_2", "[main 1a2b3c4] fix"),
+            assistant("Committed and pushed."),
+        ];
+
+        Session {
+            id: "20250101_120000".to_string(),
+            working_dir: PathBuf::from("/home/user/project"),
+            description: "Fix the build".to_string(),
+            created_at: "2025-01-01 12:00:00".to_string(),
+            updated_at: "2025-01-01 12:05:00".to_string(),
+            extension_data: ExtensionData::default(),
+            total_tokens: Some(1200),
+            input_tokens: Some(1000),
+            output_tokens: Some(200),
+            accumulated_total_tokens: Some(1200),
+            accumulated_input_tokens: Some(1000),
+            accumulated_output_tokens: Some(200),
+            schedule_id: None,
+            recipe: None,
+            message_count: messages.len(),
+            conversation: Some(Conversation::new_unvalidated(messages)),
+        }
+    }
+
+    /// Compare against `src/commands/snapshots/<name>`; run with `UPDATE_SNAPSHOTS=1` to
+    /// rewrite the snapshot after an intended change
+    fn assert_snapshot(name: &str, actual: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/commands/snapshots")
+            .join(name);
+        if std::env::var("UPDATE_SNAPSHOTS").is_ok() {
+            fs::write(&path, actual).unwrap();
+        }
+        let expected = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Missing snapshot {}: {}", path.display(), e));
+        assert_eq!(actual, expected, "snapshot {} changed", name);
+    }
+
+    #[test]
+    fn test_export_markdown_snapshot() {
+        let output =
+            render_session_export(fixture_session(), "markdown", &ExportOptions::default())
+                .unwrap();
+        assert!(output.contains("<summary>Output (60 lines, 1.6 KB)</summary>"));
+        assert_snapshot("session_export.md", &output);
+    }
+
+    #[test]
+    fn test_export_json_snapshot() {
+        let options = ExportOptions {
+            last_turns: Some(1),
+            redact_args: true,
+        };
+        let output = render_session_export(fixture_session(), "json", &options).unwrap();
+        assert!(!output.contains("ghp_secret"));
+
+        let exported: Session = serde_json::from_str(&output).unwrap();
+        assert_eq!(exported.message_count, 4);
+        assert_eq!(exported.conversation.unwrap().len(), 4);
+        assert_snapshot("session_export_last_turn.json", &output);
+    }
+
+    #[test]
+    fn test_last_turns_keeps_tool_results_with_their_turn() {
+        let messages = fixture_session().conversation.unwrap().messages().to_vec();
+
+        assert_eq!(last_turns(messages.clone(), 1), messages[4..].to_vec());
+        assert_eq!(last_turns(messages.clone(), 2), messages);
+        assert_eq!(last_turns(messages.clone(), 10), messages);
+        assert!(last_turns(messages, 0).is_empty());
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        let err = render_session_export(fixture_session(), "html", &ExportOptions::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("Unsupported format"));
+    }
+}
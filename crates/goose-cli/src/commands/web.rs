@@ -80,7 +80,7 @@ enum WebSocketMessage {
 
 pub async fn handle_web(port: u16, host: String, open: bool) -> Result<()> {
     // Setup logging
-    crate::logging::setup_logging(Some("goose-web"), None)?;
+    crate::logging::setup_logging(Some("goose-web"), None, None)?;
 
     // Load config and create agent just like the CLI does
     let config = goose::config::Config::global();
@@ -109,21 +109,22 @@ pub async fn handle_web(port: u16, host: String, open: bool) -> Result<()> {
     agent.update_provider(provider).await?;
 
     // Load and enable extensions from config
-    let extensions = goose::config::ExtensionConfigManager::get_all()?;
-    for ext_config in extensions {
-        if ext_config.enabled {
-            if let Err(e) = agent.add_extension(ext_config.config.clone()).await {
-                eprintln!(
-                    "Warning: Failed to load extension {}: {}",
-                    ext_config.config.name(),
-                    e
-                );
-            }
+    let extensions = goose::config::ExtensionConfigManager::get_all()?
+        .into_iter()
+        .filter(|ext| ext.enabled)
+        .map(|ext| ext.config)
+        .collect();
+    let mut results = agent.add_extensions(extensions);
+    while let Some((name, result)) = results.next().await {
+        if let Err(e) = result {
+            eprintln!("Warning: Failed to load extension {}: {}", name, e);
         }
     }
+    drop(results);
 
+    let agent = Arc::new(agent);
     let state = AppState {
-        agent: Arc::new(agent),
+        agent: agent.clone(),
         cancellations: Arc::new(RwLock::new(std::collections::HashMap::new())),
     };
 
@@ -164,7 +165,11 @@ pub async fn handle_web(port: u16, host: String, open: bool) -> Result<()> {
     }
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(crate::signal::shutdown_signal())
+        .await?;
+    // Stop the extensions' processes rather than leaving them behind
+    agent.shutdown().await;
 
     Ok(())
 }
@@ -1,47 +1,176 @@
 use anyhow::{Context, Result};
+use once_cell::sync::OnceCell;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Once;
 use tokio::sync::Mutex;
-use tracing_appender::rolling::Rotation;
 use tracing_subscriber::{
     filter::LevelFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer,
     Registry,
 };
 
-use goose::tracing::{langfuse_layer, otlp_layer};
+use goose::tracing::{file_layer, langfuse_layer, otlp_layer, TelemetryGuard};
 use goose_bench::bench_session::BenchAgentError;
 use goose_bench::error_capture::ErrorCaptureLayer;
 
 // Used to ensure we only set up tracing once
 static INIT: Once = Once::new();
 
+/// The file writer of the global subscriber, switched over by `set_log_session`
+static SESSION_LOG: OnceCell<SessionLogWriter> = OnceCell::new();
+
+/// A session log that reaches this size is moved to `<session-id>.log.1` and started afresh,
+/// so each session keeps at most twice this much
+const MAX_SESSION_LOG_BYTES: u64 = 50 * 1024 * 1024;
+const DEFAULT_LOG_RETENTION_DAYS: u32 = 14;
+
 /// Returns the directory where log files should be stored.
 /// Creates the directory structure if it doesn't exist.
 fn get_log_directory() -> Result<PathBuf> {
     goose::logging::get_log_directory("cli", true)
 }
 
+/// Returns the log file of a session, the most recent one if it ran on several days.
+pub fn find_session_log(session_id: &str) -> Result<Option<PathBuf>> {
+    let cli_dir = goose::logging::get_log_directory("cli", false)?;
+    let file_name = format!("{}.log", session_id);
+    let mut date_dirs: Vec<_> = std::fs::read_dir(&cli_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.join(&file_name).is_file())
+        .collect();
+    // Date directories are named YYYY-MM-DD, so they sort chronologically
+    date_dirs.sort();
+    Ok(date_dirs.pop().map(|dir| dir.join(file_name)))
+}
+
+/// Sends file logs from here on to `<date>/<session-id>.log`, so that sessions running at the
+/// same time each get a file of their own. Does nothing if logging was not set up.
+pub fn set_log_session(session_id: &str) {
+    let Some(writer) = SESSION_LOG.get() else {
+        return;
+    };
+    match get_log_directory() {
+        Ok(log_dir) => writer.switch(log_dir, session_id),
+        Err(e) => tracing::warn!("Failed to switch to the session log: {}", e),
+    }
+}
+
+/// Writes whole log events to one file, rotating it at the size cap
+#[derive(Clone)]
+struct SessionLogWriter {
+    inner: Arc<std::sync::Mutex<SessionLogFile>>,
+}
+
+struct SessionLogFile {
+    path: PathBuf,
+    max_bytes: u64,
+    file: Option<File>,
+    written: u64,
+}
+
+impl SessionLogWriter {
+    fn new(path: PathBuf, max_bytes: u64) -> Self {
+        Self {
+            inner: Arc::new(std::sync::Mutex::new(SessionLogFile {
+                path,
+                max_bytes,
+                file: None,
+                written: 0,
+            })),
+        }
+    }
+
+    fn switch(&self, log_dir: PathBuf, session_id: &str) {
+        let mut log = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        log.path = log_dir.join(format!("{}.log", session_id));
+        log.file = None;
+    }
+}
+
+impl SessionLogFile {
+    fn write_event(&mut self, buf: &[u8]) -> io::Result<()> {
+        if self.file.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            self.written = file.metadata()?.len();
+            self.file = Some(file);
+        }
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.file = None;
+            let mut rotated = self.path.clone().into_os_string();
+            rotated.push(".1");
+            std::fs::rename(&self.path, rotated)?;
+            self.file = Some(File::create(&self.path)?);
+            self.written = 0;
+        }
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(buf)?;
+            self.written += buf.len() as u64;
+        }
+        Ok(())
+    }
+}
+
+impl Write for SessionLogWriter {
+    // The fmt layer formats each event into one buffer, so rotation never splits an event
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut log = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        log.write_event(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut log = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        match log.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+fn prune_old_logs() {
+    let retention_days = goose::config::Config::global()
+        .get_param::<u32>("GOOSE_LOG_RETENTION_DAYS")
+        .unwrap_or(DEFAULT_LOG_RETENTION_DAYS);
+    if let Err(e) = goose::logging::prune_log_directories("cli", retention_days) {
+        eprintln!("Warning: Failed to prune old logs: {}", e);
+    }
+}
+
 /// Sets up the logging infrastructure for the application.
 /// This includes:
-/// - File-based logging with JSON formatting (DEBUG level)
+/// - File-based logging with JSON formatting (DEBUG level), to the session's own file when
+///   `session_id` is given or once `set_log_session` is called
+/// - Log directories older than GOOSE_LOG_RETENTION_DAYS (default 14) are removed
 /// - Console output for development (INFO level)
 /// - Optional Langfuse integration (DEBUG level)
+/// - Optional JSON trace file when GOOSE_TRACE_FILE is set (DEBUG level)
 /// - Optional error capture layer for benchmarking
+///
+/// Returns a guard for flushing the OTLP and Langfuse exporters before exit. The guard is
+/// empty if logging was already set up or no exporter is configured.
 pub fn setup_logging(
     name: Option<&str>,
+    session_id: Option<&str>,
     error_capture: Option<Arc<Mutex<Vec<BenchAgentError>>>>,
-) -> Result<()> {
-    setup_logging_internal(name, error_capture, false)
+) -> Result<TelemetryGuard> {
+    setup_logging_internal(name, session_id, error_capture, false)
 }
 
 /// Internal function that allows bypassing the Once check for testing
 fn setup_logging_internal(
     name: Option<&str>,
+    session_id: Option<&str>,
     error_capture: Option<Arc<Mutex<Vec<BenchAgentError>>>>,
     force: bool,
-) -> Result<()> {
+) -> Result<TelemetryGuard> {
     let mut result = Ok(());
+    let mut telemetry = TelemetryGuard::new();
 
     // Register the error vector if provided
     if let Some(errors) = error_capture {
@@ -51,28 +180,29 @@ fn setup_logging_internal(
     let mut setup = || {
         result = (|| {
             // Set up file appender for goose module logs
+            if !force {
+                prune_old_logs();
+            }
             let log_dir = get_log_directory()?;
             let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
 
-            // Create log file name by prefixing with timestamp
-            let log_filename = if name.is_some() {
-                format!("{}-{}.log", timestamp, name.unwrap())
-            } else {
-                format!("{}.log", timestamp)
+            // Until a session is known, log to a file named by timestamp
+            let log_filename = match (session_id, name) {
+                (Some(session_id), _) => format!("{}.log", session_id),
+                (None, Some(name)) => format!("{}-{}.log", timestamp, name),
+                (None, None) => format!("{}.log", timestamp),
             };
-
-            // Create non-rolling file appender for detailed logs
-            let file_appender = tracing_appender::rolling::RollingFileAppender::new(
-                Rotation::NEVER,
-                log_dir,
-                log_filename,
-            );
+            let file_writer =
+                SessionLogWriter::new(log_dir.join(log_filename), MAX_SESSION_LOG_BYTES);
+            if !force {
+                let _ = SESSION_LOG.set(file_writer.clone());
+            }
 
             // Create JSON file logging layer with all logs (DEBUG and above)
             let file_layer = fmt::layer()
                 .with_target(true)
                 .with_level(true)
-                .with_writer(file_appender)
+                .with_writer(move || file_writer.clone())
                 .with_ansi(false)
                 .json();
 
@@ -112,7 +242,10 @@ fn setup_logging_internal(
             }
 
             if !force {
-                if let Ok((otlp_tracing_layer, otlp_metrics_layer)) = otlp_layer::init_otlp() {
+                if let Ok(((otlp_tracing_layer, otlp_metrics_layer), otlp_guard)) =
+                    otlp_layer::init_otlp()
+                {
+                    telemetry.merge(otlp_guard);
                     layers.push(
                         otlp_tracing_layer
                             .with_filter(otlp_layer::create_otlp_tracing_filter())
@@ -126,10 +259,21 @@ fn setup_logging_internal(
                 }
             }
 
-            if let Some(langfuse) = langfuse_layer::create_langfuse_observer() {
+            if let Some((langfuse, langfuse_guard)) =
+                langfuse_layer::create_langfuse_observer_with_guard()
+            {
+                telemetry.merge(langfuse_guard);
                 layers.push(langfuse.with_filter(LevelFilter::DEBUG).boxed());
             }
 
+            if let Some(trace_file) = file_layer::create_file_trace_layer() {
+                layers.push(
+                    trace_file
+                        .with_filter(file_layer::create_file_trace_filter())
+                        .boxed(),
+                );
+            }
+
             // Build the subscriber
             let subscriber = Registry::default().with(layers);
 
@@ -137,7 +281,7 @@ fn setup_logging_internal(
                 // For testing, just create and use the subscriber without setting it globally
                 // Write a test log to ensure the file is created
                 let _guard = subscriber.set_default();
-                tracing::warn!("Test log entry from setup");
+                tracing::warn!(session_id, "Test log entry from setup");
                 tracing::info!("Another test log entry from setup");
                 // Flush the output
                 std::thread::sleep(std::time::Duration::from_millis(100));
@@ -158,7 +302,7 @@ fn setup_logging_internal(
         INIT.call_once(setup);
     }
 
-    result
+    result.map(|_| telemetry)
 }
 
 #[cfg(test)]
@@ -191,6 +335,55 @@ mod tests {
         assert!(path_components.iter().any(|c| c.as_os_str() == "cli"));
     }
 
+    fn read_log(path: &std::path::Path) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn test_sessions_log_to_their_own_files() {
+        let _temp_dir = setup_temp_home();
+        setup_logging_internal(None, Some("fake-session-a"), None, true).unwrap();
+        setup_logging_internal(None, Some("fake-session-b"), None, true).unwrap();
+
+        let log_a = find_session_log("fake-session-a").unwrap().unwrap();
+        let log_b = find_session_log("fake-session-b").unwrap().unwrap();
+        assert_eq!(log_a.parent(), Some(get_log_directory().unwrap().as_path()));
+        assert!(read_log(&log_a).contains(r#""session_id":"fake-session-a""#));
+        assert!(!read_log(&log_a).contains("fake-session-b"));
+        assert!(read_log(&log_b).contains(r#""session_id":"fake-session-b""#));
+        assert!(!read_log(&log_b).contains("fake-session-a"));
+        assert!(find_session_log("fake-session-c").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_session_log_switches_and_rotates() {
+        let dir = TempDir::new().unwrap();
+        let writer = SessionLogWriter::new(dir.path().join("startup.log"), 100);
+        let file_writer = writer.clone();
+        let subscriber = Registry::default().with(
+            fmt::layer()
+                .with_writer(move || file_writer.clone())
+                .with_ansi(false)
+                .without_time(),
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("before the session");
+            writer.switch(dir.path().to_path_buf(), "session-1");
+            tracing::info!("first event");
+            tracing::info!("second event");
+            tracing::info!("third event");
+        });
+
+        assert!(read_log(&dir.path().join("startup.log")).contains("before the session"));
+        // Each event is about 45 bytes, so the third starts a new file
+        let rotated = read_log(&dir.path().join("session-1.log.1"));
+        assert!(rotated.contains("first event") && rotated.contains("second event"));
+        let current = read_log(&dir.path().join("session-1.log"));
+        assert!(current.contains("third event"));
+        assert!(!current.contains("second event"));
+    }
+
     #[tokio::test]
     async fn test_langfuse_layer_creation() {
         let _temp_dir = setup_temp_home();
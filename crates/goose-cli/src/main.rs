@@ -1,38 +1,30 @@
 use anyhow::Result;
-use goose_cli::cli::cli;
+use goose::tracing::TelemetryGuard;
+use goose_cli::cli::{cli, CliExit};
+use std::time::Duration;
+
+/// Upper bound on how long exit waits for pending spans, metrics and Langfuse batches
+const TELEMETRY_FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    if let Err(e) = goose_cli::logging::setup_logging(None, None) {
+    let telemetry = goose_cli::logging::setup_logging(None, None, None).unwrap_or_else(|e| {
         eprintln!("Warning: Failed to initialize telemetry: {}", e);
-    }
+        TelemetryGuard::new()
+    });
 
     let result = cli().await;
 
-    // Only wait for telemetry flush if OTLP is configured
-    let should_wait = goose::config::Config::global()
-        .get_param::<String>("otel_exporter_otlp_endpoint")
-        .is_ok();
-
-    if should_wait {
-        // Use a shorter, dynamic wait with max timeout
-        let max_wait = tokio::time::Duration::from_millis(500);
-        let start = tokio::time::Instant::now();
-
-        // Give telemetry a chance to flush, but don't wait too long
-        while start.elapsed() < max_wait {
-            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
-
-            // In future, we could check if there are pending spans/metrics here
-            // For now, we just do a quick wait to allow batch exports to complete
-            if start.elapsed() >= tokio::time::Duration::from_millis(200) {
-                break; // Most exports should complete within 200ms
-            }
-        }
-
-        // Then shutdown the providers
+    telemetry.flush_with_timeout(TELEMETRY_FLUSH_TIMEOUT).await;
+    if !telemetry.is_empty() {
         goose::tracing::shutdown_otlp();
     }
 
-    result
+    match result {
+        Err(e) => match e.downcast_ref::<CliExit>() {
+            Some(CliExit(code)) => std::process::exit(*code),
+            None => Err(e),
+        },
+        ok => ok,
+    }
 }
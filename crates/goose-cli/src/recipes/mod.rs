@@ -1,6 +1,7 @@
 pub mod extract_from_cli;
 pub mod github_recipe;
 pub mod print_recipe;
+pub mod prompt_file;
 pub mod recipe;
 pub mod search_recipe;
 pub mod secret_discovery;
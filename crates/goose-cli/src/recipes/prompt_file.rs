@@ -0,0 +1,497 @@
+//! Prompt files: a reusable prompt with a YAML front-matter block declaring its parameters
+//! and the extensions it needs, run with `goose run --prompt-file`.
+//!
+//! ```markdown
+//! ---
+//! parameters:
+//!   - name: pr
+//!     type: number
+//!     required: true
+//!   - name: focus
+//!     default: correctness
+//! extensions: [developer, github]
+//! ---
+//! Review PR #{{pr}}, focusing on {{focus}}.
+//! ```
+//!
+//! Files without front matter are used as the prompt as they are.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use goose::config::{ExtensionConfig, ExtensionConfigManager};
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::cli::InputConfig;
+
+const FRONT_MATTER_DELIMITER: &str = "---";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptParamType {
+    #[default]
+    String,
+    Number,
+    Boolean,
+}
+
+impl PromptParamType {
+    fn accepts(&self, value: &str) -> bool {
+        match self {
+            PromptParamType::String => true,
+            PromptParamType::Number => value.parse::<f64>().is_ok_and(|n| n.is_finite()),
+            PromptParamType::Boolean => matches!(value, "true" | "false"),
+        }
+    }
+}
+
+impl fmt::Display for PromptParamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PromptParamType::String => "string",
+            PromptParamType::Number => "number",
+            PromptParamType::Boolean => "boolean",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PromptParameter {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub param_type: PromptParamType,
+    #[serde(default)]
+    pub required: bool,
+    /// Used when the parameter isn't supplied; any YAML scalar
+    pub default: Option<serde_yaml::Value>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FrontMatter {
+    #[serde(default)]
+    parameters: Vec<PromptParameter>,
+    #[serde(default)]
+    extensions: Vec<String>,
+}
+
+/// Why a prompt file couldn't be parsed or rendered
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptFileError {
+    UnterminatedFrontMatter,
+    InvalidFrontMatter(String),
+    DuplicateParameter(String),
+    InvalidDefault {
+        name: String,
+        expected: PromptParamType,
+    },
+    /// The template uses placeholders no parameter declares
+    UndeclaredPlaceholders(Vec<String>),
+    MissingParameters(Vec<String>),
+    UnknownParameters {
+        unknown: Vec<String>,
+        known: Vec<String>,
+    },
+    InvalidValue {
+        name: String,
+        expected: PromptParamType,
+        value: String,
+    },
+}
+
+impl fmt::Display for PromptFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PromptFileError::UnterminatedFrontMatter => write!(
+                f,
+                "Front matter is not closed; end it with a '{}' line",
+                FRONT_MATTER_DELIMITER
+            ),
+            PromptFileError::InvalidFrontMatter(e) => write!(f, "Invalid front matter: {}", e),
+            PromptFileError::DuplicateParameter(name) => {
+                write!(f, "Parameter '{}' is declared more than once", name)
+            }
+            PromptFileError::InvalidDefault { name, expected } => {
+                write!(f, "Default of parameter '{}' is not a {}", name, expected)
+            }
+            PromptFileError::UndeclaredPlaceholders(names) => write!(
+                f,
+                "The prompt uses undeclared parameters: {}",
+                names.join(", ")
+            ),
+            PromptFileError::MissingParameters(names) => write!(
+                f,
+                "Missing required parameters: {} (pass them with --params key=value)",
+                names.join(", ")
+            ),
+            PromptFileError::UnknownParameters { unknown, known } => {
+                write!(f, "Unknown parameters: {}", unknown.join(", "))?;
+                if known.is_empty() {
+                    write!(f, " (the prompt takes no parameters)")
+                } else {
+                    write!(f, " (expected one of: {})", known.join(", "))
+                }
+            }
+            PromptFileError::InvalidValue {
+                name,
+                expected,
+                value,
+            } => write!(
+                f,
+                "Parameter '{}' must be a {}, got '{}'",
+                name, expected, value
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PromptFileError {}
+
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").expect("valid placeholder regex")
+}
+
+/// A YAML scalar as the text it substitutes into the prompt
+fn scalar_to_string(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptFile {
+    pub parameters: Vec<PromptParameter>,
+    /// Names of configured extensions to enable for the run
+    pub extensions: Vec<String>,
+    pub template: String,
+}
+
+impl PromptFile {
+    pub fn parse(content: &str) -> Result<Self, PromptFileError> {
+        let (front_matter, template) = split_front_matter(content)?;
+        let front_matter: FrontMatter = match front_matter {
+            Some(yaml) if !yaml.trim().is_empty() => serde_yaml::from_str(yaml)
+                .map_err(|e| PromptFileError::InvalidFrontMatter(e.to_string()))?,
+            _ => FrontMatter::default(),
+        };
+
+        let mut seen = HashSet::new();
+        for param in &front_matter.parameters {
+            if !seen.insert(param.name.as_str()) {
+                return Err(PromptFileError::DuplicateParameter(param.name.clone()));
+            }
+            if let Some(default) = &param.default {
+                if !scalar_to_string(default).is_some_and(|v| param.param_type.accepts(&v)) {
+                    return Err(PromptFileError::InvalidDefault {
+                        name: param.name.clone(),
+                        expected: param.param_type,
+                    });
+                }
+            }
+        }
+
+        let mut undeclared: Vec<String> = placeholder_regex()
+            .captures_iter(template)
+            .map(|c| c[1].to_string())
+            .filter(|name| !seen.contains(name.as_str()))
+            .collect();
+        if !undeclared.is_empty() {
+            undeclared.sort();
+            undeclared.dedup();
+            return Err(PromptFileError::UndeclaredPlaceholders(undeclared));
+        }
+
+        Ok(Self {
+            parameters: front_matter.parameters,
+            extensions: front_matter.extensions,
+            template: template.to_string(),
+        })
+    }
+
+    /// The value of every parameter, from `params` or the defaults, checked against the
+    /// declared types. Optional parameters without a default are empty.
+    pub fn values(
+        &self,
+        params: &[(String, String)],
+    ) -> Result<HashMap<String, String>, PromptFileError> {
+        let known: Vec<String> = self.parameters.iter().map(|p| p.name.clone()).collect();
+        let unknown: Vec<String> = params
+            .iter()
+            .map(|(key, _)| key.clone())
+            .filter(|key| !known.contains(key))
+            .collect();
+        if !unknown.is_empty() {
+            return Err(PromptFileError::UnknownParameters { unknown, known });
+        }
+
+        let supplied: HashMap<&str, &str> = params
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        let mut values = HashMap::new();
+        let mut missing = Vec::new();
+        for param in &self.parameters {
+            let value = match supplied.get(param.name.as_str()) {
+                Some(value) => value.to_string(),
+                None => match param.default.as_ref().and_then(scalar_to_string) {
+                    Some(default) => default,
+                    None if param.required => {
+                        missing.push(param.name.clone());
+                        continue;
+                    }
+                    None => String::new(),
+                },
+            };
+            if !param.param_type.accepts(&value) {
+                return Err(PromptFileError::InvalidValue {
+                    name: param.name.clone(),
+                    expected: param.param_type,
+                    value,
+                });
+            }
+            values.insert(param.name.clone(), value);
+        }
+        if !missing.is_empty() {
+            return Err(PromptFileError::MissingParameters(missing));
+        }
+        Ok(values)
+    }
+
+    /// The prompt with each `{{param}}` replaced by its value
+    pub fn render(&self, params: &[(String, String)]) -> Result<String, PromptFileError> {
+        let values = self.values(params)?;
+        Ok(placeholder_regex()
+            .replace_all(&self.template, |c: &regex::Captures| values[&c[1]].clone())
+            .trim()
+            .to_string())
+    }
+
+    /// The enabled extensions plus the ones the prompt file lists, which must be configured
+    pub fn extension_configs(&self) -> Result<Vec<ExtensionConfig>> {
+        let mut configs: Vec<ExtensionConfig> = ExtensionConfigManager::get_all()?
+            .into_iter()
+            .filter(|ext| ext.enabled)
+            .map(|ext| ext.config)
+            .collect();
+        let mut unknown = Vec::new();
+        for name in &self.extensions {
+            if configs.iter().any(|config| &config.name() == name) {
+                continue;
+            }
+            match ExtensionConfigManager::get_config_by_name(name)? {
+                Some(config) => configs.push(config),
+                None => unknown.push(name.clone()),
+            }
+        }
+        if !unknown.is_empty() {
+            return Err(anyhow!(
+                "The prompt file needs extensions that are not configured: {}. Add them with `goose configure`",
+                unknown.join(", ")
+            ));
+        }
+        Ok(configs)
+    }
+}
+
+/// Split a prompt file into its front matter, if it has any, and the template after it
+fn split_front_matter(content: &str) -> Result<(Option<&str>, &str), PromptFileError> {
+    let content = content.trim_start_matches('\u{feff}');
+    let Some(rest) = content
+        .strip_prefix(FRONT_MATTER_DELIMITER)
+        .and_then(|rest| {
+            rest.strip_prefix('\n')
+                .or_else(|| rest.strip_prefix("\r\n"))
+        })
+    else {
+        return Ok((None, content));
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == FRONT_MATTER_DELIMITER {
+            return Ok((Some(&rest[..offset]), &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    Err(PromptFileError::UnterminatedFrontMatter)
+}
+
+pub fn extract_prompt_file_from_cli(
+    path: &Path,
+    params: &[(String, String)],
+    additional_system_prompt: Option<String>,
+) -> Result<InputConfig> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Could not read prompt file {}: {}", path.display(), e))?;
+    let prompt_file = PromptFile::parse(&content)
+        .map_err(|e| anyhow!("Invalid prompt file {}: {}", path.display(), e))?;
+    let contents = prompt_file.render(params)?;
+
+    let extensions_override = if prompt_file.extensions.is_empty() {
+        None
+    } else {
+        Some(prompt_file.extension_configs()?)
+    };
+
+    Ok(InputConfig {
+        contents: Some(contents),
+        extensions_override,
+        additional_system_prompt,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REVIEW: &str = "---
+parameters:
+  - name: pr
+    type: number
+    required: true
+  - name: focus
+    default: correctness
+  - name: strict
+    type: boolean
+    default: false
+extensions: [developer]
+---
+Review PR #{{pr}}, focusing on {{ focus }}. Strict: {{strict}}.
+";
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_front_matter() {
+        let prompt_file = PromptFile::parse(REVIEW).unwrap();
+        assert_eq!(prompt_file.extensions, vec!["developer"]);
+        let names: Vec<&str> = prompt_file
+            .parameters
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["pr", "focus", "strict"]);
+        assert_eq!(
+            prompt_file.parameters[0].param_type,
+            PromptParamType::Number
+        );
+        assert!(prompt_file.parameters[0].required);
+        assert_eq!(
+            prompt_file.parameters[1].param_type,
+            PromptParamType::String
+        );
+        assert!(prompt_file.template.starts_with("Review PR"));
+    }
+
+    #[test]
+    fn test_file_without_front_matter_is_the_prompt() {
+        let prompt_file = PromptFile::parse("Triage the logs\n").unwrap();
+        assert!(prompt_file.parameters.is_empty());
+        assert_eq!(prompt_file.render(&[]).unwrap(), "Triage the logs");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            PromptFile::parse("---\nparameters: []\nReview it\n").unwrap_err(),
+            PromptFileError::UnterminatedFrontMatter
+        );
+        assert!(matches!(
+            PromptFile::parse("---\nparams: []\n---\nReview it\n").unwrap_err(),
+            PromptFileError::InvalidFrontMatter(_)
+        ));
+        assert_eq!(
+            PromptFile::parse("---\nparameters:\n  - name: a\n  - name: a\n---\n{{a}}\n")
+                .unwrap_err(),
+            PromptFileError::DuplicateParameter("a".to_string())
+        );
+        assert_eq!(
+            PromptFile::parse(
+                "---\nparameters:\n  - name: n\n    type: number\n    default: many\n---\n{{n}}\n"
+            )
+            .unwrap_err(),
+            PromptFileError::InvalidDefault {
+                name: "n".to_string(),
+                expected: PromptParamType::Number
+            }
+        );
+        assert_eq!(
+            PromptFile::parse("Look at {{repo}} and {{branch}}, then {{repo}}\n").unwrap_err(),
+            PromptFileError::UndeclaredPlaceholders(vec!["branch".to_string(), "repo".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_validation_failures() {
+        let prompt_file = PromptFile::parse(REVIEW).unwrap();
+
+        let err = prompt_file.render(&[]).unwrap_err();
+        assert_eq!(
+            err,
+            PromptFileError::MissingParameters(vec!["pr".to_string()])
+        );
+        assert!(err.to_string().contains("Missing required parameters: pr"));
+
+        assert_eq!(
+            prompt_file
+                .render(&params(&[("pr", "42"), ("reviewer", "sam")]))
+                .unwrap_err(),
+            PromptFileError::UnknownParameters {
+                unknown: vec!["reviewer".to_string()],
+                known: vec!["pr".to_string(), "focus".to_string(), "strict".to_string()],
+            }
+        );
+        assert_eq!(
+            prompt_file
+                .render(&params(&[("pr", "latest")]))
+                .unwrap_err(),
+            PromptFileError::InvalidValue {
+                name: "pr".to_string(),
+                expected: PromptParamType::Number,
+                value: "latest".to_string(),
+            }
+        );
+        assert!(matches!(
+            prompt_file
+                .render(&params(&[("pr", "42"), ("strict", "yes")]))
+                .unwrap_err(),
+            PromptFileError::InvalidValue { name, .. } if name == "strict"
+        ));
+    }
+
+    #[test]
+    fn test_substitution() {
+        let prompt_file = PromptFile::parse(REVIEW).unwrap();
+        assert_eq!(
+            prompt_file.render(&params(&[("pr", "42")])).unwrap(),
+            "Review PR #42, focusing on correctness. Strict: false."
+        );
+        assert_eq!(
+            prompt_file
+                .render(&params(&[
+                    ("pr", "7"),
+                    ("focus", "naming"),
+                    ("strict", "true")
+                ]))
+                .unwrap(),
+            "Review PR #7, focusing on naming. Strict: true."
+        );
+
+        let optional =
+            PromptFile::parse("---\nparameters:\n  - name: note\n---\nDone.{{note}}\n").unwrap();
+        assert_eq!(optional.render(&[]).unwrap(), "Done.");
+    }
+}
@@ -144,6 +144,9 @@ mod tests {
                     timeout: None,
                     bundled: None,
                     available_tools: Vec::new(),
+                    depends_on: Vec::new(),
+                    response_limits: None,
+                    keep_alive_interval: None,
                 },
                 ExtensionConfig::Stdio {
                     name: "slack-mcp".to_string(),
@@ -155,6 +158,8 @@ mod tests {
                     description: None,
                     bundled: None,
                     available_tools: Vec::new(),
+                    depends_on: Vec::new(),
+                    response_limits: None,
                 },
                 ExtensionConfig::Builtin {
                     name: "builtin-ext".to_string(),
@@ -163,6 +168,8 @@ mod tests {
                     timeout: None,
                     bundled: None,
                     available_tools: Vec::new(),
+                    depends_on: Vec::new(),
+                    response_limits: None,
                 },
             ]),
             context: None,
@@ -241,6 +248,9 @@ mod tests {
                     timeout: None,
                     bundled: None,
                     available_tools: Vec::new(),
+                    depends_on: Vec::new(),
+                    response_limits: None,
+                    keep_alive_interval: None,
                 },
                 ExtensionConfig::Stdio {
                     name: "service-b".to_string(),
@@ -252,6 +262,8 @@ mod tests {
                     description: None,
                     bundled: None,
                     available_tools: Vec::new(),
+                    depends_on: Vec::new(),
+                    response_limits: None,
                 },
             ]),
             context: None,
@@ -300,6 +312,9 @@ mod tests {
                 timeout: None,
                 bundled: None,
                 available_tools: Vec::new(),
+                depends_on: Vec::new(),
+                response_limits: None,
+                keep_alive_interval: None,
             }]),
             sub_recipes: Some(vec![SubRecipe {
                 name: "child-recipe".to_string(),
@@ -4,7 +4,7 @@
 use crate::scenario_tests::scenario_runner::SCENARIO_TESTS_DIR;
 use base64::engine::general_purpose;
 use base64::Engine;
-use goose::conversation::message::Message;
+use goose::conversation::message::{Message, MessageContent};
 use goose::providers::base::Provider;
 
 pub type MessageGenerator<'a> = Box<dyn Fn(&dyn Provider) -> Message + 'a>;
@@ -28,6 +28,6 @@ pub fn image(text: &str, image_name: &str) -> MessageGenerator<'static> {
         let base64_data = general_purpose::STANDARD.encode(&image_data);
         Message::user()
             .with_text(&text)
-            .with_image(base64_data, "image/jpeg")
+            .with_content(MessageContent::image(base64_data, "image/jpeg"))
     })
 }
@@ -5,6 +5,8 @@ mod mock_client;
 #[cfg(test)]
 mod provider_configs;
 #[cfg(test)]
+mod run_output;
+#[cfg(test)]
 mod scenario_runner;
 #[cfg(test)]
 mod scenarios;
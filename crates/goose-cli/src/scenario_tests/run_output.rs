@@ -0,0 +1,195 @@
+//! Runs canned headless sessions against a scripted provider and parses the machine-readable
+//! output they produce.
+
+#[cfg(test)]
+mod tests {
+    use crate::scenario_tests::mock_client::{weather_client, WEATHER_TYPE};
+    use crate::session::{CliSession, OutputFormat, RunRecorder, RunReport, RunStatus};
+    use async_trait::async_trait;
+    use goose::agents::Agent;
+    use goose::config::ExtensionConfig;
+    use goose::conversation::message::Message;
+    use goose::model::ModelConfig;
+    use goose::providers::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
+    use goose::providers::errors::ProviderError;
+    use mcp_core::tool::ToolCall;
+    use rmcp::model::Tool;
+    use serde_json::{json, Value};
+    use std::collections::VecDeque;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    /// Replies with each scripted message in turn; `None` fails the request
+    struct ScriptedProvider {
+        replies: Mutex<VecDeque<Option<Message>>>,
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &ModelConfig,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            match self.replies.lock().unwrap().pop_front().flatten() {
+                Some(message) => Ok((
+                    message,
+                    ProviderUsage::new("mock".to_string(), Usage::new(Some(10), Some(5), Some(15))),
+                )),
+                None => Err(ProviderError::Authentication("invalid api key".to_string())),
+            }
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new_or_fail("mock")
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    fn weather_call() -> Option<Message> {
+        Some(Message::assistant().with_tool_request(
+            "call_1",
+            Ok(ToolCall::new(
+                "weather_extension__get_weather",
+                json!({"location": "Paris, France"}),
+            )),
+        ))
+    }
+
+    fn reply(text: &str) -> Option<Message> {
+        Some(Message::assistant().with_text(text))
+    }
+
+    async fn run_headless(
+        format: OutputFormat,
+        replies: Vec<Option<Message>>,
+    ) -> (RunReport, String) {
+        let agent = Agent::new();
+        agent
+            .extension_manager
+            .add_client(
+                "weather_extension".to_string(),
+                ExtensionConfig::Builtin {
+                    name: "".to_string(),
+                    display_name: None,
+                    description: None,
+                    timeout: None,
+                    bundled: None,
+                    available_tools: vec![],
+                    depends_on: Vec::new(),
+                    response_limits: None,
+                },
+                Arc::new(weather_client()),
+                None,
+                None,
+            )
+            .await;
+        agent
+            .update_provider(Arc::new(ScriptedProvider {
+                replies: Mutex::new(replies.into()),
+            }))
+            .await
+            .unwrap();
+
+        let buffer = SharedBuffer::default();
+        let mut session = CliSession::new(agent, None, false, None, None, None, None);
+        session.set_run_recorder(RunRecorder::with_writer(format, Box::new(buffer.clone())));
+
+        let result = session
+            .headless("What's the weather in Paris?".to_string())
+            .await;
+        let report = session.finish_run(&result).await.unwrap();
+        (report, buffer.contents())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_json_output_reports_the_run() {
+        let (report, output) = run_headless(
+            OutputFormat::Json,
+            vec![weather_call(), reply("It's sunny in Paris.")],
+        )
+        .await;
+
+        let document: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(document["status"], "completed");
+        assert_eq!(document["exit_code"], 0);
+        assert_eq!(document["final_message"], "It's sunny in Paris.");
+
+        let tool_calls = document["tool_calls"].as_array().unwrap();
+        assert_eq!(tool_calls.len(), 1);
+        assert_eq!(tool_calls[0]["name"], "weather_extension__get_weather");
+        assert_eq!(tool_calls[0]["success"], true);
+        assert!(tool_calls[0]["duration_ms"].is_u64());
+
+        assert_eq!(report.status, RunStatus::Completed);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_json_stream_emits_events_then_report() {
+        let (_, output) = run_headless(
+            OutputFormat::JsonStream,
+            vec![weather_call(), reply("It's sunny in Paris.")],
+        )
+        .await;
+
+        let events: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let types: Vec<&str> = events
+            .iter()
+            .map(|event| event["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                "message",
+                "tool_call_started",
+                "message",
+                "tool_call_finished",
+                "message",
+                "finished"
+            ]
+        );
+
+        let tool_result = events[2]["message"]["content"][0]["toolResult"].to_string();
+        assert!(tool_result.contains(WEATHER_TYPE));
+        assert_eq!(events[5]["report"]["status"], "completed");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_provider_error_sets_status_and_exit_code() {
+        let (report, output) = run_headless(OutputFormat::Json, vec![None]).await;
+
+        let document: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(document["status"], "provider_error");
+        assert_eq!(document["exit_code"], 2);
+        assert_eq!(document["error"], "Authentication error: invalid api key");
+        assert_eq!(report.status.exit_code(), 2);
+    }
+}
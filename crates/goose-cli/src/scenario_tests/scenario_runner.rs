@@ -137,7 +137,6 @@ where
     F: Fn(&ScenarioResult) -> Result<()>,
 {
     use goose::config::ExtensionConfig;
-    use tokio::sync::Mutex;
 
     if let Ok(path) = dotenv() {
         println!("Loaded environment from {:?}", path);
@@ -207,8 +206,10 @@ where
                 timeout: None,
                 bundled: None,
                 available_tools: vec![],
+                depends_on: Vec::new(),
+                response_limits: None,
             },
-            Arc::new(Mutex::new(Box::new(mock_client))),
+            Arc::new(mock_client),
             None,
             None,
         )
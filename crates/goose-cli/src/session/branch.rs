@@ -0,0 +1,182 @@
+//! Resuming a session from a trimmed copy of its history, so a long session can be picked up
+//! again without replaying every message to the model.
+
+use anyhow::{Context, Result};
+use goose::context_mgmt::summarize::summarize_messages;
+use goose::conversation::{fix_conversation, Conversation};
+use goose::providers::base::Provider;
+use goose::session::SessionManager;
+use std::sync::Arc;
+
+/// Keep the trailing `last_n` messages of `conversation`. With a `summarizer`, the older
+/// messages are summarized in one shot and the summary leads the result as a user message.
+///
+/// The result is run through [`fix_conversation`], so a cut that lands in the middle of a tool
+/// call, or on an assistant message, still produces a valid conversation.
+pub async fn branch_conversation(
+    conversation: &Conversation,
+    last_n: usize,
+    summarizer: Option<Arc<dyn Provider>>,
+) -> Result<Conversation> {
+    let messages = conversation.messages();
+    let split = messages.len().saturating_sub(last_n);
+    let (older, recent) = messages.split_at(split);
+
+    let mut branched = Vec::with_capacity(recent.len() + 1);
+    if let Some(provider) = summarizer {
+        if let Some((summary, _)) = summarize_messages(provider, older)
+            .await
+            .context("Failed to summarize the older messages")?
+        {
+            branched.push(summary);
+        }
+    }
+    branched.extend_from_slice(recent);
+
+    // The branch is saved as a session, so images are kept whatever the current model
+    let (fixed, issues) = fix_conversation(Conversation::new_unvalidated(branched), true);
+    if !issues.is_empty() {
+        tracing::debug!("Fixed branched conversation: {}", issues.join(", "));
+    }
+    Ok(fixed)
+}
+
+/// Copy `session_id` into a new session holding the branched conversation and return the new
+/// session's id. The original session is left untouched.
+pub async fn branch_session(
+    session_id: &str,
+    last_n: usize,
+    summarizer: Option<Arc<dyn Provider>>,
+) -> Result<String> {
+    let original = SessionManager::get_session(session_id, true).await?;
+    let conversation = original.conversation.unwrap_or_default();
+    let branched = branch_conversation(&conversation, last_n, summarizer).await?;
+
+    let session =
+        SessionManager::create_session(original.working_dir, original.description).await?;
+    SessionManager::update_session(&session.id)
+        .extension_data(original.extension_data)
+        .recipe(original.recipe)
+        .apply()
+        .await?;
+    SessionManager::replace_conversation(&session.id, &branched).await?;
+
+    Ok(session.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use goose::conversation::message::Message;
+    use goose::model::ModelConfig;
+    use goose::providers::base::{ProviderMetadata, ProviderUsage, Usage};
+    use goose::providers::errors::ProviderError;
+    use mcp_core::tool::ToolCall;
+    use rmcp::model::{Role, Tool};
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    /// Replies with a fixed summary and records the prompt it was asked to summarize
+    struct SummaryProvider {
+        summarized: Mutex<Option<String>>,
+    }
+
+    #[async_trait]
+    impl Provider for SummaryProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &ModelConfig,
+            system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            *self.summarized.lock().unwrap() = Some(system.to_string());
+            Ok((
+                Message::assistant().with_text("We fixed the build and ran the tests."),
+                ProviderUsage::new("mock".to_string(), Usage::new(Some(10), Some(5), Some(15))),
+            ))
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new_or_fail("mock")
+        }
+    }
+
+    /// Two plain turns followed by a turn with a tool call
+    fn history() -> Conversation {
+        Conversation::new_unvalidated(vec![
+            Message::user().with_text("Fix the build"),
+            Message::assistant().with_text("Fixed the missing import."),
+            Message::user().with_text("Run the tests"),
+            Message::assistant().with_tool_request(
+                "call_1",
+                Ok(ToolCall::new(
+                    "developer__shell",
+                    json!({"command": "cargo test"}),
+                )),
+            ),
+            Message::user().with_tool_response("call_1", Ok(vec![])),
+            Message::assistant().with_text("All tests pass."),
+            Message::user().with_text("Commit it"),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_last_n_keeps_trailing_messages() {
+        let branched = branch_conversation(&history(), 5, None).await.unwrap();
+
+        assert_eq!(branched.len(), 5);
+        assert_eq!(branched.messages()[0].as_concat_text(), "Run the tests");
+        assert_eq!(branched.messages()[4].as_concat_text(), "Commit it");
+        assert!(Conversation::new(branched.messages().clone()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_last_n_drops_orphaned_tool_response() {
+        // The cut lands between the tool request and its response, leaving an orphaned
+        // response and then a leading assistant reply
+        let branched = branch_conversation(&history(), 3, None).await.unwrap();
+
+        assert_eq!(branched.len(), 1);
+        assert_eq!(branched.messages()[0].role, Role::User);
+        assert_eq!(branched.messages()[0].as_concat_text(), "Commit it");
+        assert!(Conversation::new(branched.messages().clone()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_summarize_older_leads_with_summary() {
+        let provider = Arc::new(SummaryProvider {
+            summarized: Mutex::new(None),
+        });
+        let branched = branch_conversation(&history(), 2, Some(provider.clone()))
+            .await
+            .unwrap();
+
+        // The summary leads as a user message, then the trailing assistant reply and user turn
+        assert_eq!(branched.len(), 3);
+        let summary = &branched.messages()[0];
+        assert_eq!(summary.role, Role::User);
+        assert_eq!(
+            summary.as_concat_text(),
+            "We fixed the build and ran the tests."
+        );
+        assert_eq!(branched.messages()[2].as_concat_text(), "Commit it");
+        assert!(Conversation::new(branched.messages().clone()).is_ok());
+
+        let prompt = provider.summarized.lock().unwrap().clone().unwrap();
+        assert!(prompt.contains("Fix the build"));
+        assert!(!prompt.contains("Commit it"));
+    }
+
+    #[tokio::test]
+    async fn test_last_n_beyond_history_keeps_everything() {
+        let branched = branch_conversation(&history(), 100, None).await.unwrap();
+        assert_eq!(branched.len(), history().len());
+        assert!(Conversation::new(branched.messages().clone()).is_ok());
+    }
+}
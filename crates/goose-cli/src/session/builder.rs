@@ -1,6 +1,8 @@
+use super::branch::branch_session;
 use super::output;
-use super::CliSession;
+use super::{CliSession, OutputFormat};
 use console::style;
+use futures::StreamExt;
 use goose::agents::types::RetryConfig;
 use goose::agents::Agent;
 use goose::config::{Config, ExtensionConfig, ExtensionConfigManager};
@@ -12,7 +14,6 @@ use rustyline::EditMode;
 use std::collections::HashSet;
 use std::process;
 use std::sync::Arc;
-use tokio::task::JoinSet;
 
 /// Configuration for building a new Goose session
 ///
@@ -24,6 +25,10 @@ pub struct SessionBuilderConfig {
     pub session_id: Option<String>,
     /// Whether to resume an existing session
     pub resume: bool,
+    /// When resuming, branch into a new session holding only this many trailing messages
+    pub resume_last_n: Option<usize>,
+    /// When branching, summarize the dropped messages into a leading user message
+    pub summarize_older: bool,
     /// Whether to run without a session file
     pub no_session: bool,
     /// List of stdio extension commands to add
@@ -56,6 +61,8 @@ pub struct SessionBuilderConfig {
     pub interactive: bool,
     /// Quiet mode - suppress non-response output
     pub quiet: bool,
+    /// How headless runs report their result
+    pub output_format: OutputFormat,
     /// Sub-recipes to add to the session
     pub sub_recipes: Option<Vec<SubRecipe>>,
     /// Final output expected response
@@ -133,7 +140,9 @@ async fn offer_extension_debugging_help(
 
     // Process the debugging request
     println!("{}", style("Analyzing the extension failure...").yellow());
-    match debug_session.headless(debug_prompt).await {
+    let result = debug_session.headless(debug_prompt).await;
+    debug_session.shutdown().await;
+    match result {
         Ok(_) => {
             println!(
                 "{}",
@@ -281,6 +290,40 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
         Some(session.id)
     };
 
+    // Branch the resumed session so the trimmed history never overwrites the original
+    let session_id = match (session_id, session_config.resume_last_n) {
+        (Some(original_id), Some(last_n)) if session_config.resume => {
+            let summarizer = session_config
+                .summarize_older
+                .then(|| Arc::clone(&provider_for_display));
+            match branch_session(&original_id, last_n, summarizer).await {
+                Ok(branch_id) => {
+                    output::render_text(
+                        &format!(
+                            "Resuming the last {} messages of session {} as new session {}",
+                            last_n, original_id, branch_id
+                        ),
+                        Some(console::Color::Cyan),
+                        true,
+                    );
+                    Some(branch_id)
+                }
+                Err(e) => {
+                    output::render_error(&format!(
+                        "Failed to branch session {}: {}",
+                        original_id, e
+                    ));
+                    process::exit(1);
+                }
+            }
+        }
+        (session_id, _) => session_id,
+    };
+
+    if let Some(session_id) = session_id.as_ref() {
+        crate::logging::set_log_session(session_id);
+    }
+
     if session_config.resume {
         if let Some(session_id) = session_id.as_ref() {
             // Read the session metadata from database
@@ -331,20 +374,7 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
             .collect()
     };
 
-    let mut set = JoinSet::new();
-    let agent_ptr = Arc::new(agent);
-
-    let mut waiting_on = HashSet::new();
-    for extension in extensions_to_run {
-        waiting_on.insert(extension.name());
-        let agent_ptr = agent_ptr.clone();
-        set.spawn(async move {
-            (
-                extension.name(),
-                agent_ptr.add_extension(extension.clone()).await,
-            )
-        });
-    }
+    let mut waiting_on: HashSet<String> = extensions_to_run.iter().map(|e| e.name()).collect();
 
     let get_message = |waiting_on: &HashSet<String>| {
         let mut names: Vec<_> = waiting_on.iter().cloned().collect();
@@ -355,17 +385,17 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
     let spinner = cliclack::spinner();
     spinner.start(get_message(&waiting_on));
 
+    // Extensions start after the ones they depend on, and otherwise in parallel
     let mut offer_debug = Vec::new();
-    while let Some(result) = set.join_next().await {
+    let mut results = agent.add_extensions(extensions_to_run);
+    while let Some((name, result)) = results.next().await {
+        waiting_on.remove(&name);
         match result {
-            Ok((name, Ok(_))) => {
-                waiting_on.remove(&name);
-                spinner.set_message(get_message(&waiting_on));
-            }
-            Ok((name, Err(e))) => offer_debug.push((name, e)),
-            Err(e) => tracing::error!("failed to add extension: {}", e),
+            Ok(_) => spinner.set_message(get_message(&waiting_on)),
+            Err(e) => offer_debug.push((name, e)),
         }
     }
+    drop(results);
 
     spinner.clear();
 
@@ -397,7 +427,7 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
 
     // Create new session
     let mut session = CliSession::new(
-        Arc::try_unwrap(agent_ptr).unwrap_or_else(|_| panic!("There should be no more references")),
+        agent,
         session_id.clone(),
         session_config.debug,
         session_config.scheduled_job_id.clone(),
@@ -405,6 +435,10 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
         edit_mode,
         session_config.retry_config.clone(),
     );
+    session.set_quiet(session_config.quiet);
+    if !session_config.interactive {
+        session.set_output_format(session_config.output_format);
+    }
 
     // Add extensions if provided
     for extension_str in session_config.extensions {
@@ -559,8 +593,8 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
         session.agent.override_system_prompt(override_prompt).await;
     }
 
-    // Display session information unless in quiet mode
-    if !session_config.quiet {
+    // Display session information unless in quiet mode or writing machine-readable output
+    if !session_config.quiet && session_config.output_format == OutputFormat::Text {
         output::display_session_info(
             session_config.resume,
             &provider_name,
@@ -581,6 +615,8 @@ mod tests {
         let config = SessionBuilderConfig {
             session_id: Some("test".to_string()),
             resume: false,
+            resume_last_n: None,
+            summarize_older: false,
             no_session: false,
             extensions: vec!["echo test".to_string()],
             remote_extensions: vec!["http://example.com".to_string()],
@@ -597,6 +633,7 @@ mod tests {
             scheduled_job_id: None,
             interactive: true,
             quiet: false,
+            output_format: OutputFormat::Text,
             sub_recipes: None,
             final_output_response: None,
             retry_config: None,
@@ -633,6 +670,7 @@ mod tests {
         assert!(config.scheduled_job_id.is_none());
         assert!(!config.interactive);
         assert!(!config.quiet);
+        assert_eq!(config.output_format, OutputFormat::Text);
         assert!(config.final_output_response.is_none());
     }
 
@@ -5,6 +5,8 @@ use serde_json::Value;
 
 const MAX_STRING_LENGTH_MD_EXPORT: usize = 4096; // Generous limit for export
 const REDACTED_PREFIX_LENGTH: usize = 100; // Show first 100 chars before trimming
+const COLLAPSE_OUTPUT_BYTES: usize = 2048;
+const COLLAPSE_OUTPUT_LINES: usize = 40;
 
 fn value_to_simple_markdown_string(value: &Value, export_full_strings: bool) -> String {
     match value {
@@ -110,6 +112,40 @@ fn value_to_markdown(value: &Value, depth: usize, export_full_strings: bool) ->
     md_string
 }
 
+/// Wrap `body` in a code fence longer than any run of backticks inside it
+fn fenced(language: &str, body: &str) -> String {
+    let longest_run = body
+        .split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}{language}\n{body}\n{fence}\n\n")
+}
+
+fn format_size(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{} bytes", bytes),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// Fold a large tool output into a `<details>` block, labelled with its size, so long
+/// outputs don't bury the rest of the conversation
+fn collapse_if_large(block: String, body: &str) -> String {
+    let lines = body.lines().count();
+    if body.len() <= COLLAPSE_OUTPUT_BYTES && lines <= COLLAPSE_OUTPUT_LINES {
+        return block;
+    }
+    format!(
+        "<details>\n<summary>Output ({} lines, {})</summary>\n\n{}</details>\n\n",
+        lines,
+        format_size(body.len()),
+        block
+    )
+}
+
 pub fn tool_request_to_markdown(req: &ToolRequest, export_all_content: bool) -> String {
     let mut md = String::new();
     match &req.tool_call {
@@ -220,19 +256,23 @@ pub fn tool_response_to_markdown(resp: &ToolResponse, export_all_content: bool)
                 match &content.raw {
                     RawContent::Text(text_content) => {
                         let trimmed_text = text_content.text.trim();
-                        if (trimmed_text.starts_with('{') && trimmed_text.ends_with('}'))
+                        let language = if (trimmed_text.starts_with('{')
+                            && trimmed_text.ends_with('}'))
                             || (trimmed_text.starts_with('[') && trimmed_text.ends_with(']'))
                         {
-                            md.push_str(&format!("```json\n{}\n```\n", trimmed_text));
+                            "json"
                         } else if trimmed_text.starts_with('<')
                             && trimmed_text.ends_with('>')
                             && trimmed_text.contains("</")
                         {
-                            md.push_str(&format!("```xml\n{}\n```\n", trimmed_text));
+                            "xml"
                         } else {
-                            md.push_str(&text_content.text);
-                            md.push_str("\n\n");
-                        }
+                            ""
+                        };
+                        md.push_str(&collapse_if_large(
+                            fenced(language, trimmed_text),
+                            trimmed_text,
+                        ));
                     }
                     RawContent::Image(image_content) => {
                         if image_content.mime_type.starts_with("image/") {
@@ -278,10 +318,9 @@ pub fn tool_response_to_markdown(resp: &ToolResponse, export_all_content: bool)
                                 };
 
                                 md.push_str(&format!("**File:** `{}`\n", uri));
-                                md.push_str(&format!(
-                                    "```{}\n{}\n```\n\n",
-                                    syntax_type,
-                                    text.trim()
+                                md.push_str(&collapse_if_large(
+                                    fenced(syntax_type, text.trim()),
+                                    text.trim(),
                                 ));
                             }
                             ResourceContents::BlobResourceContents {
@@ -1,9 +1,11 @@
+mod branch;
 mod builder;
 mod completion;
 mod export;
 mod input;
 mod output;
 mod prompt;
+mod run_output;
 mod task_execution_display;
 mod thinking;
 
@@ -22,6 +24,7 @@ use goose::permission::Permission;
 use goose::permission::PermissionConfirmation;
 use goose::providers::base::Provider;
 use goose::utils::safe_truncate;
+pub use run_output::{OutputFormat, RunRecorder, RunReport, RunStatus, TokenUsage};
 
 use anyhow::{Context, Result};
 use completion::GooseCompleter;
@@ -33,7 +36,6 @@ use goose::config::Config;
 use goose::providers::pricing::initialize_pricing_cache;
 use goose::session;
 use input::InputResult;
-use rmcp::model::PromptMessage;
 use rmcp::model::ServerNotification;
 use rmcp::model::{ErrorCode, ErrorData};
 
@@ -65,6 +67,8 @@ pub struct CliSession {
     max_turns: Option<u32>,
     edit_mode: Option<EditMode>,
     retry_config: Option<RetryConfig>,
+    quiet: bool,
+    run_recorder: Option<RunRecorder>,
 }
 
 // Cache structure for completion data
@@ -152,9 +156,51 @@ impl CliSession {
             max_turns,
             edit_mode,
             retry_config,
+            quiet: false,
+            run_recorder: None,
         }
     }
 
+    /// Only render the model's replies, without tool calls or progress spinners
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
+    }
+
+    /// Record a headless run so it can report its outcome, in place of the rendered output
+    /// when the format is machine-readable
+    pub fn set_output_format(&mut self, format: OutputFormat) {
+        self.set_run_recorder(RunRecorder::new(format));
+    }
+
+    pub fn set_run_recorder(&mut self, recorder: RunRecorder) {
+        output::set_rendering_suppressed(recorder.is_machine_readable());
+        self.run_recorder = Some(recorder);
+    }
+
+    fn machine_readable(&self) -> bool {
+        self.run_recorder
+            .as_ref()
+            .is_some_and(RunRecorder::is_machine_readable)
+    }
+
+    /// Write the machine-readable report for a headless run, if one is being recorded
+    pub async fn finish_run(&mut self, result: &Result<()>) -> Option<RunReport> {
+        let mut recorder = self.run_recorder.take()?;
+        if let Err(e) = result {
+            recorder.record_error(e);
+        }
+        let usage = self
+            .get_metadata()
+            .await
+            .map(|metadata| TokenUsage {
+                input_tokens: metadata.input_tokens,
+                output_tokens: metadata.output_tokens,
+                total_tokens: metadata.total_tokens,
+            })
+            .unwrap_or_default();
+        Some(recorder.finish(self.session_id.clone(), usage))
+    }
+
     pub fn session_id(&self) -> Option<&String> {
         self.session_id.as_ref()
     }
@@ -212,6 +258,8 @@ impl CliSession {
             timeout: Some(goose::config::DEFAULT_EXTENSION_TIMEOUT),
             bundled: None,
             available_tools: Vec::new(),
+            depends_on: Vec::new(),
+            response_limits: None,
         };
 
         self.agent
@@ -246,6 +294,9 @@ impl CliSession {
             timeout: Some(goose::config::DEFAULT_EXTENSION_TIMEOUT),
             bundled: None,
             available_tools: Vec::new(),
+            depends_on: Vec::new(),
+            response_limits: None,
+            keep_alive_interval: None,
         };
 
         self.agent
@@ -281,6 +332,9 @@ impl CliSession {
             timeout: Some(goose::config::DEFAULT_EXTENSION_TIMEOUT),
             bundled: None,
             available_tools: Vec::new(),
+            depends_on: Vec::new(),
+            response_limits: None,
+            keep_alive_interval: None,
         };
 
         self.agent
@@ -308,6 +362,8 @@ impl CliSession {
                 bundled: None,
                 description: None,
                 available_tools: Vec::new(),
+                depends_on: Vec::new(),
+                response_limits: None,
             };
             self.agent
                 .add_extension(config)
@@ -363,8 +419,18 @@ impl CliSession {
         Ok(None)
     }
 
-    pub async fn get_prompt(&mut self, name: &str, arguments: Value) -> Result<Vec<PromptMessage>> {
-        Ok(self.agent.get_prompt(name, arguments).await?.messages)
+    pub async fn get_prompt(&mut self, name: &str, arguments: Value) -> Result<Vec<Message>> {
+        let extension = self
+            .get_prompt_info(name)
+            .await?
+            .and_then(|info| info.extension)
+            .ok_or_else(|| anyhow::anyhow!("Prompt '{}' not found", name))?;
+        Ok(self
+            .agent
+            .render_prompt(&extension, name, arguments)
+            .await?
+            .messages()
+            .clone())
     }
 
     /// Process a single message and get the response
@@ -841,6 +907,11 @@ impl CliSession {
         Ok(())
     }
 
+    /// Shut down the agent's extensions and the processes they started, on any exit path
+    pub async fn shutdown(&self) {
+        self.agent.shutdown().await;
+    }
+
     /// Process a single message and exit
     pub async fn headless(&mut self, prompt: String) -> Result<()> {
         let message = Message::user().with_text(&prompt);
@@ -873,7 +944,11 @@ impl CliSession {
             )
             .await?;
 
-        let mut progress_bars = output::McpSpinners::new();
+        let mut progress_bars = if self.quiet || self.machine_readable() {
+            output::McpSpinners::hidden()
+        } else {
+            output::McpSpinners::new()
+        };
 
         use futures::StreamExt;
         loop {
@@ -885,6 +960,16 @@ impl CliSession {
                             if let Some(MessageContent::ToolConfirmationRequest(confirmation)) = message.content.first() {
                                 output::hide_thinking();
 
+                                // Nobody can answer a prompt during a machine-readable run
+                                if let Some(recorder) = self.run_recorder.as_mut().filter(|r| r.is_machine_readable()) {
+                                    recorder.record_denied(&confirmation.id);
+                                    self.agent.handle_confirmation(confirmation.id.clone(), PermissionConfirmation {
+                                        principal_type: PrincipalType::Tool,
+                                        permission: Permission::DenyOnce,
+                                    }).await;
+                                    continue;
+                                }
+
                                 // Format the confirmation prompt - use security message if present, otherwise use generic message
                                 let prompt = if let Some(security_message) = &confirmation.prompt {
                                     println!("\n{}", security_message);
@@ -1064,10 +1149,19 @@ impl CliSession {
                                     }
                                 }
                                 self.messages.push(message.clone());
+                                if let Some(recorder) = self.run_recorder.as_mut() {
+                                    recorder.record_message(&message);
+                                }
 
                                 if interactive {output::hide_thinking()};
                                 let _ = progress_bars.hide();
-                                output::render_message(&message, self.debug);
+                                if !self.quiet {
+                                    output::render_message(&message, self.debug);
+                                } else if message.role == rmcp::model::Role::Assistant {
+                                    let mut reply = message.clone();
+                                    reply.content.retain(|content| matches!(content, MessageContent::Text(_)));
+                                    output::render_message(&reply, self.debug);
+                                }
                             }
                         }
                         Some(Ok(AgentEvent::McpNotification((_id, message)))) => {
@@ -1134,8 +1228,11 @@ impl CliSession {
                                         },
                                     };
 
+                                    if let Some(recorder) = self.run_recorder.as_mut().filter(|r| r.is_machine_readable()) {
+                                        recorder.record_notification(&formatted_message);
+                                    }
                                     // Handle subagent notifications - show immediately
-                                    if let Some(_id) = subagent_id {
+                                    else if let Some(_id) = subagent_id {
                                         // TODO: proper display for subagent notifications
                                         if interactive {
                                             let _ = progress_bars.hide();
@@ -1144,7 +1241,7 @@ impl CliSession {
                                             progress_bars.log(&formatted_message);
                                         }
                                     } else if let Some(ref notification_type) = message_notification_type {
-                                        if notification_type == TASK_EXECUTION_NOTIFICATION_TYPE {
+                                        if notification_type == TASK_EXECUTION_NOTIFICATION_TYPE && !self.quiet {
                                             if interactive {
                                                 let _ = progress_bars.hide();
                                                 print!("{}", formatted_message);
@@ -1224,6 +1321,15 @@ impl CliSession {
                                 }
                                 // Auto-compaction failed, fall through to common error handling below
                             }
+                            if let Some(recorder) = self.run_recorder.as_mut() {
+                                recorder.record_error(&e);
+                            }
+                            if self.machine_readable() {
+                                cancel_token_clone.cancel();
+                                drop(stream);
+                                let _ = self.handle_interrupted_messages(false).await;
+                                break;
+                            }
                             eprintln!("Error: {}", e);
                             cancel_token_clone.cancel();
                             drop(stream);
@@ -1289,6 +1395,9 @@ impl CliSession {
                     }
                 }
                 _ = tokio::signal::ctrl_c() => {
+                    if let Some(recorder) = self.run_recorder.as_mut() {
+                        recorder.record_cancelled();
+                    }
                     cancel_token_clone.cancel();
                     drop(stream);
                     if let Err(e) = self.handle_interrupted_messages(true).await {
@@ -1298,7 +1407,9 @@ impl CliSession {
                 }
             }
         }
-        println!();
+        if !self.machine_readable() {
+            println!();
+        }
 
         Ok(())
     }
@@ -1542,40 +1653,17 @@ impl CliSession {
 
             match self.get_prompt(&opts.name, arguments).await {
                 Ok(messages) => {
-                    let start_len = self.messages.len();
-                    let mut valid = true;
-                    for (i, prompt_message) in messages.into_iter().enumerate() {
-                        let msg = Message::from(prompt_message);
-                        // ensure we get a User - Assistant - User type pattern
-                        let expected_role = if i % 2 == 0 {
-                            rmcp::model::Role::User
-                        } else {
-                            rmcp::model::Role::Assistant
-                        };
-
-                        if msg.role != expected_role {
-                            output::render_error(&format!(
-                                "Expected {:?} message at position {}, but found {:?}",
-                                expected_role, i, msg.role
-                            ));
-                            valid = false;
-                            // get rid of everything we added to messages
-                            self.messages.truncate(start_len);
-                            break;
-                        }
-
+                    for msg in messages {
                         if msg.role == rmcp::model::Role::User {
                             output::render_message(&msg, self.debug);
                         }
                         self.push_message(msg);
                     }
 
-                    if valid {
-                        output::show_thinking();
-                        self.process_agent_response(true, CancellationToken::default())
-                            .await?;
-                        output::hide_thinking();
-                    }
+                    output::show_thinking();
+                    self.process_agent_response(true, CancellationToken::default())
+                        .await?;
+                    output::hide_thinking();
                 }
                 Err(e) => output::render_error(&e.to_string()),
             }
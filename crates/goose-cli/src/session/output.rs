@@ -6,7 +6,7 @@ use goose::conversation::message::{Message, MessageContent, ToolRequest, ToolRes
 use goose::providers::pricing::get_model_pricing;
 use goose::providers::pricing::parse_model_id;
 use goose::utils::safe_truncate;
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use mcp_core::tool::ToolCall;
 use regex::Regex;
 use rmcp::model::PromptArgument;
@@ -15,6 +15,7 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::io::{Error, IsTerminal, Write};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -136,8 +137,19 @@ thread_local! {
     static THINKING: RefCell<ThinkingIndicator> = RefCell::new(ThinkingIndicator::default());
 }
 
+// Set for machine-readable runs, where stdout must only carry JSON
+static RENDERING_SUPPRESSED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_rendering_suppressed(suppressed: bool) {
+    RENDERING_SUPPRESSED.store(suppressed, Ordering::Relaxed);
+}
+
+fn rendering_suppressed() -> bool {
+    RENDERING_SUPPRESSED.load(Ordering::Relaxed)
+}
+
 pub fn show_thinking() {
-    if std::io::stdout().is_terminal() {
+    if std::io::stdout().is_terminal() && !rendering_suppressed() {
         THINKING.with(|t| t.borrow_mut().show());
     }
 }
@@ -163,6 +175,9 @@ pub fn set_thinking_message(s: &String) {
 }
 
 pub fn render_message(message: &Message, debug: bool) {
+    if rendering_suppressed() {
+        return;
+    }
     let theme = get_theme();
 
     for content in &message.content {
@@ -203,6 +218,9 @@ pub fn render_text(text: &str, color: Option<Color>, dim: bool) {
 }
 
 pub fn render_text_no_newlines(text: &str, color: Option<Color>, dim: bool) {
+    if rendering_suppressed() {
+        return;
+    }
     if !std::io::stdout().is_terminal() {
         println!("{}", text);
         return;
@@ -296,6 +314,9 @@ fn render_tool_response(resp: &ToolResponse, theme: Theme, debug: bool) {
 }
 
 pub fn render_error(message: &str) {
+    if rendering_suppressed() {
+        return;
+    }
     println!("\n  {} {}\n", style("error:").red().bold(), message);
 }
 
@@ -874,6 +895,15 @@ impl McpSpinners {
         }
     }
 
+    /// Spinners that track progress without drawing anything
+    pub fn hidden() -> Self {
+        McpSpinners {
+            bars: HashMap::new(),
+            log_spinner: None,
+            multi_bar: MultiProgress::with_draw_target(ProgressDrawTarget::hidden()),
+        }
+    }
+
     pub fn log(&mut self, message: &str) {
         let spinner = self.log_spinner.get_or_insert_with(|| {
             let bar = self.multi_bar.add(
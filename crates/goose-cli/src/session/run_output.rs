@@ -0,0 +1,296 @@
+//! Machine-readable output for `goose run`, so scripts can read what happened in a headless
+//! run without scraping the human output.
+
+use goose::agents::{MAX_TURNS_REACHED_MESSAGE, PROVIDER_ERROR_PREFIX};
+use goose::conversation::message::{Message, MessageContent};
+use goose::providers::errors::ProviderError;
+use rmcp::model::Role;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable output
+    #[default]
+    Text,
+    /// A single JSON document once the run finishes
+    Json,
+    /// One JSON event per line as the run progresses, ending with the final document
+    JsonStream,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Completed,
+    ProviderError,
+    ToolDenied,
+    MaxTurns,
+    Cancelled,
+    Error,
+}
+
+impl RunStatus {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            RunStatus::Completed => 0,
+            RunStatus::Error => 1,
+            RunStatus::ProviderError => 2,
+            RunStatus::ToolDenied => 3,
+            RunStatus::MaxTurns => 4,
+            RunStatus::Cancelled => 130,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolCallRecord {
+    pub id: String,
+    pub name: String,
+    /// Time from the request being received to its response, if it got one
+    pub duration_ms: Option<u64>,
+    pub success: bool,
+    pub denied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TokenUsage {
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+    pub total_tokens: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunReport {
+    pub session_id: Option<String>,
+    pub status: RunStatus,
+    pub exit_code: i32,
+    /// Text of the last assistant message
+    pub final_message: Option<String>,
+    pub tool_calls: Vec<ToolCallRecord>,
+    pub usage: TokenUsage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent<'a> {
+    Message { message: &'a Message },
+    ToolCallStarted { id: &'a str, name: &'a str },
+    ToolCallFinished { tool_call: &'a ToolCallRecord },
+    Notification { message: &'a str },
+    Finished { report: &'a RunReport },
+}
+
+/// Collects what happens during a headless run and writes it out in the chosen format
+pub struct RunRecorder {
+    format: OutputFormat,
+    writer: Box<dyn Write + Send + Sync>,
+    tool_calls: Vec<ToolCallRecord>,
+    started: HashMap<String, Instant>,
+    final_message: Option<String>,
+    status: Option<RunStatus>,
+    error: Option<String>,
+}
+
+impl RunRecorder {
+    pub fn new(format: OutputFormat) -> Self {
+        Self::with_writer(format, Box::new(std::io::stdout()))
+    }
+
+    pub fn with_writer(format: OutputFormat, writer: Box<dyn Write + Send + Sync>) -> Self {
+        Self {
+            format,
+            writer,
+            tool_calls: Vec::new(),
+            started: HashMap::new(),
+            final_message: None,
+            status: None,
+            error: None,
+        }
+    }
+
+    pub fn is_machine_readable(&self) -> bool {
+        self.format != OutputFormat::Text
+    }
+
+    fn emit(&mut self, event: StreamEvent) {
+        if self.format != OutputFormat::JsonStream {
+            return;
+        }
+        if let Ok(line) = serde_json::to_string(&event) {
+            let _ = writeln!(self.writer, "{}", line);
+            let _ = self.writer.flush();
+        }
+    }
+
+    pub fn record_message(&mut self, message: &Message) {
+        self.emit(StreamEvent::Message { message });
+
+        for content in &message.content {
+            match content {
+                MessageContent::ToolRequest(request) => {
+                    let name = match &request.tool_call {
+                        Ok(call) => call.name.clone(),
+                        Err(_) => "unknown".to_string(),
+                    };
+                    self.started.insert(request.id.clone(), Instant::now());
+                    self.tool_calls.push(ToolCallRecord {
+                        id: request.id.clone(),
+                        name,
+                        duration_ms: None,
+                        success: false,
+                        denied: false,
+                        error: request.tool_call.as_ref().err().map(|e| e.to_string()),
+                    });
+                    let record = self.tool_calls.last().cloned().unwrap();
+                    self.emit(StreamEvent::ToolCallStarted {
+                        id: &record.id,
+                        name: &record.name,
+                    });
+                }
+                MessageContent::ToolResponse(response) => {
+                    let duration_ms = self
+                        .started
+                        .remove(&response.id)
+                        .map(|start| start.elapsed().as_millis() as u64);
+                    let Some(record) = self
+                        .tool_calls
+                        .iter_mut()
+                        .find(|record| record.id == response.id)
+                    else {
+                        continue;
+                    };
+                    record.duration_ms = duration_ms;
+                    record.success = !record.denied && response.tool_result.is_ok();
+                    if let Err(e) = &response.tool_result {
+                        record.error = Some(e.message.to_string());
+                    }
+                    let record = record.clone();
+                    self.emit(StreamEvent::ToolCallFinished { tool_call: &record });
+                }
+                _ => {}
+            }
+        }
+
+        if message.role == Role::Assistant {
+            let text = message.as_concat_text();
+            if !text.is_empty() {
+                if text == MAX_TURNS_REACHED_MESSAGE {
+                    self.status = Some(RunStatus::MaxTurns);
+                } else if let Some(error) = text.strip_prefix(PROVIDER_ERROR_PREFIX) {
+                    self.status = Some(RunStatus::ProviderError);
+                    self.error = error
+                        .split("\n\n")
+                        .next()
+                        .map(|e| e.trim_end_matches('.').to_string());
+                }
+                self.final_message = Some(text);
+            }
+        }
+    }
+
+    /// A tool call needed confirmation and was denied, since nobody can answer the prompt
+    pub fn record_denied(&mut self, id: &str) {
+        if let Some(record) = self.tool_calls.iter_mut().find(|record| record.id == id) {
+            record.denied = true;
+        }
+        self.status.get_or_insert(RunStatus::ToolDenied);
+    }
+
+    pub fn record_notification(&mut self, message: &str) {
+        self.emit(StreamEvent::Notification { message });
+    }
+
+    pub fn record_cancelled(&mut self) {
+        self.status = Some(RunStatus::Cancelled);
+    }
+
+    pub fn record_error(&mut self, error: &anyhow::Error) {
+        let status = if error.downcast_ref::<ProviderError>().is_some() {
+            RunStatus::ProviderError
+        } else {
+            RunStatus::Error
+        };
+        self.status = Some(status);
+        self.error = Some(error.to_string());
+    }
+
+    /// Write the final report and return it
+    pub fn finish(mut self, session_id: Option<String>, usage: TokenUsage) -> RunReport {
+        let status = self.status.unwrap_or(RunStatus::Completed);
+        let report = RunReport {
+            session_id,
+            status,
+            exit_code: status.exit_code(),
+            final_message: self.final_message.take(),
+            tool_calls: std::mem::take(&mut self.tool_calls),
+            usage,
+            error: self.error.take(),
+        };
+
+        match self.format {
+            OutputFormat::Text => {}
+            OutputFormat::Json => {
+                if let Ok(json) = serde_json::to_string_pretty(&report) {
+                    let _ = writeln!(self.writer, "{}", json);
+                }
+            }
+            OutputFormat::JsonStream => self.emit(StreamEvent::Finished { report: &report }),
+        }
+        let _ = self.writer.flush();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use serde_json::json;
+
+    fn tool_request(id: &str) -> Message {
+        Message::assistant().with_tool_request(id, Ok(ToolCall::new("developer__shell", json!({}))))
+    }
+
+    #[test]
+    fn test_max_turns_is_reported() {
+        let mut recorder = RunRecorder::with_writer(OutputFormat::Json, Box::new(std::io::sink()));
+        recorder.record_message(&Message::assistant().with_text(MAX_TURNS_REACHED_MESSAGE));
+
+        let report = recorder.finish(None, TokenUsage::default());
+        assert_eq!(report.status, RunStatus::MaxTurns);
+        assert_eq!(report.exit_code, 4);
+    }
+
+    #[test]
+    fn test_denied_tool_call_is_reported() {
+        let mut recorder = RunRecorder::with_writer(OutputFormat::Json, Box::new(std::io::sink()));
+        recorder.record_message(&tool_request("call_1"));
+        recorder.record_denied("call_1");
+        recorder.record_message(&Message::user().with_tool_response("call_1", Ok(vec![])));
+
+        let report = recorder.finish(None, TokenUsage::default());
+        assert_eq!(report.status, RunStatus::ToolDenied);
+        assert_eq!(report.exit_code, 3);
+        assert!(report.tool_calls[0].denied);
+        assert!(!report.tool_calls[0].success);
+    }
+
+    #[test]
+    fn test_unanswered_tool_call_has_no_duration() {
+        let mut recorder = RunRecorder::with_writer(OutputFormat::Json, Box::new(std::io::sink()));
+        recorder.record_message(&tool_request("call_1"));
+        recorder.record_cancelled();
+
+        let report = recorder.finish(None, TokenUsage::default());
+        assert_eq!(report.status, RunStatus::Cancelled);
+        assert_eq!(report.tool_calls[0].duration_ms, None);
+        assert!(!report.tool_calls[0].success);
+    }
+}
@@ -1,4 +1,5 @@
 use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
 use etcetera::{choose_app_strategy, AppStrategy};
 use indoc::formatdoc;
 use rmcp::{
@@ -11,7 +12,18 @@ use rmcp::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// URI for the rendered resource, keyed by the data so that distinct visualizations get
+/// distinct URIs and identical data gets the same one, e.g. `ui://chart/3f2a9c0b1d4e5f60`
+fn resource_uri(kind: &str, data_json: &str) -> String {
+    let digest = Sha256::digest(data_json.as_bytes());
+    let hash: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("ui://{}/{}", kind, hash)
+}
 
 /// Validates that the data parameter is a proper JSON value and not a string
 fn validate_data_param(params: &Value, allow_array: bool) -> Result<Value, ErrorData> {
@@ -202,6 +214,47 @@ pub struct RenderTreemapParams {
     pub data: TreemapNode,
 }
 
+/// Parameters for render_sunburst tool
+#[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct RenderSunburstParams {
+    /// The hierarchical data for the sunburst, shaped like a treemap
+    pub data: TreemapNode,
+}
+
+/// The first problem that would keep a hierarchy from drawing as a sunburst
+fn sunburst_problem(root: &TreemapNode) -> Option<String> {
+    if root
+        .children
+        .as_ref()
+        .is_none_or(|children| children.is_empty())
+    {
+        return Some(format!(
+            "'{}' must have at least one child to draw a sunburst",
+            root.name
+        ));
+    }
+    fn check(node: &TreemapNode, path: &str) -> Option<String> {
+        let path = if path.is_empty() {
+            node.name.clone()
+        } else {
+            format!("{} > {}", path, node.name)
+        };
+        if let Some(value) = node.value {
+            if !(value.is_finite() && value >= 0.0) {
+                return Some(format!(
+                    "'{}' has value {}, values must be non-negative",
+                    path, value
+                ));
+            }
+        }
+        node.children
+            .iter()
+            .flatten()
+            .find_map(|child| check(child, &path))
+    }
+    check(root, "")
+}
+
 /// Chord diagram data structure
 #[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
 pub struct ChordData {
@@ -298,8 +351,8 @@ pub struct RenderMapParams {
 /// Chart data point for scatter charts
 #[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
 pub struct ChartPoint {
-    /// X coordinate
-    pub x: f64,
+    /// X coordinate, or an ISO 8601 date when xAxisType is time
+    pub x: ChartXValue,
     /// Y coordinate
     pub y: f64,
 }
@@ -331,6 +384,16 @@ pub struct ChartDataset {
     pub fill: Option<bool>,
 }
 
+/// X coordinate of a chart point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+#[serde(untagged)]
+pub enum ChartXValue {
+    /// A number, or milliseconds since the Unix epoch on a time axis
+    Number(f64),
+    /// An ISO 8601 date or date-time, e.g. "2024-03-01" or "2024-03-01T12:30:00Z"
+    Date(String),
+}
+
 /// Chart data values - can be simple numbers or x/y points
 #[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
 #[serde(untagged)]
@@ -353,6 +416,16 @@ pub enum ChartType {
     Bar,
 }
 
+/// Scale used for the x-axis
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum XAxisType {
+    /// Plain numbers, or categories when labels are given
+    Linear,
+    /// Dates, given as ISO 8601 strings in x values or labels
+    Time,
+}
+
 /// Chart data structure
 #[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
 pub struct ChartData {
@@ -374,12 +447,96 @@ pub struct ChartData {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "xAxisLabel")]
     pub x_axis_label: Option<String>,
+    /// Optional x-axis scale; time plots dates from x values or labels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "xAxisType")]
+    pub x_axis_type: Option<XAxisType>,
     /// Optional y-axis label
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "yAxisLabel")]
     pub y_axis_label: Option<String>,
 }
 
+/// Milliseconds since the Unix epoch for an ISO 8601 date or date-time. Times without an
+/// offset are taken as UTC, as are dates, which fall at midnight.
+fn parse_chart_time(value: &str) -> Result<f64, String> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.timestamp_millis() as f64);
+    }
+    for format in [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+    ] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(time.and_utc().timestamp_millis() as f64);
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(&format!("{}-01", value), "%Y-%m-%d"))
+        .map(|date| date.and_time(NaiveTime::MIN).and_utc().timestamp_millis() as f64)
+        .map_err(|_| format!("'{}' is not an ISO 8601 date", value))
+}
+
+/// Resolve dates on a time axis to epoch milliseconds so the template can plot them on a
+/// linear scale. Numeric datasets are paired with labels, which must then be dates.
+fn resolve_chart_time_axis(data: &mut ChartData) -> Result<(), String> {
+    if data.x_axis_type != Some(XAxisType::Time) {
+        let has_dates = data.datasets.iter().any(|dataset| match &dataset.data {
+            ChartDataValues::Points(points) => points
+                .iter()
+                .any(|point| matches!(point.x, ChartXValue::Date(_))),
+            ChartDataValues::Numbers(_) => false,
+        });
+        if has_dates {
+            return Err("date x values need xAxisType 'time'".to_string());
+        }
+        return Ok(());
+    }
+
+    let label_times = data
+        .labels
+        .take()
+        .map(|labels| {
+            labels
+                .iter()
+                .map(|label| parse_chart_time(label))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+
+    for dataset in &mut data.datasets {
+        match &mut dataset.data {
+            ChartDataValues::Points(points) => {
+                for point in points {
+                    if let ChartXValue::Date(date) = &point.x {
+                        point.x = ChartXValue::Number(parse_chart_time(date)?);
+                    }
+                }
+            }
+            ChartDataValues::Numbers(values) => {
+                let times = label_times.as_ref().ok_or_else(|| {
+                    format!(
+                        "dataset '{}' has no x values; give dates as labels or as x in points",
+                        dataset.label
+                    )
+                })?;
+                let points = times
+                    .iter()
+                    .zip(values.iter())
+                    .map(|(&time, &y)| ChartPoint {
+                        x: ChartXValue::Number(time),
+                        y,
+                    })
+                    .collect();
+                dataset.data = ChartDataValues::Points(points);
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Parameters for show_chart tool
 #[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
 pub struct ShowChartParams {
@@ -387,6 +544,518 @@ pub struct ShowChartParams {
     pub data: ChartData,
 }
 
+/// A colored band on a gauge, ending at `value`
+#[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct GaugeThreshold {
+    /// Upper end of the band; it starts at the previous threshold (or min)
+    pub value: f64,
+    /// Optional band color, e.g. "red" or "#e74c3c"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Optional band label, e.g. "Target"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// Gauge style
+#[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GaugeStyle {
+    /// Half-circle dial with a needle
+    Radial,
+    /// Horizontal bullet bar
+    Bullet,
+}
+
+/// Gauge data structure
+#[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct GaugeData {
+    /// The value to show
+    pub value: f64,
+    /// Lower end of the scale
+    pub min: f64,
+    /// Upper end of the scale
+    pub max: f64,
+    /// Optional threshold bands, in any order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thresholds: Option<Vec<GaugeThreshold>>,
+    /// Optional label for the metric
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Optional unit appended to values, e.g. "%" or "ms"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// Optional style, radial (default) or bullet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<GaugeStyle>,
+}
+
+/// Parameters for render_gauge tool
+#[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct RenderGaugeParams {
+    /// The data for the gauge
+    pub data: GaugeData,
+}
+
+/// One category of values for a box plot
+#[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct BoxplotGroup {
+    /// Category label
+    pub label: String,
+    /// Raw observations; non-finite values are ignored
+    pub values: Vec<f64>,
+}
+
+/// Box plot data structure
+#[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct BoxplotData {
+    /// Groups to compare, drawn left to right
+    pub groups: Vec<BoxplotGroup>,
+    /// Optional chart title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Optional y-axis label
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "yAxisLabel")]
+    pub y_axis_label: Option<String>,
+}
+
+/// Parameters for render_boxplot tool
+#[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct RenderBoxplotParams {
+    /// The data for the box plot
+    pub data: BoxplotData,
+}
+
+/// Groups with fewer values than this are drawn as points rather than a box
+const MIN_BOX_POINTS: usize = 5;
+
+/// Quartiles with Tukey whiskers (1.5 × IQR) and the values beyond them
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct BoxStats {
+    q1: f64,
+    median: f64,
+    q3: f64,
+    lower_whisker: f64,
+    upper_whisker: f64,
+    outliers: Vec<f64>,
+}
+
+/// What the template draws for one group
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct BoxplotGroupStats {
+    label: String,
+    count: usize,
+    /// Missing when the group is too small for a box
+    stats: Option<BoxStats>,
+    /// The values of a group too small for a box
+    points: Vec<f64>,
+}
+
+/// Quantile of sorted values, interpolating linearly between closest ranks
+fn quantile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (sorted.len() - 1) as f64 * p;
+    let below = rank.floor() as usize;
+    let above = rank.ceil() as usize;
+    sorted[below] + (sorted[above] - sorted[below]) * (rank - below as f64)
+}
+
+fn boxplot_group_stats(group: &BoxplotGroup) -> BoxplotGroupStats {
+    let mut values: Vec<f64> = group
+        .values
+        .iter()
+        .copied()
+        .filter(|v| v.is_finite())
+        .collect();
+    values.sort_by(f64::total_cmp);
+
+    if values.len() < MIN_BOX_POINTS {
+        return BoxplotGroupStats {
+            label: group.label.clone(),
+            count: values.len(),
+            stats: None,
+            points: values,
+        };
+    }
+
+    let q1 = quantile(&values, 0.25);
+    let q3 = quantile(&values, 0.75);
+    let fence = 1.5 * (q3 - q1);
+    let (low_fence, high_fence) = (q1 - fence, q3 + fence);
+    let inside = || {
+        values
+            .iter()
+            .copied()
+            .filter(|v| (low_fence..=high_fence).contains(v))
+    };
+    let stats = BoxStats {
+        q1,
+        median: quantile(&values, 0.5),
+        q3,
+        lower_whisker: inside().next().unwrap_or(q1),
+        upper_whisker: inside().next_back().unwrap_or(q3),
+        outliers: values
+            .iter()
+            .copied()
+            .filter(|v| !(low_fence..=high_fence).contains(v))
+            .collect(),
+    };
+    BoxplotGroupStats {
+        label: group.label.clone(),
+        count: values.len(),
+        stats: Some(stats),
+        points: Vec::new(),
+    }
+}
+
+/// One step of a funnel
+#[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct FunnelStage {
+    /// Stage label, e.g. "Visited pricing"
+    pub label: String,
+    /// How many reached this stage
+    pub value: f64,
+}
+
+/// Funnel data structure
+#[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct FunnelData {
+    /// Stages in order, from the top of the funnel down
+    pub stages: Vec<FunnelStage>,
+    /// Optional chart title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// Parameters for render_funnel tool
+#[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct RenderFunnelParams {
+    /// The data for the funnel
+    pub data: FunnelData,
+}
+
+/// What the template draws for one stage, percentages from 0 to 100
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct FunnelStageStats {
+    label: String,
+    value: f64,
+    /// Share of the first stage; missing when the first stage is empty
+    percent_of_first: Option<f64>,
+    /// Share of the previous stage lost at this one; missing for the first stage and after
+    /// an empty one
+    drop_off: Option<f64>,
+}
+
+fn funnel_stage_stats(stages: &[FunnelStage]) -> Vec<FunnelStageStats> {
+    let first = stages.first().map_or(0.0, |stage| stage.value);
+    let percent = |value: f64, of: f64| (of > 0.0).then(|| value / of * 100.0);
+    stages
+        .iter()
+        .enumerate()
+        .map(|(index, stage)| FunnelStageStats {
+            label: stage.label.clone(),
+            value: stage.value,
+            percent_of_first: percent(stage.value, first),
+            drop_off: index.checked_sub(1).and_then(|previous| {
+                let previous = stages[previous].value;
+                percent(previous - stage.value, previous)
+            }),
+        })
+        .collect()
+}
+
+/// A word and how much it should stand out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct WordWeight {
+    pub word: String,
+    /// Relative weight, e.g. a count; must be positive
+    pub weight: f64,
+}
+
+/// Word cloud data structure; give either `words` or `text`
+#[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct WordcloudData {
+    /// Precomputed words with their weights
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<WordWeight>>,
+    /// Raw text to count words in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Most words to show (defaults to 100)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_words: Option<usize>,
+    /// Whether to leave out common English words when counting `text` (defaults to true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove_stopwords: Option<bool>,
+    /// More words to leave out when counting `text`, e.g. the product name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_stopwords: Option<Vec<String>>,
+    /// Shortest word to count from `text`, in characters (defaults to 3)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_word_length: Option<usize>,
+    /// Optional chart title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// Parameters for render_wordcloud tool
+#[derive(Debug, Serialize, Deserialize, rmcp::schemars::JsonSchema)]
+pub struct RenderWordcloudParams {
+    /// The data for the word cloud
+    pub data: WordcloudData,
+}
+
+const DEFAULT_MAX_WORDS: usize = 100;
+const DEFAULT_MIN_WORD_LENGTH: usize = 3;
+
+/// Common English words that say little about what a text is about
+const ENGLISH_STOPWORDS: &[&str] = &[
+    "a",
+    "about",
+    "above",
+    "after",
+    "again",
+    "against",
+    "all",
+    "also",
+    "am",
+    "an",
+    "and",
+    "any",
+    "are",
+    "aren't",
+    "as",
+    "at",
+    "be",
+    "because",
+    "been",
+    "before",
+    "being",
+    "below",
+    "between",
+    "both",
+    "but",
+    "by",
+    "can",
+    "can't",
+    "could",
+    "couldn't",
+    "did",
+    "didn't",
+    "do",
+    "does",
+    "doesn't",
+    "doing",
+    "don't",
+    "down",
+    "during",
+    "each",
+    "even",
+    "few",
+    "for",
+    "from",
+    "further",
+    "get",
+    "got",
+    "had",
+    "hadn't",
+    "has",
+    "hasn't",
+    "have",
+    "haven't",
+    "having",
+    "he",
+    "her",
+    "here",
+    "hers",
+    "herself",
+    "him",
+    "himself",
+    "his",
+    "how",
+    "i",
+    "i'm",
+    "i've",
+    "if",
+    "in",
+    "into",
+    "is",
+    "isn't",
+    "it",
+    "it's",
+    "its",
+    "itself",
+    "just",
+    "let's",
+    "like",
+    "more",
+    "most",
+    "much",
+    "must",
+    "my",
+    "myself",
+    "no",
+    "nor",
+    "not",
+    "now",
+    "of",
+    "off",
+    "on",
+    "once",
+    "only",
+    "or",
+    "other",
+    "our",
+    "ours",
+    "ourselves",
+    "out",
+    "over",
+    "own",
+    "really",
+    "same",
+    "she",
+    "should",
+    "shouldn't",
+    "so",
+    "some",
+    "such",
+    "than",
+    "that",
+    "that's",
+    "the",
+    "their",
+    "theirs",
+    "them",
+    "themselves",
+    "then",
+    "there",
+    "there's",
+    "these",
+    "they",
+    "they're",
+    "this",
+    "those",
+    "through",
+    "to",
+    "too",
+    "under",
+    "until",
+    "up",
+    "us",
+    "very",
+    "was",
+    "wasn't",
+    "we",
+    "we're",
+    "were",
+    "weren't",
+    "what",
+    "when",
+    "where",
+    "which",
+    "while",
+    "who",
+    "whom",
+    "why",
+    "will",
+    "with",
+    "won't",
+    "would",
+    "wouldn't",
+    "you",
+    "you're",
+    "your",
+    "yours",
+    "yourself",
+    "yourselves",
+];
+
+/// The `max_words` most frequent words in `text`, most frequent first.
+///
+/// Words are split on Unicode word boundaries and lowercased. Words shorter than
+/// `min_word_length` characters, stopwords, and tokens without a letter (such as numbers)
+/// are left out.
+fn word_frequencies(
+    text: &str,
+    max_words: usize,
+    min_word_length: usize,
+    stopwords: &HashSet<String>,
+) -> Vec<WordWeight> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for word in text.unicode_words() {
+        let word = word.to_lowercase();
+        if word.chars().count() < min_word_length
+            || !word.chars().any(char::is_alphabetic)
+            || stopwords.contains(&word)
+        {
+            continue;
+        }
+        *counts.entry(word).or_default() += 1;
+    }
+
+    let mut words: Vec<(String, usize)> = counts.into_iter().collect();
+    // Ties are broken alphabetically so the same text always gives the same cloud
+    words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    words.truncate(max_words);
+    words
+        .into_iter()
+        .map(|(word, count)| WordWeight {
+            word,
+            weight: count as f64,
+        })
+        .collect()
+}
+
+/// The weighted words to draw, counted from the text or taken from the given list
+fn wordcloud_words(data: &WordcloudData) -> Result<Vec<WordWeight>, String> {
+    let max_words = data.max_words.unwrap_or(DEFAULT_MAX_WORDS);
+    if max_words == 0 {
+        return Err("'max_words' must be at least 1".to_string());
+    }
+
+    let words = match (&data.words, &data.text) {
+        (Some(words), None) => {
+            if let Some(word) = words
+                .iter()
+                .find(|word| !(word.weight.is_finite() && word.weight > 0.0))
+            {
+                return Err(format!(
+                    "Word '{}' has weight {}, weights must be positive",
+                    word.word, word.weight
+                ));
+            }
+            let mut words = words.clone();
+            words.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+            words.truncate(max_words);
+            words
+        }
+        (None, Some(text)) => {
+            let mut stopwords: HashSet<String> = HashSet::new();
+            if data.remove_stopwords.unwrap_or(true) {
+                stopwords.extend(ENGLISH_STOPWORDS.iter().map(|word| word.to_string()));
+            }
+            stopwords.extend(
+                data.extra_stopwords
+                    .iter()
+                    .flatten()
+                    .map(|word| word.trim().to_lowercase()),
+            );
+            word_frequencies(
+                text,
+                max_words,
+                data.min_word_length.unwrap_or(DEFAULT_MIN_WORD_LENGTH),
+                &stopwords,
+            )
+        }
+        _ => return Err("Provide exactly one of 'words' or 'text'".to_string()),
+    };
+
+    if words.is_empty() {
+        return Err("No words left to show after filtering".to_string());
+    }
+    Ok(words)
+}
+
 /// An extension for automatic data visualization and UI generation
 #[derive(Clone)]
 pub struct AutoVisualiserRouter {
@@ -445,7 +1114,12 @@ impl AutoVisualiserRouter {
             - **render_treemap**: Creates interactive treemap visualizations for hierarchical data
             - **render_chord**: Creates interactive chord diagrams for relationship/flow visualization
             - **render_map**: Creates interactive map visualizations with location markers
-            - **show_chart**: Creates interactive line, scatter, or bar charts for data visualization
+            - **show_chart**: Creates interactive line, scatter, or bar charts for data visualization, including time series over dates
+            - **render_gauge**: Shows a single KPI against its range and targets as a radial gauge or bullet bar
+            - **render_boxplot**: Compares distributions across categories as box plots with quartiles, whiskers and outliers
+            - **render_sunburst**: Shows hierarchical share-of-total as concentric rings, for drilling down through levels
+            - **render_funnel**: Shows how many make it through each ordered stage of a process, with the drop-off between stages
+            - **render_wordcloud**: Shows the most common words in raw text, or weighted words you provide, as a word cloud
         "#};
 
         Self {
@@ -522,7 +1196,7 @@ Example:
         let base64_encoded = STANDARD.encode(html_bytes);
 
         let resource_contents = ResourceContents::BlobResourceContents {
-            uri: "ui://sankey/diagram".to_string(),
+            uri: resource_uri("sankey", &data_json),
             mime_type: Some("text/html".to_string()),
             blob: base64_encoded,
             meta: None,
@@ -604,7 +1278,7 @@ Example:
         let base64_encoded = STANDARD.encode(html_bytes);
 
         let resource_contents = ResourceContents::BlobResourceContents {
-            uri: "ui://radar/chart".to_string(),
+            uri: resource_uri("radar", &data_json),
             mime_type: Some("text/html".to_string()),
             blob: base64_encoded,
             meta: None,
@@ -691,7 +1365,7 @@ Example multiple charts:
         let base64_encoded = STANDARD.encode(html_bytes);
 
         let resource_contents = ResourceContents::BlobResourceContents {
-            uri: "ui://donut/chart".to_string(),
+            uri: resource_uri("donut", &data_json),
             mime_type: Some("text/html".to_string()),
             blob: base64_encoded,
             meta: None,
@@ -775,7 +1449,7 @@ Example:
         let base64_encoded = STANDARD.encode(html_bytes);
 
         let resource_contents = ResourceContents::BlobResourceContents {
-            uri: "ui://treemap/visualization".to_string(),
+            uri: resource_uri("treemap", &data_json),
             mime_type: Some("text/html".to_string()),
             blob: base64_encoded,
             meta: None,
@@ -853,7 +1527,7 @@ Example:
         let base64_encoded = STANDARD.encode(html_bytes);
 
         let resource_contents = ResourceContents::BlobResourceContents {
-            uri: "ui://chord/diagram".to_string(),
+            uri: resource_uri("chord", &data_json),
             mime_type: Some("text/html".to_string()),
             blob: base64_encoded,
             meta: None,
@@ -962,7 +1636,7 @@ Example:
         let base64_encoded = STANDARD.encode(html_bytes);
 
         let resource_contents = ResourceContents::BlobResourceContents {
-            uri: "ui://map/visualization".to_string(),
+            uri: resource_uri("map", &data_json),
             mime_type: Some("text/html".to_string()),
             blob: base64_encoded,
             meta: None,
@@ -980,7 +1654,11 @@ Example:
         description = r#"show interactive line, scatter, or bar charts
 
 Required: type ('line', 'scatter', or 'bar'), datasets array
-Optional: labels, title, subtitle, xAxisLabel, yAxisLabel, options
+Optional: labels, title, subtitle, xAxisLabel, yAxisLabel, xAxisType, options
+
+For values over dates, set xAxisType to 'time' and give ISO 8601 dates ('2024-03-01' or
+'2024-03-01T12:30:00Z') either as labels or as x in {x, y} points. Ticks are formatted to suit
+the span of the dates.
 
 Example:
 {
@@ -990,14 +1668,27 @@ Example:
   "datasets": [
     {"label": "Product A", "data": [65, 59, 80]}
   ]
+}
+
+Time series example:
+{
+  "type": "line",
+  "xAxisType": "time",
+  "datasets": [
+    {"label": "Signups", "data": [{"x": "2024-03-01", "y": 12}, {"x": "2024-03-08", "y": 19}]}
+  ]
 }"#
     )]
     pub async fn show_chart(
         &self,
         params: Parameters<ShowChartParams>,
     ) -> Result<CallToolResult, ErrorData> {
+        let mut params = params.0;
+        resolve_chart_time_axis(&mut params.data)
+            .map_err(|e| ErrorData::new(ErrorCode::INVALID_PARAMS, e, None))?;
+
         let data = validate_data_param(
-            &serde_json::to_value(params.0).map_err(|e| {
+            &serde_json::to_value(params).map_err(|e| {
                 ErrorData::new(
                     ErrorCode::INVALID_PARAMS,
                     format!("Invalid parameters: {}", e),
@@ -1038,7 +1729,7 @@ Example:
         let base64_encoded = STANDARD.encode(html_bytes);
 
         let resource_contents = ResourceContents::BlobResourceContents {
-            uri: "ui://chart/interactive".to_string(),
+            uri: resource_uri("chart", &data_json),
             mime_type: Some("text/html".to_string()),
             blob: base64_encoded,
             meta: None,
@@ -1049,73 +1740,519 @@ Example:
         )
         .with_audience(vec![Role::User])]))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rmcp::handler::server::wrapper::Parameters;
-    use rmcp::model::RawContent;
-    use serde_json::json;
+    /// show a single metric against its range and targets
+    #[tool(
+        name = "render_gauge",
+        description = r#"show a gauge for a single KPI against its range and target thresholds
+Use this instead of a chart when reporting one metric, e.g. uptime against an SLO.
 
-    #[test]
-    fn test_validate_data_param_rejects_string() {
-        // Test that a string value for data is rejected
-        let params = json!({
-            "data": "{\"labels\": [\"A\", \"B\"], \"matrix\": [[0, 1], [1, 0]]}"
-        });
+The data must contain:
+- value: The metric value
+- min, max: The range of the scale
+- thresholds: Optional bands, each with 'value' (where the band ends) and optional 'color' and 'label'
+- label: Optional metric name
+- unit: Optional unit such as "%" or "ms"
+- style: Optional 'radial' (default) or 'bullet' for a horizontal bar
 
-        let result = validate_data_param(&params, false);
-        assert!(result.is_err());
+Example:
+{
+  "label": "Uptime",
+  "value": 99.2,
+  "min": 95,
+  "max": 100,
+  "unit": "%",
+  "thresholds": [
+    {"value": 99, "color": "red", "label": "Breach"},
+    {"value": 99.5, "color": "gold", "label": "At risk"},
+    {"value": 100, "color": "green", "label": "Healthy"}
+  ]
+}"#
+    )]
+    pub async fn render_gauge(
+        &self,
+        params: Parameters<RenderGaugeParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let gauge = &params.0.data;
+        if !(gauge.min.is_finite() && gauge.max.is_finite() && gauge.min < gauge.max) {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "'min' ({}) must be less than 'max' ({})",
+                    gauge.min, gauge.max
+                ),
+                None,
+            ));
+        }
+        if !gauge.value.is_finite() {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "'value' must be a finite number".to_string(),
+                None,
+            ));
+        }
 
-        let err = result.unwrap_err();
-        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
-        assert!(err
-            .message
-            .contains("must be a JSON object, not a JSON string"));
-        assert!(err.message.contains("without comments"));
-    }
+        let data = validate_data_param(
+            &serde_json::to_value(params.0).map_err(|e| {
+                ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!("Invalid parameters: {}", e),
+                    None,
+                )
+            })?,
+            false,
+        )?;
 
-    #[test]
-    fn test_validate_data_param_accepts_object() {
-        // Test that a proper object is accepted
-        let params = json!({
-            "data": {
-                "labels": ["A", "B"],
-                "matrix": [[0, 1], [1, 0]]
-            }
-        });
+        // Convert the data to JSON string
+        let data_json = serde_json::to_string(&data).map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Invalid JSON data: {}", e),
+                None,
+            )
+        })?;
 
-        let result = validate_data_param(&params, false);
-        assert!(result.is_ok());
+        // Load the template at compile time; the gauge is plain SVG, so no libraries needed
+        const TEMPLATE: &str = include_str!("templates/gauge_template.html");
 
-        let data = result.unwrap();
-        assert!(data.is_object());
-        assert_eq!(data["labels"][0], "A");
-    }
+        let html_content = TEMPLATE.replace("{{GAUGE_DATA}}", &data_json);
 
-    #[test]
-    fn test_validate_data_param_rejects_array_when_not_allowed() {
-        // Test that an array is rejected when allow_array is false
-        let params = json!({
-            "data": [
-                {"label": "A", "value": 10},
-                {"label": "B", "value": 20}
-            ]
-        });
+        // Save to /tmp/gauge.html for debugging
+        let debug_path = std::path::Path::new("/tmp/gauge.html");
+        if let Err(e) = std::fs::write(debug_path, &html_content) {
+            tracing::warn!("Failed to write debug HTML to /tmp/gauge.html: {}", e);
+        } else {
+            tracing::info!("Debug HTML saved to /tmp/gauge.html");
+        }
 
-        let result = validate_data_param(&params, false);
-        assert!(result.is_err());
+        // Use BlobResourceContents with base64 encoding to avoid JSON string escaping issues
+        let html_bytes = html_content.as_bytes();
+        let base64_encoded = STANDARD.encode(html_bytes);
 
-        let err = result.unwrap_err();
-        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
-        assert!(err.message.contains("must be a JSON object"));
-    }
+        let resource_contents = ResourceContents::BlobResourceContents {
+            uri: resource_uri("gauge", &data_json),
+            mime_type: Some("text/html".to_string()),
+            blob: base64_encoded,
+            meta: None,
+        };
 
-    #[test]
-    fn test_validate_data_param_accepts_array_when_allowed() {
-        // Test that an array is accepted when allow_array is true
-        let params = json!({
+        Ok(CallToolResult::success(vec![Content::resource(
+            resource_contents,
+        )
+        .with_audience(vec![Role::User])]))
+    }
+    /// show box plots comparing distributions across groups
+    #[tool(
+        name = "render_boxplot",
+        description = r#"show box plots comparing the distribution of values across groups
+Pass the raw observations; quartiles, whiskers (1.5 × IQR) and outliers are computed for you.
+Use this to compare spread and skew between categories, e.g. response times per endpoint.
+
+The data must contain:
+- groups: Array of objects, each with 'label' and 'values' (array of numbers)
+- title: Optional chart title
+- yAxisLabel: Optional y-axis label
+
+Groups with fewer than 5 values are drawn as individual points, and empty groups are shown as having no data.
+
+Example:
+{
+  "title": "Latency by endpoint",
+  "yAxisLabel": "ms",
+  "groups": [
+    {"label": "/search", "values": [120, 135, 128, 140, 132, 450, 125]},
+    {"label": "/login", "values": [80, 85, 82, 90, 88, 84]}
+  ]
+}"#
+    )]
+    pub async fn render_boxplot(
+        &self,
+        params: Parameters<RenderBoxplotParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let boxplot = &params.0.data;
+        if boxplot.groups.is_empty() {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "'groups' must contain at least one group".to_string(),
+                None,
+            ));
+        }
+
+        let groups: Vec<BoxplotGroupStats> =
+            boxplot.groups.iter().map(boxplot_group_stats).collect();
+        let data = serde_json::json!({
+            "title": boxplot.title,
+            "yAxisLabel": boxplot.y_axis_label,
+            "groups": groups,
+        });
+
+        // Convert the data to JSON string
+        let data_json = serde_json::to_string(&data).map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Invalid JSON data: {}", e),
+                None,
+            )
+        })?;
+
+        // Load the template at compile time; the plot is plain SVG, so no libraries needed
+        const TEMPLATE: &str = include_str!("templates/boxplot_template.html");
+
+        let html_content = TEMPLATE.replace("{{BOXPLOT_DATA}}", &data_json);
+
+        // Save to /tmp/boxplot.html for debugging
+        let debug_path = std::path::Path::new("/tmp/boxplot.html");
+        if let Err(e) = std::fs::write(debug_path, &html_content) {
+            tracing::warn!("Failed to write debug HTML to /tmp/boxplot.html: {}", e);
+        } else {
+            tracing::info!("Debug HTML saved to /tmp/boxplot.html");
+        }
+
+        // Use BlobResourceContents with base64 encoding to avoid JSON string escaping issues
+        let html_bytes = html_content.as_bytes();
+        let base64_encoded = STANDARD.encode(html_bytes);
+
+        let resource_contents = ResourceContents::BlobResourceContents {
+            uri: resource_uri("boxplot", &data_json),
+            mime_type: Some("text/html".to_string()),
+            blob: base64_encoded,
+            meta: None,
+        };
+
+        Ok(CallToolResult::success(vec![Content::resource(
+            resource_contents,
+        )
+        .with_audience(vec![Role::User])]))
+    }
+
+    /// show a sunburst for hierarchical share-of-total data
+    #[tool(
+        name = "render_sunburst",
+        description = r#"show a sunburst, concentric rings where each ring is one level of a hierarchy and arc size is the share of the total
+Use this for share-of-total questions drilled down through several levels, e.g. budget by team then category. Click a ring to zoom into it.
+
+The data is the same hierarchy as render_treemap:
+- name: Name of the node (required)
+- value: Non-negative number for leaf nodes (optional for parent nodes)
+- children: Array of child nodes; the root needs at least one
+- category: Category for coloring (optional)
+
+Example:
+{
+  "name": "Budget",
+  "children": [
+    {
+      "name": "Engineering",
+      "children": [
+        {"name": "Salaries", "value": 800},
+        {"name": "Cloud", "value": 200}
+      ]
+    },
+    {"name": "Marketing", "children": [{"name": "Ads", "value": 300}]}
+  ]
+}"#
+    )]
+    pub async fn render_sunburst(
+        &self,
+        params: Parameters<RenderSunburstParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Some(problem) = sunburst_problem(&params.0.data) {
+            return Err(ErrorData::new(ErrorCode::INVALID_PARAMS, problem, None));
+        }
+
+        let data = validate_data_param(
+            &serde_json::to_value(params.0).map_err(|e| {
+                ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!("Invalid parameters: {}", e),
+                    None,
+                )
+            })?,
+            false,
+        )?;
+
+        // Convert the data to JSON string
+        let data_json = serde_json::to_string(&data).map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Invalid JSON data: {}", e),
+                None,
+            )
+        })?;
+
+        // Load all resources at compile time using include_str!
+        const TEMPLATE: &str = include_str!("templates/sunburst_template.html");
+        const D3_MIN: &str = include_str!("templates/assets/d3.min.js");
+
+        // Replace all placeholders with actual content
+        let html_content = TEMPLATE
+            .replace("{{D3_MIN}}", D3_MIN)
+            .replace("{{SUNBURST_DATA}}", &data_json);
+
+        // Save to /tmp/sunburst.html for debugging
+        let debug_path = std::path::Path::new("/tmp/sunburst.html");
+        if let Err(e) = std::fs::write(debug_path, &html_content) {
+            tracing::warn!("Failed to write debug HTML to /tmp/sunburst.html: {}", e);
+        } else {
+            tracing::info!("Debug HTML saved to /tmp/sunburst.html");
+        }
+
+        // Use BlobResourceContents with base64 encoding to avoid JSON string escaping issues
+        let html_bytes = html_content.as_bytes();
+        let base64_encoded = STANDARD.encode(html_bytes);
+
+        let resource_contents = ResourceContents::BlobResourceContents {
+            uri: resource_uri("sunburst", &data_json),
+            mime_type: Some("text/html".to_string()),
+            blob: base64_encoded,
+            meta: None,
+        };
+
+        Ok(CallToolResult::success(vec![Content::resource(
+            resource_contents,
+        )
+        .with_audience(vec![Role::User])]))
+    }
+
+    /// show a funnel of ordered stages with the drop-off between them
+    #[tool(
+        name = "render_funnel",
+        description = r#"show a funnel of ordered stages, e.g. a signup or checkout conversion
+Give the count at each stage; the share of the first stage and the drop-off from the previous stage are computed for you.
+
+The data must contain:
+- stages: Array of objects in order, each with 'label' and a non-negative 'value'
+- title: Optional chart title
+
+Example:
+{
+  "title": "Signup funnel",
+  "stages": [
+    {"label": "Visited", "value": 12000},
+    {"label": "Started signup", "value": 3400},
+    {"label": "Verified email", "value": 2100},
+    {"label": "Subscribed", "value": 480}
+  ]
+}"#
+    )]
+    pub async fn render_funnel(
+        &self,
+        params: Parameters<RenderFunnelParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let funnel = &params.0.data;
+        if funnel.stages.is_empty() {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "'stages' must contain at least one stage".to_string(),
+                None,
+            ));
+        }
+        if let Some(stage) = funnel
+            .stages
+            .iter()
+            .find(|stage| !(stage.value.is_finite() && stage.value >= 0.0))
+        {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "Stage '{}' has value {}, funnel values must be non-negative",
+                    stage.label, stage.value
+                ),
+                None,
+            ));
+        }
+
+        let data = serde_json::json!({
+            "title": funnel.title,
+            "stages": funnel_stage_stats(&funnel.stages),
+        });
+
+        // Convert the data to JSON string
+        let data_json = serde_json::to_string(&data).map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Invalid JSON data: {}", e),
+                None,
+            )
+        })?;
+
+        // Load the template at compile time; the funnel is plain SVG, so no libraries needed
+        const TEMPLATE: &str = include_str!("templates/funnel_template.html");
+
+        let html_content = TEMPLATE.replace("{{FUNNEL_DATA}}", &data_json);
+
+        // Save to /tmp/funnel.html for debugging
+        let debug_path = std::path::Path::new("/tmp/funnel.html");
+        if let Err(e) = std::fs::write(debug_path, &html_content) {
+            tracing::warn!("Failed to write debug HTML to /tmp/funnel.html: {}", e);
+        } else {
+            tracing::info!("Debug HTML saved to /tmp/funnel.html");
+        }
+
+        // Use BlobResourceContents with base64 encoding to avoid JSON string escaping issues
+        let html_bytes = html_content.as_bytes();
+        let base64_encoded = STANDARD.encode(html_bytes);
+
+        let resource_contents = ResourceContents::BlobResourceContents {
+            uri: resource_uri("funnel", &data_json),
+            mime_type: Some("text/html".to_string()),
+            blob: base64_encoded,
+            meta: None,
+        };
+
+        Ok(CallToolResult::success(vec![Content::resource(
+            resource_contents,
+        )
+        .with_audience(vec![Role::User])]))
+    }
+
+    /// show a word cloud of the most common words in a text
+    #[tool(
+        name = "render_wordcloud",
+        description = r#"show a word cloud, sizing words by how often they occur
+Use this to summarize free text such as survey feedback or log messages. Pass the raw text and the words are counted for you, or pass words you have already weighted.
+
+The data must contain one of:
+- text: Raw text; it is split into words in any language, lowercased, and counted
+- words: Array of objects, each with 'word' and a positive 'weight'
+
+Options:
+- max_words: Most words to show (default 100)
+- remove_stopwords: Leave out common English words such as "the" and "and" (default true)
+- extra_stopwords: More words to leave out, e.g. the product name
+- min_word_length: Shortest word to count, in characters (default 3)
+- title: Optional chart title
+
+Example:
+{
+  "title": "Survey feedback",
+  "text": "Setup was quick. The docs were confusing, but setup support was quick to help.",
+  "extra_stopwords": ["goose"],
+  "max_words": 50
+}"#
+    )]
+    pub async fn render_wordcloud(
+        &self,
+        params: Parameters<RenderWordcloudParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let wordcloud = &params.0.data;
+        let words = wordcloud_words(wordcloud)
+            .map_err(|message| ErrorData::new(ErrorCode::INVALID_PARAMS, message, None))?;
+
+        let data = serde_json::json!({
+            "title": wordcloud.title,
+            "words": words,
+        });
+
+        // Convert the data to JSON string
+        let data_json = serde_json::to_string(&data).map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Invalid JSON data: {}", e),
+                None,
+            )
+        })?;
+
+        // Load all resources at compile time using include_str!
+        const TEMPLATE: &str = include_str!("templates/wordcloud_template.html");
+        const D3_MIN: &str = include_str!("templates/assets/d3.min.js");
+
+        // Replace all placeholders with actual content
+        let html_content = TEMPLATE
+            .replace("{{D3_MIN}}", D3_MIN)
+            .replace("{{WORDCLOUD_DATA}}", &data_json);
+
+        // Save to /tmp/wordcloud.html for debugging
+        let debug_path = std::path::Path::new("/tmp/wordcloud.html");
+        if let Err(e) = std::fs::write(debug_path, &html_content) {
+            tracing::warn!("Failed to write debug HTML to /tmp/wordcloud.html: {}", e);
+        } else {
+            tracing::info!("Debug HTML saved to /tmp/wordcloud.html");
+        }
+
+        // Use BlobResourceContents with base64 encoding to avoid JSON string escaping issues
+        let html_bytes = html_content.as_bytes();
+        let base64_encoded = STANDARD.encode(html_bytes);
+
+        let resource_contents = ResourceContents::BlobResourceContents {
+            uri: resource_uri("wordcloud", &data_json),
+            mime_type: Some("text/html".to_string()),
+            blob: base64_encoded,
+            meta: None,
+        };
+
+        Ok(CallToolResult::success(vec![Content::resource(
+            resource_contents,
+        )
+        .with_audience(vec![Role::User])]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::handler::server::wrapper::Parameters;
+    use rmcp::model::RawContent;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_data_param_rejects_string() {
+        // Test that a string value for data is rejected
+        let params = json!({
+            "data": "{\"labels\": [\"A\", \"B\"], \"matrix\": [[0, 1], [1, 0]]}"
+        });
+
+        let result = validate_data_param(&params, false);
+        assert!(result.is_err());
+
+        let err = result.unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        assert!(err
+            .message
+            .contains("must be a JSON object, not a JSON string"));
+        assert!(err.message.contains("without comments"));
+    }
+
+    #[test]
+    fn test_validate_data_param_accepts_object() {
+        // Test that a proper object is accepted
+        let params = json!({
+            "data": {
+                "labels": ["A", "B"],
+                "matrix": [[0, 1], [1, 0]]
+            }
+        });
+
+        let result = validate_data_param(&params, false);
+        assert!(result.is_ok());
+
+        let data = result.unwrap();
+        assert!(data.is_object());
+        assert_eq!(data["labels"][0], "A");
+    }
+
+    #[test]
+    fn test_validate_data_param_rejects_array_when_not_allowed() {
+        // Test that an array is rejected when allow_array is false
+        let params = json!({
+            "data": [
+                {"label": "A", "value": 10},
+                {"label": "B", "value": 20}
+            ]
+        });
+
+        let result = validate_data_param(&params, false);
+        assert!(result.is_err());
+
+        let err = result.unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        assert!(err.message.contains("must be a JSON object"));
+    }
+
+    #[test]
+    fn test_validate_data_param_accepts_array_when_allowed() {
+        // Test that an array is accepted when allow_array is true
+        let params = json!({
             "data": [
                 {"label": "A", "value": 10},
                 {"label": "B", "value": 20}
@@ -1233,7 +2370,7 @@ mod tests {
             if let ResourceContents::BlobResourceContents { uri, mime_type, .. } =
                 &resource.resource
             {
-                assert_eq!(uri, "ui://sankey/diagram");
+                assert!(uri.starts_with("ui://sankey/"));
                 assert_eq!(mime_type.as_ref().unwrap(), "text/html");
             } else {
                 panic!("Expected BlobResourceContents");
@@ -1282,7 +2419,7 @@ mod tests {
                 ..
             } = &resource.resource
             {
-                assert_eq!(uri, "ui://radar/chart");
+                assert!(uri.starts_with("ui://radar/"));
                 assert_eq!(mime_type.as_ref().unwrap(), "text/html");
                 assert!(!blob.is_empty(), "HTML content should not be empty");
             } else {
@@ -1293,6 +2430,41 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_resource_uri_is_keyed_by_data() {
+        let router = AutoVisualiserRouter::new();
+        let uri_for = |data: Vec<f64>| {
+            let router = router.clone();
+            async move {
+                let params = Parameters(RenderRadarParams {
+                    data: RadarData {
+                        labels: vec!["Speed".to_string(), "Power".to_string()],
+                        datasets: vec![RadarDataset {
+                            label: "Player 1".to_string(),
+                            data,
+                        }],
+                    },
+                });
+                let tool_result = router.render_radar(params).await.unwrap();
+                let RawContent::Resource(resource) = &*tool_result.content[0] else {
+                    panic!("Expected Resource content");
+                };
+                let ResourceContents::BlobResourceContents { uri, .. } = &resource.resource else {
+                    panic!("Expected BlobResourceContents");
+                };
+                uri.clone()
+            }
+        };
+
+        let first = uri_for(vec![80.0, 90.0]).await;
+        assert_eq!(first, uri_for(vec![80.0, 90.0]).await);
+        assert_ne!(first, uri_for(vec![80.0, 91.0]).await);
+
+        let hash = first.strip_prefix("ui://radar/").unwrap();
+        assert_eq!(hash.len(), 16);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
     #[tokio::test]
     async fn test_render_donut() {
         let router = AutoVisualiserRouter::new();
@@ -1437,8 +2609,14 @@ mod tests {
                 datasets: vec![ChartDataset {
                     label: "Test Data".to_string(),
                     data: ChartDataValues::Points(vec![
-                        ChartPoint { x: 1.0, y: 2.0 },
-                        ChartPoint { x: 2.0, y: 4.0 },
+                        ChartPoint {
+                            x: ChartXValue::Number(1.0),
+                            y: 2.0,
+                        },
+                        ChartPoint {
+                            x: ChartXValue::Number(2.0),
+                            y: 4.0,
+                        },
                     ]),
                     background_color: None,
                     border_color: None,
@@ -1450,6 +2628,7 @@ mod tests {
                 title: None,
                 subtitle: None,
                 x_axis_label: None,
+                x_axis_type: None,
                 y_axis_label: None,
             },
         });
@@ -1469,4 +2648,474 @@ mod tests {
             &vec![Role::User]
         );
     }
+
+    #[test]
+    fn test_resolve_chart_time_axis() {
+        let chart = |labels: Option<Vec<&str>>, data: ChartDataValues, x_axis_type| ChartData {
+            chart_type: ChartType::Line,
+            datasets: vec![ChartDataset {
+                label: "signups".to_string(),
+                data,
+                background_color: None,
+                border_color: None,
+                border_width: None,
+                tension: None,
+                fill: None,
+            }],
+            labels: labels.map(|labels| labels.into_iter().map(String::from).collect()),
+            title: None,
+            subtitle: None,
+            x_axis_label: None,
+            x_axis_type,
+            y_axis_label: None,
+        };
+        let xs = |data: &ChartData| match &data.datasets[0].data {
+            ChartDataValues::Points(points) => points.iter().map(|p| p.x.clone()).collect(),
+            ChartDataValues::Numbers(_) => Vec::new(),
+        };
+        let day = 86_400_000.0;
+
+        // Date labels pair up with numeric values
+        let mut data = chart(
+            Some(vec!["2024-03-01", "2024-03-02T12:00:00Z"]),
+            ChartDataValues::Numbers(vec![12.0, 19.0]),
+            Some(XAxisType::Time),
+        );
+        resolve_chart_time_axis(&mut data).unwrap();
+        assert_eq!(data.labels, None);
+        assert_eq!(
+            xs(&data),
+            vec![
+                ChartXValue::Number(19783.0 * day),
+                ChartXValue::Number(19784.5 * day)
+            ]
+        );
+
+        // Dates in points, with offsets and a bare month
+        let point = |x: &str| ChartPoint {
+            x: ChartXValue::Date(x.to_string()),
+            y: 1.0,
+        };
+        let mut data = chart(
+            None,
+            ChartDataValues::Points(vec![point("2024-03-01T02:00:00+02:00"), point("2024-03")]),
+            Some(XAxisType::Time),
+        );
+        resolve_chart_time_axis(&mut data).unwrap();
+        assert_eq!(xs(&data), vec![ChartXValue::Number(19783.0 * day); 2]);
+
+        let mut data = chart(
+            None,
+            ChartDataValues::Points(vec![point("March 1st")]),
+            Some(XAxisType::Time),
+        );
+        assert!(resolve_chart_time_axis(&mut data)
+            .unwrap_err()
+            .contains("ISO 8601"));
+
+        // Dates need a time axis, and a time axis needs dates
+        let mut data = chart(
+            None,
+            ChartDataValues::Points(vec![point("2024-03-01")]),
+            None,
+        );
+        assert!(resolve_chart_time_axis(&mut data)
+            .unwrap_err()
+            .contains("xAxisType"));
+        let mut data = chart(
+            None,
+            ChartDataValues::Numbers(vec![1.0]),
+            Some(XAxisType::Time),
+        );
+        assert!(resolve_chart_time_axis(&mut data)
+            .unwrap_err()
+            .contains("signups"));
+    }
+
+    #[tokio::test]
+    async fn test_render_gauge() {
+        let router = AutoVisualiserRouter::new();
+        let gauge = |min: f64, max: f64| {
+            Parameters(RenderGaugeParams {
+                data: GaugeData {
+                    value: 99.2,
+                    min,
+                    max,
+                    thresholds: Some(vec![
+                        GaugeThreshold {
+                            value: 99.0,
+                            color: Some("#e74c3c".to_string()),
+                            label: Some("Breach".to_string()),
+                        },
+                        GaugeThreshold {
+                            value: 100.0,
+                            color: None,
+                            label: None,
+                        },
+                    ]),
+                    label: Some("Uptime".to_string()),
+                    unit: Some("%".to_string()),
+                    style: Some(GaugeStyle::Bullet),
+                },
+            })
+        };
+
+        let tool_result = router.render_gauge(gauge(95.0, 100.0)).await.unwrap();
+        assert_eq!(tool_result.content.len(), 1);
+        assert_eq!(
+            tool_result.content[0].audience().unwrap(),
+            &vec![Role::User]
+        );
+        let RawContent::Resource(resource) = &*tool_result.content[0] else {
+            panic!("Expected Resource content");
+        };
+        let ResourceContents::BlobResourceContents { uri, blob, .. } = &resource.resource else {
+            panic!("Expected BlobResourceContents");
+        };
+        assert!(uri.starts_with("ui://gauge/"));
+        let html = String::from_utf8(STANDARD.decode(blob).unwrap()).unwrap();
+        assert!(html.contains(r#""style":"bullet""#));
+        assert!(html.contains(r#""label":"Breach""#));
+        assert!(!html.contains("{{GAUGE_DATA}}"));
+
+        let err = router.render_gauge(gauge(100.0, 95.0)).await.unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_boxplot_group_stats() {
+        let group = |values: Vec<f64>| BoxplotGroup {
+            label: "latency".to_string(),
+            values,
+        };
+
+        // 1..=9 plus an outlier; quartiles interpolate between ranks
+        let mut values: Vec<f64> = (1..=9).map(f64::from).collect();
+        values.extend([100.0, f64::NAN]);
+        let summary = boxplot_group_stats(&group(values));
+        assert_eq!(summary.count, 10);
+        assert_eq!(
+            summary.stats,
+            Some(BoxStats {
+                q1: 3.25,
+                median: 5.5,
+                q3: 7.75,
+                lower_whisker: 1.0,
+                upper_whisker: 9.0,
+                outliers: vec![100.0],
+            })
+        );
+        assert!(summary.points.is_empty());
+
+        // Too few values for a box
+        let summary = boxplot_group_stats(&group(vec![3.0, 1.0]));
+        assert_eq!(summary.stats, None);
+        assert_eq!(summary.points, vec![1.0, 3.0]);
+
+        let summary = boxplot_group_stats(&group(Vec::new()));
+        assert_eq!(summary.count, 0);
+        assert!(summary.stats.is_none() && summary.points.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_render_boxplot() {
+        let router = AutoVisualiserRouter::new();
+        let params = Parameters(RenderBoxplotParams {
+            data: BoxplotData {
+                groups: vec![BoxplotGroup {
+                    label: "/search".to_string(),
+                    values: vec![120.0, 135.0, 128.0, 140.0, 132.0, 450.0, 125.0],
+                }],
+                title: Some("Latency by endpoint".to_string()),
+                y_axis_label: Some("ms".to_string()),
+            },
+        });
+
+        let tool_result = router.render_boxplot(params).await.unwrap();
+        assert_eq!(tool_result.content.len(), 1);
+        let RawContent::Resource(resource) = &*tool_result.content[0] else {
+            panic!("Expected Resource content");
+        };
+        let ResourceContents::BlobResourceContents { uri, blob, .. } = &resource.resource else {
+            panic!("Expected BlobResourceContents");
+        };
+        assert!(uri.starts_with("ui://boxplot/"));
+        let html = String::from_utf8(STANDARD.decode(blob).unwrap()).unwrap();
+        assert!(html.contains(r#""outliers":[450.0]"#));
+        assert!(html.contains(r#""median":132.0"#));
+        assert!(!html.contains("{{BOXPLOT_DATA}}"));
+
+        let err = router
+            .render_boxplot(Parameters(RenderBoxplotParams {
+                data: BoxplotData {
+                    groups: Vec::new(),
+                    title: None,
+                    y_axis_label: None,
+                },
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_render_sunburst() {
+        let router = AutoVisualiserRouter::new();
+        let node =
+            |name: &str, value: Option<f64>, children: Option<Vec<TreemapNode>>| TreemapNode {
+                name: name.to_string(),
+                value,
+                category: None,
+                children,
+            };
+        let params = Parameters(RenderSunburstParams {
+            data: node(
+                "Budget",
+                None,
+                Some(vec![
+                    node(
+                        "Engineering",
+                        None,
+                        Some(vec![node("Cloud", Some(200.0), None)]),
+                    ),
+                    node("Marketing", Some(300.0), None),
+                ]),
+            ),
+        });
+
+        let tool_result = router.render_sunburst(params).await.unwrap();
+        assert_eq!(tool_result.content.len(), 1);
+        assert_eq!(
+            tool_result.content[0].audience().unwrap(),
+            &vec![Role::User]
+        );
+        let RawContent::Resource(resource) = &*tool_result.content[0] else {
+            panic!("Expected Resource content");
+        };
+        let ResourceContents::BlobResourceContents { uri, blob, .. } = &resource.resource else {
+            panic!("Expected BlobResourceContents");
+        };
+        assert!(uri.starts_with("ui://sunburst/"));
+        let html = String::from_utf8(STANDARD.decode(blob).unwrap()).unwrap();
+        assert!(html.contains(r#""name":"Cloud""#));
+        assert!(!html.contains("{{SUNBURST_DATA}}"));
+        assert!(!html.contains("{{D3_MIN}}"));
+
+        // A lone root has nothing to draw, and negative values have no arc
+        for data in [
+            node("Budget", Some(10.0), None),
+            node("Budget", None, Some(Vec::new())),
+            node(
+                "Budget",
+                None,
+                Some(vec![node("Refunds", Some(-5.0), None)]),
+            ),
+        ] {
+            let err = router
+                .render_sunburst(Parameters(RenderSunburstParams { data }))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        }
+    }
+
+    #[test]
+    fn test_funnel_stage_stats() {
+        let stages: Vec<FunnelStage> = [
+            ("Visited", 200.0),
+            ("Signed up", 50.0),
+            ("Paid", 0.0),
+            ("Renewed", 0.0),
+        ]
+        .into_iter()
+        .map(|(label, value)| FunnelStage {
+            label: label.to_string(),
+            value,
+        })
+        .collect();
+        let stats = funnel_stage_stats(&stages);
+
+        assert_eq!(stats[0].percent_of_first, Some(100.0));
+        assert_eq!(stats[0].drop_off, None);
+        assert_eq!(stats[1].percent_of_first, Some(25.0));
+        assert_eq!(stats[1].drop_off, Some(75.0));
+        assert_eq!(stats[2].drop_off, Some(100.0));
+        // Nothing left to lose after an empty stage
+        assert_eq!(stats[3].drop_off, None);
+
+        let empty_top = funnel_stage_stats(&stages[2..]);
+        assert_eq!(empty_top[0].percent_of_first, None);
+    }
+
+    #[tokio::test]
+    async fn test_render_funnel() {
+        let router = AutoVisualiserRouter::new();
+        let funnel = |values: &[f64]| {
+            Parameters(RenderFunnelParams {
+                data: FunnelData {
+                    stages: values
+                        .iter()
+                        .enumerate()
+                        .map(|(index, value)| FunnelStage {
+                            label: format!("Stage {}", index + 1),
+                            value: *value,
+                        })
+                        .collect(),
+                    title: Some("Signup funnel".to_string()),
+                },
+            })
+        };
+
+        let tool_result = router
+            .render_funnel(funnel(&[1000.0, 400.0, 100.0]))
+            .await
+            .unwrap();
+        assert_eq!(tool_result.content.len(), 1);
+        let RawContent::Resource(resource) = &*tool_result.content[0] else {
+            panic!("Expected Resource content");
+        };
+        let ResourceContents::BlobResourceContents { uri, blob, .. } = &resource.resource else {
+            panic!("Expected BlobResourceContents");
+        };
+        assert!(uri.starts_with("ui://funnel/"));
+        let html = String::from_utf8(STANDARD.decode(blob).unwrap()).unwrap();
+        assert!(html.contains(r#""drop_off":60.0"#));
+        assert!(html.contains(r#""percent_of_first":10.0"#));
+        assert!(!html.contains("{{FUNNEL_DATA}}"));
+
+        for values in [&[][..], &[100.0, -1.0][..]] {
+            let err = router.render_funnel(funnel(values)).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        }
+    }
+
+    fn wordcloud(text: &str) -> WordcloudData {
+        WordcloudData {
+            words: None,
+            text: Some(text.to_string()),
+            max_words: None,
+            remove_stopwords: None,
+            extra_stopwords: None,
+            min_word_length: None,
+            title: None,
+        }
+    }
+
+    fn counts(words: &[WordWeight]) -> Vec<(&str, f64)> {
+        words
+            .iter()
+            .map(|word| (word.word.as_str(), word.weight))
+            .collect()
+    }
+
+    #[test]
+    fn test_word_frequencies_tokenizes_unicode_text() {
+        let text = "Café crème, CAFÉ! Über-schnell über 2024 straße; naïve café.";
+        let words = word_frequencies(text, 10, 3, &HashSet::new());
+        assert_eq!(
+            counts(&words),
+            vec![
+                ("café", 3.0),
+                ("über", 2.0),
+                ("crème", 1.0),
+                ("naïve", 1.0),
+                ("schnell", 1.0),
+                ("straße", 1.0),
+            ]
+        );
+
+        // Unicode word boundaries put each ideograph in a word of its own
+        let words = word_frequencies("東京 東京 大阪", 10, 1, &HashSet::new());
+        assert_eq!(
+            counts(&words),
+            vec![("京", 2.0), ("東", 2.0), ("大", 1.0), ("阪", 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_wordcloud_stopwords() {
+        let text =
+            "The setup was quick and the docs were great, but the setup docs for Goose were long";
+
+        let words = wordcloud_words(&wordcloud(text)).unwrap();
+        assert_eq!(counts(&words)[..2], [("docs", 2.0), ("setup", 2.0)]);
+        assert!(!words
+            .iter()
+            .any(|word| word.word == "the" || word.word == "were"));
+
+        let mut data = wordcloud(text);
+        data.extra_stopwords = Some(vec![" Goose ".to_string()]);
+        let words = wordcloud_words(&data).unwrap();
+        assert!(!words.iter().any(|word| word.word == "goose"));
+
+        let mut data = wordcloud(text);
+        data.remove_stopwords = Some(false);
+        let words = wordcloud_words(&data).unwrap();
+        assert_eq!(words[0].word, "the");
+        assert_eq!(words[0].weight, 3.0);
+    }
+
+    #[test]
+    fn test_wordcloud_max_words() {
+        let mut data = wordcloud("alpha beta beta gamma gamma gamma delta delta delta delta");
+        data.max_words = Some(2);
+        let words = wordcloud_words(&data).unwrap();
+        assert_eq!(counts(&words), vec![("delta", 4.0), ("gamma", 3.0)]);
+
+        let weighted = |word: &str, weight: f64| WordWeight {
+            word: word.to_string(),
+            weight,
+        };
+        let mut data = wordcloud("");
+        data.text = None;
+        data.words = Some(vec![
+            weighted("low", 1.0),
+            weighted("high", 9.0),
+            weighted("mid", 5.0),
+        ]);
+        data.max_words = Some(2);
+        let words = wordcloud_words(&data).unwrap();
+        assert_eq!(counts(&words), vec![("high", 9.0), ("mid", 5.0)]);
+
+        data.max_words = Some(0);
+        assert!(wordcloud_words(&data).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_render_wordcloud() {
+        let router = AutoVisualiserRouter::new();
+        let tool_result = router
+            .render_wordcloud(Parameters(RenderWordcloudParams {
+                data: wordcloud("quick setup, quick docs"),
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            tool_result.content[0].audience().unwrap(),
+            &vec![Role::User]
+        );
+        let RawContent::Resource(resource) = &*tool_result.content[0] else {
+            panic!("Expected Resource content");
+        };
+        let ResourceContents::BlobResourceContents { uri, blob, .. } = &resource.resource else {
+            panic!("Expected BlobResourceContents");
+        };
+        assert!(uri.starts_with("ui://wordcloud/"));
+        let html = String::from_utf8(STANDARD.decode(blob).unwrap()).unwrap();
+        assert!(html.contains(r#"{"weight":2.0,"word":"quick"}"#));
+        assert!(!html.contains("{{WORDCLOUD_DATA}}"));
+
+        // Neither words nor text, both, or nothing left after filtering
+        let mut both = wordcloud("quick");
+        both.words = Some(Vec::new());
+        let mut neither = wordcloud("");
+        neither.text = None;
+        for data in [both, neither, wordcloud("the and of")] {
+            let err = router
+                .render_wordcloud(Parameters(RenderWordcloudParams { data }))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        }
+    }
 }
@@ -0,0 +1,511 @@
+//! List, extract and create zip and tar.gz archives without shelling out to platform tools.
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use rmcp::model::{Content, ErrorCode, ErrorData};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    pub fn from_path(path: &Path) -> Result<Self, ErrorData> {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if name.ends_with(".zip") {
+            Ok(ArchiveFormat::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Ok(ArchiveFormat::TarGz)
+        } else {
+            Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "Unsupported archive {}. Supported formats are .zip, .tar.gz and .tgz",
+                    path.display()
+                ),
+                None,
+            ))
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "application/zip",
+            ArchiveFormat::TarGz => "application/gzip",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+fn internal_error(context: &str, e: impl std::fmt::Display) -> ErrorData {
+    ErrorData::new(
+        ErrorCode::INTERNAL_ERROR,
+        format!("{}: {}", context, e),
+        None,
+    )
+}
+
+fn open_archive(path: &Path) -> Result<File, ErrorData> {
+    File::open(path).map_err(|e| internal_error(&format!("Failed to open {}", path.display()), e))
+}
+
+/// The relative path an entry extracts to, or `None` for absolute paths and paths that climb
+/// out of the destination with `..`
+pub fn safe_entry_path(name: &str) -> Option<PathBuf> {
+    let name = name.replace('\\', "/");
+    let mut path = PathBuf::new();
+    for component in Path::new(&name).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!path.as_os_str().is_empty()).then_some(path)
+}
+
+/// Whether `entry` was asked for, either directly or through one of its parent directories
+fn is_selected(entry: &Path, selected: &[PathBuf]) -> bool {
+    selected.is_empty() || selected.iter().any(|wanted| entry.starts_with(wanted))
+}
+
+pub fn list_entries(path: &Path) -> Result<Vec<ArchiveEntry>, ErrorData> {
+    let file = open_archive(path)?;
+    let mut entries = Vec::new();
+    match ArchiveFormat::from_path(path)? {
+        ArchiveFormat::Zip => {
+            let mut archive =
+                ZipArchive::new(file).map_err(|e| internal_error("Failed to read zip", e))?;
+            for index in 0..archive.len() {
+                let entry = archive
+                    .by_index_raw(index)
+                    .map_err(|e| internal_error("Failed to read zip entry", e))?;
+                entries.push(ArchiveEntry {
+                    path: entry.name().to_string(),
+                    size: entry.size(),
+                    is_dir: entry.is_dir(),
+                });
+            }
+        }
+        ArchiveFormat::TarGz => {
+            let mut archive = tar::Archive::new(GzDecoder::new(file));
+            for entry in archive
+                .entries()
+                .map_err(|e| internal_error("Failed to read tar.gz", e))?
+            {
+                let entry = entry.map_err(|e| internal_error("Failed to read tar entry", e))?;
+                entries.push(ArchiveEntry {
+                    path: String::from_utf8_lossy(&entry.path_bytes()).into_owned(),
+                    size: entry.size(),
+                    is_dir: entry.header().entry_type().is_dir(),
+                });
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// What an extraction wrote and what it refused to write
+#[derive(Debug, Default)]
+pub struct ExtractReport {
+    pub files: usize,
+    pub directories: usize,
+    /// Entries that were not written, with the reason
+    pub skipped: Vec<(String, &'static str)>,
+}
+
+fn write_entry(destination: &Path, relative: &Path, reader: &mut impl Read) -> io::Result<()> {
+    let target = destination.join(relative);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = File::create(&target)?;
+    io::copy(reader, &mut file)?;
+    Ok(())
+}
+
+/// Extract the archive into `destination`, limited to the `selected` paths when any are given.
+///
+/// Entries with absolute paths or `..` components are skipped rather than written, as are
+/// symlinks and other special entries, so nothing lands outside `destination`.
+pub fn extract_entries(
+    path: &Path,
+    destination: &Path,
+    selected: &[String],
+) -> Result<ExtractReport, ErrorData> {
+    let selected: Vec<PathBuf> = selected
+        .iter()
+        .map(|wanted| {
+            safe_entry_path(wanted).ok_or_else(|| {
+                ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!("Invalid path to extract: {}", wanted),
+                    None,
+                )
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    let file = open_archive(path)?;
+    fs::create_dir_all(destination)
+        .map_err(|e| internal_error("Failed to create the destination directory", e))?;
+    let write_error = |e: io::Error| internal_error("Failed to extract entry", e);
+
+    let mut report = ExtractReport::default();
+    match ArchiveFormat::from_path(path)? {
+        ArchiveFormat::Zip => {
+            let mut archive =
+                ZipArchive::new(file).map_err(|e| internal_error("Failed to read zip", e))?;
+            for index in 0..archive.len() {
+                let mut entry = archive
+                    .by_index(index)
+                    .map_err(|e| internal_error("Failed to read zip entry", e))?;
+                let name = entry.name().to_string();
+                let Some(relative) = safe_entry_path(&name) else {
+                    report.skipped.push((name, "path escapes the destination"));
+                    continue;
+                };
+                if !is_selected(&relative, &selected) {
+                    continue;
+                }
+                if entry.is_symlink() {
+                    report.skipped.push((name, "symlinks are not extracted"));
+                } else if entry.is_dir() {
+                    fs::create_dir_all(destination.join(&relative)).map_err(write_error)?;
+                    report.directories += 1;
+                } else {
+                    write_entry(destination, &relative, &mut entry).map_err(write_error)?;
+                    report.files += 1;
+                }
+            }
+        }
+        ArchiveFormat::TarGz => {
+            let mut archive = tar::Archive::new(GzDecoder::new(file));
+            for entry in archive
+                .entries()
+                .map_err(|e| internal_error("Failed to read tar.gz", e))?
+            {
+                let mut entry = entry.map_err(|e| internal_error("Failed to read tar entry", e))?;
+                let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+                let Some(relative) = safe_entry_path(&name) else {
+                    report.skipped.push((name, "path escapes the destination"));
+                    continue;
+                };
+                if !is_selected(&relative, &selected) {
+                    continue;
+                }
+                let entry_type = entry.header().entry_type();
+                if entry_type.is_dir() {
+                    fs::create_dir_all(destination.join(&relative)).map_err(write_error)?;
+                    report.directories += 1;
+                } else if entry_type.is_file() {
+                    write_entry(destination, &relative, &mut entry).map_err(write_error)?;
+                    report.files += 1;
+                } else {
+                    report
+                        .skipped
+                        .push((name, "links and special files are not extracted"));
+                }
+            }
+        }
+    }
+
+    if !selected.is_empty() && report.files + report.directories == 0 {
+        return Err(ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            format!(
+                "None of the requested paths are in the archive: {}",
+                selected
+                    .iter()
+                    .map(|wanted| wanted.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None,
+        ));
+    }
+    Ok(report)
+}
+
+/// Each file to add, paired with its name in the archive. Directories are added recursively,
+/// named relative to their parent so the directory itself is kept.
+fn collect_inputs(inputs: &[PathBuf]) -> Result<Vec<(PathBuf, String, bool)>, ErrorData> {
+    let mut files = Vec::new();
+    for input in inputs {
+        if !input.exists() {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Path does not exist: {}", input.display()),
+                None,
+            ));
+        }
+        let base = input.parent().unwrap_or(Path::new(""));
+        for entry in WalkDir::new(input).sort_by_file_name() {
+            let entry = entry.map_err(|e| internal_error("Failed to read directory", e))?;
+            let name = entry
+                .path()
+                .strip_prefix(base)
+                .unwrap_or(entry.path())
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((entry.path().to_path_buf(), name, entry.file_type().is_dir()));
+        }
+    }
+    Ok(files)
+}
+
+/// Write the `inputs` into a new archive at `output`, returning how many entries were added
+pub fn create_archive(output: &Path, inputs: &[PathBuf]) -> Result<usize, ErrorData> {
+    if inputs.is_empty() {
+        return Err(ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            "The create operation needs at least one path to add".to_string(),
+            None,
+        ));
+    }
+    let format = ArchiveFormat::from_path(output)?;
+    let entries = collect_inputs(inputs)?;
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| internal_error("Failed to create the output directory", e))?;
+    }
+    let file = File::create(output)
+        .map_err(|e| internal_error(&format!("Failed to create {}", output.display()), e))?;
+    let write_error = |e: io::Error| internal_error("Failed to write archive", e);
+
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = ZipWriter::new(file);
+            let options = SimpleFileOptions::default();
+            for (source, name, is_dir) in &entries {
+                if *is_dir {
+                    zip.add_directory(name.as_str(), options)
+                        .map_err(|e| internal_error("Failed to write archive", e))?;
+                } else {
+                    zip.start_file(name.as_str(), options)
+                        .map_err(|e| internal_error("Failed to write archive", e))?;
+                    let mut reader = File::open(source).map_err(write_error)?;
+                    io::copy(&mut reader, &mut zip).map_err(write_error)?;
+                }
+            }
+            zip.finish()
+                .map_err(|e| internal_error("Failed to write archive", e))?;
+        }
+        ArchiveFormat::TarGz => {
+            let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+            for (source, name, is_dir) in &entries {
+                if *is_dir {
+                    tar.append_dir(name, source).map_err(write_error)?;
+                } else {
+                    tar.append_path_with_name(source, name)
+                        .map_err(write_error)?;
+                }
+            }
+            tar.into_inner()
+                .and_then(|gz| gz.finish())
+                .and_then(|mut file| file.flush())
+                .map_err(write_error)?;
+        }
+    }
+    Ok(entries.len())
+}
+
+pub fn render_entries(path: &Path, entries: &[ArchiveEntry]) -> Vec<Content> {
+    if entries.is_empty() {
+        return vec![Content::text(format!("{} is empty", path.display()))];
+    }
+    let total: u64 = entries.iter().map(|entry| entry.size).sum();
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            if entry.is_dir {
+                format!("{:>12}  {}", "-", entry.path)
+            } else {
+                format!("{:>12}  {}", entry.size, entry.path)
+            }
+        })
+        .collect();
+    vec![Content::text(format!(
+        "{} contains {} entries ({} bytes uncompressed):\n{:>12}  PATH\n{}",
+        path.display(),
+        entries.len(),
+        total,
+        "SIZE",
+        lines.join("\n")
+    ))]
+}
+
+pub fn render_extract_report(destination: &Path, report: &ExtractReport) -> Vec<Content> {
+    let mut text = format!(
+        "Extracted {} files and {} directories to {}",
+        report.files,
+        report.directories,
+        destination.display()
+    );
+    if !report.skipped.is_empty() {
+        text.push_str(&format!("\nSkipped {} entries:", report.skipped.len()));
+        for (name, reason) in &report.skipped {
+            text.push_str(&format!("\n- {} ({})", name, reason));
+        }
+    }
+    vec![Content::text(text)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/archives")
+            .join(name)
+    }
+
+    #[test]
+    fn test_safe_entry_path() {
+        assert_eq!(
+            safe_entry_path("docs/readme.txt"),
+            Some(PathBuf::from("docs/readme.txt"))
+        );
+        assert_eq!(
+            safe_entry_path("./docs/readme.txt"),
+            Some(PathBuf::from("docs/readme.txt"))
+        );
+        for name in [
+            "../evil.txt",
+            "docs/../../evil.txt",
+            "/etc/passwd",
+            "..\\evil.txt",
+            "",
+        ] {
+            assert_eq!(safe_entry_path(name), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_list_entries() {
+        for name in ["sample.zip", "sample.tar.gz"] {
+            let entries = list_entries(&fixture(name)).unwrap();
+            let files: Vec<_> = entries
+                .iter()
+                .filter(|entry| !entry.is_dir)
+                .map(|entry| (entry.path.as_str(), entry.size))
+                .collect();
+            assert_eq!(
+                files,
+                vec![
+                    ("docs/readme.txt", 13),
+                    ("docs/notes/todo.txt", 9),
+                    ("data.csv", 12)
+                ],
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_all_and_subset() {
+        for name in ["sample.zip", "sample.tar.gz"] {
+            let destination = tempfile::tempdir().unwrap();
+            let report = extract_entries(&fixture(name), destination.path(), &[]).unwrap();
+            assert_eq!(report.files, 3, "{}", name);
+            assert!(report.skipped.is_empty());
+            assert_eq!(
+                fs::read_to_string(destination.path().join("docs/readme.txt")).unwrap(),
+                "Hello, world!"
+            );
+
+            let destination = tempfile::tempdir().unwrap();
+            let report = extract_entries(
+                &fixture(name),
+                destination.path(),
+                &["docs/notes".to_string()],
+            )
+            .unwrap();
+            assert_eq!(report.files, 1, "{}", name);
+            assert!(destination.path().join("docs/notes/todo.txt").exists());
+            assert!(!destination.path().join("data.csv").exists());
+        }
+    }
+
+    #[test]
+    fn test_extract_skips_traversal_entries() {
+        for name in ["malicious.zip", "malicious.tar.gz"] {
+            let root = tempfile::tempdir().unwrap();
+            let destination = root.path().join("out");
+            let report = extract_entries(&fixture(name), &destination, &[]).unwrap();
+
+            assert_eq!(report.files, 1, "{}", name);
+            assert!(destination.join("safe.txt").exists());
+            let skipped: Vec<_> = report.skipped.iter().map(|(n, _)| n.as_str()).collect();
+            assert_eq!(
+                skipped,
+                vec!["../evil.txt", "/tmp/absolute.txt"],
+                "{}",
+                name
+            );
+            assert!(!root.path().join("evil.txt").exists());
+        }
+    }
+
+    #[test]
+    fn test_extract_rejects_missing_selection() {
+        let destination = tempfile::tempdir().unwrap();
+        let err = extract_entries(
+            &fixture("sample.zip"),
+            destination.path(),
+            &["nope".to_string()],
+        )
+        .unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_create_round_trips() {
+        let root = tempfile::tempdir().unwrap();
+        let input = root.path().join("project");
+        fs::create_dir_all(input.join("src")).unwrap();
+        fs::write(input.join("src/main.rs"), "fn main() {}").unwrap();
+        let extra = root.path().join("notes.txt");
+        fs::write(&extra, "notes").unwrap();
+
+        for name in ["out.zip", "out.tar.gz"] {
+            let output = root.path().join(name);
+            let added = create_archive(&output, &[input.clone(), extra.clone()]).unwrap();
+            assert_eq!(added, 4, "{}", name);
+
+            let mut paths: Vec<_> = list_entries(&output)
+                .unwrap()
+                .into_iter()
+                .filter(|entry| !entry.is_dir)
+                .map(|entry| entry.path)
+                .collect();
+            paths.sort();
+            assert_eq!(paths, vec!["notes.txt", "project/src/main.rs"], "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        let err = ArchiveFormat::from_path(Path::new("archive.rar")).unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+    }
+}
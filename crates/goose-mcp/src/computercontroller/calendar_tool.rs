@@ -0,0 +1,944 @@
+//! Calendar events and reminders. On macOS these go through Calendar and Reminders with
+//! generated AppleScript, on Windows through Outlook when it is installed, and everywhere
+//! else into an ICS file in the cache directory.
+//!
+//! Times without an offset are taken to be in the system time zone, and results are shown
+//! in it.
+
+use super::platform::SystemAutomation;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use rmcp::model::{ErrorCode, ErrorData};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Name of the fallback calendar file inside the cache directory
+pub const ICS_FILE_NAME: &str = "calendar.ics";
+/// Days listed when list_events is given no end
+pub const DEFAULT_RANGE_DAYS: i64 = 7;
+
+/// Format used to pass local times to and from scripts
+const SCRIPT_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+const ICS_UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const DISPLAY_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub title: String,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub notes: Option<String>,
+    /// The calendar the event is in, when the backend has more than one
+    pub calendar: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reminder {
+    pub title: String,
+    pub due: Option<DateTime<Local>>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Backend {
+    /// Calendar and Reminders on macOS
+    AppleScript,
+    /// Outlook on Windows, with reminders kept as tasks
+    Outlook,
+    /// A local ICS file
+    Ics(PathBuf),
+}
+
+impl Backend {
+    /// Pick the backend for this machine, falling back to an ICS file in `cache_dir`
+    pub fn detect(automation: &dyn SystemAutomation, cache_dir: &Path) -> Self {
+        if cfg!(target_os = "macos") {
+            Backend::AppleScript
+        } else if cfg!(target_os = "windows") && outlook_available(automation) {
+            Backend::Outlook
+        } else {
+            Backend::Ics(cache_dir.join(ICS_FILE_NAME))
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Backend::AppleScript => "macOS Calendar and Reminders".to_string(),
+            Backend::Outlook => "Outlook".to_string(),
+            Backend::Ics(path) => format!("local ICS file {}", path.display()),
+        }
+    }
+}
+
+// Starting Outlook to probe for it is slow, so only do it once
+fn outlook_available(automation: &dyn SystemAutomation) -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        automation
+            .execute_system_script(
+                "try { New-Object -ComObject Outlook.Application | Out-Null; 'ok' } catch { 'missing' }",
+            )
+            .map(|output| output.trim() == "ok")
+            .unwrap_or(false)
+    })
+}
+
+fn invalid_params(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INVALID_PARAMS, message, None)
+}
+
+fn internal_error(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INTERNAL_ERROR, message, None)
+}
+
+fn to_local(naive: NaiveDateTime) -> Option<DateTime<Local>> {
+    Local.from_local_datetime(&naive).earliest()
+}
+
+/// Parse a time given to the tool. RFC 3339 times keep their offset; a bare date or date and
+/// time is taken to be in the system time zone.
+pub fn parse_time(input: &str) -> Result<DateTime<Local>, ErrorData> {
+    let input = input.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Ok(time.with_timezone(&Local));
+    }
+    let naive = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(input, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+    })
+    .ok_or_else(|| {
+        invalid_params(format!(
+            "Could not read '{}' as a time, use e.g. 2024-05-01 14:30 or 2024-05-01T14:30:00+02:00",
+            input
+        ))
+    })?;
+    to_local(naive)
+        .ok_or_else(|| invalid_params(format!("{} does not exist in the system time zone", input)))
+}
+
+fn script_time(time: &DateTime<Local>) -> String {
+    time.format(SCRIPT_TIME_FORMAT).to_string()
+}
+
+fn parse_script_time(value: &str) -> Option<DateTime<Local>> {
+    NaiveDateTime::parse_from_str(value.trim(), SCRIPT_TIME_FORMAT)
+        .ok()
+        .and_then(to_local)
+}
+
+/// Parse tab-separated `title, start, end, calendar` lines printed by the list scripts
+fn parse_event_lines(output: &str) -> Vec<CalendarEvent> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let (title, start, end) = (fields.first()?, fields.get(1)?, fields.get(2)?);
+            Some(CalendarEvent {
+                title: title.to_string(),
+                start: parse_script_time(start)?,
+                end: parse_script_time(end)?,
+                notes: None,
+                calendar: fields
+                    .get(3)
+                    .map(|c| c.trim().to_string())
+                    .filter(|c| !c.is_empty()),
+            })
+        })
+        .collect()
+}
+
+/// Parse tab-separated `title, due` lines printed by the reminder scripts
+fn parse_reminder_lines(output: &str) -> Vec<Reminder> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut fields = line.split('\t');
+            Reminder {
+                title: fields.next().unwrap_or_default().to_string(),
+                due: fields.next().and_then(parse_script_time),
+                notes: None,
+            }
+        })
+        .collect()
+}
+
+pub mod applescript {
+    use super::*;
+
+    pub fn string(value: &str) -> String {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    /// Build a date from its components, since parsing a date string depends on the user's
+    /// locale. The day is reset first so changing the month never overflows it.
+    pub fn date(name: &str, time: &DateTime<Local>) -> String {
+        let naive = time.naive_local();
+        format!(
+            "set {name} to current date\n\
+             set day of {name} to 1\n\
+             set year of {name} to {year}\n\
+             set month of {name} to {month}\n\
+             set day of {name} to {day}\n\
+             set time of {name} to {seconds}\n",
+            name = name,
+            year = naive.format("%Y"),
+            month = naive.format("%-m"),
+            day = naive.format("%-d"),
+            seconds = naive
+                .time()
+                .signed_duration_since(chrono::NaiveTime::MIN)
+                .num_seconds(),
+        )
+    }
+
+    pub fn list_events(start: &DateTime<Local>, end: &DateTime<Local>) -> String {
+        format!(
+            "{}{}set output to \"\"\n\
+             tell application \"Calendar\"\n\
+             \trepeat with cal in calendars\n\
+             \t\trepeat with e in (every event of cal whose start date < rangeEnd and end date > rangeStart)\n\
+             \t\t\tset output to output & (summary of e) & tab & ((start date of e) as «class isot» as string) & tab & ((end date of e) as «class isot» as string) & tab & (name of cal) & linefeed\n\
+             \t\tend repeat\n\
+             \tend repeat\n\
+             end tell\n\
+             return output",
+            date("rangeStart", start),
+            date("rangeEnd", end),
+        )
+    }
+
+    pub fn create_event(event: &CalendarEvent) -> String {
+        let mut properties = format!(
+            "summary:{}, start date:eventStart, end date:eventEnd",
+            string(&event.title)
+        );
+        if let Some(notes) = &event.notes {
+            properties.push_str(&format!(", description:{}", string(notes)));
+        }
+        format!(
+            "{}{}tell application \"Calendar\"\n\
+             \ttell (first calendar whose writable is true)\n\
+             \t\tmake new event at end of events with properties {{{}}}\n\
+             \tend tell\n\
+             end tell\n\
+             return \"ok\"",
+            date("eventStart", &event.start),
+            date("eventEnd", &event.end),
+            properties,
+        )
+    }
+
+    pub fn list_reminders() -> String {
+        "set output to \"\"\n\
+         tell application \"Reminders\"\n\
+         \trepeat with r in (every reminder whose completed is false)\n\
+         \t\tset dueText to \"\"\n\
+         \t\tif due date of r is not missing value then set dueText to ((due date of r) as «class isot» as string)\n\
+         \t\tset output to output & (name of r) & tab & dueText & linefeed\n\
+         \tend repeat\n\
+         end tell\n\
+         return output"
+            .to_string()
+    }
+
+    pub fn create_reminder(reminder: &Reminder) -> String {
+        let mut script = String::new();
+        let mut properties = format!("name:{}", string(&reminder.title));
+        if let Some(notes) = &reminder.notes {
+            properties.push_str(&format!(", body:{}", string(notes)));
+        }
+        if let Some(due) = &reminder.due {
+            script.push_str(&date("reminderDue", due));
+            properties.push_str(", due date:reminderDue");
+        }
+        script.push_str(&format!(
+            "tell application \"Reminders\"\n\
+             \tmake new reminder with properties {{{}}}\n\
+             end tell\n\
+             return \"ok\"",
+            properties
+        ));
+        script
+    }
+}
+
+pub mod powershell {
+    use super::*;
+
+    const OUTLOOK: &str = "$ErrorActionPreference = 'Stop'\n\
+        $outlook = New-Object -ComObject Outlook.Application\n\
+        $ns = $outlook.GetNamespace('MAPI')\n";
+
+    pub fn string(value: &str) -> String {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+
+    fn date(time: &DateTime<Local>) -> String {
+        format!(
+            "[datetime]::ParseExact('{}', 's', $null)",
+            script_time(time)
+        )
+    }
+
+    pub fn list_events(start: &DateTime<Local>, end: &DateTime<Local>) -> String {
+        // Restrict expects dates in Outlook's own locale, which ToString('g') produces
+        format!(
+            "{OUTLOOK}$start = {}\n\
+             $end = {}\n\
+             $folder = $ns.GetDefaultFolder(9)\n\
+             $items = $folder.Items\n\
+             $items.IncludeRecurrences = $true\n\
+             $items.Sort('[Start]')\n\
+             $filter = \"[Start] < '\" + $end.ToString('g') + \"' AND [End] > '\" + $start.ToString('g') + \"'\"\n\
+             foreach ($item in $items.Restrict($filter)) {{\n\
+             \t$item.Subject + \"`t\" + $item.Start.ToString('s') + \"`t\" + $item.End.ToString('s') + \"`t\" + $folder.Name\n\
+             }}",
+            date(start),
+            date(end),
+        )
+    }
+
+    pub fn create_event(event: &CalendarEvent) -> String {
+        let mut script = format!(
+            "{OUTLOOK}$item = $outlook.CreateItem(1)\n\
+             $item.Subject = {}\n\
+             $item.Start = {}\n\
+             $item.End = {}\n",
+            string(&event.title),
+            date(&event.start),
+            date(&event.end),
+        );
+        if let Some(notes) = &event.notes {
+            script.push_str(&format!("$item.Body = {}\n", string(notes)));
+        }
+        script.push_str("$item.Save()\n'ok'");
+        script
+    }
+
+    pub fn list_reminders() -> String {
+        // Outlook marks a task without a due date with the year 4501
+        format!(
+            "{OUTLOOK}foreach ($task in $ns.GetDefaultFolder(13).Items) {{\n\
+             \tif (-not $task.Complete) {{\n\
+             \t\t$due = ''\n\
+             \t\tif ($task.DueDate.Year -lt 4000) {{ $due = $task.DueDate.ToString('s') }}\n\
+             \t\t$task.Subject + \"`t\" + $due\n\
+             \t}}\n\
+             }}"
+        )
+    }
+
+    pub fn create_reminder(reminder: &Reminder) -> String {
+        let mut script = format!(
+            "{OUTLOOK}$task = $outlook.CreateItem(3)\n$task.Subject = {}\n",
+            string(&reminder.title)
+        );
+        if let Some(notes) = &reminder.notes {
+            script.push_str(&format!("$task.Body = {}\n", string(notes)));
+        }
+        if let Some(due) = &reminder.due {
+            script.push_str(&format!(
+                "$task.DueDate = {due}\n$task.ReminderSet = $true\n$task.ReminderTime = {due}\n",
+                due = date(due)
+            ));
+        }
+        script.push_str("$task.Save()\n'ok'");
+        script
+    }
+}
+
+pub mod ics {
+    use super::*;
+
+    const HEADER: &str =
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//goose//computer controller//EN\r\n";
+    const FOOTER: &str = "END:VCALENDAR\r\n";
+
+    fn escape(value: &str) -> String {
+        value
+            .replace('\\', "\\\\")
+            .replace(';', "\\;")
+            .replace(',', "\\,")
+            .replace('\n', "\\n")
+    }
+
+    fn unescape(value: &str) -> String {
+        let mut result = String::with_capacity(value.len());
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                result.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') | Some('N') => result.push('\n'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+        }
+        result
+    }
+
+    /// Fold a content line at 75 octets as RFC 5545 requires
+    fn fold(line: &str) -> String {
+        let mut folded = String::new();
+        let mut width = 0;
+        for c in line.chars() {
+            if width + c.len_utf8() > 75 {
+                folded.push_str("\r\n ");
+                width = 1;
+            }
+            folded.push(c);
+            width += c.len_utf8();
+        }
+        folded.push_str("\r\n");
+        folded
+    }
+
+    fn utc(time: &DateTime<Local>) -> String {
+        time.with_timezone(&Utc).format(ICS_UTC_FORMAT).to_string()
+    }
+
+    fn new_uid() -> String {
+        format!("{}@goose", Utc::now().format("%Y%m%dT%H%M%S%f"))
+    }
+
+    /// Parse a DATE or DATE-TIME value. Times without `Z` are floating and taken to be in the
+    /// system time zone.
+    fn parse_value(value: &str) -> Option<DateTime<Local>> {
+        if let Some(utc) = value.strip_suffix('Z') {
+            return NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+                .ok()
+                .map(|naive| Utc.from_utc_datetime(&naive).with_timezone(&Local));
+        }
+        NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(value, "%Y%m%d")
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })
+            .and_then(to_local)
+    }
+
+    fn component(kind: &str, properties: &[(&str, String)]) -> String {
+        let mut text = format!("BEGIN:{}\r\n", kind);
+        for (name, value) in properties {
+            text.push_str(&fold(&format!("{}:{}", name, value)));
+        }
+        text.push_str(&format!("END:{}\r\n", kind));
+        text
+    }
+
+    pub fn event_component(event: &CalendarEvent) -> String {
+        let mut properties = vec![
+            ("UID", new_uid()),
+            ("DTSTAMP", utc(&Local::now())),
+            ("DTSTART", utc(&event.start)),
+            ("DTEND", utc(&event.end)),
+            ("SUMMARY", escape(&event.title)),
+        ];
+        if let Some(notes) = &event.notes {
+            properties.push(("DESCRIPTION", escape(notes)));
+        }
+        component("VEVENT", &properties)
+    }
+
+    pub fn reminder_component(reminder: &Reminder) -> String {
+        let mut properties = vec![
+            ("UID", new_uid()),
+            ("DTSTAMP", utc(&Local::now())),
+            ("SUMMARY", escape(&reminder.title)),
+            ("STATUS", "NEEDS-ACTION".to_string()),
+        ];
+        if let Some(due) = &reminder.due {
+            properties.push(("DUE", utc(due)));
+        }
+        if let Some(notes) = &reminder.notes {
+            properties.push(("DESCRIPTION", escape(notes)));
+        }
+        component("VTODO", &properties)
+    }
+
+    /// Add a component to the calendar file, creating it if needed. Existing content is kept
+    /// as is, so anything this module does not understand survives.
+    pub fn append(path: &Path, component: &str) -> Result<(), ErrorData> {
+        let existing = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HEADER.to_string() + FOOTER,
+            Err(e) => {
+                return Err(internal_error(format!(
+                    "Failed to read {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+        let insert_at = existing
+            .rfind("END:VCALENDAR")
+            .ok_or_else(|| internal_error(format!("{} is not a calendar file", path.display())))?;
+        let updated = format!(
+            "{}{}{}",
+            &existing[..insert_at],
+            component,
+            &existing[insert_at..]
+        );
+        fs::write(path, updated)
+            .map_err(|e| internal_error(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    /// Read the events and open reminders in a calendar file. A missing file is an empty
+    /// calendar.
+    pub fn load(path: &Path) -> Result<(Vec<CalendarEvent>, Vec<Reminder>), ErrorData> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(internal_error(format!(
+                    "Failed to read {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+
+        let mut lines: Vec<String> = Vec::new();
+        for line in text.lines() {
+            match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+                (Some(continuation), Some(previous)) => previous.push_str(continuation),
+                _ => lines.push(line.to_string()),
+            }
+        }
+
+        let mut events = Vec::new();
+        let mut reminders = Vec::new();
+        let mut current: Option<(String, HashMap<String, String>)> = None;
+        for line in lines {
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            // Parameters such as TZID or VALUE=DATE are not needed to read the value
+            let name = name.split(';').next().unwrap_or(name).to_uppercase();
+            match (name.as_str(), current.as_mut()) {
+                ("BEGIN", None) if value == "VEVENT" || value == "VTODO" => {
+                    current = Some((value.to_string(), HashMap::new()));
+                }
+                ("END", Some((kind, _))) if value == kind => {
+                    let (kind, properties) = current.take().unwrap();
+                    let text = |key: &str| properties.get(key).map(|v| unescape(v));
+                    let time = |key: &str| properties.get(key).and_then(|v| parse_value(v));
+                    let title = text("SUMMARY").unwrap_or_default();
+                    if kind == "VEVENT" {
+                        let Some(start) = time("DTSTART") else {
+                            continue;
+                        };
+                        events.push(CalendarEvent {
+                            title,
+                            start,
+                            end: time("DTEND").unwrap_or(start),
+                            notes: text("DESCRIPTION"),
+                            calendar: None,
+                        });
+                    } else if text("STATUS").as_deref() != Some("COMPLETED") {
+                        reminders.push(Reminder {
+                            title,
+                            due: time("DUE"),
+                            notes: text("DESCRIPTION"),
+                        });
+                    }
+                }
+                (_, Some((_, properties))) => {
+                    properties.insert(name, value.to_string());
+                }
+                _ => {}
+            }
+        }
+        Ok((events, reminders))
+    }
+}
+
+fn run_script(automation: &dyn SystemAutomation, script: &str) -> Result<String, ErrorData> {
+    automation
+        .execute_system_script(script)
+        .map_err(|e| internal_error(format!("Failed to run calendar script: {}", e)))
+}
+
+// Create scripts print ok once they finish, since a failed script may still exit cleanly
+fn expect_ok(backend: &Backend, output: String) -> Result<(), ErrorData> {
+    if output.trim().ends_with("ok") {
+        Ok(())
+    } else {
+        Err(internal_error(format!(
+            "{} did not confirm the change: {}",
+            backend.describe(),
+            output.trim()
+        )))
+    }
+}
+
+/// Events overlapping `start..end`, sorted by start time
+pub fn list_events(
+    backend: &Backend,
+    automation: &dyn SystemAutomation,
+    start: &DateTime<Local>,
+    end: &DateTime<Local>,
+) -> Result<Vec<CalendarEvent>, ErrorData> {
+    let mut events = match backend {
+        Backend::AppleScript => parse_event_lines(&run_script(
+            automation,
+            &applescript::list_events(start, end),
+        )?),
+        Backend::Outlook => parse_event_lines(&run_script(
+            automation,
+            &powershell::list_events(start, end),
+        )?),
+        Backend::Ics(path) => ics::load(path)?
+            .0
+            .into_iter()
+            .filter(|event| event.start < *end && event.end > *start)
+            .collect(),
+    };
+    events.sort_by_key(|event| event.start);
+    Ok(events)
+}
+
+pub fn create_event(
+    backend: &Backend,
+    automation: &dyn SystemAutomation,
+    event: &CalendarEvent,
+) -> Result<(), ErrorData> {
+    if event.end < event.start {
+        return Err(invalid_params(
+            "The event must not end before it starts".to_string(),
+        ));
+    }
+    match backend {
+        Backend::AppleScript => expect_ok(
+            backend,
+            run_script(automation, &applescript::create_event(event))?,
+        ),
+        Backend::Outlook => expect_ok(
+            backend,
+            run_script(automation, &powershell::create_event(event))?,
+        ),
+        Backend::Ics(path) => ics::append(path, &ics::event_component(event)),
+    }
+}
+
+/// Reminders that have not been completed
+pub fn list_reminders(
+    backend: &Backend,
+    automation: &dyn SystemAutomation,
+) -> Result<Vec<Reminder>, ErrorData> {
+    match backend {
+        Backend::AppleScript => Ok(parse_reminder_lines(&run_script(
+            automation,
+            &applescript::list_reminders(),
+        )?)),
+        Backend::Outlook => Ok(parse_reminder_lines(&run_script(
+            automation,
+            &powershell::list_reminders(),
+        )?)),
+        Backend::Ics(path) => Ok(ics::load(path)?.1),
+    }
+}
+
+pub fn create_reminder(
+    backend: &Backend,
+    automation: &dyn SystemAutomation,
+    reminder: &Reminder,
+) -> Result<(), ErrorData> {
+    match backend {
+        Backend::AppleScript => expect_ok(
+            backend,
+            run_script(automation, &applescript::create_reminder(reminder))?,
+        ),
+        Backend::Outlook => expect_ok(
+            backend,
+            run_script(automation, &powershell::create_reminder(reminder))?,
+        ),
+        Backend::Ics(path) => ics::append(path, &ics::reminder_component(reminder)),
+    }
+}
+
+fn display(time: &DateTime<Local>) -> String {
+    time.format(DISPLAY_FORMAT).to_string()
+}
+
+fn zone_note() -> String {
+    format!(
+        "Times are in the system time zone (UTC{})",
+        Local::now().format("%:z")
+    )
+}
+
+pub fn render_events(
+    backend: &Backend,
+    start: &DateTime<Local>,
+    end: &DateTime<Local>,
+    events: &[CalendarEvent],
+) -> String {
+    let mut text = format!(
+        "Backend: {}\n{}\nEvents from {} to {}:",
+        backend.describe(),
+        zone_note(),
+        display(start),
+        display(end)
+    );
+    if events.is_empty() {
+        text.push_str("\nNo events");
+    }
+    for event in events {
+        text.push_str(&format!(
+            "\n- {} to {}: {}",
+            display(&event.start),
+            display(&event.end),
+            event.title
+        ));
+        if let Some(calendar) = &event.calendar {
+            text.push_str(&format!(" ({})", calendar));
+        }
+        if let Some(notes) = &event.notes {
+            text.push_str(&format!("\n  {}", notes.replace('\n', "\n  ")));
+        }
+    }
+    text
+}
+
+pub fn render_reminders(backend: &Backend, reminders: &[Reminder]) -> String {
+    let mut text = format!(
+        "Backend: {}\n{}\nOpen reminders:",
+        backend.describe(),
+        zone_note()
+    );
+    if reminders.is_empty() {
+        text.push_str("\nNo reminders");
+    }
+    for reminder in reminders {
+        text.push_str(&format!("\n- {}", reminder.title));
+        if let Some(due) = &reminder.due {
+            text.push_str(&format!(" (due {})", display(due)));
+        }
+        if let Some(notes) = &reminder.notes {
+            text.push_str(&format!("\n  {}", notes.replace('\n', "\n  ")));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    struct NoAutomation;
+
+    impl SystemAutomation for NoAutomation {
+        fn execute_system_script(&self, _script: &str) -> std::io::Result<String> {
+            panic!("the ICS backend must not run scripts")
+        }
+
+        fn get_shell_command(&self) -> (&'static str, &'static str) {
+            ("sh", "-c")
+        }
+
+        fn get_temp_path(&self) -> PathBuf {
+            std::env::temp_dir()
+        }
+    }
+
+    fn local(input: &str) -> DateTime<Local> {
+        parse_time(input).unwrap()
+    }
+
+    fn event(title: &str, start: &str, end: &str) -> CalendarEvent {
+        CalendarEvent {
+            title: title.to_string(),
+            start: local(start),
+            end: local(end),
+            notes: None,
+            calendar: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_time_uses_system_zone() {
+        let naive = NaiveDate::from_ymd_opt(2024, 5, 1)
+            .unwrap()
+            .and_hms_opt(14, 30, 0)
+            .unwrap();
+        assert_eq!(local("2024-05-01 14:30").naive_local(), naive);
+        assert_eq!(local("2024-05-01T14:30:00").naive_local(), naive);
+        assert_eq!(
+            local("2024-05-01").naive_local(),
+            naive.date().and_hms_opt(0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            local("2024-05-01T12:30:00Z").with_timezone(&Utc),
+            Utc.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap()
+        );
+        assert!(parse_time("next tuesday").is_err());
+    }
+
+    #[test]
+    fn test_ics_fallback_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let backend = Backend::Ics(dir.path().join(ICS_FILE_NAME));
+        assert!(backend.describe().contains(ICS_FILE_NAME));
+
+        let mut standup = event("Standup", "2024-05-01 09:00", "2024-05-01 09:15");
+        standup.notes = Some("Room 4; bring notes,\nand coffee".to_string());
+        let review = event(
+            "A very long design review title that needs folding across several ICS content lines",
+            "2024-05-02 14:00",
+            "2024-05-02 15:30",
+        );
+        let later = event("Offsite", "2024-06-10 09:00", "2024-06-10 17:00");
+        for e in [&review, &later, &standup] {
+            create_event(&backend, &NoAutomation, e).unwrap();
+        }
+        create_reminder(
+            &backend,
+            &NoAutomation,
+            &Reminder {
+                title: "Send report".to_string(),
+                due: Some(local("2024-05-03 17:00")),
+                notes: None,
+            },
+        )
+        .unwrap();
+
+        let written = fs::read_to_string(dir.path().join(ICS_FILE_NAME)).unwrap();
+        assert!(written.starts_with("BEGIN:VCALENDAR"));
+        assert!(written.trim_end().ends_with("END:VCALENDAR"));
+        assert!(written
+            .lines()
+            .all(|line| line.trim_end_matches('\r').len() <= 75));
+
+        let events = list_events(
+            &backend,
+            &NoAutomation,
+            &local("2024-05-01"),
+            &local("2024-05-08"),
+        )
+        .unwrap();
+        assert_eq!(events, vec![standup, review]);
+
+        let reminders = list_reminders(&backend, &NoAutomation).unwrap();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].title, "Send report");
+        assert_eq!(reminders[0].due, Some(local("2024-05-03 17:00")));
+
+        let rendered = render_events(
+            &backend,
+            &local("2024-05-01"),
+            &local("2024-05-08"),
+            &events,
+        );
+        assert!(rendered.contains("- 2024-05-01 09:00 to 2024-05-01 09:15: Standup"));
+        assert!(rendered.contains("\n  and coffee"));
+    }
+
+    #[test]
+    fn test_ics_reads_floating_and_completed_entries() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(ICS_FILE_NAME);
+        fs::write(
+            &path,
+            "BEGIN:VCALENDAR\r\n\
+             BEGIN:VEVENT\r\nDTSTART;TZID=Local:20240501T100000\r\nDTEND:20240501T110000\r\nSUMMARY:Floating\r\nEND:VEVENT\r\n\
+             BEGIN:VTODO\r\nSUMMARY:Done already\r\nSTATUS:COMPLETED\r\nEND:VTODO\r\n\
+             END:VCALENDAR\r\n",
+        )
+        .unwrap();
+
+        let (events, reminders) = ics::load(&path).unwrap();
+        assert_eq!(
+            events,
+            vec![event("Floating", "2024-05-01 10:00", "2024-05-01 11:00")]
+        );
+        assert!(reminders.is_empty());
+    }
+
+    #[test]
+    fn test_applescript_generation() {
+        let start = local("2024-05-01 14:30");
+        assert_eq!(
+            applescript::date("eventStart", &start),
+            "set eventStart to current date\n\
+             set day of eventStart to 1\n\
+             set year of eventStart to 2024\n\
+             set month of eventStart to 5\n\
+             set day of eventStart to 1\n\
+             set time of eventStart to 52200\n"
+        );
+        assert_eq!(
+            applescript::string(r#"Say "hi" \ wave"#),
+            r#""Say \"hi\" \\ wave""#
+        );
+
+        let mut lunch = event("Lunch with \"Sam\"", "2024-05-01 12:00", "2024-05-01 13:00");
+        lunch.notes = Some("Booked".to_string());
+        let script = applescript::create_event(&lunch);
+        assert!(script.contains("set time of eventStart to 43200\n"));
+        assert!(script.contains("set time of eventEnd to 46800\n"));
+        assert!(script.contains(
+            "make new event at end of events with properties {summary:\"Lunch with \\\"Sam\\\"\", start date:eventStart, end date:eventEnd, description:\"Booked\"}"
+        ));
+        assert!(script.ends_with("return \"ok\""));
+
+        let script = applescript::list_events(&start, &local("2024-05-02"));
+        assert!(script.contains("set day of rangeEnd to 2\n"));
+        assert!(script.contains("whose start date < rangeEnd and end date > rangeStart"));
+
+        let without_due = applescript::create_reminder(&Reminder {
+            title: "Call back".to_string(),
+            due: None,
+            notes: None,
+        });
+        assert!(without_due.starts_with("tell application \"Reminders\""));
+        assert!(without_due.contains("make new reminder with properties {name:\"Call back\"}"));
+        let with_due = applescript::create_reminder(&Reminder {
+            title: "Call back".to_string(),
+            due: Some(start),
+            notes: None,
+        });
+        assert!(with_due.contains("set time of reminderDue to 52200\n"));
+        assert!(with_due.contains("{name:\"Call back\", due date:reminderDue}"));
+    }
+
+    #[test]
+    fn test_powershell_generation_and_output_parsing() {
+        let script = powershell::create_event(&event(
+            "Bob's party",
+            "2024-05-01 18:00",
+            "2024-05-01 21:00",
+        ));
+        assert!(script.contains("$item.Subject = 'Bob''s party'\n"));
+        assert!(script
+            .contains("$item.Start = [datetime]::ParseExact('2024-05-01T18:00:00', 's', $null)\n"));
+        assert!(script.ends_with("$item.Save()\n'ok'"));
+
+        let events = parse_event_lines(
+            "Standup\t2024-05-01T09:00:00\t2024-05-01T09:15:00\tWork\nbroken line\n",
+        );
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].start, local("2024-05-01 09:00"));
+        assert_eq!(events[0].calendar.as_deref(), Some("Work"));
+
+        let reminders = parse_reminder_lines("Call back\t\nPay rent\t2024-05-31T09:00:00\n");
+        assert_eq!(reminders[0].due, None);
+        assert_eq!(reminders[1].due, Some(local("2024-05-31 09:00")));
+    }
+}
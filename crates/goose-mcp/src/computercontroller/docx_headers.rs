@@ -0,0 +1,611 @@
+//! Headers, footers and page numbers for DOCX files.
+//!
+//! These are edited directly in the package parts (word/headerN.xml, word/footerN.xml, the
+//! document relationships, content types and the body's section properties), so the rest of
+//! the document is copied through untouched.
+
+use regex::Regex;
+use rmcp::model::{ErrorCode, ErrorData};
+use std::io::{Cursor, Read, Write};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+const WORD_NS: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+const REL_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const DOCUMENT_PART: &str = "word/document.xml";
+const DOCUMENT_RELS_PART: &str = "word/_rels/document.xml.rels";
+const CONTENT_TYPES_PART: &str = "[Content_Types].xml";
+
+/// Text used for the page number paragraph when `page_numbers` is set.
+pub const PAGE_NUMBER_FORMAT: &str = "Page {PAGE} of {NUMPAGES}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartKind {
+    Header,
+    Footer,
+}
+
+impl PartKind {
+    fn name(self) -> &'static str {
+        match self {
+            PartKind::Header => "header",
+            PartKind::Footer => "footer",
+        }
+    }
+
+    fn root_tag(self) -> &'static str {
+        match self {
+            PartKind::Header => "w:hdr",
+            PartKind::Footer => "w:ftr",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HeaderFooterOptions {
+    pub header_text: Option<String>,
+    pub footer_text: Option<String>,
+    /// Value for `w:jc`: "left", "center", "right" or "both"
+    pub alignment: Option<String>,
+    /// Add a "Page X of Y" paragraph to the footer
+    pub page_numbers: bool,
+    /// `Some(true)` gives the first page a blank header and footer, `Some(false)` removes a
+    /// different first page, `None` leaves the current setting alone
+    pub different_first_page: Option<bool>,
+}
+
+/// Text of a header or footer referenced by the document
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderFooterText {
+    pub kind: PartKind,
+    /// Reference type: "default", "first" or "even"
+    pub scope: String,
+    pub text: String,
+}
+
+impl HeaderFooterText {
+    pub fn label(&self) -> String {
+        let kind = match self.kind {
+            PartKind::Header => "Header",
+            PartKind::Footer => "Footer",
+        };
+        match self.scope.as_str() {
+            "first" => format!("{} (first page)", kind),
+            "even" => format!("{} (even pages)", kind),
+            _ => kind.to_string(),
+        }
+    }
+}
+
+fn internal_error(context: &str, e: impl std::fmt::Display) -> ErrorData {
+    ErrorData::new(
+        ErrorCode::INTERNAL_ERROR,
+        format!("{}: {}", context, e),
+        None,
+    )
+}
+
+struct Package {
+    entries: Vec<(String, Vec<u8>)>,
+}
+
+impl Package {
+    fn read(bytes: &[u8]) -> Result<Self, ErrorData> {
+        let mut archive = ZipArchive::new(Cursor::new(bytes))
+            .map_err(|e| internal_error("Failed to open DOCX package", e))?;
+        let mut entries = Vec::with_capacity(archive.len());
+        for i in 0..archive.len() {
+            let mut file = archive
+                .by_index(i)
+                .map_err(|e| internal_error("Failed to read DOCX package", e))?;
+            if file.is_dir() {
+                continue;
+            }
+            let mut data = Vec::new();
+            file.read_to_end(&mut data)
+                .map_err(|e| internal_error("Failed to read DOCX package", e))?;
+            entries.push((file.name().to_string(), data));
+        }
+        Ok(Self { entries })
+    }
+
+    fn write(&self) -> Result<Vec<u8>, ErrorData> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for (name, data) in &self.entries {
+            writer
+                .start_file(name.as_str(), options)
+                .map_err(|e| internal_error("Failed to write DOCX package", e))?;
+            writer
+                .write_all(data)
+                .map_err(|e| internal_error("Failed to write DOCX package", e))?;
+        }
+        let cursor = writer
+            .finish()
+            .map_err(|e| internal_error("Failed to write DOCX package", e))?;
+        Ok(cursor.into_inner())
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.entries.iter().any(|(entry, _)| entry == name)
+    }
+
+    fn text(&self, name: &str) -> Result<Option<String>, ErrorData> {
+        match self.entries.iter().find(|(entry, _)| entry == name) {
+            Some((_, data)) => String::from_utf8(data.clone())
+                .map(Some)
+                .map_err(|e| internal_error(&format!("Failed to read {}", name), e)),
+            None => Ok(None),
+        }
+    }
+
+    fn set_text(&mut self, name: &str, text: String) {
+        match self.entries.iter_mut().find(|(entry, _)| entry == name) {
+            Some((_, data)) => *data = text.into_bytes(),
+            None => self.entries.push((name.to_string(), text.into_bytes())),
+        }
+    }
+
+    /// First unused `word/<kind>N.xml` part name
+    fn next_part_name(&self, kind: PartKind) -> String {
+        (1..)
+            .map(|n| format!("word/{}{}.xml", kind.name(), n))
+            .find(|name| !self.contains(name))
+            .expect("unbounded range always yields a free name")
+    }
+}
+
+fn attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!(r#"(?:^|\s){}="([^"]*)""#, regex::escape(name));
+    Regex::new(&pattern)
+        .ok()?
+        .captures(tag)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str())
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// One paragraph, with `{PAGE}` and `{NUMPAGES}` turned into fields Word fills in when the
+/// document is laid out.
+fn paragraph_xml(line: &str, alignment: &str) -> String {
+    let field = Regex::new(r"\{(PAGE|NUMPAGES)\}").expect("valid field regex");
+    let mut runs = String::new();
+    let mut last = 0;
+    let push_text = |runs: &mut String, text: &str| {
+        if !text.is_empty() {
+            runs.push_str(&format!(
+                r#"<w:r><w:t xml:space="preserve">{}</w:t></w:r>"#,
+                escape_xml(text)
+            ));
+        }
+    };
+    for caps in field.captures_iter(line) {
+        let whole = caps.get(0).expect("match has a group 0");
+        push_text(&mut runs, &line[last..whole.start()]);
+        runs.push_str(&format!(
+            r#"<w:fldSimple w:instr=" {} "><w:r><w:t>1</w:t></w:r></w:fldSimple>"#,
+            &caps[1]
+        ));
+        last = whole.end();
+    }
+    push_text(&mut runs, &line[last..]);
+    format!(
+        r#"<w:p><w:pPr><w:jc w:val="{}"/></w:pPr>{}</w:p>"#,
+        alignment, runs
+    )
+}
+
+fn part_xml(kind: PartKind, lines: &[String], alignment: &str) -> String {
+    let body = if lines.is_empty() {
+        "<w:p/>".to_string()
+    } else {
+        lines
+            .iter()
+            .map(|line| paragraph_xml(line, alignment))
+            .collect()
+    };
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><{tag} xmlns:w="{w}" xmlns:r="{r}">{body}</{tag}>"#,
+        tag = kind.root_tag(),
+        w = WORD_NS,
+        r = REL_NS,
+        body = body
+    )
+}
+
+/// Byte range of the inner content of the body-level `w:sectPr`, creating an empty one if the
+/// body has none.
+fn body_section(document: &mut String) -> Result<std::ops::Range<usize>, ErrorData> {
+    let body_end = document
+        .rfind("</w:body>")
+        .ok_or_else(|| internal_error("Failed to update DOCX", "word/document.xml has no body"))?;
+    let before = document[..body_end].trim_end();
+    if before.ends_with("</w:sectPr>") {
+        let close = before.len() - "</w:sectPr>".len();
+        let open = document[..close]
+            .rfind("<w:sectPr")
+            .ok_or_else(|| internal_error("Failed to update DOCX", "malformed w:sectPr"))?;
+        let inner_start = open
+            + document[open..]
+                .find('>')
+                .ok_or_else(|| internal_error("Failed to update DOCX", "malformed w:sectPr"))?
+            + 1;
+        return Ok(inner_start..close);
+    }
+    if before.ends_with("/>") {
+        if let Some(open) = before.rfind("<w:sectPr") {
+            // Expand a self-closing <w:sectPr .../> so references can go inside it
+            let tag = before[open..before.len() - 2].trim_end().to_string();
+            let replacement = format!("{}></w:sectPr>", tag);
+            let end = before.len();
+            document.replace_range(open..end, &replacement);
+            let inner = open + tag.len() + 1;
+            return Ok(inner..inner);
+        }
+    }
+    document.insert_str(body_end, "<w:sectPr></w:sectPr>");
+    let inner = body_end + "<w:sectPr>".len();
+    Ok(inner..inner)
+}
+
+fn next_relationship_id(rels: &str) -> usize {
+    Regex::new(r#"Id="rId(\d+)""#)
+        .expect("valid relationship id regex")
+        .captures_iter(rels)
+        .filter_map(|caps| caps[1].parse::<usize>().ok())
+        .max()
+        .unwrap_or(0)
+        + 1
+}
+
+/// Add or replace the header and footer of the document at `bytes`, returning the new package.
+pub fn set_header_footer(
+    bytes: &[u8],
+    options: &HeaderFooterOptions,
+) -> Result<Vec<u8>, ErrorData> {
+    if options.header_text.is_none() && options.footer_text.is_none() && !options.page_numbers {
+        return Err(ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            "set_header_footer mode requires header_text, footer_text or page_numbers",
+            None,
+        ));
+    }
+    let alignment = options.alignment.as_deref().unwrap_or("center");
+
+    let mut package = Package::read(bytes)?;
+    let mut document = package
+        .text(DOCUMENT_PART)?
+        .ok_or_else(|| internal_error("Failed to update DOCX", "missing word/document.xml"))?;
+    let mut rels = package.text(DOCUMENT_RELS_PART)?.unwrap_or_else(|| {
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"></Relationships>"#
+            .to_string()
+    });
+    let mut content_types = package
+        .text(CONTENT_TYPES_PART)?
+        .ok_or_else(|| internal_error("Failed to update DOCX", "missing [Content_Types].xml"))?;
+
+    let mut parts = Vec::new();
+    if let Some(text) = &options.header_text {
+        parts.push((PartKind::Header, text.lines().map(String::from).collect()));
+    }
+    if options.footer_text.is_some() || options.page_numbers {
+        let mut lines: Vec<String> = options
+            .footer_text
+            .as_deref()
+            .map(|text| text.lines().map(String::from).collect())
+            .unwrap_or_default();
+        if options.page_numbers {
+            lines.push(PAGE_NUMBER_FORMAT.to_string());
+        }
+        parts.push((PartKind::Footer, lines));
+    }
+
+    let first_page = options.different_first_page == Some(true);
+    let mut references = String::new();
+    let mut scopes_to_replace = Vec::new();
+    for (kind, lines) in &parts {
+        let mut variants = vec![("default", part_xml(*kind, lines, alignment))];
+        if first_page {
+            variants.push(("first", part_xml(*kind, &[], alignment)));
+        }
+        for (scope, xml) in variants {
+            let part_name = package.next_part_name(*kind);
+            let target = part_name.trim_start_matches("word/").to_string();
+            let id = format!("rId{}", next_relationship_id(&rels));
+
+            let relationship = format!(
+                r#"<Relationship Id="{}" Type="{}/{}" Target="{}"/>"#,
+                id,
+                REL_NS,
+                kind.name(),
+                target
+            );
+            let rels_end = rels.rfind("</Relationships>").ok_or_else(|| {
+                internal_error("Failed to update DOCX", "malformed document relationships")
+            })?;
+            rels.insert_str(rels_end, &relationship);
+
+            let override_entry = format!(
+                r#"<Override PartName="/{}" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.{}+xml"/>"#,
+                part_name,
+                kind.name()
+            );
+            let types_end = content_types.rfind("</Types>").ok_or_else(|| {
+                internal_error("Failed to update DOCX", "malformed [Content_Types].xml")
+            })?;
+            content_types.insert_str(types_end, &override_entry);
+
+            references.push_str(&format!(
+                r#"<w:{}Reference w:type="{}" r:id="{}"/>"#,
+                kind.name(),
+                scope,
+                id
+            ));
+            scopes_to_replace.push((*kind, scope));
+            package.set_text(&part_name, xml);
+        }
+    }
+
+    // Point the body's section at the new parts, dropping any references they replace
+    let section = body_section(&mut document)?;
+    let mut inner = document[section.clone()].to_string();
+    for (kind, scope) in &scopes_to_replace {
+        let reference = Regex::new(&format!(
+            r#"<w:{}Reference\b[^>]*w:type="{}"[^>]*/>"#,
+            kind.name(),
+            scope
+        ))
+        .expect("valid reference regex");
+        inner = reference.replace_all(&inner, "").into_owned();
+    }
+    inner.insert_str(0, &references);
+    if let Some(different) = options.different_first_page {
+        let title_page = Regex::new(r"<w:titlePg\b[^>]*/>").expect("valid titlePg regex");
+        inner = title_page.replace_all(&inner, "").into_owned();
+        if different {
+            // w:titlePg comes before these in the section schema
+            let position = ["<w:textDirection", "<w:bidi", "<w:rtlGutter", "<w:docGrid"]
+                .iter()
+                .filter_map(|tag| inner.find(tag))
+                .min()
+                .unwrap_or(inner.len());
+            inner.insert_str(position, "<w:titlePg/>");
+        }
+    }
+    document.replace_range(section, &inner);
+
+    if !document.contains("xmlns:r=") {
+        if let Some(start) = document.find("<w:document") {
+            let insert_at = start + "<w:document".len();
+            document.insert_str(insert_at, &format!(r#" xmlns:r="{}""#, REL_NS));
+        }
+    }
+
+    package.set_text(DOCUMENT_PART, document);
+    package.set_text(DOCUMENT_RELS_PART, rels);
+    package.set_text(CONTENT_TYPES_PART, content_types);
+    package.write()
+}
+
+/// Plain text of a header or footer part. Page number fields are shown as `{PAGE}` and
+/// `{NUMPAGES}` instead of their last computed value.
+fn part_text(xml: &str) -> String {
+    let paragraph = Regex::new(r"(?s)<w:p\b[^>]*/>|<w:p\b[^>]*>.*?</w:p>").expect("valid regex");
+    let token = Regex::new(
+        r#"(?s)<w:fldSimple\b([^>]*?)(?:/>|>.*?</w:fldSimple>)|<w:fldChar\b([^>]*)/>|<w:instrText\b[^>]*>([^<]*)</w:instrText>|<w:t(?:\s[^>]*)?>([^<]*)</w:t>|<w:tab/>"#,
+    )
+    .expect("valid regex");
+    let field_name = |instr: &str| {
+        let name = instr.split_whitespace().next().unwrap_or_default();
+        format!("{{{}}}", name.to_uppercase())
+    };
+
+    let mut lines = Vec::new();
+    for para in paragraph.find_iter(xml) {
+        let mut line = String::new();
+        // Complex fields: collect the instruction, then skip the cached result
+        let mut instruction: Option<String> = None;
+        let mut in_result = false;
+        for caps in token.captures_iter(para.as_str()) {
+            if let Some(attrs) = caps.get(1) {
+                line.push_str(&field_name(&unescape_xml(
+                    attr(attrs.as_str(), "w:instr").unwrap_or_default(),
+                )));
+            } else if let Some(attrs) = caps.get(2) {
+                match attr(attrs.as_str(), "w:fldCharType") {
+                    Some("begin") => instruction = Some(String::new()),
+                    Some("separate") => {
+                        if let Some(instr) = instruction.take() {
+                            line.push_str(&field_name(&instr));
+                        }
+                        in_result = true;
+                    }
+                    Some("end") => {
+                        if let Some(instr) = instruction.take() {
+                            line.push_str(&field_name(&instr));
+                        }
+                        in_result = false;
+                    }
+                    _ => {}
+                }
+            } else if let Some(instr) = caps.get(3) {
+                if let Some(current) = instruction.as_mut() {
+                    current.push_str(&unescape_xml(instr.as_str()));
+                }
+            } else if let Some(text) = caps.get(4) {
+                if !in_result && instruction.is_none() {
+                    line.push_str(&unescape_xml(text.as_str()));
+                }
+            } else if !in_result {
+                line.push('\t');
+            }
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// Headers and footers referenced from the document's sections, in document order
+pub fn read_headers_footers(bytes: &[u8]) -> Result<Vec<HeaderFooterText>, ErrorData> {
+    let package = Package::read(bytes)?;
+    let Some(document) = package.text(DOCUMENT_PART)? else {
+        return Ok(Vec::new());
+    };
+    let rels = package.text(DOCUMENT_RELS_PART)?.unwrap_or_default();
+
+    let relationship = Regex::new(r"<Relationship\b[^>]*>").expect("valid regex");
+    let targets: Vec<(String, String)> = relationship
+        .find_iter(&rels)
+        .filter_map(|m| {
+            let id = attr(m.as_str(), "Id")?;
+            let target = attr(m.as_str(), "Target")?;
+            Some((id.to_string(), target.to_string()))
+        })
+        .collect();
+
+    let reference = Regex::new(r"<w:(header|footer)Reference\b([^>]*)/>").expect("valid regex");
+    let mut found: Vec<HeaderFooterText> = Vec::new();
+    for caps in reference.captures_iter(&document) {
+        let kind = if &caps[1] == "header" {
+            PartKind::Header
+        } else {
+            PartKind::Footer
+        };
+        let attrs = &caps[2];
+        let scope = attr(attrs, "w:type").unwrap_or("default").to_string();
+        let Some(id) = attr(attrs, "r:id") else {
+            continue;
+        };
+        let Some((_, target)) = targets.iter().find(|(rel_id, _)| rel_id == id) else {
+            continue;
+        };
+        let part_name = match target.strip_prefix('/') {
+            Some(absolute) => absolute.to_string(),
+            None => format!("word/{}", target),
+        };
+        let Some(xml) = package.text(&part_name)? else {
+            continue;
+        };
+        let entry = HeaderFooterText {
+            kind,
+            scope,
+            text: part_text(&xml),
+        };
+        if !found.contains(&entry) {
+            found.push(entry);
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use docx_rs::{Docx, Paragraph, Run};
+
+    fn sample_docx() -> Vec<u8> {
+        let mut buf = Vec::new();
+        Docx::new()
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Body text")))
+            .build()
+            .pack(&mut Cursor::new(&mut buf))
+            .unwrap();
+        buf
+    }
+
+    fn part(bytes: &[u8], name: &str) -> Option<String> {
+        Package::read(bytes).unwrap().text(name).unwrap()
+    }
+
+    #[test]
+    fn test_set_header_footer_with_page_numbers() {
+        let options = HeaderFooterOptions {
+            header_text: Some("Quarterly Report & Summary".to_string()),
+            footer_text: Some("Confidential".to_string()),
+            alignment: Some("right".to_string()),
+            page_numbers: true,
+            different_first_page: None,
+        };
+        let bytes = set_header_footer(&sample_docx(), &options).unwrap();
+
+        let document = part(&bytes, DOCUMENT_PART).unwrap();
+        assert!(document.contains(r#"<w:headerReference w:type="default""#));
+        assert!(document.contains(r#"<w:footerReference w:type="default""#));
+        assert!(!document.contains("<w:titlePg/>"));
+        assert!(document.contains("Body text"));
+
+        let types = part(&bytes, CONTENT_TYPES_PART).unwrap();
+        assert!(types.contains(r#"PartName="/word/header1.xml""#));
+        assert!(types.contains(r#"PartName="/word/footer1.xml""#));
+
+        let footer = part(&bytes, "word/footer1.xml").unwrap();
+        assert!(footer.contains(r#"w:instr=" PAGE ""#));
+        assert!(footer.contains(r#"w:instr=" NUMPAGES ""#));
+        assert!(footer.contains(r#"<w:jc w:val="right"/>"#));
+
+        let found = read_headers_footers(&bytes).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].label(), "Header");
+        assert_eq!(found[0].text, "Quarterly Report & Summary");
+        assert_eq!(found[1].label(), "Footer");
+        assert_eq!(found[1].text, "Confidential\nPage {PAGE} of {NUMPAGES}");
+    }
+
+    #[test]
+    fn test_set_header_footer_replaces_existing_and_first_page() {
+        let first = HeaderFooterOptions {
+            header_text: Some("Draft".to_string()),
+            ..Default::default()
+        };
+        let bytes = set_header_footer(&sample_docx(), &first).unwrap();
+        let second = HeaderFooterOptions {
+            header_text: Some("Final".to_string()),
+            different_first_page: Some(true),
+            ..Default::default()
+        };
+        let bytes = set_header_footer(&bytes, &second).unwrap();
+
+        let document = part(&bytes, DOCUMENT_PART).unwrap();
+        assert!(document.contains("<w:titlePg/>"));
+        assert_eq!(document.matches("<w:headerReference").count(), 2);
+        assert!(document.contains(r#"<w:headerReference w:type="first""#));
+
+        let found = read_headers_footers(&bytes).unwrap();
+        let labels: Vec<_> = found.iter().map(|h| (h.label(), h.text.as_str())).collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("Header".to_string(), "Final"),
+                ("Header (first page)".to_string(), "")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_header_footer_requires_content() {
+        let err = set_header_footer(&sample_docx(), &HeaderFooterOptions::default()).unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_part_text_reads_complex_fields() {
+        let xml = r#"<w:ftr><w:p><w:r><w:t xml:space="preserve">Page </w:t></w:r><w:r><w:fldChar w:fldCharType="begin"/></w:r><w:r><w:instrText xml:space="preserve"> PAGE \* MERGEFORMAT </w:instrText></w:r><w:r><w:fldChar w:fldCharType="separate"/></w:r><w:r><w:t>3</w:t></w:r><w:r><w:fldChar w:fldCharType="end"/></w:r></w:p></w:ftr>"#;
+        assert_eq!(part_text(xml), "Page {PAGE}");
+    }
+}
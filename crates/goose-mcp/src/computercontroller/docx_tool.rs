@@ -1,7 +1,10 @@
+use super::docx_headers::{self, HeaderFooterOptions};
 use docx_rs::*;
 use image::{self, ImageFormat};
 use rmcp::model::{Content, ErrorCode, ErrorData};
 use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use std::{fs, io::Cursor};
 
 #[derive(Debug)]
@@ -159,7 +162,7 @@ pub async fn docx_tool(
                 }
             }
 
-            let result = if !structure.is_empty() {
+            let mut result = if !structure.is_empty() {
                 format!(
                     "Document Structure:\n{}\n\nFull Text:\n{}",
                     structure.join("\n"),
@@ -169,10 +172,29 @@ pub async fn docx_tool(
                 format!("Extracted Text:\n{}", text)
             };
 
+            let headers_footers = docx_headers::read_headers_footers(&file)?;
+            if !headers_footers.is_empty() {
+                result.push_str("\nHeaders and Footers:\n");
+                for entry in headers_footers {
+                    let text = if entry.text.trim().is_empty() {
+                        "(empty)".to_string()
+                    } else {
+                        entry.text.replace('\n', "\n  ")
+                    };
+                    result.push_str(&format!("{}: {}\n", entry.label(), text));
+                }
+            }
+
             Ok(vec![Content::text(result)])
         }
 
         "update_doc" => {
+            if let Some(params) =
+                params.filter(|p| p.get("mode").and_then(|v| v.as_str()) == Some("set_header_footer"))
+            {
+                return set_header_footer(path, params);
+            }
+
             let content = content.ok_or_else(|| ErrorData {
                 code: ErrorCode::INVALID_PARAMS,
                 message: Cow::from("Content parameter required for update_doc"),
@@ -241,7 +263,7 @@ pub async fn docx_tool(
                     }
                     _ => return Err(ErrorData {
                     code: ErrorCode::INVALID_PARAMS,
-                    message: Cow::from("Invalid mode. Must be 'append', 'replace', 'structured', or 'add_image'"),
+                    message: Cow::from("Invalid mode. Must be 'append', 'replace', 'structured', 'add_image', or 'set_header_footer'"),
                     data: None,
                 }),
                 };
@@ -594,7 +616,7 @@ pub async fn docx_tool(
         _ => Err(ErrorData {
             code: ErrorCode::INVALID_PARAMS,
             message: Cow::from(format!(
-                "Invalid operation: {}. Valid operations are: 'extract_text', 'update_doc'",
+                "Invalid operation: {}. Valid operations are: 'extract_text', 'update_doc', 'to_pdf'",
                 operation
             )),
             data: None,
@@ -602,6 +624,196 @@ pub async fn docx_tool(
     }
 }
 
+/// Set the header and footer of the DOCX at `path`, creating an empty document if it is missing
+fn set_header_footer(path: &str, params: &serde_json::Value) -> Result<Vec<Content>, ErrorData> {
+    let alignment = match params.get("alignment").and_then(|v| v.as_str()) {
+        None => None,
+        Some("left") => Some("left"),
+        Some("center") => Some("center"),
+        Some("right") => Some("right"),
+        Some("justified") => Some("both"),
+        Some(_) => {
+            return Err(ErrorData {
+                code: ErrorCode::INVALID_PARAMS,
+                message: Cow::from(
+                    "Invalid alignment. Must be 'left', 'center', 'right', or 'justified'",
+                ),
+                data: None,
+            })
+        }
+    };
+    let options = HeaderFooterOptions {
+        header_text: params
+            .get("header_text")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        footer_text: params
+            .get("footer_text")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        alignment: alignment.map(String::from),
+        page_numbers: params
+            .get("page_numbers")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        different_first_page: params.get("different_first_page").and_then(|v| v.as_bool()),
+    };
+
+    let file = if Path::new(path).exists() {
+        fs::read(path).map_err(|e| ErrorData {
+            code: ErrorCode::INTERNAL_ERROR,
+            message: Cow::from(format!("Failed to read DOCX file: {}", e)),
+            data: None,
+        })?
+    } else {
+        let mut buf = Vec::new();
+        let mut cursor = Cursor::new(&mut buf);
+        Docx::new()
+            .build()
+            .pack(&mut cursor)
+            .map_err(|e| ErrorData {
+                code: ErrorCode::INTERNAL_ERROR,
+                message: Cow::from(format!("Failed to build DOCX: {}", e)),
+                data: None,
+            })?;
+        buf
+    };
+
+    let updated = docx_headers::set_header_footer(&file, &options)?;
+    fs::write(path, &updated).map_err(|e| ErrorData {
+        code: ErrorCode::INTERNAL_ERROR,
+        message: Cow::from(format!("Failed to write DOCX file: {}", e)),
+        data: None,
+    })?;
+
+    let mut parts = Vec::new();
+    if options.header_text.is_some() {
+        parts.push("header");
+    }
+    if options.footer_text.is_some() {
+        parts.push("footer");
+    }
+    if options.page_numbers {
+        parts.push("page numbers");
+    }
+    let mut message = format!("Successfully set {} in {}", parts.join(" and "), path);
+    match options.different_first_page {
+        Some(true) => message.push_str("; the first page is left blank"),
+        Some(false) => message.push_str("; the first page uses the same header and footer"),
+        None => {}
+    }
+    Ok(vec![Content::text(message)])
+}
+
+/// LibreOffice commands that can convert DOCX to PDF when installed on PATH
+const PDF_CONVERTER_COMMANDS: [&str; 2] = ["soffice", "libreoffice"];
+/// Default LibreOffice install locations that are not normally on PATH
+const PDF_CONVERTER_PATHS: [&str; 2] = [
+    "/Applications/LibreOffice.app/Contents/MacOS/soffice",
+    r"C:\Program Files\LibreOffice\program\soffice.exe",
+];
+const PDF_CONVERSION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Find a LibreOffice binary to render DOCX files to PDF
+pub fn find_pdf_converter() -> Option<PathBuf> {
+    PDF_CONVERTER_COMMANDS
+        .iter()
+        .find_map(|command| which::which(command).ok())
+        .or_else(|| {
+            PDF_CONVERTER_PATHS
+                .iter()
+                .map(PathBuf::from)
+                .find(|path| path.is_file())
+        })
+}
+
+fn conversion_error(message: String) -> ErrorData {
+    ErrorData {
+        code: ErrorCode::INTERNAL_ERROR,
+        message: Cow::from(message),
+        data: None,
+    }
+}
+
+/// Render the DOCX at `path` to a PDF at `output` using LibreOffice (`converter`), which keeps
+/// the headings, images and layout of the document.
+pub async fn docx_to_pdf(
+    path: &str,
+    output: &Path,
+    converter: Option<&Path>,
+) -> Result<Vec<Content>, ErrorData> {
+    let file =
+        fs::read(path).map_err(|e| conversion_error(format!("Failed to read DOCX file: {}", e)))?;
+    read_docx(&file).map_err(|e| conversion_error(format!("Failed to parse DOCX file: {}", e)))?;
+
+    let converter = converter.ok_or_else(|| {
+        conversion_error(
+            "Converting DOCX to PDF requires LibreOffice, which was not found. Install it \
+             (e.g. 'brew install --cask libreoffice' or 'apt install libreoffice-writer') and \
+             make sure 'soffice' is on PATH."
+                .to_string(),
+        )
+    })?;
+
+    // A private profile lets the conversion run while LibreOffice is open elsewhere
+    let work_dir = tempfile::tempdir()
+        .map_err(|e| conversion_error(format!("Failed to create temp directory: {}", e)))?;
+    let profile_dir = work_dir.path().join("profile");
+    let out_dir = work_dir.path().join("out");
+    let profile_url = url::Url::from_directory_path(&profile_dir)
+        .map_err(|_| conversion_error("Invalid temp directory".to_string()))?;
+
+    let mut command = tokio::process::Command::new(converter);
+    command
+        .arg("--headless")
+        .arg("--norestore")
+        .arg(format!("-env:UserInstallation={}", profile_url))
+        .arg("--convert-to")
+        .arg("pdf")
+        .arg("--outdir")
+        .arg(&out_dir)
+        .arg(path)
+        .kill_on_drop(true);
+
+    let result = tokio::time::timeout(PDF_CONVERSION_TIMEOUT, command.output())
+        .await
+        .map_err(|_| {
+            conversion_error(format!(
+                "PDF conversion timed out after {} seconds",
+                PDF_CONVERSION_TIMEOUT.as_secs()
+            ))
+        })?
+        .map_err(|e| {
+            conversion_error(format!(
+                "Failed to run PDF converter {}: {}",
+                converter.display(),
+                e
+            ))
+        })?;
+
+    let stem = Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let converted = out_dir.join(format!("{}.pdf", stem));
+    if !result.status.success() || !converted.is_file() {
+        return Err(conversion_error(format!(
+            "PDF converter failed ({}): {}",
+            result.status,
+            String::from_utf8_lossy(&result.stderr).trim()
+        )));
+    }
+
+    fs::copy(&converted, output)
+        .map_err(|e| conversion_error(format!("Failed to write PDF file: {}", e)))?;
+
+    Ok(vec![Content::text(format!(
+        "Converted {} to PDF: {}",
+        path,
+        output.display()
+    ))])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -818,6 +1030,71 @@ mod tests {
         fs::remove_file(test_image_path).unwrap();
     }
 
+    #[tokio::test]
+    async fn test_docx_set_header_footer() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let test_output_path = temp_dir.path().join("header_footer.docx");
+        let path = test_output_path.to_str().unwrap();
+
+        docx_tool(path, "update_doc", Some("Body paragraph"), None)
+            .await
+            .unwrap();
+
+        let params = json!({
+            "mode": "set_header_footer",
+            "header_text": "Project Plan",
+            "footer_text": "Internal use only",
+            "alignment": "right",
+            "page_numbers": true,
+            "different_first_page": true
+        });
+        let result = docx_tool(path, "update_doc", None, Some(&params))
+            .await
+            .unwrap();
+        let message = result[0].as_text().unwrap();
+        assert!(message.text.contains("header and footer and page numbers"));
+
+        // Re-open the package and check the new parts
+        let bytes = fs::read(&test_output_path).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut read_part = |name: &str| {
+            let mut xml = String::new();
+            std::io::Read::read_to_string(&mut archive.by_name(name).unwrap(), &mut xml).unwrap();
+            xml
+        };
+        assert!(read_part("word/header1.xml").contains("Project Plan"));
+        let footer = read_part("word/footer1.xml");
+        assert!(footer.contains("Internal use only"));
+        assert!(footer.contains(r#"w:instr=" NUMPAGES ""#));
+        assert!(read_part("word/document.xml").contains("<w:titlePg/>"));
+
+        let result = docx_tool(path, "extract_text", None, None).await.unwrap();
+        let text = &result[0].as_text().unwrap().text;
+        assert!(text.contains("Body paragraph"));
+        assert!(text.contains("Headers and Footers:"));
+        assert!(text.contains("Header: Project Plan"));
+        assert!(text.contains("Footer: Internal use only\n  Page {PAGE} of {NUMPAGES}"));
+        assert!(text.contains("Header (first page): (empty)"));
+    }
+
+    #[tokio::test]
+    async fn test_docx_set_header_footer_without_text() {
+        let test_docx_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/sample.docx");
+        let params = json!({"mode": "set_header_footer"});
+
+        let err = docx_tool(
+            test_docx_path.to_str().unwrap(),
+            "update_doc",
+            None,
+            Some(&params),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+    }
+
     #[tokio::test]
     async fn test_docx_invalid_path() {
         let result = docx_tool("nonexistent.docx", "extract_text", None, None).await;
@@ -924,4 +1201,54 @@ mod tests {
         // Clean up
         fs::remove_file(test_output_path).unwrap();
     }
+
+    #[tokio::test]
+    async fn test_docx_to_pdf_without_converter() {
+        let test_docx_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/sample.docx");
+        let output = tempfile::tempdir().unwrap();
+
+        let err = docx_to_pdf(
+            test_docx_path.to_str().unwrap(),
+            &output.path().join("sample.pdf"),
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.message.contains("requires LibreOffice"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_docx_to_pdf_runs_converter() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let test_docx_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/sample.docx");
+        let dir = tempfile::tempdir().unwrap();
+
+        // Stands in for soffice: writes <outdir>/<stem>.pdf like the real converter
+        let converter = dir.path().join("soffice");
+        fs::write(
+            &converter,
+            "#!/bin/sh\n\
+             while [ \"$1\" != \"--outdir\" ]; do shift; done\n\
+             mkdir -p \"$2\"\n\
+             printf '%%PDF-1.4' > \"$2/$(basename \"$3\" .docx).pdf\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&converter, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let output = dir.path().join("sample.pdf");
+        let result = docx_to_pdf(test_docx_path.to_str().unwrap(), &output, Some(&converter))
+            .await
+            .unwrap();
+        assert!(result[0].as_text().unwrap().text.contains("sample.pdf"));
+        assert_eq!(fs::read(&output).unwrap(), b"%PDF-1.4");
+
+        let err = docx_to_pdf("nonexistent.docx", &output, Some(&converter))
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("Failed to read DOCX file"));
+    }
 }
@@ -0,0 +1,414 @@
+//! Inspect, resize, convert and crop images with the image crate, so none of this depends on
+//! ImageMagick being installed.
+
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ImageError, ImageFormat, ImageReader};
+use rmcp::model::{Content, ErrorCode, ErrorData};
+use std::fs::File;
+use std::io::{BufReader, Cursor};
+use std::path::Path;
+
+/// Formats that can be read, listed in errors for anything else (such as HEIC)
+pub const SUPPORTED_FORMATS: &str = "png, jpeg, webp, gif, bmp and tiff";
+const READABLE_FORMATS: [ImageFormat; 6] = [
+    ImageFormat::Png,
+    ImageFormat::Jpeg,
+    ImageFormat::WebP,
+    ImageFormat::Gif,
+    ImageFormat::Bmp,
+    ImageFormat::Tiff,
+];
+
+/// Results at most this large in both dimensions are returned as a thumbnail as well
+const THUMBNAIL_MAX_SOURCE: u32 = 1024;
+const THUMBNAIL_SIZE: u32 = 256;
+const DEFAULT_JPEG_QUALITY: u8 = 85;
+
+const EXIF_FIELDS: [(exif::Tag, &str); 9] = [
+    (exif::Tag::Make, "Camera make"),
+    (exif::Tag::Model, "Camera model"),
+    (exif::Tag::LensModel, "Lens"),
+    (exif::Tag::DateTimeOriginal, "Taken"),
+    (exif::Tag::Orientation, "Orientation"),
+    (exif::Tag::ExposureTime, "Exposure"),
+    (exif::Tag::FNumber, "Aperture"),
+    (exif::Tag::PhotographicSensitivity, "ISO"),
+    (exif::Tag::FocalLength, "Focal length"),
+];
+
+/// Formats images can be written as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Png,
+    Jpeg,
+    WebP,
+}
+
+impl OutputFormat {
+    pub fn from_name(name: &str) -> Result<Self, ErrorData> {
+        match name.trim().trim_start_matches('.').to_lowercase().as_str() {
+            "png" => Ok(OutputFormat::Png),
+            "jpg" | "jpeg" => Ok(OutputFormat::Jpeg),
+            "webp" => Ok(OutputFormat::WebP),
+            other => Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "Unsupported output format '{}'. Images can be written as png, jpeg or webp",
+                    other
+                ),
+                None,
+            )),
+        }
+    }
+
+    /// The output format matching a decoded image, falling back to PNG for formats that can
+    /// only be read
+    pub fn matching(format: ImageFormat) -> Self {
+        match format {
+            ImageFormat::Jpeg => OutputFormat::Jpeg,
+            ImageFormat::WebP => OutputFormat::WebP,
+            _ => OutputFormat::Png,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::WebP => "webp",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "image/png",
+            OutputFormat::Jpeg => "image/jpeg",
+            OutputFormat::WebP => "image/webp",
+        }
+    }
+}
+
+fn internal_error(context: &str, e: impl std::fmt::Display) -> ErrorData {
+    ErrorData::new(
+        ErrorCode::INTERNAL_ERROR,
+        format!("{}: {}", context, e),
+        None,
+    )
+}
+
+fn unsupported(path: &Path) -> ErrorData {
+    ErrorData::new(
+        ErrorCode::INVALID_PARAMS,
+        format!(
+            "Unsupported image format for {}. Supported formats are {}",
+            path.display(),
+            SUPPORTED_FORMATS
+        ),
+        None,
+    )
+}
+
+/// Decode the image at `path`, detecting its format from the contents
+pub fn open_image(path: &Path) -> Result<(DynamicImage, ImageFormat), ErrorData> {
+    let reader = ImageReader::open(path)
+        .map_err(|e| internal_error(&format!("Failed to open {}", path.display()), e))?
+        .with_guessed_format()
+        .map_err(|e| internal_error(&format!("Failed to read {}", path.display()), e))?;
+    let format = reader
+        .format()
+        .filter(|format| READABLE_FORMATS.contains(format))
+        .ok_or_else(|| unsupported(path))?;
+    let image = reader.decode().map_err(|e| match e {
+        ImageError::Unsupported(_) => unsupported(path),
+        e => internal_error(&format!("Failed to decode {}", path.display()), e),
+    })?;
+    Ok((image, format))
+}
+
+/// The EXIF fields worth showing, as (label, value) pairs. Images without EXIF data have none.
+pub fn exif_summary(path: &Path) -> Vec<(&'static str, String)> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    let Ok(exif) = exif::Reader::new().read_from_container(&mut BufReader::new(file)) else {
+        return Vec::new();
+    };
+    EXIF_FIELDS
+        .iter()
+        .filter_map(|(tag, label)| {
+            let field = exif.get_field(*tag, exif::In::PRIMARY)?;
+            let value = field.display_value().with_unit(&exif).to_string();
+            let value = value.trim_matches('"').trim().to_string();
+            (!value.is_empty()).then_some((*label, value))
+        })
+        .collect()
+}
+
+pub fn describe(path: &Path, image: &DynamicImage, format: ImageFormat) -> String {
+    let mut lines = vec![
+        format!("Image: {}", path.display()),
+        format!("Format: {}", format.to_mime_type()),
+        format!("Dimensions: {}x{}", image.width(), image.height()),
+        format!("Color type: {:?}", image.color()),
+    ];
+    if let Ok(metadata) = path.metadata() {
+        lines.push(format!("File size: {} bytes", metadata.len()));
+    }
+
+    let exif = exif_summary(path);
+    if exif.is_empty() {
+        lines.push("EXIF: none".to_string());
+    } else {
+        lines.push("EXIF:".to_string());
+        lines.extend(
+            exif.into_iter()
+                .map(|(label, value)| format!("  {}: {}", label, value)),
+        );
+    }
+    lines.join("\n")
+}
+
+/// Resize to a percentage of the original size, or to fit `width` and/or `height`. The aspect
+/// ratio is always preserved; when only one dimension is given the other follows from it.
+pub fn resize(
+    image: &DynamicImage,
+    width: Option<u32>,
+    height: Option<u32>,
+    percent: Option<f32>,
+) -> Result<DynamicImage, ErrorData> {
+    let (source_width, source_height) = (image.width(), image.height());
+    let scaled = |size: u32, scale: f64| ((size as f64 * scale).round() as u32).max(1);
+
+    let (target_width, target_height) = match (width, height, percent) {
+        (_, _, Some(percent)) if percent > 0.0 => {
+            let scale = percent as f64 / 100.0;
+            (scaled(source_width, scale), scaled(source_height, scale))
+        }
+        (_, _, Some(_)) => {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "percent must be greater than 0".to_string(),
+                None,
+            ))
+        }
+        (Some(0), _, _) | (_, Some(0), _) => {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "width and height must be greater than 0".to_string(),
+                None,
+            ))
+        }
+        (Some(width), Some(height), None) => {
+            let scale =
+                (width as f64 / source_width as f64).min(height as f64 / source_height as f64);
+            (scaled(source_width, scale), scaled(source_height, scale))
+        }
+        (Some(width), None, None) => (
+            width,
+            scaled(source_height, width as f64 / source_width as f64),
+        ),
+        (None, Some(height), None) => (
+            scaled(source_width, height as f64 / source_height as f64),
+            height,
+        ),
+        (None, None, None) => {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "Resize requires width, height or percent".to_string(),
+                None,
+            ))
+        }
+    };
+
+    Ok(image.resize_exact(
+        target_width,
+        target_height,
+        image::imageops::FilterType::Lanczos3,
+    ))
+}
+
+/// Crop the `width` x `height` region whose top left corner is at (`x`, `y`)
+pub fn crop(
+    image: &DynamicImage,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<DynamicImage, ErrorData> {
+    let fits = width > 0
+        && height > 0
+        && x.checked_add(width)
+            .is_some_and(|right| right <= image.width())
+        && y.checked_add(height)
+            .is_some_and(|bottom| bottom <= image.height());
+    if !fits {
+        return Err(ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            format!(
+                "Crop region {}x{} at ({}, {}) does not fit inside the {}x{} image",
+                width,
+                height,
+                x,
+                y,
+                image.width(),
+                image.height()
+            ),
+            None,
+        ));
+    }
+    Ok(image.crop_imm(x, y, width, height))
+}
+
+/// Encode `image` as `format`. `quality` (1-100) applies to JPEG; WebP is always written
+/// losslessly.
+pub fn encode(
+    image: &DynamicImage,
+    format: OutputFormat,
+    quality: Option<u8>,
+) -> Result<Vec<u8>, ErrorData> {
+    let mut bytes = Vec::new();
+    let result = match format {
+        OutputFormat::Png => image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png),
+        OutputFormat::Jpeg => {
+            let quality = quality.unwrap_or(DEFAULT_JPEG_QUALITY).clamp(1, 100);
+            // JPEG has no alpha channel
+            let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+            rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, quality))
+        }
+        OutputFormat::WebP => {
+            let rgba = DynamicImage::ImageRgba8(image.to_rgba8());
+            rgba.write_with_encoder(WebPEncoder::new_lossless(&mut bytes))
+        }
+    };
+    result.map_err(|e| internal_error("Failed to encode image", e))?;
+    Ok(bytes)
+}
+
+/// A PNG preview of `image` as image content, when the image is small enough to be worth
+/// showing inline
+pub fn thumbnail(image: &DynamicImage) -> Result<Option<Content>, ErrorData> {
+    if image.width() > THUMBNAIL_MAX_SOURCE || image.height() > THUMBNAIL_MAX_SOURCE {
+        return Ok(None);
+    }
+    let preview = if image.width() > THUMBNAIL_SIZE || image.height() > THUMBNAIL_SIZE {
+        image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+    } else {
+        image.clone()
+    };
+    let bytes = encode(&preview, OutputFormat::Png, None)?;
+    Ok(Some(Content::image(
+        base64::prelude::BASE64_STANDARD.encode(bytes),
+        "image/png",
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/images")
+            .join(name)
+    }
+
+    #[test]
+    fn test_open_detects_format_from_contents() {
+        let (image, format) = open_image(&fixture("sample.png")).unwrap();
+        assert_eq!(format, ImageFormat::Png);
+        assert_eq!((image.width(), image.height()), (64, 48));
+
+        let (image, format) = open_image(&fixture("photo.jpg")).unwrap();
+        assert_eq!(format, ImageFormat::Jpeg);
+        assert_eq!((image.width(), image.height()), (40, 30));
+    }
+
+    #[test]
+    fn test_unsupported_format_lists_supported_ones() {
+        let err = open_image(&fixture("sample.heic")).unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        assert!(err.message.contains("Unsupported image format"));
+        assert!(err.message.contains(SUPPORTED_FORMATS));
+    }
+
+    #[test]
+    fn test_exif_summary() {
+        let exif = exif_summary(&fixture("photo.jpg"));
+        assert!(exif.contains(&("Camera make", "Goose Optics".to_string())));
+        assert!(exif.contains(&("Camera model", "Honk 1".to_string())));
+        assert!(exif.contains(&("Taken", "2024-05-01 12:30:00".to_string())));
+        assert!(exif_summary(&fixture("sample.png")).is_empty());
+    }
+
+    #[test]
+    fn test_resize_preserves_aspect_ratio() {
+        let (image, _) = open_image(&fixture("sample.png")).unwrap();
+
+        let resized = resize(&image, Some(32), None, None).unwrap();
+        assert_eq!((resized.width(), resized.height()), (32, 24));
+
+        let resized = resize(&image, None, Some(12), None).unwrap();
+        assert_eq!((resized.width(), resized.height()), (16, 12));
+
+        // Fits inside both bounds
+        let resized = resize(&image, Some(32), Some(32), None).unwrap();
+        assert_eq!((resized.width(), resized.height()), (32, 24));
+
+        let resized = resize(&image, None, None, Some(50.0)).unwrap();
+        assert_eq!((resized.width(), resized.height()), (32, 24));
+
+        assert!(resize(&image, None, None, None).is_err());
+        assert!(resize(&image, None, None, Some(0.0)).is_err());
+    }
+
+    #[test]
+    fn test_crop_checks_bounds() {
+        let (image, _) = open_image(&fixture("sample.png")).unwrap();
+
+        let cropped = crop(&image, 8, 4, 16, 10).unwrap();
+        assert_eq!((cropped.width(), cropped.height()), (16, 10));
+        assert_eq!(
+            cropped.to_rgb8().get_pixel(0, 0),
+            image.to_rgb8().get_pixel(8, 4)
+        );
+
+        let err = crop(&image, 60, 0, 16, 10).unwrap_err();
+        assert!(err.message.contains("does not fit inside the 64x48 image"));
+    }
+
+    #[test]
+    fn test_encode_round_trips() {
+        let (image, _) = open_image(&fixture("sample.png")).unwrap();
+
+        for (format, expected) in [
+            (OutputFormat::Png, ImageFormat::Png),
+            (OutputFormat::Jpeg, ImageFormat::Jpeg),
+            (OutputFormat::WebP, ImageFormat::WebP),
+        ] {
+            let bytes = encode(&image, format, Some(60)).unwrap();
+            assert_eq!(image::guess_format(&bytes).unwrap(), expected);
+            let decoded = image::load_from_memory(&bytes).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (64, 48));
+        }
+
+        // Lower quality JPEGs are smaller
+        let low = encode(&image, OutputFormat::Jpeg, Some(10)).unwrap();
+        let high = encode(&image, OutputFormat::Jpeg, Some(95)).unwrap();
+        assert!(low.len() < high.len());
+
+        assert!(OutputFormat::from_name("heic").is_err());
+        assert_eq!(OutputFormat::from_name("JPG").unwrap(), OutputFormat::Jpeg);
+    }
+
+    #[test]
+    fn test_thumbnail_only_for_small_images() {
+        let (image, _) = open_image(&fixture("sample.png")).unwrap();
+        assert!(thumbnail(&image).unwrap().is_some());
+
+        let large = DynamicImage::new_rgb8(THUMBNAIL_MAX_SOURCE + 1, 10);
+        assert!(thumbnail(&large).unwrap().is_none());
+    }
+}
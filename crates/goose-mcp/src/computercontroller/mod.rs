@@ -1,4 +1,5 @@
 use etcetera::{choose_app_strategy, AppStrategy};
+use goose::config::Config;
 use indoc::{formatdoc, indoc};
 use reqwest::{Client, Url};
 use rmcp::{
@@ -13,19 +14,49 @@ use rmcp::{
     tool, tool_handler, tool_router, RoleServer, ServerHandler,
 };
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fs, path::PathBuf, sync::Arc, sync::Mutex};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    sync::Mutex,
+};
 use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 
+mod archive_tool;
+mod calendar_tool;
+mod docx_headers;
 mod docx_tool;
+mod image_tool;
+mod pdf_forms;
+mod pdf_tables;
 mod pdf_tool;
+mod scrape_sessions;
+mod sqlite_tool;
+mod url_policy;
+mod web_client;
 mod xlsx_tool;
 
 mod platform;
 use platform::{create_system_automation, SystemAutomation};
 
+const MAX_BATCH_URLS: usize = 20;
+const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+const MAX_BATCH_CONCURRENCY: usize = 8;
+
+/// Set to `project` to keep cached files under the working directory, or `global` to use the
+/// shared cache directory even when GOOSE_WORKING_DIR is set
+pub const CACHE_SCOPE_ENV: &str = "GOOSE_CC_CACHE_SCOPE";
+
+/// Comma-separated (or JSON list of) tool names to leave out of this extension, e.g.
+/// `computer_control,automation_script` on machines where desktop automation is not allowed
+pub const DISABLED_TOOLS_KEY: &str = "GOOSE_CC_DISABLED_TOOLS";
+
 /// Enum for save_as parameter in web_scrape tool
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(rename_all = "lowercase")]
@@ -47,6 +78,30 @@ pub struct WebScrapeParams {
     /// How to interpret and save the content
     #[serde(default)]
     pub save_as: SaveAsFormat,
+    /// Name of a scraping session whose cookies are sent and updated, so sequential scrapes
+    /// can stay logged in
+    pub session_id: Option<String>,
+}
+
+/// Parameters for the web_scrape_clear_session tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WebScrapeClearSessionParams {
+    /// The session to clear; clears every session when omitted
+    pub session_id: Option<String>,
+}
+
+/// Parameters for the web_scrape_batch tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WebScrapeBatchParams {
+    /// The URLs to fetch content from
+    pub urls: Vec<String>,
+    /// How to interpret and save the content of every URL
+    #[serde(default)]
+    pub save_as: SaveAsFormat,
+    /// How many URLs to fetch at once (defaults to 4, at most 8)
+    pub max_concurrency: Option<usize>,
+    /// Name of a scraping session whose cookies are shared by every URL
+    pub session_id: Option<String>,
 }
 
 /// Enum for language parameter in automation_script tool
@@ -88,6 +143,8 @@ pub struct AutomationScriptParams {
     /// Whether to save the script output to a file
     #[serde(default)]
     pub save_output: bool,
+    /// Directory to run the script in (defaults to the current directory)
+    pub cwd: Option<String>,
 }
 
 /// Parameters for the computer_control tool
@@ -118,6 +175,14 @@ pub enum PdfOperation {
     ExtractText,
     /// Extract and save embedded images to PNG files
     ExtractImages,
+    /// Detect tables from text positions and return them as rows of cells
+    ExtractTables,
+    /// Read the title, author, dates, page count and encryption status
+    GetMetadata,
+    /// List the form fields with their type, current value and options
+    ListFormFields,
+    /// Fill form fields and save the result to the cache directory
+    FillForm,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -126,6 +191,15 @@ pub struct PdfToolParams {
     pub path: String,
     /// Operation to perform on the PDF
     pub operation: PdfOperation,
+    /// Values to fill in by field name (required for fill_form). Checkboxes take true, false
+    /// or their option label; radio groups and choice fields take an option label.
+    pub fields: Option<HashMap<String, serde_json::Value>>,
+    /// File name for the filled PDF in the cache directory (fill_form, defaults to
+    /// '<name>_filled.pdf')
+    pub output_path: Option<String>,
+    /// Draw the filled fields into the pages and remove the form, so it can no longer be edited
+    #[serde(default)]
+    pub flatten: bool,
 }
 
 /// Enum for operation parameter in docx_tool
@@ -136,6 +210,8 @@ pub enum DocxOperation {
     ExtractText,
     /// Create a new DOCX or update existing one with provided content
     UpdateDoc,
+    /// Render the DOCX to a PDF in the cache directory
+    ToPdf,
 }
 
 /// Enum for update mode in docx_tool params
@@ -151,6 +227,8 @@ pub enum DocxUpdateMode {
     Structured,
     /// Add an image to the document (with optional caption)
     AddImage,
+    /// Set the page header and footer, optionally with page numbers
+    SetHeaderFooter,
 }
 
 /// Enum for text alignment in docx_tool params
@@ -214,6 +292,22 @@ pub struct DocxUpdateParams {
     /// Styling options for the text
     #[serde(skip_serializing_if = "Option::is_none")]
     pub style: Option<DocxTextStyle>,
+    /// Header text for set_header_footer mode, one paragraph per line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header_text: Option<String>,
+    /// Footer text for set_header_footer mode, one paragraph per line. '{PAGE}' and
+    /// '{NUMPAGES}' become page number fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer_text: Option<String>,
+    /// Alignment of the header and footer (set_header_footer mode, default: center)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alignment: Option<TextAlignment>,
+    /// Add 'Page X of Y' to the footer (set_header_footer mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_numbers: Option<bool>,
+    /// Leave the header and footer blank on the first page (set_header_footer mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub different_first_page: Option<bool>,
 }
 
 /// Parameters for the docx_tool
@@ -231,6 +325,26 @@ pub struct DocxToolParams {
     pub params: Option<DocxUpdateParams>,
 }
 
+/// Formatting options for cells in xlsx_tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+pub struct XlsxCellStyle {
+    /// Make text bold
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bold: Option<bool>,
+    /// Make text italic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub italic: Option<bool>,
+    /// Excel number format code (e.g., '0.00%', '$#,##0', 'yyyy-mm-dd')
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_format: Option<String>,
+    /// Font color in hex format (e.g., 'FF0000' for red)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_color: Option<String>,
+    /// Background fill color in hex format (e.g., 'FFFF00' for yellow)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fill_color: Option<String>,
+}
+
 /// Parameters for the xlsx_tool
 /// Enum for operation parameter in xlsx_tool
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
@@ -248,8 +362,14 @@ pub enum XlsxOperation {
     UpdateCell,
     /// Get value and formula from a specific cell
     GetCell,
+    /// Apply formatting to a single cell
+    FormatCell,
+    /// Apply formatting to every cell in a range
+    FormatRange,
     /// Save changes back to the file
     Save,
+    /// Create a new workbook, optionally with named worksheets
+    CreateWorkbook,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -260,19 +380,244 @@ pub struct XlsxToolParams {
     pub operation: XlsxOperation,
     /// Worksheet name (if not provided, uses first worksheet)
     pub worksheet: Option<String>,
-    /// Cell range in A1 notation (e.g., 'A1:C10') for get_range operation
+    /// Cell range in A1 notation (e.g., 'A1:C10') for get_range and format_range operations
     pub range: Option<String>,
     /// Text to search for in find_text operation
     pub search_text: Option<String>,
     /// Whether search should be case-sensitive
     #[serde(default)]
     pub case_sensitive: bool,
-    /// Row number for update_cell and get_cell operations
+    /// Row number for update_cell, get_cell and format_cell operations
     pub row: Option<u64>,
-    /// Column number for update_cell and get_cell operations
+    /// Column number for update_cell, get_cell and format_cell operations
     pub col: Option<u64>,
     /// New value for update_cell operation
     pub value: Option<String>,
+    /// For get_range and get_cell, also report the computed result of formula cells as cached
+    /// in the workbook, or that only the formula is stored
+    #[serde(default)]
+    pub computed: bool,
+    /// Formatting for format_cell and format_range operations
+    pub style: Option<XlsxCellStyle>,
+    /// Worksheet names for create_workbook (defaults to a single 'Sheet1')
+    pub sheets: Option<Vec<String>>,
+    /// Let create_workbook replace an existing file
+    #[serde(default)]
+    pub overwrite: bool,
+    /// For update_cell, create the workbook and worksheet when they don't exist yet
+    #[serde(default)]
+    pub create_if_missing: bool,
+}
+
+/// Enum for operation parameter in archive_tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveOperation {
+    /// List the entries in the archive with their sizes
+    List,
+    /// Extract the archive, or only some of its entries
+    Extract,
+    /// Create a new archive from files and directories
+    Create,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ArchiveToolParams {
+    /// Path to the archive (.zip, .tar.gz or .tgz). For create, where to write the new archive
+    pub path: String,
+    /// Operation to perform on the archive
+    pub operation: ArchiveOperation,
+    /// For extract, only extract these entries or directories; for create, the files and
+    /// directories to add
+    pub paths: Option<Vec<String>>,
+    /// For extract, the directory to extract into (defaults to a new directory in the cache)
+    pub destination: Option<String>,
+}
+
+/// Enum for operation parameter in image_tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageOperation {
+    /// Report dimensions, format and common EXIF fields
+    GetInfo,
+    /// Resize by width, height or percent, keeping the aspect ratio
+    Resize,
+    /// Convert to another format
+    Convert,
+    /// Crop a region out of the image
+    Crop,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ImageToolParams {
+    /// Path to the image
+    pub path: String,
+    /// Operation to perform on the image
+    pub operation: ImageOperation,
+    /// For resize, the target width; for crop, the width of the region
+    pub width: Option<u32>,
+    /// For resize, the target height; for crop, the height of the region
+    pub height: Option<u32>,
+    /// For resize, scale to this percentage of the original size instead
+    pub percent: Option<f32>,
+    /// For crop, the left edge of the region
+    pub x: Option<u32>,
+    /// For crop, the top edge of the region
+    pub y: Option<u32>,
+    /// Output format: png, jpeg or webp. Required for convert; resize and crop keep the
+    /// source format when omitted
+    pub format: Option<String>,
+    /// JPEG quality from 1 to 100 (defaults to 85)
+    pub quality: Option<u8>,
+}
+
+/// Enum for operation parameter in sqlite_tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum SqliteOperation {
+    /// List the tables and views with their row counts
+    ListTables,
+    /// Show the definition and columns of a table or view
+    Schema,
+    /// Run a query and return the rows as JSON
+    Query,
+    /// Run a query and save every row to a CSV file in the cache
+    Export,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SqliteToolParams {
+    /// Path to the SQLite database file
+    pub path: String,
+    /// Operation to perform on the database
+    pub operation: SqliteOperation,
+    /// Table or view name for schema
+    pub table: Option<String>,
+    /// A single SQL statement for query and export, with ? placeholders for params
+    pub sql: Option<String>,
+    /// Values bound to the ? placeholders in sql, in order
+    pub params: Option<Vec<serde_json::Value>>,
+    /// Most rows query returns (defaults to 100)
+    pub max_rows: Option<usize>,
+    /// Let query run INSERT, UPDATE and DELETE statements. The database is opened read-only
+    /// otherwise.
+    #[serde(default)]
+    pub allow_writes: bool,
+}
+
+/// Enum for operation parameter in calendar_tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarOperation {
+    /// List the events between start and end
+    ListEvents,
+    /// Add an event to the calendar
+    CreateEvent,
+    /// List the reminders that are not completed
+    ListReminders,
+    /// Add a reminder
+    CreateReminder,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CalendarToolParams {
+    /// Operation to perform
+    pub operation: CalendarOperation,
+    /// Event title or reminder text, for create_event and create_reminder
+    pub title: Option<String>,
+    /// For list_events, the start of the range (defaults to now); for create_event, when the
+    /// event starts. Given as e.g. 2024-05-01 14:30, in the system time zone unless an offset
+    /// is included.
+    pub start: Option<String>,
+    /// For list_events, the end of the range (defaults to 7 days after start); for
+    /// create_event, when the event ends
+    pub end: Option<String>,
+    /// For create_reminder, when the reminder is due
+    pub due: Option<String>,
+    /// Notes to add to the event or reminder
+    pub notes: Option<String>,
+}
+
+/// Resolve a path given to the cache tool and make sure it points at a file inside `cache_dir`.
+///
+/// Relative paths are taken relative to the cache directory. Both sides are canonicalized, so
+/// `..` segments and symlinks cannot be used to reach files elsewhere on disk. The returned path
+/// is expressed under `cache_dir` so it matches the paths handed out by the other tools.
+fn resolve_cache_path(cache_dir: &Path, path: &str) -> Result<PathBuf, ErrorData> {
+    let canonical_cache_dir = cache_dir.canonicalize().map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to resolve cache directory: {}", e),
+            None,
+        )
+    })?;
+    let canonical_path = cache_dir.join(path).canonicalize().map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            format!("Cached file not found: {} ({})", path, e),
+            None,
+        )
+    })?;
+
+    match canonical_path.strip_prefix(&canonical_cache_dir) {
+        Ok(relative) if !relative.as_os_str().is_empty() && canonical_path.is_file() => {
+            Ok(cache_dir.join(relative))
+        }
+        _ => Err(ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            format!(
+                "Path {} is not a file inside the cache directory {}",
+                path,
+                cache_dir.display()
+            ),
+            None,
+        )),
+    }
+}
+
+/// Pick the cache directory. Project scope, the default whenever GOOSE_WORKING_DIR is set, uses
+/// `<working dir>/.goose/cache/computer_controller` so sessions in different projects don't see
+/// each other's files. Otherwise the shared per-user cache directory is used.
+fn resolve_cache_dir() -> PathBuf {
+    let working_dir = std::env::var("GOOSE_WORKING_DIR").ok();
+    let project_scope = match std::env::var(CACHE_SCOPE_ENV).ok().as_deref() {
+        Some("project") => true,
+        Some("global") => false,
+        _ => working_dir.is_some(),
+    };
+
+    if project_scope {
+        return working_dir
+            .map(PathBuf::from)
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_default())
+            .join(".goose")
+            .join("cache")
+            .join("computer_controller");
+    }
+
+    // choose_app_strategy().cache_dir()
+    // - macOS/Linux: ~/.cache/goose/computer_controller/
+    // - Windows:     ~\AppData\Local\Block\goose\cache\computer_controller\
+    // keep previous behavior of defaulting to /tmp/
+    choose_app_strategy(crate::APP_STRATEGY.clone())
+        .map(|strategy| strategy.in_cache_dir("computer_controller"))
+        .unwrap_or_else(|_| create_system_automation().get_temp_path())
+}
+
+fn disabled_tools(config: &Config) -> HashSet<String> {
+    let names = config
+        .get_param::<Vec<String>>(DISABLED_TOOLS_KEY)
+        .or_else(|_| {
+            config
+                .get_param::<String>(DISABLED_TOOLS_KEY)
+                .map(|value| value.split(',').map(str::to_string).collect())
+        })
+        .unwrap_or_default();
+    names
+        .into_iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
 }
 
 /// ComputerController MCP Server using official RMCP SDK
@@ -282,8 +627,11 @@ pub struct ComputerControllerServer {
     cache_dir: PathBuf,
     active_resources: Arc<Mutex<HashMap<String, ResourceContents>>>,
     http_client: Client,
+    web_client_settings: web_client::WebClientSettings,
+    scrape_sessions: Arc<scrape_sessions::ScrapeSessions>,
     instructions: String,
     system_automation: Arc<Box<dyn SystemAutomation + Send + Sync>>,
+    disabled_tools: Arc<HashSet<String>>,
 }
 
 impl Default for ComputerControllerServer {
@@ -295,88 +643,100 @@ impl Default for ComputerControllerServer {
 #[tool_router(router = tool_router)]
 impl ComputerControllerServer {
     pub fn new() -> Self {
-        // choose_app_strategy().cache_dir()
-        // - macOS/Linux: ~/.cache/goose/computer_controller/
-        // - Windows:     ~\AppData\Local\Block\goose\cache\computer_controller\
-        // keep previous behavior of defaulting to /tmp/
-        let cache_dir = choose_app_strategy(crate::APP_STRATEGY.clone())
-            .map(|strategy| strategy.in_cache_dir("computer_controller"))
-            .unwrap_or_else(|_| create_system_automation().get_temp_path());
-
-        fs::create_dir_all(&cache_dir).unwrap_or_else(|_| {
-            println!(
-                "Warning: Failed to create cache directory at {:?}",
-                cache_dir
-            )
-        });
+        // Created on first write rather than here, so a project directory only gets a
+        // .goose/cache folder once something is actually cached
+        let cache_dir = resolve_cache_dir();
 
         let system_automation: Arc<Box<dyn SystemAutomation + Send + Sync>> =
             Arc::new(create_system_automation());
 
-        let os_specific_instructions = match std::env::consts::OS {
-            "windows" => indoc! {r#"
-            Here are some extra tools:
-            automation_script
-              - Create and run PowerShell or Batch scripts
-              - PowerShell is recommended for most tasks
-              - Scripts can save their output to files
-              - Windows-specific features:
-                - PowerShell for system automation and UI control
-                - Windows Management Instrumentation (WMI)
-                - Registry access and system settings
-              - Use the screenshot tool if needed to help with tasks
-
-            computer_control
-              - System automation using PowerShell
-              - Consider the screenshot tool to work out what is on screen and what to do to help with the control task.
-            "#},
-            "macos" => indoc! {r#"
-            Here are some extra tools:
-            automation_script
-              - Create and run Shell and Ruby scripts
-              - Shell (bash) is recommended for most tasks
-              - Scripts can save their output to files
-              - macOS-specific features:
-                - AppleScript for system and UI control
-                - Integration with macOS apps and services
-              - Use the screenshot tool if needed to help with tasks
-
-            computer_control
-              - System automation using AppleScript
-              - Consider the screenshot tool to work out what is on screen and what to do to help with the control task.
-
-            When you need to interact with websites or web applications, consider using the computer_control tool with AppleScript, which can automate Safari or other browsers to:
-              - Open specific URLs
-              - Fill in forms
-              - Click buttons
-              - Extract content
-              - Handle web-based workflows
-            This is often more reliable than web scraping for modern web applications.
-            "#},
-            _ => indoc! {r#"
-            Here are some extra tools:
-            automation_script
-              - Create and run Shell scripts
-              - Shell (bash) is recommended for most tasks
-              - Scripts can save their output to files
-              - Linux-specific features:
-                - System automation through shell scripting
-                - X11/Wayland window management
-                - D-Bus system services integration
-                - Desktop environment control
-              - Use the screenshot tool if needed to help with tasks
-
-            computer_control
-              - System automation using shell commands and system tools
-              - Desktop environment automation (GNOME, KDE, etc.)
-              - Consider the screenshot tool to work out what is on screen and what to do to help with the control task.
-
-            When you need to interact with websites or web applications, consider using tools like xdotool or wmctrl for:
-              - Window management
-              - Simulating keyboard/mouse input
-              - Automating UI interactions
-              - Desktop environment control
-            "#},
+        let disabled_tools = disabled_tools(Config::global());
+
+        let (automation_script_instructions, computer_control_instructions) =
+            match std::env::consts::OS {
+                "windows" => (
+                    indoc! {r#"
+                    automation_script
+                      - Create and run PowerShell or Batch scripts
+                      - PowerShell is recommended for most tasks
+                      - Scripts can save their output to files
+                      - Windows-specific features:
+                        - PowerShell for system automation and UI control
+                        - Windows Management Instrumentation (WMI)
+                        - Registry access and system settings
+                      - Use the screenshot tool if needed to help with tasks
+                    "#},
+                    indoc! {r#"
+                    computer_control
+                      - System automation using PowerShell
+                      - Consider the screenshot tool to work out what is on screen and what to do to help with the control task.
+                    "#},
+                ),
+                "macos" => (
+                    indoc! {r#"
+                    automation_script
+                      - Create and run Shell and Ruby scripts
+                      - Shell (bash) is recommended for most tasks
+                      - Scripts can save their output to files
+                      - macOS-specific features:
+                        - AppleScript for system and UI control
+                        - Integration with macOS apps and services
+                      - Use the screenshot tool if needed to help with tasks
+                    "#},
+                    indoc! {r#"
+                    computer_control
+                      - System automation using AppleScript
+                      - Consider the screenshot tool to work out what is on screen and what to do to help with the control task.
+
+                    When you need to interact with websites or web applications, consider using the computer_control tool with AppleScript, which can automate Safari or other browsers to:
+                      - Open specific URLs
+                      - Fill in forms
+                      - Click buttons
+                      - Extract content
+                      - Handle web-based workflows
+                    This is often more reliable than web scraping for modern web applications.
+                    "#},
+                ),
+                _ => (
+                    indoc! {r#"
+                    automation_script
+                      - Create and run Shell scripts
+                      - Shell (bash) is recommended for most tasks
+                      - Scripts can save their output to files
+                      - Linux-specific features:
+                        - System automation through shell scripting
+                        - X11/Wayland window management
+                        - D-Bus system services integration
+                        - Desktop environment control
+                      - Use the screenshot tool if needed to help with tasks
+
+                    When you need to interact with websites or web applications, consider using tools like xdotool or wmctrl for:
+                      - Window management
+                      - Simulating keyboard/mouse input
+                      - Automating UI interactions
+                      - Desktop environment control
+                    "#},
+                    indoc! {r#"
+                    computer_control
+                      - System automation using shell commands and system tools
+                      - Desktop environment automation (GNOME, KDE, etc.)
+                      - Consider the screenshot tool to work out what is on screen and what to do to help with the control task.
+                    "#},
+                ),
+            };
+
+        let extra_tools: Vec<&str> = [
+            ("automation_script", automation_script_instructions),
+            ("computer_control", computer_control_instructions),
+        ]
+        .into_iter()
+        .filter(|(name, _)| !disabled_tools.contains(*name))
+        .map(|(_, text)| text)
+        .collect();
+        let os_specific_instructions = if extra_tools.is_empty() {
+            String::new()
+        } else {
+            format!("Here are some extra tools:\n{}", extra_tools.join("\n"))
         };
 
         let instructions = formatdoc! {r#"
@@ -401,6 +761,14 @@ impl ComputerControllerServer {
               - Save as text, JSON, or binary files
               - Content is cached locally for later use
               - This is not optimised for complex websites, so don't use this as the first tool.
+              - Pass a session_id to keep cookies (such as a login) across scrapes;
+                clear it with web_scrape_clear_session when done
+            web_scrape_batch
+              - Fetch several URLs at once, caching each one like web_scrape
+              - Reports which URLs succeeded and which failed
+            calendar_tool
+              - List and add calendar events and reminders
+              - Prefer this over writing calendar scripts
             cache
               - Manage your cached files
               - List, view, delete files
@@ -413,21 +781,75 @@ impl ComputerControllerServer {
             cache_dir = cache_dir.display()
         };
 
+        let web_client_settings = web_client::WebClientSettings::from_config(Config::global());
+
+        let mut tool_router = Self::tool_router();
+        for name in &disabled_tools {
+            if tool_router.has_route(name) {
+                tool_router.remove_route(name);
+            } else {
+                tracing::warn!("{} names unknown tool '{}'", DISABLED_TOOLS_KEY, name);
+            }
+        }
+        let mut active_tools: Vec<String> = tool_router
+            .list_all()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect();
+        active_tools.sort();
+        tracing::info!(
+            "Computer controller tools enabled: {}",
+            active_tools.join(", ")
+        );
+
         Self {
-            tool_router: Self::tool_router(),
+            tool_router,
             cache_dir,
             active_resources: Arc::new(Mutex::new(HashMap::new())),
-            http_client: Client::builder().user_agent("goose/1.0").build().unwrap(),
+            http_client: web_client_settings.client_builder().build().unwrap(),
+            web_client_settings,
+            scrape_sessions: Arc::new(scrape_sessions::ScrapeSessions::default()),
             instructions,
             system_automation,
+            disabled_tools: Arc::new(disabled_tools),
         }
     }
 
+    // Disabled tools are already removed from the router; this guards against calls that
+    // reach a handler some other way
+    fn ensure_enabled(&self, name: &str) -> Result<(), ErrorData> {
+        if self.disabled_tools.contains(name) {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_REQUEST,
+                format!("The {} tool is disabled by {}", name, DISABLED_TOOLS_KEY),
+                None,
+            ));
+        }
+        Ok(())
+    }
+
+    // Helper function to create the cache directory before anything is written to it
+    fn ensure_cache_dir(&self) -> Result<&Path, ErrorData> {
+        fs::create_dir_all(&self.cache_dir).map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!(
+                    "Failed to create cache directory {}: {}",
+                    self.cache_dir.display(),
+                    e
+                ),
+                None,
+            )
+        })?;
+        Ok(&self.cache_dir)
+    }
+
     // Helper function to generate a cache file path
-    fn get_cache_path(&self, prefix: &str, extension: &str) -> PathBuf {
+    fn get_cache_path(&self, prefix: &str, extension: &str) -> Result<PathBuf, ErrorData> {
         let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-        self.cache_dir
-            .join(format!("{}_{}.{}", prefix, timestamp, extension))
+        Ok(self
+            .ensure_cache_dir()?
+            .join(format!("{}_{}.{}", prefix, timestamp, extension)))
     }
 
     // Helper function to save content to cache
@@ -437,7 +859,7 @@ impl ComputerControllerServer {
         prefix: &str,
         extension: &str,
     ) -> Result<PathBuf, ErrorData> {
-        let cache_path = self.get_cache_path(prefix, extension);
+        let cache_path = self.get_cache_path(prefix, extension)?;
         fs::write(&cache_path, content).map_err(|e| {
             ErrorData::new(
                 ErrorCode::INTERNAL_ERROR,
@@ -471,33 +893,42 @@ impl ComputerControllerServer {
         Ok(())
     }
 
-    /// Fetch and save content from a web page
-    #[tool(
-        name = "web_scrape",
-        description = "
-            Fetch and save content from a web page. The content can be saved as:
-            - text (for HTML pages)
-            - json (for API responses)
-            - binary (for images and other files)
-            The content is cached locally and can be accessed later using the cache_path
-            returned in the response.
-        "
-    )]
-    pub async fn web_scrape(
+    /// Fetch a URL and save its content to the cache as a resource, returning the cache path
+    async fn fetch_to_cache(
         &self,
-        params: Parameters<WebScrapeParams>,
-    ) -> Result<CallToolResult, ErrorData> {
-        let params = params.0;
-        let url = &params.url;
-        let save_as = params.save_as;
-
-        // Fetch the content
-        let response = self.http_client.get(url).send().await.map_err(|e| {
-            ErrorData::new(
-                ErrorCode::INTERNAL_ERROR,
-                format!("Failed to fetch URL: {}", e),
-                None,
+        url: &str,
+        save_as: &SaveAsFormat,
+        prefix: &str,
+        session_id: Option<&str>,
+    ) -> Result<PathBuf, ErrorData> {
+        // Fetch the content, refusing internal addresses unless the user opted in
+        let allow_private = url_policy::private_networks_allowed();
+        let cookies = session_id.map(|id| self.scrape_sessions.jar(id));
+        let response = if allow_private && cookies.is_none() {
+            self.http_client
+                .get(url)
+                .send()
+                .await
+                .map_err(url_policy::FetchError::from)
+        } else {
+            url_policy::get_following_redirects(
+                url,
+                || self.web_client_settings.client_builder(),
+                !allow_private,
+                cookies.as_deref(),
             )
+            .await
+        }
+        .map_err(|e| {
+            let code = match &e {
+                url_policy::FetchError::Blocked(reason) => {
+                    url_policy::log_blocked(reason);
+                    ErrorCode::INVALID_PARAMS
+                }
+                url_policy::FetchError::Invalid(_) => ErrorCode::INVALID_PARAMS,
+                url_policy::FetchError::Request(_) => ErrorCode::INTERNAL_ERROR,
+            };
+            ErrorData::new(code, e.to_string(), None)
         })?;
 
         let status = response.status();
@@ -552,17 +983,145 @@ impl ComputerControllerServer {
         };
 
         // Save to cache
-        let cache_path = self.save_to_cache(&content, "web", extension).await?;
+        let cache_path = self.save_to_cache(&content, prefix, extension).await?;
 
         // Register as a resource
         self.register_as_resource(&cache_path, mime_type)?;
 
+        Ok(cache_path)
+    }
+
+    /// Fetch and save content from a web page
+    #[tool(
+        name = "web_scrape",
+        description = "
+            Fetch and save content from a web page. The content can be saved as:
+            - text (for HTML pages)
+            - json (for API responses)
+            - binary (for images and other files)
+            The content is cached locally and can be accessed later using the cache_path
+            returned in the response.
+        "
+    )]
+    pub async fn web_scrape(
+        &self,
+        params: Parameters<WebScrapeParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+        let cache_path = self
+            .fetch_to_cache(
+                &params.url,
+                &params.save_as,
+                "web",
+                params.session_id.as_deref(),
+            )
+            .await?;
+
         Ok(CallToolResult::success(vec![Content::text(format!(
             "Content saved to: {}",
             cache_path.display()
         ))]))
     }
 
+    /// Fetch and save content from several web pages concurrently
+    #[tool(
+        name = "web_scrape_batch",
+        description = "
+            Fetch and save content from multiple web pages at once. Every URL is fetched and
+            cached like web_scrape, using the same save_as format. Up to 20 URLs can be given;
+            they are fetched a few at a time (max_concurrency, default 4).
+            The response lists the cache_path for each URL that succeeded and the error for
+            each URL that failed.
+        "
+    )]
+    pub async fn web_scrape_batch(
+        &self,
+        params: Parameters<WebScrapeBatchParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+        if params.urls.is_empty() || params.urls.len() > MAX_BATCH_URLS {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("urls must contain between 1 and {} URLs", MAX_BATCH_URLS),
+                None,
+            ));
+        }
+
+        let concurrency = params
+            .max_concurrency
+            .unwrap_or(DEFAULT_BATCH_CONCURRENCY)
+            .clamp(1, MAX_BATCH_CONCURRENCY);
+        let semaphore = Arc::new(Semaphore::new(concurrency));
+
+        let mut fetches = JoinSet::new();
+        for (index, url) in params.urls.iter().enumerate() {
+            let server = self.clone();
+            let semaphore = Arc::clone(&semaphore);
+            let url = url.clone();
+            let save_as = params.save_as.clone();
+            let session_id = params.session_id.clone();
+            fetches.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                // Distinct prefixes keep pages fetched in the same second from colliding
+                let prefix = format!("web_{}", index + 1);
+                let result = server
+                    .fetch_to_cache(&url, &save_as, &prefix, session_id.as_deref())
+                    .await;
+                (index, result)
+            });
+        }
+
+        let mut results: Vec<Option<Result<PathBuf, ErrorData>>> =
+            params.urls.iter().map(|_| None).collect();
+        while let Some(joined) = fetches.join_next().await {
+            if let Ok((index, result)) = joined {
+                results[index] = Some(result);
+            }
+        }
+
+        let succeeded = results
+            .iter()
+            .filter(|result| matches!(result, Some(Ok(_))))
+            .count();
+        let mut report = format!("Fetched {} of {} URLs:\n", succeeded, params.urls.len());
+        for (url, result) in params.urls.iter().zip(results) {
+            let line = match result {
+                Some(Ok(cache_path)) => format!("- {}: saved to {}", url, cache_path.display()),
+                Some(Err(e)) => format!("- {}: failed: {}", url, e.message),
+                None => format!("- {}: failed: fetch did not complete", url),
+            };
+            report.push_str(&line);
+            report.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(report)]))
+    }
+
+    /// Forget the cookies kept for web_scrape sessions
+    #[tool(
+        name = "web_scrape_clear_session",
+        description = "
+            Clear the cookies stored for a web_scrape session_id, for example to log out.
+            Clears every session when no session_id is given.
+        "
+    )]
+    pub async fn web_scrape_clear_session(
+        &self,
+        params: Parameters<WebScrapeClearSessionParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let message = match params.0.session_id {
+            Some(session_id) if self.scrape_sessions.clear(&session_id) => {
+                format!("Cleared web_scrape session {}", session_id)
+            }
+            Some(session_id) => format!("No web_scrape session named {}", session_id),
+            None => format!(
+                "Cleared {} web_scrape session(s)",
+                self.scrape_sessions.clear_all()
+            ),
+        };
+        Ok(CallToolResult::success(vec![Content::text(message)]))
+    }
+
     /// Create and run small scripts for automation tasks
     #[cfg(target_os = "windows")]
     #[tool(
@@ -614,11 +1173,30 @@ impl ComputerControllerServer {
         &self,
         params: Parameters<AutomationScriptParams>,
     ) -> Result<CallToolResult, ErrorData> {
+        self.ensure_enabled("automation_script")?;
         let params = params.0;
         let language = params.language;
         let script = &params.script;
         let save_output = params.save_output;
 
+        let cwd = match params.cwd.as_deref() {
+            Some(cwd) => {
+                let cwd = PathBuf::from(shellexpand::tilde(cwd).into_owned());
+                if !cwd.is_dir() {
+                    return Err(ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        format!(
+                            "Working directory {} does not exist or is not a directory",
+                            cwd.display()
+                        ),
+                        None,
+                    ));
+                }
+                Some(cwd)
+            }
+            None => None,
+        };
+
         // Create a temporary directory for the script
         let script_dir = tempfile::tempdir().map_err(|e| {
             ErrorData::new(
@@ -695,44 +1273,45 @@ impl ComputerControllerServer {
         };
 
         // Run the script
-        let output = match language {
+        let mut process = match language {
             ScriptLanguage::Powershell => {
                 // For PowerShell, we need to use -File instead of -Command
-                Command::new("powershell")
+                let mut process = Command::new("powershell");
+                process
                     .arg("-NoProfile")
                     .arg("-NonInteractive")
                     .arg("-File")
-                    .arg(&command)
-                    .env("GOOSE_TERMINAL", "1")
-                    .output()
-                    .await
-                    .map_err(|e| {
-                        ErrorData::new(
-                            ErrorCode::INTERNAL_ERROR,
-                            format!("Failed to run script: {}", e),
-                            None,
-                        )
-                    })?
+                    .arg(&command);
+                process
+            }
+            _ => {
+                let mut process = Command::new(shell);
+                process.arg(shell_arg).arg(&command);
+                process
             }
-            _ => Command::new(shell)
-                .arg(shell_arg)
-                .arg(&command)
-                .env("GOOSE_TERMINAL", "1")
-                .output()
-                .await
-                .map_err(|e| {
-                    ErrorData::new(
-                        ErrorCode::INTERNAL_ERROR,
-                        format!("Failed to run script: {}", e),
-                        None,
-                    )
-                })?,
         };
+        if let Some(cwd) = &cwd {
+            process.current_dir(cwd);
+        }
+        let output = process
+            .env("GOOSE_TERMINAL", "1")
+            .output()
+            .await
+            .map_err(|e| {
+                ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Failed to run script: {}", e),
+                    None,
+                )
+            })?;
 
         let output_str = String::from_utf8_lossy(&output.stdout).into_owned();
         let error_str = String::from_utf8_lossy(&output.stderr).into_owned();
+        let success = output.status.success();
+        // None when the script was killed by a signal
+        let exit_code = output.status.code();
 
-        let mut result = if output.status.success() {
+        let mut result = if success {
             format!("Script completed successfully.\n\nOutput:\n{}", output_str)
         } else {
             format!(
@@ -752,7 +1331,13 @@ impl ComputerControllerServer {
             self.register_as_resource(&cache_path, "text")?;
         }
 
-        Ok(CallToolResult::success(vec![Content::text(result)]))
+        // The exit status is also given structurally so callers can branch on it
+        let mut tool_result = CallToolResult::success(vec![Content::text(result)]);
+        tool_result.structured_content = Some(serde_json::json!({
+            "success": success,
+            "exit_code": exit_code,
+        }));
+        Ok(tool_result)
     }
 
     /// Control the computer using system automation
@@ -794,7 +1379,7 @@ impl ComputerControllerServer {
             - Web & Email: Open URLs, web automation, send/organize emails, handle attachments
             - Media: Manage music libraries, photo collections, playlists
             - File Operations: Organize files/folders
-            - Integration: Calendar, reminders, messages
+            - Integration: Messages (use calendar_tool for calendar events and reminders)
             - Data: Interact with spreadsheets and documents
 
             Can be combined with screenshot tool for visual task assistance.
@@ -850,6 +1435,7 @@ impl ComputerControllerServer {
         &self,
         params: Parameters<ComputerControlParams>,
     ) -> Result<CallToolResult, ErrorData> {
+        self.ensure_enabled("computer_control")?;
         let params = params.0;
         let script = &params.script;
         let save_output = params.save_output;
@@ -894,7 +1480,18 @@ impl ComputerControllerServer {
             - find_text: Search for text in a worksheet (returns a list of (row, column) coordinates)
             - update_cell: Update a single cell's value (returns confirmation message)
             - get_cell: Get value and formula from a specific cell (returns both value and formula if present)
+              Set computed=true with get_range or get_cell to also get each formula's computed result.
+              Results are read from the values cached in the workbook, formulas are not evaluated;
+              a formula with no cached result is reported as FormulaOnly.
+            - format_cell: Format a single cell (row, col) with a style (returns confirmation message)
+            - format_range: Format every cell in a range (e.g., 'A1:D1') with a style (returns confirmation message)
+              The style accepts bold, italic, number_format (e.g., '0.00%', '$#,##0'),
+              font_color and fill_color (hex, e.g., 'FF0000'). Changes are saved to the file.
             - save: Save changes back to the file (returns confirmation message)
+            - create_workbook: Create a new workbook with optional worksheet names in 'sheets'
+              (parent directories are created; set overwrite=true to replace an existing file)
+              Alternatively, set create_if_missing=true with update_cell to create the workbook
+              and worksheet on the first write.
 
             Use this when working with Excel spreadsheets to analyze or modify data.
         "
@@ -960,7 +1557,7 @@ impl ComputerControllerServer {
                     })?
                 };
                 let range_data = xlsx
-                    .get_range(worksheet, range)
+                    .get_range(worksheet, range, params.computed)
                     .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
                 Ok(CallToolResult::success(vec![Content::text(format!(
                     "{:#?}",
@@ -1022,7 +1619,12 @@ impl ComputerControllerServer {
 
                 let worksheet_name = params.worksheet.as_deref().unwrap_or("Sheet1");
 
-                let mut xlsx = xlsx_tool::XlsxTool::new(path)
+                let xlsx = if params.create_if_missing {
+                    xlsx_tool::XlsxTool::open_or_create(path, worksheet_name)
+                } else {
+                    xlsx_tool::XlsxTool::new(path)
+                };
+                let mut xlsx = xlsx
                     .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
                 xlsx.update_cell(worksheet_name, row as u32, col as u32, value)
                     .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
@@ -1042,6 +1644,16 @@ impl ComputerControllerServer {
                     "File saved successfully.",
                 )]))
             }
+            XlsxOperation::CreateWorkbook => {
+                let sheets = params.sheets.unwrap_or_default();
+                let xlsx = xlsx_tool::XlsxTool::create_file(path, &sheets, params.overwrite)
+                    .map_err(|e| ErrorData::new(ErrorCode::INVALID_PARAMS, e.to_string(), None))?;
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Created workbook {} with worksheet(s): {}",
+                    path,
+                    xlsx.worksheet_names().join(", ")
+                ))]))
+            }
             XlsxOperation::GetCell => {
                 let row = params.row.ok_or_else(|| {
                     ErrorData::new(
@@ -1071,13 +1683,62 @@ impl ComputerControllerServer {
                     })?
                 };
                 let cell_value = xlsx
-                    .get_cell_value(worksheet, row as u32, col as u32)
+                    .get_cell_value(worksheet, row as u32, col as u32, params.computed)
                     .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
                 Ok(CallToolResult::success(vec![Content::text(format!(
                     "{:#?}",
                     cell_value
                 ))]))
             }
+            XlsxOperation::FormatCell | XlsxOperation::FormatRange => {
+                let style = params.style.as_ref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'style' parameter".to_string(),
+                        None,
+                    )
+                })?;
+                let range = if matches!(operation, XlsxOperation::FormatRange) {
+                    params.range.clone().ok_or_else(|| {
+                        ErrorData::new(
+                            ErrorCode::INVALID_PARAMS,
+                            "Missing 'range' parameter".to_string(),
+                            None,
+                        )
+                    })?
+                } else {
+                    let (row, col) = params.row.zip(params.col).ok_or_else(|| {
+                        ErrorData::new(
+                            ErrorCode::INVALID_PARAMS,
+                            "Missing 'row' or 'col' parameter".to_string(),
+                            None,
+                        )
+                    })?;
+                    xlsx_tool::cell_range(row as u32, col as u32)
+                };
+
+                let mut xlsx = xlsx_tool::XlsxTool::new(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                let worksheet_name = match &params.worksheet {
+                    Some(name) => name.clone(),
+                    None => xlsx
+                        .get_worksheet_by_index(0)
+                        .map_err(|e| {
+                            ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None)
+                        })?
+                        .get_name()
+                        .to_string(),
+                };
+                let cell_count = xlsx
+                    .format_range(&worksheet_name, &range, style)
+                    .map_err(|e| ErrorData::new(ErrorCode::INVALID_PARAMS, e.to_string(), None))?;
+                xlsx.save(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Formatted {} cell(s) in range {} of worksheet '{}'",
+                    cell_count, range, worksheet_name
+                ))]))
+            }
         }
     }
 
@@ -1087,13 +1748,18 @@ impl ComputerControllerServer {
         description = "
             Process DOCX files to extract text and create/update documents.
             Supports operations:
-            - extract_text: Extract all text content and structure (headings, TOC) from the DOCX
+            - extract_text: Extract all text content and structure (headings, TOC), plus any
+              headers and footers, from the DOCX
             - update_doc: Create a new DOCX or update existing one with provided content
               Modes:
               - append: Add content to end of document (default)
               - replace: Replace specific text with new content
               - structured: Add content with specific heading level and styling
               - add_image: Add an image to the document (with optional caption)
+              - set_header_footer: Set the page header and/or footer text and alignment, with
+                optional 'Page X of Y' numbering and a blank first page (no content needed)
+            - to_pdf: Render the DOCX to a PDF, keeping headings, images and layout (returns the PDF path)
+              Requires LibreOffice ('soffice') to be installed.
 
             Use this when there is a .docx file that needs to be processed or created.
         "
@@ -1110,6 +1776,22 @@ impl ComputerControllerServer {
         let operation_str = match operation {
             DocxOperation::ExtractText => "extract_text",
             DocxOperation::UpdateDoc => "update_doc",
+            DocxOperation::ToPdf => {
+                let stem = Path::new(path)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "document".to_string());
+                let output = self.get_cache_path(&stem, "pdf")?;
+                let converter = crate::computercontroller::docx_tool::find_pdf_converter();
+                let result = crate::computercontroller::docx_tool::docx_to_pdf(
+                    path,
+                    &output,
+                    converter.as_deref(),
+                )
+                .await?;
+                self.register_as_resource(&output, "application/pdf")?;
+                return Ok(CallToolResult::success(result));
+            }
         };
 
         // Convert typed params back to JSON for the internal docx_tool impl
@@ -1134,10 +1816,22 @@ impl ComputerControllerServer {
     #[tool(
         name = "pdf_tool",
         description = "
-            Process PDF files to extract text and images.
+            Process PDF files to extract text, images, tables and metadata.
             Supports operations:
             - extract_text: Extract all text content from the PDF
             - extract_images: Extract and save embedded images to PNG files
+            - extract_tables: Detect tables from the position of text on each page and save each
+              as a CSV file (returns each table's page, rows x columns and CSV path, with small
+              tables shown inline). Detection is heuristic, so check the result against the
+              extracted text.
+            - get_metadata: Read the title, author, creation and modification dates, page count
+              and whether the document is encrypted. This is cheap, so use it to size up a large
+              or unfamiliar PDF before extracting everything.
+            - list_form_fields: List the fillable form fields with their type, current value and
+              options (choices, and the labels checkboxes and radio buttons accept)
+            - fill_form: Fill form fields given in fields (field name to value) and save the
+              result as a new PDF in the cache directory, named by output_path. Checkboxes take
+              true or false. Set flatten to make the filled form uneditable.
 
             Use this when there is a .pdf file or files that need to be processed.
         "
@@ -1154,16 +1848,379 @@ impl ComputerControllerServer {
         let operation_str = match operation {
             PdfOperation::ExtractText => "extract_text",
             PdfOperation::ExtractImages => "extract_images",
+            PdfOperation::ExtractTables => "extract_tables",
+            PdfOperation::GetMetadata => "get_metadata",
+            PdfOperation::ListFormFields => "list_form_fields",
+            PdfOperation::FillForm => {
+                let fields = params.fields.ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "fields is required for the fill_form operation".to_string(),
+                        None,
+                    )
+                })?;
+                let (result, output) = crate::computercontroller::pdf_tool::fill_pdf_form(
+                    path,
+                    &fields,
+                    params.output_path.as_deref(),
+                    params.flatten,
+                    self.ensure_cache_dir()?,
+                )
+                .await?;
+                self.register_as_resource(&output, "application/pdf")?;
+                return Ok(CallToolResult::success(result));
+            }
         };
 
-        let result =
-            crate::computercontroller::pdf_tool::pdf_tool(path, operation_str, &self.cache_dir)
-                .await
-                .map_err(|e| ErrorData::new(e.code, e.message, e.data))?;
+        let result = crate::computercontroller::pdf_tool::pdf_tool(
+            path,
+            operation_str,
+            self.ensure_cache_dir()?,
+        )
+        .await
+        .map_err(|e| ErrorData::new(e.code, e.message, e.data))?;
+
+        Ok(CallToolResult::success(result))
+    }
+
+    /// List, extract and create zip and tar.gz archives
+    #[tool(
+        name = "archive_tool",
+        description = "
+            Work with .zip, .tar.gz and .tgz archives without shelling out to unzip or tar.
+            Supports operations:
+            - list: List the entries in the archive with their uncompressed sizes
+            - extract: Extract the archive, or only the entries and directories given in paths,
+              into destination (defaults to a new directory in the cache). Entries that would
+              land outside the destination, such as '../' or absolute paths, and links are
+              skipped and reported.
+            - create: Create the archive at path from the files and directories given in paths.
+              The format follows the extension of path.
+
+            Use this when the user has an archive to look inside or unpack, or wants files bundled up.
+        "
+    )]
+    pub async fn archive_tool(
+        &self,
+        params: Parameters<ArchiveToolParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+        let path = PathBuf::from(&params.path);
+        let paths = params.paths.unwrap_or_default();
+
+        let result = match params.operation {
+            ArchiveOperation::List => {
+                let entries = archive_tool::list_entries(&path)?;
+                archive_tool::render_entries(&path, &entries)
+            }
+            ArchiveOperation::Extract => {
+                let destination = match params.destination {
+                    Some(destination) => PathBuf::from(destination),
+                    None => {
+                        let name = path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_else(|| "archive".to_string());
+                        let stem = [".tar.gz", ".tgz", ".zip"]
+                            .iter()
+                            .find_map(|ext| {
+                                name.len()
+                                    .checked_sub(ext.len())
+                                    .filter(|&end| name[end..].eq_ignore_ascii_case(ext))
+                                    .map(|end| name[..end].to_string())
+                            })
+                            .unwrap_or(name);
+                        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+                        self.ensure_cache_dir()?
+                            .join(format!("{}_{}", stem, timestamp))
+                    }
+                };
+                let report = archive_tool::extract_entries(&path, &destination, &paths)?;
+                self.register_as_resource(&destination, "inode/directory")?;
+                archive_tool::render_extract_report(&destination, &report)
+            }
+            ArchiveOperation::Create => {
+                let inputs: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+                let added = archive_tool::create_archive(&path, &inputs)?;
+                let format = archive_tool::ArchiveFormat::from_path(&path)?;
+                self.register_as_resource(&path, format.mime_type())?;
+                vec![Content::text(format!(
+                    "Created {} with {} entries",
+                    path.display(),
+                    added
+                ))]
+            }
+        };
+
+        Ok(CallToolResult::success(result))
+    }
+
+    /// Inspect, resize, convert and crop images
+    #[tool(
+        name = "image_tool",
+        description = "
+            Work with png, jpeg, webp, gif, bmp and tiff images without needing ImageMagick.
+            Supports operations:
+            - get_info: Report the format, dimensions, color type, file size and common EXIF
+              fields such as camera, date taken and exposure
+            - resize: Resize to width and/or height, or to percent of the original size. The
+              aspect ratio is kept, fitting inside both when width and height are given.
+            - convert: Convert to format (png, jpeg or webp), with quality for jpeg
+            - crop: Crop the width x height region whose top left corner is at x, y
+
+            Results are saved to the cache, and small results are also shown as a thumbnail.
+            Use this when the user wants a screenshot smaller, photos converted or an image cropped.
+        "
+    )]
+    pub async fn image_tool(
+        &self,
+        params: Parameters<ImageToolParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+        let path = PathBuf::from(&params.path);
+        let (image, source_format) = image_tool::open_image(&path)?;
+
+        let (output, action) = match params.operation {
+            ImageOperation::GetInfo => {
+                return Ok(CallToolResult::success(vec![Content::text(
+                    image_tool::describe(&path, &image, source_format),
+                )]));
+            }
+            ImageOperation::Resize => (
+                image_tool::resize(&image, params.width, params.height, params.percent)?,
+                "resized",
+            ),
+            ImageOperation::Convert => (image.clone(), "converted"),
+            ImageOperation::Crop => {
+                let (Some(width), Some(height)) = (params.width, params.height) else {
+                    return Err(ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Crop requires width and height".to_string(),
+                        None,
+                    ));
+                };
+                (
+                    image_tool::crop(
+                        &image,
+                        params.x.unwrap_or(0),
+                        params.y.unwrap_or(0),
+                        width,
+                        height,
+                    )?,
+                    "cropped",
+                )
+            }
+        };
+
+        let format = match (&params.operation, params.format.as_deref()) {
+            (_, Some(format)) => image_tool::OutputFormat::from_name(format)?,
+            (ImageOperation::Convert, None) => {
+                return Err(ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    "Convert requires a format: png, jpeg or webp".to_string(),
+                    None,
+                ))
+            }
+            (_, None) => image_tool::OutputFormat::matching(source_format),
+        };
+
+        let bytes = image_tool::encode(&output, format, params.quality)?;
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "image".to_string());
+        let cache_path = self
+            .save_to_cache(&bytes, &format!("{}_{}", stem, action), format.extension())
+            .await?;
+        self.register_as_resource(&cache_path, format.mime_type())?;
+
+        let mut result = vec![Content::text(format!(
+            "Image {} from {}x{} to {}x{} {}, saved to: {}",
+            action,
+            image.width(),
+            image.height(),
+            output.width(),
+            output.height(),
+            format.extension(),
+            cache_path.display()
+        ))];
+        result.extend(image_tool::thumbnail(&output)?);
+        Ok(CallToolResult::success(result))
+    }
+
+    /// Inspect and query SQLite database files
+    #[tool(
+        name = "sqlite_tool",
+        description = "
+            Work with SQLite database files without needing the sqlite3 command line tool.
+            Supports operations:
+            - list_tables: List the tables and views with their row counts
+            - schema: Show the CREATE statement and columns of table
+            - query: Run a single SQL statement, binding params to its ? placeholders, and
+              return the rows as JSON. At most max_rows rows (default 100) are returned.
+            - export: Run a SELECT and save every row to a CSV file in the cache
+
+            The database is opened read-only. Set allow_writes to run INSERT, UPDATE or DELETE
+            with query, which reports how many rows changed; only do so when the user asked
+            for the data to be changed.
+            Use this for browser history exports, app data and analytics dumps stored as SQLite.
+        "
+    )]
+    pub async fn sqlite_tool(
+        &self,
+        params: Parameters<SqliteToolParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+        let path = PathBuf::from(&params.path);
+        let bound = params.params.unwrap_or_default();
+        let conn = sqlite_tool::open_database(&path, params.allow_writes)?;
+        let require_sql = || {
+            params.sql.as_deref().ok_or_else(|| {
+                ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    "Missing 'sql' parameter".to_string(),
+                    None,
+                )
+            })
+        };
+
+        let result = match params.operation {
+            SqliteOperation::ListTables => {
+                let tables = sqlite_tool::list_tables(&conn)?;
+                sqlite_tool::render_tables(&path, &tables)
+            }
+            SqliteOperation::Schema => {
+                let table = params.table.as_deref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'table' parameter".to_string(),
+                        None,
+                    )
+                })?;
+                vec![Content::text(sqlite_tool::schema(&conn, table)?)]
+            }
+            SqliteOperation::Query => {
+                let outcome = sqlite_tool::query(
+                    &conn,
+                    require_sql()?,
+                    &bound,
+                    params.max_rows.unwrap_or(sqlite_tool::DEFAULT_MAX_ROWS),
+                    params.allow_writes,
+                )?;
+                sqlite_tool::render_outcome(outcome)
+            }
+            SqliteOperation::Export => {
+                let sql = require_sql()?;
+                let stem = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "sqlite".to_string());
+                let output = self.get_cache_path(&format!("{}_export", stem), "csv")?;
+                let count = sqlite_tool::export_csv(&conn, sql, &bound, &output)?;
+                self.register_as_resource(&output, "text/csv")?;
+                vec![Content::text(format!(
+                    "Exported {} rows to: {}",
+                    count,
+                    output.display()
+                ))]
+            }
+        };
 
         Ok(CallToolResult::success(result))
     }
 
+    /// Read and add calendar events and reminders
+    #[tool(
+        name = "calendar_tool",
+        description = "
+            Read and add calendar events and reminders without writing scripts.
+            Supports operations:
+            - list_events: List the events between start and end (defaults to the next 7 days)
+            - create_event: Add an event with a title, start, end and optional notes
+            - list_reminders: List the reminders that are not completed
+            - create_reminder: Add a reminder with a title, optional due time and notes
+
+            Uses Calendar and Reminders on macOS and Outlook on Windows. Elsewhere, or when
+            Outlook is not installed, events are kept in a calendar.ics file in the cache
+            directory. Every result names the backend it used.
+            Times such as 2024-05-01 14:30 are in the system time zone.
+        "
+    )]
+    pub async fn calendar_tool(
+        &self,
+        params: Parameters<CalendarToolParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+        let automation = self.system_automation.as_ref().as_ref();
+        let backend = calendar_tool::Backend::detect(automation, &self.cache_dir);
+        if matches!(backend, calendar_tool::Backend::Ics(_)) {
+            self.ensure_cache_dir()?;
+        }
+        let required = |value: &Option<String>, name: &str| {
+            value.clone().ok_or_else(|| {
+                ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!("Missing '{}' parameter", name),
+                    None,
+                )
+            })
+        };
+        let optional_time =
+            |value: &Option<String>| value.as_deref().map(calendar_tool::parse_time).transpose();
+
+        let text = match params.operation {
+            CalendarOperation::ListEvents => {
+                let start = optional_time(&params.start)?.unwrap_or_else(chrono::Local::now);
+                let end = optional_time(&params.end)?.unwrap_or_else(|| {
+                    start + chrono::Duration::days(calendar_tool::DEFAULT_RANGE_DAYS)
+                });
+                let events = calendar_tool::list_events(&backend, automation, &start, &end)?;
+                calendar_tool::render_events(&backend, &start, &end, &events)
+            }
+            CalendarOperation::CreateEvent => {
+                let event = calendar_tool::CalendarEvent {
+                    title: required(&params.title, "title")?,
+                    start: calendar_tool::parse_time(&required(&params.start, "start")?)?,
+                    end: calendar_tool::parse_time(&required(&params.end, "end")?)?,
+                    notes: params.notes,
+                    calendar: None,
+                };
+                calendar_tool::create_event(&backend, automation, &event)?;
+                format!(
+                    "Created event '{}' from {} to {} using {}",
+                    event.title,
+                    event.start.format("%Y-%m-%d %H:%M %:z"),
+                    event.end.format("%Y-%m-%d %H:%M %:z"),
+                    backend.describe()
+                )
+            }
+            CalendarOperation::ListReminders => {
+                let reminders = calendar_tool::list_reminders(&backend, automation)?;
+                calendar_tool::render_reminders(&backend, &reminders)
+            }
+            CalendarOperation::CreateReminder => {
+                let reminder = calendar_tool::Reminder {
+                    title: required(&params.title, "title")?,
+                    due: optional_time(&params.due)?,
+                    notes: params.notes,
+                };
+                calendar_tool::create_reminder(&backend, automation, &reminder)?;
+                let due = reminder
+                    .due
+                    .map(|due| format!(" due {}", due.format("%Y-%m-%d %H:%M %:z")))
+                    .unwrap_or_default();
+                format!(
+                    "Created reminder '{}'{} using {}",
+                    reminder.title,
+                    due,
+                    backend.describe()
+                )
+            }
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
     /// Manage cached files and data
     #[tool(
         name = "cache",
@@ -1185,7 +2242,7 @@ impl ComputerControllerServer {
         match command {
             CacheCommand::List => {
                 let mut files = Vec::new();
-                for entry in fs::read_dir(&self.cache_dir).map_err(|e| {
+                for entry in fs::read_dir(self.ensure_cache_dir()?).map_err(|e| {
                     ErrorData::new(
                         ErrorCode::INTERNAL_ERROR,
                         format!("Failed to read cache directory: {}", e),
@@ -1215,8 +2272,9 @@ impl ComputerControllerServer {
                         None,
                     )
                 })?;
+                let cache_path = resolve_cache_path(self.ensure_cache_dir()?, path)?;
 
-                let content = fs::read_to_string(path).map_err(|e| {
+                let content = fs::read_to_string(&cache_path).map_err(|e| {
                     ErrorData::new(
                         ErrorCode::INTERNAL_ERROR,
                         format!("Failed to read file: {}", e),
@@ -1226,7 +2284,8 @@ impl ComputerControllerServer {
 
                 Ok(CallToolResult::success(vec![Content::text(format!(
                     "Content of {}:\n\n{}",
-                    path, content
+                    cache_path.display(),
+                    content
                 ))]))
             }
             CacheCommand::Delete => {
@@ -1237,8 +2296,9 @@ impl ComputerControllerServer {
                         None,
                     )
                 })?;
+                let cache_path = resolve_cache_path(self.ensure_cache_dir()?, path)?;
 
-                fs::remove_file(path).map_err(|e| {
+                fs::remove_file(&cache_path).map_err(|e| {
                     ErrorData::new(
                         ErrorCode::INTERNAL_ERROR,
                         format!("Failed to delete file: {}", e),
@@ -1247,7 +2307,7 @@ impl ComputerControllerServer {
                 })?;
 
                 // Remove from active resources if present
-                if let Ok(url) = Url::from_file_path(path) {
+                if let Ok(url) = Url::from_file_path(&cache_path) {
                     self.active_resources
                         .lock()
                         .unwrap()
@@ -1256,24 +2316,18 @@ impl ComputerControllerServer {
 
                 Ok(CallToolResult::success(vec![Content::text(format!(
                     "Deleted file: {}",
-                    path
+                    cache_path.display()
                 ))]))
             }
             CacheCommand::Clear => {
-                fs::remove_dir_all(&self.cache_dir).map_err(|e| {
+                fs::remove_dir_all(self.ensure_cache_dir()?).map_err(|e| {
                     ErrorData::new(
                         ErrorCode::INTERNAL_ERROR,
                         format!("Failed to clear cache directory: {}", e),
                         None,
                     )
                 })?;
-                fs::create_dir_all(&self.cache_dir).map_err(|e| {
-                    ErrorData::new(
-                        ErrorCode::INTERNAL_ERROR,
-                        format!("Failed to recreate cache directory: {}", e),
-                        None,
-                    )
-                })?;
+                self.ensure_cache_dir()?;
 
                 // Clear active resources
                 self.active_resources.lock().unwrap().clear();
@@ -1348,3 +2402,542 @@ impl ServerHandler for ComputerControllerServer {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn cache_with_file() -> (TempDir, PathBuf) {
+        let root = TempDir::new().unwrap();
+        let cache_dir = root.path().join("cache");
+        fs::create_dir_all(&cache_dir).unwrap();
+        fs::write(cache_dir.join("page.txt"), "cached").unwrap();
+        fs::write(root.path().join("secret.txt"), "do not read").unwrap();
+        (root, cache_dir)
+    }
+
+    fn server_with_cache_dir(cache_dir: PathBuf) -> ComputerControllerServer {
+        ComputerControllerServer {
+            cache_dir,
+            ..ComputerControllerServer::new()
+        }
+    }
+
+    #[test]
+    fn test_resolve_cache_path_accepts_files_in_cache() {
+        let (_root, cache_dir) = cache_with_file();
+        let absolute = cache_dir.join("page.txt");
+
+        assert_eq!(
+            resolve_cache_path(&cache_dir, absolute.to_str().unwrap()).unwrap(),
+            absolute
+        );
+        assert_eq!(
+            resolve_cache_path(&cache_dir, "page.txt").unwrap(),
+            absolute
+        );
+    }
+
+    #[test]
+    fn test_resolve_cache_path_rejects_escapes() {
+        let (root, cache_dir) = cache_with_file();
+        let outside = root.path().join("secret.txt");
+        let dotdot = cache_dir.join("../secret.txt");
+
+        for path in [
+            "../secret.txt",
+            dotdot.to_str().unwrap(),
+            outside.to_str().unwrap(),
+            cache_dir.to_str().unwrap(),
+            ".",
+        ] {
+            let err = resolve_cache_path(&cache_dir, path).unwrap_err();
+            assert_eq!(err.code, ErrorCode::INVALID_PARAMS, "{}", path);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_cache_path_rejects_symlinks_out_of_cache() {
+        let (root, cache_dir) = cache_with_file();
+        std::os::unix::fs::symlink(root.path().join("secret.txt"), cache_dir.join("link.txt"))
+            .unwrap();
+
+        assert!(resolve_cache_path(&cache_dir, "link.txt").is_err());
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_web_scrape_rejects_internal_urls() {
+        let (_root, cache_dir) = cache_with_file();
+        let server = server_with_cache_dir(cache_dir);
+
+        for url in [
+            "http://169.254.169.254/latest/meta-data/",
+            "file:///etc/passwd",
+        ] {
+            let err = server
+                .web_scrape(Parameters(WebScrapeParams {
+                    url: url.to_string(),
+                    save_as: SaveAsFormat::Text,
+                    session_id: None,
+                }))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::INVALID_PARAMS, "{}", url);
+        }
+    }
+
+    /// Answer every request on a local port with the raw HTTP response `respond` builds
+    async fn serve(respond: fn(&str) -> String) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = vec![0; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let response = respond(&String::from_utf8_lossy(&buf[..n]));
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn ok_response(body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    }
+
+    /// Respond with the request path as the body
+    fn echo_path(request: &str) -> String {
+        ok_response(request.split_whitespace().nth(1).unwrap_or("/"))
+    }
+
+    /// `/login` sets a session cookie and redirects home; every other page echoes the cookies
+    fn login_site(request: &str) -> String {
+        if request.starts_with("GET /login ") {
+            return "HTTP/1.1 302 Found\r\nlocation: /home\r\nset-cookie: sid=42; Path=/\r\n\
+                    content-length: 0\r\nconnection: close\r\n\r\n"
+                .to_string();
+        }
+        let cookie = request
+            .lines()
+            .find_map(|line| line.strip_prefix("cookie: "))
+            .unwrap_or("anonymous");
+        ok_response(cookie)
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_web_scrape_batch_reports_each_url() {
+        let (_root, cache_dir) = cache_with_file();
+        let server = server_with_cache_dir(cache_dir.clone());
+        let base = serve(echo_path).await;
+        std::env::set_var(url_policy::ALLOW_PRIVATE_NETWORKS_KEY, "true");
+
+        let urls = vec![
+            format!("{}/one", base),
+            "not a url".to_string(),
+            format!("{}/two", base),
+        ];
+        let result = server
+            .web_scrape_batch(Parameters(WebScrapeBatchParams {
+                urls: urls.clone(),
+                save_as: SaveAsFormat::Text,
+                max_concurrency: Some(2),
+                session_id: None,
+            }))
+            .await;
+        std::env::remove_var(url_policy::ALLOW_PRIVATE_NETWORKS_KEY);
+
+        let report = result.unwrap().content[0].as_text().unwrap().text.clone();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "Fetched 2 of 3 URLs:");
+        assert!(lines[1].starts_with(&format!("- {}: saved to ", urls[0])));
+        assert!(lines[2].starts_with("- not a url: failed: "));
+        assert!(lines[3].starts_with(&format!("- {}: saved to ", urls[2])));
+
+        for (line, expected) in [(lines[1], "/one"), (lines[3], "/two")] {
+            let path = PathBuf::from(line.split("saved to ").nth(1).unwrap());
+            assert!(path.starts_with(&cache_dir));
+            assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+        }
+        assert_eq!(server.active_resources.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_web_scrape_sessions_keep_cookies() {
+        let (_root, cache_dir) = cache_with_file();
+        let server = server_with_cache_dir(cache_dir);
+        let base = serve(login_site).await;
+        std::env::set_var(url_policy::ALLOW_PRIVATE_NETWORKS_KEY, "true");
+
+        let scrape = |path: &str, session_id: Option<&str>| {
+            let server = server.clone();
+            let params = WebScrapeParams {
+                url: format!("{}{}", base, path),
+                save_as: SaveAsFormat::Text,
+                session_id: session_id.map(str::to_string),
+            };
+            async move {
+                let result = server.web_scrape(Parameters(params)).await.unwrap();
+                let text = &result.content[0].as_text().unwrap().text;
+                let path = text.strip_prefix("Content saved to: ").unwrap();
+                fs::read_to_string(path).unwrap()
+            }
+        };
+
+        let mut pages = Vec::new();
+        // The cookie set while being redirected from /login is sent to /home
+        pages.push(scrape("/login", Some("work")).await);
+        pages.push(scrape("/account", Some("work")).await);
+        pages.push(scrape("/account", None).await);
+        pages.push(scrape("/account", Some("other")).await);
+        server
+            .web_scrape_clear_session(Parameters(WebScrapeClearSessionParams {
+                session_id: Some("work".to_string()),
+            }))
+            .await
+            .unwrap();
+        pages.push(scrape("/account", Some("work")).await);
+        std::env::remove_var(url_policy::ALLOW_PRIVATE_NETWORKS_KEY);
+
+        assert_eq!(
+            pages,
+            vec!["sid=42", "sid=42", "anonymous", "anonymous", "anonymous"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_web_scrape_batch_rejects_bad_url_counts() {
+        let (_root, cache_dir) = cache_with_file();
+        let server = server_with_cache_dir(cache_dir);
+
+        for count in [0, MAX_BATCH_URLS + 1] {
+            let err = server
+                .web_scrape_batch(Parameters(WebScrapeBatchParams {
+                    urls: vec!["https://example.com".to_string(); count],
+                    save_as: SaveAsFormat::Text,
+                    max_concurrency: None,
+                    session_id: None,
+                }))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archive_extract_registers_directory() {
+        let (_root, cache_dir) = cache_with_file();
+        let server = server_with_cache_dir(cache_dir.clone());
+        let archive = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/archives/sample.tar.gz");
+
+        let result = server
+            .archive_tool(Parameters(ArchiveToolParams {
+                path: archive.to_string_lossy().to_string(),
+                operation: ArchiveOperation::Extract,
+                paths: None,
+                destination: None,
+            }))
+            .await
+            .unwrap();
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.starts_with("Extracted 3 files and 2 directories to "));
+
+        let destination = fs::read_dir(&cache_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.is_dir())
+            .unwrap();
+        assert!(destination
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("sample_"));
+        assert!(destination.join("docs/readme.txt").exists());
+
+        let uri = Url::from_file_path(&destination).unwrap().to_string();
+        assert!(server.active_resources.lock().unwrap().contains_key(&uri));
+    }
+
+    #[tokio::test]
+    async fn test_image_resize_saves_to_cache_with_thumbnail() {
+        let (_root, cache_dir) = cache_with_file();
+        let server = server_with_cache_dir(cache_dir.clone());
+        let image = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/images/sample.png");
+        let params = |operation, format: Option<&str>| ImageToolParams {
+            path: image.to_string_lossy().to_string(),
+            operation,
+            width: Some(32),
+            height: None,
+            percent: None,
+            x: None,
+            y: None,
+            format: format.map(String::from),
+            quality: None,
+        };
+
+        let result = server
+            .image_tool(Parameters(params(ImageOperation::Resize, Some("jpeg"))))
+            .await
+            .unwrap();
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.starts_with("Image resized from 64x48 to 32x24 jpg, saved to: "));
+        assert!(result.content[1].as_image().is_some());
+
+        let saved = PathBuf::from(text.split("saved to: ").nth(1).unwrap());
+        assert!(saved.starts_with(&cache_dir));
+        assert_eq!(saved.extension().unwrap(), "jpg");
+        let uri = Url::from_file_path(&saved).unwrap().to_string();
+        assert!(server.active_resources.lock().unwrap().contains_key(&uri));
+
+        let result = server
+            .image_tool(Parameters(params(ImageOperation::GetInfo, None)))
+            .await
+            .unwrap();
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.contains("Format: image/png"));
+        assert!(text.contains("Dimensions: 64x48"));
+
+        let err = server
+            .image_tool(Parameters(params(ImageOperation::Convert, None)))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_cache_view_and_delete_stay_in_cache() {
+        let (root, cache_dir) = cache_with_file();
+        let server = server_with_cache_dir(cache_dir.clone());
+        let outside = root.path().join("secret.txt");
+
+        for command in [CacheCommand::View, CacheCommand::Delete] {
+            let err = server
+                .cache(Parameters(CacheParams {
+                    command,
+                    path: Some(outside.to_string_lossy().to_string()),
+                }))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        }
+        assert!(outside.exists());
+
+        server
+            .cache(Parameters(CacheParams {
+                command: CacheCommand::Delete,
+                path: Some("page.txt".to_string()),
+            }))
+            .await
+            .unwrap();
+        assert!(!cache_dir.join("page.txt").exists());
+    }
+
+    fn server_with_env(
+        working_dir: Option<&Path>,
+        scope: Option<&str>,
+    ) -> ComputerControllerServer {
+        temp_env::with_vars(
+            [
+                ("GOOSE_WORKING_DIR", working_dir.map(|dir| dir.as_os_str())),
+                (CACHE_SCOPE_ENV, scope.map(std::ffi::OsStr::new)),
+            ],
+            ComputerControllerServer::new,
+        )
+    }
+
+    async fn list_cache(server: &ComputerControllerServer) -> String {
+        let result = server
+            .cache(Parameters(CacheParams {
+                command: CacheCommand::List,
+                path: None,
+            }))
+            .await
+            .unwrap();
+        result.content[0].as_text().unwrap().text.clone()
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_project_scoped_cache_is_isolated() {
+        let project_a = TempDir::new().unwrap();
+        let project_b = TempDir::new().unwrap();
+        let server_a = server_with_env(Some(project_a.path()), None);
+        let server_b = server_with_env(Some(project_b.path()), None);
+        let global = server_with_env(None, None);
+
+        let expected = project_a.path().join(".goose/cache/computer_controller");
+        assert_eq!(server_a.cache_dir, expected);
+        assert!(server_a
+            .instructions
+            .contains(&format!("Cache directory: {}", expected.display())));
+        assert!(!global.cache_dir.starts_with(project_a.path()));
+        // Nothing is created until something gets cached
+        assert!(!expected.exists());
+
+        let saved = server_a
+            .save_to_cache(b"project a", "page", "txt")
+            .await
+            .unwrap();
+        server_a.register_as_resource(&saved, "text").unwrap();
+        assert!(saved.starts_with(&expected));
+
+        assert!(list_cache(&server_a)
+            .await
+            .contains(&saved.display().to_string()));
+        assert_eq!(list_cache(&server_b).await, "Cached files:\n");
+        let err = server_b
+            .cache(Parameters(CacheParams {
+                command: CacheCommand::View,
+                path: Some(saved.to_string_lossy().to_string()),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+        server_a
+            .cache(Parameters(CacheParams {
+                command: CacheCommand::Clear,
+                path: None,
+            }))
+            .await
+            .unwrap();
+        assert!(!saved.exists());
+        assert!(server_a.active_resources.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_cache_scope_env_overrides_default() {
+        let project = TempDir::new().unwrap();
+        let global = server_with_env(None, None);
+
+        let server = server_with_env(Some(project.path()), Some("global"));
+        assert_eq!(server.cache_dir, global.cache_dir);
+
+        let cwd = std::env::current_dir().unwrap();
+        let server = server_with_env(None, Some("project"));
+        assert_eq!(
+            server.cache_dir,
+            cwd.join(".goose/cache/computer_controller")
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_automation_script_reports_exit_code() {
+        let server = ComputerControllerServer::new();
+        let run = |script: &str| {
+            server.automation_script(Parameters(AutomationScriptParams {
+                language: ScriptLanguage::Shell,
+                script: script.to_string(),
+                save_output: false,
+                cwd: None,
+            }))
+        };
+
+        let result = run("echo done").await.unwrap();
+        assert_eq!(
+            result.structured_content,
+            Some(serde_json::json!({"success": true, "exit_code": 0}))
+        );
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.starts_with("Script completed successfully."));
+
+        let result = run("echo oops >&2\nexit 3").await.unwrap();
+        assert_eq!(
+            result.structured_content,
+            Some(serde_json::json!({"success": false, "exit_code": 3}))
+        );
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.starts_with("Script failed with error code"));
+        assert!(text.contains("oops"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_automation_script_runs_in_cwd() {
+        let server = ComputerControllerServer::new();
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("marker.txt"), "here").unwrap();
+
+        let result = server
+            .automation_script(Parameters(AutomationScriptParams {
+                language: ScriptLanguage::Shell,
+                script: "cat marker.txt".to_string(),
+                save_output: false,
+                cwd: Some(dir.path().display().to_string()),
+            }))
+            .await
+            .unwrap();
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.starts_with("Script completed successfully."));
+        assert!(text.contains("here"));
+
+        let err = server
+            .automation_script(Parameters(AutomationScriptParams {
+                language: ScriptLanguage::Shell,
+                script: "pwd".to_string(),
+                save_output: false,
+                cwd: Some(dir.path().join("missing").display().to_string()),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        assert!(err.message.contains("does not exist"));
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_disabled_tools_are_left_out() {
+        let server = temp_env::with_var(
+            DISABLED_TOOLS_KEY,
+            Some("computer_control, automation_script"),
+            ComputerControllerServer::new,
+        );
+
+        let tools: Vec<String> = server
+            .tool_router
+            .list_all()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect();
+        assert!(!tools.contains(&"computer_control".to_string()));
+        assert!(!tools.contains(&"automation_script".to_string()));
+        assert!(tools.contains(&"web_scrape".to_string()));
+        assert!(!server.instructions.contains("automation_script\n"));
+        assert!(!server.instructions.contains("computer_control\n"));
+        assert!(!server.instructions.contains("Here are some extra tools"));
+
+        let err = server
+            .computer_control(Parameters(ComputerControlParams {
+                script: "echo hi".to_string(),
+                save_output: false,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_REQUEST);
+
+        let enabled = temp_env::with_var(
+            DISABLED_TOOLS_KEY,
+            Some("computer_control"),
+            ComputerControllerServer::new,
+        );
+        assert!(enabled.tool_router.has_route("automation_script"));
+        assert!(!enabled.tool_router.has_route("computer_control"));
+        assert!(enabled.instructions.contains("automation_script\n"));
+    }
+}
@@ -0,0 +1,1052 @@
+//! AcroForm support for `pdf_tool`: listing the fields of a fillable PDF and filling them in.
+//!
+//! Filled text and choice fields get a simple Helvetica appearance so every viewer shows the
+//! new value, and the form asks viewers to regenerate appearances in their own style. Flattening
+//! draws each field's appearance into the page and removes the form, leaving static content.
+
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use rmcp::model::{ErrorCode, ErrorData};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Field flags, from the PDF specification
+const FLAG_READ_ONLY: i64 = 1;
+const FLAG_RADIO: i64 = 1 << 15;
+const FLAG_PUSH_BUTTON: i64 = 1 << 16;
+const FLAG_COMBO: i64 = 1 << 17;
+const FLAG_EDIT: i64 = 1 << 18;
+/// Annotation flag of widgets that are never shown
+const ANNOT_HIDDEN: i64 = 1 << 1;
+
+const OFF_STATE: &[u8] = b"Off";
+/// Font size of generated appearances when the field's own is automatic or unreadable
+const DEFAULT_FONT_SIZE: f32 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormFieldKind {
+    Text,
+    Checkbox,
+    Radio,
+    ComboBox,
+    ListBox,
+    PushButton,
+    Signature,
+}
+
+impl FormFieldKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FormFieldKind::Text => "text",
+            FormFieldKind::Checkbox => "checkbox",
+            FormFieldKind::Radio => "radio",
+            FormFieldKind::ComboBox => "combo box",
+            FormFieldKind::ListBox => "list box",
+            FormFieldKind::PushButton => "push button",
+            FormFieldKind::Signature => "signature",
+        }
+    }
+}
+
+/// A choice the user can pick: the label shown and the value stored in the PDF
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormOption {
+    pub label: String,
+    pub export: Vec<u8>,
+}
+
+/// A terminal form field, with the widgets that show it on the page
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormField {
+    /// Fully qualified name, with the names of parent fields joined by dots
+    pub name: String,
+    pub kind: FormFieldKind,
+    /// Checkboxes are "true" or "false"; radio groups the label of the selected option
+    pub value: Option<String>,
+    /// Option labels of choice fields, and the on states of checkboxes and radio groups
+    pub options: Vec<FormOption>,
+    pub read_only: bool,
+    /// Whether a choice field also accepts text that isn't one of its options
+    pub editable: bool,
+    pub id: ObjectId,
+    pub widgets: Vec<ObjectId>,
+}
+
+impl FormField {
+    pub fn option_labels(&self) -> Vec<&str> {
+        self.options.iter().map(|o| o.label.as_str()).collect()
+    }
+}
+
+fn invalid_params(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INVALID_PARAMS, message, None)
+}
+
+fn internal_error(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INTERNAL_ERROR, message, None)
+}
+
+/// Follow a reference to the object it points at
+fn resolve<'a>(doc: &'a Document, object: &'a Object) -> Option<&'a Object> {
+    match object {
+        Object::Reference(id) => doc.get_object(*id).ok(),
+        other => Some(other),
+    }
+}
+
+fn resolve_dict<'a>(doc: &'a Document, object: &'a Object) -> Option<&'a Dictionary> {
+    resolve(doc, object).and_then(|o| o.as_dict().ok())
+}
+
+fn references(doc: &Document, object: &Object) -> Vec<ObjectId> {
+    resolve(doc, object)
+        .and_then(|o| o.as_array().ok())
+        .map(|items| items.iter().filter_map(|o| o.as_reference().ok()).collect())
+        .unwrap_or_default()
+}
+
+fn text_value(doc: &Document, object: &Object) -> Option<String> {
+    resolve(doc, object).and_then(|o| lopdf::decode_text_string(o).ok())
+}
+
+/// A text string as PDF stores it: plain for ASCII, UTF-16 with a byte order mark otherwise
+fn text_string(value: &str) -> Object {
+    if value.is_ascii() {
+        Object::string_literal(value)
+    } else {
+        let mut bytes = vec![0xFE, 0xFF];
+        for unit in value.encode_utf16() {
+            bytes.extend(unit.to_be_bytes());
+        }
+        Object::String(bytes, StringFormat::Hexadecimal)
+    }
+}
+
+fn catalog_id(doc: &Document) -> Option<ObjectId> {
+    doc.trailer.get(b"Root").ok()?.as_reference().ok()
+}
+
+fn acro_form(doc: &Document) -> Option<&Dictionary> {
+    let catalog = doc.get_dictionary(catalog_id(doc)?).ok()?;
+    resolve_dict(doc, catalog.get(b"AcroForm").ok()?)
+}
+
+/// Every terminal field of the document's form, in the order the form lists them
+pub fn list_form_fields(doc: &Document) -> Vec<FormField> {
+    let mut fields = Vec::new();
+    if let Some(form) = acro_form(doc) {
+        if let Ok(roots) = form.get(b"Fields") {
+            for id in references(doc, roots) {
+                collect_fields(doc, id, None, None, 0, &mut fields, &mut HashSet::new());
+            }
+        }
+    }
+    fields
+}
+
+fn collect_fields(
+    doc: &Document,
+    id: ObjectId,
+    parent_name: Option<&str>,
+    parent_type: Option<&[u8]>,
+    parent_flags: i64,
+    fields: &mut Vec<FormField>,
+    visited: &mut HashSet<ObjectId>,
+) {
+    // Malformed forms can loop back on themselves
+    if !visited.insert(id) {
+        return;
+    }
+    let Ok(dict) = doc.get_dictionary(id) else {
+        return;
+    };
+
+    let partial = dict.get(b"T").ok().and_then(|t| text_value(doc, t));
+    let name = match (parent_name, partial) {
+        (Some(parent), Some(partial)) => format!("{}.{}", parent, partial),
+        (None, Some(partial)) => partial,
+        (Some(parent), None) => parent.to_string(),
+        (None, None) => return,
+    };
+    let field_type = dict
+        .get(b"FT")
+        .ok()
+        .and_then(|t| t.as_name().ok())
+        .or(parent_type);
+    let flags = dict
+        .get(b"Ff")
+        .ok()
+        .and_then(|f| f.as_i64().ok())
+        .unwrap_or(parent_flags);
+
+    // Kids with names are fields of their own; kids without are this field's widgets
+    let kids = dict
+        .get(b"Kids")
+        .map(|kids| references(doc, kids))
+        .unwrap_or_default();
+    let (child_fields, widgets): (Vec<ObjectId>, Vec<ObjectId>) =
+        kids.into_iter().partition(|kid| {
+            doc.get_dictionary(*kid)
+                .map(|kid| kid.has(b"T"))
+                .unwrap_or(false)
+        });
+    if !child_fields.is_empty() {
+        for child in child_fields {
+            collect_fields(doc, child, Some(&name), field_type, flags, fields, visited);
+        }
+        return;
+    }
+    let widgets = if widgets.is_empty() {
+        vec![id]
+    } else {
+        widgets
+    };
+
+    let kind = match field_type {
+        Some(b"Tx") => FormFieldKind::Text,
+        Some(b"Btn") if flags & FLAG_PUSH_BUTTON != 0 => FormFieldKind::PushButton,
+        Some(b"Btn") if flags & FLAG_RADIO != 0 => FormFieldKind::Radio,
+        Some(b"Btn") => FormFieldKind::Checkbox,
+        Some(b"Ch") if flags & FLAG_COMBO != 0 => FormFieldKind::ComboBox,
+        Some(b"Ch") => FormFieldKind::ListBox,
+        Some(b"Sig") => FormFieldKind::Signature,
+        _ => return,
+    };
+
+    let current = dict.get(b"V").ok().and_then(|v| resolve(doc, v));
+    let (options, value) = match kind {
+        FormFieldKind::Checkbox | FormFieldKind::Radio => {
+            let options = on_states(doc, &widgets);
+            let selected = current
+                .and_then(|v| v.as_name().ok())
+                .filter(|state| *state != OFF_STATE);
+            let value = if kind == FormFieldKind::Checkbox {
+                Some(selected.is_some().to_string())
+            } else {
+                selected.map(|state| String::from_utf8_lossy(state).into_owned())
+            };
+            (options, value)
+        }
+        FormFieldKind::ComboBox | FormFieldKind::ListBox => {
+            let options = choice_options(doc, dict);
+            let value = current.and_then(|v| match v {
+                Object::Array(selected) => {
+                    let labels: Vec<String> = selected
+                        .iter()
+                        .filter_map(|s| text_value(doc, s))
+                        .map(|s| choice_label(&options, &s))
+                        .collect();
+                    Some(labels.join(", "))
+                }
+                other => text_value(doc, other).map(|s| choice_label(&options, &s)),
+            });
+            (options, value)
+        }
+        _ => (Vec::new(), current.and_then(|v| text_value(doc, v))),
+    };
+
+    fields.push(FormField {
+        name,
+        kind,
+        value,
+        options,
+        read_only: flags & FLAG_READ_ONLY != 0,
+        editable: flags & FLAG_EDIT != 0,
+        id,
+        widgets,
+    });
+}
+
+/// The label of a choice field's option with this export value
+fn choice_label(options: &[FormOption], export: &str) -> String {
+    options
+        .iter()
+        .find(|o| o.export == export.as_bytes())
+        .map(|o| o.label.clone())
+        .unwrap_or_else(|| export.to_string())
+}
+
+fn choice_options(doc: &Document, dict: &Dictionary) -> Vec<FormOption> {
+    let Some(items) = dict
+        .get(b"Opt")
+        .ok()
+        .and_then(|o| resolve(doc, o))
+        .and_then(|o| o.as_array().ok())
+    else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| match resolve(doc, item)? {
+            // [export value, label] pairs
+            Object::Array(pair) if pair.len() == 2 => {
+                let export = text_value(doc, &pair[0])?;
+                let label = text_value(doc, &pair[1])?;
+                Some(FormOption {
+                    label,
+                    export: export.into_bytes(),
+                })
+            }
+            other => {
+                let label = text_value(doc, other)?;
+                Some(FormOption {
+                    export: label.clone().into_bytes(),
+                    label,
+                })
+            }
+        })
+        .collect()
+}
+
+/// The appearance states of each widget other than Off, which name what it stands for
+fn on_states(doc: &Document, widgets: &[ObjectId]) -> Vec<FormOption> {
+    let mut options: Vec<FormOption> = Vec::new();
+    for widget in widgets {
+        for state in normal_appearances(doc, *widget).into_keys() {
+            if state != OFF_STATE && !options.iter().any(|o| o.export == state) {
+                options.push(FormOption {
+                    label: String::from_utf8_lossy(&state).into_owned(),
+                    export: state,
+                });
+            }
+        }
+    }
+    options
+}
+
+/// A widget's normal appearance streams by state name
+fn normal_appearances(doc: &Document, widget: ObjectId) -> BTreeMap<Vec<u8>, ObjectId> {
+    let normal = doc
+        .get_dictionary(widget)
+        .ok()
+        .and_then(|w| w.get(b"AP").ok())
+        .and_then(|ap| resolve_dict(doc, ap))
+        .and_then(|ap| ap.get(b"N").ok())
+        .and_then(|n| resolve(doc, n));
+    match normal {
+        Some(Object::Dictionary(states)) => states
+            .iter()
+            .filter_map(|(state, stream)| Some((state.clone(), stream.as_reference().ok()?)))
+            .collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+/// The stream a widget is currently drawn with
+fn current_appearance(doc: &Document, widget: ObjectId) -> Option<ObjectId> {
+    let dict = doc.get_dictionary(widget).ok()?;
+    let normal = resolve_dict(doc, dict.get(b"AP").ok()?)?.get(b"N").ok()?;
+    match normal {
+        Object::Reference(id) if doc.get_object(*id).ok()?.as_stream().is_ok() => Some(*id),
+        _ => {
+            let state = dict.get(b"AS").ok()?.as_name().ok()?;
+            normal_appearances(doc, widget).get(state).copied()
+        }
+    }
+}
+
+/// What a field is set to, in the form the PDF stores it
+enum FieldUpdate {
+    Text(String),
+    /// The appearance state to switch to, Off to clear
+    State(Vec<u8>),
+    Choice {
+        export: String,
+        label: String,
+    },
+}
+
+fn value_as_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn mismatch(field: &FormField, expected: String, value: &Value) -> ErrorData {
+    invalid_params(format!(
+        "Field '{}' is a {} field and takes {}, got {}",
+        field.name,
+        field.kind.label(),
+        expected,
+        value
+    ))
+}
+
+fn quoted(labels: &[&str]) -> String {
+    labels
+        .iter()
+        .map(|l| format!("'{}'", l))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn field_update(field: &FormField, value: &Value) -> Result<FieldUpdate, ErrorData> {
+    let labels = field.option_labels();
+    match field.kind {
+        FormFieldKind::Text => value_as_text(value)
+            .map(FieldUpdate::Text)
+            .ok_or_else(|| mismatch(field, "text".to_string(), value)),
+        FormFieldKind::Checkbox => {
+            let on_state = field
+                .options
+                .first()
+                .map(|o| o.export.clone())
+                .unwrap_or_else(|| b"Yes".to_vec());
+            let checked = match value {
+                Value::Bool(b) => Some(*b),
+                Value::String(s) if s.eq_ignore_ascii_case("true") => Some(true),
+                Value::String(s) if s.eq_ignore_ascii_case("false") || s == "Off" => Some(false),
+                Value::String(s) if labels.contains(&s.as_str()) => Some(true),
+                _ => None,
+            };
+            checked
+                .map(|checked| {
+                    FieldUpdate::State(if checked {
+                        on_state
+                    } else {
+                        OFF_STATE.to_vec()
+                    })
+                })
+                .ok_or_else(|| {
+                    let mut accepted = vec!["true", "false"];
+                    accepted.extend(labels.iter());
+                    mismatch(field, format!("one of {}", quoted(&accepted)), value)
+                })
+        }
+        FormFieldKind::Radio => match value {
+            Value::Bool(false) => Ok(FieldUpdate::State(OFF_STATE.to_vec())),
+            Value::String(s) if s.eq_ignore_ascii_case("false") || s == "Off" => {
+                Ok(FieldUpdate::State(OFF_STATE.to_vec()))
+            }
+            Value::Bool(true) if field.options.len() == 1 => {
+                Ok(FieldUpdate::State(field.options[0].export.clone()))
+            }
+            Value::String(s) if labels.contains(&s.as_str()) => {
+                Ok(FieldUpdate::State(s.as_bytes().to_vec()))
+            }
+            _ => Err(mismatch(
+                field,
+                format!("one of {} (or false to clear it)", quoted(&labels)),
+                value,
+            )),
+        },
+        FormFieldKind::ComboBox | FormFieldKind::ListBox => {
+            let text = value_as_text(value)
+                .ok_or_else(|| mismatch(field, format!("one of {}", quoted(&labels)), value))?;
+            match field
+                .options
+                .iter()
+                .find(|o| o.label == text || o.export == text.as_bytes())
+            {
+                Some(option) => Ok(FieldUpdate::Choice {
+                    export: String::from_utf8_lossy(&option.export).into_owned(),
+                    label: option.label.clone(),
+                }),
+                None if field.editable || field.options.is_empty() => Ok(FieldUpdate::Choice {
+                    export: text.clone(),
+                    label: text,
+                }),
+                None => Err(mismatch(
+                    field,
+                    format!("one of {}", quoted(&labels)),
+                    value,
+                )),
+            }
+        }
+        FormFieldKind::PushButton | FormFieldKind::Signature => Err(invalid_params(format!(
+            "Field '{}' is a {} field, which cannot be filled",
+            field.name,
+            field.kind.label()
+        ))),
+    }
+}
+
+/// Set form fields by name. Every value is checked before any is written, so a bad value
+/// leaves the document untouched. With `flatten`, the fields are then drawn into the pages
+/// and the form is removed.
+pub fn fill_form(
+    doc: &mut Document,
+    values: &HashMap<String, Value>,
+    flatten: bool,
+) -> Result<(), ErrorData> {
+    let fields = list_form_fields(doc);
+    if fields.is_empty() {
+        return Err(invalid_params("The PDF has no form fields".to_string()));
+    }
+
+    let mut unknown: Vec<&str> = values
+        .keys()
+        .filter(|name| !fields.iter().any(|f| &f.name == *name))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        unknown.sort();
+        let valid: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+        return Err(invalid_params(format!(
+            "Unknown form fields: {}. Valid fields are: {}",
+            unknown.join(", "),
+            valid.join(", ")
+        )));
+    }
+
+    let mut updates = Vec::new();
+    for field in &fields {
+        if let Some(value) = values.get(&field.name) {
+            if field.read_only {
+                return Err(invalid_params(format!(
+                    "Field '{}' is read-only",
+                    field.name
+                )));
+            }
+            updates.push((field, field_update(field, value)?));
+        }
+    }
+
+    for (field, update) in updates {
+        apply_update(doc, field, update)?;
+    }
+
+    if flatten {
+        flatten_form(doc)
+    } else {
+        // Viewers draw the fields again in their own style
+        let form = form_dict_mut(doc)?;
+        form.set("NeedAppearances", Object::Boolean(true));
+        Ok(())
+    }
+}
+
+fn dict_mut(doc: &mut Document, id: ObjectId) -> Result<&mut Dictionary, ErrorData> {
+    doc.get_object_mut(id)
+        .and_then(Object::as_dict_mut)
+        .map_err(|e| internal_error(format!("Failed to update PDF object {:?}: {}", id, e)))
+}
+
+fn form_dict_mut(doc: &mut Document) -> Result<&mut Dictionary, ErrorData> {
+    let catalog =
+        catalog_id(doc).ok_or_else(|| internal_error("The PDF has no catalog".to_string()))?;
+    match locate(dict_mut(doc, catalog)?, b"AcroForm") {
+        Location::Object(id) => dict_mut(doc, id),
+        _ => dict_mut(doc, catalog)?
+            .get_mut(b"AcroForm")
+            .and_then(Object::as_dict_mut)
+            .map_err(|e| internal_error(format!("Failed to update the PDF form: {}", e))),
+    }
+}
+
+fn apply_update(
+    doc: &mut Document,
+    field: &FormField,
+    update: FieldUpdate,
+) -> Result<(), ErrorData> {
+    match update {
+        FieldUpdate::Text(text) => {
+            dict_mut(doc, field.id)?.set("V", text_string(&text));
+            set_text_appearances(doc, field, &text)
+        }
+        FieldUpdate::Choice { export, label } => {
+            dict_mut(doc, field.id)?.set("V", text_string(&export));
+            set_text_appearances(doc, field, &label)
+        }
+        FieldUpdate::State(state) => {
+            dict_mut(doc, field.id)?.set("V", Object::Name(state.clone()));
+            for widget in &field.widgets {
+                let shown = if normal_appearances(doc, *widget).contains_key(&state) {
+                    state.clone()
+                } else {
+                    OFF_STATE.to_vec()
+                };
+                dict_mut(doc, *widget)?.set("AS", Object::Name(shown));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn number(object: &Object) -> Option<f32> {
+    object.as_float().ok()
+}
+
+/// [llx, lly, urx, ury] of a widget's /Rect, or of a stream's /BBox
+fn rectangle(doc: &Document, dict: &Dictionary, key: &[u8]) -> Option<[f32; 4]> {
+    let values: Vec<f32> = resolve(doc, dict.get(key).ok()?)?
+        .as_array()
+        .ok()?
+        .iter()
+        .filter_map(number)
+        .collect();
+    let [x1, y1, x2, y2] = values.try_into().ok()?;
+    Some([x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2)])
+}
+
+/// The font size a field's /DA asks for, falling back when it is automatic (0)
+fn font_size(default_appearance: Option<String>, height: f32) -> f32 {
+    let size = default_appearance.and_then(|da| {
+        let tokens: Vec<&str> = da.split_whitespace().collect();
+        let tf = tokens.iter().position(|t| *t == "Tf")?;
+        tokens.get(tf.checked_sub(1)?)?.parse::<f32>().ok()
+    });
+    match size {
+        Some(size) if size > 0.0 => size,
+        _ => DEFAULT_FONT_SIZE.min((height - 4.0).max(4.0)),
+    }
+}
+
+/// Text as a literal string in Helvetica's WinAnsi encoding
+fn pdf_literal(text: &str) -> Vec<u8> {
+    let mut bytes = vec![b'('];
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => bytes.extend([b'\\', c as u8]),
+            '\n' | '\r' => bytes.push(b' '),
+            c if (c as u32) < 0x100 => bytes.push(c as u32 as u8),
+            _ => bytes.push(b'?'),
+        }
+    }
+    bytes.push(b')');
+    bytes
+}
+
+/// Give each widget of a text or choice field an appearance showing `text`
+fn set_text_appearances(
+    doc: &mut Document,
+    field: &FormField,
+    text: &str,
+) -> Result<(), ErrorData> {
+    let inherited_da = doc
+        .get_dictionary(field.id)
+        .ok()
+        .and_then(|d| d.get(b"DA").ok())
+        .and_then(|da| text_value(doc, da))
+        .or_else(|| {
+            acro_form(doc)
+                .and_then(|form| form.get(b"DA").ok())
+                .and_then(|da| text_value(doc, da))
+        });
+
+    for widget in &field.widgets {
+        let Ok(widget_dict) = doc.get_dictionary(*widget) else {
+            continue;
+        };
+        let Some([x1, y1, x2, y2]) = rectangle(doc, widget_dict, b"Rect") else {
+            continue;
+        };
+        let (width, height) = (x2 - x1, y2 - y1);
+        let da = widget_dict
+            .get(b"DA")
+            .ok()
+            .and_then(|da| text_value(doc, da))
+            .or_else(|| inherited_da.clone());
+        let size = font_size(da, height);
+        let baseline = ((height - size) / 2.0 + size * 0.22).max(1.0);
+
+        let mut content = format!(
+            "/Tx BMC\nq\n1 1 {:.2} {:.2} re W n\nBT\n/Helv {:.2} Tf\n0 g\n2 {:.2} Td\n",
+            (width - 2.0).max(0.0),
+            (height - 2.0).max(0.0),
+            size,
+            baseline
+        )
+        .into_bytes();
+        content.extend(pdf_literal(text));
+        content.extend_from_slice(b" Tj\nET\nQ\nEMC\n");
+
+        let appearance = Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Form",
+                "BBox" => vec![
+                    Object::Integer(0),
+                    Object::Integer(0),
+                    Object::Real(width),
+                    Object::Real(height),
+                ],
+                "Resources" => dictionary! {
+                    "Font" => dictionary! {
+                        "Helv" => dictionary! {
+                            "Type" => "Font",
+                            "Subtype" => "Type1",
+                            "BaseFont" => "Helvetica",
+                            "Encoding" => "WinAnsiEncoding",
+                        },
+                    },
+                },
+            },
+            content,
+        );
+        let appearance_id = doc.add_object(appearance);
+        dict_mut(doc, *widget)?.set(
+            "AP",
+            dictionary! { "N" => Object::Reference(appearance_id) },
+        );
+    }
+    Ok(())
+}
+
+/// Draw every visible widget of the form into its page and remove the form
+fn flatten_form(doc: &mut Document) -> Result<(), ErrorData> {
+    let fields = list_form_fields(doc);
+    // Values set by other tools may have no appearance to draw yet
+    for field in &fields {
+        let shows_text = matches!(
+            field.kind,
+            FormFieldKind::Text | FormFieldKind::ComboBox | FormFieldKind::ListBox
+        );
+        if let (true, Some(value)) = (shows_text, &field.value) {
+            if field
+                .widgets
+                .iter()
+                .any(|w| current_appearance(doc, *w).is_none())
+            {
+                set_text_appearances(doc, field, value)?;
+            }
+        }
+    }
+
+    let widgets: HashSet<ObjectId> = fields
+        .iter()
+        .flat_map(|f| f.widgets.iter().copied())
+        .collect();
+
+    for page_id in doc.get_pages().into_values() {
+        let annots = doc
+            .get_dictionary(page_id)
+            .ok()
+            .and_then(|page| page.get(b"Annots").ok())
+            .map(|annots| references(doc, annots))
+            .unwrap_or_default();
+        let (flattened, kept): (Vec<ObjectId>, Vec<ObjectId>) =
+            annots.into_iter().partition(|a| widgets.contains(a));
+        if flattened.is_empty() {
+            continue;
+        }
+
+        let mut content = Vec::new();
+        for (index, widget) in flattened.iter().enumerate() {
+            let Some((appearance, x, y)) = placed_appearance(doc, *widget) else {
+                continue;
+            };
+            let name = format!("GooseForm{}", index);
+            add_xobject(doc, page_id, &name, appearance)?;
+            content.extend(format!("q 1 0 0 1 {:.2} {:.2} cm /{} Do Q\n", x, y, name).bytes());
+        }
+        append_page_content(doc, page_id, content)?;
+
+        let page = dict_mut(doc, page_id)?;
+        if kept.is_empty() {
+            page.remove(b"Annots");
+        } else {
+            page.set(
+                "Annots",
+                kept.into_iter().map(Object::Reference).collect::<Vec<_>>(),
+            );
+        }
+    }
+
+    if let Some(catalog) = catalog_id(doc) {
+        dict_mut(doc, catalog)?.remove(b"AcroForm");
+    }
+    Ok(())
+}
+
+/// A visible widget's appearance stream and where its origin goes on the page
+fn placed_appearance(doc: &mut Document, widget: ObjectId) -> Option<(ObjectId, f32, f32)> {
+    let dict = doc.get_dictionary(widget).ok()?;
+    let annotation_flags = dict
+        .get(b"F")
+        .ok()
+        .and_then(|f| f.as_i64().ok())
+        .unwrap_or(0);
+    if annotation_flags & ANNOT_HIDDEN != 0 {
+        return None;
+    }
+    let [x, y, _, _] = rectangle(doc, dict, b"Rect")?;
+    let appearance = current_appearance(doc, widget)?;
+    let stream_dict = &doc.get_object(appearance).ok()?.as_stream().ok()?.dict;
+    let [bx, by, _, _] = rectangle(doc, stream_dict, b"BBox").unwrap_or([0.0; 4]);
+
+    // Appearance streams are form XObjects, though some writers leave that implicit
+    let stream = doc.get_object_mut(appearance).ok()?.as_stream_mut().ok()?;
+    stream.dict.set("Type", "XObject");
+    stream.dict.set("Subtype", "Form");
+    Some((appearance, x - bx, y - by))
+}
+
+/// Where a dictionary-valued entry lives, so it can be changed in place
+enum Location {
+    Inline,
+    Object(ObjectId),
+    Missing,
+}
+
+fn locate(dict: &Dictionary, key: &[u8]) -> Location {
+    match dict.get(key) {
+        Ok(Object::Reference(id)) => Location::Object(*id),
+        Ok(_) => Location::Inline,
+        Err(_) => Location::Missing,
+    }
+}
+
+/// Resources a page inherits from the page tree when it has none of its own
+fn inherited_resources(doc: &Document, page_id: ObjectId) -> Dictionary {
+    let mut current = doc.get_dictionary(page_id).ok();
+    let mut visited = HashSet::new();
+    while let Some(dict) = current {
+        if let Some(resources) = dict
+            .get(b"Resources")
+            .ok()
+            .and_then(|r| resolve_dict(doc, r))
+        {
+            return resources.clone();
+        }
+        current = dict
+            .get(b"Parent")
+            .ok()
+            .and_then(|p| p.as_reference().ok())
+            .filter(|p| visited.insert(*p))
+            .and_then(|p| doc.get_dictionary(p).ok());
+    }
+    Dictionary::new()
+}
+
+fn add_xobject(
+    doc: &mut Document,
+    page_id: ObjectId,
+    name: &str,
+    xobject: ObjectId,
+) -> Result<(), ErrorData> {
+    let page = doc
+        .get_dictionary(page_id)
+        .map_err(|e| internal_error(format!("Failed to read PDF page: {}", e)))?;
+    let resources_location = locate(page, b"Resources");
+    if let Location::Missing = resources_location {
+        let resources = inherited_resources(doc, page_id);
+        dict_mut(doc, page_id)?.set("Resources", resources);
+    }
+    let resources = match resources_location {
+        Location::Object(id) => dict_mut(doc, id)?,
+        _ => dict_mut(doc, page_id)?
+            .get_mut(b"Resources")
+            .and_then(Object::as_dict_mut)
+            .map_err(|e| internal_error(format!("Failed to update page resources: {}", e)))?,
+    };
+
+    let xobjects_location = locate(resources, b"XObject");
+    if let Location::Missing = xobjects_location {
+        resources.set("XObject", Dictionary::new());
+    }
+    let xobjects = match xobjects_location {
+        Location::Object(id) => dict_mut(doc, id)?,
+        _ => resources
+            .get_mut(b"XObject")
+            .and_then(Object::as_dict_mut)
+            .map_err(|e| internal_error(format!("Failed to update page resources: {}", e)))?,
+    };
+    xobjects.set(name, Object::Reference(xobject));
+    Ok(())
+}
+
+/// Draw `content` over the page, isolated from the graphics state the page leaves behind
+fn append_page_content(
+    doc: &mut Document,
+    page_id: ObjectId,
+    content: Vec<u8>,
+) -> Result<(), ErrorData> {
+    let existing = doc
+        .get_dictionary(page_id)
+        .ok()
+        .and_then(|page| page.get(b"Contents").ok())
+        .map(|contents| match contents {
+            Object::Reference(id) if doc.get_object(*id).is_ok_and(|o| o.as_stream().is_ok()) => {
+                vec![*id]
+            }
+            other => references(doc, other),
+        })
+        .unwrap_or_default();
+
+    let save = doc.add_object(Stream::new(Dictionary::new(), b"q\n".to_vec()));
+    let mut restore_and_draw = b"Q\n".to_vec();
+    restore_and_draw.extend(content);
+    let draw = doc.add_object(Stream::new(Dictionary::new(), restore_and_draw));
+
+    let mut contents = vec![Object::Reference(save)];
+    contents.extend(existing.into_iter().map(Object::Reference));
+    contents.push(Object::Reference(draw));
+    dict_mut(doc, page_id)?.set("Contents", contents);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn form_pdf() -> Document {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/form.pdf");
+        Document::load(path).unwrap()
+    }
+
+    fn field<'a>(fields: &'a [FormField], name: &str) -> &'a FormField {
+        fields.iter().find(|f| f.name == name).unwrap()
+    }
+
+    fn values(pairs: Value) -> HashMap<String, Value> {
+        serde_json::from_value(pairs).unwrap()
+    }
+
+    #[test]
+    fn test_list_form_fields() {
+        let fields = list_form_fields(&form_pdf());
+        let names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["full_name", "amount", "approved", "payment", "department"]
+        );
+
+        let full_name = field(&fields, "full_name");
+        assert_eq!(full_name.kind, FormFieldKind::Text);
+        assert_eq!(full_name.value.as_deref(), Some("Jane Doe"));
+        assert_eq!(field(&fields, "amount").value, None);
+
+        let approved = field(&fields, "approved");
+        assert_eq!(approved.kind, FormFieldKind::Checkbox);
+        assert_eq!(approved.value.as_deref(), Some("false"));
+        assert_eq!(approved.option_labels(), vec!["Yes"]);
+
+        let payment = field(&fields, "payment");
+        assert_eq!(payment.kind, FormFieldKind::Radio);
+        assert_eq!(payment.value.as_deref(), Some("Card"));
+        assert_eq!(payment.option_labels(), vec!["Card", "Cash"]);
+        assert_eq!(payment.widgets.len(), 2);
+
+        let department = field(&fields, "department");
+        assert_eq!(department.kind, FormFieldKind::ComboBox);
+        assert_eq!(department.value.as_deref(), Some("Sales"));
+        assert_eq!(
+            department.option_labels(),
+            vec!["Sales", "Engineering", "Finance"]
+        );
+    }
+
+    #[test]
+    fn test_fill_form_and_relist() {
+        let mut doc = form_pdf();
+        fill_form(
+            &mut doc,
+            &values(json!({"amount": 42.5, "approved": true})),
+            false,
+        )
+        .unwrap();
+
+        let output = tempfile::NamedTempFile::new().unwrap();
+        doc.save(output.path()).unwrap();
+        let fields = list_form_fields(&Document::load(output.path()).unwrap());
+        assert_eq!(field(&fields, "amount").value.as_deref(), Some("42.5"));
+        assert_eq!(field(&fields, "approved").value.as_deref(), Some("true"));
+        // Fields that weren't given are left alone
+        assert_eq!(
+            field(&fields, "full_name").value.as_deref(),
+            Some("Jane Doe")
+        );
+    }
+
+    #[test]
+    fn test_fill_radio_and_choice_by_label() {
+        let mut doc = form_pdf();
+        fill_form(
+            &mut doc,
+            &values(json!({"payment": "Cash", "department": "Finance", "approved": "Yes"})),
+            false,
+        )
+        .unwrap();
+
+        let fields = list_form_fields(&doc);
+        assert_eq!(field(&fields, "payment").value.as_deref(), Some("Cash"));
+        assert_eq!(
+            field(&fields, "department").value.as_deref(),
+            Some("Finance")
+        );
+        assert_eq!(field(&fields, "approved").value.as_deref(), Some("true"));
+
+        // Only the chosen radio button is switched on
+        let states: Vec<Vec<u8>> = field(&fields, "payment")
+            .widgets
+            .iter()
+            .map(|w| {
+                doc.get_dictionary(*w)
+                    .unwrap()
+                    .get(b"AS")
+                    .unwrap()
+                    .as_name()
+                    .unwrap()
+                    .to_vec()
+            })
+            .collect();
+        assert_eq!(states, vec![b"Off".to_vec(), b"Cash".to_vec()]);
+    }
+
+    #[test]
+    fn test_fill_form_rejects_unknown_fields_and_mismatches() {
+        let mut doc = form_pdf();
+
+        let err = fill_form(&mut doc, &values(json!({"nickname": "JD"})), false).unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        assert!(err.message.contains("Unknown form fields: nickname"));
+        assert!(err
+            .message
+            .contains("Valid fields are: full_name, amount, approved, payment, department"));
+
+        let err = fill_form(&mut doc, &values(json!({"approved": "maybe"})), false).unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        assert!(err.message.contains("one of 'true', 'false', 'Yes'"));
+
+        let err = fill_form(&mut doc, &values(json!({"payment": "Cheque"})), false).unwrap_err();
+        assert!(err.message.contains("'Card', 'Cash'"));
+
+        let err = fill_form(&mut doc, &values(json!({"department": "Legal"})), false).unwrap_err();
+        assert!(err.message.contains("'Sales', 'Engineering', 'Finance'"));
+
+        let err = fill_form(&mut doc, &values(json!({"full_name": true})), false).unwrap_err();
+        assert!(err.message.contains("takes text"));
+
+        // Nothing is written when any value is bad
+        let err = fill_form(
+            &mut doc,
+            &values(json!({"full_name": "Sam", "approved": 3})),
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        let fields = list_form_fields(&doc);
+        assert_eq!(
+            field(&fields, "full_name").value.as_deref(),
+            Some("Jane Doe")
+        );
+    }
+
+    #[test]
+    fn test_flatten_removes_the_form() {
+        let mut doc = form_pdf();
+        fill_form(
+            &mut doc,
+            &values(json!({"full_name": "Sam Lee", "approved": true})),
+            true,
+        )
+        .unwrap();
+
+        let output = tempfile::NamedTempFile::new().unwrap();
+        doc.save(output.path()).unwrap();
+        let doc = Document::load(output.path()).unwrap();
+        assert!(list_form_fields(&doc).is_empty());
+
+        let page_id = *doc.get_pages().get(&1).unwrap();
+        let page = doc.get_dictionary(page_id).unwrap();
+        assert!(!page.has(b"Annots"));
+        let content = String::from_utf8_lossy(&doc.get_page_content(page_id).unwrap()).into_owned();
+        assert!(content.contains("Expense claim"));
+        assert!(content.contains("/GooseForm0 Do"));
+    }
+}
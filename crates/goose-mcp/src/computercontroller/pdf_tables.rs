@@ -0,0 +1,452 @@
+//! Heuristic table detection for `pdf_tool`. PDFs have no notion of a table, so tables are
+//! recovered from where each piece of text is placed: text is grouped into lines by baseline,
+//! lines are split into cells at wide horizontal gaps, and runs of lines whose cells line up
+//! become a table.
+
+use lopdf::content::Content as PdfContent;
+use lopdf::{Document, Encoding, Object};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::{fs, path::Path};
+
+/// Average glyph width as a fraction of the font size, used to estimate where text ends
+const AVG_CHAR_WIDTH: f32 = 0.5;
+/// Baselines closer than this fraction of the font size belong to the same line
+const LINE_TOLERANCE: f32 = 0.5;
+/// Horizontal gaps wider than this fraction of the font size separate cells
+const CELL_GAP: f32 = 1.0;
+
+/// A table found on a page, as rows of cell text. Missing cells are empty strings.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PdfTable {
+    pub page: u32,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl PdfTable {
+    /// (rows, columns)
+    pub fn dimensions(&self) -> (usize, usize) {
+        (
+            self.rows.len(),
+            self.rows.first().map(|row| row.len()).unwrap_or(0),
+        )
+    }
+
+    /// How many cells are empty, which is high when columns were split or merged wrongly
+    pub fn empty_cells(&self) -> usize {
+        self.rows
+            .iter()
+            .flatten()
+            .filter(|cell| cell.is_empty())
+            .count()
+    }
+
+    /// The table as CSV text
+    pub fn to_csv(&self) -> String {
+        self.rows
+            .iter()
+            .map(|row| {
+                let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+                format!("{}\n", fields.join(","))
+            })
+            .collect()
+    }
+}
+
+/// A piece of text drawn at one position, in page space
+#[derive(Debug, Clone)]
+struct TextRun {
+    x: f32,
+    y: f32,
+    width: f32,
+    font_size: f32,
+    text: String,
+}
+
+#[derive(Debug, Clone)]
+struct Cell {
+    start: f32,
+    end: f32,
+    text: String,
+}
+
+type Matrix = [f32; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+fn multiply(m1: &Matrix, m2: &Matrix) -> Matrix {
+    [
+        m1[0] * m2[0] + m1[1] * m2[2],
+        m1[0] * m2[1] + m1[1] * m2[3],
+        m1[2] * m2[0] + m1[3] * m2[2],
+        m1[2] * m2[1] + m1[3] * m2[3],
+        m1[4] * m2[0] + m1[5] * m2[2] + m2[4],
+        m1[4] * m2[1] + m1[5] * m2[3] + m2[5],
+    ]
+}
+
+fn translation(tx: f32, ty: f32) -> Matrix {
+    [1.0, 0.0, 0.0, 1.0, tx, ty]
+}
+
+fn operand(operands: &[Object], index: usize) -> f32 {
+    operands
+        .get(index)
+        .and_then(|o| o.as_float().ok())
+        .unwrap_or(0.0)
+}
+
+/// Tracks the text state of a content stream and records where text is drawn
+struct TextCollector<'a> {
+    encodings: &'a BTreeMap<Vec<u8>, Encoding<'a>>,
+    ctm: Matrix,
+    ctm_stack: Vec<Matrix>,
+    text_matrix: Matrix,
+    line_matrix: Matrix,
+    leading: f32,
+    font: Vec<u8>,
+    font_size: f32,
+    runs: Vec<TextRun>,
+}
+
+impl<'a> TextCollector<'a> {
+    fn new(encodings: &'a BTreeMap<Vec<u8>, Encoding<'a>>) -> Self {
+        Self {
+            encodings,
+            ctm: IDENTITY,
+            ctm_stack: Vec::new(),
+            text_matrix: IDENTITY,
+            line_matrix: IDENTITY,
+            leading: 0.0,
+            font: Vec::new(),
+            font_size: 0.0,
+            runs: Vec::new(),
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        self.encodings
+            .get(&self.font)
+            .and_then(|encoding| Document::decode_text(encoding, bytes).ok())
+            .unwrap_or_else(|| String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    fn move_line(&mut self, tx: f32, ty: f32) {
+        self.line_matrix = multiply(&translation(tx, ty), &self.line_matrix);
+        self.text_matrix = self.line_matrix;
+    }
+
+    /// Record `text` at the current position and advance past it by `advance` text space units
+    fn show(&mut self, text: String, advance: f32) {
+        let page = multiply(&self.text_matrix, &self.ctm);
+        let scale_x = page[0].hypot(page[1]);
+        let scale_y = page[2].hypot(page[3]);
+        if !text.trim().is_empty() {
+            self.runs.push(TextRun {
+                x: page[4],
+                y: page[5],
+                width: advance * scale_x,
+                font_size: self.font_size * scale_y,
+                text,
+            });
+        }
+        self.text_matrix = multiply(&translation(advance, 0.0), &self.text_matrix);
+    }
+
+    fn show_string(&mut self, bytes: &[u8]) {
+        let text = self.decode(bytes);
+        let advance = text.chars().count() as f32 * self.font_size * AVG_CHAR_WIDTH;
+        self.show(text, advance);
+    }
+
+    /// `TJ` mixes strings with position adjustments; an adjustment wide enough to be a cell gap
+    /// starts a new run
+    fn show_array(&mut self, elements: &[Object]) {
+        let mut text = String::new();
+        let mut advance = 0.0;
+        for element in elements {
+            match element {
+                Object::String(bytes, _) => {
+                    let decoded = self.decode(bytes);
+                    advance += decoded.chars().count() as f32 * self.font_size * AVG_CHAR_WIDTH;
+                    text.push_str(&decoded);
+                }
+                Object::Integer(_) | Object::Real(_) => {
+                    let shift = -element.as_float().unwrap_or(0.0) / 1000.0 * self.font_size;
+                    if shift >= CELL_GAP * self.font_size {
+                        self.show(std::mem::take(&mut text), advance);
+                        self.text_matrix = multiply(&translation(shift, 0.0), &self.text_matrix);
+                        advance = 0.0;
+                    } else {
+                        if shift > AVG_CHAR_WIDTH * self.font_size / 2.0 {
+                            text.push(' ');
+                        }
+                        advance += shift;
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.show(text, advance);
+    }
+
+    fn apply(&mut self, operator: &str, operands: &[Object]) {
+        match operator {
+            "q" => self.ctm_stack.push(self.ctm),
+            "Q" => self.ctm = self.ctm_stack.pop().unwrap_or(IDENTITY),
+            "cm" => {
+                let m = [0, 1, 2, 3, 4, 5].map(|i| operand(operands, i));
+                self.ctm = multiply(&m, &self.ctm);
+            }
+            "BT" => {
+                self.text_matrix = IDENTITY;
+                self.line_matrix = IDENTITY;
+            }
+            "Tf" => {
+                if let Some(Ok(name)) = operands.first().map(|o| o.as_name()) {
+                    self.font = name.to_vec();
+                }
+                self.font_size = operand(operands, 1);
+            }
+            "TL" => self.leading = operand(operands, 0),
+            "Td" => self.move_line(operand(operands, 0), operand(operands, 1)),
+            "TD" => {
+                self.leading = -operand(operands, 1);
+                self.move_line(operand(operands, 0), operand(operands, 1));
+            }
+            "Tm" => {
+                self.line_matrix = [0, 1, 2, 3, 4, 5].map(|i| operand(operands, i));
+                self.text_matrix = self.line_matrix;
+            }
+            "T*" => self.move_line(0.0, -self.leading),
+            "Tj" => {
+                if let Some(Object::String(bytes, _)) = operands.first() {
+                    self.show_string(bytes);
+                }
+            }
+            "'" | "\"" => {
+                self.move_line(0.0, -self.leading);
+                if let Some(Object::String(bytes, _)) = operands.last() {
+                    self.show_string(bytes);
+                }
+            }
+            "TJ" => {
+                if let Some(Object::Array(elements)) = operands.first() {
+                    self.show_array(elements);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn page_text_runs(doc: &Document, page_id: lopdf::ObjectId) -> Vec<TextRun> {
+    let encodings: BTreeMap<Vec<u8>, Encoding> = doc
+        .get_page_fonts(page_id)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(name, font)| font.get_font_encoding(doc).ok().map(|e| (name, e)))
+        .collect();
+
+    let Ok(content) = doc
+        .get_page_content(page_id)
+        .and_then(|data| PdfContent::decode(&data))
+    else {
+        return Vec::new();
+    };
+
+    let mut collector = TextCollector::new(&encodings);
+    for operation in &content.operations {
+        collector.apply(&operation.operator, &operation.operands);
+    }
+    collector.runs
+}
+
+/// Group runs into lines from the top of the page down, each split into cells left to right
+fn lines_of_cells(mut runs: Vec<TextRun>) -> Vec<Vec<Cell>> {
+    runs.sort_by(|a, b| b.y.total_cmp(&a.y));
+
+    let mut lines: Vec<Vec<TextRun>> = Vec::new();
+    for run in runs {
+        match lines.last_mut() {
+            Some(line) if (line[0].y - run.y).abs() <= LINE_TOLERANCE * line[0].font_size => {
+                line.push(run)
+            }
+            _ => lines.push(vec![run]),
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|mut line| {
+            line.sort_by(|a, b| a.x.total_cmp(&b.x));
+            let mut cells: Vec<Cell> = Vec::new();
+            for run in line {
+                let text = run.text.trim();
+                match cells.last_mut() {
+                    Some(cell) if run.x - cell.end <= CELL_GAP * run.font_size => {
+                        if run.x - cell.end > AVG_CHAR_WIDTH * run.font_size / 2.0 {
+                            cell.text.push(' ');
+                        }
+                        cell.text.push_str(text);
+                        cell.end = cell.end.max(run.x + run.width);
+                    }
+                    _ => cells.push(Cell {
+                        start: run.x,
+                        end: run.x + run.width,
+                        text: text.to_string(),
+                    }),
+                }
+            }
+            cells
+        })
+        .collect()
+}
+
+/// Columns are the horizontal spans that cells from different rows overlap into
+fn column_spans(lines: &[Vec<Cell>]) -> Vec<(f32, f32)> {
+    let mut spans: Vec<(f32, f32)> = lines
+        .iter()
+        .flatten()
+        .map(|cell| (cell.start, cell.end))
+        .collect();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut columns: Vec<(f32, f32)> = Vec::new();
+    for (start, end) in spans {
+        match columns.last_mut() {
+            Some(column) if start <= column.1 => column.1 = column.1.max(end),
+            _ => columns.push((start, end)),
+        }
+    }
+    columns
+}
+
+fn table_from_lines(page: u32, lines: &[Vec<Cell>]) -> Option<PdfTable> {
+    let columns = column_spans(lines);
+    if lines.len() < 2 || columns.len() < 2 {
+        return None;
+    }
+
+    let rows = lines
+        .iter()
+        .map(|line| {
+            let mut row = vec![String::new(); columns.len()];
+            for cell in line {
+                let index = columns
+                    .iter()
+                    .position(|(start, end)| cell.start >= *start && cell.start <= *end)
+                    .unwrap_or(columns.len() - 1);
+                if !row[index].is_empty() {
+                    row[index].push(' ');
+                }
+                row[index].push_str(&cell.text);
+            }
+            row
+        })
+        .collect();
+    Some(PdfTable { page, rows })
+}
+
+/// Find tables on every page: runs of at least two consecutive lines that each have two or
+/// more cells, and whose cells line up into at least two columns
+pub fn extract_tables(doc: &Document) -> Vec<PdfTable> {
+    let mut tables = Vec::new();
+    for (page, page_id) in doc.get_pages() {
+        let lines = lines_of_cells(page_text_runs(doc, page_id));
+        for block in lines
+            .split(|line| line.len() < 2)
+            .filter(|block| !block.is_empty())
+        {
+            tables.extend(table_from_lines(page, block));
+        }
+    }
+    tables
+}
+
+pub(super) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn write_csv(table: &PdfTable, path: &Path) -> std::io::Result<()> {
+    fs::write(path, table.to_csv())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn table_pdf() -> Document {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/table.pdf");
+        Document::load(path).unwrap()
+    }
+
+    #[test]
+    fn test_extract_tables_from_positioned_text() {
+        let tables = extract_tables(&table_pdf());
+
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].page, 1);
+        assert_eq!(tables[0].dimensions(), (4, 3));
+        assert_eq!(tables[0].empty_cells(), 1);
+        assert_eq!(
+            tables[0].rows,
+            vec![
+                vec!["Region", "Q1 Revenue", "Q2 Revenue"],
+                vec!["North", "1,200", "1,350"],
+                vec!["South", "", "980"],
+                vec!["East Coast", "2,050", "2,400"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prose_is_not_a_table() {
+        let runs = vec![
+            TextRun {
+                x: 72.0,
+                y: 700.0,
+                width: 300.0,
+                font_size: 12.0,
+                text: "A paragraph of ordinary text".to_string(),
+            },
+            TextRun {
+                x: 72.0,
+                y: 686.0,
+                width: 280.0,
+                font_size: 12.0,
+                text: "that continues on the next line.".to_string(),
+            },
+        ];
+        let lines = lines_of_cells(runs);
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() == 1));
+        assert!(table_from_lines(1, &lines).is_none());
+    }
+
+    #[test]
+    fn test_write_csv_quotes_fields() {
+        let table = PdfTable {
+            page: 1,
+            rows: vec![
+                vec!["Region".to_string(), "Revenue".to_string()],
+                vec!["North".to_string(), "1,200".to_string()],
+                vec!["Say \"hi\"".to_string(), String::new()],
+            ],
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("table.csv");
+        write_csv(&table, &path).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "Region,Revenue\nNorth,\"1,200\"\n\"Say \"\"hi\"\"\",\n"
+        );
+    }
+}
@@ -1,6 +1,15 @@
+use super::pdf_forms::{fill_form, list_form_fields, FormFieldKind};
+use super::pdf_tables::{extract_tables, write_csv};
 use lopdf::{content::Content as PdfContent, Document, Object};
 use rmcp::model::{Content, ErrorCode, ErrorData};
-use std::{fs, path::Path};
+use std::collections::HashMap;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Tables with at most this many cells are shown inline as well as saved as CSV
+const MAX_PREVIEW_CELLS: usize = 60;
 
 pub async fn pdf_tool(
     path: &str,
@@ -344,11 +353,160 @@ pub async fn pdf_tool(
             }
         }
 
+        "get_metadata" => {
+            let encrypted = doc.is_encrypted();
+            // Metadata strings are encrypted too; most encrypted PDFs only need the empty user
+            // password to be read
+            let readable = if encrypted {
+                let mut decrypted = doc.clone();
+                decrypted.decrypt("").ok().map(|_| decrypted)
+            } else {
+                None
+            };
+            let info_doc = readable.as_ref().unwrap_or(&doc);
+            let info = info_doc
+                .trailer
+                .get(b"Info")
+                .ok()
+                .filter(|_| !encrypted || readable.is_some())
+                .and_then(|info| match info {
+                    Object::Reference(id) => info_doc.get_dictionary(*id).ok(),
+                    Object::Dictionary(dict) => Some(dict),
+                    _ => None,
+                });
+
+            let field = |key: &[u8]| {
+                info.and_then(|info| info.get(key).ok())
+                    .and_then(|value| lopdf::decode_text_string(value).ok())
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+            };
+            let date = |key: &[u8]| field(key).map(|raw| format_pdf_date(&raw));
+
+            let mut lines = vec![
+                format!("Title: {}", field(b"Title").unwrap_or_else(not_set)),
+                format!("Author: {}", field(b"Author").unwrap_or_else(not_set)),
+                format!("Subject: {}", field(b"Subject").unwrap_or_else(not_set)),
+                format!("Creator: {}", field(b"Creator").unwrap_or_else(not_set)),
+                format!("Producer: {}", field(b"Producer").unwrap_or_else(not_set)),
+                format!("Created: {}", date(b"CreationDate").unwrap_or_else(not_set)),
+                format!("Modified: {}", date(b"ModDate").unwrap_or_else(not_set)),
+                format!("Pages: {}", doc.get_pages().len()),
+                format!("PDF version: {}", doc.version),
+                format!("Encrypted: {}", if encrypted { "yes" } else { "no" }),
+            ];
+            if encrypted && readable.is_none() {
+                lines.push(
+                    "The document is password protected, so its metadata and contents cannot be read"
+                        .to_string(),
+                );
+            }
+            format!("PDF metadata:\n{}", lines.join("\n"))
+        }
+
+        "extract_tables" => {
+            let tables = extract_tables(&doc);
+
+            if tables.is_empty() {
+                "No tables found in PDF".to_string()
+            } else {
+                let cache_dir = cache_dir.join("pdf_tables");
+                fs::create_dir_all(&cache_dir).map_err(|e| {
+                    ErrorData::new(
+                        ErrorCode::INTERNAL_ERROR,
+                        format!("Failed to create table cache directory: {}", e),
+                        None,
+                    )
+                })?;
+
+                let stem = Path::new(path)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "pdf".to_string());
+                let mut result = format!("Found {} tables:\n", tables.len());
+                for (index, table) in tables.iter().enumerate() {
+                    let csv_path = cache_dir.join(format!(
+                        "{}_page{}_table{}.csv",
+                        stem,
+                        table.page,
+                        index + 1
+                    ));
+                    write_csv(table, &csv_path).map_err(|e| {
+                        ErrorData::new(
+                            ErrorCode::INTERNAL_ERROR,
+                            format!("Failed to write CSV: {}", e),
+                            None,
+                        )
+                    })?;
+
+                    let (rows, columns) = table.dimensions();
+                    result.push_str(&format!(
+                        "\nTable {}: page {}, {} rows x {} columns\nSaved table to: {}\n",
+                        index + 1,
+                        table.page,
+                        rows,
+                        columns,
+                        csv_path.display()
+                    ));
+                    let empty = table.empty_cells();
+                    if empty * 3 > rows * columns {
+                        result.push_str(&format!(
+                            "Note: {} of {} cells are empty, so columns may be split or merged wrongly\n",
+                            empty,
+                            rows * columns
+                        ));
+                    }
+                    if rows * columns <= MAX_PREVIEW_CELLS {
+                        result.push_str(&table.to_csv());
+                    }
+                }
+                result.push_str(
+                    "\nTables are detected from where text sits on the page, not from the PDF's structure. \
+                     Merged cells, wrapped text and tables without clear column gaps may come out wrong, \
+                     so check important values against the extracted text.",
+                );
+                result
+            }
+        }
+
+        "list_form_fields" => {
+            let fields = list_form_fields(&doc);
+            if fields.is_empty() {
+                "No form fields found in PDF".to_string()
+            } else {
+                let mut result = format!("Found {} form fields:\n", fields.len());
+                for field in &fields {
+                    result.push_str(&format!(
+                        "\n- {} ({}{})\n  Value: {}\n",
+                        field.name,
+                        field.kind.label(),
+                        if field.read_only { ", read-only" } else { "" },
+                        field.value.as_deref().unwrap_or("not set")
+                    ));
+                    let options = field.option_labels();
+                    match field.kind {
+                        FormFieldKind::Checkbox => result.push_str(&format!(
+                            "  Accepts: true, false{}\n",
+                            options
+                                .iter()
+                                .map(|o| format!(", {}", o))
+                                .collect::<String>()
+                        )),
+                        _ if !options.is_empty() => {
+                            result.push_str(&format!("  Options: {}\n", options.join(", ")))
+                        }
+                        _ => {}
+                    }
+                }
+                result
+            }
+        }
+
         _ => {
             return Err(ErrorData::new(
                 ErrorCode::INVALID_PARAMS,
                 format!(
-                    "Invalid operation: {}. Valid operations are: 'extract_text', 'extract_images'",
+                    "Invalid operation: {}. Valid operations are: 'extract_text', 'extract_images', 'extract_tables', 'get_metadata', 'list_form_fields', 'fill_form'",
                     operation
                 ),
                 None,
@@ -359,6 +517,118 @@ pub async fn pdf_tool(
     Ok(vec![Content::text(result)])
 }
 
+/// Fill the form in the PDF at `path` and save the result to the cache directory, returning
+/// the tool result and where the filled PDF was saved
+pub async fn fill_pdf_form(
+    path: &str,
+    fields: &HashMap<String, serde_json::Value>,
+    output_path: Option<&str>,
+    flatten: bool,
+    cache_dir: &Path,
+) -> Result<(Vec<Content>, PathBuf), ErrorData> {
+    if fields.is_empty() {
+        return Err(ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            "fields must name at least one form field to fill".to_string(),
+            None,
+        ));
+    }
+    let mut doc = Document::load(path).map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to open PDF file: {}", e),
+            None,
+        )
+    })?;
+    fill_form(&mut doc, fields, flatten)?;
+
+    // Only the file name is used, so the filled PDF always lands in the cache
+    let file_name = output_path
+        .and_then(|output| Path::new(output).file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| {
+            let stem = Path::new(path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "form".to_string());
+            format!("{}_filled.pdf", stem)
+        });
+    let output_dir = cache_dir.join("pdf_forms");
+    fs::create_dir_all(&output_dir).map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to create form cache directory: {}", e),
+            None,
+        )
+    })?;
+    let output = output_dir.join(file_name);
+    doc.save(&output).map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to save filled PDF: {}", e),
+            None,
+        )
+    })?;
+
+    let mut names: Vec<&str> = fields.keys().map(String::as_str).collect();
+    names.sort();
+    let result = format!(
+        "Filled {} form fields ({}){}.\nSaved filled PDF to: {}",
+        names.len(),
+        names.join(", "),
+        if flatten {
+            " and flattened the form"
+        } else {
+            ""
+        },
+        output.display()
+    );
+    Ok((vec![Content::text(result)], output))
+}
+
+fn not_set() -> String {
+    "not set".to_string()
+}
+
+/// Turn a PDF date such as `D:20250304190227+11'00'` into `2025-03-04T19:02:27+11:00`, leaving
+/// dates that do not follow the format as they are
+fn format_pdf_date(raw: &str) -> String {
+    let date = raw.strip_prefix("D:").unwrap_or(raw);
+    let digits = date.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits < 4 || digits % 2 != 0 || digits > 14 {
+        return raw.to_string();
+    }
+
+    // Missing parts default to the start of the year, month or day
+    let mut parts = "00000101000000".to_string();
+    parts.replace_range(..digits, &date[..digits]);
+    let formatted = format!(
+        "{}-{}-{}T{}:{}:{}",
+        &parts[0..4],
+        &parts[4..6],
+        &parts[6..8],
+        &parts[8..10],
+        &parts[10..12],
+        &parts[12..14]
+    );
+
+    let offset = &date[digits..];
+    match offset.chars().next() {
+        Some('Z') => format!("{}Z", formatted),
+        Some(sign @ ('+' | '-')) => {
+            let numbers: String = offset[1..].chars().filter(|c| c.is_ascii_digit()).collect();
+            if numbers.len() >= 4 {
+                format!("{}{}{}:{}", formatted, sign, &numbers[..2], &numbers[2..4])
+            } else if numbers.len() == 2 {
+                format!("{}{}{}:00", formatted, sign, numbers)
+            } else {
+                formatted
+            }
+        }
+        _ => formatted,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -434,6 +704,169 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_pdf_table_extraction() {
+        let test_pdf_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/table.pdf");
+        let cache_dir = tempfile::tempdir().unwrap();
+
+        let result = pdf_tool(
+            test_pdf_path.to_str().unwrap(),
+            "extract_tables",
+            cache_dir.path(),
+        )
+        .await
+        .unwrap();
+        let text = &result[0].as_text().unwrap().text;
+        assert!(text.starts_with("Found 1 tables:"));
+        assert!(text.contains("Table 1: page 1, 4 rows x 3 columns"));
+        assert!(text.contains("check important values"));
+
+        let csv_path = cache_dir.path().join("pdf_tables/table_page1_table1.csv");
+        assert!(text.contains(&format!("Saved table to: {}", csv_path.display())));
+        let csv = fs::read_to_string(csv_path).unwrap();
+        assert_eq!(
+            csv,
+            "Region,Q1 Revenue,Q2 Revenue\nNorth,\"1,200\",\"1,350\"\nSouth,,980\nEast Coast,\"2,050\",\"2,400\"\n"
+        );
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].split(',').count(), 3);
+
+        // Small tables are previewed inline
+        assert!(text.contains(&csv));
+    }
+
+    #[tokio::test]
+    async fn test_pdf_without_tables() {
+        let test_pdf_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/test.pdf");
+        let cache_dir = tempfile::tempdir().unwrap();
+
+        let result = pdf_tool(
+            test_pdf_path.to_str().unwrap(),
+            "extract_tables",
+            cache_dir.path(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result[0].as_text().unwrap().text, "No tables found in PDF");
+    }
+
+    #[tokio::test]
+    async fn test_pdf_metadata() {
+        let test_pdf_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/test.pdf");
+        let cache_dir = tempfile::tempdir().unwrap();
+
+        let result = pdf_tool(
+            test_pdf_path.to_str().unwrap(),
+            "get_metadata",
+            cache_dir.path(),
+        )
+        .await
+        .unwrap();
+        let text = &result[0].as_text().unwrap().text;
+        assert!(text.contains("Title: not set"));
+        assert!(text.contains("Author: not set"));
+        assert!(text.contains("Creator: LaTeX via pandoc"));
+        assert!(text.contains("Created: 2025-03-04T19:02:27+11:00"));
+        assert!(text.contains("Modified: 2025-03-05T03:11:09Z"));
+        assert!(text.contains("Pages: 1"));
+        assert!(text.contains("Encrypted: no"));
+    }
+
+    #[test]
+    fn test_format_pdf_date() {
+        assert_eq!(
+            format_pdf_date("D:20250304190227+11'00'"),
+            "2025-03-04T19:02:27+11:00"
+        );
+        assert_eq!(format_pdf_date("D:20240101120000Z"), "2024-01-01T12:00:00Z");
+        assert_eq!(format_pdf_date("D:2023"), "2023-01-01T00:00:00");
+        assert_eq!(format_pdf_date("last tuesday"), "last tuesday");
+    }
+
+    #[tokio::test]
+    async fn test_pdf_fill_form_and_list_fields() {
+        let test_pdf_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/form.pdf");
+        let cache_dir = tempfile::tempdir().unwrap();
+
+        let result = pdf_tool(
+            test_pdf_path.to_str().unwrap(),
+            "list_form_fields",
+            cache_dir.path(),
+        )
+        .await
+        .unwrap();
+        let text = &result[0].as_text().unwrap().text;
+        assert!(text.starts_with("Found 5 form fields:"));
+        assert!(text.contains("- full_name (text)\n  Value: Jane Doe\n"));
+        assert!(
+            text.contains("- approved (checkbox)\n  Value: false\n  Accepts: true, false, Yes\n")
+        );
+        assert!(text.contains("  Options: Sales, Engineering, Finance\n"));
+
+        let fields: HashMap<String, serde_json::Value> =
+            serde_json::from_value(serde_json::json!({"full_name": "Sam Lee", "payment": "Cash"}))
+                .unwrap();
+        let (result, output) = fill_pdf_form(
+            test_pdf_path.to_str().unwrap(),
+            &fields,
+            Some("../claim.pdf"),
+            false,
+            cache_dir.path(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(output, cache_dir.path().join("pdf_forms/claim.pdf"));
+        let text = &result[0].as_text().unwrap().text;
+        assert!(text.starts_with("Filled 2 form fields (full_name, payment)."));
+
+        let result = pdf_tool(
+            output.to_str().unwrap(),
+            "list_form_fields",
+            cache_dir.path(),
+        )
+        .await
+        .unwrap();
+        let text = &result[0].as_text().unwrap().text;
+        assert!(text.contains("- full_name (text)\n  Value: Sam Lee\n"));
+        assert!(text.contains("- payment (radio)\n  Value: Cash\n"));
+    }
+
+    #[tokio::test]
+    async fn test_pdf_without_form_fields() {
+        let test_pdf_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data/test.pdf");
+        let cache_dir = tempfile::tempdir().unwrap();
+
+        let result = pdf_tool(
+            test_pdf_path.to_str().unwrap(),
+            "list_form_fields",
+            cache_dir.path(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            result[0].as_text().unwrap().text,
+            "No form fields found in PDF"
+        );
+
+        let fields = HashMap::from([("name".to_string(), serde_json::json!("Sam"))]);
+        let err = fill_pdf_form(
+            test_pdf_path.to_str().unwrap(),
+            &fields,
+            None,
+            false,
+            cache_dir.path(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+    }
+
     #[tokio::test]
     async fn test_pdf_invalid_path() {
         let cache_dir = tempfile::tempdir().unwrap().into_path();
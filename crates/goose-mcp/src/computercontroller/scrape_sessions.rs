@@ -0,0 +1,137 @@
+//! Cookie jars that let a sequence of `web_scrape` calls share a login or other session state.
+
+use cookie_store::{CookieStore, RawCookie};
+use reqwest::header::{HeaderMap, HeaderValue, SET_COOKIE};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use url::Url;
+
+/// The cookies collected by one scraping session
+#[derive(Default)]
+pub struct CookieJar {
+    store: Mutex<CookieStore>,
+}
+
+impl CookieJar {
+    fn lock(&self) -> MutexGuard<'_, CookieStore> {
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The `Cookie` header to send with a request to `url`, if any cookies apply
+    pub fn header_for(&self, url: &Url) -> Option<HeaderValue> {
+        let store = self.lock();
+        let cookies: Vec<String> = store
+            .get_request_values(url)
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        if cookies.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&cookies.join("; ")).ok()
+    }
+
+    /// Remember the cookies a response from `url` set
+    pub fn store_from(&self, url: &Url, headers: &HeaderMap) {
+        let cookies = headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| RawCookie::parse(value.to_string()).ok());
+        self.lock().store_response_cookies(cookies, url);
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().iter_unexpired().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Named cookie jars kept for the lifetime of the server
+#[derive(Default)]
+pub struct ScrapeSessions {
+    jars: Mutex<HashMap<String, Arc<CookieJar>>>,
+}
+
+impl ScrapeSessions {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Arc<CookieJar>>> {
+        self.jars.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The jar for `session_id`, created empty on first use
+    pub fn jar(&self, session_id: &str) -> Arc<CookieJar> {
+        Arc::clone(self.lock().entry(session_id.to_string()).or_default())
+    }
+
+    /// Forget a session's cookies, returning whether it existed
+    pub fn clear(&self, session_id: &str) -> bool {
+        self.lock().remove(session_id).is_some()
+    }
+
+    /// Forget every session, returning how many there were
+    pub fn clear_all(&self) -> usize {
+        let mut jars = self.lock();
+        let count = jars.len();
+        jars.clear();
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_cookies(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(SET_COOKIE, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_jar_returns_cookies_for_matching_urls() {
+        let jar = CookieJar::default();
+        let login = Url::parse("https://example.com/login").unwrap();
+        jar.store_from(
+            &login,
+            &set_cookies(&[
+                "session=abc123; Path=/; HttpOnly",
+                "admin_pref=dark; Path=/admin",
+                "gone=1; Max-Age=0",
+            ]),
+        );
+
+        let page = Url::parse("https://example.com/reports").unwrap();
+        assert_eq!(jar.header_for(&page).unwrap(), "session=abc123");
+
+        let admin = Url::parse("https://example.com/admin/users").unwrap();
+        let header = jar.header_for(&admin).unwrap();
+        let mut cookies: Vec<&str> = header.to_str().unwrap().split("; ").collect();
+        cookies.sort();
+        assert_eq!(cookies, vec!["admin_pref=dark", "session=abc123"]);
+
+        let other_site = Url::parse("https://example.org/").unwrap();
+        assert!(jar.header_for(&other_site).is_none());
+        assert_eq!(jar.len(), 2);
+    }
+
+    #[test]
+    fn test_sessions_are_isolated_and_clearable() {
+        let sessions = ScrapeSessions::default();
+        let url = Url::parse("https://example.com/").unwrap();
+        sessions
+            .jar("a")
+            .store_from(&url, &set_cookies(&["token=1"]));
+
+        assert!(sessions.jar("b").header_for(&url).is_none());
+        assert_eq!(sessions.jar("a").header_for(&url).unwrap(), "token=1");
+
+        assert!(sessions.clear("a"));
+        assert!(!sessions.clear("a"));
+        assert!(sessions.jar("a").is_empty());
+        assert_eq!(sessions.clear_all(), 2);
+    }
+}
@@ -0,0 +1,520 @@
+//! Inspect and query SQLite database files without needing the sqlite3 command line tool.
+
+use super::pdf_tables::csv_field;
+use base64::Engine;
+use rmcp::model::{Content, ErrorCode, ErrorData};
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{params_from_iter, Batch, Connection, OpenFlags, Statement};
+use serde_json::{Map, Value};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Rows returned by a query when no limit is given
+pub const DEFAULT_MAX_ROWS: usize = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableInfo {
+    pub name: String,
+    /// `table` or `view`
+    pub kind: String,
+    /// Only counted for tables, since counting a view runs its query
+    pub rows: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryOutcome {
+    Rows {
+        columns: Vec<String>,
+        rows: Vec<Map<String, Value>>,
+        /// More rows matched than were returned
+        truncated: bool,
+    },
+    /// A write statement ran and changed this many rows
+    Changed(usize),
+}
+
+fn invalid_params(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INVALID_PARAMS, message, None)
+}
+
+fn sql_error(context: &str, e: rusqlite::Error) -> ErrorData {
+    invalid_params(format!("{}: {}", context, e))
+}
+
+/// Open an existing database, read-only unless `allow_writes` is set
+pub fn open_database(path: &Path, allow_writes: bool) -> Result<Connection, ErrorData> {
+    if !path.is_file() {
+        return Err(invalid_params(format!(
+            "Database file not found: {}",
+            path.display()
+        )));
+    }
+    let flags = if allow_writes {
+        OpenFlags::SQLITE_OPEN_READ_WRITE
+    } else {
+        OpenFlags::SQLITE_OPEN_READ_ONLY
+    } | OpenFlags::SQLITE_OPEN_NO_MUTEX
+        | OpenFlags::SQLITE_OPEN_URI;
+    Connection::open_with_flags(path, flags).map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to open {}: {}", path.display(), e),
+            None,
+        )
+    })
+}
+
+pub fn list_tables(conn: &Connection) -> Result<Vec<TableInfo>, ErrorData> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT name, type FROM sqlite_master \
+             WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )
+        .map_err(|e| sql_error("Failed to list tables", e))?;
+    let tables = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| sql_error("Failed to list tables", e))?;
+
+    tables
+        .into_iter()
+        .map(|(name, kind)| {
+            let rows = if kind == "table" {
+                let count = format!("SELECT COUNT(*) FROM {}", quote_identifier(&name));
+                Some(
+                    conn.query_row(&count, [], |row| row.get(0))
+                        .map_err(|e| sql_error(&format!("Failed to count rows in {}", name), e))?,
+                )
+            } else {
+                None
+            };
+            Ok(TableInfo { name, kind, rows })
+        })
+        .collect()
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// The statement that created `table`, along with its columns
+pub fn schema(conn: &Connection, table: &str) -> Result<String, ErrorData> {
+    let sql: Option<String> = conn
+        .query_row(
+            "SELECT sql FROM sqlite_master WHERE name = ?1 AND type IN ('table', 'view')",
+            [table],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => {
+                invalid_params(format!("No table or view named '{}'", table))
+            }
+            e => sql_error("Failed to read schema", e),
+        })?;
+
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", quote_identifier(table)))
+        .map_err(|e| sql_error("Failed to read columns", e))?;
+    let columns = stmt
+        .query_map([], |row| {
+            let name: String = row.get(1)?;
+            let column_type: String = row.get(2)?;
+            let not_null: bool = row.get(3)?;
+            let primary_key: i64 = row.get(5)?;
+            let mut line = format!("- {} {}", name, column_type);
+            if primary_key > 0 {
+                line.push_str(" PRIMARY KEY");
+            }
+            if not_null {
+                line.push_str(" NOT NULL");
+            }
+            Ok(line.trim_end().to_string())
+        })
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| sql_error("Failed to read columns", e))?;
+
+    Ok(format!(
+        "{}\n\nColumns:\n{}",
+        sql.unwrap_or_default(),
+        columns.join("\n")
+    ))
+}
+
+fn bind_value(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+fn json_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Value::from(f),
+        ValueRef::Text(text) => Value::String(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(bytes) => Value::String(base64::prelude::BASE64_STANDARD.encode(bytes)),
+    }
+}
+
+fn prepare<'a>(
+    conn: &'a Connection,
+    sql: &str,
+    allow_writes: bool,
+) -> Result<Statement<'a>, ErrorData> {
+    let mut batch = Batch::new(conn, sql);
+    let stmt = batch
+        .next()
+        .map_err(|e| sql_error("Invalid query", e))?
+        .ok_or_else(|| invalid_params("The query is empty".to_string()))?;
+    if !matches!(batch.next(), Ok(None)) {
+        return Err(invalid_params(
+            "Only one statement can be run at a time".to_string(),
+        ));
+    }
+    if !allow_writes && !stmt.readonly() {
+        return Err(invalid_params(
+            "The statement would modify the database; set allow_writes to run it".to_string(),
+        ));
+    }
+    Ok(stmt)
+}
+
+fn column_names(stmt: &Statement<'_>) -> Vec<String> {
+    stmt.column_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Run a single statement with `params` bound to its `?` placeholders. Reads return at most
+/// `max_rows` rows; writes are refused unless `allow_writes` is set.
+pub fn query(
+    conn: &Connection,
+    sql: &str,
+    params: &[Value],
+    max_rows: usize,
+    allow_writes: bool,
+) -> Result<QueryOutcome, ErrorData> {
+    let mut stmt = prepare(conn, sql, allow_writes)?;
+    let bound = params_from_iter(params.iter().map(bind_value));
+
+    if !stmt.readonly() {
+        let changed = stmt
+            .execute(bound)
+            .map_err(|e| sql_error("Statement failed", e))?;
+        return Ok(QueryOutcome::Changed(changed));
+    }
+
+    let columns = column_names(&stmt);
+    let mut rows = stmt
+        .query(bound)
+        .map_err(|e| sql_error("Query failed", e))?;
+    let mut collected = Vec::new();
+    let mut truncated = false;
+    while let Some(row) = rows.next().map_err(|e| sql_error("Query failed", e))? {
+        if collected.len() == max_rows {
+            truncated = true;
+            break;
+        }
+        let mut object = Map::new();
+        for (index, column) in columns.iter().enumerate() {
+            let value = row
+                .get_ref(index)
+                .map_err(|e| sql_error("Query failed", e))?;
+            object.insert(column.clone(), json_value(value));
+        }
+        collected.push(object);
+    }
+
+    Ok(QueryOutcome::Rows {
+        columns,
+        rows: collected,
+        truncated,
+    })
+}
+
+/// Write every row of a read-only query to `output` as CSV with a header row, returning the
+/// number of rows written
+pub fn export_csv(
+    conn: &Connection,
+    sql: &str,
+    params: &[Value],
+    output: &Path,
+) -> Result<usize, ErrorData> {
+    let mut stmt = prepare(conn, sql, false)?;
+    let columns = column_names(&stmt);
+    let mut rows = stmt
+        .query(params_from_iter(params.iter().map(bind_value)))
+        .map_err(|e| sql_error("Query failed", e))?;
+
+    let write_error = |e: std::io::Error| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to write {}: {}", output.display(), e),
+            None,
+        )
+    };
+    let mut writer = BufWriter::new(File::create(output).map_err(write_error)?);
+    let header: Vec<String> = columns.iter().map(|column| csv_field(column)).collect();
+    writeln!(writer, "{}", header.join(",")).map_err(write_error)?;
+
+    let mut count = 0;
+    while let Some(row) = rows.next().map_err(|e| sql_error("Query failed", e))? {
+        let fields = (0..columns.len())
+            .map(|index| {
+                let field = match row.get_ref(index)? {
+                    ValueRef::Null => String::new(),
+                    value => match json_value(value) {
+                        Value::String(s) => s,
+                        other => other.to_string(),
+                    },
+                };
+                Ok(csv_field(&field))
+            })
+            .collect::<Result<Vec<String>, rusqlite::Error>>()
+            .map_err(|e| sql_error("Query failed", e))?;
+        writeln!(writer, "{}", fields.join(",")).map_err(write_error)?;
+        count += 1;
+    }
+    writer.flush().map_err(write_error)?;
+    Ok(count)
+}
+
+pub fn render_tables(path: &Path, tables: &[TableInfo]) -> Vec<Content> {
+    if tables.is_empty() {
+        return vec![Content::text(format!("{} has no tables", path.display()))];
+    }
+    let lines: Vec<String> = tables
+        .iter()
+        .map(|table| match table.rows {
+            Some(rows) => format!("- {} ({} rows)", table.name, rows),
+            None => format!("- {} ({})", table.name, table.kind),
+        })
+        .collect();
+    vec![Content::text(format!(
+        "Tables in {}:\n{}",
+        path.display(),
+        lines.join("\n")
+    ))]
+}
+
+pub fn render_outcome(outcome: QueryOutcome) -> Vec<Content> {
+    match outcome {
+        QueryOutcome::Changed(changed) => {
+            vec![Content::text(format!("{} rows changed", changed))]
+        }
+        QueryOutcome::Rows {
+            columns,
+            rows,
+            truncated,
+        } => {
+            let count = rows.len();
+            let body = serde_json::json!({
+                "columns": columns,
+                "rows": rows,
+                "row_count": count,
+                "truncated": truncated,
+            });
+            let mut result = vec![Content::text(
+                serde_json::to_string_pretty(&body).unwrap_or_default(),
+            )];
+            if truncated {
+                result.push(Content::text(format!(
+                    "Only the first {} rows are shown. Raise max_rows, narrow the query or use export for every row.",
+                    count
+                )));
+            }
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn fixture(dir: &TempDir) -> std::path::PathBuf {
+        let path = dir.path().join("history.db");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE visits (
+                id INTEGER PRIMARY KEY,
+                url TEXT NOT NULL,
+                title TEXT,
+                duration REAL
+            );
+            INSERT INTO visits (url, title, duration) VALUES
+                ('https://example.com', 'Example, Inc', 1.5),
+                ('https://rust-lang.org', 'Rust', 30.0),
+                ('https://docs.rs', NULL, 12.25);
+            CREATE VIEW long_visits AS SELECT url FROM visits WHERE duration > 10;",
+        )
+        .unwrap();
+        path
+    }
+
+    fn returned_rows(outcome: QueryOutcome) -> (Vec<Map<String, Value>>, bool) {
+        match outcome {
+            QueryOutcome::Rows {
+                rows, truncated, ..
+            } => (rows, truncated),
+            other => panic!("expected rows, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_list_tables_and_schema() {
+        let dir = TempDir::new().unwrap();
+        let conn = open_database(&fixture(&dir), false).unwrap();
+
+        let tables = list_tables(&conn).unwrap();
+        assert_eq!(
+            tables,
+            vec![
+                TableInfo {
+                    name: "long_visits".into(),
+                    kind: "view".into(),
+                    rows: None
+                },
+                TableInfo {
+                    name: "visits".into(),
+                    kind: "table".into(),
+                    rows: Some(3)
+                },
+            ]
+        );
+
+        let schema = schema(&conn, "visits").unwrap();
+        assert!(schema.starts_with("CREATE TABLE visits"));
+        assert!(schema.contains("- id INTEGER PRIMARY KEY"));
+        assert!(schema.contains("- url TEXT NOT NULL"));
+        assert!(super::schema(&conn, "missing").is_err());
+    }
+
+    #[test]
+    fn test_query_binds_parameters() {
+        let dir = TempDir::new().unwrap();
+        let conn = open_database(&fixture(&dir), false).unwrap();
+
+        let outcome = query(
+            &conn,
+            "SELECT url, title, duration FROM visits WHERE duration > ? ORDER BY id",
+            &[json!(10)],
+            DEFAULT_MAX_ROWS,
+            false,
+        )
+        .unwrap();
+        let (rows, truncated) = returned_rows(outcome);
+        assert!(!truncated);
+        assert_eq!(
+            Value::Array(rows.into_iter().map(Value::Object).collect()),
+            json!([
+                {"url": "https://rust-lang.org", "title": "Rust", "duration": 30.0},
+                {"url": "https://docs.rs", "title": null, "duration": 12.25},
+            ])
+        );
+
+        // Parameters are bound, never spliced into the SQL
+        let outcome = query(
+            &conn,
+            "SELECT COUNT(*) AS n FROM visits WHERE title = ?1",
+            &[json!("x' OR '1'='1")],
+            DEFAULT_MAX_ROWS,
+            false,
+        )
+        .unwrap();
+        assert_eq!(returned_rows(outcome).0[0]["n"], json!(0));
+    }
+
+    #[test]
+    fn test_query_row_cap() {
+        let dir = TempDir::new().unwrap();
+        let conn = open_database(&fixture(&dir), false).unwrap();
+
+        let (capped, truncated) = returned_rows(
+            query(&conn, "SELECT id FROM visits ORDER BY id", &[], 2, false).unwrap(),
+        );
+        assert_eq!(capped.len(), 2);
+        assert!(truncated);
+
+        let (all, truncated) = returned_rows(
+            query(&conn, "SELECT id FROM visits ORDER BY id", &[], 3, false).unwrap(),
+        );
+        assert_eq!(all.len(), 3);
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn test_writes_need_allow_writes() {
+        let dir = TempDir::new().unwrap();
+        let path = fixture(&dir);
+        let update = "UPDATE visits SET title = ?1 WHERE title IS NULL";
+
+        let conn = open_database(&path, false).unwrap();
+        let err = query(&conn, update, &[json!("Docs")], DEFAULT_MAX_ROWS, false).unwrap_err();
+        assert!(err.message.contains("allow_writes"));
+        // A second statement cannot be smuggled in behind a read
+        assert!(query(
+            &conn,
+            "SELECT 1; DELETE FROM visits",
+            &[],
+            DEFAULT_MAX_ROWS,
+            false
+        )
+        .is_err());
+
+        let conn = open_database(&path, true).unwrap();
+        assert_eq!(
+            query(&conn, update, &[json!("Docs")], DEFAULT_MAX_ROWS, true).unwrap(),
+            QueryOutcome::Changed(1)
+        );
+        assert_eq!(
+            query(
+                &conn,
+                "INSERT INTO visits (url) VALUES (?1), (?2)",
+                &[json!("https://a.test"), json!("https://b.test")],
+                DEFAULT_MAX_ROWS,
+                true
+            )
+            .unwrap(),
+            QueryOutcome::Changed(2)
+        );
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let dir = TempDir::new().unwrap();
+        let conn = open_database(&fixture(&dir), false).unwrap();
+        let output = dir.path().join("visits.csv");
+
+        let count = export_csv(
+            &conn,
+            "SELECT url, title, duration FROM visits ORDER BY id",
+            &[],
+            &output,
+        )
+        .unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "url,title,duration\n\
+             https://example.com,\"Example, Inc\",1.5\n\
+             https://rust-lang.org,Rust,30.0\n\
+             https://docs.rs,,12.25\n"
+        );
+        assert!(export_csv(&conn, "DELETE FROM visits", &[], &output).is_err());
+    }
+}
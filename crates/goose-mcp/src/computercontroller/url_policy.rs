@@ -0,0 +1,284 @@
+//! Restricts which URLs `web_scrape` may fetch, so the model cannot be used to reach cloud
+//! metadata endpoints, localhost services or the rest of the private network.
+
+use super::scrape_sessions::CookieJar;
+use goose::config::Config;
+use reqwest::header::{COOKIE, LOCATION};
+use reqwest::{redirect::Policy, ClientBuilder, Response};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use url::{Host, Url};
+
+/// Config key (or env var) that lets `web_scrape` reach private, loopback and link-local hosts
+pub const ALLOW_PRIVATE_NETWORKS_KEY: &str = "GOOSE_WEB_SCRAPE_ALLOW_PRIVATE_NETWORKS";
+
+const MAX_REDIRECTS: usize = 10;
+
+#[derive(Debug)]
+pub enum FetchError {
+    /// The URL, or a redirect it led to, is not allowed by the policy
+    Blocked(String),
+    /// The URL, or a redirect it led to, could not be parsed
+    Invalid(String),
+    Request(reqwest::Error),
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            // The opt-out is not mentioned here: this text goes back to the model, which a
+            // scraped page could talk into setting it
+            FetchError::Blocked(reason) => write!(f, "{}", reason),
+            FetchError::Invalid(reason) => write!(f, "{}", reason),
+            FetchError::Request(e) => write!(f, "Failed to fetch URL: {}", e),
+        }
+    }
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(e: reqwest::Error) -> Self {
+        FetchError::Request(e)
+    }
+}
+
+/// Tell the user, not the model, how to let `web_scrape` reach internal addresses
+pub fn log_blocked(reason: &str) {
+    tracing::warn!(
+        "Blocked web_scrape request: {}. Set {}=true in the global config or environment to \
+         allow scraping internal addresses",
+        reason,
+        ALLOW_PRIVATE_NETWORKS_KEY
+    );
+}
+
+/// Whether the user has opted out of the private network restriction
+pub fn private_networks_allowed() -> bool {
+    Config::global()
+        .get_param::<bool>(ALLOW_PRIVATE_NETWORKS_KEY)
+        .unwrap_or(false)
+}
+
+fn is_blocked_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // "this network", carrier-grade NAT, IETF protocol assignments, benchmarking, reserved
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 192 && b == 0 && ip.octets()[2] == 0)
+        || (a == 198 && (18..20).contains(&b))
+        || a >= 240
+}
+
+fn is_blocked_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return is_blocked_ipv4(v4);
+    }
+    let segments = ip.segments();
+    // NAT64 addresses embed an IPv4 address in the low 32 bits
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [.., hi, lo] = segments;
+        return is_blocked_ipv4(Ipv4Addr::from(((hi as u32) << 16) | lo as u32));
+    }
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // unique local fc00::/7 and link-local fe80::/10
+        || (segments[0] & 0xfe00) == 0xfc00
+        || (segments[0] & 0xffc0) == 0xfe80
+}
+
+/// Addresses that are never fetched unless private networks are explicitly allowed
+pub fn is_blocked_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_blocked_ipv4(v4),
+        IpAddr::V6(v6) => is_blocked_ipv6(v6),
+    }
+}
+
+/// Check the scheme and every address the host resolves to, returning the addresses that the
+/// request must connect to so a second DNS lookup cannot swap in an internal address.
+pub async fn check_url(url: &Url) -> Result<Vec<SocketAddr>, FetchError> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(FetchError::Blocked(format!(
+            "URL scheme '{}' is not allowed, only http and https",
+            url.scheme()
+        )));
+    }
+    let port = url.port_or_known_default().unwrap_or(80);
+
+    let addrs: Vec<SocketAddr> = match url.host() {
+        Some(Host::Ipv4(ip)) => vec![SocketAddr::new(IpAddr::V4(ip), port)],
+        Some(Host::Ipv6(ip)) => vec![SocketAddr::new(IpAddr::V6(ip), port)],
+        Some(Host::Domain(domain)) => tokio::net::lookup_host((domain, port))
+            .await
+            .map_err(|e| FetchError::Blocked(format!("Could not resolve {}: {}", domain, e)))?
+            .collect(),
+        None => {
+            return Err(FetchError::Invalid(format!("URL {} has no host", url)));
+        }
+    };
+
+    if addrs.is_empty() {
+        return Err(FetchError::Blocked(format!(
+            "{} did not resolve to any address",
+            url.host_str().unwrap_or_default()
+        )));
+    }
+    if let Some(addr) = addrs.iter().find(|addr| is_blocked_ip(addr.ip())) {
+        return Err(FetchError::Blocked(format!(
+            "{} resolves to {}, which is a private, loopback or link-local address",
+            url.host_str().unwrap_or_default(),
+            addr.ip()
+        )));
+    }
+    Ok(addrs)
+}
+
+/// GET `url`, checking it and every redirect hop against the policy.
+///
+/// `client_builder` supplies the base client settings; redirects are followed here rather than
+/// by reqwest so each hop is checked, and each connection is pinned to the checked addresses.
+/// Behind a proxy the proxy makes the final connection, so only the check applies.
+pub async fn guarded_get(
+    url: &str,
+    client_builder: impl Fn() -> ClientBuilder,
+) -> Result<Response, FetchError> {
+    get_following_redirects(url, client_builder, true, None).await
+}
+
+/// GET `url`, following redirects one hop at a time so each hop can be checked against the
+/// policy when `enforce_policy` is set, and can send and collect the cookies in `cookies`.
+pub async fn get_following_redirects(
+    url: &str,
+    client_builder: impl Fn() -> ClientBuilder,
+    enforce_policy: bool,
+    cookies: Option<&CookieJar>,
+) -> Result<Response, FetchError> {
+    let mut current =
+        Url::parse(url).map_err(|e| FetchError::Invalid(format!("Invalid URL {}: {}", url, e)))?;
+
+    for _ in 0..=MAX_REDIRECTS {
+        let mut builder = client_builder().redirect(Policy::none());
+        if enforce_policy {
+            let addrs = check_url(&current).await?;
+            if let Some(Host::Domain(domain)) = current.host() {
+                builder = builder.resolve_to_addrs(domain, &addrs);
+            }
+        }
+
+        let mut request = builder.build()?.get(current.clone());
+        if let Some(header) = cookies.and_then(|jar| jar.header_for(&current)) {
+            request = request.header(COOKIE, header);
+        }
+        let response = request.send().await?;
+        if let Some(jar) = cookies {
+            jar.store_from(&current, response.headers());
+        }
+
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|value| value.to_str().ok());
+        match location {
+            Some(location) if response.status().is_redirection() => {
+                current = current.join(location).map_err(|e| {
+                    FetchError::Invalid(format!("Invalid redirect to {}: {}", location, e))
+                })?;
+            }
+            _ => return Ok(response),
+        }
+    }
+
+    Err(FetchError::Blocked(format!(
+        "Too many redirects fetching {}",
+        url
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocked_ips() {
+        for ip in [
+            "169.254.169.254",
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:169.254.169.254",
+            "64:ff9b::a9fe:a9fe",
+        ] {
+            assert!(
+                is_blocked_ip(ip.parse().unwrap()),
+                "{} should be blocked",
+                ip
+            );
+        }
+
+        for ip in ["93.184.216.34", "8.8.8.8", "2606:4700::1111"] {
+            assert!(
+                !is_blocked_ip(ip.parse().unwrap()),
+                "{} should be allowed",
+                ip
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metadata_ip_is_blocked() {
+        let url = Url::parse("http://169.254.169.254/latest/meta-data/").unwrap();
+        let err = check_url(&url).await.unwrap_err();
+        assert!(matches!(err, FetchError::Blocked(_)));
+        assert!(err.to_string().contains("169.254.169.254"));
+        assert!(!err.to_string().contains(ALLOW_PRIVATE_NETWORKS_KEY));
+    }
+
+    #[tokio::test]
+    async fn test_non_http_schemes_are_blocked() {
+        for url in [
+            "file:///etc/passwd",
+            "ftp://example.com/",
+            "gopher://localhost/",
+        ] {
+            let err = check_url(&Url::parse(url).unwrap()).await.unwrap_err();
+            assert!(err.to_string().contains("scheme"), "{}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolved_hostnames_are_checked() {
+        let err = check_url(&Url::parse("http://localhost:8080/admin").unwrap())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("localhost resolves to"));
+    }
+
+    #[tokio::test]
+    async fn test_public_ip_literal_is_allowed() {
+        let addrs = check_url(&Url::parse("https://93.184.216.34/").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(addrs, vec!["93.184.216.34:443".parse().unwrap()]);
+    }
+
+    #[tokio::test]
+    async fn test_guarded_get_blocks_before_connecting() {
+        let err = guarded_get("http://[::ffff:127.0.0.1]:1/", ClientBuilder::new)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, FetchError::Blocked(_)));
+    }
+}
@@ -0,0 +1,140 @@
+//! HTTP client settings for `web_scrape`, so it can run behind corporate proxies and identify
+//! itself the way a network requires.
+
+use goose::config::Config;
+use reqwest::{Client, ClientBuilder, Proxy};
+
+/// Config key (or env var) holding a proxy URL used for every `web_scrape` request
+pub const PROXY_KEY: &str = "GOOSE_WEB_SCRAPE_PROXY";
+/// Config key (or env var) overriding the `User-Agent` sent by `web_scrape`
+pub const USER_AGENT_KEY: &str = "GOOSE_WEB_SCRAPE_USER_AGENT";
+
+pub const DEFAULT_USER_AGENT: &str = "goose/1.0";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebClientSettings {
+    pub user_agent: String,
+    /// Explicit proxy; when unset the standard `HTTP_PROXY`/`HTTPS_PROXY`/`NO_PROXY` env vars
+    /// are honored
+    pub proxy: Option<String>,
+}
+
+impl Default for WebClientSettings {
+    fn default() -> Self {
+        Self {
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            proxy: None,
+        }
+    }
+}
+
+impl WebClientSettings {
+    pub fn from_config(config: &Config) -> Self {
+        let non_empty = |key: &str| {
+            config
+                .get_param::<String>(key)
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        Self {
+            user_agent: non_empty(USER_AGENT_KEY).unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()),
+            proxy: non_empty(PROXY_KEY),
+        }
+    }
+
+    /// A client builder with the user agent and proxy applied. An invalid proxy URL is logged
+    /// and ignored, leaving the env var proxies in effect.
+    pub fn client_builder(&self) -> ClientBuilder {
+        let builder = Client::builder().user_agent(self.user_agent.as_str());
+        match self.proxy.as_deref().map(Proxy::all) {
+            Some(Ok(proxy)) => builder.proxy(proxy),
+            Some(Err(e)) => {
+                tracing::warn!("Ignoring invalid {} value: {}", PROXY_KEY, e);
+                builder
+            }
+            None => builder,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn config_with(dir: &TempDir, yaml: &str) -> Config {
+        let config_path = dir.path().join("config.yaml");
+        std::fs::write(&config_path, yaml).unwrap();
+        Config::new_with_file_secrets(config_path, dir.path().join("secrets.yaml")).unwrap()
+    }
+
+    #[test]
+    fn test_defaults_without_config() {
+        let dir = TempDir::new().unwrap();
+        let settings = WebClientSettings::from_config(&config_with(&dir, "{}"));
+        assert_eq!(settings, WebClientSettings::default());
+    }
+
+    #[test]
+    fn test_reads_user_agent_and_proxy() {
+        let dir = TempDir::new().unwrap();
+        let config = config_with(
+            &dir,
+            &format!(
+                "{}: \"acme-scraper/2.0\"\n{}: \"http://proxy.corp:3128\"\n",
+                USER_AGENT_KEY, PROXY_KEY
+            ),
+        );
+
+        let settings = WebClientSettings::from_config(&config);
+        assert_eq!(settings.user_agent, "acme-scraper/2.0");
+        assert_eq!(settings.proxy.as_deref(), Some("http://proxy.corp:3128"));
+    }
+
+    #[test]
+    fn test_invalid_proxy_still_builds_client() {
+        let settings = WebClientSettings {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        assert!(settings.client_builder().build().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_requests_go_through_proxy_with_user_agent() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = format!("http://{}", listener.local_addr().unwrap());
+        let proxy_task = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        });
+
+        let settings = WebClientSettings {
+            user_agent: "acme-scraper/2.0".to_string(),
+            proxy: Some(proxy),
+        };
+        let response = settings
+            .client_builder()
+            .build()
+            .unwrap()
+            .get("http://example.invalid/page")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.text().await.unwrap(), "ok");
+
+        let request = proxy_task.await.unwrap().to_lowercase();
+        assert!(request.starts_with("get http://example.invalid/page http/1.1"));
+        assert!(request.contains("user-agent: acme-scraper/2.0"));
+    }
+}
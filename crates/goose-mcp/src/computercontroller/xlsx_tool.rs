@@ -1,7 +1,8 @@
+use super::XlsxCellStyle;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
-use umya_spreadsheet::{Spreadsheet, Worksheet};
+use umya_spreadsheet::{Cell, Spreadsheet, Worksheet};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WorksheetInfo {
@@ -15,6 +16,53 @@ pub struct WorksheetInfo {
 pub struct CellValue {
     value: String,
     formula: Option<String>,
+    /// Only set for formula cells when computed values are requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    computed: Option<ComputedValue>,
+}
+
+/// The result of a formula as stored in the workbook. Formulas are not evaluated here, so this
+/// is the value cached by the application that last calculated the workbook.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ComputedValue {
+    Cached {
+        value: String,
+    },
+    /// The workbook stores the formula without a result, e.g. when it was generated by a
+    /// library rather than saved from a spreadsheet application
+    FormulaOnly,
+}
+
+impl CellValue {
+    fn from_cell(cell: Option<&Cell>, computed: bool) -> Self {
+        let Some(cell) = cell else {
+            return CellValue {
+                value: String::new(),
+                formula: None,
+                computed: None,
+            };
+        };
+
+        let value = cell.get_value().into_owned();
+        let formula = (!cell.get_formula().is_empty()).then(|| cell.get_formula().to_string());
+        let computed = match &formula {
+            Some(_) if computed => Some(if cell.get_raw_value().is_empty() {
+                ComputedValue::FormulaOnly
+            } else {
+                ComputedValue::Cached {
+                    value: value.clone(),
+                }
+            }),
+            _ => None,
+        };
+
+        CellValue {
+            value,
+            formula,
+            computed,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -31,6 +79,9 @@ pub struct XlsxTool {
     workbook: Spreadsheet,
 }
 
+/// The worksheet a new workbook gets when no names are given
+pub const DEFAULT_WORKSHEET: &str = "Sheet1";
+
 impl XlsxTool {
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
         let workbook =
@@ -38,6 +89,70 @@ impl XlsxTool {
         Ok(Self { workbook })
     }
 
+    /// An empty workbook with the given worksheets, or a single 'Sheet1' when none are named
+    pub fn create(sheet_names: &[String]) -> Result<Self> {
+        let mut xlsx = Self {
+            workbook: umya_spreadsheet::new_file_empty_worksheet(),
+        };
+        if sheet_names.is_empty() {
+            xlsx.add_worksheet(DEFAULT_WORKSHEET)?;
+        }
+        for name in sheet_names {
+            xlsx.add_worksheet(name)?;
+        }
+        Ok(xlsx)
+    }
+
+    /// Create and save a new workbook at `path`. An existing file is only replaced when
+    /// `overwrite` is set.
+    pub fn create_file<P: AsRef<Path>>(
+        path: P,
+        sheet_names: &[String],
+        overwrite: bool,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        if path.exists() && !overwrite {
+            anyhow::bail!(
+                "'{}' already exists; set overwrite to replace it",
+                path.display()
+            );
+        }
+        let xlsx = Self::create(sheet_names)?;
+        xlsx.save(path)?;
+        Ok(xlsx)
+    }
+
+    /// Open `path` for writing to `worksheet_name`, starting a new workbook when the file does
+    /// not exist and adding the worksheet when the workbook lacks it
+    pub fn open_or_create<P: AsRef<Path>>(path: P, worksheet_name: &str) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Self::create(&[worksheet_name.to_string()]);
+        }
+        let mut xlsx = Self::new(path)?;
+        if xlsx.workbook.get_sheet_by_name(worksheet_name).is_none() {
+            xlsx.add_worksheet(worksheet_name)?;
+        }
+        Ok(xlsx)
+    }
+
+    fn add_worksheet(&mut self, name: &str) -> Result<()> {
+        // Excel refuses to open workbooks that break these rules
+        if name.trim().is_empty()
+            || name.chars().count() > 31
+            || name.contains(['[', ']', ':', '*', '?', '/', '\\'])
+        {
+            anyhow::bail!(
+                "Invalid worksheet name '{}': names need 1 to 31 characters and none of []:*?/\\",
+                name
+            );
+        }
+        self.workbook
+            .new_sheet(name)
+            .map_err(|_| anyhow::anyhow!("Worksheet '{}' already exists", name))?;
+        Ok(())
+    }
+
     pub fn list_worksheets(&self) -> Result<Vec<WorksheetInfo>> {
         let mut worksheets = Vec::new();
         for (index, worksheet) in self.workbook.get_sheet_collection().iter().enumerate() {
@@ -52,6 +167,14 @@ impl XlsxTool {
         Ok(worksheets)
     }
 
+    pub fn worksheet_names(&self) -> Vec<String> {
+        self.workbook
+            .get_sheet_collection()
+            .iter()
+            .map(|worksheet| worksheet.get_name().to_string())
+            .collect()
+    }
+
     pub fn get_worksheet_by_name(&self, name: &str) -> Result<&Worksheet> {
         self.workbook
             .get_sheet_by_name(name)
@@ -96,7 +219,14 @@ impl XlsxTool {
         Ok(names)
     }
 
-    pub fn get_range(&self, worksheet: &Worksheet, range: &str) -> Result<RangeData> {
+    /// Values and formulas for a range; with `computed`, formula cells also report their cached
+    /// result or that none is stored
+    pub fn get_range(
+        &self,
+        worksheet: &Worksheet,
+        range: &str,
+        computed: bool,
+    ) -> Result<RangeData> {
         let (start_row, start_col, end_row, end_col) = parse_range(range)?;
         let mut values = Vec::new();
 
@@ -104,22 +234,10 @@ impl XlsxTool {
         for row_idx in start_row..=end_row {
             let mut row_values = Vec::new();
             for col_idx in start_col..=end_col {
-                let cell_value = if let Some(cell) = worksheet.get_cell((col_idx, row_idx)) {
-                    CellValue {
-                        value: cell.get_value().into_owned(),
-                        formula: if cell.get_formula().is_empty() {
-                            None
-                        } else {
-                            Some(cell.get_formula().to_string())
-                        },
-                    }
-                } else {
-                    CellValue {
-                        value: String::new(),
-                        formula: None,
-                    }
-                };
-                row_values.push(cell_value);
+                row_values.push(CellValue::from_cell(
+                    worksheet.get_cell((col_idx, row_idx)),
+                    computed,
+                ));
             }
             values.push(row_values);
         }
@@ -151,7 +269,62 @@ impl XlsxTool {
         Ok(())
     }
 
+    /// Apply `style` to every cell in `range`, returning how many cells were formatted. Only the
+    /// attributes set in `style` change; the rest of each cell's formatting is kept.
+    pub fn format_range(
+        &mut self,
+        worksheet_name: &str,
+        range: &str,
+        style: &XlsxCellStyle,
+    ) -> Result<usize> {
+        let font_color = style.font_color.as_deref().map(to_argb).transpose()?;
+        let fill_color = style.fill_color.as_deref().map(to_argb).transpose()?;
+        if style.bold.is_none()
+            && style.italic.is_none()
+            && style.number_format.is_none()
+            && font_color.is_none()
+            && fill_color.is_none()
+        {
+            anyhow::bail!("Style must set at least one of bold, italic, number_format, font_color or fill_color");
+        }
+
+        let (start_row, start_col, end_row, end_col) = parse_range(range)?;
+        let worksheet = self
+            .workbook
+            .get_sheet_by_name_mut(worksheet_name)
+            .context("Worksheet not found")?;
+
+        let mut count = 0;
+        for row in start_row.min(end_row)..=start_row.max(end_row) {
+            for col in start_col.min(end_col)..=start_col.max(end_col) {
+                let cell_style = worksheet.get_style_mut((col, row));
+                if let Some(bold) = style.bold {
+                    cell_style.get_font_mut().set_bold(bold);
+                }
+                if let Some(italic) = style.italic {
+                    cell_style.get_font_mut().set_italic(italic);
+                }
+                if let Some(color) = &font_color {
+                    cell_style.get_font_mut().get_color_mut().set_argb(color);
+                }
+                if let Some(color) = &fill_color {
+                    cell_style.set_background_color(color);
+                }
+                if let Some(format) = &style.number_format {
+                    cell_style.get_number_format_mut().set_format_code(format);
+                }
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Write the workbook to `path`, creating missing parent directories
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create parent directories")?;
+        }
         umya_spreadsheet::writer::xlsx::write(&self.workbook, path)
             .context("Failed to save Excel file")?;
         Ok(())
@@ -191,17 +364,40 @@ impl XlsxTool {
         Ok(matches)
     }
 
-    pub fn get_cell_value(&self, worksheet: &Worksheet, row: u32, col: u32) -> Result<CellValue> {
+    pub fn get_cell_value(
+        &self,
+        worksheet: &Worksheet,
+        row: u32,
+        col: u32,
+        computed: bool,
+    ) -> Result<CellValue> {
         let cell = worksheet.get_cell((col, row)).context("Cell not found")?;
+        Ok(CellValue::from_cell(Some(cell), computed))
+    }
+}
 
-        Ok(CellValue {
-            value: cell.get_value().into_owned(),
-            formula: if cell.get_formula().is_empty() {
-                None
-            } else {
-                Some(cell.get_formula().to_string())
-            },
-        })
+/// A single-cell range in A1 notation, e.g. `cell_range(2, 3)` is `C2:C2`
+pub fn cell_range(row: u32, col: u32) -> String {
+    let mut letters = String::new();
+    let mut col = col;
+    while col > 0 {
+        let rem = (col - 1) % 26;
+        letters.insert(0, (b'A' + rem as u8) as char);
+        col = (col - 1) / 26;
+    }
+    format!("{letters}{row}:{letters}{row}")
+}
+
+/// Convert a hex color ('FF0000', '#FF0000' or ARGB 'FFFF0000') to the ARGB form xlsx stores
+fn to_argb(color: &str) -> Result<String> {
+    let hex = color.trim().trim_start_matches('#').to_ascii_uppercase();
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!("Invalid color '{}', expected hex like 'FF0000'", color);
+    }
+    match hex.len() {
+        6 => Ok(format!("FF{}", hex)),
+        8 => Ok(hex),
+        _ => anyhow::bail!("Invalid color '{}', expected hex like 'FF0000'", color),
     }
 }
 
@@ -292,7 +488,7 @@ mod tests {
     fn test_get_range() -> Result<()> {
         let xlsx = XlsxTool::new(get_test_file())?;
         let worksheet = xlsx.get_worksheet_by_index(0)?;
-        let range = xlsx.get_range(worksheet, "A1:C5")?;
+        let range = xlsx.get_range(worksheet, "A1:C5", false)?;
         assert_eq!(range.values.len(), 5);
         println!("Range data: {:?}", range);
         Ok(())
@@ -314,22 +510,22 @@ mod tests {
         let worksheet = xlsx.get_worksheet_by_index(0)?;
 
         // Test header cell (known value from FinancialSample.xlsx)
-        let header_cell = xlsx.get_cell_value(worksheet, 1, 1)?;
+        let header_cell = xlsx.get_cell_value(worksheet, 1, 1, false)?;
         assert_eq!(header_cell.value, "Segment");
         assert!(header_cell.formula.is_none());
 
         // Test data cell (known value from FinancialSample.xlsx)
-        let data_cell = xlsx.get_cell_value(worksheet, 2, 2)?;
+        let data_cell = xlsx.get_cell_value(worksheet, 2, 2, false)?;
         assert_eq!(data_cell.value, "Canada");
         assert!(data_cell.formula.is_none());
 
         // Test B1 cell (known value from FinancialSample.xlsx)
-        let b1_cell = xlsx.get_cell_value(worksheet, 1, 2)?;
+        let b1_cell = xlsx.get_cell_value(worksheet, 1, 2, false)?;
         assert_eq!(b1_cell.value, "Country");
         assert!(b1_cell.formula.is_none());
 
         // Test A2 cell (known value from FinancialSample.xlsx)
-        let a2_cell = xlsx.get_cell_value(worksheet, 2, 1)?;
+        let a2_cell = xlsx.get_cell_value(worksheet, 2, 1, false)?;
         assert_eq!(a2_cell.value, "Government");
         assert!(a2_cell.formula.is_none());
 
@@ -348,22 +544,22 @@ mod tests {
 
         // Verify the coordinate system mapping
         // A1 should be row=1, col=1
-        let a1 = xlsx.get_cell_value(worksheet, 1, 1)?;
+        let a1 = xlsx.get_cell_value(worksheet, 1, 1, false)?;
         println!("A1 (1,1): {}", a1.value);
         assert_eq!(a1.value, "Segment");
 
         // A2 should be row=2, col=1
-        let a2 = xlsx.get_cell_value(worksheet, 2, 1)?;
+        let a2 = xlsx.get_cell_value(worksheet, 2, 1, false)?;
         println!("A2 (2,1): {}", a2.value);
         assert_eq!(a2.value, "Government");
 
         // B1 should be row=1, col=2
-        let b1 = xlsx.get_cell_value(worksheet, 1, 2)?;
+        let b1 = xlsx.get_cell_value(worksheet, 1, 2, false)?;
         println!("B1 (1,2): {}", b1.value);
         assert_eq!(b1.value, "Country");
 
         // B2 should be row=2, col=2
-        let b2 = xlsx.get_cell_value(worksheet, 2, 2)?;
+        let b2 = xlsx.get_cell_value(worksheet, 2, 2, false)?;
         println!("B2 (2,2): {}", b2.value);
         assert_eq!(b2.value, "Canada");
 
@@ -385,18 +581,18 @@ mod tests {
         let worksheet = xlsx.get_worksheet_by_index(0)?;
 
         // Test that A2 (row 2, column 1) returns the correct value
-        let a2_value = xlsx.get_cell_value(worksheet, 2, 1)?;
+        let a2_value = xlsx.get_cell_value(worksheet, 2, 1, false)?;
         assert_eq!(
             a2_value.value, "Government",
             "A2 should contain 'Government'"
         );
 
         // Test that B1 (row 1, column 2) returns its own value, not A2's
-        let b1_value = xlsx.get_cell_value(worksheet, 1, 2)?;
+        let b1_value = xlsx.get_cell_value(worksheet, 1, 2, false)?;
         assert_eq!(b1_value.value, "Country", "B1 should contain 'Country'");
 
         // Additional verification with ranges
-        let range = xlsx.get_range(worksheet, "A1:B2")?;
+        let range = xlsx.get_range(worksheet, "A1:B2", false)?;
         assert_eq!(
             range.values[0][0].value, "Segment",
             "A1 should be 'Segment'"
@@ -413,4 +609,167 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_computed_values() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("formulas.xlsx");
+
+        let mut workbook = umya_spreadsheet::new_file();
+        let sheet = workbook.get_sheet_mut(&0).unwrap();
+        sheet.get_cell_mut("A1").set_value_number(2);
+        sheet.get_cell_mut("A2").set_value_number(3);
+        sheet
+            .get_cell_mut("A3")
+            .set_formula("SUM(A1:A2)")
+            .set_formula_result_default("5");
+        sheet.get_cell_mut("A4").set_formula("A3*2");
+        umya_spreadsheet::writer::xlsx::write(&workbook, &path)?;
+
+        let xlsx = XlsxTool::new(&path)?;
+        let worksheet = xlsx.get_worksheet_by_index(0)?;
+
+        let cached = xlsx.get_cell_value(worksheet, 3, 1, true)?;
+        assert_eq!(cached.formula.as_deref(), Some("SUM(A1:A2)"));
+        assert_eq!(
+            cached.computed,
+            Some(ComputedValue::Cached {
+                value: "5".to_string()
+            })
+        );
+
+        let uncached = xlsx.get_cell_value(worksheet, 4, 1, true)?;
+        assert_eq!(uncached.formula.as_deref(), Some("A3*2"));
+        assert_eq!(uncached.computed, Some(ComputedValue::FormulaOnly));
+
+        // Plain values and requests without `computed` are unchanged
+        let range = xlsx.get_range(worksheet, "A1:A4", true)?;
+        assert!(range.values[0][0].computed.is_none());
+        assert_eq!(range.values[2][0].computed, cached.computed);
+        assert!(xlsx
+            .get_cell_value(worksheet, 3, 1, false)?
+            .computed
+            .is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_range_persists_on_save() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("report.xlsx");
+        let mut workbook = umya_spreadsheet::new_file();
+        let sheet = workbook.get_sheet_mut(&0).unwrap();
+        sheet.get_cell_mut("A1").set_value("Region");
+        sheet.get_cell_mut("B2").set_value_number(0.125);
+        umya_spreadsheet::writer::xlsx::write(&workbook, &path)?;
+
+        let mut xlsx = XlsxTool::new(&path)?;
+        let header = XlsxCellStyle {
+            bold: Some(true),
+            font_color: Some("#ffffff".to_string()),
+            fill_color: Some("1F4E78".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(xlsx.format_range("Sheet1", "A1:B1", &header)?, 2);
+        let percent = XlsxCellStyle {
+            italic: Some(true),
+            number_format: Some("0.00%".to_string()),
+            ..Default::default()
+        };
+        xlsx.format_range("Sheet1", &cell_range(2, 2), &percent)?;
+        xlsx.save(&path)?;
+
+        let reopened = XlsxTool::new(&path)?;
+        let worksheet = reopened.get_worksheet_by_name("Sheet1")?;
+        let style = worksheet.get_style("B1");
+        let font = style.get_font().unwrap();
+        assert!(*font.get_bold());
+        assert_eq!(font.get_color().get_argb(), "FFFFFFFF");
+        assert_eq!(style.get_background_color().unwrap().get_argb(), "FF1F4E78");
+
+        let style = worksheet.get_style("B2");
+        assert!(*style.get_font().unwrap().get_italic());
+        assert_eq!(
+            style.get_number_format().unwrap().get_format_code(),
+            "0.00%"
+        );
+        assert_eq!(worksheet.get_value("B2"), "0.125");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_range_rejects_bad_styles() -> Result<()> {
+        let mut xlsx = XlsxTool::new(get_test_file())?;
+        let sheet = xlsx.get_worksheet_by_index(0)?.get_name().to_string();
+
+        let empty = XlsxCellStyle::default();
+        assert!(xlsx.format_range(&sheet, "A1:A1", &empty).is_err());
+
+        let bad_color = XlsxCellStyle {
+            fill_color: Some("yellow".to_string()),
+            ..Default::default()
+        };
+        let err = xlsx.format_range(&sheet, "A1:A1", &bad_color).unwrap_err();
+        assert!(err.to_string().contains("Invalid color 'yellow'"));
+
+        assert_eq!(cell_range(1, 28), "AB1:AB1");
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_workbook_then_write_and_read() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("reports").join("q3").join("results.xlsx");
+
+        XlsxTool::create_file(&path, &["Summary".to_string(), "Raw".to_string()], false)?;
+        let mut xlsx = XlsxTool::new(&path)?;
+        assert_eq!(xlsx.worksheet_names(), ["Summary", "Raw"]);
+
+        xlsx.update_cell("Summary", 1, 1, "Region")?;
+        xlsx.update_cell("Summary", 2, 1, "EMEA")?;
+        xlsx.save(&path)?;
+
+        let reopened = XlsxTool::new(&path)?;
+        let worksheet = reopened.get_worksheet_by_name("Summary")?;
+        assert_eq!(reopened.get_column_names(worksheet)?, ["Region"]);
+        assert_eq!(
+            reopened.get_cell_value(worksheet, 2, 1, false)?.value,
+            "EMEA"
+        );
+
+        // An existing file is only replaced on request
+        let err = XlsxTool::create_file(&path, &[], false).err().unwrap();
+        assert!(err.to_string().contains("already exists"));
+        XlsxTool::create_file(&path, &[], true)?;
+        assert_eq!(XlsxTool::new(&path)?.worksheet_names(), [DEFAULT_WORKSHEET]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_or_create_adds_missing_workbook_and_sheet() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("new").join("data.xlsx");
+
+        let mut xlsx = XlsxTool::open_or_create(&path, "Results")?;
+        xlsx.update_cell("Results", 1, 2, "42")?;
+        xlsx.save(&path)?;
+
+        let mut xlsx = XlsxTool::open_or_create(&path, "Notes")?;
+        xlsx.update_cell("Notes", 1, 1, "draft")?;
+        xlsx.save(&path)?;
+
+        let reopened = XlsxTool::new(&path)?;
+        assert_eq!(reopened.worksheet_names(), ["Results", "Notes"]);
+        let results = reopened.get_worksheet_by_name("Results")?;
+        assert_eq!(reopened.get_cell_value(results, 1, 2, false)?.value, "42");
+        let notes = reopened.get_worksheet_by_name("Notes")?;
+        let range = reopened.get_range(notes, "A1:A1", false)?;
+        assert_eq!(range.values[0][0].value, "draft");
+
+        assert!(XlsxTool::create(&["a/b".to_string()]).is_err());
+        assert!(XlsxTool::create(&["Same".to_string(), "Same".to_string()]).is_err());
+        Ok(())
+    }
 }
@@ -62,6 +62,29 @@ impl AnalysisCache {
         cache.put(key, Arc::new(result));
     }
 
+    /// Look up several files under a single lock, returning a result per entry in order
+    pub fn get_many(&self, entries: &[(PathBuf, SystemTime)]) -> Vec<Option<AnalysisResult>> {
+        let mut cache = lock_or_recover(&self.cache, |c| c.clear());
+        entries
+            .iter()
+            .map(|(path, modified)| {
+                let key = CacheKey {
+                    path: path.clone(),
+                    modified: *modified,
+                };
+                cache.get(&key).map(|result| (**result).clone())
+            })
+            .collect()
+    }
+
+    /// Store several results under a single lock
+    pub fn put_many(&self, entries: Vec<(PathBuf, SystemTime, AnalysisResult)>) {
+        let mut cache = lock_or_recover(&self.cache, |c| c.clear());
+        for (path, modified, result) in entries {
+            cache.put(CacheKey { path, modified }, Arc::new(result));
+        }
+    }
+
     pub fn clear(&self) {
         let mut cache = lock_or_recover(&self.cache, |c| c.clear());
         cache.clear();
@@ -0,0 +1,124 @@
+use glob::Pattern;
+use std::path::{Path, PathBuf};
+
+use crate::developer::analyze::graph::CallGraph;
+use crate::developer::analyze::types::AnalysisResult;
+use crate::developer::lang;
+
+/// A function that nothing in the analyzed files calls
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeadCodeCandidate {
+    pub path: PathBuf,
+    pub line: usize,
+    pub name: String,
+}
+
+/// Find functions with no incoming call edges, leaving out entry points (`main`, exported and
+/// test functions) and anything matching `exclude`. Exclude patterns are globs matched against
+/// the function name and against the file path relative to `root`.
+pub fn find_dead_code(
+    root: &Path,
+    results: &[(PathBuf, AnalysisResult)],
+    graph: &CallGraph,
+    exclude: &[Pattern],
+) -> Vec<DeadCodeCandidate> {
+    let mut candidates = Vec::new();
+
+    for (path, result) in results {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        if exclude.iter().any(|pattern| pattern.matches_path(relative)) {
+            continue;
+        }
+        let test_file = is_test_file(relative);
+        let language = lang::get_language_identifier(path);
+        let source = std::fs::read_to_string(path).unwrap_or_default();
+        let lines: Vec<&str> = source.lines().collect();
+
+        for function in &result.functions {
+            if graph.is_called(&graph.node_id(&function.name, path))
+                || exclude
+                    .iter()
+                    .any(|pattern| pattern.matches(&function.name))
+                || test_file
+                || is_entry_point(language, &function.name, &lines, function.line)
+            {
+                continue;
+            }
+            candidates.push(DeadCodeCandidate {
+                path: path.clone(),
+                line: function.line,
+                name: function.name.clone(),
+            });
+        }
+    }
+
+    candidates.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    candidates
+}
+
+/// Whether the file holds tests, judging by the usual naming conventions
+fn is_test_file(relative: &Path) -> bool {
+    let in_test_dir = relative.parent().is_some_and(|parent| {
+        parent.components().any(|component| {
+            matches!(
+                component.as_os_str().to_str(),
+                Some("test" | "tests" | "__tests__" | "spec")
+            )
+        })
+    });
+    let name = relative
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let stem = name.split('.').next().unwrap_or_default();
+
+    in_test_dir
+        || name == "conftest.py"
+        || stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with("Test")
+        || stem.ends_with("Tests")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+}
+
+/// Whether a function is reached from outside the analyzed code: `main`, functions that are
+/// part of a public API, tests, and language hooks such as Python's dunder methods
+fn is_entry_point(language: &str, name: &str, lines: &[&str], line: usize) -> bool {
+    if name == "main" || (name.starts_with("__") && name.ends_with("__")) {
+        return true;
+    }
+
+    // Reported lines are 1-based and point at the function name
+    let index = line.saturating_sub(1);
+    let definition = lines.get(index).map(|l| l.trim_start()).unwrap_or_default();
+    let preceding = &lines[index.saturating_sub(3).min(lines.len())..index.min(lines.len())];
+    let has_attribute = |needle: &str| preceding.iter().any(|l| l.trim_start().contains(needle));
+
+    match language {
+        "rust" => {
+            definition.starts_with("pub ")
+                || definition.starts_with("pub(")
+                || has_attribute("#[test]")
+                || has_attribute("::test]")
+        }
+        "python" => name.starts_with("test"),
+        "javascript" | "typescript" => definition.starts_with("export "),
+        "go" => {
+            name.chars().next().is_some_and(char::is_uppercase)
+                || name.starts_with("Test")
+                || name.starts_with("Benchmark")
+        }
+        "java" => definition.contains("public ") || has_attribute("@Test"),
+        "kotlin" => {
+            !(definition.contains("private ") || definition.contains("internal "))
+                || has_attribute("@Test")
+        }
+        "swift" => {
+            definition.contains("public ")
+                || definition.contains("open ")
+                || name.starts_with("test")
+        }
+        _ => false,
+    }
+}
@@ -2,6 +2,8 @@ use rmcp::model::{Content, Role};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
+use crate::developer::analyze::dead_code::DeadCodeCandidate;
+use crate::developer::analyze::graph::split_node;
 use crate::developer::analyze::types::{
     AnalysisMode, AnalysisResult, CallChain, EntryType, FocusedAnalysisData,
 };
@@ -30,14 +32,24 @@ impl Formatter {
         match mode {
             AnalysisMode::Structure => Self::format_structure_overview(path, result),
             AnalysisMode::Semantic => Self::format_semantic_result(path, result),
-            AnalysisMode::Focused => {
-                // Focused mode is handled separately
-                tracing::warn!("format_analysis_result called with Focused mode");
+            AnalysisMode::Focused | AnalysisMode::DeadCode => {
+                // Focused and dead code modes are handled separately
+                tracing::warn!("format_analysis_result called with {:?} mode", mode);
                 String::new()
             }
         }
     }
 
+    /// Format a file that was not parsed because it is over the size limit
+    pub fn format_skipped_file(path: &Path, size: u64, max_file_bytes: u64) -> String {
+        format!(
+            "{} [skipped: {} bytes is over max_file_bytes={}]\n",
+            path.display(),
+            size,
+            max_file_bytes
+        )
+    }
+
     /// Format structure overview (compact format)
     pub fn format_structure_overview(path: &Path, result: &AnalysisResult) -> String {
         let mut output = String::new();
@@ -231,6 +243,14 @@ impl Formatter {
             ));
         }
 
+        let skipped = results
+            .iter()
+            .filter(|(_, entry)| matches!(entry, EntryType::SkippedFile(_)))
+            .count();
+        if skipped > 0 {
+            output.push_str(&format!("Skipped: {} files over max_file_bytes\n", skipped));
+        }
+
         // Add language distribution
         Self::append_language_stats(output, results, total_lines);
     }
@@ -255,7 +275,7 @@ impl Formatter {
         // Format language percentages
         if !language_lines.is_empty() && total_lines > 0 {
             let mut languages: Vec<_> = language_lines.iter().collect();
-            languages.sort_by(|a, b| b.1.cmp(a.1)); // Sort by lines descending
+            languages.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0))); // Sort by lines descending
 
             let lang_str: Vec<String> = languages
                 .iter()
@@ -363,6 +383,9 @@ impl Formatter {
                 }
                 output.push('\n');
             }
+            EntryType::SkippedFile(size) => {
+                output.push_str(&format!("{}{} [skipped: {} bytes]\n", indent, name, size));
+            }
             EntryType::Directory => {
                 // Only print if not already printed as a parent
                 if !printed_dirs.contains(relative_path) {
@@ -388,6 +411,45 @@ impl Formatter {
         }
     }
 
+    /// Format dead code candidates grouped by file, with paths relative to `base_path`
+    pub fn format_dead_code(
+        base_path: &Path,
+        candidates: &[DeadCodeCandidate],
+        functions_checked: usize,
+    ) -> String {
+        if candidates.is_empty() {
+            return format!(
+                "No dead code candidates: all {} functions have callers or are entry points.\n",
+                functions_checked
+            );
+        }
+
+        let mut output = format!(
+            "DEAD CODE CANDIDATES: {} of {} functions have no callers\n",
+            candidates.len(),
+            functions_checked
+        );
+        let mut current_file: Option<&Path> = None;
+        for candidate in candidates {
+            if current_file != Some(candidate.path.as_path()) {
+                let relative = candidate
+                    .path
+                    .strip_prefix(base_path)
+                    .unwrap_or(&candidate.path);
+                output.push_str(&format!("\n{}\n", relative.display()));
+                current_file = Some(candidate.path.as_path());
+            }
+            output.push_str(&format!("  {}:{}\n", candidate.name, candidate.line));
+        }
+
+        output.push_str(
+            "\nEntry points (main, exported and test functions) are not listed. \
+            Calls the analyzer cannot see, such as callbacks, trait or interface dispatch \
+            and reflection, can make a used function look dead, so check before deleting.\n",
+        );
+        output
+    }
+
     /// Format focused analysis output with call chains
     pub fn format_focused_output(focus_data: &FocusedAnalysisData) -> String {
         let mut output = format!("FOCUSED ANALYSIS: {}\n\n", focus_data.focus_symbol);
@@ -471,8 +533,13 @@ impl Formatter {
         }
 
         for chain in incoming_chains.iter().chain(outgoing_chains.iter()) {
-            for (file, _, _, _) in &chain.path {
+            for (file, _, from, to) in &chain.path {
                 all_files.insert(file.clone());
+                for node in [from, to] {
+                    if let (_, Some(defined_in)) = split_node(node) {
+                        all_files.insert(defined_in.to_path_buf());
+                    }
+                }
             }
         }
 
@@ -561,12 +628,34 @@ impl Formatter {
                         .unwrap_or("unknown")
                         .to_string()
                 });
-                format!("{}:{} ({} -> {})", alias, line, from, to)
+                format!(
+                    "{}:{} ({} -> {})",
+                    alias,
+                    line,
+                    Self::format_node(from, file_map),
+                    Self::format_node(to, file_map)
+                )
             })
             .collect::<Vec<_>>()
             .join(" -> ")
     }
 
+    /// Show a qualified graph node as `name@alias`, using the same file aliases as the chain
+    fn format_node(node: &str, file_map: &HashMap<PathBuf, String>) -> String {
+        match split_node(node) {
+            (name, Some(file)) => {
+                let alias = file_map.get(file).cloned().unwrap_or_else(|| {
+                    file.file_name()
+                        .and_then(|n| n.to_str())
+                        .unwrap_or("unknown")
+                        .to_string()
+                });
+                format!("{}@{}", name, alias)
+            }
+            (name, None) => name.to_string(),
+        }
+    }
+
     /// Append statistics section to output
     fn append_statistics(
         output: &mut String,
@@ -1,13 +1,34 @@
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::developer::analyze::types::{AnalysisResult, CallChain};
+use crate::developer::analyze::types::{AnalysisResult, CallChain, MatchMode};
+use crate::developer::lang;
 
+/// Caller recorded for calls made outside any function
+const MODULE_CALLER: &str = "<module>";
+
+/// Split a graph node into its symbol name and, for qualified nodes, the file defining it
+pub fn split_node(node: &str) -> (&str, Option<&Path>) {
+    match node.split_once('@') {
+        Some((name, file)) => (name, Some(Path::new(file))),
+        None => (node, None),
+    }
+}
+
+/// Call graph across the analyzed files.
+///
+/// With [`MatchMode::Exact`] each node is a symbol qualified by its defining file,
+/// `name@path`. A call resolves to the definition in the calling file, or else to the only
+/// definition in another file of the same language. Calls that can't be resolved that way
+/// point at the bare name. With [`MatchMode::NameOnly`] nodes are bare names, so same-named
+/// symbols in different files and languages are merged, which is what tracking a call through
+/// language bindings needs.
 #[derive(Debug, Clone, Default)]
 pub struct CallGraph {
     callers: HashMap<String, Vec<(PathBuf, usize, String)>>,
     callees: HashMap<String, Vec<(PathBuf, usize, String)>>,
     pub definitions: HashMap<String, Vec<(PathBuf, usize)>>,
+    match_mode: MatchMode,
 }
 
 impl CallGraph {
@@ -16,8 +37,45 @@ impl CallGraph {
     }
 
     pub fn build_from_results(results: &[(PathBuf, AnalysisResult)]) -> Self {
-        tracing::debug!("Building call graph from {} files", results.len());
-        let mut graph = Self::new();
+        Self::build_with_mode(results, MatchMode::default())
+    }
+
+    pub fn build_with_mode(results: &[(PathBuf, AnalysisResult)], match_mode: MatchMode) -> Self {
+        tracing::debug!(
+            "Building call graph from {} files with {:?} matching",
+            results.len(),
+            match_mode
+        );
+        let mut graph = Self {
+            match_mode,
+            ..Self::new()
+        };
+
+        // Symbols each file defines; functions that make calls count as defined there too,
+        // which covers methods
+        let mut defined_in: HashMap<&str, Vec<&Path>> = HashMap::new();
+        for (file_path, result) in results {
+            let names = result
+                .functions
+                .iter()
+                .map(|func| func.name.as_str())
+                .chain(result.classes.iter().flat_map(|class| {
+                    std::iter::once(class.name.as_str())
+                        .chain(class.methods.iter().map(|method| method.name.as_str()))
+                }))
+                .chain(
+                    result
+                        .calls
+                        .iter()
+                        .filter_map(|call| call.caller_name.as_deref()),
+                );
+            for name in names {
+                let files = defined_in.entry(name).or_default();
+                if !files.contains(&file_path.as_path()) {
+                    files.push(file_path);
+                }
+            }
+        }
 
         for (file_path, result) in results {
             // Record definitions
@@ -39,24 +97,25 @@ impl CallGraph {
 
             // Record call relationships
             for call in &result.calls {
-                let caller = call
-                    .caller_name
-                    .clone()
-                    .unwrap_or_else(|| "<module>".to_string());
+                let caller = match &call.caller_name {
+                    Some(name) => graph.node_id(name, file_path),
+                    None => MODULE_CALLER.to_string(),
+                };
+                let callee = graph.resolve_callee(&call.callee_name, file_path, &defined_in);
 
                 // Add to callers map (who calls this function)
-                graph
-                    .callers
-                    .entry(call.callee_name.clone())
-                    .or_default()
-                    .push((file_path.clone(), call.line, caller.clone()));
+                graph.callers.entry(callee.clone()).or_default().push((
+                    file_path.clone(),
+                    call.line,
+                    caller.clone(),
+                ));
 
                 // Add to callees map (what this function calls)
-                if caller != "<module>" {
+                if caller != MODULE_CALLER {
                     graph.callees.entry(caller).or_default().push((
                         file_path.clone(),
                         call.line,
-                        call.callee_name.clone(),
+                        callee,
                     ));
                 }
             }
@@ -72,6 +131,73 @@ impl CallGraph {
         graph
     }
 
+    /// The node for a symbol defined in `file`
+    pub fn node_id(&self, name: &str, file: &Path) -> String {
+        match self.match_mode {
+            MatchMode::Exact => format!("{}@{}", name, file.display()),
+            MatchMode::NameOnly => name.to_string(),
+        }
+    }
+
+    fn resolve_callee(
+        &self,
+        name: &str,
+        file: &Path,
+        defined_in: &HashMap<&str, Vec<&Path>>,
+    ) -> String {
+        if self.match_mode == MatchMode::NameOnly {
+            return name.to_string();
+        }
+        let files = defined_in.get(name).map(Vec::as_slice).unwrap_or_default();
+        if files.contains(&file) {
+            return self.node_id(name, file);
+        }
+        let language = lang::get_language_identifier(file);
+        let mut same_language = files
+            .iter()
+            .filter(|other| lang::get_language_identifier(other) == language);
+        match (same_language.next(), same_language.next()) {
+            (Some(only), None) => self.node_id(name, only),
+            _ => name.to_string(),
+        }
+    }
+
+    /// The nodes a query for `symbol` starts from: the node itself when it is qualified,
+    /// otherwise every node with that name, in a stable order
+    fn nodes_for<'a>(
+        &self,
+        edges: &'a HashMap<String, Vec<(PathBuf, usize, String)>>,
+        symbol: &str,
+    ) -> Vec<&'a String> {
+        let mut nodes: Vec<&String> = edges
+            .keys()
+            .filter(|node| {
+                node.as_str() == symbol || (!symbol.contains('@') && split_node(node).0 == symbol)
+            })
+            .collect();
+        nodes.sort();
+        nodes
+    }
+
+    /// Whether anything other than the symbol itself calls it. A qualified symbol also counts
+    /// as called when a call to its bare name couldn't be resolved to a single definition.
+    pub fn is_called(&self, symbol: &str) -> bool {
+        let (name, _) = split_node(symbol);
+        self.nodes_for(&self.callers, symbol)
+            .into_iter()
+            .chain(self.callers.get_key_value(name).map(|(node, _)| node))
+            .filter_map(|node| self.callers.get(node))
+            .any(|callers| {
+                callers.iter().any(|(_, _, caller)| {
+                    if symbol.contains('@') {
+                        caller != symbol
+                    } else {
+                        split_node(caller).0 != name
+                    }
+                })
+            })
+    }
+
     pub fn find_incoming_chains(&self, symbol: &str, max_depth: u32) -> Vec<CallChain> {
         tracing::trace!(
             "Finding incoming chains for {} with depth {}",
@@ -88,9 +214,9 @@ impl CallGraph {
         let mut queue = VecDeque::new();
 
         // Start with direct callers
-        if let Some(direct_callers) = self.callers.get(symbol) {
-            for (file, line, caller) in direct_callers {
-                let initial_path = vec![(file.clone(), *line, caller.clone(), symbol.to_string())];
+        for node in self.nodes_for(&self.callers, symbol) {
+            for (file, line, caller) in &self.callers[node] {
+                let initial_path = vec![(file.clone(), *line, caller.clone(), node.clone())];
 
                 if max_depth == 1 {
                     chains.push(CallChain { path: initial_path });
@@ -153,9 +279,9 @@ impl CallGraph {
         let mut queue = VecDeque::new();
 
         // Start with what this symbol calls
-        if let Some(direct_callees) = self.callees.get(symbol) {
-            for (file, line, callee) in direct_callees {
-                let initial_path = vec![(file.clone(), *line, symbol.to_string(), callee.clone())];
+        for node in self.nodes_for(&self.callees, symbol) {
+            for (file, line, callee) in &self.callees[node] {
+                let initial_path = vec![(file.clone(), *line, node.clone(), callee.clone())];
 
                 if max_depth == 1 {
                     chains.push(CallChain { path: initial_path });
@@ -0,0 +1,222 @@
+//! Incremental re-analysis of directories in a git work tree
+//!
+//! The results of each incremental directory analysis are kept along with `git status`. On
+//! the next run over the same directory only the paths git reports as changed, untracked or
+//! ignored, now or last time, are walked and parsed again; everything else is reused as is.
+//! Git ignored paths are included since a full walk does not skip them, and whole directories
+//! are shown for them, so they are cheap to list. When git is unavailable, HEAD has moved or
+//! an ignore file changed, the directory is analyzed in full.
+
+use lru::LruCache;
+use rmcp::model::ErrorData;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+use super::lock_or_recover;
+use super::traversal::{FileCandidate, FileTraverser};
+use super::types::{AnalysisMode, AnalysisResult, EntryType};
+
+/// Directories whose last results are kept
+const MAX_SNAPSHOTS: usize = 8;
+
+/// Files that change what a walk finds without git reporting the affected paths
+const IGNORE_FILES: &[&str] = &[".gitignore", ".gooseignore"];
+
+/// The options a snapshot was taken with; it is only reused for a run with the same ones
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotSettings {
+    pub mode: AnalysisMode,
+    pub max_depth: u32,
+    pub max_file_size: u64,
+    pub exclude: Vec<String>,
+}
+
+/// What `git status` reports for a directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitStatus {
+    pub head: Option<String>,
+    /// Changed, untracked and ignored paths under the directory
+    pub changed: HashSet<PathBuf>,
+}
+
+impl GitStatus {
+    /// Read the status of `root`, or `None` if git is unavailable or `root` is not in a work
+    /// tree
+    pub fn read(root: &Path) -> Option<Self> {
+        let prefix = git(root, &["rev-parse", "--show-prefix"])?;
+        let head = git(root, &["rev-parse", "--verify", "--quiet", "HEAD"])
+            .map(|head| head.trim().to_string());
+        let status = git(
+            root,
+            &[
+                "status",
+                "--porcelain",
+                "-z",
+                "--untracked-files=all",
+                "--ignored=matching",
+                "--",
+                ".",
+            ],
+        )?;
+        Some(Self {
+            head,
+            changed: parse_porcelain(&status, prefix.trim_end_matches('\n'), root),
+        })
+    }
+}
+
+fn git(root: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Turn `git status --porcelain -z` output into paths under `root`. Git reports paths
+/// relative to the top of the work tree, so `prefix` is the path of `root` within it.
+pub fn parse_porcelain(output: &str, prefix: &str, root: &Path) -> HashSet<PathBuf> {
+    let mut changed = HashSet::new();
+    let mut add = |path: &str| {
+        if let Some(relative) = path.trim_end_matches('/').strip_prefix(prefix) {
+            changed.insert(root.join(relative));
+        }
+    };
+
+    let mut fields = output.split('\0');
+    while let Some(entry) = fields.next() {
+        let Some((code, path)) = entry.split_at_checked(3) else {
+            continue;
+        };
+        add(path);
+        // Renames and copies are followed by the path they came from
+        if code.contains(['R', 'C']) {
+            if let Some(from) = fields.next() {
+                add(from);
+            }
+        }
+    }
+    changed
+}
+
+struct Snapshot {
+    settings: SnapshotSettings,
+    status: GitStatus,
+    entries: HashMap<PathBuf, EntryType>,
+}
+
+/// The last results of incremental directory analyses, by directory
+#[derive(Clone)]
+pub struct GitSnapshots {
+    snapshots: Arc<Mutex<LruCache<PathBuf, Snapshot>>>,
+}
+
+impl Default for GitSnapshots {
+    fn default() -> Self {
+        Self {
+            snapshots: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(MAX_SNAPSHOTS).unwrap(),
+            ))),
+        }
+    }
+}
+
+impl GitSnapshots {
+    /// Collect directory results like [`FileTraverser::collect_directory_results`], reusing
+    /// the last results for `root` where git shows nothing has changed since
+    pub fn collect_directory_results<F>(
+        &self,
+        root: &Path,
+        settings: SnapshotSettings,
+        traverser: &FileTraverser<'_>,
+        analyze_files: F,
+    ) -> Result<Vec<(PathBuf, EntryType)>, ErrorData>
+    where
+        F: FnOnce(&[FileCandidate]) -> Result<Vec<AnalysisResult>, ErrorData>,
+    {
+        let Some(status) = GitStatus::read(root) else {
+            tracing::debug!("{:?} is not in a git work tree, analyzing it in full", root);
+            return traverser.collect_directory_results(root, settings.max_depth, analyze_files);
+        };
+
+        let previous = lock_or_recover(&self.snapshots, |s| s.clear())
+            .pop(root)
+            .filter(|previous| {
+                previous.settings == settings && previous.status.head == status.head
+            });
+        let mut changed: Vec<PathBuf> = status.changed.iter().cloned().collect();
+        if let Some(previous) = &previous {
+            // A file changed last time and clean now was reverted or stashed
+            changed.extend(previous.status.changed.difference(&status.changed).cloned());
+        }
+        let ignore_files_changed = changed.iter().any(|path| {
+            path.file_name()
+                .is_some_and(|name| IGNORE_FILES.iter().any(|f| name == *f))
+        });
+
+        let entries = match previous {
+            Some(previous) if !ignore_files_changed => {
+                // Git never reports its own directory, so it is walked every time
+                let git_dir = root.join(".git");
+                if git_dir.exists() {
+                    changed.push(git_dir);
+                }
+                changed.sort();
+                tracing::debug!(
+                    "Re-analyzing {} changed paths under {:?}",
+                    changed.len(),
+                    root
+                );
+
+                let mut entries = previous.entries;
+                entries.retain(|path, _| !changed.iter().any(|c| path.starts_with(c)));
+                let files = traverser.collect_paths(root, &changed, settings.max_depth)?;
+                let results = analyze_files(&files.candidates)?;
+                entries.extend(files_to_entries(files.candidates, results, files.oversized));
+                entries
+            }
+            _ => {
+                let files = traverser.collect_candidates(root, settings.max_depth)?;
+                let results = analyze_files(&files.candidates)?;
+                files_to_entries(files.candidates, results, files.oversized).collect()
+            }
+        };
+
+        let results = entries
+            .iter()
+            .map(|(path, entry)| (path.clone(), entry.clone()))
+            .collect();
+        lock_or_recover(&self.snapshots, |s| s.clear()).put(
+            root.to_path_buf(),
+            Snapshot {
+                settings,
+                status,
+                entries,
+            },
+        );
+        Ok(results)
+    }
+}
+
+fn files_to_entries(
+    candidates: Vec<FileCandidate>,
+    results: Vec<AnalysisResult>,
+    oversized: Vec<(PathBuf, u64)>,
+) -> impl Iterator<Item = (PathBuf, EntryType)> {
+    candidates
+        .into_iter()
+        .zip(results)
+        .map(|(candidate, result)| (candidate.path, EntryType::File(result)))
+        .chain(
+            oversized
+                .into_iter()
+                .map(|(path, size)| (path, EntryType::SkippedFile(size))),
+        )
+}
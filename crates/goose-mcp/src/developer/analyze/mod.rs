@@ -1,6 +1,8 @@
 pub mod cache;
+pub mod dead_code;
 pub mod formatter;
 pub mod graph;
+pub mod incremental;
 pub mod languages;
 pub mod parser;
 pub mod traversal;
@@ -9,19 +11,30 @@ pub mod types;
 #[cfg(test)]
 mod tests;
 
+use goose::config::Config;
+use goose::token_counter::{CountTokens, HeuristicTokenCounter};
 use ignore::gitignore::Gitignore;
+use rayon::prelude::*;
 use rmcp::model::{CallToolResult, ErrorCode, ErrorData};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
 
 use crate::developer::lang;
 
 use self::cache::AnalysisCache;
+use self::dead_code::find_dead_code;
 use self::formatter::Formatter;
 use self::graph::CallGraph;
+use self::incremental::{GitSnapshots, SnapshotSettings};
 use self::parser::{ElementExtractor, ParserManager};
-use self::traversal::FileTraverser;
+use self::traversal::{build_exclusions, FileCandidate, FileTraverser, DEFAULT_MAX_FILE_SIZE};
 use self::types::{AnalysisMode, AnalysisResult, AnalyzeParams, FocusedAnalysisData};
 
+/// Config key for the size in bytes above which files are skipped when analyzing directories
+pub const MAX_FILE_SIZE_KEY: &str = "GOOSE_ANALYZE_MAX_FILE_SIZE";
+
 /// Helper to safely lock a mutex with poison recovery
 /// The recovery function is called on the mutex contents if the lock was poisoned
 pub(crate) fn lock_or_recover<T, F>(
@@ -44,6 +57,8 @@ where
 pub struct CodeAnalyzer {
     parser_manager: ParserManager,
     cache: AnalysisCache,
+    snapshots: GitSnapshots,
+    files_parsed: Arc<AtomicUsize>,
 }
 
 impl Default for CodeAnalyzer {
@@ -59,9 +74,16 @@ impl CodeAnalyzer {
         Self {
             parser_manager: ParserManager::new(),
             cache: AnalysisCache::new(100),
+            snapshots: GitSnapshots::default(),
+            files_parsed: Arc::new(AtomicUsize::new(0)),
         }
     }
 
+    /// Number of files read and parsed so far, not counting those served from a cache
+    pub fn files_parsed(&self) -> usize {
+        self.files_parsed.load(Ordering::Relaxed)
+    }
+
     /// Main analyze entry point
     pub fn analyze(
         &self,
@@ -71,7 +93,20 @@ impl CodeAnalyzer {
     ) -> Result<CallToolResult, ErrorData> {
         tracing::info!("Starting analysis of {:?} with params {:?}", path, params);
 
-        let traverser = FileTraverser::new(ignore_patterns);
+        let max_file_size = params.max_file_bytes.unwrap_or_else(|| {
+            Config::global()
+                .get_param::<u64>(MAX_FILE_SIZE_KEY)
+                .unwrap_or(DEFAULT_MAX_FILE_SIZE)
+        });
+        let root = if path.is_file() {
+            path.parent().unwrap_or(&path)
+        } else {
+            &path
+        };
+        let exclusions = build_exclusions(root, &params.exclude)?;
+        let traverser = FileTraverser::new(ignore_patterns)
+            .with_max_file_size(max_file_size)
+            .with_exclusions(exclusions);
 
         traverser.validate_path(&path)?;
 
@@ -81,10 +116,10 @@ impl CodeAnalyzer {
 
         let mut output = match mode {
             AnalysisMode::Focused => self.analyze_focused(&path, &params, &traverser)?,
+            AnalysisMode::DeadCode => self.analyze_dead_code(&path, &params, &traverser)?,
             AnalysisMode::Semantic => {
                 if path.is_file() {
-                    let result = self.analyze_file(&path, &mode)?;
-                    Formatter::format_analysis_result(&path, &result, &mode)
+                    self.analyze_single_file(&path, &traverser, &mode)?
                 } else {
                     // Semantic mode on directory - analyze all files
                     self.analyze_directory(&path, &params, &traverser, &mode)?
@@ -92,8 +127,7 @@ impl CodeAnalyzer {
             }
             AnalysisMode::Structure => {
                 if path.is_file() {
-                    let result = self.analyze_file(&path, &mode)?;
-                    Formatter::format_analysis_result(&path, &result, &mode)
+                    self.analyze_single_file(&path, &traverser, &mode)?
                 } else {
                     self.analyze_directory(&path, &params, &traverser, &mode)?
                 }
@@ -123,7 +157,7 @@ impl CodeAnalyzer {
                     • Using focus mode: focus=\"symbol_name\"\n\
                     • Reducing depth: max_depth=1",
                     line_count,
-                    line_count * 10, // rough token estimate
+                    HeuristicTokenCounter.count_tokens(&output),
                     OUTPUT_LIMIT,
                     path.display(),
                     if let Some(f) = &params.focus {
@@ -149,6 +183,10 @@ impl CodeAnalyzer {
             return AnalysisMode::Focused;
         }
 
+        if params.dead_code {
+            return AnalysisMode::DeadCode;
+        }
+
         // Otherwise, use semantic for files, structure for directories
         if path.is_file() {
             AnalysisMode::Semantic
@@ -157,6 +195,26 @@ impl CodeAnalyzer {
         }
     }
 
+    /// Analyze and format a single file, or report it as skipped if it is over the size limit
+    fn analyze_single_file(
+        &self,
+        path: &Path,
+        traverser: &FileTraverser<'_>,
+        mode: &AnalysisMode,
+    ) -> Result<String, ErrorData> {
+        let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if size > traverser.max_file_size() {
+            tracing::debug!("Skipping oversized file {:?} ({} bytes)", path, size);
+            return Ok(Formatter::format_skipped_file(
+                path,
+                size,
+                traverser.max_file_size(),
+            ));
+        }
+        let result = self.analyze_file(path, mode)?;
+        Ok(Formatter::format_analysis_result(path, &result, mode))
+    }
+
     /// Analyze a single file
     fn analyze_file(&self, path: &Path, mode: &AnalysisMode) -> Result<AnalysisResult, ErrorData> {
         tracing::debug!("Analyzing file {:?} in {:?} mode", path, mode);
@@ -189,13 +247,72 @@ impl CodeAnalyzer {
             return Ok(cached);
         }
 
+        if lang::is_binary_extension(path) {
+            tracing::trace!("Skipping binary file {:?}", path);
+            return Ok(AnalysisResult::empty(0));
+        }
+
+        let (result, cacheable) = self.parse_file(path, mode)?;
+        if cacheable {
+            self.cache.put(path.to_path_buf(), modified, result.clone());
+        }
+        Ok(result)
+    }
+
+    /// Analyze many files in parallel. The cache is consulted and updated once for the whole
+    /// batch rather than once per file, which keeps rayon workers from queueing on its lock.
+    fn analyze_candidates(
+        &self,
+        candidates: &[FileCandidate],
+        mode: &AnalysisMode,
+    ) -> Result<Vec<AnalysisResult>, ErrorData> {
+        let keys: Vec<(PathBuf, SystemTime)> = candidates
+            .iter()
+            .filter_map(|candidate| Some((candidate.path.clone(), candidate.modified?)))
+            .collect();
+        let mut cached = self.cache.get_many(&keys).into_iter();
+        let hits: Vec<Option<AnalysisResult>> = candidates
+            .iter()
+            .map(|candidate| candidate.modified.and_then(|_| cached.next().flatten()))
+            .collect();
+
+        let analyzed: Vec<(AnalysisResult, bool)> = candidates
+            .par_iter()
+            .zip(hits)
+            .map(|(candidate, hit)| match hit {
+                Some(result) => Ok((result, false)),
+                None => self.parse_file(&candidate.path, mode),
+            })
+            .collect::<Result<_, ErrorData>>()?;
+
+        let fresh = candidates
+            .iter()
+            .zip(&analyzed)
+            .filter(|(_, (_, cacheable))| *cacheable)
+            .filter_map(|(candidate, (result, _))| {
+                Some((candidate.path.clone(), candidate.modified?, result.clone()))
+            })
+            .collect();
+        self.cache.put_many(fresh);
+
+        Ok(analyzed.into_iter().map(|(result, _)| result).collect())
+    }
+
+    /// Read and parse a file, returning the result and whether it is worth caching
+    fn parse_file(
+        &self,
+        path: &Path,
+        mode: &AnalysisMode,
+    ) -> Result<(AnalysisResult, bool), ErrorData> {
+        self.files_parsed.fetch_add(1, Ordering::Relaxed);
+
         // Read file content - handle binary files gracefully
         let content = match std::fs::read_to_string(path) {
             Ok(content) => content,
             Err(e) => {
                 // Binary or non-UTF-8 file, skip parsing
                 tracing::trace!("Skipping binary/non-UTF-8 file {:?}: {}", path, e);
-                return Ok(AnalysisResult::empty(0));
+                return Ok((AnalysisResult::empty(0), false));
             }
         };
 
@@ -207,7 +324,7 @@ impl CodeAnalyzer {
         if language.is_empty() {
             tracing::trace!("Unsupported file type: {:?}", path);
             // Unsupported language, return empty result
-            return Ok(AnalysisResult::empty(line_count));
+            return Ok((AnalysisResult::empty(line_count), false));
         }
 
         // Check if we support this language for parsing
@@ -218,7 +335,7 @@ impl CodeAnalyzer {
 
         if !supported {
             tracing::trace!("Language {} not supported for parsing", language);
-            return Ok(AnalysisResult::empty(line_count));
+            return Ok((AnalysisResult::empty(line_count), false));
         }
 
         // Parse the file
@@ -231,10 +348,7 @@ impl CodeAnalyzer {
         // Add line count to the result
         result.line_count = line_count;
 
-        // Cache the result
-        self.cache.put(path.to_path_buf(), modified, result.clone());
-
-        Ok(result)
+        Ok((result, true))
     }
 
     /// Analyze a directory
@@ -248,11 +362,22 @@ impl CodeAnalyzer {
         tracing::debug!("Analyzing directory {:?} in {:?} mode", path, mode);
 
         let mode = *mode;
+        let analyze_files =
+            |candidates: &[FileCandidate]| self.analyze_candidates(candidates, &mode);
 
         // Collect directory results with parallel processing
-        let results = traverser.collect_directory_results(path, params.max_depth, |file_path| {
-            self.analyze_file(file_path, &mode)
-        })?;
+        let results = if params.incremental {
+            let settings = SnapshotSettings {
+                mode,
+                max_depth: params.max_depth,
+                max_file_size: traverser.max_file_size(),
+                exclude: params.exclude.clone(),
+            };
+            self.snapshots
+                .collect_directory_results(path, settings, traverser, analyze_files)?
+        } else {
+            traverser.collect_directory_results(path, params.max_depth, analyze_files)?
+        };
 
         // Format based on mode
         Ok(Formatter::format_directory_structure(
@@ -282,11 +407,14 @@ impl CodeAnalyzer {
         tracing::info!("Running focused analysis for symbol '{}'", focus_symbol);
 
         // Step 1: Collect all files to analyze
-        let files_to_analyze = if path.is_file() {
-            vec![path.to_path_buf()]
-        } else {
-            traverser.collect_files_for_focused(path, params.max_depth)?
-        };
+        let collected = traverser.collect_candidates(path, params.max_depth)?;
+        if !collected.oversized.is_empty() {
+            tracing::debug!(
+                "Leaving {} oversized files out of focused analysis",
+                collected.oversized.len()
+            );
+        }
+        let files_to_analyze = collected.candidates;
 
         tracing::debug!(
             "Analyzing {} files for focused analysis",
@@ -294,18 +422,15 @@ impl CodeAnalyzer {
         );
 
         // Step 2: Analyze all files and collect results using parallel processing
-        use rayon::prelude::*;
-        let all_results: Result<Vec<_>, _> = files_to_analyze
-            .par_iter()
-            .map(|file_path| {
-                self.analyze_file(file_path, &AnalysisMode::Semantic)
-                    .map(|result| (file_path.clone(), result))
-            })
+        let results = self.analyze_candidates(&files_to_analyze, &AnalysisMode::Semantic)?;
+        let files_to_analyze: Vec<PathBuf> = files_to_analyze
+            .into_iter()
+            .map(|candidate| candidate.path)
             .collect();
-        let all_results = all_results?;
+        let all_results: Vec<_> = files_to_analyze.iter().cloned().zip(results).collect();
 
         // Step 3: Build the call graph
-        let graph = CallGraph::build_from_results(&all_results);
+        let graph = CallGraph::build_with_mode(&all_results, params.match_mode);
 
         // Step 4: Find call chains based on follow_depth
         let incoming_chains = if params.follow_depth > 0 {
@@ -339,4 +464,50 @@ impl CodeAnalyzer {
 
         Ok(Formatter::format_focused_output(&focus_data))
     }
+
+    /// Dead code mode - list functions that nothing in the analyzed files calls
+    fn analyze_dead_code(
+        &self,
+        path: &Path,
+        params: &AnalyzeParams,
+        traverser: &FileTraverser<'_>,
+    ) -> Result<String, ErrorData> {
+        let exclude = params
+            .exclude
+            .iter()
+            .map(|pattern| {
+                glob::Pattern::new(pattern).map_err(|e| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        format!("Invalid exclude pattern '{}': {}", pattern, e),
+                        None,
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let candidates = traverser
+            .collect_candidates(path, params.max_depth)?
+            .candidates;
+        let results = self.analyze_candidates(&candidates, &AnalysisMode::Semantic)?;
+        let all_results: Vec<_> = candidates
+            .into_iter()
+            .map(|candidate| candidate.path)
+            .zip(results)
+            .collect();
+
+        let graph = CallGraph::build_with_mode(&all_results, params.match_mode);
+        let root = if path.is_file() {
+            path.parent().unwrap_or(path)
+        } else {
+            path
+        };
+        let dead = find_dead_code(root, &all_results, &graph, &exclude);
+        let functions_checked = all_results
+            .iter()
+            .map(|(_, result)| result.functions.len())
+            .sum();
+
+        Ok(Formatter::format_dead_code(root, &dead, functions_checked))
+    }
 }
@@ -209,7 +209,7 @@ impl ElementExtractor {
             for capture in match_.captures {
                 let node = capture.node;
                 let text = &source[node.byte_range()];
-                let line = source[..node.start_byte()].lines().count() + 1;
+                let line = node.start_position().row + 1;
 
                 match query.capture_names()[capture.index as usize] {
                     "func" => {
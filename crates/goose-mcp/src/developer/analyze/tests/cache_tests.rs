@@ -92,3 +92,19 @@ fn test_cache_default() {
     cache.put(path.clone(), time, result);
     assert!(cache.get(&path, time).is_some());
 }
+
+#[test]
+fn test_cache_batch_lookup() {
+    let cache = AnalysisCache::new(10);
+    let time = SystemTime::now();
+    let cached = PathBuf::from("cached.rs");
+    let missing = PathBuf::from("missing.rs");
+
+    cache.put_many(vec![(cached.clone(), time, create_test_result())]);
+
+    let results = cache.get_many(&[(missing, time), (cached.clone(), time)]);
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_none());
+    assert_eq!(results[1].as_ref().unwrap().function_count, 1);
+    assert!(cache.get(&cached, time).is_some());
+}
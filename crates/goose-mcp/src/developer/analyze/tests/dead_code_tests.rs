@@ -0,0 +1,123 @@
+// Tests for dead code detection
+
+use crate::developer::analyze::tests::fixtures::{
+    create_test_gitignore, create_test_result_with_calls,
+};
+use crate::developer::analyze::types::{AnalyzeParams, MatchMode};
+use crate::developer::analyze::CodeAnalyzer;
+use crate::developer::analyze::{dead_code::find_dead_code, graph::CallGraph};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn dead_code_params(path: &Path, exclude: Vec<String>) -> AnalyzeParams {
+    AnalyzeParams {
+        path: path.to_string_lossy().to_string(),
+        focus: None,
+        follow_depth: 2,
+        max_depth: 3,
+        force: false,
+        max_file_bytes: None,
+        dead_code: true,
+        match_mode: MatchMode::Exact,
+        exclude,
+        incremental: false,
+    }
+}
+
+fn create_fixture() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("tests")).unwrap();
+    fs::write(
+        root.join("app.py"),
+        "def main():\n    run()\n\n\ndef run():\n    helper()\n\n\ndef helper():\n    pass\n\n\ndef forgotten():\n    helper()\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("lib.rs"),
+        "pub fn api() {\n    inner();\n}\n\nfn inner() {}\n\nfn orphan() {\n    orphan();\n}\n\n#[test]\nfn checks_api() {\n    api();\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("tests/test_app.py"),
+        "def test_run():\n    pass\n\n\ndef make_fixture():\n    pass\n",
+    )
+    .unwrap();
+    temp_dir
+}
+
+#[test]
+fn test_reports_unreferenced_functions() {
+    let fixture = create_fixture();
+    let root = fixture.path();
+    let analyzer = CodeAnalyzer::new();
+
+    let result = analyzer
+        .analyze(
+            dead_code_params(root, vec![]),
+            root.to_path_buf(),
+            &create_test_gitignore(),
+        )
+        .unwrap();
+    let text = &result.content[0].as_text().unwrap().text;
+
+    assert!(text.starts_with("DEAD CODE CANDIDATES: 2 of 10 functions have no callers"));
+    assert!(text.contains("\napp.py\n  forgotten:13\n"));
+    // Recursion alone doesn't count as a caller
+    assert!(text.contains("\nlib.rs\n  orphan:7\n"));
+    for used_or_entry_point in [
+        "main",
+        "run",
+        "helper",
+        "api",
+        "inner",
+        "checks_api",
+        "test_run",
+    ] {
+        assert!(!text.contains(&format!("  {}:", used_or_entry_point)));
+    }
+    // Functions in test files are never reported
+    assert!(!text.contains("make_fixture"));
+}
+
+#[test]
+fn test_exclude_patterns() {
+    let fixture = create_fixture();
+    let root = fixture.path();
+    let analyzer = CodeAnalyzer::new();
+
+    let result = analyzer
+        .analyze(
+            dead_code_params(root, vec!["forgot*".to_string(), "*.rs".to_string()]),
+            root.to_path_buf(),
+            &create_test_gitignore(),
+        )
+        .unwrap();
+    let text = &result.content[0].as_text().unwrap().text;
+    // "*.rs" leaves the Rust files out of the walk entirely, so their functions aren't counted
+    assert!(text.starts_with("No dead code candidates: all 6 functions"));
+
+    let err = analyzer
+        .analyze(
+            dead_code_params(root, vec!["[".to_string()]),
+            root.to_path_buf(),
+            &create_test_gitignore(),
+        )
+        .unwrap_err();
+    assert!(err.message.starts_with("Invalid exclude pattern"));
+}
+
+#[test]
+fn test_find_dead_code_from_graph() {
+    let results = vec![(
+        PathBuf::from("/src/other.rb"),
+        create_test_result_with_calls(vec!["a", "b", "c"], vec![("a", "b")]),
+    )];
+    let graph = CallGraph::build_from_results(&results);
+
+    // Languages without a known export convention report every uncalled function
+    let dead = find_dead_code(Path::new("/src"), &results, &graph, &[]);
+    let names: Vec<_> = dead.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["a", "c"]);
+}
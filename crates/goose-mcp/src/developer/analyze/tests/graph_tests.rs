@@ -1,8 +1,10 @@
 // Tests for the graph module
 
+use crate::developer::analyze::formatter::Formatter;
 use crate::developer::analyze::graph::CallGraph;
 use crate::developer::analyze::tests::fixtures::create_test_result_with_calls;
-use std::path::PathBuf;
+use crate::developer::analyze::types::{AnalysisResult, CallChain, FocusedAnalysisData, MatchMode};
+use std::path::{Path, PathBuf};
 
 #[test]
 fn test_simple_call_chain() {
@@ -114,3 +116,107 @@ fn test_deep_chain() {
     assert_eq!(chains.len(), 1);
     assert_eq!(chains[0].path.len(), 4); // Full chain a->b->c->d->e
 }
+
+/// A Rust core and its Python bindings, both defining `compute`
+fn bindings_project() -> Vec<(PathBuf, AnalysisResult)> {
+    vec![
+        (
+            PathBuf::from("src/core.rs"),
+            create_test_result_with_calls(
+                vec!["compute", "kernel", "run"],
+                vec![("compute", "kernel"), ("run", "compute")],
+            ),
+        ),
+        (
+            PathBuf::from("python/bindings.py"),
+            create_test_result_with_calls(vec!["compute", "handler"], vec![("handler", "compute")]),
+        ),
+    ]
+}
+
+fn chain_nodes(chain: &CallChain) -> Vec<(String, String)> {
+    chain
+        .path
+        .iter()
+        .map(|(_, _, from, to)| (from.clone(), to.clone()))
+        .collect()
+}
+
+#[test]
+fn test_exact_match_keeps_same_named_symbols_apart() {
+    let graph = CallGraph::build_with_mode(&bindings_project(), MatchMode::Exact);
+
+    // Each call resolves to the definition in the calling file
+    let mut chains: Vec<_> = graph
+        .find_incoming_chains("compute", 1)
+        .iter()
+        .map(chain_nodes)
+        .collect();
+    chains.sort();
+    assert_eq!(
+        chains,
+        vec![
+            vec![(
+                "handler@python/bindings.py".to_string(),
+                "compute@python/bindings.py".to_string()
+            )],
+            vec![(
+                "run@src/core.rs".to_string(),
+                "compute@src/core.rs".to_string()
+            )],
+        ]
+    );
+
+    // A qualified symbol selects one definition
+    let chains = graph.find_incoming_chains("compute@src/core.rs", 1);
+    assert_eq!(chains.len(), 1);
+    assert_eq!(chain_nodes(&chains[0])[0].0, "run@src/core.rs");
+
+    // The Python wrapper doesn't lead into the Rust kernel
+    let chains = graph.find_outgoing_chains("handler", 2);
+    assert_eq!(chains.len(), 1);
+    assert_eq!(chains[0].path.len(), 1);
+
+    assert!(graph.is_called(&graph.node_id("kernel", Path::new("src/core.rs"))));
+    assert!(!graph.is_called(&graph.node_id("run", Path::new("src/core.rs"))));
+
+    // Chain output shows the qualifier with the file alias
+    let definitions = graph.definitions["compute"].clone();
+    let incoming = graph.find_incoming_chains("compute", 1);
+    let files = vec![
+        PathBuf::from("python/bindings.py"),
+        PathBuf::from("src/core.rs"),
+    ];
+    let output = Formatter::format_focused_output(&FocusedAnalysisData {
+        focus_symbol: "compute",
+        follow_depth: 1,
+        files_analyzed: &files,
+        definitions: &definitions,
+        incoming_chains: &incoming,
+        outgoing_chains: &[],
+    });
+    assert!(output.contains("F1:1 (handler@F1 -> compute@F1)"));
+    assert!(output.contains("F2:1 (run@F2 -> compute@F2)"));
+}
+
+#[test]
+fn test_name_only_match_merges_across_languages() {
+    let graph = CallGraph::build_with_mode(&bindings_project(), MatchMode::NameOnly);
+
+    let chains = graph.find_incoming_chains("compute", 1);
+    assert_eq!(chains.len(), 2);
+    assert!(chains
+        .iter()
+        .all(|chain| chain_nodes(chain)[0].1 == "compute"));
+
+    // The call from Python is followed into the Rust implementation
+    let chains = graph.find_outgoing_chains("handler", 2);
+    assert_eq!(chains.len(), 1);
+    assert_eq!(
+        chain_nodes(&chains[0]),
+        vec![
+            ("handler".to_string(), "compute".to_string()),
+            ("compute".to_string(), "kernel".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,150 @@
+// Tests for incremental re-analysis
+
+use crate::developer::analyze::incremental::parse_porcelain;
+use crate::developer::analyze::tests::fixtures::create_test_gitignore;
+use crate::developer::analyze::types::{AnalyzeParams, MatchMode};
+use crate::developer::analyze::CodeAnalyzer;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_AUTHOR_NAME", "test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+fn create_repo() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src/nested")).unwrap();
+    fs::write(root.join("main.py"), "def main():\n    helper()\n").unwrap();
+    fs::write(root.join("src/lib.rs"), "fn helper() {}\n").unwrap();
+    fs::write(root.join("src/nested/util.rs"), "fn util() {}\n").unwrap();
+    fs::write(root.join(".gitignore"), "build/\n").unwrap();
+    fs::create_dir_all(root.join("build")).unwrap();
+    fs::write(root.join("build/generated.rs"), "fn generated() {}\n").unwrap();
+    git(root, &["init", "-q"]);
+    git(root, &["add", "."]);
+    git(root, &["commit", "-q", "-m", "initial"]);
+    temp_dir
+}
+
+fn analyze(analyzer: &CodeAnalyzer, root: &Path, incremental: bool) -> String {
+    let params = AnalyzeParams {
+        path: root.to_string_lossy().to_string(),
+        focus: None,
+        follow_depth: 2,
+        max_depth: 3,
+        force: false,
+        max_file_bytes: None,
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: vec![],
+        incremental,
+    };
+    let result = analyzer
+        .analyze(params, root.to_path_buf(), &create_test_gitignore())
+        .unwrap();
+    result
+        .content
+        .iter()
+        .map(|content| content.as_text().unwrap().text.clone())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Run an incremental analysis with the per-file cache cleared, so any file not taken from
+/// the snapshot has to be parsed, returning the output and how many files were parsed
+fn analyze_incremental(analyzer: &CodeAnalyzer, root: &Path) -> (String, usize) {
+    analyzer.cache.clear();
+    let before = analyzer.files_parsed();
+    let output = analyze(analyzer, root, true);
+    (output, analyzer.files_parsed() - before)
+}
+
+#[test]
+fn test_incremental_reparses_only_changed_files() {
+    let repo = create_repo();
+    let root = repo.path();
+    let analyzer = CodeAnalyzer::new();
+
+    let (output, parsed) = analyze_incremental(&analyzer, root);
+    assert_eq!(parsed, 4);
+    assert_eq!(output, analyze(&CodeAnalyzer::new(), root, false));
+
+    fs::write(root.join("src/lib.rs"), "fn helper() {}\nfn another() {}\n").unwrap();
+    let (output, parsed) = analyze_incremental(&analyzer, root);
+    // The modified file, and the git ignored one which is walked every time
+    assert_eq!(parsed, 2);
+    assert_eq!(output, analyze(&CodeAnalyzer::new(), root, false));
+    assert!(output.contains("lib.rs [2L, 2F]"), "{}", output);
+
+    fs::write(root.join("src/new.rs"), "fn fresh() {}\n").unwrap();
+    let (output, parsed) = analyze_incremental(&analyzer, root);
+    assert_eq!(parsed, 3);
+    assert_eq!(output, analyze(&CodeAnalyzer::new(), root, false));
+
+    // Reverting a change is picked up even though git no longer reports the file
+    git(root, &["checkout", "--", "src/lib.rs"]);
+    fs::remove_file(root.join("src/new.rs")).unwrap();
+    let (output, parsed) = analyze_incremental(&analyzer, root);
+    assert_eq!(parsed, 2);
+    assert_eq!(output, analyze(&CodeAnalyzer::new(), root, false));
+    assert!(!output.contains("new.rs"));
+}
+
+#[test]
+fn test_incremental_runs_in_full_after_head_moves() {
+    let repo = create_repo();
+    let root = repo.path();
+    let analyzer = CodeAnalyzer::new();
+    analyze_incremental(&analyzer, root);
+
+    fs::write(root.join("src/lib.rs"), "fn helper() {}\nfn b() {}\n").unwrap();
+    git(root, &["commit", "-q", "-am", "second"]);
+    let (output, parsed) = analyze_incremental(&analyzer, root);
+    assert_eq!(parsed, 4);
+    assert_eq!(output, analyze(&CodeAnalyzer::new(), root, false));
+}
+
+#[test]
+fn test_incremental_outside_git_matches_full_run() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+    fs::write(root.join("b.py"), "def b():\n    pass\n").unwrap();
+
+    let analyzer = CodeAnalyzer::new();
+    let (output, parsed) = analyze_incremental(&analyzer, root);
+    assert_eq!(parsed, 2);
+    assert_eq!(output, analyze(&CodeAnalyzer::new(), root, false));
+}
+
+#[test]
+fn test_parse_porcelain() {
+    let root = Path::new("/repo/sub");
+    let output =
+        " M sub/a.rs\0?? sub/new/b.rs\0R  sub/moved.rs\0sub/old.rs\0!! sub/build/\0 M other/c.rs\0";
+    let changed = parse_porcelain(output, "sub/", root);
+    let expected: HashSet<PathBuf> = [
+        "/repo/sub/a.rs",
+        "/repo/sub/new/b.rs",
+        "/repo/sub/moved.rs",
+        "/repo/sub/old.rs",
+        "/repo/sub/build",
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .collect();
+    assert_eq!(changed, expected);
+}
@@ -1,7 +1,8 @@
 // Integration tests for the analyze module
 
 use crate::developer::analyze::tests::fixtures::create_test_gitignore;
-use crate::developer::analyze::{types::AnalyzeParams, CodeAnalyzer};
+use crate::developer::analyze::types::{AnalyzeParams, MatchMode};
+use crate::developer::analyze::CodeAnalyzer;
 use std::fs;
 use tempfile::TempDir;
 
@@ -18,6 +19,11 @@ fn test_analyze_python_file() {
         follow_depth: 2,
         max_depth: 3,
         force: false,
+        max_file_bytes: None,
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: vec![],
+        incremental: false,
     };
 
     let ignore = create_test_gitignore();
@@ -46,6 +52,11 @@ fn test_analyze_directory() {
         follow_depth: 2,
         max_depth: 3,
         force: false,
+        max_file_bytes: None,
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: vec![],
+        incremental: false,
     };
 
     let ignore = create_test_gitignore();
@@ -82,6 +93,11 @@ fn test_focused_analysis() {
         follow_depth: 1,
         max_depth: 3,
         force: false,
+        max_file_bytes: None,
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: vec![],
+        incremental: false,
     };
 
     let ignore = create_test_gitignore();
@@ -110,6 +126,11 @@ fn test_analyze_with_cache() {
         follow_depth: 2,
         max_depth: 3,
         force: false,
+        max_file_bytes: None,
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: vec![],
+        incremental: false,
     };
 
     let ignore = create_test_gitignore();
@@ -141,6 +162,11 @@ fn test_analyze_unsupported_file() {
         follow_depth: 2,
         max_depth: 3,
         force: false,
+        max_file_bytes: None,
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: vec![],
+        incremental: false,
     };
 
     let ignore = create_test_gitignore();
@@ -159,6 +185,11 @@ fn test_analyze_nonexistent_path() {
         follow_depth: 2,
         max_depth: 3,
         force: false,
+        max_file_bytes: None,
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: vec![],
+        incremental: false,
     };
 
     let ignore = create_test_gitignore();
@@ -183,6 +214,11 @@ fn test_focused_without_symbol() {
         follow_depth: 1,
         max_depth: 3,
         force: false,
+        max_file_bytes: None,
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: vec![],
+        incremental: false,
     };
 
     let ignore = create_test_gitignore();
@@ -220,6 +256,11 @@ fn test_nested_directory_analysis() {
         follow_depth: 2,
         max_depth: 3, // Increase max_depth to ensure we reach nested files
         force: false,
+        max_file_bytes: None,
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: vec![],
+        incremental: false,
     };
 
     let ignore = create_test_gitignore();
@@ -234,3 +275,42 @@ fn test_nested_directory_analysis() {
         assert!(text_content.text.contains("src"));
     }
 }
+
+#[test]
+fn test_oversized_files_reported_as_skipped() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir_path = temp_dir.path();
+    let bundle = dir_path.join("bundle.js");
+    fs::write(dir_path.join("app.py"), "def main():\n    pass\n").unwrap();
+    fs::write(&bundle, "function f(){return 1}\n".repeat(100)).unwrap();
+
+    let analyzer = CodeAnalyzer::new();
+    let ignore = create_test_gitignore();
+    let params = |path: &std::path::Path| AnalyzeParams {
+        path: path.to_string_lossy().to_string(),
+        focus: None,
+        follow_depth: 2,
+        max_depth: 3,
+        force: false,
+        max_file_bytes: Some(1000),
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: vec![],
+        incremental: false,
+    };
+
+    let result = analyzer
+        .analyze(params(dir_path), dir_path.to_path_buf(), &ignore)
+        .unwrap();
+    let text = &result.content[0].as_text().unwrap().text;
+    assert!(text.contains("Shown: 1 files"));
+    assert!(text.contains("Skipped: 1 files over max_file_bytes"));
+    assert!(text.contains("bundle.js [skipped: 2300 bytes]"));
+    assert!(text.contains("app.py [2L, 1F]"));
+
+    let result = analyzer
+        .analyze(params(&bundle), bundle.clone(), &ignore)
+        .unwrap();
+    let text = &result.content[0].as_text().unwrap().text;
+    assert!(text.ends_with("bundle.js [skipped: 2300 bytes is over max_file_bytes=1000]\n"));
+}
@@ -1,5 +1,6 @@
 use super::fixtures::create_test_gitignore;
-use crate::developer::analyze::{types::AnalyzeParams, CodeAnalyzer};
+use crate::developer::analyze::types::{AnalyzeParams, MatchMode};
+use crate::developer::analyze::CodeAnalyzer;
 use std::fs;
 use tempfile::TempDir;
 
@@ -35,6 +36,11 @@ fn test_large_output_warning() {
         follow_depth: 2,
         max_depth: 3,
         force: false, // Should trigger warning
+        max_file_bytes: None,
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: vec![],
+        incremental: false,
     };
 
     let result = analyzer
@@ -83,6 +89,11 @@ fn test_force_flag_bypasses_warning() {
         follow_depth: 2,
         max_depth: 3,
         force: true, // Should bypass warning
+        max_file_bytes: None,
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: vec![],
+        incremental: false,
     };
 
     let result = analyzer
@@ -120,6 +131,11 @@ fn test_small_output_no_warning() {
         follow_depth: 2,
         max_depth: 3,
         force: false, // Shouldn't matter for small output
+        max_file_bytes: None,
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: vec![],
+        incremental: false,
     };
 
     let result = analyzer
@@ -1,9 +1,11 @@
 // Test modules for the analyze tool
 
 pub mod cache_tests;
+pub mod dead_code_tests;
 pub mod fixtures;
 pub mod formatter_tests;
 pub mod graph_tests;
+pub mod incremental_tests;
 pub mod integration_tests;
 pub mod large_output_tests;
 pub mod parser_tests;
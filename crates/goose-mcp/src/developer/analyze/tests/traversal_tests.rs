@@ -1,7 +1,9 @@
 // Tests for the traversal module
 
 use crate::developer::analyze::tests::fixtures::create_test_gitignore;
-use crate::developer::analyze::traversal::FileTraverser;
+use crate::developer::analyze::traversal::{build_exclusions, FileTraverser};
+use crate::developer::analyze::types::{AnalyzeParams, MatchMode};
+use crate::developer::analyze::CodeAnalyzer;
 use ignore::gitignore::Gitignore;
 use std::fs;
 use std::path::Path;
@@ -188,3 +190,156 @@ fn test_gitignore_patterns() {
     assert!(files.iter().any(|p| p.ends_with("main.py")));
     assert!(!files.iter().any(|p| p.ends_with(".log")));
 }
+
+#[test]
+fn test_skips_large_and_binary_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir_path = temp_dir.path();
+
+    fs::write(dir_path.join("small.rs"), "fn main() {}").unwrap();
+    fs::write(dir_path.join("generated.rs"), "// table\n".repeat(200)).unwrap();
+    fs::write(dir_path.join("logo.png"), [0x89, b'P', b'N', b'G']).unwrap();
+
+    let ignore = Gitignore::empty();
+    let traverser = FileTraverser::new(&ignore).with_max_file_size(1024);
+
+    let files = traverser.collect_candidates(dir_path, 0).unwrap();
+
+    assert_eq!(files.candidates.len(), 1);
+    assert!(files.candidates[0].path.ends_with("small.rs"));
+    assert!(files.candidates[0].modified.is_some());
+    assert_eq!(files.oversized, vec![(dir_path.join("generated.rs"), 1800)]);
+
+    // Without the cap the large file is included
+    let traverser = FileTraverser::new(&ignore).with_max_file_size(u64::MAX);
+    let files = traverser.collect_candidates(dir_path, 0).unwrap();
+    assert_eq!(files.candidates.len(), 2);
+    assert!(files.oversized.is_empty());
+}
+
+fn analyze_params(path: &Path, focus: Option<&str>, exclude: &[&str]) -> AnalyzeParams {
+    AnalyzeParams {
+        path: path.to_string_lossy().to_string(),
+        focus: focus.map(String::from),
+        follow_depth: 2,
+        max_depth: 0,
+        force: true,
+        max_file_bytes: None,
+        dead_code: false,
+        match_mode: MatchMode::Exact,
+        exclude: exclude.iter().map(|pattern| pattern.to_string()).collect(),
+        incremental: false,
+    }
+}
+
+fn output_text(result: rmcp::model::CallToolResult) -> String {
+    result
+        .content
+        .iter()
+        .filter_map(|content| content.as_text().map(|text| text.text.clone()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn tree_with_generated_code(dir_path: &Path) {
+    fs::create_dir_all(dir_path.join("src")).unwrap();
+    fs::create_dir_all(dir_path.join("generated/proto")).unwrap();
+    fs::write(
+        dir_path.join("src/main.rs"),
+        "fn main() {\n    helper();\n}\n\nfn helper() {}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir_path.join("generated/proto/messages.rs"),
+        "fn encode() {\n    helper();\n}\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_exclude_globs_apply_to_structure_and_focused_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir_path = temp_dir.path();
+    tree_with_generated_code(dir_path);
+
+    let analyzer = CodeAnalyzer::new();
+    let ignore = Gitignore::empty();
+
+    // Without exclusions both files show up
+    let structure = output_text(
+        analyzer
+            .analyze(
+                analyze_params(dir_path, None, &[]),
+                dir_path.to_path_buf(),
+                &ignore,
+            )
+            .unwrap(),
+    );
+    assert!(structure.contains("messages.rs"));
+
+    let structure = output_text(
+        analyzer
+            .analyze(
+                analyze_params(dir_path, None, &["generated/"]),
+                dir_path.to_path_buf(),
+                &ignore,
+            )
+            .unwrap(),
+    );
+    assert!(structure.contains("main.rs"));
+    assert!(!structure.contains("messages.rs"));
+    assert!(!structure.contains("generated"));
+
+    // A fresh analyzer, since cached structure results leave out call details
+    let focused = output_text(
+        CodeAnalyzer::new()
+            .analyze(
+                analyze_params(dir_path, Some("helper"), &["generated/"]),
+                dir_path.to_path_buf(),
+                &ignore,
+            )
+            .unwrap(),
+    );
+    assert!(focused.contains("main.rs"));
+    assert!(!focused.contains("messages.rs"));
+    assert!(!focused.contains("encode"));
+
+    // Invalid globs are rejected
+    let err = analyzer
+        .analyze(
+            analyze_params(dir_path, None, &["src/[z-a]"]),
+            dir_path.to_path_buf(),
+            &ignore,
+        )
+        .unwrap_err();
+    assert_eq!(err.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+}
+
+#[test]
+fn test_gooseignore_at_analysis_root() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir_path = temp_dir.path();
+    tree_with_generated_code(dir_path);
+    fs::write(dir_path.join(".gooseignore"), "generated/proto\n").unwrap();
+
+    let exclusions = build_exclusions(dir_path, &[]).unwrap();
+    let ignore = Gitignore::empty();
+    let traverser = FileTraverser::new(&ignore).with_exclusions(exclusions);
+    assert!(traverser.is_excluded(&dir_path.join("generated/proto"), true));
+    assert!(!traverser.is_excluded(&dir_path.join("src"), true));
+
+    let files = traverser.collect_files_for_focused(dir_path, 0).unwrap();
+    assert_eq!(files, vec![dir_path.join("src/main.rs")]);
+
+    let focused = output_text(
+        CodeAnalyzer::new()
+            .analyze(
+                analyze_params(dir_path, Some("helper"), &[]),
+                dir_path.to_path_buf(),
+                &ignore,
+            )
+            .unwrap(),
+    );
+    assert!(focused.contains("main.rs"));
+    assert!(!focused.contains("messages.rs"));
+}
@@ -1,20 +1,94 @@
-use ignore::gitignore::Gitignore;
-use rayon::prelude::*;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use rmcp::model::{ErrorCode, ErrorData};
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use crate::developer::analyze::types::{AnalysisResult, EntryType};
 use crate::developer::lang;
 
+/// Files larger than this are skipped by default; they are almost always generated code or
+/// data, and parsing them dominates the time spent on large trees
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// A file found while walking a directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileCandidate {
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+}
+
+/// The files found under a path: those to analyze, and those skipped for being over the size
+/// limit along with their size in bytes
+#[derive(Debug, Default)]
+pub struct CollectedFiles {
+    pub candidates: Vec<FileCandidate>,
+    pub oversized: Vec<(PathBuf, u64)>,
+}
+
+/// Build the exclusions for a single analysis rooted at `root`: the `exclude` globs passed to
+/// the tool, plus a `.gooseignore` file at `root` if there is one. Both use gitignore syntax,
+/// relative to `root`.
+pub fn build_exclusions(root: &Path, exclude: &[String]) -> Result<Gitignore, ErrorData> {
+    let mut builder = GitignoreBuilder::new(root);
+
+    let local_ignore = root.join(".gooseignore");
+    if local_ignore.is_file() {
+        if let Some(e) = builder.add(&local_ignore) {
+            tracing::warn!("Failed to read {:?}: {}", local_ignore, e);
+        }
+    }
+
+    for pattern in exclude {
+        builder.add_line(None, pattern).map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Invalid exclude pattern '{}': {}", pattern, e),
+                None,
+            )
+        })?;
+    }
+
+    builder.build().map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            format!("Invalid exclude patterns: {}", e),
+            None,
+        )
+    })
+}
+
 /// Handles file system traversal with ignore patterns
 pub struct FileTraverser<'a> {
     ignore_patterns: &'a Gitignore,
+    exclusions: Gitignore,
+    max_file_size: u64,
 }
 
 impl<'a> FileTraverser<'a> {
     /// Create a new file traverser with the given ignore patterns
     pub fn new(ignore_patterns: &'a Gitignore) -> Self {
-        Self { ignore_patterns }
+        Self {
+            ignore_patterns,
+            exclusions: Gitignore::empty(),
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+        }
+    }
+
+    /// Also leave out files and directories matching `exclusions` while walking, see
+    /// [`build_exclusions`]
+    pub fn with_exclusions(mut self, exclusions: Gitignore) -> Self {
+        self.exclusions = exclusions;
+        self
+    }
+
+    /// Skip files larger than `max_file_size` bytes
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    pub fn max_file_size(&self) -> u64 {
+        self.max_file_size
     }
 
     /// Check if a path should be ignored
@@ -26,6 +100,15 @@ impl<'a> FileTraverser<'a> {
         ignored
     }
 
+    /// Check if a path found while walking is excluded for this analysis
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        let excluded = self.exclusions.matched(path, is_dir).is_ignore();
+        if excluded {
+            tracing::trace!("Path {:?} is excluded", path);
+        }
+        excluded
+    }
+
     /// Validate that a path exists and is not ignored
     pub fn validate_path(&self, path: &Path) -> Result<(), ErrorData> {
         // Check if path is ignored
@@ -58,6 +141,21 @@ impl<'a> FileTraverser<'a> {
         path: &Path,
         max_depth: u32,
     ) -> Result<Vec<PathBuf>, ErrorData> {
+        Ok(self
+            .collect_candidates(path, max_depth)?
+            .candidates
+            .into_iter()
+            .map(|candidate| candidate.path)
+            .collect())
+    }
+
+    /// Collect the files worth analyzing under `path`, with the modification time read while
+    /// walking so analysis doesn't stat each file again
+    pub fn collect_candidates(
+        &self,
+        path: &Path,
+        max_depth: u32,
+    ) -> Result<CollectedFiles, ErrorData> {
         tracing::debug!(
             "Collecting files from {:?} with max_depth {}",
             path,
@@ -68,36 +166,116 @@ impl<'a> FileTraverser<'a> {
             tracing::warn!("Unlimited depth traversal requested for {:?}", path);
         }
 
-        let files = self.collect_files_recursive(path, 0, max_depth)?;
+        let mut files = CollectedFiles::default();
+        if path.is_file() {
+            if let Ok(metadata) = path.metadata() {
+                self.push_candidate(path.to_path_buf(), &metadata, &mut files);
+            }
+        } else {
+            self.collect_files_recursive(path, 0, max_depth, &mut files)?;
+        }
 
-        tracing::info!("Collected {} files from {:?}", files.len(), path);
+        tracing::info!(
+            "Collected {} files from {:?} ({} over the size limit)",
+            files.candidates.len(),
+            path,
+            files.oversized.len()
+        );
         Ok(files)
     }
 
-    /// Recursively collect files
-    fn collect_files_recursive(
+    /// Collect what a full walk of `root` would find at `paths`, walking those that are
+    /// directories. Paths the walk would not reach, for being too deep or under an ignored or
+    /// excluded directory, are left out.
+    pub fn collect_paths(
         &self,
-        path: &Path,
-        current_depth: u32,
+        root: &Path,
+        paths: &[PathBuf],
         max_depth: u32,
-    ) -> Result<Vec<PathBuf>, ErrorData> {
-        let mut files = Vec::new();
+    ) -> Result<CollectedFiles, ErrorData> {
+        let mut files = CollectedFiles::default();
+        for path in paths {
+            let Ok(relative) = path.strip_prefix(root) else {
+                continue;
+            };
+            let components: Vec<_> = relative.components().collect();
+            let depth = components.len() as u32;
+            if depth == 0 {
+                continue;
+            }
+            let hidden_parent = (1..components.len()).any(|i| {
+                let dir = root.join(components[..i].iter().collect::<PathBuf>());
+                self.is_ignored(&dir) || self.is_excluded(&dir, true)
+            });
+            let is_dir = path.is_dir();
+            if hidden_parent || self.is_ignored(path) || self.is_excluded(path, is_dir) {
+                continue;
+            }
 
-        // Check if we're at a file (base case)
-        if path.is_file() {
-            let lang = lang::get_language_identifier(path);
-            if !lang.is_empty() {
-                tracing::trace!("Including file {:?} (language: {})", path, lang);
-                files.push(path.to_path_buf());
+            if is_dir {
+                self.collect_files_recursive(path, depth, max_depth, &mut files)?;
+            } else if (max_depth == 0 || depth <= max_depth) && !Self::skip_by_name(path) {
+                if let Ok(metadata) = std::fs::metadata(path) {
+                    if metadata.is_file() {
+                        self.push_candidate(path.clone(), &metadata, &mut files);
+                    }
+                }
             }
-            return Ok(files);
         }
 
+        // A changed directory and a changed file inside it would otherwise both be listed
+        files.candidates.sort_by(|a, b| a.path.cmp(&b.path));
+        files.candidates.dedup_by(|a, b| a.path == b.path);
+        files.oversized.sort();
+        files.oversized.dedup();
+        Ok(files)
+    }
+
+    /// Whether a file can be skipped from its name alone: binary files and languages we
+    /// don't report on
+    fn skip_by_name(path: &Path) -> bool {
+        lang::is_binary_extension(path) || lang::get_language_identifier(path).is_empty()
+    }
+
+    fn push_candidate(
+        &self,
+        path: PathBuf,
+        metadata: &std::fs::Metadata,
+        files: &mut CollectedFiles,
+    ) {
+        if Self::skip_by_name(&path) {
+            return;
+        }
+        if metadata.len() > self.max_file_size {
+            tracing::debug!(
+                "Skipping {:?}: {} bytes is over the {} byte limit",
+                path,
+                metadata.len(),
+                self.max_file_size
+            );
+            files.oversized.push((path, metadata.len()));
+            return;
+        }
+        tracing::trace!("Including file {:?}", path);
+        files.candidates.push(FileCandidate {
+            path,
+            modified: metadata.modified().ok(),
+        });
+    }
+
+    /// Recursively collect files
+    fn collect_files_recursive(
+        &self,
+        path: &Path,
+        current_depth: u32,
+        max_depth: u32,
+        files: &mut CollectedFiles,
+    ) -> Result<(), ErrorData> {
         // max_depth of 0 means unlimited depth
         // current_depth starts at 0, max_depth is the number of directory levels to traverse
         if max_depth > 0 && current_depth >= max_depth {
             tracing::trace!("Reached max depth {} at {:?}", max_depth, path);
-            return Ok(files);
+            return Ok(());
         }
 
         let entries = std::fs::read_dir(path).map_err(|e| {
@@ -125,47 +303,65 @@ impl<'a> FileTraverser<'a> {
                 continue;
             }
 
-            if entry_path.is_file() {
-                // Only include supported file types
-                let lang = lang::get_language_identifier(&entry_path);
-                if !lang.is_empty() {
-                    tracing::trace!("Including file {:?} (language: {})", entry_path, lang);
-                    files.push(entry_path);
-                }
-            } else if entry_path.is_dir() {
+            // The entry's type usually comes from the directory listing itself, so only
+            // symlinks need a stat to find out what they point at
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let is_dir = if file_type.is_symlink() {
+                entry_path.is_dir()
+            } else {
+                file_type.is_dir()
+            };
+
+            if self.is_excluded(&entry_path, is_dir) {
+                continue;
+            }
+
+            if is_dir {
                 // Recurse into subdirectory
-                let mut sub_files =
-                    self.collect_files_recursive(&entry_path, current_depth + 1, max_depth)?;
-                files.append(&mut sub_files);
+                self.collect_files_recursive(&entry_path, current_depth + 1, max_depth, files)?;
+            } else if !Self::skip_by_name(&entry_path) {
+                // Only stat files that pass the name checks
+                if let Ok(metadata) = std::fs::metadata(&entry_path) {
+                    if metadata.is_file() {
+                        self.push_candidate(entry_path, &metadata, files);
+                    }
+                }
             }
         }
 
-        Ok(files)
+        Ok(())
     }
 
-    /// Collect directory results for analysis with parallel processing
+    /// Collect directory results for analysis. `analyze_files` gets every candidate at once,
+    /// so it can batch its work, and returns one result per candidate in the same order.
+    /// Files over the size limit are listed as skipped.
     pub fn collect_directory_results<F>(
         &self,
         path: &Path,
         max_depth: u32,
-        analyze_file: F,
+        analyze_files: F,
     ) -> Result<Vec<(PathBuf, EntryType)>, ErrorData>
     where
-        F: Fn(&Path) -> Result<AnalysisResult, ErrorData> + Sync,
+        F: FnOnce(&[FileCandidate]) -> Result<Vec<AnalysisResult>, ErrorData>,
     {
         tracing::debug!("Collecting directory results from {:?}", path);
 
-        // First collect all files to analyze
-        let files_to_analyze = self.collect_files_recursive(path, 0, max_depth)?;
+        let files = self.collect_candidates(path, max_depth)?;
+        let results = analyze_files(&files.candidates)?;
 
-        // Then analyze them in parallel using Rayon
-        let results: Result<Vec<_>, ErrorData> = files_to_analyze
-            .par_iter()
-            .map(|file_path| {
-                analyze_file(file_path).map(|result| (file_path.clone(), EntryType::File(result)))
-            })
-            .collect();
-
-        results
+        Ok(files
+            .candidates
+            .into_iter()
+            .zip(results)
+            .map(|(candidate, result)| (candidate.path, EntryType::File(result)))
+            .chain(
+                files
+                    .oversized
+                    .into_iter()
+                    .map(|(path, size)| (path, EntryType::SkippedFile(size))),
+            )
+            .collect())
     }
 }
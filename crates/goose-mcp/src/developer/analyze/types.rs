@@ -22,6 +22,37 @@ pub struct AnalyzeParams {
     /// Allow large outputs without warning (default: false)
     #[serde(default)]
     pub force: bool,
+
+    /// Files larger than this many bytes are listed as skipped instead of parsed (default: 1048576)
+    pub max_file_bytes: Option<u64>,
+
+    /// List functions that nothing calls as dead code candidates, instead of the overview
+    #[serde(default)]
+    pub dead_code: bool,
+
+    /// How calls are matched to definitions in focused and dead code modes. "exact" (default) keeps same-named symbols in different files apart and shows chains as name@file. "name-only" merges them, e.g. to follow a call from Python bindings into a Rust core
+    #[serde(default)]
+    pub match_mode: MatchMode,
+
+    /// Gitignore-style globs for files and directories to leave out, e.g. "gen/" or "*.pb.go". A .gooseignore at the analyzed path is applied too. In dead code mode they also match function names
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// Reuse the last overview of this directory, re-parsing only what git status reports as changed or untracked. Output matches a full run; falls back to one outside a git work tree
+    #[serde(default)]
+    pub incremental: bool,
+}
+
+/// How the call graph matches calls to definitions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum MatchMode {
+    /// Symbols are qualified by their defining file
+    #[default]
+    Exact,
+    /// Same-named symbols are merged across files and languages
+    #[serde(alias = "name_only")]
+    NameOnly,
 }
 
 fn default_follow_depth() -> u32 {
@@ -91,6 +122,8 @@ pub enum ReferenceType {
 #[derive(Debug, Clone)]
 pub enum EntryType {
     File(AnalysisResult),
+    /// A file over the size limit, with its size in bytes
+    SkippedFile(u64),
     Directory,
     SymlinkDir(PathBuf),
     SymlinkFile(PathBuf),
@@ -120,6 +153,7 @@ pub enum AnalysisMode {
     Structure, // Directory overview
     Semantic,  // File details
     Focused,   // Symbol tracking
+    DeadCode,  // Uncalled functions
 }
 
 impl AnalysisMode {
@@ -128,6 +162,7 @@ impl AnalysisMode {
             AnalysisMode::Structure => "structure",
             AnalysisMode::Semantic => "semantic",
             AnalysisMode::Focused => "focused",
+            AnalysisMode::DeadCode => "dead_code",
         }
     }
 
@@ -136,6 +171,7 @@ impl AnalysisMode {
             "structure" => AnalysisMode::Structure,
             "semantic" => AnalysisMode::Semantic,
             "focused" => AnalysisMode::Focused,
+            "dead_code" => AnalysisMode::DeadCode,
             _ => AnalysisMode::Structure,
         }
     }
@@ -37,3 +37,20 @@ pub fn get_language_identifier(path: &Path) -> &'static str {
         _ => "",
     }
 }
+
+/// Whether a file's extension marks it as binary, so its contents aren't worth reading
+pub fn is_binary_extension(path: &Path) -> bool {
+    const BINARY_EXTENSIONS: &[&str] = &[
+        "png", "jpg", "jpeg", "gif", "bmp", "ico", "webp", "tiff", "psd", "pdf", "zip", "gz",
+        "tgz", "bz2", "xz", "7z", "rar", "jar", "war", "class", "exe", "dll", "so", "dylib", "a",
+        "o", "obj", "lib", "wasm", "bin", "dat", "db", "sqlite", "pyc", "pyo", "whl", "mp3", "mp4",
+        "mov", "avi", "wav", "flac", "ogg", "woff", "woff2", "ttf", "otf", "eot",
+    ];
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            BINARY_EXTENSIONS
+                .iter()
+                .any(|binary| binary.eq_ignore_ascii_case(ext))
+        })
+}
@@ -2,6 +2,7 @@ pub mod analyze;
 mod editor_models;
 mod goose_hints;
 mod lang;
+mod search_files;
 mod shell;
 mod text_editor;
 
@@ -34,8 +34,12 @@ use tokio_util::sync::CancellationToken;
 use super::analyze::{types::AnalyzeParams, CodeAnalyzer};
 use super::editor_models::{create_editor_model, EditorModel};
 use super::goose_hints::load_hints::{load_hint_files, GOOSE_HINTS_FILENAME};
+use super::search_files::{
+    format_results, search_files, SearchOptions, DEFAULT_MAX_FILE_BYTES, DEFAULT_MAX_RESULTS,
+};
 use super::shell::{
     configure_shell_command, expand_path, get_shell_config, is_absolute_path, kill_process_group,
+    OutputFilter,
 };
 use super::text_editor::{
     text_editor_insert, text_editor_replace, text_editor_undo, text_editor_view, text_editor_write,
@@ -90,6 +94,32 @@ pub struct TextEditorParams {
 pub struct ShellParams {
     /// The command string to execute in the shell
     pub command: String,
+
+    /// Filters that shrink the output before it is returned, such as only the last lines or
+    /// only lines matching a regex. The unfiltered output is saved to a file whose path is
+    /// reported.
+    #[serde(default)]
+    pub output_filter: Option<OutputFilter>,
+}
+
+/// Parameters for the search_files tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchFilesParams {
+    /// Directory to search, absolute or relative to the current directory
+    pub path: String,
+
+    /// Only search files matching this glob, e.g. `*.rs` or `src/**/*.ts`
+    pub glob: Option<String>,
+
+    /// Regular expression to find in file contents. Without it, matching file paths are listed.
+    pub pattern: Option<String>,
+
+    /// Whether the glob and pattern are case-sensitive (defaults to false)
+    #[serde(default)]
+    pub case_sensitive: bool,
+
+    /// Maximum number of matches to return (defaults to 100)
+    pub max_results: Option<usize>,
 }
 
 /// Parameters for the image_processor tool
@@ -198,7 +228,8 @@ impl ServerHandler for DeveloperServer {
                 You can use the shell tool to run Windows commands (PowerShell or CMD).
                 When using paths, you can use either backslashes or forward slashes.
 
-                Use the shell tool as needed to locate files or interact with the project.
+                Use the search_files tool to find files by name or content, and the shell tool as needed
+                to interact with the project.
 
                 Leverage `analyze` through `return_last_only=true` subagents for deep codebase understanding with lean context
                 - delegate analysis, retain summaries
@@ -222,7 +253,8 @@ impl ServerHandler for DeveloperServer {
                 and can be used to solve a wide range of problems.
 
             You can use the shell tool to run any command that would work on the relevant operating system.
-            Use the shell tool as needed to locate files or interact with the project.
+            Use the search_files tool to find files by name or content, and the shell tool as needed
+            to interact with the project.
 
             Leverage `analyze` through `return_last_only=true` subagents for deep codebase understanding with lean context
             - delegate analysis, retain summaries
@@ -570,7 +602,8 @@ impl DeveloperServer {
     /// of the screen_capture tool.
     #[tool(
         name = "list_windows",
-        description = "List all available window titles that can be used with screen_capture. Returns a list of window titles that can be used with the window_title parameter of the screen_capture tool."
+        description = "List all available window titles that can be used with screen_capture. Returns a list of window titles that can be used with the window_title parameter of the screen_capture tool.",
+        annotations(read_only_hint = true)
     )]
     pub async fn list_windows(&self) -> Result<CallToolResult, ErrorData> {
         let windows = Window::all().map_err(|_| {
@@ -602,7 +635,8 @@ impl DeveloperServer {
     /// Only one of display or window_title should be specified.
     #[tool(
         name = "screen_capture",
-        description = "Capture a screenshot of a specified display or window. You can capture either: 1. A full display (monitor) using the display parameter 2. A specific window by its title using the window_title parameter. Only one of display or window_title should be specified."
+        description = "Capture a screenshot of a specified display or window. You can capture either: 1. A full display (monitor) using the display parameter 2. A specific window by its title using the window_title parameter. Only one of display or window_title should be specified.",
+        annotations(read_only_hint = true)
     )]
     pub async fn screen_capture(
         &self,
@@ -845,7 +879,7 @@ impl DeveloperServer {
     /// this tool does not run indefinitely.
     #[tool(
         name = "shell",
-        description = "Execute a command in the shell.This will return the output and error concatenated into a single string, as you would see from running on the command line. There will also be an indication of if the command succeeded or failed. Avoid commands that produce a large amount of output, and consider piping those outputs to files. If you need to run a long lived command, background it - e.g. `uvicorn main:app &` so that this tool does not run indefinitely."
+        description = "Execute a command in the shell.This will return the output and error concatenated into a single string, as you would see from running on the command line. There will also be an indication of if the command succeeded or failed. Avoid commands that produce a large amount of output, and consider piping those outputs to files. If you need to run a long lived command, background it - e.g. `uvicorn main:app &` so that this tool does not run indefinitely. For long build or test output, set output_filter (grep, head_lines, tail_lines, strip_ansi) to return only the lines that matter; the full output is saved to a file."
     )]
     pub async fn shell(
         &self,
@@ -859,6 +893,19 @@ impl DeveloperServer {
 
         // Validate the shell command
         self.validate_shell_command(command)?;
+        let grep = params
+            .output_filter
+            .as_ref()
+            .map(OutputFilter::grep_regex)
+            .transpose()
+            .map_err(|e| {
+                ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!("Invalid grep pattern in output_filter: {}", e),
+                    None,
+                )
+            })?
+            .flatten();
 
         let cancellation_token = CancellationToken::new();
         // Track the process using the request ID
@@ -888,11 +935,31 @@ impl DeveloperServer {
 
         let output_str = output_result?;
 
+        // Filter before the size check, since the full output goes to a file instead
+        let (output_str, filter_note) = match &params.output_filter {
+            Some(filter) => {
+                let (filtered, total_lines) = filter.apply(&output_str, grep.as_ref());
+                let path = save_output_to_temp_file(&output_str)?;
+                let note = format!(
+                    "Output filtered to {} of {} lines; the full output is in {}",
+                    filtered.lines().count(),
+                    total_lines,
+                    path.display()
+                );
+                (filtered, Some(note))
+            }
+            None => (output_str, None),
+        };
+
         // Validate output size
         self.validate_shell_output_size(command, &output_str)?;
 
         // Process and format the output
-        let (final_output, user_output) = self.process_shell_output(&output_str)?;
+        let (mut final_output, mut user_output) = self.process_shell_output(&output_str)?;
+        if let Some(note) = filter_note {
+            final_output = format!("{}\n{}", note, final_output);
+            user_output = format!("NOTE: {}\n\n{}", note, user_output);
+        }
 
         Ok(CallToolResult::success(vec![
             Content::text(final_output).with_audience(vec![Role::Assistant]),
@@ -1097,14 +1164,18 @@ impl DeveloperServer {
     /// - Files: Semantic analysis with call graphs
     /// - Directories: Structure overview with metrics
     /// - With focus parameter: Track symbol across files
+    /// - With dead_code: List functions nothing calls
     ///
     /// Examples:
     /// analyze(path="file.py") -> semantic analysis
     /// analyze(path="src/") -> structure overview down to max_depth subdirs
     /// analyze(path="src/", focus="main") -> track main() across files in src/ down to max_depth subdirs
+    /// analyze(path="src/", dead_code=true) -> uncalled functions in src/, minus entry points
+    /// analyze(path="src/", incremental=true) -> structure overview, re-parsing only files changed since the last one
     #[tool(
         name = "analyze",
-        description = "Analyze code structure in 3 modes: 1) Directory overview - file tree with LOC/function/class counts to max_depth. 2) File details - functions, classes, imports. 3) Symbol focus - call graphs across directory to max_depth (requires directory path, case-sensitive). Typical flow: directory → files → symbols. Functions called >3x show •N."
+        description = "Analyze code structure in 4 modes: 1) Directory overview - file tree with LOC/function/class counts to max_depth. 2) File details - functions, classes, imports. 3) Symbol focus - call graphs across directory to max_depth (requires directory path, case-sensitive). 4) Dead code - dead_code=true lists functions with no callers, skipping main, exported and test functions; pass exclude globs for known public API. In any mode, exclude globs and a .gooseignore at the path leave out generated or vendored files. Symbols are matched per file (match_mode=exact, chains show name@file); match_mode=name-only merges same-named symbols across files and languages, e.g. bindings into a core. Typical flow: directory → files → symbols. When overviewing the same git repo again after edits, incremental=true re-parses only changed files. Functions called >3x show •N.",
+        annotations(read_only_hint = true)
     )]
     pub async fn analyze(
         &self,
@@ -1116,6 +1187,44 @@ impl DeveloperServer {
             .analyze(params, path, &self.ignore_patterns)
     }
 
+    /// Search a directory for files by glob and/or lines by regex, without relying on
+    /// platform-specific find/rg invocations.
+    #[tool(
+        name = "search_files",
+        description = "Search files under a directory. Give a glob to find files by name, a regex pattern to find matching lines (returned as path:line: excerpt), or both to search only matching files. Respects .gitignore, skips hidden and binary files, and caps results at max_results (default 100). Prefer this to find/grep/rg in the shell.",
+        annotations(read_only_hint = true)
+    )]
+    pub async fn search_files(
+        &self,
+        params: Parameters<SearchFilesParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+        let root = self.resolve_path(&params.path)?;
+        if self.is_ignored(&root) {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "Access to '{}' is restricted by .gooseignore",
+                    root.display()
+                ),
+                None,
+            ));
+        }
+
+        let options = SearchOptions {
+            glob: params.glob.as_deref(),
+            pattern: params.pattern.as_deref(),
+            case_sensitive: params.case_sensitive,
+            max_results: params.max_results.unwrap_or(DEFAULT_MAX_RESULTS),
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+        };
+        let results = search_files(&root, &options, &self.ignore_patterns)?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            format_results(&root, &results),
+        )]))
+    }
+
     /// Process an image file from disk.
     ///
     /// The image will be:
@@ -1362,29 +1471,7 @@ impl DeveloperServer {
         let last_100_lines_str = lines[start..].join("\n");
 
         let final_output = if line_count > 100 {
-            let tmp_file = tempfile::NamedTempFile::new().map_err(|e| {
-                ErrorData::new(
-                    ErrorCode::INTERNAL_ERROR,
-                    format!("Failed to create temporary file: {}", e),
-                    None,
-                )
-            })?;
-
-            std::fs::write(tmp_file.path(), output_str).map_err(|e| {
-                ErrorData::new(
-                    ErrorCode::INTERNAL_ERROR,
-                    format!("Failed to write to temporary file: {}", e),
-                    None,
-                )
-            })?;
-
-            let (_, path) = tmp_file.keep().map_err(|e| {
-                ErrorData::new(
-                    ErrorCode::INTERNAL_ERROR,
-                    format!("Failed to persist temporary file: {}", e),
-                    None,
-                )
-            })?;
+            let path = save_output_to_temp_file(output_str)?;
 
             format!(
                 "private note: output was {} lines and we are only showing the most recent lines, remainder of lines in {} do not show tmp file to user, that file can be searched if extra context needed to fulfill request. truncated output: \n{}",
@@ -1409,6 +1496,34 @@ impl DeveloperServer {
     }
 }
 
+/// Write shell output to a temporary file that outlives this call, returning its path
+fn save_output_to_temp_file(output: &str) -> Result<PathBuf, ErrorData> {
+    let tmp_file = tempfile::NamedTempFile::new().map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to create temporary file: {}", e),
+            None,
+        )
+    })?;
+
+    std::fs::write(tmp_file.path(), output).map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to write to temporary file: {}", e),
+            None,
+        )
+    })?;
+
+    let (_, path) = tmp_file.keep().map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to persist temporary file: {}", e),
+            None,
+        )
+    })?;
+    Ok(path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1428,6 +1543,22 @@ mod tests {
         DeveloperServer::new()
     }
 
+    #[test]
+    fn test_read_only_tools_are_annotated_and_none_run_alone() {
+        let tools = create_test_server().tool_router.list_all();
+        for name in ["analyze", "search_files", "list_windows", "screen_capture"] {
+            let tool = tools.iter().find(|tool| tool.name == name).unwrap();
+            assert_eq!(
+                tool.annotations.as_ref().and_then(|a| a.read_only_hint),
+                Some(true),
+                "{} should be annotated read-only",
+                name
+            );
+        }
+        // Nothing the developer extension offers is held back from running in parallel
+        assert!(!tools.iter().any(goose::agents::runs_sequentially));
+    }
+
     /// Creates a test transport using in-memory streams instead of stdio
     /// This avoids the hanging issues caused by multiple tests competing for stdio
     fn create_test_transport() -> impl rmcp::transport::IntoTransport<
@@ -1483,6 +1614,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: "".to_string(),
+                        output_filter: None,
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -1518,6 +1650,7 @@ mod tests {
             // Test PowerShell command
             let shell_params = Parameters(ShellParams {
                 command: "Get-ChildItem".to_string(),
+                output_filter: None,
             });
 
             let result = server
@@ -1901,6 +2034,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: format!("cat {}", secret_file_path.to_str().unwrap()),
+                        output_filter: None,
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -1923,6 +2057,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: format!("cat {}", allowed_file_path.to_str().unwrap()),
+                        output_filter: None,
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -2097,6 +2232,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: format!("cat {}", log_file_path.to_str().unwrap()),
+                        output_filter: None,
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -2122,6 +2258,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: format!("cat {}", allowed_file_path.to_str().unwrap()),
+                        output_filter: None,
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -3142,6 +3279,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: command.to_string(),
+                        output_filter: None,
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -3232,6 +3370,116 @@ mod tests {
         });
     }
 
+    #[test]
+    #[serial]
+    #[cfg(unix)]
+    fn test_shell_output_filters() {
+        run_shell_test(|| async {
+            let temp_dir = tempfile::tempdir().unwrap();
+            std::env::set_current_dir(&temp_dir).unwrap();
+
+            let server = create_test_server();
+            let running_service = serve_directly(server.clone(), create_test_transport(), None);
+            let peer = running_service.peer().clone();
+
+            let command = "printf '\\033[31merror: one\\033[0m\\nok 1\\nerror: two\\nok 2\\nerror: three\\nok 3\\n'";
+            let run = |filter: OutputFilter| {
+                let server = server.clone();
+                let peer = peer.clone();
+                async move {
+                    let result = server
+                        .shell(
+                            Parameters(ShellParams {
+                                command: command.to_string(),
+                                output_filter: Some(filter),
+                            }),
+                            RequestContext {
+                                ct: Default::default(),
+                                id: NumberOrString::Number(1),
+                                meta: Default::default(),
+                                extensions: Default::default(),
+                                peer,
+                            },
+                        )
+                        .await
+                        .unwrap();
+                    let text = result.content[0].as_text().unwrap().text.clone();
+                    let (note, output) = text.split_once('\n').unwrap();
+                    (note.to_string(), output.to_string())
+                }
+            };
+
+            let (_, output) = run(OutputFilter {
+                tail_lines: Some(2),
+                ..Default::default()
+            })
+            .await;
+            assert_eq!(output, "error: three\nok 3");
+
+            let (_, output) = run(OutputFilter {
+                head_lines: Some(1),
+                strip_ansi: true,
+                ..Default::default()
+            })
+            .await;
+            assert_eq!(output, "error: one");
+
+            let (_, output) = run(OutputFilter {
+                head_lines: Some(1),
+                ..Default::default()
+            })
+            .await;
+            assert_eq!(output, "\x1b[31merror: one\x1b[0m");
+
+            let (_, output) = run(OutputFilter {
+                grep: Some("^ok".to_string()),
+                ..Default::default()
+            })
+            .await;
+            assert_eq!(output, "ok 1\nok 2\nok 3");
+
+            // grep, then tail
+            let (note, output) = run(OutputFilter {
+                grep: Some("^error".to_string()),
+                tail_lines: Some(2),
+                strip_ansi: true,
+                ..Default::default()
+            })
+            .await;
+            assert_eq!(output, "error: two\nerror: three");
+            assert!(note.starts_with("Output filtered to 2 of 6 lines"));
+
+            let path = note.rsplit(' ').next().unwrap();
+            let full = std::fs::read_to_string(path).unwrap();
+            assert_eq!(full.lines().count(), 6);
+            assert!(full.starts_with("\x1b[31merror: one"));
+            assert!(full.ends_with("ok 3\n"));
+
+            let invalid = server
+                .shell(
+                    Parameters(ShellParams {
+                        command: "echo hi".to_string(),
+                        output_filter: Some(OutputFilter {
+                            grep: Some("(".to_string()),
+                            ..Default::default()
+                        }),
+                    }),
+                    RequestContext {
+                        ct: Default::default(),
+                        id: NumberOrString::Number(2),
+                        meta: Default::default(),
+                        extensions: Default::default(),
+                        peer: peer.clone(),
+                    },
+                )
+                .await
+                .unwrap_err();
+            assert_eq!(invalid.code, ErrorCode::INVALID_PARAMS);
+
+            cleanup_test_service(running_service, peer);
+        });
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_process_shell_output_short() {
@@ -3288,6 +3536,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: command.to_string(),
+                        output_filter: None,
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -3653,6 +3902,7 @@ Additional instructions here.
                     .shell(
                         Parameters(ShellParams {
                             command: "sleep 30".to_string(),
+                            output_filter: None,
                         }),
                         context,
                     )
@@ -3741,6 +3991,7 @@ Additional instructions here.
                     .shell(
                         Parameters(ShellParams {
                             command: "bash -c 'sleep 60 & wait'".to_string(),
+                            output_filter: None,
                         }),
                         context,
                     )
@@ -3838,6 +4089,7 @@ Additional instructions here.
                 .shell(
                     Parameters(ShellParams {
                         command: "echo 'Hello, World!'".to_string(),
+                        output_filter: None,
                     }),
                     context,
                 )
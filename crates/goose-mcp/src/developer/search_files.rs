@@ -0,0 +1,450 @@
+use ignore::gitignore::Gitignore;
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use regex::{Regex, RegexBuilder};
+use rmcp::model::{ErrorCode, ErrorData};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read};
+use std::path::{Path, PathBuf};
+
+/// Default cap on the number of matches returned
+pub const DEFAULT_MAX_RESULTS: usize = 100;
+/// Excerpts longer than this are cut short
+const MAX_EXCERPT_CHARS: usize = 200;
+/// How much of a file is checked for NUL bytes to decide whether it is binary
+const BINARY_SNIFF_BYTES: usize = 8192;
+/// Default size above which files are not searched for content, such as large logs or build
+/// artifacts
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// A file matching the glob, or a line matching the content pattern
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileMatch {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub excerpt: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchResults {
+    pub matches: Vec<FileMatch>,
+    pub files_searched: usize,
+    pub binary_files_skipped: usize,
+    pub large_files_skipped: usize,
+    pub truncated: bool,
+}
+
+/// Options for [`search_files`]
+#[derive(Debug)]
+pub struct SearchOptions<'a> {
+    pub glob: Option<&'a str>,
+    pub pattern: Option<&'a str>,
+    pub case_sensitive: bool,
+    pub max_results: usize,
+    /// Files larger than this are skipped by content searches
+    pub max_file_bytes: u64,
+}
+
+fn invalid_params(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INVALID_PARAMS, message, None)
+}
+
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes.contains(&0)
+}
+
+fn excerpt(line: &str) -> String {
+    let line = line.trim();
+    if line.chars().count() > MAX_EXCERPT_CHARS {
+        let cut: String = line.chars().take(MAX_EXCERPT_CHARS).collect();
+        format!("{}...", cut)
+    } else {
+        line.to_string()
+    }
+}
+
+fn content_matches(
+    path: &Path,
+    regex: &Regex,
+    results: &mut SearchResults,
+    options: &SearchOptions,
+) {
+    let Ok(mut file) = File::open(path) else {
+        return;
+    };
+    if file
+        .metadata()
+        .is_ok_and(|metadata| metadata.len() > options.max_file_bytes)
+    {
+        results.large_files_skipped += 1;
+        return;
+    }
+
+    // Only the start of the file is read to decide whether it is binary
+    let mut head = Vec::with_capacity(BINARY_SNIFF_BYTES);
+    if (&mut file)
+        .take(BINARY_SNIFF_BYTES as u64)
+        .read_to_end(&mut head)
+        .is_err()
+    {
+        return;
+    }
+    if is_binary(&head) {
+        results.binary_files_skipped += 1;
+        return;
+    }
+    results.files_searched += 1;
+
+    let mut reader = BufReader::new(Cursor::new(head).chain(file));
+    let mut buf = Vec::new();
+    let mut index = 0;
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+        index += 1;
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim_end_matches(['\n', '\r']);
+        if !regex.is_match(line) {
+            continue;
+        }
+        if results.matches.len() == options.max_results {
+            results.truncated = true;
+            return;
+        }
+        results.matches.push(FileMatch {
+            path: path.to_path_buf(),
+            line: Some(index),
+            excerpt: Some(excerpt(line)),
+        });
+    }
+}
+
+/// Walk `root` for files matching the glob and/or lines matching the content pattern.
+///
+/// The walk respects .gitignore (like `rg`) as well as the `.gooseignore` patterns passed in,
+/// skips hidden files, and visits files in name order so results are stable.
+pub fn search_files(
+    root: &Path,
+    options: &SearchOptions,
+    ignore_patterns: &Gitignore,
+) -> Result<SearchResults, ErrorData> {
+    if options.glob.is_none() && options.pattern.is_none() {
+        return Err(invalid_params(
+            "search_files requires a glob, a pattern, or both".to_string(),
+        ));
+    }
+    if !root.exists() {
+        return Err(invalid_params(format!(
+            "Path '{}' does not exist",
+            root.display()
+        )));
+    }
+
+    let regex = options
+        .pattern
+        .map(|pattern| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(!options.case_sensitive)
+                .build()
+                .map_err(|e| invalid_params(format!("Invalid pattern '{}': {}", pattern, e)))
+        })
+        .transpose()?;
+
+    let mut walker = WalkBuilder::new(root);
+    walker.require_git(false).sort_by_file_name(|a, b| a.cmp(b));
+    if let Some(glob) = options.glob {
+        let mut overrides = OverrideBuilder::new(root);
+        overrides
+            .case_insensitive(!options.case_sensitive)
+            .and_then(|builder| builder.add(glob))
+            .map_err(|e| invalid_params(format!("Invalid glob '{}': {}", glob, e)))?;
+        let overrides = overrides
+            .build()
+            .map_err(|e| invalid_params(format!("Invalid glob '{}': {}", glob, e)))?;
+        walker.overrides(overrides);
+    }
+    let gooseignore = ignore_patterns.clone();
+    walker.filter_entry(move |entry| {
+        let is_dir = entry.file_type().is_some_and(|kind| kind.is_dir());
+        !gooseignore.matched(entry.path(), is_dir).is_ignore()
+    });
+
+    let mut results = SearchResults::default();
+    for entry in walker.build() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_some_and(|kind| kind.is_file()) {
+            continue;
+        }
+
+        match &regex {
+            Some(regex) => content_matches(entry.path(), regex, &mut results, options),
+            None => {
+                if results.matches.len() == options.max_results {
+                    results.truncated = true;
+                } else {
+                    results.files_searched += 1;
+                    results.matches.push(FileMatch {
+                        path: entry.path().to_path_buf(),
+                        line: None,
+                        excerpt: None,
+                    });
+                }
+            }
+        }
+        if results.truncated {
+            break;
+        }
+    }
+    Ok(results)
+}
+
+/// One match per line as `path:line: excerpt`, or just `path` for glob-only searches, with
+/// paths relative to `root`
+pub fn format_results(root: &Path, results: &SearchResults) -> String {
+    let mut skipped = Vec::new();
+    if results.binary_files_skipped > 0 {
+        skipped.push(format!(
+            "{} binary files skipped",
+            results.binary_files_skipped
+        ));
+    }
+    if results.large_files_skipped > 0 {
+        skipped.push(format!(
+            "{} files over the size limit skipped",
+            results.large_files_skipped
+        ));
+    }
+
+    if results.matches.is_empty() {
+        let mut text = "No matches found".to_string();
+        if !skipped.is_empty() {
+            text.push_str(&format!(" ({})", skipped.join(", ")));
+        }
+        return text;
+    }
+
+    let mut lines: Vec<String> = results
+        .matches
+        .iter()
+        .map(|found| {
+            let path = found.path.strip_prefix(root).unwrap_or(&found.path);
+            match (found.line, &found.excerpt) {
+                (Some(line), Some(excerpt)) => {
+                    format!("{}:{}: {}", path.display(), line, excerpt)
+                }
+                _ => path.display().to_string(),
+            }
+        })
+        .collect();
+
+    if !skipped.is_empty() {
+        lines.push(format!("({})", skipped.join(", ")));
+    }
+    if results.truncated {
+        lines.push(format!(
+            "Results truncated at {} matches. Narrow the search with a glob or a more specific pattern.",
+            results.matches.len()
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn fixture_tree() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::create_dir_all(root.join("target")).unwrap();
+        fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        fs::write(
+            root.join("src/main.rs"),
+            "fn main() {\n    println!(\"Hello\");\n    helper();\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/nested/helper.rs"),
+            "pub fn helper() {\n    // TODO: say hello\n}\n",
+        )
+        .unwrap();
+        fs::write(root.join("README.md"), "# Demo\nSay hello to the demo.\n").unwrap();
+        fs::write(root.join("target/build.rs"), "fn hello() {}\n").unwrap();
+        fs::write(root.join("logo.bin"), b"hello\0\x01\x02binary").unwrap();
+        dir
+    }
+
+    fn options<'a>(glob: Option<&'a str>, pattern: Option<&'a str>) -> SearchOptions<'a> {
+        SearchOptions {
+            glob,
+            pattern,
+            case_sensitive: false,
+            max_results: DEFAULT_MAX_RESULTS,
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+        }
+    }
+
+    fn relative_paths(root: &Path, results: &SearchResults) -> Vec<String> {
+        results
+            .matches
+            .iter()
+            .map(|found| {
+                found
+                    .path
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_glob_only_search() {
+        let dir = fixture_tree();
+        let results = search_files(
+            dir.path(),
+            &options(Some("*.rs"), None),
+            &Gitignore::empty(),
+        )
+        .unwrap();
+
+        // target/ is excluded by .gitignore
+        assert_eq!(
+            relative_paths(dir.path(), &results),
+            vec!["src/main.rs", "src/nested/helper.rs"]
+        );
+        assert!(results.matches.iter().all(|found| found.line.is_none()));
+        assert_eq!(
+            format_results(dir.path(), &results).replace('\\', "/"),
+            "src/main.rs\nsrc/nested/helper.rs"
+        );
+    }
+
+    #[test]
+    fn test_content_only_search_skips_binary_files() {
+        let dir = fixture_tree();
+        let results = search_files(
+            dir.path(),
+            &options(None, Some("hello")),
+            &Gitignore::empty(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            relative_paths(dir.path(), &results),
+            vec!["README.md", "src/main.rs", "src/nested/helper.rs"]
+        );
+        assert_eq!(results.binary_files_skipped, 1);
+
+        let text = format_results(dir.path(), &results).replace('\\', "/");
+        assert!(text.contains("README.md:2: Say hello to the demo."));
+        assert!(text.contains("src/main.rs:2: println!(\"Hello\");"));
+        assert!(text.contains("src/nested/helper.rs:2: // TODO: say hello"));
+        assert!(text.ends_with("(1 binary files skipped)"));
+
+        // Case-sensitive searches miss "Hello"
+        let mut case_sensitive = options(None, Some("Hello"));
+        case_sensitive.case_sensitive = true;
+        let results = search_files(dir.path(), &case_sensitive, &Gitignore::empty()).unwrap();
+        assert_eq!(relative_paths(dir.path(), &results), vec!["src/main.rs"]);
+    }
+
+    #[test]
+    fn test_content_search_skips_files_over_size_limit() {
+        let dir = fixture_tree();
+        let mut log = "noise\n".repeat(2000);
+        log.push_str("hello from the end of a large log\r\n");
+        fs::write(dir.path().join("big.log"), &log).unwrap();
+
+        // Lines past the sniffed head are still found, with CRLF endings trimmed
+        let results = search_files(
+            dir.path(),
+            &options(Some("*.log"), Some("hello")),
+            &Gitignore::empty(),
+        )
+        .unwrap();
+        assert_eq!(
+            results.matches,
+            vec![FileMatch {
+                path: dir.path().join("big.log"),
+                line: Some(2001),
+                excerpt: Some("hello from the end of a large log".to_string()),
+            }]
+        );
+
+        let mut limited = options(None, Some("hello"));
+        limited.max_file_bytes = 1024;
+        let results = search_files(dir.path(), &limited, &Gitignore::empty()).unwrap();
+        assert_eq!(
+            relative_paths(dir.path(), &results),
+            vec!["README.md", "src/main.rs", "src/nested/helper.rs"]
+        );
+        assert_eq!(results.large_files_skipped, 1);
+        assert!(format_results(dir.path(), &results)
+            .ends_with("(1 binary files skipped, 1 files over the size limit skipped)"));
+    }
+
+    #[test]
+    fn test_combined_search() {
+        let dir = fixture_tree();
+        let results = search_files(
+            dir.path(),
+            &options(Some("*.rs"), Some(r"TODO|println")),
+            &Gitignore::empty(),
+        )
+        .unwrap();
+
+        let found: Vec<_> = results
+            .matches
+            .iter()
+            .map(|found| (found.line.unwrap(), found.excerpt.clone().unwrap()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (2, "println!(\"Hello\");".to_string()),
+                (2, "// TODO: say hello".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_results_are_capped() {
+        let dir = fixture_tree();
+        let mut capped = options(None, Some("hello"));
+        capped.max_results = 2;
+        let results = search_files(dir.path(), &capped, &Gitignore::empty()).unwrap();
+
+        assert_eq!(results.matches.len(), 2);
+        assert!(results.truncated);
+        assert!(format_results(dir.path(), &results).ends_with(
+            "Results truncated at 2 matches. Narrow the search with a glob or a more specific pattern."
+        ));
+    }
+
+    #[test]
+    fn test_respects_gooseignore_and_validates_input() {
+        let dir = fixture_tree();
+        let mut builder = ignore::gitignore::GitignoreBuilder::new(dir.path());
+        builder.add_line(None, "src/nested/").unwrap();
+        let gooseignore = builder.build().unwrap();
+
+        let results = search_files(dir.path(), &options(Some("*.rs"), None), &gooseignore).unwrap();
+        assert_eq!(relative_paths(dir.path(), &results), vec!["src/main.rs"]);
+
+        let err = search_files(dir.path(), &options(None, None), &Gitignore::empty()).unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        let err =
+            search_files(dir.path(), &options(None, Some("(")), &Gitignore::empty()).unwrap_err();
+        assert!(err.message.starts_with("Invalid pattern"));
+    }
+}
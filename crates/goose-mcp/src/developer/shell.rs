@@ -1,5 +1,9 @@
 use goose::config::get_config_dir;
-use std::{env, ffi::OsString, process::Stdio};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rmcp::schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, env, ffi::OsString, process::Stdio};
 
 #[cfg(unix)]
 #[allow(unused_imports)] // False positive: trait is used for process_group method
@@ -121,6 +125,60 @@ pub fn normalize_line_endings(text: &str) -> String {
     }
 }
 
+/// CSI sequences (colors, cursor movement) and OSC sequences (titles, hyperlinks)
+static ANSI_ESCAPE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[@-_]")
+        .expect("Invalid ANSI escape regex pattern")
+});
+
+/// Filters applied to captured shell output before it is returned. They compose in a fixed
+/// order: strip_ansi, then grep, then head_lines, then tail_lines.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct OutputFilter {
+    /// Keep only the last N lines
+    pub tail_lines: Option<usize>,
+    /// Keep only the first N lines
+    pub head_lines: Option<usize>,
+    /// Keep only lines matching this regular expression
+    pub grep: Option<String>,
+    /// Remove color codes and other terminal escape sequences
+    #[serde(default)]
+    pub strip_ansi: bool,
+}
+
+impl OutputFilter {
+    pub fn grep_regex(&self) -> Result<Option<Regex>, regex::Error> {
+        self.grep.as_deref().map(Regex::new).transpose()
+    }
+
+    /// Apply the filters to `output`, with `grep` compiled by [`Self::grep_regex`]. Returns
+    /// the filtered text and the number of lines it was taken from.
+    pub fn apply(&self, output: &str, grep: Option<&Regex>) -> (String, usize) {
+        let text = if self.strip_ansi {
+            strip_ansi(output)
+        } else {
+            Cow::Borrowed(output)
+        };
+        let mut lines: Vec<&str> = text.lines().collect();
+        let total = lines.len();
+
+        if let Some(grep) = grep {
+            lines.retain(|line| grep.is_match(line));
+        }
+        if let Some(head) = self.head_lines {
+            lines.truncate(head);
+        }
+        if let Some(tail) = self.tail_lines {
+            lines.drain(..lines.len().saturating_sub(tail));
+        }
+        (lines.join("\n"), total)
+    }
+}
+
+pub fn strip_ansi(text: &str) -> Cow<'_, str> {
+    ANSI_ESCAPE_REGEX.replace_all(text, "")
+}
+
 /// Configure a shell command with process group support for proper child process tracking.
 ///
 /// On Unix systems, creates a new process group so child processes can be killed together.
@@ -0,0 +1,177 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use goose::config::{Config, ConfigError};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hkdf::{Salt, HKDF_SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::io;
+
+/// Config key turning on encryption of memory files at rest
+pub const ENCRYPTION_CONFIG_KEY: &str = "GOOSE_MEMORY_ENCRYPTION";
+/// Secret holding the random master key that memory file keys are derived from
+pub const MASTER_KEY_SECRET: &str = "GOOSE_MEMORY_MASTER_KEY";
+
+/// First line of an encrypted memory file; the rest is base64 of the nonce and ciphertext
+const HEADER: &str = "goose-memory-encrypted:v1";
+const MASTER_KEY_LEN: usize = 32;
+
+/// Whether a stored memory file is encrypted
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(HEADER)
+}
+
+/// AES-256-GCM encryption of memory files, keyed from a master key in the secret store
+#[derive(Clone)]
+pub struct MemoryCipher {
+    key: [u8; 32],
+}
+
+impl MemoryCipher {
+    /// Derive the file key from a master key with HKDF-SHA256
+    pub fn from_master_key(master_key: &[u8]) -> Self {
+        let mut key = [0u8; 32];
+        Salt::new(HKDF_SHA256, b"goose-memory")
+            .extract(master_key)
+            .expand(&[b"memory files v1"], HKDF_SHA256)
+            .and_then(|okm| okm.fill(&mut key))
+            .expect("HKDF output fits the SHA-256 length");
+        Self { key }
+    }
+
+    /// The cipher for the master key in the secret store (the OS keychain unless secrets are
+    /// kept in a file), creating and storing a random master key on first use
+    pub fn from_secret_store(config: &Config) -> anyhow::Result<Self> {
+        // Only a missing key is replaced: overwriting one that failed to load would leave the
+        // memories encrypted with it unreadable
+        match config.get_secret::<String>(MASTER_KEY_SECRET) {
+            Ok(encoded) => {
+                let master_key = STANDARD.decode(encoded.trim())?;
+                if master_key.len() != MASTER_KEY_LEN {
+                    anyhow::bail!("{} has the wrong length", MASTER_KEY_SECRET);
+                }
+                return Ok(Self::from_master_key(&master_key));
+            }
+            Err(ConfigError::NotFound(_)) => {}
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Could not read {} from the secret store: {}",
+                    MASTER_KEY_SECRET,
+                    e
+                ))
+            }
+        }
+
+        let mut master_key = [0u8; MASTER_KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut master_key)
+            .map_err(|_| anyhow::anyhow!("Could not generate a memory encryption key"))?;
+        config.set_secret(
+            MASTER_KEY_SECRET,
+            serde_json::Value::String(STANDARD.encode(master_key)),
+        )?;
+        Ok(Self::from_master_key(&master_key))
+    }
+
+    fn key(&self) -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &self.key).expect("key is 32 bytes"))
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> io::Result<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| io::Error::other("Could not generate a nonce"))?;
+        let mut sealed = plaintext.as_bytes().to_vec();
+        self.key()
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut sealed,
+            )
+            .map_err(|_| io::Error::other("Could not encrypt memory file"))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend(sealed);
+        Ok(format!("{}\n{}\n", HEADER, STANDARD.encode(payload)))
+    }
+
+    pub fn decrypt(&self, stored: &str) -> io::Result<String> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Memory file could not be decrypted with the memory encryption key",
+            )
+        };
+        let encoded = stored.strip_prefix(HEADER).ok_or_else(invalid)?.trim();
+        let payload = STANDARD.decode(encoded).map_err(|_| invalid())?;
+        if payload.len() < NONCE_LEN {
+            return Err(invalid());
+        }
+        let (nonce, sealed) = payload.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| invalid())?;
+        let mut sealed = sealed.to_vec();
+        let plaintext = self
+            .key()
+            .open_in_place(nonce, Aad::empty(), &mut sealed)
+            .map_err(|_| invalid())?;
+        String::from_utf8(plaintext.to_vec()).map_err(|_| invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_wrong_key() {
+        let cipher = MemoryCipher::from_master_key(&[7u8; 32]);
+        let stored = cipher
+            .encrypt("# tokens\nThe staging token is abc123\n\n")
+            .unwrap();
+        assert!(is_encrypted(&stored));
+        assert!(!stored.contains("abc123"));
+        assert_eq!(
+            cipher.decrypt(&stored).unwrap(),
+            "# tokens\nThe staging token is abc123\n\n"
+        );
+
+        // Every write gets a fresh nonce
+        assert_ne!(
+            stored,
+            cipher
+                .encrypt("# tokens\nThe staging token is abc123\n\n")
+                .unwrap()
+        );
+
+        let other = MemoryCipher::from_master_key(&[8u8; 32]);
+        let err = other.decrypt(&stored).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(cipher.decrypt("plain text").is_err());
+    }
+
+    #[test]
+    fn test_master_key_is_created_once_and_never_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let secrets = dir.path().join("secrets.yaml");
+        let config =
+            Config::new_with_file_secrets(dir.path().join("config.yaml"), &secrets).unwrap();
+
+        let stored = MemoryCipher::from_secret_store(&config)
+            .unwrap()
+            .encrypt("remember this")
+            .unwrap();
+        let cipher = MemoryCipher::from_secret_store(&config).unwrap();
+        assert_eq!(cipher.decrypt(&stored).unwrap(), "remember this");
+
+        // A key that cannot be read is reported rather than overwritten
+        config
+            .set_secret(MASTER_KEY_SECRET, serde_json::json!(42))
+            .unwrap();
+        assert!(MemoryCipher::from_secret_store(&config).is_err());
+        assert_eq!(
+            config
+                .get_secret::<serde_json::Value>(MASTER_KEY_SECRET)
+                .unwrap(),
+            serde_json::json!(42)
+        );
+    }
+}
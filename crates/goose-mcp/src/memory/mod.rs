@@ -1,22 +1,91 @@
+mod encryption;
+
+use chrono::{DateTime, Duration, Utc};
+use encryption::MemoryCipher;
 use etcetera::{choose_app_strategy, AppStrategy};
+use goose::config::Config;
 use indoc::formatdoc;
 use rmcp::{
     handler::server::{router::tool::ToolRouter, wrapper::Parameters},
     model::{
-        CallToolResult, Content, ErrorCode, ErrorData, Implementation, ServerCapabilities,
-        ServerInfo,
+        CallToolResult, Content, ErrorCode, ErrorData, Implementation, ListResourcesResult,
+        PaginatedRequestParam, RawResource, ReadResourceRequestParam, ReadResourceResult, Resource,
+        ResourceContents, ServerCapabilities, ServerInfo,
     },
     schemars::JsonSchema,
-    tool, tool_handler, tool_router, ServerHandler,
+    service::{NotificationContext, Peer, RequestContext},
+    tool, tool_handler, tool_router, RoleServer, ServerHandler,
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap, HashSet},
     fs,
-    io::{self, Read, Write},
-    path::PathBuf,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
 };
 
+/// Scheme of the resources that expose each memory category, as
+/// `memory://global/<category>` or `memory://local/<category>`
+const RESOURCE_SCHEME: &str = "memory://";
+
+/// Trim a category name and make sure it names a single file inside the memory directory.
+///
+/// Categories become file names, so anything that could step outside the memory directory
+/// (path separators, `..`, absolute or drive-prefixed paths) is rejected as invalid input.
+fn normalize_category(category: &str) -> io::Result<String> {
+    let category = category.trim();
+    let invalid = |reason: &str| {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid memory category '{}': {}", category, reason),
+        ))
+    };
+
+    if category.is_empty() {
+        return invalid("category must not be empty");
+    }
+    if category.contains(['/', '\\']) {
+        return invalid("category must not contain path separators");
+    }
+    if category.contains("..") {
+        return invalid("category must not contain '..'");
+    }
+    if category.contains([':', '\0']) {
+        return invalid("category must be a plain name");
+    }
+    Ok(category.to_string())
+}
+
+/// Write `content` to a temporary file next to `path` and rename it over `path`, so a crash
+/// part way through leaves the old file rather than a truncated one
+fn replace_file(path: &Path, content: &str) -> io::Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(content.as_bytes())?;
+    file.as_file().sync_all()?;
+    file.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+fn key_unavailable(reason: &str) -> io::Error {
+    io::Error::other(format!(
+        "Memory encryption is on but its key is unavailable: {}",
+        reason
+    ))
+}
+
+/// Invalid categories are the caller's mistake; every other I/O failure is internal
+fn io_error_to_error_data(e: io::Error) -> ErrorData {
+    let code = if e.kind() == io::ErrorKind::InvalidInput {
+        ErrorCode::INVALID_PARAMS
+    } else {
+        ErrorCode::INTERNAL_ERROR
+    };
+    ErrorData::new(code, e.to_string(), None)
+}
+
 /// Parameters for the remember_memory tool
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct RememberMemoryParams {
@@ -29,6 +98,8 @@ pub struct RememberMemoryParams {
     pub tags: Vec<String>,
     /// Whether to store globally or locally
     pub is_global: bool,
+    /// Forget the memory after this many days, for guidance that only holds for a while
+    pub expires_in_days: Option<u32>,
 }
 
 /// Parameters for the retrieve_memories tool
@@ -38,6 +109,11 @@ pub struct RetrieveMemoriesParams {
     pub category: String,
     /// Whether to retrieve from global or local storage
     pub is_global: bool,
+    /// Maximum number of memories to return (defaults to 50)
+    pub limit: Option<usize>,
+    /// Number of memories to skip, to fetch the pages after the first
+    #[serde(default)]
+    pub offset: usize,
 }
 
 /// Parameters for the remove_memory_category tool
@@ -60,6 +136,333 @@ pub struct RemoveSpecificMemoryParams {
     pub is_global: bool,
 }
 
+/// Parameters for the search_memories tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchMemoriesParams {
+    /// The text to look for in memory contents and tags
+    pub query: String,
+    /// Only search this category (searches every category when omitted)
+    pub category: Option<String>,
+    /// Whether to search global or local storage (searches both when omitted)
+    pub is_global: Option<bool>,
+    /// Rank memories by similarity to the query instead of requiring an exact match, so typos
+    /// and half-remembered wording still find them
+    #[serde(default)]
+    pub fuzzy: bool,
+    /// Maximum number of fuzzy matches to return (defaults to 5)
+    pub limit: Option<usize>,
+}
+
+/// Parameters for the export_memories tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ExportMemoriesParams {
+    /// Whether to export global or local memories (exports both when omitted)
+    pub is_global: Option<bool>,
+    /// Also write the export to this file
+    pub path: Option<String>,
+}
+
+/// Parameters for the list_tags tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListTagsParams {
+    /// Whether to list tags of global or local memories (lists both when omitted)
+    pub is_global: Option<bool>,
+}
+
+/// Parameters for the memory_stats tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryStatsParams {
+    /// Whether to count global or local memories (counts both when omitted)
+    pub is_global: Option<bool>,
+}
+
+/// How import_memories treats categories that already hold memories
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Add imported memories alongside the existing ones, keeping existing memories on conflict
+    #[default]
+    Merge,
+    /// Replace each imported category with the imported memories
+    Replace,
+}
+
+/// Parameters for the import_memories tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ImportMemoriesParams {
+    /// A JSON document produced by export_memories
+    pub document: Option<String>,
+    /// Path to a file holding a JSON document produced by export_memories
+    pub path: Option<String>,
+    /// Whether to merge with or replace existing categories (defaults to merge)
+    #[serde(default)]
+    pub mode: ImportMode,
+}
+
+/// Version of the document written by export_memories
+pub const MEMORY_EXPORT_VERSION: u32 = 1;
+
+/// Every memory in a portable form, for backups and moving memories between machines
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryExport {
+    pub version: u32,
+    pub memories: Vec<MemoryEntry>,
+}
+
+impl MemoryExport {
+    /// Every problem with the document, so a bad import can be fixed in one go
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.version != MEMORY_EXPORT_VERSION {
+            problems.push(format!(
+                "unsupported version {} (expected {})",
+                self.version, MEMORY_EXPORT_VERSION
+            ));
+        }
+        for (index, entry) in self.memories.iter().enumerate() {
+            if let Err(e) = normalize_category(&entry.category) {
+                problems.push(format!("memory {}: {}", index, e));
+            }
+            if entry.content.trim().is_empty() {
+                problems.push(format!("memory {}: content must not be empty", index));
+            }
+            // Memories are stored separated by blank lines, so one can't contain any
+            if entry.content.contains("\n\n") {
+                problems.push(format!(
+                    "memory {}: content must not contain blank lines",
+                    index
+                ));
+            }
+            if entry.content.starts_with('#') {
+                problems.push(format!("memory {}: content must not start with '#'", index));
+            }
+            if entry
+                .tags
+                .iter()
+                .any(|tag| tag.is_empty() || tag.contains(char::is_whitespace))
+            {
+                problems.push(format!("memory {}: tags must be single words", index));
+            }
+            for (prefix, field) in [
+                (EXPIRES_TAG_PREFIX, "expires_at"),
+                (SAVED_TAG_PREFIX, "saved_at"),
+            ] {
+                if entry.tags.iter().any(|tag| tag.starts_with(prefix)) {
+                    problems.push(format!(
+                        "memory {}: tags must not start with '{}', use {}",
+                        index, prefix, field
+                    ));
+                }
+            }
+        }
+        problems
+    }
+}
+
+/// What import_memories did
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportReport {
+    pub imported: usize,
+    /// Memories that were already stored with the same tags
+    pub duplicates: usize,
+    /// Memories already stored with different tags, which were left as they were
+    pub conflicts: Vec<String>,
+    /// Categories whose existing memories were replaced
+    pub replaced: Vec<String>,
+}
+
+/// How much is stored in one scope, for memory_stats
+#[derive(Debug, Default, PartialEq)]
+pub struct MemoryStats {
+    pub is_global: bool,
+    pub entries: usize,
+    /// Size of the memory files on disk
+    pub bytes: u64,
+    /// (category, entries, bytes), sorted by category
+    pub categories: Vec<(String, usize, u64)>,
+}
+
+/// A tag in use, for list_tags
+#[derive(Debug, PartialEq)]
+pub struct TagUsage {
+    pub tag: String,
+    /// Memories carrying the tag
+    pub count: usize,
+    /// Categories holding those memories, as "(scope) category", sorted
+    pub categories: Vec<String>,
+}
+
+pub use encryption::ENCRYPTION_CONFIG_KEY;
+
+/// Config key capping how many bytes of saved memories are loaded into the instructions
+pub const MAX_INSTRUCTION_BYTES_KEY: &str = "GOOSE_MEMORY_MAX_INSTRUCTION_BYTES";
+const DEFAULT_MAX_INSTRUCTION_BYTES: usize = 32 * 1024;
+
+const DEFAULT_FUZZY_LIMIT: usize = 5;
+/// Page size of retrieve_memories when the caller doesn't give a limit
+const DEFAULT_RETRIEVE_LIMIT: usize = 50;
+/// Fuzzy matches scoring below this are too far from the query to be useful
+const MIN_FUZZY_SCORE: f64 = 0.5;
+
+/// Marks the expiry time among the tags of a memory, e.g. `# sprint expires:2025-06-01T00:00:00Z`
+const EXPIRES_TAG_PREFIX: &str = "expires:";
+/// Marks when a memory was saved among its tags, e.g. `# sprint saved:2024-11-02T09:30:00Z`
+const SAVED_TAG_PREFIX: &str = "saved:";
+
+/// The tags on a memory's `#` line, with the times kept among them split out
+struct Header {
+    tags: Vec<String>,
+    expires_at: Option<DateTime<Utc>>,
+    saved_at: Option<DateTime<Utc>>,
+}
+
+impl Header {
+    fn parse(tags: &str) -> Self {
+        let mut header = Header {
+            tags: Vec::new(),
+            expires_at: None,
+            saved_at: None,
+        };
+        let time = |tag: &str, prefix: &str| {
+            tag.strip_prefix(prefix)
+                .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+                .map(|time| time.with_timezone(&Utc))
+        };
+        for tag in tags.split_whitespace() {
+            if let Some(expires_at) = time(tag, EXPIRES_TAG_PREFIX) {
+                header.expires_at = Some(expires_at);
+            } else if let Some(saved_at) = time(tag, SAVED_TAG_PREFIX) {
+                header.saved_at = Some(saved_at);
+            } else {
+                header.tags.push(tag.to_string());
+            }
+        }
+        header
+    }
+}
+
+fn is_expired(entry: &str, now: DateTime<Utc>) -> bool {
+    entry
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix('#'))
+        .and_then(|tags| Header::parse(tags).expires_at)
+        .is_some_and(|expires_at| expires_at <= now)
+}
+
+/// Sort memories newest first. Those without a saved time, stored before it was recorded,
+/// come last, and within a category later entries count as newer.
+fn sort_newest_first(entries: &mut [MemoryEntry]) {
+    entries.reverse();
+    entries.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+}
+
+/// A single stored memory, as written by one remember call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryEntry {
+    pub category: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub content: String,
+    pub is_global: bool,
+    /// When the memory is forgotten
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// When the memory was stored, unknown for memories saved before this was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved_at: Option<DateTime<Utc>>,
+}
+
+impl MemoryEntry {
+    fn describe(&self) -> String {
+        let scope = if self.is_global { "global" } else { "local" };
+        let tags: String = self.tags.iter().map(|tag| format!(" #{}", tag)).collect();
+        format!("({}) {}{}: {}", scope, self.category, tags, self.content)
+    }
+
+    /// When the memory was saved, as `saved 2024-11-02` or `unknown date`
+    fn saved_label(&self) -> String {
+        match self.saved_at {
+            Some(saved_at) => format!("saved {}", saved_at.format("%Y-%m-%d")),
+            None => "unknown date".to_string(),
+        }
+    }
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// How closely `query` matches somewhere in `text`, from 0 to 1.
+///
+/// Takes the better of the edit distance to the closest run of words as long as the query, and
+/// how tightly the query's characters appear in order in the text, so both typos and
+/// abbreviations score well.
+fn similarity(query: &str, text: &str) -> f64 {
+    let query = query.to_lowercase();
+    let text = text.to_lowercase();
+    let query_words: Vec<&str> = query.split_whitespace().collect();
+    let text_words: Vec<&str> = text.split_whitespace().collect();
+    if query_words.is_empty() || text_words.is_empty() {
+        return 0.0;
+    }
+    let query_chars: Vec<char> = query_words.join(" ").chars().collect();
+
+    let window = query_words.len().min(text_words.len());
+    let edit_score = text_words
+        .windows(window)
+        .map(|words| {
+            let candidate: Vec<char> = words.join(" ").chars().collect();
+            let distance = levenshtein(&query_chars, &candidate);
+            1.0 - distance as f64 / query_chars.len().max(candidate.len()) as f64
+        })
+        .fold(0.0, f64::max);
+
+    // Characters of the query found in order; the score falls as the match spreads out
+    let text_chars: Vec<char> = text.chars().collect();
+    let mut position = 0;
+    let mut span = None;
+    for wanted in query_chars.iter().filter(|c| !c.is_whitespace()) {
+        match text_chars[position..].iter().position(|c| c == wanted) {
+            Some(offset) => {
+                let index = position + offset;
+                span = Some((span.map_or(index, |(start, _)| start), index));
+                position = index + 1;
+            }
+            None => {
+                span = None;
+                break;
+            }
+        }
+    }
+    let matched = query_chars.iter().filter(|c| !c.is_whitespace()).count();
+    let subsequence_score = span.map_or(0.0, |(start, end)| {
+        0.9 * matched as f64 / (end - start + 1).max(matched) as f64
+    });
+
+    edit_score.max(subsequence_score)
+}
+
+/// How category files are kept on disk
+#[derive(Clone)]
+enum Storage {
+    Plaintext,
+    Encrypted(MemoryCipher),
+    /// Encryption is on but its key could not be loaded, so nothing is written in plaintext
+    Unavailable(String),
+}
+
 /// Memory MCP Server using official RMCP SDK
 #[derive(Clone)]
 pub struct MemoryServer {
@@ -67,6 +470,9 @@ pub struct MemoryServer {
     instructions: String,
     global_memory_dir: PathBuf,
     local_memory_dir: PathBuf,
+    storage: Storage,
+    /// The connected client, told when the memory resources change
+    peer: Arc<Mutex<Option<Peer<RoleServer>>>>,
 }
 
 impl Default for MemoryServer {
@@ -110,6 +516,7 @@ impl MemoryServer {
                   - Global storage (~/.config/goose/memory) for user-wide data.
                 - Use the remember_memory tool to store the information.
                   - `remember_memory(category, data, tags, is_global)`
+                - If the information only holds for a while, like the current sprint's conventions, set `expires_in_days` so it is forgotten afterwards.
              Keywords that trigger memory tools:
              - "remember"
              - "forget"
@@ -154,6 +561,17 @@ impl MemoryServer {
              - **Filter by Tags**:
                - Enables targeted retrieval based on specific tags.
                - Use: Provide tag filters to refine search.
+             - **Search by Content**:
+               - Finds memories whose content or tags contain a query, across categories.
+               - Use: `search_memories(query="black")`
+               - Note: If the user only half-remembers what was stored, search with `fuzzy=True` to rank memories by similarity.
+             - **Back Up or Move Memories**:
+               - `export_memories()` returns every memory as a JSON document, and `import_memories(document=...)` restores one.
+               - Note: Imports merge by default and report conflicts; only use `mode="replace"` when the user asks to overwrite.
+             - **Overview**:
+               - `memory_stats()` counts memories per category and scope without loading their contents.
+               - `list_tags()` shows the tags in use; prefer reusing them when storing new memories.
+               - Use it when the user asks what is remembered about them, then retrieve the categories they care about.
             To remove a memory, use the following protocol:
             - **Remove by Category**:
               - Removes all memories within the specified category.
@@ -196,15 +614,36 @@ impl MemoryServer {
             .map(|strategy| strategy.in_config_dir("memory"))
             .unwrap_or_else(|_| PathBuf::from(".config/goose/memory"));
 
+        let config = Config::global();
+        let storage = if config
+            .get_param::<bool>(ENCRYPTION_CONFIG_KEY)
+            .unwrap_or(false)
+        {
+            match MemoryCipher::from_secret_store(config) {
+                Ok(cipher) => Storage::Encrypted(cipher),
+                Err(e) => {
+                    tracing::warn!("Memory encryption is on but its key is unavailable: {}", e);
+                    Storage::Unavailable(e.to_string())
+                }
+            }
+        } else {
+            Storage::Plaintext
+        };
+
         let mut memory_router = Self {
             tool_router: Self::tool_router(),
             instructions: instructions.clone(),
             global_memory_dir,
             local_memory_dir,
+            storage,
+            peer: Arc::default(),
         };
 
-        let retrieved_global_memories = memory_router.retrieve_all(true);
-        let retrieved_local_memories = memory_router.retrieve_all(false);
+        match memory_router.encrypt_plaintext_files() {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Encrypted {} plaintext memory files", count),
+            Err(e) => tracing::warn!("Failed to encrypt plaintext memory files: {}", e),
+        }
 
         let mut updated_instructions = instructions;
 
@@ -219,29 +658,10 @@ impl MemoryServer {
         updated_instructions.push_str("\n\n");
         updated_instructions.push_str(&memories_follow_up_instructions);
 
-        if let Ok(global_memories) = retrieved_global_memories {
-            if !global_memories.is_empty() {
-                updated_instructions.push_str("\n\nGlobal Memories:\n");
-                for (category, memories) in global_memories {
-                    updated_instructions.push_str(&format!("\nCategory: {}\n", category));
-                    for memory in memories {
-                        updated_instructions.push_str(&format!("- {}\n", memory));
-                    }
-                }
-            }
-        }
-
-        if let Ok(local_memories) = retrieved_local_memories {
-            if !local_memories.is_empty() {
-                updated_instructions.push_str("\n\nLocal Memories:\n");
-                for (category, memories) in local_memories {
-                    updated_instructions.push_str(&format!("\nCategory: {}\n", category));
-                    for memory in memories {
-                        updated_instructions.push_str(&format!("- {}\n", memory));
-                    }
-                }
-            }
-        }
+        let max_bytes = config
+            .get_param::<usize>(MAX_INSTRUCTION_BYTES_KEY)
+            .unwrap_or(DEFAULT_MAX_INSTRUCTION_BYTES);
+        updated_instructions.push_str(&memory_router.memory_instructions(max_bytes));
 
         memory_router.set_instructions(updated_instructions);
 
@@ -257,102 +677,465 @@ impl MemoryServer {
         &self.instructions
     }
 
-    fn get_memory_file(&self, category: &str, is_global: bool) -> PathBuf {
+    /// The saved memories as instruction text, holding at most `max_bytes` of memory lines.
+    ///
+    /// When they don't all fit, the most recently saved memories are kept: those in the most
+    /// recently modified category files first, and later entries within a file before earlier
+    /// ones. A note then points at the tools for looking up the rest.
+    pub fn memory_instructions(&self, max_bytes: usize) -> String {
+        let line = |entry: &MemoryEntry| format!("- {} ({})\n", entry.content, entry.saved_label());
+
+        let scopes: Vec<(bool, Vec<MemoryEntry>)> = [true, false]
+            .into_iter()
+            .map(|is_global| (is_global, self.entries(None, is_global).unwrap_or_default()))
+            .collect();
+
+        // (saved, position in category, scope, entry), newest first. Memories stored before
+        // their saved time was recorded go by when their file was last changed.
+        let mut by_recency = Vec::new();
+        for (scope_index, (is_global, entries)) in scopes.iter().enumerate() {
+            let mut positions: HashMap<&str, usize> = HashMap::new();
+            for (entry_index, entry) in entries.iter().enumerate() {
+                let modified = entry.saved_at.map(SystemTime::from).unwrap_or_else(|| {
+                    self.get_memory_file(&entry.category, *is_global)
+                        .and_then(fs::metadata)
+                        .and_then(|metadata| metadata.modified())
+                        .unwrap_or(SystemTime::UNIX_EPOCH)
+                });
+                let position = positions.entry(entry.category.as_str()).or_default();
+                by_recency.push((modified, *position, scope_index, entry_index));
+                *position += 1;
+            }
+        }
+        by_recency.sort_by(|a, b| (b.0, b.1).cmp(&(a.0, a.1)));
+
+        let mut kept = HashSet::new();
+        let mut used = 0;
+        for (_, _, scope_index, entry_index) in &by_recency {
+            let size = line(&scopes[*scope_index].1[*entry_index]).len();
+            if used + size > max_bytes {
+                break;
+            }
+            used += size;
+            kept.insert((*scope_index, *entry_index));
+        }
+
+        let mut text = String::new();
+        for (scope_index, (is_global, entries)) in scopes.iter().enumerate() {
+            let mut category = None;
+            for (entry_index, entry) in entries.iter().enumerate() {
+                if !kept.contains(&(scope_index, entry_index)) {
+                    continue;
+                }
+                if category.is_none() {
+                    text.push_str(if *is_global {
+                        "\n\nGlobal Memories:\n"
+                    } else {
+                        "\n\nLocal Memories:\n"
+                    });
+                }
+                if category != Some(entry.category.as_str()) {
+                    text.push_str(&format!("\nCategory: {}\n", entry.category));
+                    category = Some(entry.category.as_str());
+                }
+                text.push_str(&line(entry));
+            }
+        }
+
+        let omitted = by_recency.len() - kept.len();
+        if omitted > 0 {
+            text.push_str(&format!(
+                "\n\nNote: {} older memories were left out to keep these instructions short. Use retrieve_memories or search_memories to look them up when they may be relevant.\n",
+                omitted
+            ));
+        }
+        text
+    }
+
+    fn get_memory_file(&self, category: &str, is_global: bool) -> io::Result<PathBuf> {
         // Defaults to local memory if no is_global flag is provided
         let base_dir = if is_global {
             &self.global_memory_dir
         } else {
             &self.local_memory_dir
         };
-        base_dir.join(format!("{}.txt", category))
+        let category = normalize_category(category)?;
+        Ok(base_dir.join(format!("{}.txt", category)))
+    }
+
+    /// Every memory in one scope, newest first
+    pub fn retrieve_all(&self, is_global: bool) -> io::Result<Vec<MemoryEntry>> {
+        let mut entries = Vec::new();
+        for category in self.categories(is_global)? {
+            entries.extend(self.entries(Some(&category), is_global)?);
+        }
+        sort_newest_first(&mut entries);
+        Ok(entries)
+    }
+
+    pub fn remember(
+        &self,
+        context: &str,
+        category: &str,
+        data: &str,
+        tags: &[&str],
+        is_global: bool,
+    ) -> io::Result<()> {
+        self.remember_until(context, category, data, tags, is_global, None)
+    }
+
+    /// Store a memory that is forgotten at `expires_at`, or kept until removed when `None`
+    pub fn remember_until(
+        &self,
+        _context: &str,
+        category: &str,
+        data: &str,
+        tags: &[&str],
+        is_global: bool,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> io::Result<()> {
+        self.append(&MemoryEntry {
+            category: category.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            content: data.to_string(),
+            is_global,
+            expires_at,
+            saved_at: Some(Utc::now()),
+        })
+    }
+
+    /// Add a memory to the end of its category file
+    fn append(&self, entry: &MemoryEntry) -> io::Result<()> {
+        let memory_file_path = self.get_memory_file(&entry.category, entry.is_global)?;
+
+        if let Some(parent) = memory_file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut content = if memory_file_path.exists() {
+            self.read_memory_file(&memory_file_path)?
+        } else {
+            String::new()
+        };
+        let mut header = entry.tags.clone();
+        for (prefix, time) in [
+            (EXPIRES_TAG_PREFIX, entry.expires_at),
+            (SAVED_TAG_PREFIX, entry.saved_at),
+        ] {
+            if let Some(time) = time {
+                header.push(format!(
+                    "{}{}",
+                    prefix,
+                    time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+                ));
+            }
+        }
+        if !header.is_empty() {
+            content.push_str(&format!("# {}\n", header.join(" ")));
+        }
+        content.push_str(&format!("{}\n\n", entry.content));
+
+        self.write_memory_file(&memory_file_path, &content)
+    }
+
+    /// The memories in one category, newest first
+    pub fn retrieve(&self, category: &str, is_global: bool) -> io::Result<Vec<MemoryEntry>> {
+        let mut entries = self.entries(Some(category), is_global)?;
+        sort_newest_first(&mut entries);
+        Ok(entries)
+    }
+
+    /// The contents of a category file without its expired memories, which are deleted from
+    /// the file on the way. `None` when the category has no file.
+    fn read_unexpired(&self, category: &str, is_global: bool) -> io::Result<Option<String>> {
+        let memory_file_path = self.get_memory_file(category, is_global)?;
+        if !memory_file_path.exists() {
+            return Ok(None);
+        }
+        let content = self.read_memory_file(&memory_file_path)?;
+
+        let now = Utc::now();
+        if !content.split("\n\n").any(|entry| is_expired(entry, now)) {
+            return Ok(Some(content));
+        }
+        let live: Vec<&str> = content
+            .split("\n\n")
+            .filter(|entry| !is_expired(entry, now))
+            .collect();
+        let content = live.join("\n\n");
+        self.write_memory_file(&memory_file_path, &content)?;
+        Ok(Some(content))
+    }
+
+    /// A category file's contents, decrypted when the file is encrypted
+    fn read_memory_file(&self, path: &Path) -> io::Result<String> {
+        let stored = fs::read_to_string(path)?;
+        if !encryption::is_encrypted(&stored) {
+            return Ok(stored);
+        }
+        match &self.storage {
+            Storage::Encrypted(cipher) => cipher.decrypt(&stored),
+            Storage::Plaintext => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} is encrypted; set {} to read it",
+                    path.display(),
+                    ENCRYPTION_CONFIG_KEY
+                ),
+            )),
+            Storage::Unavailable(reason) => Err(key_unavailable(reason)),
+        }
+    }
+
+    /// Replace a category file's contents, encrypting them when encryption is on
+    fn write_memory_file(&self, path: &Path, content: &str) -> io::Result<()> {
+        match &self.storage {
+            Storage::Plaintext => replace_file(path, content),
+            Storage::Encrypted(cipher) => replace_file(path, &cipher.encrypt(content)?),
+            Storage::Unavailable(reason) => Err(key_unavailable(reason)),
+        }
     }
 
-    pub fn retrieve_all(&self, is_global: bool) -> io::Result<HashMap<String, Vec<String>>> {
+    /// Encrypt the category files still stored in plaintext, in both scopes, returning how
+    /// many were encrypted. Does nothing unless encryption is on.
+    pub fn encrypt_plaintext_files(&self) -> io::Result<usize> {
+        if !matches!(self.storage, Storage::Encrypted(_)) {
+            return Ok(0);
+        }
+        let mut count = 0;
+        for is_global in [true, false] {
+            for category in self.categories(is_global)? {
+                let path = self.get_memory_file(&category, is_global)?;
+                let stored = fs::read_to_string(&path)?;
+                if !encryption::is_encrypted(&stored) {
+                    self.write_memory_file(&path, &stored)?;
+                    count += 1;
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    /// The categories stored in one scope, sorted by name
+    fn categories(&self, is_global: bool) -> io::Result<Vec<String>> {
         let base_dir = if is_global {
             &self.global_memory_dir
         } else {
             &self.local_memory_dir
         };
-        let mut memories = HashMap::new();
+        let mut categories = Vec::new();
         if base_dir.exists() {
             for entry in fs::read_dir(base_dir)? {
                 let entry = entry?;
                 if entry.file_type()?.is_file() {
                     let category = entry.file_name().to_string_lossy().replace(".txt", "");
-                    let category_memories = self.retrieve(&category, is_global)?;
-                    memories.insert(
-                        category,
-                        category_memories.into_iter().flat_map(|(_, v)| v).collect(),
-                    );
+                    if normalize_category(&category).is_ok() {
+                        categories.push(category);
+                    }
                 }
             }
         }
-        Ok(memories)
+        categories.sort();
+        Ok(categories)
     }
 
-    pub fn remember(
+    /// Every memory in `category`, or in every category when `None`, kept as separate entries
+    pub fn entries(&self, category: Option<&str>, is_global: bool) -> io::Result<Vec<MemoryEntry>> {
+        let categories = match category {
+            Some(category) => vec![normalize_category(category)?],
+            None => self.categories(is_global)?,
+        };
+
+        let mut entries = Vec::new();
+        for category in categories {
+            let Some(content) = self.read_unexpired(&category, is_global)? else {
+                continue;
+            };
+            for entry in content.split("\n\n") {
+                let mut lines = entry.lines().peekable();
+                let header = match lines.peek().and_then(|line| line.strip_prefix('#')) {
+                    Some(stripped) => {
+                        lines.next();
+                        Header::parse(stripped)
+                    }
+                    None => Header::parse(""),
+                };
+                let content = lines.collect::<Vec<_>>().join("\n");
+                if !content.trim().is_empty() {
+                    entries.push(MemoryEntry {
+                        category: category.clone(),
+                        tags: header.tags,
+                        content,
+                        is_global,
+                        expires_at: header.expires_at,
+                        saved_at: header.saved_at,
+                    });
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Memories matching `query`, with their scores. Exact search keeps every memory whose
+    /// content or tags contain the query, ignoring case, in storage order with a score of 1.
+    /// Fuzzy search ranks memories by [`similarity`] and keeps the best `limit`.
+    pub fn search(
         &self,
-        _context: &str,
-        category: &str,
-        data: &str,
-        tags: &[&str],
-        is_global: bool,
-    ) -> io::Result<()> {
-        let memory_file_path = self.get_memory_file(category, is_global);
+        query: &str,
+        category: Option<&str>,
+        scopes: &[bool],
+        fuzzy: bool,
+        limit: usize,
+    ) -> io::Result<Vec<(MemoryEntry, f64)>> {
+        let mut entries = Vec::new();
+        for &is_global in scopes {
+            entries.extend(self.entries(category, is_global)?);
+        }
 
-        if let Some(parent) = memory_file_path.parent() {
-            fs::create_dir_all(parent)?;
+        if !fuzzy {
+            let query = query.to_lowercase();
+            return Ok(entries
+                .into_iter()
+                .filter(|entry| {
+                    entry.content.to_lowercase().contains(&query)
+                        || entry
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase().contains(&query))
+                })
+                .map(|entry| (entry, 1.0))
+                .collect());
+        }
+
+        let mut scored: Vec<(MemoryEntry, f64)> = entries
+            .into_iter()
+            .map(|entry| {
+                let score = std::iter::once(entry.content.as_str())
+                    .chain(entry.tags.iter().map(String::as_str))
+                    .map(|text| similarity(query, text))
+                    .fold(0.0, f64::max);
+                (entry, score)
+            })
+            .filter(|(_, score)| *score >= MIN_FUZZY_SCORE)
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(limit);
+        Ok(scored)
+    }
+
+    pub fn stats(&self, is_global: bool) -> io::Result<MemoryStats> {
+        let mut stats = MemoryStats {
+            is_global,
+            ..Default::default()
+        };
+        for category in self.categories(is_global)? {
+            let entries = self.entries(Some(&category), is_global)?.len();
+            let bytes = fs::metadata(self.get_memory_file(&category, is_global)?)?.len();
+            stats.entries += entries;
+            stats.bytes += bytes;
+            stats.categories.push((category, entries, bytes));
         }
+        Ok(stats)
+    }
 
-        let mut file = fs::OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(&memory_file_path)?;
-        if !tags.is_empty() {
-            writeln!(file, "# {}", tags.join(" "))?;
+    /// Every tag in use across `scopes`, most used first
+    pub fn tags(&self, scopes: &[bool]) -> io::Result<Vec<TagUsage>> {
+        let mut usage: HashMap<String, (usize, BTreeSet<String>)> = HashMap::new();
+        for &is_global in scopes {
+            let scope = if is_global { "global" } else { "local" };
+            for entry in self.entries(None, is_global)? {
+                for tag in entry.tags {
+                    let (count, categories) = usage.entry(tag).or_default();
+                    *count += 1;
+                    categories.insert(format!("({}) {}", scope, entry.category));
+                }
+            }
         }
-        writeln!(file, "{}\n", data)?;
 
-        Ok(())
+        let mut tags: Vec<TagUsage> = usage
+            .into_iter()
+            .map(|(tag, (count, categories))| TagUsage {
+                tag,
+                count,
+                categories: categories.into_iter().collect(),
+            })
+            .collect();
+        tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+        Ok(tags)
     }
 
-    pub fn retrieve(
-        &self,
-        category: &str,
-        is_global: bool,
-    ) -> io::Result<HashMap<String, Vec<String>>> {
-        let memory_file_path = self.get_memory_file(category, is_global);
-        if !memory_file_path.exists() {
-            return Ok(HashMap::new());
-        }
-
-        let mut file = fs::File::open(memory_file_path)?;
-        let mut content = String::new();
-        file.read_to_string(&mut content)?;
-
-        let mut memories = HashMap::new();
-        for entry in content.split("\n\n") {
-            let mut lines = entry.lines();
-            if let Some(first_line) = lines.next() {
-                if let Some(stripped) = first_line.strip_prefix('#') {
-                    let tags = stripped
-                        .split_whitespace()
-                        .map(String::from)
-                        .collect::<Vec<_>>();
-                    memories.insert(tags.join(" "), lines.map(String::from).collect());
+    pub fn export(&self, scopes: &[bool]) -> io::Result<MemoryExport> {
+        let mut memories = Vec::new();
+        for &is_global in scopes {
+            memories.extend(self.entries(None, is_global)?);
+        }
+        Ok(MemoryExport {
+            version: MEMORY_EXPORT_VERSION,
+            memories,
+        })
+    }
+
+    /// Store the memories in `export`. Nothing is written unless the whole document is valid.
+    ///
+    /// Merging skips memories that are already stored and reports, rather than overwrites, ones
+    /// stored with different tags. Replacing clears each category the document mentions first.
+    pub fn import(&self, export: &MemoryExport, mode: ImportMode) -> io::Result<ImportReport> {
+        let problems = export.problems();
+        if !problems.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid memory export: {}", problems.join("; ")),
+            ));
+        }
+
+        let mut report = ImportReport::default();
+        let mut existing: HashMap<(bool, String), Vec<MemoryEntry>> = HashMap::new();
+        for entry in &export.memories {
+            let category = normalize_category(&entry.category)?;
+            let key = (entry.is_global, category.clone());
+            if !existing.contains_key(&key) {
+                let stored = self.entries(Some(&category), entry.is_global)?;
+                if mode == ImportMode::Replace {
+                    if !stored.is_empty() {
+                        let scope = if entry.is_global { "global" } else { "local" };
+                        report.replaced.push(format!("({}) {}", scope, category));
+                    }
+                    self.clear_memory(&category, entry.is_global)?;
+                    existing.insert(key.clone(), Vec::new());
                 } else {
-                    let entry_data: Vec<String> = std::iter::once(first_line.to_string())
-                        .chain(lines.map(String::from))
-                        .collect();
-                    memories
-                        .entry("untagged".to_string())
-                        .or_insert_with(Vec::new)
-                        .extend(entry_data);
+                    existing.insert(key.clone(), stored);
                 }
             }
-        }
 
-        Ok(memories)
+            let stored = existing.get_mut(&key).expect("category was just loaded");
+            let content = entry.content.trim();
+            if let Some(previous) = stored.iter().find(|stored| stored.content == content) {
+                if previous.tags == entry.tags {
+                    report.duplicates += 1;
+                } else {
+                    report.conflicts.push(format!(
+                        "{} is stored with tags [{}], import has [{}]",
+                        previous.describe(),
+                        previous.tags.join(", "),
+                        entry.tags.join(", ")
+                    ));
+                }
+                continue;
+            }
+
+            let imported = MemoryEntry {
+                category,
+                tags: entry.tags.clone(),
+                content: content.to_string(),
+                is_global: entry.is_global,
+                expires_at: entry.expires_at,
+                saved_at: entry.saved_at,
+            };
+            self.append(&imported)?;
+            stored.push(imported);
+            report.imported += 1;
+        }
+        Ok(report)
     }
 
     pub fn remove_specific_memory_internal(
@@ -361,14 +1144,12 @@ impl MemoryServer {
         memory_content: &str,
         is_global: bool,
     ) -> io::Result<()> {
-        let memory_file_path = self.get_memory_file(category, is_global);
+        let memory_file_path = self.get_memory_file(category, is_global)?;
         if !memory_file_path.exists() {
             return Ok(());
         }
 
-        let mut file = fs::File::open(&memory_file_path)?;
-        let mut content = String::new();
-        file.read_to_string(&mut content)?;
+        let content = self.read_memory_file(&memory_file_path)?;
 
         let memories: Vec<&str> = content.split("\n\n").collect();
         let new_content: Vec<String> = memories
@@ -377,13 +1158,11 @@ impl MemoryServer {
             .map(|s| s.to_string())
             .collect();
 
-        fs::write(memory_file_path, new_content.join("\n\n"))?;
-
-        Ok(())
+        self.write_memory_file(&memory_file_path, &new_content.join("\n\n"))
     }
 
     pub fn clear_memory(&self, category: &str, is_global: bool) -> io::Result<()> {
-        let memory_file_path = self.get_memory_file(category, is_global);
+        let memory_file_path = self.get_memory_file(category, is_global)?;
         if memory_file_path.exists() {
             fs::remove_file(memory_file_path)?;
         }
@@ -403,45 +1182,162 @@ impl MemoryServer {
         Ok(())
     }
 
-    /// Stores a memory with optional tags in a specified category
-    #[tool(
-        name = "remember_memory",
-        description = "Stores a memory with optional tags in a specified category"
-    )]
-    pub async fn remember_memory(
-        &self,
-        params: Parameters<RememberMemoryParams>,
-    ) -> Result<CallToolResult, ErrorData> {
-        let params = params.0;
+    fn resource_uri(category: &str, is_global: bool) -> String {
+        let scope = if is_global { "global" } else { "local" };
+        format!("{}{}/{}", RESOURCE_SCHEME, scope, category)
+    }
 
-        if params.data.is_empty() {
-            return Err(ErrorData::new(
-                ErrorCode::INVALID_PARAMS,
+    /// A `memory://` resource for each category in both scopes, global first
+    pub fn memory_resources(&self) -> io::Result<Vec<Resource>> {
+        let mut resources = Vec::new();
+        for is_global in [true, false] {
+            let scope = if is_global { "global" } else { "local" };
+            for category in self.categories(is_global)? {
+                let count = self.entries(Some(&category), is_global)?.len();
+                let mut resource = RawResource::new(
+                    Self::resource_uri(&category, is_global),
+                    format!("{} ({})", category, scope),
+                );
+                resource.description = Some(format!("{} {} memories", count, scope));
+                resource.mime_type = Some("text/markdown".to_string());
+                resources.push(Resource {
+                    raw: resource,
+                    annotations: None,
+                });
+            }
+        }
+        Ok(resources)
+    }
+
+    /// The memories of the category behind a `memory://` URI, rendered as markdown
+    pub fn read_memory_resource(&self, uri: &str) -> Result<ResourceContents, ErrorData> {
+        let not_found = || {
+            ErrorData::new(
+                ErrorCode::INVALID_REQUEST,
+                format!("Memory resource not found: {}", uri),
+                None,
+            )
+        };
+        let (scope, category) = uri
+            .strip_prefix(RESOURCE_SCHEME)
+            .and_then(|rest| rest.split_once('/'))
+            .ok_or_else(not_found)?;
+        let is_global = match scope {
+            "global" => true,
+            "local" => false,
+            _ => return Err(not_found()),
+        };
+        let category = normalize_category(category).map_err(|_| not_found())?;
+        if !self
+            .get_memory_file(&category, is_global)
+            .map_err(io_error_to_error_data)?
+            .exists()
+        {
+            return Err(not_found());
+        }
+
+        let entries = self
+            .entries(Some(&category), is_global)
+            .map_err(io_error_to_error_data)?;
+        let mut markdown = format!("# {}\n", category);
+        for entry in entries {
+            markdown.push('\n');
+            if !entry.tags.is_empty() {
+                let tags: Vec<String> = entry.tags.iter().map(|tag| format!("`{}`", tag)).collect();
+                markdown.push_str(&format!("Tags: {}\n\n", tags.join(" ")));
+            }
+            if let Some(saved_at) = entry.saved_at {
+                markdown.push_str(&format!(
+                    "Saved: {}\n\n",
+                    saved_at.format("%Y-%m-%d %H:%M UTC")
+                ));
+            }
+            if let Some(expires_at) = entry.expires_at {
+                markdown.push_str(&format!(
+                    "Expires: {}\n\n",
+                    expires_at.format("%Y-%m-%d %H:%M UTC")
+                ));
+            }
+            markdown.push_str(&format!("{}\n", entry.content));
+        }
+
+        Ok(ResourceContents::TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some("text/markdown".to_string()),
+            text: markdown,
+            meta: None,
+        })
+    }
+
+    /// Tell the client to refetch the resource list after memories were added or removed
+    async fn notify_resources_changed(&self) {
+        let peer = self.peer.lock().unwrap().clone();
+        if let Some(peer) = peer {
+            if let Err(e) = peer.notify_resource_list_changed().await {
+                tracing::debug!("Failed to send resource list change: {}", e);
+            }
+        }
+    }
+
+    /// Stores a memory with optional tags in a specified category
+    #[tool(
+        name = "remember_memory",
+        description = "Stores a memory with optional tags in a specified category"
+    )]
+    pub async fn remember_memory(
+        &self,
+        params: Parameters<RememberMemoryParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+
+        if params.data.is_empty() {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
                 "Data must not be empty when remembering a memory".to_string(),
                 None,
             ));
         }
 
+        let expires_at = match params.expires_in_days {
+            Some(0) => {
+                return Err(ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    "expires_in_days must be at least 1".to_string(),
+                    None,
+                ))
+            }
+            Some(days) => Some(Utc::now() + Duration::days(i64::from(days))),
+            None => None,
+        };
+
         let tags: Vec<&str> = params.tags.iter().map(|s| s.as_str()).collect();
-        self.remember(
+        self.remember_until(
             "context",
             &params.category,
             &params.data,
             &tags,
             params.is_global,
+            expires_at,
         )
-        .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+        .map_err(io_error_to_error_data)?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!(
-            "Stored memory in category: {}",
-            params.category
-        ))]))
+        self.notify_resources_changed().await;
+
+        let mut message = format!("Stored memory in category: {}", params.category);
+        if let Some(expires_at) = expires_at {
+            message.push_str(&format!(
+                " (expires {})",
+                expires_at.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+        Ok(CallToolResult::success(vec![Content::text(message)]))
     }
 
-    /// Retrieves all memories from a specified category
+    /// Retrieves a page of memories from a specified category
     #[tool(
         name = "retrieve_memories",
-        description = "Retrieves all memories from a specified category"
+        description = "Retrieves memories from a specified category, newest first, one page at a time. Returns at most limit memories (50 by default) starting at offset, along with the total count; pass the offset given at the end of the page to fetch the next one",
+        annotations(read_only_hint = true)
     )]
     pub async fn retrieve_memories(
         &self,
@@ -449,17 +1345,312 @@ impl MemoryServer {
     ) -> Result<CallToolResult, ErrorData> {
         let params = params.0;
 
+        let limit = params.limit.unwrap_or(DEFAULT_RETRIEVE_LIMIT);
+        if limit == 0 {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "Limit must be at least 1 when retrieving memories".to_string(),
+                None,
+            ));
+        }
+
         let memories = if params.category == "*" {
             self.retrieve_all(params.is_global)
         } else {
             self.retrieve(&params.category, params.is_global)
         }
-        .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+        .map_err(io_error_to_error_data)?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!(
-            "Retrieved memories: {:?}",
-            memories
-        ))]))
+        if memories.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No memories found",
+            )]));
+        }
+        let total = memories.len();
+        if params.offset >= total {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No memories at offset {}; there are {} in total",
+                params.offset, total
+            ))]));
+        }
+        let end = total.min(params.offset.saturating_add(limit));
+        let mut text = format!(
+            "Retrieved memories {}-{} of {}, newest first:\n",
+            params.offset + 1,
+            end,
+            total
+        );
+        for entry in &memories[params.offset..end] {
+            let tags: String = entry.tags.iter().map(|tag| format!(" #{}", tag)).collect();
+            text.push_str(&format!(
+                "- ({}) {}{}: {}\n",
+                entry.saved_label(),
+                entry.category,
+                tags,
+                entry.content
+            ));
+        }
+        if end < total {
+            text.push_str(&format!(
+                "\n{} more memories; retrieve again with offset {} for the next page\n",
+                total - end,
+                end
+            ));
+        }
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    /// Searches memories for a query, exactly or fuzzily
+    #[tool(
+        name = "search_memories",
+        description = "Searches memory contents and tags for a query. Matches are exact (ignoring case) by default; set fuzzy to rank memories by similarity instead, which tolerates typos and half-remembered wording",
+        annotations(read_only_hint = true)
+    )]
+    pub async fn search_memories(
+        &self,
+        params: Parameters<SearchMemoriesParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+
+        if params.query.trim().is_empty() {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "Query must not be empty when searching memories".to_string(),
+                None,
+            ));
+        }
+
+        let scopes = match params.is_global {
+            Some(is_global) => vec![is_global],
+            None => vec![true, false],
+        };
+        let category = params
+            .category
+            .as_deref()
+            .filter(|category| *category != "*");
+        let matches = self
+            .search(
+                params.query.trim(),
+                category,
+                &scopes,
+                params.fuzzy,
+                params.limit.unwrap_or(DEFAULT_FUZZY_LIMIT),
+            )
+            .map_err(io_error_to_error_data)?;
+
+        let text = if matches.is_empty() {
+            format!("No memories found matching '{}'", params.query)
+        } else {
+            let lines: Vec<String> = matches
+                .iter()
+                .map(|(entry, score)| {
+                    if params.fuzzy {
+                        format!("- [{:.2}] {}", score, entry.describe())
+                    } else {
+                        format!("- {}", entry.describe())
+                    }
+                })
+                .collect();
+            format!(
+                "Found {} memories matching '{}':\n{}",
+                matches.len(),
+                params.query,
+                lines.join("\n")
+            )
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    /// Lists the tags in use with how often and where they are used
+    #[tool(
+        name = "list_tags",
+        description = "Lists every tag in use with the number of memories carrying it and the categories they are in, most used first. Use it to see how memories are organized and to reuse existing tags when storing new memories",
+        annotations(read_only_hint = true)
+    )]
+    pub async fn list_tags(
+        &self,
+        params: Parameters<ListTagsParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+
+        let scopes = match params.is_global {
+            Some(is_global) => vec![is_global],
+            None => vec![true, false],
+        };
+        let tags = self.tags(&scopes).map_err(io_error_to_error_data)?;
+
+        let text = if tags.is_empty() {
+            "No tags in use".to_string()
+        } else {
+            let lines: Vec<String> = tags
+                .iter()
+                .map(|usage| {
+                    format!(
+                        "- #{} ({}): {}",
+                        usage.tag,
+                        usage.count,
+                        usage.categories.join(", ")
+                    )
+                })
+                .collect();
+            format!("{} tags in use:\n{}", tags.len(), lines.join("\n"))
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    /// Summarizes how many memories are stored, without their contents
+    #[tool(
+        name = "memory_stats",
+        description = "Counts stored memories per category with their size on disk, split into global and local. Use it for an overview of what is remembered or to decide what to prune, instead of retrieving every memory",
+        annotations(read_only_hint = true)
+    )]
+    pub async fn memory_stats(
+        &self,
+        params: Parameters<MemoryStatsParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+
+        let scopes = match params.is_global {
+            Some(is_global) => vec![is_global],
+            None => vec![true, false],
+        };
+        let stats = scopes
+            .into_iter()
+            .map(|is_global| self.stats(is_global))
+            .collect::<io::Result<Vec<_>>>()
+            .map_err(io_error_to_error_data)?;
+
+        let entries: usize = stats.iter().map(|scope| scope.entries).sum();
+        let bytes: u64 = stats.iter().map(|scope| scope.bytes).sum();
+        let categories: usize = stats.iter().map(|scope| scope.categories.len()).sum();
+        let mut lines = vec![format!(
+            "{} memories in {} categories, {} bytes",
+            entries, categories, bytes
+        )];
+        for scope in &stats {
+            let (name, dir) = if scope.is_global {
+                ("Global", &self.global_memory_dir)
+            } else {
+                ("Local", &self.local_memory_dir)
+            };
+            lines.push(format!(
+                "\n{}: {} memories, {} bytes ({})",
+                name,
+                scope.entries,
+                scope.bytes,
+                dir.display()
+            ));
+            lines.extend(scope.categories.iter().map(|(category, entries, bytes)| {
+                format!("- {}: {} memories, {} bytes", category, entries, bytes)
+            }));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(
+            lines.join("\n"),
+        )]))
+    }
+
+    /// Exports memories as a JSON document
+    #[tool(
+        name = "export_memories",
+        description = "Exports memories with their categories, tags and scope as a JSON document, for backing them up or moving them to another machine"
+    )]
+    pub async fn export_memories(
+        &self,
+        params: Parameters<ExportMemoriesParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+
+        let scopes = match params.is_global {
+            Some(is_global) => vec![is_global],
+            None => vec![true, false],
+        };
+        let export = self.export(&scopes).map_err(io_error_to_error_data)?;
+        let document = serde_json::to_string_pretty(&export).map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to serialize memories: {}", e),
+                None,
+            )
+        })?;
+
+        let mut content = Vec::new();
+        if let Some(path) = params.path {
+            fs::write(&path, &document).map_err(io_error_to_error_data)?;
+            content.push(Content::text(format!(
+                "Exported {} memories to {}",
+                export.memories.len(),
+                path
+            )));
+        }
+        content.push(Content::text(document));
+
+        Ok(CallToolResult::success(content))
+    }
+
+    /// Imports memories from a JSON document written by export_memories
+    #[tool(
+        name = "import_memories",
+        description = "Imports memories from a JSON document written by export_memories, given inline or as a file path. Merging (the default) keeps existing memories and reports conflicts; replace clears each imported category first"
+    )]
+    pub async fn import_memories(
+        &self,
+        params: Parameters<ImportMemoriesParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+
+        let document = match (params.document, params.path) {
+            (Some(document), None) => document,
+            (None, Some(path)) => fs::read_to_string(&path).map_err(io_error_to_error_data)?,
+            _ => {
+                return Err(ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    "Provide either document or path when importing memories".to_string(),
+                    None,
+                ))
+            }
+        };
+        let export: MemoryExport = serde_json::from_str(&document).map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Invalid memory export: {}", e),
+                None,
+            )
+        })?;
+
+        let report = self
+            .import(&export, params.mode)
+            .map_err(io_error_to_error_data)?;
+
+        let mut lines = vec![format!(
+            "Imported {} memories ({} already stored)",
+            report.imported, report.duplicates
+        )];
+        if !report.replaced.is_empty() {
+            lines.push(format!(
+                "Replaced categories: {}",
+                report.replaced.join(", ")
+            ));
+        }
+        if !report.conflicts.is_empty() {
+            lines.push(format!(
+                "{} conflicts kept the existing memory:",
+                report.conflicts.len()
+            ));
+            lines.extend(
+                report
+                    .conflicts
+                    .iter()
+                    .map(|conflict| format!("- {}", conflict)),
+            );
+        }
+
+        self.notify_resources_changed().await;
+        Ok(CallToolResult::success(vec![Content::text(
+            lines.join("\n"),
+        )]))
     }
 
     /// Removes all memories within a specified category
@@ -475,16 +1666,17 @@ impl MemoryServer {
 
         let message = if params.category == "*" {
             self.clear_all_global_or_local_memories(params.is_global)
-                .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                .map_err(io_error_to_error_data)?;
             format!(
                 "Cleared all memory {} categories",
                 if params.is_global { "global" } else { "local" }
             )
         } else {
             self.clear_memory(&params.category, params.is_global)
-                .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                .map_err(io_error_to_error_data)?;
             format!("Cleared memories in category: {}", params.category)
         };
+        self.notify_resources_changed().await;
 
         Ok(CallToolResult::success(vec![Content::text(message)]))
     }
@@ -505,7 +1697,8 @@ impl MemoryServer {
             &params.memory_content,
             params.is_global,
         )
-        .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+        .map_err(io_error_to_error_data)?;
+        self.notify_resources_changed().await;
 
         Ok(CallToolResult::success(vec![Content::text(format!(
             "Removed specific memory from category: {}",
@@ -522,11 +1715,40 @@ impl ServerHandler for MemoryServer {
                 name: "goose-memory".to_string(),
                 version: env!("CARGO_PKG_VERSION").to_owned(),
             },
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .enable_resources_list_changed()
+                .build(),
             instructions: Some(self.instructions.clone()),
             ..Default::default()
         }
     }
+
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        *self.peer.lock().unwrap() = Some(context.peer);
+    }
+
+    async fn list_resources(
+        &self,
+        _pagination: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        Ok(ListResourcesResult {
+            resources: self.memory_resources().map_err(io_error_to_error_data)?,
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        params: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        Ok(ReadResourceResult {
+            contents: vec![self.read_memory_resource(&params.uri)?],
+        })
+    }
 }
 
 // Remove the old MemoryArgs struct since we're using the new parameter structs
@@ -546,6 +1768,8 @@ mod tests {
             instructions: String::new(),
             global_memory_dir: memory_base.join("global"),
             local_memory_dir: memory_base.join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
         };
 
         assert!(!router.global_memory_dir.exists());
@@ -587,6 +1811,8 @@ mod tests {
             instructions: String::new(),
             global_memory_dir: memory_base.join("global"),
             local_memory_dir: memory_base.join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
         };
 
         assert!(router.clear_all_global_or_local_memories(false).is_ok());
@@ -603,6 +1829,8 @@ mod tests {
             instructions: String::new(),
             global_memory_dir: memory_base.join("global"),
             local_memory_dir: memory_base.join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
         };
 
         router
@@ -618,10 +1846,9 @@ mod tests {
         let memories = router.retrieve("test_category", false).unwrap();
         assert!(!memories.is_empty());
 
-        let has_content = memories.values().any(|v| {
-            v.iter()
-                .any(|content| content.contains("test_data_content"))
-        });
+        let has_content = memories
+            .iter()
+            .any(|entry| entry.content.contains("test_data_content"));
         assert!(has_content);
 
         router.clear_memory("test_category", false).unwrap();
@@ -640,6 +1867,8 @@ mod tests {
             instructions: String::new(),
             global_memory_dir: memory_base.join("global"),
             local_memory_dir: memory_base.join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
         };
 
         assert!(!router.local_memory_dir.exists());
@@ -652,6 +1881,35 @@ mod tests {
         assert!(router.local_memory_dir.join("category.txt").exists());
     }
 
+    #[test]
+    fn test_writes_replace_the_category_file_whole() {
+        let temp_dir = tempdir().unwrap();
+        let router = MemoryServer {
+            tool_router: ToolRouter::new(),
+            instructions: String::new(),
+            global_memory_dir: temp_dir.path().join("global"),
+            local_memory_dir: temp_dir.path().join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
+        };
+
+        for data in ["first", "second"] {
+            router
+                .remember("context", "category", data, &[], false)
+                .unwrap();
+        }
+
+        // Only the category file is left behind, holding both memories
+        let files: Vec<_> = fs::read_dir(&router.local_memory_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(files, vec!["category.txt"]);
+        let content = fs::read_to_string(router.local_memory_dir.join("category.txt")).unwrap();
+        assert!(content.contains("first\n\n"));
+        assert!(content.contains("second\n\n"));
+    }
+
     #[test]
     fn test_remove_specific_memory() {
         let temp_dir = tempdir().unwrap();
@@ -662,6 +1920,8 @@ mod tests {
             instructions: String::new(),
             global_memory_dir: memory_base.join("global"),
             local_memory_dir: memory_base.join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
         };
 
         router
@@ -672,7 +1932,7 @@ mod tests {
             .unwrap();
 
         let memories = router.retrieve("category", false).unwrap();
-        assert_eq!(memories.len(), 1);
+        assert_eq!(memories.len(), 2);
 
         router
             .remove_specific_memory_internal("category", "remove_this", false)
@@ -680,13 +1940,935 @@ mod tests {
 
         let memories_after = router.retrieve("category", false).unwrap();
         let has_removed = memories_after
-            .values()
-            .any(|v| v.iter().any(|content| content.contains("remove_this")));
+            .iter()
+            .any(|entry| entry.content.contains("remove_this"));
         assert!(!has_removed);
 
         let has_kept = memories_after
-            .values()
-            .any(|v| v.iter().any(|content| content.contains("keep_this")));
+            .iter()
+            .any(|entry| entry.content.contains("keep_this"));
         assert!(has_kept);
     }
+
+    const TRAVERSAL_CATEGORIES: &[&str] = &[
+        "../../etc/passwd",
+        "..",
+        "..\\..\\secrets",
+        "nested/category",
+        "/absolute",
+        "C:evil",
+        "   ",
+    ];
+
+    #[test]
+    fn test_rejects_traversal_categories() {
+        let temp_dir = tempdir().unwrap();
+        let memory_base = temp_dir.path().join("memory");
+
+        let router = MemoryServer {
+            tool_router: ToolRouter::new(),
+            instructions: String::new(),
+            global_memory_dir: memory_base.join("global"),
+            local_memory_dir: memory_base.join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
+        };
+
+        for category in TRAVERSAL_CATEGORIES {
+            let err = router
+                .remember("context", category, "data", &[], false)
+                .unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{}", category);
+            assert_eq!(
+                router.retrieve(category, true).unwrap_err().kind(),
+                io::ErrorKind::InvalidInput
+            );
+            assert!(router.clear_memory(category, false).is_err());
+            assert!(router
+                .remove_specific_memory_internal(category, "data", false)
+                .is_err());
+        }
+
+        // Nothing may have been written anywhere under the temp dir
+        assert!(!memory_base.exists());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_category_is_trimmed() {
+        let temp_dir = tempdir().unwrap();
+        let memory_base = temp_dir.path().join("memory");
+
+        let router = MemoryServer {
+            tool_router: ToolRouter::new(),
+            instructions: String::new(),
+            global_memory_dir: memory_base.join("global"),
+            local_memory_dir: memory_base.join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
+        };
+
+        router
+            .remember("context", "  development ", "data", &[], false)
+            .unwrap();
+        assert!(router.local_memory_dir.join("development.txt").exists());
+        assert!(!router.retrieve("development", false).unwrap().is_empty());
+    }
+
+    fn router_with_memories(temp_dir: &tempfile::TempDir) -> MemoryServer {
+        let memory_base = temp_dir.path().join("memory");
+        let router = MemoryServer {
+            tool_router: ToolRouter::new(),
+            instructions: String::new(),
+            global_memory_dir: memory_base.join("global"),
+            local_memory_dir: memory_base.join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
+        };
+        router
+            .remember(
+                "context",
+                "development",
+                "We use black for code formatting",
+                &["formatting", "tools"],
+                false,
+            )
+            .unwrap();
+        router
+            .remember(
+                "context",
+                "development",
+                "Run the integration tests with just test-all",
+                &[],
+                false,
+            )
+            .unwrap();
+        router
+            .remember(
+                "context",
+                "github",
+                "gh pr view --comments shows review comments",
+                &["comments"],
+                true,
+            )
+            .unwrap();
+        router
+    }
+
+    #[test]
+    fn test_memories_are_exposed_as_resources() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+
+        let resources = router.memory_resources().unwrap();
+        let uris: Vec<&str> = resources.iter().map(|r| r.raw.uri.as_str()).collect();
+        assert_eq!(
+            uris,
+            vec!["memory://global/github", "memory://local/development"]
+        );
+        assert_eq!(resources[1].raw.name, "development (local)");
+        assert_eq!(
+            resources[1].raw.description.as_deref(),
+            Some("2 local memories")
+        );
+
+        let ResourceContents::TextResourceContents {
+            uri,
+            mime_type,
+            text,
+            ..
+        } = router
+            .read_memory_resource("memory://local/development")
+            .unwrap()
+        else {
+            panic!("expected text contents");
+        };
+        assert_eq!(uri, "memory://local/development");
+        assert_eq!(mime_type.as_deref(), Some("text/markdown"));
+        let saved = format!("Saved: {}", Utc::now().format("%Y-%m-%d"));
+        assert_eq!(
+            text.lines()
+                .filter(|line| !line.starts_with(&saved))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            "# development\n\nTags: `formatting` `tools`\n\n\nWe use black for code formatting\n\n\nRun the integration tests with just test-all"
+        );
+        assert_eq!(text.matches("Saved: ").count(), 2);
+
+        let ResourceContents::TextResourceContents { text, .. } = router
+            .read_memory_resource("memory://global/github")
+            .unwrap()
+        else {
+            panic!("expected text contents");
+        };
+        assert!(text.contains("gh pr view --comments shows review comments"));
+
+        for uri in [
+            "memory://local/github",
+            "memory://global/missing",
+            "memory://other/development",
+            "memory://local/../global/github",
+            "file:///etc/passwd",
+        ] {
+            let err = router.read_memory_resource(uri).unwrap_err();
+            assert_eq!(err.code, ErrorCode::INVALID_REQUEST, "{}", uri);
+        }
+
+        router.clear_memory("github", true).unwrap();
+        let resources = router.memory_resources().unwrap();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].raw.uri, "memory://local/development");
+    }
+
+    #[test]
+    fn test_exact_search_is_the_default() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+
+        let matches = router
+            .search("BLACK", None, &[true, false], false, 5)
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].0.content, "We use black for code formatting");
+        assert_eq!(matches[0].0.tags, vec!["formatting", "tools"]);
+
+        // Tags match too
+        let matches = router.search("comments", None, &[true], false, 5).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].0.is_global);
+
+        // A typo finds nothing without fuzzy search
+        assert!(router
+            .search("blak formating", None, &[true, false], false, 5)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_search_ranks_near_matches() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+
+        let matches = router
+            .search("blak formating", None, &[true, false], true, 5)
+            .unwrap();
+        assert!(!matches.is_empty());
+        assert_eq!(matches[0].0.content, "We use black for code formatting");
+        assert!(matches.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        // Subsequences such as abbreviations match as well
+        let matches = router
+            .search("intgr tests", Some("development"), &[false], true, 5)
+            .unwrap();
+        assert_eq!(
+            matches[0].0.content,
+            "Run the integration tests with just test-all"
+        );
+
+        let matches = router
+            .search("test", None, &[true, false], true, 1)
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+
+        assert!(router
+            .search("kubernetes", None, &[true, false], true, 5)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_similarity_bounds() {
+        assert_eq!(similarity("black", "We use black"), 1.0);
+        assert_eq!(similarity("", "anything"), 0.0);
+        assert!(similarity("blak", "black") > 0.7);
+        assert!(similarity("zebra", "We use black") < MIN_FUZZY_SCORE);
+    }
+
+    #[tokio::test]
+    async fn test_search_memories_tool_reports_scores() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+
+        let result = router
+            .search_memories(Parameters(SearchMemoriesParams {
+                query: "blak formating".to_string(),
+                category: None,
+                is_global: None,
+                fuzzy: true,
+                limit: None,
+            }))
+            .await
+            .unwrap();
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.starts_with("Found "));
+        assert!(text.contains("(local) development #formatting #tools: We use black"));
+        assert!(text.contains("- [0."));
+
+        let result = router
+            .search_memories(Parameters(SearchMemoriesParams {
+                query: "blak formating".to_string(),
+                category: None,
+                is_global: None,
+                fuzzy: false,
+                limit: None,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.content[0].as_text().unwrap().text,
+            "No memories found matching 'blak formating'"
+        );
+    }
+
+    #[test]
+    fn test_memory_stats() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+
+        let local = router.stats(false).unwrap();
+        assert_eq!(local.entries, 2);
+        assert_eq!(local.categories.len(), 1);
+        let (category, entries, bytes) = &local.categories[0];
+        assert_eq!((category.as_str(), *entries), ("development", 2));
+        let file = router.get_memory_file("development", false).unwrap();
+        assert_eq!(*bytes, fs::metadata(file).unwrap().len());
+        assert_eq!(local.bytes, *bytes);
+
+        let global = router.stats(true).unwrap();
+        assert_eq!(global.entries, 1);
+        assert_eq!(global.categories[0].0, "github");
+
+        let empty_dir = tempdir().unwrap();
+        let empty = MemoryServer {
+            tool_router: ToolRouter::new(),
+            instructions: String::new(),
+            global_memory_dir: empty_dir.path().join("global"),
+            local_memory_dir: empty_dir.path().join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
+        };
+        assert_eq!(
+            empty.stats(true).unwrap(),
+            MemoryStats {
+                is_global: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_stats_tool() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+
+        let result = router
+            .memory_stats(Parameters(MemoryStatsParams { is_global: None }))
+            .await
+            .unwrap();
+        let text = result.content[0].as_text().unwrap().text.clone();
+        assert!(text.starts_with("3 memories in 2 categories"));
+        assert!(text.contains("\nGlobal: 1 memories"));
+        assert!(text.contains("- development: 2 memories"));
+        // Only counts, never contents
+        assert!(!text.contains("black"));
+    }
+
+    #[test]
+    fn test_list_tags() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+        router
+            .remember(
+                "context",
+                "github",
+                "Use gh run watch to follow CI",
+                &["tools"],
+                false,
+            )
+            .unwrap();
+
+        let tags = router.tags(&[true, false]).unwrap();
+        assert_eq!(
+            tags,
+            vec![
+                TagUsage {
+                    tag: "tools".to_string(),
+                    count: 2,
+                    categories: vec![
+                        "(local) development".to_string(),
+                        "(local) github".to_string()
+                    ],
+                },
+                TagUsage {
+                    tag: "comments".to_string(),
+                    count: 1,
+                    categories: vec!["(global) github".to_string()],
+                },
+                TagUsage {
+                    tag: "formatting".to_string(),
+                    count: 1,
+                    categories: vec!["(local) development".to_string()],
+                },
+            ]
+        );
+
+        let global = router.tags(&[true]).unwrap();
+        assert_eq!(global.len(), 1);
+        assert_eq!(global[0].tag, "comments");
+    }
+
+    #[test]
+    fn test_expired_memories_are_forgotten() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+        let now = Utc::now();
+        router
+            .remember_until(
+                "context",
+                "development",
+                "This sprint we freeze the public API",
+                &["sprint"],
+                false,
+                Some(now - Duration::days(1)),
+            )
+            .unwrap();
+        let next_week = (now + Duration::days(7))
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+            .parse::<DateTime<Utc>>()
+            .unwrap();
+        router
+            .remember_until(
+                "context",
+                "development",
+                "Reviews go to the release captain",
+                &[],
+                false,
+                Some(next_week),
+            )
+            .unwrap();
+
+        let retrieved = router.retrieve("development", false).unwrap();
+        assert_eq!(retrieved.len(), 3);
+        assert_eq!(retrieved[0].content, "Reviews go to the release captain");
+        assert!(retrieved
+            .iter()
+            .all(|entry| !entry.content.contains("freeze") && entry.tags != ["sprint"]));
+
+        // The expired memory was deleted from the file on the way
+        let file = router.get_memory_file("development", false).unwrap();
+        let content = fs::read_to_string(file).unwrap();
+        assert!(!content.contains("freeze"));
+        assert!(content.contains("expires:"));
+
+        let entries = router.entries(Some("development"), false).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].expires_at, Some(next_week));
+        assert!(entries[2].tags.is_empty());
+        assert!(router
+            .tags(&[false])
+            .unwrap()
+            .iter()
+            .all(|usage| usage.tag != "sprint"));
+
+        // Expiry survives an export and import
+        let target_dir = tempdir().unwrap();
+        let target = MemoryServer {
+            tool_router: ToolRouter::new(),
+            instructions: String::new(),
+            global_memory_dir: target_dir.path().join("global"),
+            local_memory_dir: target_dir.path().join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
+        };
+        target
+            .import(&router.export(&[false]).unwrap(), ImportMode::Merge)
+            .unwrap();
+        assert_eq!(target.entries(Some("development"), false).unwrap(), entries);
+    }
+
+    #[test]
+    fn test_expired_memories_are_left_out_of_instructions() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+        router
+            .remember_until(
+                "context",
+                "development",
+                "Deploys are frozen until Friday",
+                &[],
+                false,
+                Some(Utc::now() - Duration::hours(1)),
+            )
+            .unwrap();
+
+        let instructions = router.memory_instructions(DEFAULT_MAX_INSTRUCTION_BYTES);
+        assert!(instructions.contains("We use black for code formatting"));
+        assert!(!instructions.contains("Deploys are frozen"));
+    }
+
+    #[test]
+    fn test_saved_time_round_trips() {
+        let temp_dir = tempdir().unwrap();
+        let before = Utc::now() - Duration::seconds(1);
+        let router = router_with_memories(&temp_dir);
+
+        let entries = router.entries(Some("development"), false).unwrap();
+        let saved_at = entries[0].saved_at.unwrap();
+        assert!(saved_at >= before && saved_at <= Utc::now());
+        assert_eq!(entries[0].tags, vec!["formatting", "tools"]);
+        let file = router.get_memory_file("development", false).unwrap();
+        assert!(fs::read_to_string(file).unwrap().contains(" saved:"));
+
+        let target_dir = tempdir().unwrap();
+        let target = MemoryServer {
+            tool_router: ToolRouter::new(),
+            instructions: String::new(),
+            global_memory_dir: target_dir.path().join("global"),
+            local_memory_dir: target_dir.path().join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
+        };
+        let json = serde_json::to_string(&router.export(&[false]).unwrap()).unwrap();
+        target
+            .import(&serde_json::from_str(&json).unwrap(), ImportMode::Merge)
+            .unwrap();
+        assert_eq!(target.entries(Some("development"), false).unwrap(), entries);
+    }
+
+    #[tokio::test]
+    async fn test_retrieval_is_newest_first() {
+        let temp_dir = tempdir().unwrap();
+        let memory_base = temp_dir.path().join("memory");
+        let router = MemoryServer {
+            tool_router: ToolRouter::new(),
+            instructions: String::new(),
+            global_memory_dir: memory_base.join("global"),
+            local_memory_dir: memory_base.join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
+        };
+        // Stored before saved times were recorded
+        fs::create_dir_all(&router.local_memory_dir).unwrap();
+        fs::write(
+            router.local_memory_dir.join("notes.txt"),
+            "# legacy\nThe old build used make\n\n",
+        )
+        .unwrap();
+        for (content, saved_at) in [
+            ("Standup moved to 10am", "2024-11-02T09:30:00Z"),
+            ("Releases are cut on Thursdays", "2025-03-14T16:00:00Z"),
+            ("The wiki lives on Confluence", "2023-01-20T08:00:00Z"),
+        ] {
+            router
+                .append(&MemoryEntry {
+                    category: "notes".to_string(),
+                    tags: vec![],
+                    content: content.to_string(),
+                    is_global: false,
+                    expires_at: None,
+                    saved_at: Some(saved_at.parse().unwrap()),
+                })
+                .unwrap();
+        }
+
+        let retrieved = router.retrieve("notes", false).unwrap();
+        let contents: Vec<&str> = retrieved.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(
+            contents,
+            vec![
+                "Releases are cut on Thursdays",
+                "Standup moved to 10am",
+                "The wiki lives on Confluence",
+                "The old build used make",
+            ]
+        );
+        assert_eq!(router.retrieve_all(false).unwrap(), retrieved);
+
+        let result = router
+            .retrieve_memories(Parameters(RetrieveMemoriesParams {
+                category: "notes".to_string(),
+                is_global: false,
+                limit: None,
+                offset: 0,
+            }))
+            .await
+            .unwrap();
+        let text = &result.content[0].as_text().unwrap().text;
+        assert!(text.contains("- (saved 2024-11-02) notes: Standup moved to 10am\n"));
+        assert!(text.contains("- (unknown date) notes #legacy: The old build used make\n"));
+
+        let instructions = router.memory_instructions(DEFAULT_MAX_INSTRUCTION_BYTES);
+        assert!(instructions.contains("- Standup moved to 10am (saved 2024-11-02)\n"));
+        assert!(instructions.contains("- The old build used make (unknown date)\n"));
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_memories_in_pages() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+        for i in 0..4 {
+            router
+                .remember("context", "notes", &format!("Note {}", i), &[], false)
+                .unwrap();
+        }
+        let page = |limit, offset| {
+            let router = router.clone();
+            async move {
+                router
+                    .retrieve_memories(Parameters(RetrieveMemoriesParams {
+                        category: "*".to_string(),
+                        is_global: false,
+                        limit: Some(limit),
+                        offset,
+                    }))
+                    .await
+                    .map(|result| result.content[0].as_text().unwrap().text.clone())
+            }
+        };
+
+        let first = page(4, 0).await.unwrap();
+        assert!(first.starts_with("Retrieved memories 1-4 of 6, newest first:\n"));
+        assert!(first.contains("retrieve again with offset 4 for the next page"));
+        let second = page(4, 4).await.unwrap();
+        assert!(second.starts_with("Retrieved memories 5-6 of 6, newest first:\n"));
+        assert!(!second.contains("next page"));
+
+        // Every memory shows up exactly once across the pages
+        let all = page(10, 0).await.unwrap();
+        let lines = |text: &str| -> Vec<String> {
+            text.lines()
+                .filter(|line| line.starts_with("- "))
+                .map(str::to_string)
+                .collect()
+        };
+        let mut paged = lines(&first);
+        paged.extend(lines(&second));
+        assert_eq!(paged, lines(&all));
+        assert_eq!(paged.len(), 6);
+
+        let past_end = page(4, 6).await.unwrap();
+        assert_eq!(past_end, "No memories at offset 6; there are 6 in total");
+        assert_eq!(
+            page(0, 0).await.unwrap_err().code,
+            ErrorCode::INVALID_PARAMS
+        );
+    }
+
+    #[test]
+    fn test_encrypted_memories_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let plaintext = router_with_memories(&temp_dir);
+        let encrypted = MemoryServer {
+            storage: Storage::Encrypted(MemoryCipher::from_master_key(&[3u8; 32])),
+            peer: Arc::default(),
+            ..plaintext.clone()
+        };
+
+        // Files written before encryption was turned on are migrated
+        assert_eq!(encrypted.encrypt_plaintext_files().unwrap(), 2);
+        assert_eq!(encrypted.encrypt_plaintext_files().unwrap(), 0);
+        let file = encrypted.get_memory_file("development", false).unwrap();
+        assert!(!fs::read_to_string(&file).unwrap().contains("black"));
+
+        encrypted
+            .remember(
+                "context",
+                "development",
+                "The staging password is hunter2",
+                &["secrets"],
+                false,
+            )
+            .unwrap();
+        assert!(!fs::read_to_string(&file).unwrap().contains("hunter2"));
+
+        let retrieved = encrypted.retrieve("development", false).unwrap();
+        let secret = retrieved
+            .iter()
+            .find(|entry| entry.tags == ["secrets"])
+            .unwrap();
+        assert_eq!(secret.content, "The staging password is hunter2");
+        assert_eq!(
+            encrypted.entries(Some("development"), false).unwrap().len(),
+            3
+        );
+        encrypted
+            .remove_specific_memory_internal("development", "hunter2", false)
+            .unwrap();
+        assert_eq!(
+            encrypted.entries(Some("development"), false).unwrap().len(),
+            2
+        );
+
+        // Without the key, encrypted files are refused rather than misread
+        let err = plaintext.retrieve("development", false).unwrap_err();
+        assert!(err.to_string().contains(ENCRYPTION_CONFIG_KEY));
+        let unavailable = MemoryServer {
+            storage: Storage::Unavailable("no keychain".to_string()),
+            peer: Arc::default(),
+            ..plaintext.clone()
+        };
+        assert!(unavailable
+            .remember("context", "new", "not stored in plaintext", &[], false)
+            .is_err());
+        assert!(!unavailable.get_memory_file("new", false).unwrap().exists());
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let source_dir = tempdir().unwrap();
+        let source = router_with_memories(&source_dir);
+        let export = source.export(&[true, false]).unwrap();
+        assert_eq!(export.version, MEMORY_EXPORT_VERSION);
+        assert_eq!(export.memories.len(), 3);
+
+        let json = serde_json::to_string(&export).unwrap();
+        let parsed: MemoryExport = serde_json::from_str(&json).unwrap();
+
+        let target_dir = tempdir().unwrap();
+        let memory_base = target_dir.path().join("memory");
+        let target = MemoryServer {
+            tool_router: ToolRouter::new(),
+            instructions: String::new(),
+            global_memory_dir: memory_base.join("global"),
+            local_memory_dir: memory_base.join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
+        };
+        let report = target.import(&parsed, ImportMode::Merge).unwrap();
+        assert_eq!(report.imported, 3);
+        assert_eq!(target.export(&[true, false]).unwrap(), export);
+
+        // Importing again finds everything already stored
+        let report = target.import(&parsed, ImportMode::Merge).unwrap();
+        assert_eq!(report.imported, 0);
+        assert_eq!(report.duplicates, 3);
+    }
+
+    #[test]
+    fn test_import_merge_reports_conflicts() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+        let export = MemoryExport {
+            version: MEMORY_EXPORT_VERSION,
+            memories: vec![
+                MemoryEntry {
+                    category: "development".to_string(),
+                    tags: vec!["style".to_string()],
+                    content: "We use black for code formatting".to_string(),
+                    is_global: false,
+                    expires_at: None,
+                    saved_at: None,
+                },
+                MemoryEntry {
+                    category: "development".to_string(),
+                    tags: vec![],
+                    content: "Use ruff for linting".to_string(),
+                    is_global: false,
+                    expires_at: None,
+                    saved_at: None,
+                },
+            ],
+        };
+
+        let report = router.import(&export, ImportMode::Merge).unwrap();
+        assert_eq!(report.imported, 1);
+        assert_eq!(report.conflicts.len(), 1);
+        assert!(report.conflicts[0].contains("[formatting, tools], import has [style]"));
+
+        // The existing memory keeps its tags
+        let entries = router.entries(Some("development"), false).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].tags, vec!["formatting", "tools"]);
+    }
+
+    #[test]
+    fn test_import_replace_clears_imported_categories() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+        let export = MemoryExport {
+            version: MEMORY_EXPORT_VERSION,
+            memories: vec![MemoryEntry {
+                category: "development".to_string(),
+                tags: vec!["style".to_string()],
+                content: "We use black for code formatting".to_string(),
+                is_global: false,
+                expires_at: None,
+                saved_at: None,
+            }],
+        };
+
+        let report = router.import(&export, ImportMode::Replace).unwrap();
+        assert_eq!(report.imported, 1);
+        assert_eq!(report.replaced, vec!["(local) development"]);
+        assert_eq!(
+            router.entries(Some("development"), false).unwrap(),
+            export.memories
+        );
+
+        // Categories missing from the document are left alone
+        assert_eq!(router.entries(Some("github"), true).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_documents() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+        let export = MemoryExport {
+            version: 2,
+            memories: vec![
+                MemoryEntry {
+                    category: "../escape".to_string(),
+                    tags: vec![],
+                    content: "first\n\nsecond".to_string(),
+                    is_global: false,
+                    expires_at: None,
+                    saved_at: None,
+                },
+                MemoryEntry {
+                    category: "development".to_string(),
+                    tags: vec!["two words".to_string()],
+                    content: "Valid content".to_string(),
+                    is_global: false,
+                    expires_at: None,
+                    saved_at: None,
+                },
+            ],
+        };
+
+        let err = router.import(&export, ImportMode::Merge).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let message = err.to_string();
+        assert!(message.contains("unsupported version 2"));
+        assert!(message.contains("memory 0: Invalid memory category"));
+        assert!(message.contains("memory 0: content must not contain blank lines"));
+        assert!(message.contains("memory 1: tags must be single words"));
+
+        // Nothing was written
+        assert_eq!(router.entries(Some("development"), false).unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_import_memories_tool_validates_schema() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+
+        let err = router
+            .import_memories(Parameters(ImportMemoriesParams {
+                document: Some(r#"{"version": 1, "entries": []}"#.to_string()),
+                path: None,
+                mode: ImportMode::Merge,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+        assert!(err.message.starts_with("Invalid memory export"));
+
+        let export_path = temp_dir.path().join("memories.json");
+        router
+            .export_memories(Parameters(ExportMemoriesParams {
+                is_global: Some(false),
+                path: Some(export_path.to_string_lossy().to_string()),
+            }))
+            .await
+            .unwrap();
+        let result = router
+            .import_memories(Parameters(ImportMemoriesParams {
+                document: None,
+                path: Some(export_path.to_string_lossy().to_string()),
+                mode: ImportMode::Merge,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.content[0].as_text().unwrap().text,
+            "Imported 0 memories (2 already stored)"
+        );
+    }
+
+    #[test]
+    fn test_memory_instructions_fit_under_cap() {
+        let temp_dir = tempdir().unwrap();
+        let router = router_with_memories(&temp_dir);
+
+        let saved = format!("(saved {})", Utc::now().format("%Y-%m-%d"));
+        let text = router
+            .memory_instructions(DEFAULT_MAX_INSTRUCTION_BYTES)
+            .replace(&saved, "(saved today)");
+        assert_eq!(
+            text,
+            "\n\nGlobal Memories:\n\nCategory: github\n- gh pr view --comments shows review comments (saved today)\n\
+             \n\nLocal Memories:\n\nCategory: development\n- We use black for code formatting (saved today)\n\
+             - Run the integration tests with just test-all (saved today)\n"
+        );
+        assert!(!text.contains("left out"));
+    }
+
+    #[test]
+    fn test_memory_instructions_keep_most_recent_when_truncated() {
+        let temp_dir = tempdir().unwrap();
+        let memory_base = temp_dir.path().join("memory");
+        let router = MemoryServer {
+            tool_router: ToolRouter::new(),
+            instructions: String::new(),
+            global_memory_dir: memory_base.join("global"),
+            local_memory_dir: memory_base.join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
+        };
+        for n in 1..=10 {
+            router
+                .remember("context", "notes", &format!("note {:02}", n), &[], false)
+                .unwrap();
+        }
+
+        // Each line is "- note NN (saved YYYY-MM-DD)\n", 29 bytes, so three fit
+        let text = router.memory_instructions(100);
+        assert!(text.contains("- note 08 (saved "));
+        assert!(text.contains("- note 10 (saved "));
+        assert!(!text.contains("note 07"));
+        assert!(text.contains("Note: 7 older memories were left out"));
+        assert!(text.contains("retrieve_memories"));
+
+        let text = router.memory_instructions(0);
+        assert!(!text.contains("Local Memories"));
+        assert!(text.contains("Note: 10 older memories were left out"));
+    }
+
+    #[tokio::test]
+    async fn test_tools_return_invalid_params_for_bad_category() {
+        let temp_dir = tempdir().unwrap();
+        let memory_base = temp_dir.path().join("memory");
+
+        let router = MemoryServer {
+            tool_router: ToolRouter::new(),
+            instructions: String::new(),
+            global_memory_dir: memory_base.join("global"),
+            local_memory_dir: memory_base.join("local"),
+            storage: Storage::Plaintext,
+            peer: Arc::default(),
+        };
+
+        let err = router
+            .remember_memory(Parameters(RememberMemoryParams {
+                category: "../../etc/passwd".to_string(),
+                data: "root::0:0".to_string(),
+                tags: vec![],
+                is_global: false,
+                expires_in_days: None,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+        let err = router
+            .retrieve_memories(Parameters(RetrieveMemoriesParams {
+                category: "../outside".to_string(),
+                is_global: true,
+                limit: None,
+                offset: 0,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+    }
 }
@@ -83,7 +83,8 @@ impl TutorialServer {
     /// The tutorial will be returned as markdown content that provides step by step instructions.
     #[tool(
         name = "load_tutorial",
-        description = "Load a specific tutorial by name. The tutorial will be returned as markdown content that provides step by step instructions."
+        description = "Load a specific tutorial by name. The tutorial will be returned as markdown content that provides step by step instructions.",
+        annotations(read_only_hint = true)
     )]
     pub async fn load_tutorial(
         &self,
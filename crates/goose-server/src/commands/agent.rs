@@ -10,10 +10,19 @@ use goose::providers::pricing::initialize_pricing_cache;
 
 pub async fn run() -> Result<()> {
     // Initialize logging and telemetry
-    crate::logging::setup_logging(Some("goosed"))?;
+    let telemetry = crate::logging::setup_logging(Some("goosed"))?;
 
     let settings = configuration::Settings::new()?;
 
+    match goose::config::Config::global().validate() {
+        Ok(problems) => {
+            for problem in problems {
+                tracing::warn!("Invalid configuration value {}", problem);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to validate configuration: {}", e),
+    }
+
     // Initialize pricing cache on startup
     tracing::info!("Initializing pricing cache...");
     if let Err(e) = initialize_pricing_cache().await {
@@ -42,6 +51,10 @@ pub async fn run() -> Result<()> {
 
     let listener = tokio::net::TcpListener::bind(settings.socket_addr()).await?;
     info!("listening on {}", listener.local_addr()?);
-    axum::serve(listener, app).await?;
-    Ok(())
+    let result = axum::serve(listener, app).await;
+
+    telemetry
+        .flush_with_timeout(crate::logging::TELEMETRY_FLUSH_TIMEOUT)
+        .await;
+    Ok(result?)
 }
@@ -1,12 +1,16 @@
 use anyhow::{Context, Result};
 use std::path::PathBuf;
+use std::time::Duration;
 use tracing_appender::rolling::Rotation;
 use tracing_subscriber::{
     filter::LevelFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer,
     Registry,
 };
 
-use goose::tracing::{langfuse_layer, otlp_layer};
+use goose::tracing::{file_layer, langfuse_layer, otlp_layer, TelemetryGuard};
+
+/// Upper bound on how long shutdown waits for pending spans, metrics and Langfuse batches
+pub const TELEMETRY_FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// Returns the directory where log files should be stored.
 /// Creates the directory structure if it doesn't exist.
@@ -19,7 +23,11 @@ fn get_log_directory() -> Result<PathBuf> {
 /// - File-based logging with JSON formatting (DEBUG level)
 /// - Console output for development (INFO level)
 /// - Optional Langfuse integration (DEBUG level)
-pub fn setup_logging(name: Option<&str>) -> Result<()> {
+/// - Optional JSON trace file when GOOSE_TRACE_FILE is set (DEBUG level)
+///
+/// The returned guard must be kept alive until exit and flushed before returning, so
+/// buffered telemetry is exported.
+pub fn setup_logging(name: Option<&str>) -> Result<TelemetryGuard> {
     // Set up file appender for goose module logs
     let log_dir = get_log_directory()?;
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
@@ -74,7 +82,10 @@ pub fn setup_logging(name: Option<&str>) -> Result<()> {
         console_layer.with_filter(LevelFilter::INFO).boxed(),
     ];
 
-    if let Ok((otlp_tracing_layer, otlp_metrics_layer)) = otlp_layer::init_otlp() {
+    let mut telemetry = TelemetryGuard::new();
+
+    if let Ok(((otlp_tracing_layer, otlp_metrics_layer), otlp_guard)) = otlp_layer::init_otlp() {
+        telemetry.merge(otlp_guard);
         layers.push(
             otlp_tracing_layer
                 .with_filter(otlp_layer::create_otlp_tracing_filter())
@@ -87,15 +98,25 @@ pub fn setup_logging(name: Option<&str>) -> Result<()> {
         );
     }
 
-    if let Some(langfuse) = langfuse_layer::create_langfuse_observer() {
+    if let Some((langfuse, langfuse_guard)) = langfuse_layer::create_langfuse_observer_with_guard()
+    {
+        telemetry.merge(langfuse_guard);
         layers.push(langfuse.with_filter(LevelFilter::DEBUG).boxed());
     }
 
+    if let Some(trace_file) = file_layer::create_file_trace_layer() {
+        layers.push(
+            trace_file
+                .with_filter(file_layer::create_file_trace_filter())
+                .boxed(),
+        );
+    }
+
     let subscriber = Registry::default().with(layers);
 
     subscriber
         .try_init()
         .context("Failed to set global subscriber")?;
 
-    Ok(())
+    Ok(telemetry)
 }
@@ -36,8 +36,12 @@ async fn main() -> anyhow::Result<()> {
             commands::agent::run().await?;
         }
         Commands::Mcp { name } => {
-            logging::setup_logging(Some(&format!("mcp-{name}")))?;
-            goose_mcp::mcp_server_runner::run_mcp_server(name).await?;
+            let telemetry = logging::setup_logging(Some(&format!("mcp-{name}")))?;
+            let result = goose_mcp::mcp_server_runner::run_mcp_server(name).await;
+            telemetry
+                .flush_with_timeout(logging::TELEMETRY_FLUSH_TIMEOUT)
+                .await;
+            result?;
         }
     }
 
@@ -15,9 +15,9 @@ use rmcp::model::{
 use utoipa::{OpenApi, ToSchema};
 
 use goose::conversation::message::{
-    ContextLengthExceeded, FrontendToolRequest, Message, MessageContent, MessageMetadata,
-    RedactedThinkingContent, SummarizationRequested, ThinkingContent, ToolConfirmationRequest,
-    ToolRequest, ToolResponse,
+    ContextLengthExceeded, FileAttachment, FrontendToolRequest, Message, MessageContent,
+    MessageMetadata, RedactedThinkingContent, SummarizationRequested, ThinkingContent,
+    ToolConfirmationRequest, ToolRequest, ToolResponse,
 };
 use utoipa::openapi::schema::{
     AdditionalProperties, AnyOfBuilder, ArrayBuilder, ObjectBuilder, OneOfBuilder, Schema,
@@ -429,6 +429,7 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         ResourceContentsSchema,
         ContextLengthExceeded,
         SummarizationRequested,
+        FileAttachment,
         RoleSchema,
         ProviderMetadata,
         ExtensionEntry,
@@ -487,6 +488,7 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         super::routes::agent::StartAgentRequest,
         super::routes::agent::ResumeAgentRequest,
         super::routes::agent::ErrorResponse,
+        super::routes::setup::SetupQuery,
         super::routes::setup::SetupResponse,
     ))
 )]
@@ -189,6 +189,9 @@ async fn add_extension(
             timeout,
             bundled: None,
             available_tools: Vec::new(),
+            depends_on: Vec::new(),
+            response_limits: None,
+            keep_alive_interval: None,
         },
         ExtensionConfigRequest::StreamableHttp {
             name,
@@ -207,6 +210,9 @@ async fn add_extension(
             timeout,
             bundled: None,
             available_tools: Vec::new(),
+            depends_on: Vec::new(),
+            response_limits: None,
+            keep_alive_interval: None,
         },
         ExtensionConfigRequest::Stdio {
             name,
@@ -238,6 +244,8 @@ async fn add_extension(
                 timeout,
                 bundled: None,
                 available_tools: Vec::new(),
+                depends_on: Vec::new(),
+                response_limits: None,
             }
         }
         ExtensionConfigRequest::Builtin {
@@ -251,6 +259,8 @@ async fn add_extension(
             bundled: None,
             description: None,
             available_tools: Vec::new(),
+            depends_on: Vec::new(),
+            response_limits: None,
         },
         ExtensionConfigRequest::Frontend {
             name,
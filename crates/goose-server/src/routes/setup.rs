@@ -1,9 +1,10 @@
 use crate::state::AppState;
-use axum::{http::StatusCode, routing::post, Json, Router};
+use axum::{extract::Query, http::StatusCode, routing::post, Json, Router};
 use goose::config::signup_openrouter::OpenRouterAuth;
-use goose::config::signup_tetrate::{configure_tetrate, TetrateAuth};
-use goose::config::{configure_openrouter, Config};
-use serde::Serialize;
+use goose::config::signup_tetrate::{configure_tetrate, TetrateAuth, TETRATE_DEFAULT_MODEL};
+use goose::config::{configure_openrouter, validate_signup_model, Config};
+use goose::providers::openrouter::OPENROUTER_DEFAULT_MODEL;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utoipa::ToSchema;
 
@@ -13,6 +14,14 @@ pub struct SetupResponse {
     pub message: String,
 }
 
+// Query parameters for the setup endpoints
+#[derive(Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct SetupQuery {
+    /// Model to use instead of the provider's default, checked against the models it offers
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
 pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/handle_openrouter", post(start_openrouter_setup))
@@ -23,11 +32,14 @@ pub fn routes(state: Arc<AppState>) -> Router {
 #[utoipa::path(
     post,
     path = "/handle_openrouter",
+    params(SetupQuery),
     responses(
         (status = 200, body=SetupResponse)
     ),
 )]
-async fn start_openrouter_setup() -> Result<Json<SetupResponse>, StatusCode> {
+async fn start_openrouter_setup(
+    Query(SetupQuery { model }): Query<SetupQuery>,
+) -> Result<Json<SetupResponse>, StatusCode> {
     tracing::info!("Starting OpenRouter setup flow");
 
     let mut auth_flow = OpenRouterAuth::new().map_err(|e| {
@@ -37,13 +49,16 @@ async fn start_openrouter_setup() -> Result<Json<SetupResponse>, StatusCode> {
 
     tracing::info!("Auth flow initialized, starting complete_flow");
 
-    match auth_flow.complete_flow().await {
+    match auth_flow
+        .complete_flow_with_status(|status| tracing::info!("{}", status))
+        .await
+    {
         Ok(api_key) => {
             tracing::info!("Got API key, configuring OpenRouter...");
 
             let config = Config::global();
 
-            if let Err(e) = configure_openrouter(config, api_key) {
+            if let Err(e) = configure_openrouter(config, api_key.clone(), model.as_deref()) {
                 tracing::error!("Failed to configure OpenRouter: {}", e);
                 return Ok(Json(SetupResponse {
                     success: false,
@@ -51,10 +66,27 @@ async fn start_openrouter_setup() -> Result<Json<SetupResponse>, StatusCode> {
                 }));
             }
 
+            let mut message = "OpenRouter setup completed successfully".to_string();
+            if let Some(model) = &model {
+                if let Err(e) = validate_signup_model("openrouter", model).await {
+                    tracing::warn!("{}, using {} instead", e, OPENROUTER_DEFAULT_MODEL);
+                    if let Err(e) = configure_openrouter(config, api_key, None) {
+                        return Ok(Json(SetupResponse {
+                            success: false,
+                            message: format!("Failed to configure OpenRouter: {}", e),
+                        }));
+                    }
+                    message = format!(
+                        "{}, but {}; using {} instead",
+                        message, e, OPENROUTER_DEFAULT_MODEL
+                    );
+                }
+            }
+
             tracing::info!("OpenRouter setup completed successfully");
             Ok(Json(SetupResponse {
                 success: true,
-                message: "OpenRouter setup completed successfully".to_string(),
+                message,
             }))
         }
         Err(e) => {
@@ -70,11 +102,14 @@ async fn start_openrouter_setup() -> Result<Json<SetupResponse>, StatusCode> {
 #[utoipa::path(
     post,
     path = "/handle_tetrate",
+    params(SetupQuery),
     responses(
         (status = 200, body=SetupResponse)
     ),
 )]
-async fn start_tetrate_setup() -> Result<Json<SetupResponse>, StatusCode> {
+async fn start_tetrate_setup(
+    Query(SetupQuery { model }): Query<SetupQuery>,
+) -> Result<Json<SetupResponse>, StatusCode> {
     tracing::info!("Starting Tetrate Agent Router Service setup flow");
 
     let mut auth_flow = TetrateAuth::new().map_err(|e| {
@@ -84,13 +119,16 @@ async fn start_tetrate_setup() -> Result<Json<SetupResponse>, StatusCode> {
 
     tracing::info!("Auth flow initialized, starting complete_flow");
 
-    match auth_flow.complete_flow().await {
+    match auth_flow
+        .complete_flow_with_status(|status| tracing::info!("{}", status))
+        .await
+    {
         Ok(api_key) => {
             tracing::info!("Got API key, configuring Tetrate Agent Router Service...");
 
             let config = Config::global();
 
-            if let Err(e) = configure_tetrate(config, api_key) {
+            if let Err(e) = configure_tetrate(config, api_key.clone(), model.as_deref()) {
                 tracing::error!("Failed to configure Tetrate Agent Router Service: {}", e);
                 return Ok(Json(SetupResponse {
                     success: false,
@@ -98,10 +136,31 @@ async fn start_tetrate_setup() -> Result<Json<SetupResponse>, StatusCode> {
                 }));
             }
 
+            let mut message =
+                "Tetrate Agent Router Service setup completed successfully".to_string();
+            if let Some(model) = &model {
+                if let Err(e) = validate_signup_model("tetrate", model).await {
+                    tracing::warn!("{}, using {} instead", e, TETRATE_DEFAULT_MODEL);
+                    if let Err(e) = configure_tetrate(config, api_key, None) {
+                        return Ok(Json(SetupResponse {
+                            success: false,
+                            message: format!(
+                                "Failed to configure Tetrate Agent Router Service: {}",
+                                e
+                            ),
+                        }));
+                    }
+                    message = format!(
+                        "{}, but {}; using {} instead",
+                        message, e, TETRATE_DEFAULT_MODEL
+                    );
+                }
+            }
+
             tracing::info!("Tetrate Agent Router Service setup completed successfully");
             Ok(Json(SetupResponse {
                 success: true,
-                message: "Tetrate Agent Router Service setup completed successfully".to_string(),
+                message,
             }))
         }
         Err(e) => {
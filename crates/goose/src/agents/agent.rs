@@ -2,14 +2,18 @@ use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::{anyhow, Result};
 use futures::stream::BoxStream;
 use futures::{stream, FutureExt, Stream, StreamExt, TryStreamExt};
 use uuid::Uuid;
 
+use crate::agents::audit_log::{AuditDecision, AuditEvent, AuditLogger};
 use crate::agents::extension::{ExtensionConfig, ExtensionError, ExtensionResult, ToolInfo};
-use crate::agents::extension_manager::{get_parameter_names, ExtensionManager};
+use crate::agents::extension_manager::{
+    get_parameter_names, plan_startup, ExtensionManager, DEFAULT_SHUTDOWN_TIMEOUT,
+};
 use crate::agents::final_output_tool::{FINAL_OUTPUT_CONTINUATION_MESSAGE, FINAL_OUTPUT_TOOL_NAME};
 use crate::agents::platform_tools::{
     PLATFORM_LIST_RESOURCES_TOOL_NAME, PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME,
@@ -31,9 +35,11 @@ use crate::agents::tool_route_manager::ToolRouteManager;
 use crate::agents::tool_router_index_manager::ToolRouterIndexManager;
 use crate::agents::types::SessionConfig;
 use crate::agents::types::{FrontendTool, ToolResultReceiver};
+use crate::config::extensions::name_to_key;
 use crate::config::{Config, ExtensionConfigManager};
 use crate::context_mgmt::auto_compact;
 use crate::conversation::{debug_conversation_fix, fix_conversation, Conversation};
+use crate::execution::manager::{SessionCancellation, SubTaskResult, SubTaskResults};
 use crate::permission::permission_inspector::PermissionInspector;
 use crate::permission::permission_judge::PermissionCheckResult;
 use crate::permission::PermissionConfirmation;
@@ -42,8 +48,9 @@ use crate::providers::errors::ProviderError;
 use crate::recipe::{Author, Recipe, Response, Settings, SubRecipe};
 use crate::scheduler_trait::SchedulerTrait;
 use crate::security::security_inspector::SecurityInspector;
-use crate::tool_inspection::ToolInspectionManager;
+use crate::tool_inspection::{InspectionResult, ToolInspectionManager};
 use crate::tool_monitor::RepetitionInspector;
+use crate::tracing::{record_usage, UsageEvent};
 use crate::utils::is_token_cancelled;
 use mcp_core::ToolResult;
 use regex::Regex;
@@ -58,7 +65,10 @@ use tracing::{debug, error, info, instrument, warn};
 use super::final_output_tool::FinalOutputTool;
 use super::model_selector::autopilot::AutoPilot;
 use super::platform_tools;
-use super::tool_execution::{ToolCallResult, CHAT_MODE_TOOL_SKIPPED_RESPONSE, DECLINED_RESPONSE};
+use super::tool_execution::{
+    order_tool_responses, ToolCallLimiter, ToolCallResult, CHAT_MODE_TOOL_SKIPPED_RESPONSE,
+    DECLINED_RESPONSE, DEFAULT_MAX_PARALLEL_TOOL_CALLS,
+};
 use crate::agents::subagent_task_config::TaskConfig;
 use crate::agents::todo_tools::{
     todo_read_tool, todo_write_tool, TODO_READ_TOOL_NAME, TODO_WRITE_TOOL_NAME,
@@ -69,6 +79,11 @@ use crate::session::{extension_data, SessionManager};
 
 const DEFAULT_MAX_TURNS: u32 = 1000;
 
+/// Sent as the final assistant message when a reply stops because it hit the max turns limit
+pub const MAX_TURNS_REACHED_MESSAGE: &str = "I've reached the maximum number of actions I can do without user input. Would you like me to continue?";
+/// Starts the final assistant message when a reply stops on a provider error
+pub const PROVIDER_ERROR_PREFIX: &str = "Ran into this error: ";
+
 /// Context needed for the reply function
 pub struct ReplyContext {
     pub conversation: Conversation,
@@ -106,6 +121,8 @@ pub struct Agent {
     pub(super) retry_manager: RetryManager,
     pub(super) tool_inspection_manager: ToolInspectionManager,
     pub(super) autopilot: Mutex<AutoPilot>,
+    pub(super) audit_logger: Option<Arc<AuditLogger>>,
+    pub(super) session_cancellation: Mutex<Option<SessionCancellation>>,
 }
 
 #[derive(Clone, Debug)]
@@ -180,6 +197,8 @@ impl Agent {
             retry_manager: RetryManager::new(),
             tool_inspection_manager: Self::create_default_tool_inspection_manager(),
             autopilot: Mutex::new(AutoPilot::new()),
+            audit_logger: AuditLogger::from_config().map(Arc::new),
+            session_cancellation: Mutex::new(None),
         }
     }
 
@@ -245,7 +264,13 @@ impl Agent {
         session: &Option<SessionConfig>,
     ) -> Result<ReplyContext> {
         let unfixed_messages = unfixed_conversation.messages().clone();
-        let (conversation, issues) = fix_conversation(unfixed_conversation.clone());
+        let supports_vision = self
+            .provider()
+            .await
+            .map(|provider| provider.supports_vision())
+            .unwrap_or(true);
+        let (conversation, issues) =
+            fix_conversation(unfixed_conversation.clone(), supports_vision);
         if !issues.is_empty() {
             debug!(
                 "Conversation issue fixed: {}",
@@ -298,7 +323,7 @@ impl Agent {
         &self,
         permission_check_result: &PermissionCheckResult,
         message_tool_response: Arc<Mutex<Message>>,
-        cancel_token: Option<tokio_util::sync::CancellationToken>,
+        limiter: &ToolCallLimiter,
         session: &Option<SessionConfig>,
     ) -> Result<Vec<(String, ToolStream)>> {
         let mut tool_futures: Vec<(String, ToolStream)> = Vec::new();
@@ -306,29 +331,17 @@ impl Agent {
         // Handle pre-approved and read-only tools
         for request in &permission_check_result.approved {
             if let Ok(tool_call) = request.tool_call.clone() {
+                let tool_name = tool_call.name.clone();
                 let (req_id, tool_result) = self
                     .dispatch_tool_call(
                         tool_call,
                         request.id.clone(),
-                        cancel_token.clone(),
+                        limiter.call_token(),
                         session,
                     )
                     .await;
 
-                tool_futures.push((
-                    req_id,
-                    match tool_result {
-                        Ok(result) => tool_stream(
-                            result
-                                .notification_stream
-                                .unwrap_or_else(|| Box::new(stream::empty())),
-                            result.result,
-                        ),
-                        Err(e) => {
-                            tool_stream(Box::new(stream::empty()), futures::future::ready(Err(e)))
-                        }
-                    },
-                ));
+                tool_futures.push((req_id, limiter.stream(&tool_name, tool_result)));
             }
         }
 
@@ -344,12 +357,59 @@ impl Agent {
         Ok(tool_futures)
     }
 
+    /// Record how each tool request was routed, along with any security findings, in the
+    /// session's audit log
+    fn audit_permission_decisions(
+        &self,
+        session: &Option<SessionConfig>,
+        permission_check_result: &PermissionCheckResult,
+        inspection_results: &[InspectionResult],
+    ) {
+        let (Some(audit_logger), Some(session_config)) = (&self.audit_logger, session) else {
+            return;
+        };
+
+        let routed = [
+            (&permission_check_result.approved, AuditDecision::Approved),
+            (
+                &permission_check_result.needs_approval,
+                AuditDecision::NeedsApproval,
+            ),
+            (&permission_check_result.denied, AuditDecision::Denied),
+        ];
+        for (requests, decision) in routed {
+            for request in requests {
+                if let Ok(tool_call) = &request.tool_call {
+                    audit_logger.record_inspection(
+                        &session_config.id,
+                        &tool_call.name,
+                        &request.id,
+                        decision,
+                        inspection_results,
+                    );
+                }
+            }
+        }
+    }
+
     /// Set the scheduler service for this agent
     pub async fn set_scheduler(&self, scheduler: Arc<dyn SchedulerTrait>) {
         let mut scheduler_service = self.scheduler_service.lock().await;
         *scheduler_service = Some(scheduler);
     }
 
+    /// Take the results published so far by subtasks that `session_id` spawned, in the order
+    /// they finished
+    pub async fn take_subtask_results(&self, session_id: &str) -> Vec<SubTaskResult> {
+        SubTaskResults::global().poll(session_id).await
+    }
+
+    /// Stop replies when the session is cancelled through the session registry, and record
+    /// there when they have stopped
+    pub async fn set_session_cancellation(&self, cancellation: SessionCancellation) {
+        *self.session_cancellation.lock().await = Some(cancellation);
+    }
+
     pub async fn disable_router_for_recipe(&self) {
         self.tool_route_manager.disable_router_for_recipe().await;
     }
@@ -393,6 +453,71 @@ impl Agent {
         request_id: String,
         cancellation_token: Option<CancellationToken>,
         session: &Option<SessionConfig>,
+    ) -> (String, Result<ToolCallResult, ErrorData>) {
+        self.dispatch_tool_call_with_audit(
+            tool_call,
+            request_id,
+            cancellation_token,
+            session,
+            session,
+        )
+        .await
+    }
+
+    /// Dispatch a tool call with `session` as its context, recording it in the audit log of
+    /// `audit_session`. The two differ for calls approved by the user, which are dispatched
+    /// without session context but still belong to the session's audit log.
+    pub(crate) async fn dispatch_tool_call_with_audit(
+        &self,
+        tool_call: mcp_core::tool::ToolCall,
+        request_id: String,
+        cancellation_token: Option<CancellationToken>,
+        session: &Option<SessionConfig>,
+        audit_session: &Option<SessionConfig>,
+    ) -> (String, Result<ToolCallResult, ErrorData>) {
+        let (Some(audit_logger), Some(session_config)) = (&self.audit_logger, audit_session) else {
+            return self
+                .dispatch_tool_call_unaudited(tool_call, request_id, cancellation_token, session)
+                .await;
+        };
+
+        let started = Instant::now();
+        let audited_call = tool_call.clone();
+        let (request_id, result) = self
+            .dispatch_tool_call_unaudited(tool_call, request_id, cancellation_token, session)
+            .await;
+        let result = result.map(|result| {
+            audit_logger.audit_tool_call(
+                &session_config.id,
+                &request_id,
+                &audited_call,
+                started,
+                result,
+            )
+        });
+        if let Err(e) = &result {
+            audit_logger.record(
+                &session_config.id,
+                &request_id,
+                &audited_call.name,
+                AuditEvent::ToolCall {
+                    arguments: audit_logger.redact(&audited_call.arguments),
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    result_bytes: 0,
+                    success: false,
+                    error: Some(e.message.to_string()),
+                },
+            );
+        }
+        (request_id, result)
+    }
+
+    async fn dispatch_tool_call_unaudited(
+        &self,
+        tool_call: mcp_core::tool::ToolCall,
+        request_id: String,
+        cancellation_token: Option<CancellationToken>,
+        session: &Option<SessionConfig>,
     ) -> (String, Result<ToolCallResult, ErrorData>) {
         if tool_call.name == PLATFORM_MANAGE_SCHEDULE_TOOL_NAME {
             let result = self
@@ -454,8 +579,17 @@ impl Agent {
                 .await
         } else if tool_call.name == SUBAGENT_EXECUTE_TASK_TOOL_NAME {
             let provider = self.provider().await.ok();
+            let parent_session_id = match session {
+                Some(session) => Some(session.id.clone()),
+                None => self
+                    .session_cancellation
+                    .lock()
+                    .await
+                    .as_ref()
+                    .map(|cancellation| cancellation.session_id().to_string()),
+            };
 
-            let task_config = TaskConfig::new(provider);
+            let task_config = TaskConfig::new(provider).with_parent_session(parent_session_id);
             subagent_execute_task_tool::run_tasks(
                 tool_call.arguments.clone(),
                 task_config,
@@ -801,6 +935,55 @@ impl Agent {
         Ok(())
     }
 
+    /// Start several extensions, each after the extensions it depends on, reporting each one
+    /// by name as it finishes. Extensions that do not depend on each other start concurrently.
+    pub fn add_extensions(
+        &self,
+        extensions: Vec<ExtensionConfig>,
+    ) -> BoxStream<'_, (String, ExtensionResult<()>)> {
+        Box::pin(async_stream::stream! {
+            let running = self.extension_manager.list_extensions().await.unwrap_or_default();
+            let plan = plan_startup(extensions, &running);
+            for (name, error) in plan.rejected {
+                yield (name, Err(error));
+            }
+
+            let mut failed: Vec<String> = Vec::new();
+            for wave in plan.waves {
+                let mut starting = futures::stream::FuturesUnordered::new();
+                for extension in wave {
+                    let blocker = extension
+                        .depends_on()
+                        .iter()
+                        .find(|dep| failed.contains(&name_to_key(dep)))
+                        .cloned();
+                    if let Some(dep) = blocker {
+                        failed.push(extension.key());
+                        yield (
+                            extension.name(),
+                            Err(ExtensionError::SetupError(format!(
+                                "'{}' depends on '{}', which failed to start",
+                                extension.name(),
+                                dep
+                            ))),
+                        );
+                        continue;
+                    }
+                    starting.push(async move {
+                        let (name, key) = (extension.name(), extension.key());
+                        (name, key, self.add_extension(extension).await)
+                    });
+                }
+                while let Some((name, key, result)) = starting.next().await {
+                    if result.is_err() {
+                        failed.push(key);
+                    }
+                    yield (name, result);
+                }
+            }
+        })
+    }
+
     pub async fn list_tools(&self, extension_name: Option<String>) -> Vec<Tool> {
         let mut prefixed_tools = self
             .extension_manager
@@ -870,6 +1053,13 @@ impl Agent {
         Ok(())
     }
 
+    /// Shut down all extensions, reaping the processes they started. Call this before exiting.
+    pub async fn shutdown(&self) {
+        self.extension_manager
+            .shutdown(DEFAULT_SHUTDOWN_TIMEOUT)
+            .await;
+    }
+
     pub async fn list_extensions(&self) -> Vec<String> {
         self.extension_manager
             .list_extensions()
@@ -1005,6 +1195,14 @@ impl Agent {
         let reply_span = tracing::Span::current();
         self.reset_retry_attempts().await;
 
+        let (cancel_token, reply_guard) = match self.session_cancellation.lock().await.as_ref() {
+            Some(cancellation) => {
+                let (token, guard) = cancellation.start_reply(cancel_token);
+                (Some(token), Some(guard))
+            }
+            None => (cancel_token, None),
+        };
+
         // This will need further refactoring. In the ideal world we pass the new message into
         // reply and load the existing conversation. Until we get to that point, fetch the conversation
         // so far and append the last (user) message that the caller already added.
@@ -1055,6 +1253,7 @@ impl Agent {
 
         Ok(Box::pin(async_stream::try_stream! {
             let _ = reply_span.enter();
+            let _reply_guard = reply_guard;
             let mut turns_taken = 0u32;
             let max_turns = session
                 .as_ref()
@@ -1062,6 +1261,9 @@ impl Agent {
                 .unwrap_or_else(|| {
                     config.get_param("GOOSE_MAX_TURNS").unwrap_or(DEFAULT_MAX_TURNS)
                 });
+            let max_parallel_tool_calls: usize = config
+                .get_param("GOOSE_MAX_PARALLEL_TOOL_CALLS")
+                .unwrap_or(DEFAULT_MAX_PARALLEL_TOOL_CALLS);
 
             loop {
                 if is_token_cancelled(&cancel_token) {
@@ -1080,9 +1282,7 @@ impl Agent {
 
                 turns_taken += 1;
                 if turns_taken > max_turns {
-                    yield AgentEvent::Message(Message::assistant().with_text(
-                        "I've reached the maximum number of actions I can do without user input. Would you like me to continue?"
-                    ));
+                    yield AgentEvent::Message(Message::assistant().with_text(MAX_TURNS_REACHED_MESSAGE));
                     break;
                 }
 
@@ -1139,6 +1339,18 @@ impl Agent {
                                 }
                             }
 
+                            if let Some(ref usage) = usage {
+                                record_usage(UsageEvent {
+                                    provider: config
+                                        .get_param("GOOSE_PROVIDER")
+                                        .unwrap_or_else(|_| "unknown".to_string()),
+                                    model: usage.model.clone(),
+                                    mode: goose_mode.clone(),
+                                    input_tokens: usage.usage.input_tokens.unwrap_or(0).max(0) as u64,
+                                    output_tokens: usage.usage.output_tokens.unwrap_or(0).max(0) as u64,
+                                });
+                            }
+
                             // Record usage for the session
                             if let Some(ref session_config) = &session {
                                 if let Some(ref usage) = usage {
@@ -1215,6 +1427,12 @@ impl Agent {
                                             result
                                         });
 
+                                    self.audit_permission_decisions(
+                                        &session,
+                                        &permission_check_result,
+                                        &inspection_results,
+                                    );
+
                                     // Track extension requests for special handling
                                     let mut enable_extension_request_ids = vec![];
                                     for request in &remaining_requests {
@@ -1225,10 +1443,15 @@ impl Agent {
                                         }
                                     }
 
+                                    let limiter = ToolCallLimiter::new(
+                                        max_parallel_tool_calls,
+                                        &tools,
+                                        cancel_token.clone(),
+                                    );
                                     let mut tool_futures = self.handle_approved_and_denied_tools(
                                         &permission_check_result,
                                         message_tool_response.clone(),
-                                        cancel_token.clone(),
+                                        &limiter,
                                         &session
                                     ).await?;
 
@@ -1239,8 +1462,9 @@ impl Agent {
                                         &permission_check_result.needs_approval,
                                         tool_futures_arc.clone(),
                                         message_tool_response.clone(),
-                                        cancel_token.clone(),
+                                        &limiter,
                                         &inspection_results,
+                                        &session,
                                     );
 
                                     while let Some(msg) = tool_approval_stream.try_next().await? {
@@ -1290,7 +1514,14 @@ impl Agent {
                                     }
                                 }
 
-                                let final_message_tool_resp = message_tool_response.lock().await.clone();
+                                let mut final_message_tool_resp = message_tool_response.lock().await.clone();
+                                let request_ids: Vec<String> = response
+                                    .content
+                                    .iter()
+                                    .filter_map(|content| content.as_tool_request())
+                                    .map(|request| request.id.clone())
+                                    .collect();
+                                order_tool_responses(&mut final_message_tool_resp, &request_ids);
                                 yield AgentEvent::Message(final_message_tool_resp.clone());
 
                                 no_tools_called = false;
@@ -1326,7 +1557,7 @@ impl Agent {
                         Err(e) => {
                             error!("Error: {}", e);
                             yield AgentEvent::Message(Message::assistant().with_text(
-                                    format!("Ran into this error: {e}.\n\nPlease retry if you think this is a transient or recoverable error.")
+                                    format!("{PROVIDER_ERROR_PREFIX}{e}.\n\nPlease retry if you think this is a transient or recoverable error.")
                                 ));
                             break;
                         }
@@ -1463,6 +1694,19 @@ impl Agent {
         Err(anyhow!("Prompt '{}' not found", name))
     }
 
+    /// Get an extension's prompt as messages ready to add to the conversation, failing with
+    /// the missing arguments listed when a required one isn't given
+    pub async fn render_prompt(
+        &self,
+        extension: &str,
+        name: &str,
+        arguments: Value,
+    ) -> Result<Conversation> {
+        self.extension_manager
+            .render_prompt(extension, name, arguments, CancellationToken::default())
+            .await
+    }
+
     pub async fn get_plan_prompt(&self) -> Result<String> {
         let tools = self.extension_manager.get_prefixed_tools(None).await?;
         let tools_info = tools
@@ -1534,7 +1778,7 @@ impl Agent {
 
         messages.push(Message::user().with_text(recipe_prompt));
 
-        let (messages, issues) = fix_conversation(messages);
+        let (messages, issues) = fix_conversation(messages, provider.supports_vision());
         if !issues.is_empty() {
             issues
                 .iter()
@@ -0,0 +1,560 @@
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use etcetera::{choose_app_strategy, AppStrategy};
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::agents::tool_execution::ToolCallResult;
+use crate::config::{Config, APP_STRATEGY};
+use crate::tool_inspection::{InspectionAction, InspectionResult};
+
+/// Config key that turns on the audit log
+pub const AUDIT_LOG_ENABLED_KEY: &str = "GOOSE_AUDIT_LOG";
+/// Config key listing argument names whose values are redacted in the audit log
+pub const AUDIT_REDACT_KEYS_KEY: &str = "GOOSE_AUDIT_REDACT_KEYS";
+
+const DEFAULT_REDACT_KEYS: &[&str] = &["token", "password", "authorization", "api_key", "secret"];
+const REDACTED: &str = "[REDACTED]";
+
+/// How a tool request was routed by the permission check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditDecision {
+    Approved,
+    NeedsApproval,
+    Denied,
+    UserApproved,
+    UserDeclined,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    ToolCall {
+        arguments: Value,
+        duration_ms: u64,
+        result_bytes: usize,
+        success: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    PermissionDecision {
+        decision: AuditDecision,
+    },
+    SecurityFinding {
+        finding_id: String,
+        confidence: f32,
+        explanation: String,
+        blocked: bool,
+    },
+}
+
+/// One line of a session's audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub timestamp: DateTime<Utc>,
+    pub session_id: String,
+    pub tool_request_id: String,
+    pub tool_name: String,
+    #[serde(flatten)]
+    pub event: AuditEvent,
+}
+
+/// Selects records when reading an audit log back
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub tool_name: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl AuditFilter {
+    fn matches(&self, record: &AuditRecord) -> bool {
+        self.tool_name
+            .as_deref()
+            .is_none_or(|name| record.tool_name == name)
+            && self.since.is_none_or(|since| record.timestamp >= since)
+            && self.until.is_none_or(|until| record.timestamp <= until)
+    }
+}
+
+/// Appends tool calls, permission decisions and security findings to a JSONL file per session.
+///
+/// Writes are best effort: a failure to write the audit log is reported through tracing but
+/// never fails the tool call being audited.
+pub struct AuditLogger {
+    dir: PathBuf,
+    redact_keys: Vec<String>,
+    write_lock: Mutex<()>,
+}
+
+impl AuditLogger {
+    pub fn new(dir: impl Into<PathBuf>, redact_keys: Vec<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            redact_keys: redact_keys
+                .into_iter()
+                .map(|key| key.to_lowercase())
+                .collect(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Build a logger from the global config, or `None` if `GOOSE_AUDIT_LOG` is not enabled
+    pub fn from_config() -> Option<Self> {
+        let config = Config::global();
+        if !config
+            .get_param::<bool>(AUDIT_LOG_ENABLED_KEY)
+            .unwrap_or(false)
+        {
+            return None;
+        }
+
+        let redact_keys = config
+            .get_param::<Vec<String>>(AUDIT_REDACT_KEYS_KEY)
+            .unwrap_or_else(|_| DEFAULT_REDACT_KEYS.iter().map(|k| k.to_string()).collect());
+
+        match default_audit_dir() {
+            Ok(dir) => Some(Self::new(dir, redact_keys)),
+            Err(e) => {
+                tracing::warn!("Audit log disabled: {}", e);
+                None
+            }
+        }
+    }
+
+    pub fn session_path(&self, session_id: &str) -> PathBuf {
+        // Session ids are generated by goose, but never let one name a file outside the audit dir
+        let file_name: String = session_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{}.jsonl", file_name))
+    }
+
+    /// Replace the value of any object key matching the redaction list, at any depth
+    pub fn redact(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, value)| {
+                        let value = if self.is_sensitive_key(key) {
+                            Value::String(REDACTED.to_string())
+                        } else {
+                            self.redact(value)
+                        };
+                        (key.clone(), value)
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.redact(v)).collect()),
+            other => other.clone(),
+        }
+    }
+
+    /// A key is sensitive when one of the redaction keys appears in it as whole words, so
+    /// `api_token` and `apiKey` match `token` and `api_key` but `max_tokens` does not
+    fn is_sensitive_key(&self, key: &str) -> bool {
+        let words = key_words(key);
+        self.redact_keys.iter().any(|redact_key| {
+            let redact_words = key_words(redact_key);
+            !redact_words.is_empty()
+                && words
+                    .windows(redact_words.len())
+                    .any(|window| window == redact_words.as_slice())
+        })
+    }
+
+    pub fn record(
+        &self,
+        session_id: &str,
+        tool_request_id: &str,
+        tool_name: &str,
+        event: AuditEvent,
+    ) {
+        let record = AuditRecord {
+            timestamp: Utc::now(),
+            session_id: session_id.to_string(),
+            tool_request_id: tool_request_id.to_string(),
+            tool_name: tool_name.to_string(),
+            event,
+        };
+        if let Err(e) = self.append(&record) {
+            tracing::warn!(session_id, tool_name, "Failed to write audit record: {}", e);
+        }
+    }
+
+    fn append(&self, record: &AuditRecord) -> Result<()> {
+        let line = serde_json::to_string(record)?;
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        fs::create_dir_all(&self.dir).context("Failed to create audit log directory")?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.session_path(&record.session_id))?;
+        writeln!(file, "{}", line)?;
+        Ok(())
+    }
+
+    /// Record the permission routing and any security findings for a batch of tool requests
+    pub fn record_inspection(
+        &self,
+        session_id: &str,
+        tool_name: &str,
+        tool_request_id: &str,
+        decision: AuditDecision,
+        inspection_results: &[InspectionResult],
+    ) {
+        for result in inspection_results
+            .iter()
+            .filter(|r| r.tool_request_id == tool_request_id)
+        {
+            if let Some(finding_id) = &result.finding_id {
+                self.record(
+                    session_id,
+                    tool_request_id,
+                    tool_name,
+                    AuditEvent::SecurityFinding {
+                        finding_id: finding_id.clone(),
+                        confidence: result.confidence,
+                        explanation: result.reason.clone(),
+                        blocked: result.action != InspectionAction::Allow,
+                    },
+                );
+            }
+        }
+        self.record(
+            session_id,
+            tool_request_id,
+            tool_name,
+            AuditEvent::PermissionDecision { decision },
+        );
+    }
+
+    /// Wrap a dispatched tool call so its outcome is recorded once the result resolves.
+    /// Duration is measured from `started`, which should be taken before dispatch.
+    pub fn audit_tool_call(
+        self: &std::sync::Arc<Self>,
+        session_id: &str,
+        tool_request_id: &str,
+        tool_call: &mcp_core::tool::ToolCall,
+        started: Instant,
+        result: ToolCallResult,
+    ) -> ToolCallResult {
+        let logger = self.clone();
+        let session_id = session_id.to_string();
+        let tool_request_id = tool_request_id.to_string();
+        let tool_name = tool_call.name.clone();
+        let arguments = self.redact(&tool_call.arguments);
+
+        let audited = result.result.map(move |outcome| {
+            let (success, result_bytes, error) = match &outcome {
+                Ok(contents) => (
+                    true,
+                    serde_json::to_string(contents).map_or(0, |s| s.len()),
+                    None,
+                ),
+                Err(e) => (false, 0, Some(e.message.to_string())),
+            };
+            logger.record(
+                &session_id,
+                &tool_request_id,
+                &tool_name,
+                AuditEvent::ToolCall {
+                    arguments,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    result_bytes,
+                    success,
+                    error,
+                },
+            );
+            outcome
+        });
+
+        ToolCallResult {
+            result: Box::new(audited),
+            notification_stream: result.notification_stream,
+        }
+    }
+
+    /// Read a session's audit log back, keeping only records that match `filter`
+    pub fn read(&self, session_id: &str, filter: &AuditFilter) -> Result<Vec<AuditRecord>> {
+        read_audit_file(&self.session_path(session_id), filter)
+    }
+}
+
+/// Split a key into lowercase words on separators and camelCase boundaries
+fn key_words(key: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in key.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Directory holding one audit log per session, under the app data dir
+pub fn default_audit_dir() -> Result<PathBuf> {
+    Ok(choose_app_strategy(APP_STRATEGY.clone())
+        .context("HOME environment variable not set")?
+        .in_data_dir("audit"))
+}
+
+pub fn read_audit_file(path: &Path, filter: &AuditFilter) -> Result<Vec<AuditRecord>> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: AuditRecord = serde_json::from_str(&line)
+            .with_context(|| format!("Malformed audit record in {}", path.display()))?;
+        if filter.matches(&record) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use rmcp::model::{Content, ErrorCode, ErrorData};
+    use serde_json::json;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn test_logger(dir: &TempDir) -> Arc<AuditLogger> {
+        Arc::new(AuditLogger::new(
+            dir.path(),
+            DEFAULT_REDACT_KEYS.iter().map(|k| k.to_string()).collect(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_tool_call_record_shape_and_redaction() {
+        let dir = TempDir::new().unwrap();
+        let logger = test_logger(&dir);
+        let tool_call = ToolCall::new(
+            "http__fetch",
+            json!({
+                "url": "https://example.com",
+                "headers": {"Authorization": "Bearer abc123", "Accept": "text/html"},
+                "auth": [{"api_token": "xyz"}],
+                "password": "hunter2",
+            }),
+        );
+
+        let result = ToolCallResult::from(Ok(vec![Content::text("hello")]));
+        let audited =
+            logger.audit_tool_call("session-1", "req-1", &tool_call, Instant::now(), result);
+        let outcome = audited.result.await.unwrap();
+        assert_eq!(outcome.len(), 1);
+
+        let contents = fs::read_to_string(logger.session_path("session-1")).unwrap();
+        assert!(!contents.contains("abc123"));
+        assert!(!contents.contains("hunter2"));
+        assert!(!contents.contains("xyz"));
+
+        let line: Value = serde_json::from_str(contents.trim()).unwrap();
+        assert_eq!(line["event"], "tool_call");
+        assert_eq!(line["session_id"], "session-1");
+        assert_eq!(line["tool_request_id"], "req-1");
+        assert_eq!(line["tool_name"], "http__fetch");
+        assert_eq!(line["success"], true);
+        assert!(line["result_bytes"].as_u64().unwrap() > 0);
+        assert!(line["duration_ms"].is_u64());
+        assert!(line["timestamp"].is_string());
+        assert_eq!(
+            line["arguments"],
+            json!({
+                "url": "https://example.com",
+                "headers": {"Authorization": REDACTED, "Accept": "text/html"},
+                "auth": [{"api_token": REDACTED}],
+                "password": REDACTED,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_tool_call_records_error() {
+        let dir = TempDir::new().unwrap();
+        let logger = test_logger(&dir);
+        let tool_call = ToolCall::new("shell", json!({"command": "false"}));
+        let result = ToolCallResult::from(Err(ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            "exit status 1".to_string(),
+            None,
+        )));
+
+        let audited = logger.audit_tool_call("s", "r", &tool_call, Instant::now(), result);
+        assert!(audited.result.await.is_err());
+
+        let records = logger.read("s", &AuditFilter::default()).unwrap();
+        assert_eq!(records.len(), 1);
+        match &records[0].event {
+            AuditEvent::ToolCall { success, error, .. } => {
+                assert!(!success);
+                assert_eq!(error.as_deref(), Some("exit status 1"));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_inspection_records_security_finding_and_decision() {
+        let dir = TempDir::new().unwrap();
+        let logger = test_logger(&dir);
+        let results = vec![InspectionResult {
+            tool_request_id: "req-1".to_string(),
+            action: InspectionAction::RequireApproval(None),
+            reason: "curl piped to shell".to_string(),
+            confidence: 0.9,
+            inspector_name: "security".to_string(),
+            finding_id: Some("SEC-1".to_string()),
+        }];
+
+        logger.record_inspection(
+            "s",
+            "shell",
+            "req-1",
+            AuditDecision::NeedsApproval,
+            &results,
+        );
+
+        let events: Vec<AuditEvent> = logger
+            .read("s", &AuditFilter::default())
+            .unwrap()
+            .into_iter()
+            .map(|r| r.event)
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                AuditEvent::SecurityFinding {
+                    finding_id: "SEC-1".to_string(),
+                    confidence: 0.9,
+                    explanation: "curl piped to shell".to_string(),
+                    blocked: true,
+                },
+                AuditEvent::PermissionDecision {
+                    decision: AuditDecision::NeedsApproval
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_filters_by_tool_and_time() {
+        let dir = TempDir::new().unwrap();
+        let logger = test_logger(&dir);
+        let decision = || AuditEvent::PermissionDecision {
+            decision: AuditDecision::Approved,
+        };
+
+        logger.record("s", "1", "shell", decision());
+        let middle = Utc::now();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        logger.record("s", "2", "text_editor", decision());
+        logger.record("s", "3", "shell", decision());
+
+        let shell = AuditFilter {
+            tool_name: Some("shell".to_string()),
+            ..Default::default()
+        };
+        let ids: Vec<String> = logger
+            .read("s", &shell)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.tool_request_id)
+            .collect();
+        assert_eq!(ids, vec!["1", "3"]);
+
+        let recent = AuditFilter {
+            since: Some(middle),
+            ..Default::default()
+        };
+        assert_eq!(logger.read("s", &recent).unwrap().len(), 2);
+
+        assert!(logger
+            .read("missing", &AuditFilter::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_sensitive_keys_match_whole_words() {
+        let dir = TempDir::new().unwrap();
+        let logger = AuditLogger::new(
+            dir.path(),
+            vec![
+                "token".to_string(),
+                "api_key".to_string(),
+                "key".to_string(),
+            ],
+        );
+
+        for key in [
+            "token",
+            "access_token",
+            "X-Auth-Token",
+            "apiKey",
+            "OPENAI_API_KEY",
+            "key",
+        ] {
+            assert!(logger.is_sensitive_key(key), "{} should be redacted", key);
+        }
+        for key in [
+            "max_tokens",
+            "tokenizer",
+            "monkey",
+            "keyboard_layout",
+            "api",
+        ] {
+            assert!(!logger.is_sensitive_key(key), "{} should be kept", key);
+        }
+    }
+
+    #[test]
+    fn test_session_path_stays_in_audit_dir() {
+        let dir = TempDir::new().unwrap();
+        let logger = test_logger(&dir);
+        let path = logger.session_path("../../etc/passwd");
+        assert_eq!(path.parent().unwrap(), dir.path());
+    }
+}
@@ -2,11 +2,11 @@ use anyhow::Ok;
 
 use crate::conversation::message::{Message, MessageMetadata};
 use crate::conversation::Conversation;
-use crate::token_counter::create_async_token_counter;
+use crate::token_counter::{create_async_token_counter, CountTokens};
 
 use crate::context_mgmt::summarize::summarize_messages;
 use crate::context_mgmt::truncate::{truncate_messages, OldestFirstTruncation};
-use crate::context_mgmt::{estimate_target_context_limit, get_messages_token_counts_async};
+use crate::context_mgmt::{estimate_target_context_limit, get_messages_token_counts};
 
 use super::super::agents::Agent;
 
@@ -21,7 +21,7 @@ impl Agent {
             .await
             .map_err(|e| anyhow::anyhow!("Failed to create token counter: {}", e))?;
         let target_context_limit = estimate_target_context_limit(provider);
-        let token_counts = get_messages_token_counts_async(&token_counter, messages);
+        let token_counts = get_messages_token_counts(&token_counter, messages);
 
         let (mut new_messages, mut new_token_counts) = truncate_messages(
             messages,
@@ -32,8 +32,7 @@ impl Agent {
 
         // Only add an assistant message if we have room for it and it won't cause another overflow
         let assistant_message = Message::assistant().with_text("I had run into a context length exceeded error so I truncated some of the oldest messages in our conversation.");
-        let assistant_tokens =
-            token_counter.count_chat_tokens("", std::slice::from_ref(&assistant_message), &[]);
+        let assistant_tokens = token_counter.count_message_tokens(&assistant_message);
 
         let current_total: usize = new_token_counts.iter().sum();
         if current_total + assistant_tokens <= target_context_limit {
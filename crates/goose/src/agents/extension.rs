@@ -1,6 +1,8 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use mcp_client::client::Error as ClientError;
+use mcp_client::{KeepAlive, ResponseLimits};
 use rmcp::model::Tool;
 use rmcp::service::ClientInitializeError;
 use serde::{Deserialize, Serialize};
@@ -30,6 +32,11 @@ impl ProcessExit {
             source,
         }
     }
+
+    pub fn map_stderr(mut self, f: impl FnOnce(&str) -> String) -> Self {
+        self.stderr = f(&self.stderr);
+        self
+    }
 }
 
 /// Errors from Extension operation
@@ -142,6 +149,27 @@ impl Envs {
     }
 }
 
+/// Per-extension overrides of the response size limits; unset values keep the defaults
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+pub struct ResponseLimitsConfig {
+    /// Total bytes of text and binary data kept from a tool result or resource
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_result_bytes: Option<usize>,
+    /// Content items kept from a tool result or resource
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_content_items: Option<usize>,
+}
+
+impl ResponseLimitsConfig {
+    pub fn resolve(&self) -> ResponseLimits {
+        let defaults = ResponseLimits::default();
+        ResponseLimits {
+            max_result_bytes: self.max_result_bytes.unwrap_or(defaults.max_result_bytes),
+            max_content_items: self.max_content_items.unwrap_or(defaults.max_content_items),
+        }
+    }
+}
+
 /// Represents the different types of MCP extensions that can be added to the manager
 #[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 #[serde(tag = "type")]
@@ -160,11 +188,20 @@ pub enum ExtensionConfig {
         // NOTE: set timeout to be optional for compatibility.
         // However, new configurations should include this field.
         timeout: Option<u64>,
+        /// Overrides of the default caps on tool result and resource sizes
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        response_limits: Option<ResponseLimitsConfig>,
+        /// Seconds between keep-alive pings while the connection is idle; no pings when unset
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        keep_alive_interval: Option<u64>,
         /// Whether this extension is bundled with goose
         #[serde(default)]
         bundled: Option<bool>,
         #[serde(default)]
         available_tools: Vec<String>,
+        /// Extensions that must be running before this one is started
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        depends_on: Vec<String>,
     },
     /// Standard I/O client with command and arguments
     #[serde(rename = "stdio")]
@@ -178,12 +215,18 @@ pub enum ExtensionConfig {
         #[serde(default)]
         env_keys: Vec<String>,
         timeout: Option<u64>,
+        /// Overrides of the default caps on tool result and resource sizes
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        response_limits: Option<ResponseLimitsConfig>,
         description: Option<String>,
         /// Whether this extension is bundled with goose
         #[serde(default)]
         bundled: Option<bool>,
         #[serde(default)]
         available_tools: Vec<String>,
+        /// Extensions that must be running before this one is started
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        depends_on: Vec<String>,
     },
     /// Built-in extension that is part of the goose binary
     #[serde(rename = "builtin")]
@@ -193,11 +236,17 @@ pub enum ExtensionConfig {
         display_name: Option<String>, // needed for the UI
         description: Option<String>,
         timeout: Option<u64>,
+        /// Overrides of the default caps on tool result and resource sizes
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        response_limits: Option<ResponseLimitsConfig>,
         /// Whether this extension is bundled with goose
         #[serde(default)]
         bundled: Option<bool>,
         #[serde(default)]
         available_tools: Vec<String>,
+        /// Extensions that must be running before this one is started
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        depends_on: Vec<String>,
     },
     /// Streamable HTTP client with a URI endpoint using MCP Streamable HTTP specification
     #[serde(rename = "streamable_http")]
@@ -215,11 +264,20 @@ pub enum ExtensionConfig {
         // NOTE: set timeout to be optional for compatibility.
         // However, new configurations should include this field.
         timeout: Option<u64>,
+        /// Overrides of the default caps on tool result and resource sizes
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        response_limits: Option<ResponseLimitsConfig>,
+        /// Seconds between keep-alive pings while the connection is idle; no pings when unset
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        keep_alive_interval: Option<u64>,
         /// Whether this extension is bundled with goose
         #[serde(default)]
         bundled: Option<bool>,
         #[serde(default)]
         available_tools: Vec<String>,
+        /// Extensions that must be running before this one is started
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        depends_on: Vec<String>,
     },
     /// Frontend-provided tools that will be called through the frontend
     #[serde(rename = "frontend")]
@@ -247,11 +305,17 @@ pub enum ExtensionConfig {
         description: Option<String>,
         /// Timeout in seconds
         timeout: Option<u64>,
+        /// Overrides of the default caps on tool result and resource sizes
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        response_limits: Option<ResponseLimitsConfig>,
         /// Python package dependencies required by this extension
         #[serde(default)]
         dependencies: Option<Vec<String>>,
         #[serde(default)]
         available_tools: Vec<String>,
+        /// Extensions that must be running before this one is started
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        depends_on: Vec<String>,
     },
 }
 
@@ -264,11 +328,64 @@ impl Default for ExtensionConfig {
             timeout: Some(config::DEFAULT_EXTENSION_TIMEOUT),
             bundled: Some(true),
             available_tools: Vec::new(),
+            depends_on: Vec::new(),
+            response_limits: None,
         }
     }
 }
 
 impl ExtensionConfig {
+    /// The response limit overrides for this extension, none for frontend tools
+    pub fn response_limits(&self) -> ResponseLimitsConfig {
+        match self {
+            Self::Sse {
+                response_limits, ..
+            }
+            | Self::Stdio {
+                response_limits, ..
+            }
+            | Self::Builtin {
+                response_limits, ..
+            }
+            | Self::StreamableHttp {
+                response_limits, ..
+            }
+            | Self::InlinePython {
+                response_limits, ..
+            } => response_limits.unwrap_or_default(),
+            Self::Frontend { .. } => ResponseLimitsConfig::default(),
+        }
+    }
+
+    /// Names of the extensions this one needs running first
+    pub fn depends_on(&self) -> &[String] {
+        match self {
+            Self::Sse { depends_on, .. }
+            | Self::Stdio { depends_on, .. }
+            | Self::Builtin { depends_on, .. }
+            | Self::StreamableHttp { depends_on, .. }
+            | Self::InlinePython { depends_on, .. } => depends_on,
+            Self::Frontend { .. } => &[],
+        }
+    }
+
+    /// Keep-alive settings for network extensions that ask for them
+    pub fn keep_alive(&self) -> Option<KeepAlive> {
+        match self {
+            Self::Sse {
+                keep_alive_interval,
+                ..
+            }
+            | Self::StreamableHttp {
+                keep_alive_interval,
+                ..
+            } => keep_alive_interval
+                .filter(|secs| *secs > 0)
+                .map(|secs| KeepAlive::new(Duration::from_secs(secs))),
+            _ => None,
+        }
+    }
+
     pub fn sse<S: Into<String>, T: Into<u64>>(name: S, uri: S, description: S, timeout: T) -> Self {
         Self::Sse {
             name: name.into(),
@@ -279,6 +396,9 @@ impl ExtensionConfig {
             timeout: Some(timeout.into()),
             bundled: None,
             available_tools: Vec::new(),
+            depends_on: Vec::new(),
+            response_limits: None,
+            keep_alive_interval: None,
         }
     }
 
@@ -298,6 +418,9 @@ impl ExtensionConfig {
             timeout: Some(timeout.into()),
             bundled: None,
             available_tools: Vec::new(),
+            depends_on: Vec::new(),
+            response_limits: None,
+            keep_alive_interval: None,
         }
     }
 
@@ -317,6 +440,8 @@ impl ExtensionConfig {
             timeout: Some(timeout.into()),
             bundled: None,
             available_tools: Vec::new(),
+            depends_on: Vec::new(),
+            response_limits: None,
         }
     }
 
@@ -333,6 +458,8 @@ impl ExtensionConfig {
             timeout: Some(timeout.into()),
             dependencies: None,
             available_tools: Vec::new(),
+            depends_on: Vec::new(),
+            response_limits: None,
         }
     }
 
@@ -351,6 +478,7 @@ impl ExtensionConfig {
                 description,
                 bundled,
                 available_tools,
+                depends_on,
                 ..
             } => Self::Stdio {
                 name,
@@ -362,6 +490,8 @@ impl ExtensionConfig {
                 timeout,
                 bundled,
                 available_tools,
+                response_limits: None,
+                depends_on,
             },
             other => other,
         }
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use axum::http::{HeaderMap, HeaderName};
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
 use chrono::{DateTime, Utc};
 use futures::stream::{FuturesUnordered, StreamExt};
 use futures::{future, FutureExt};
@@ -10,34 +10,57 @@ use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig
 use rmcp::transport::{
     ConfigureCommandExt, SseClientTransport, StreamableHttpClientTransport, TokioChildProcess,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::process::Stdio;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tempfile::{tempdir, TempDir};
 use tokio::io::AsyncReadExt;
 use tokio::process::Command;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify};
 use tokio::task;
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, warn};
 
 use super::extension::{ExtensionConfig, ExtensionError, ExtensionInfo, ExtensionResult, ToolInfo};
+use super::tool_cache::ToolResultCache;
 use super::tool_execution::ToolCallResult;
-use crate::agents::extension::{Envs, ProcessExit};
+use super::tool_usage::{ToolUsageTracker, UsageStats};
+use crate::agents::extension::ProcessExit;
 use crate::agents::extension_malware_check;
+use crate::agents::extension_template::ExtensionTemplate;
 use crate::config::{Config, ExtensionConfigManager};
+use crate::conversation::message::Message;
+use crate::conversation::Conversation;
 use crate::oauth::oauth_flow;
 use crate::prompt_template;
 use mcp_client::client::{McpClient, McpClientTrait};
 use rmcp::model::{
-    Content, ErrorCode, ErrorData, GetPromptResult, Prompt, ResourceContents, ServerInfo, Tool,
+    Content, ErrorCode, ErrorData, GetPromptResult, Prompt, PromptArgument, ResourceContents,
+    ServerInfo, Tool,
 };
 use rmcp::transport::auth::AuthClient;
 use serde_json::Value;
 
-type McpClientBox = Arc<Mutex<Box<dyn McpClientTrait>>>;
+type McpClientBox = Arc<dyn McpClientTrait>;
+
+/// Spell out what a prompt's arguments leave implicit, so callers can list every argument
+/// with whether it is required without handling absent fields
+fn with_argument_metadata(mut prompt: Prompt) -> Prompt {
+    let arguments = prompt
+        .arguments
+        .take()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|arg| PromptArgument {
+            required: Some(arg.required.unwrap_or(false)),
+            ..arg
+        })
+        .collect();
+    prompt.arguments = Some(arguments);
+    prompt
+}
 
 struct Extension {
     pub config: ExtensionConfig,
@@ -45,6 +68,7 @@ struct Extension {
     client: McpClientBox,
     server_info: Option<ServerInfo>,
     _temp_dir: Option<tempfile::TempDir>,
+    child: Option<ChildProcess>,
 }
 
 impl Extension {
@@ -53,12 +77,22 @@ impl Extension {
         client: McpClientBox,
         server_info: Option<ServerInfo>,
         temp_dir: Option<tempfile::TempDir>,
+        child: Option<ChildProcess>,
     ) -> Self {
         Self {
             client,
             config,
             server_info,
             _temp_dir: temp_dir,
+            child,
+        }
+    }
+
+    /// Close the client, then make sure the server and anything it spawned are gone
+    async fn shutdown(self, timeout: Duration) {
+        self.client.shutdown(timeout).await;
+        if let Some(child) = self.child {
+            child.terminate(timeout).await;
         }
     }
 
@@ -83,6 +117,10 @@ impl Extension {
 /// Manages goose extensions / MCP clients and their interactions
 pub struct ExtensionManager {
     extensions: Mutex<HashMap<String, Extension>>,
+    usage: Arc<ToolUsageTracker>,
+    tool_cache: Arc<ToolResultCache>,
+    /// Notified whenever an extension finishes starting
+    started: Notify,
 }
 
 /// A flattened representation of a resource used by the agent to prepare inference
@@ -121,6 +159,72 @@ impl ResourceItem {
 #[cfg(windows)]
 const CREATE_NO_WINDOW_FLAG: u32 = 0x08000000;
 
+/// How long to wait for an extension to exit on its own before it is killed
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long an extension waits for the extensions it depends on to start
+const DEPENDENCY_READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Grace period between SIGTERM and SIGKILL for whatever is left in a process group
+#[cfg(unix)]
+const PROCESS_GROUP_GRACE: Duration = Duration::from_secs(1);
+
+/// The parts of a child process extension that outlive its client
+struct ChildProcess {
+    /// The server leads its own process group, so this also reaches anything it spawned
+    #[cfg(unix)]
+    process_group: Option<u32>,
+    stderr: task::JoinHandle<std::io::Result<String>>,
+}
+
+impl ChildProcess {
+    async fn terminate(self, timeout: Duration) {
+        // On Windows the job object kills the whole tree when the transport drops the child
+        #[cfg(unix)]
+        if let Some(pgid) = self.process_group {
+            terminate_process_group(pgid).await;
+        }
+
+        let mut stderr = self.stderr;
+        if tokio::time::timeout(timeout, &mut stderr).await.is_err() {
+            stderr.abort();
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn terminate_process_group(pgid: u32) {
+    let pgid = -(pgid as libc::pid_t);
+    // Signal 0 only checks whether anything in the group is still around
+    let alive = || unsafe { libc::kill(pgid, 0) } == 0;
+    if !alive() {
+        return;
+    }
+
+    unsafe { libc::kill(pgid, libc::SIGTERM) };
+    let deadline = tokio::time::Instant::now() + PROCESS_GROUP_GRACE;
+    while alive() && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    if alive() {
+        unsafe { libc::kill(pgid, libc::SIGKILL) };
+    }
+}
+
+/// Put the server and everything it spawns in a job object that is killed along with it
+#[cfg(windows)]
+fn job_object_command(command: Command) -> process_wrap::tokio::TokioCommandWrap {
+    use process_wrap::tokio::{CreationFlags, JobObject, KillOnDrop, TokioCommandWrap};
+    use windows::Win32::System::Threading::PROCESS_CREATION_FLAGS;
+
+    let mut command = TokioCommandWrap::from(command);
+    command
+        .wrap(CreationFlags(PROCESS_CREATION_FLAGS(CREATE_NO_WINDOW_FLAG)))
+        .wrap(JobObject)
+        .wrap(KillOnDrop);
+    command
+}
+
 /// Sanitizes a string by replacing invalid characters with underscores.
 /// Valid characters match [a-zA-Z0-9_-]
 fn normalize(input: String) -> String {
@@ -143,6 +247,153 @@ pub fn get_parameter_names(tool: &Tool) -> Vec<String> {
         .unwrap_or_default()
 }
 
+/// The order to start a set of extensions in
+#[derive(Debug, Default)]
+pub struct StartupPlan {
+    /// Groups of extensions to start one after another. The extensions within a wave do not
+    /// depend on each other and can be started concurrently.
+    pub waves: Vec<Vec<ExtensionConfig>>,
+    /// Extensions that cannot be started, by name, with the reason
+    pub rejected: Vec<(String, ExtensionError)>,
+}
+
+/// Order `configs` so every extension comes after the ones listed in its `depends_on`.
+///
+/// Dependencies may also be extensions that are already `running`. Extensions in a dependency
+/// cycle, or depending on one that is neither running nor part of `configs`, are rejected along
+/// with everything that depends on them; the rest are still planned.
+pub fn plan_startup(configs: Vec<ExtensionConfig>, running: &[String]) -> StartupPlan {
+    let mut plan = StartupPlan::default();
+    let mut names: HashMap<String, String> = HashMap::new();
+    for config in &configs {
+        names.insert(normalize(config.key()), config.name());
+    }
+    let mut placed: HashSet<String> = running.iter().map(|n| normalize(n.clone())).collect();
+    let mut failed: HashSet<String> = HashSet::new();
+
+    let mut pending: Vec<(String, Vec<String>, ExtensionConfig)> = Vec::new();
+    for config in configs {
+        let key = normalize(config.key());
+        let deps: Vec<String> = config
+            .depends_on()
+            .iter()
+            .map(|dep| normalize(dep.clone()))
+            .collect();
+        let unknown = config
+            .depends_on()
+            .iter()
+            .zip(&deps)
+            .find(|(_, dep)| !names.contains_key(*dep) && !placed.contains(*dep));
+        if let Some((dep, _)) = unknown {
+            plan.rejected.push((
+                config.name(),
+                ExtensionError::ConfigError(format!(
+                    "'{}' depends on '{}', which is not enabled",
+                    config.name(),
+                    dep
+                )),
+            ));
+            failed.insert(key);
+        } else {
+            pending.push((key, deps, config));
+        }
+    }
+
+    loop {
+        // Anything depending on a rejected extension cannot start either
+        loop {
+            let before = pending.len();
+            pending.retain(|(key, deps, config)| {
+                let Some(dep) = deps.iter().find(|dep| failed.contains(*dep)) else {
+                    return true;
+                };
+                plan.rejected.push((
+                    config.name(),
+                    ExtensionError::ConfigError(format!(
+                        "'{}' depends on '{}', which cannot be started",
+                        config.name(),
+                        names.get(dep).unwrap_or(dep)
+                    )),
+                ));
+                failed.insert(key.clone());
+                false
+            });
+            if pending.len() == before {
+                break;
+            }
+        }
+        if pending.is_empty() {
+            return plan;
+        }
+
+        let (ready, waiting): (Vec<_>, Vec<_>) = pending
+            .into_iter()
+            .partition(|(_, deps, _)| deps.iter().all(|dep| placed.contains(dep)));
+        pending = waiting;
+        if !ready.is_empty() {
+            placed.extend(ready.iter().map(|(key, _, _)| key.clone()));
+            plan.waves
+                .push(ready.into_iter().map(|(_, _, config)| config).collect());
+            continue;
+        }
+
+        // Nothing can start, so what is left depends on a cycle; reject its members and let
+        // the loop above reject the extensions waiting on them
+        let graph: HashMap<&str, &[String]> = pending
+            .iter()
+            .map(|(key, deps, _)| (key.as_str(), deps.as_slice()))
+            .collect();
+        let cycles: Vec<(String, Vec<String>)> = pending
+            .iter()
+            .filter_map(|(key, _, _)| find_cycle(key, &graph).map(|path| (key.clone(), path)))
+            .collect();
+        pending.retain(|(key, _, config)| {
+            let Some((_, path)) = cycles.iter().find(|(member, _)| member == key) else {
+                return true;
+            };
+            let path: Vec<&str> = path
+                .iter()
+                .map(|key| names.get(key).unwrap_or(key).as_str())
+                .collect();
+            plan.rejected.push((
+                config.name(),
+                ExtensionError::ConfigError(format!("dependency cycle: {}", path.join(" -> "))),
+            ));
+            false
+        });
+        failed.extend(cycles.into_iter().map(|(key, _)| key));
+    }
+}
+
+/// The path from `start` back to itself through `graph`, if `start` is part of a cycle
+fn find_cycle(start: &str, graph: &HashMap<&str, &[String]>) -> Option<Vec<String>> {
+    fn visit(
+        node: &str,
+        start: &str,
+        graph: &HashMap<&str, &[String]>,
+        path: &mut Vec<String>,
+        seen: &mut HashSet<String>,
+    ) -> bool {
+        for dep in graph.get(node).copied().unwrap_or_default() {
+            if dep == start {
+                path.push(dep.clone());
+                return true;
+            }
+            if graph.contains_key(dep.as_str()) && seen.insert(dep.clone()) {
+                path.push(dep.clone());
+                if visit(dep, start, graph, path, seen) {
+                    return true;
+                }
+                path.pop();
+            }
+        }
+        false
+    }
+
+    let mut path = vec![start.to_string()];
+    visit(start, start, graph, &mut path, &mut HashSet::new()).then_some(path)
+}
+
 impl Default for ExtensionManager {
     fn default() -> Self {
         Self::new()
@@ -150,16 +401,20 @@ impl Default for ExtensionManager {
 }
 
 async fn child_process_client(
-    mut command: Command,
+    command: Command,
     timeout: &Option<u64>,
-) -> ExtensionResult<McpClient> {
+) -> ExtensionResult<(McpClient, ChildProcess)> {
     #[cfg(unix)]
-    command.process_group(0);
+    let command = command.configure(|command| {
+        command.process_group(0);
+    });
     #[cfg(windows)]
-    command.creation_flags(CREATE_NO_WINDOW_FLAG);
+    let command = job_object_command(command);
     let (transport, mut stderr) = TokioChildProcess::builder(command)
         .stderr(Stdio::piped())
         .spawn()?;
+    #[cfg(unix)]
+    let process_group = transport.id();
     let mut stderr = stderr.take().ok_or_else(|| {
         ExtensionError::SetupError("failed to attach child process stderr".to_owned())
     })?;
@@ -177,10 +432,17 @@ async fn child_process_client(
     .await;
 
     match client_result {
-        Ok(client) => Ok(client),
+        Ok(client) => Ok((
+            client,
+            ChildProcess {
+                #[cfg(unix)]
+                process_group,
+                stderr: stderr_task,
+            },
+        )),
         Err(error) => {
             let error_task_out = stderr_task.await?;
-            Err::<McpClient, ExtensionError>(match error_task_out {
+            Err(match error_task_out {
                 Ok(stderr_content) => ProcessExit::new(stderr_content, error).into(),
                 Err(e) => e.into(),
             })
@@ -192,6 +454,9 @@ impl ExtensionManager {
     pub fn new() -> Self {
         Self {
             extensions: Mutex::new(HashMap::new()),
+            usage: Arc::new(ToolUsageTracker::default()),
+            tool_cache: Arc::new(ToolResultCache::from_config()),
+            started: Notify::new(),
         }
     }
 
@@ -206,15 +471,17 @@ impl ExtensionManager {
     pub async fn add_extension(&self, config: ExtensionConfig) -> ExtensionResult<()> {
         let config_name = config.key().to_string();
         let sanitized_name = normalize(config_name.clone());
+        self.wait_for_dependencies(&config, DEPENDENCY_READY_TIMEOUT)
+            .await?;
         let mut temp_dir = None;
+        let mut child = None;
 
         /// Helper function to merge environment variables from direct envs and keychain-stored env_keys
         async fn merge_environments(
-            envs: &Envs,
+            mut all_envs: HashMap<String, String>,
             env_keys: &[String],
             ext_name: &str,
         ) -> Result<HashMap<String, String>, ExtensionError> {
-            let mut all_envs = envs.get_env();
             let config_instance = Config::global();
 
             for key in env_keys {
@@ -265,7 +532,7 @@ impl ExtensionManager {
             Ok(all_envs)
         }
 
-        let client: Box<dyn McpClientTrait> = match &config {
+        let client = match &config {
             ExtensionConfig::Sse { uri, timeout, .. } => {
                 let transport = SseClientTransport::start(uri.to_string()).await.map_err(
                     |transport_error| {
@@ -275,15 +542,13 @@ impl ExtensionManager {
                         )
                     },
                 )?;
-                Box::new(
-                    McpClient::connect(
-                        transport,
-                        Duration::from_secs(
-                            timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT),
-                        ),
-                    )
-                    .await?,
+                McpClient::connect(
+                    transport,
+                    Duration::from_secs(
+                        timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT),
+                    ),
                 )
+                .await?
             }
             ExtensionConfig::StreamableHttp {
                 uri,
@@ -292,15 +557,19 @@ impl ExtensionManager {
                 name,
                 ..
             } => {
+                let mut template = ExtensionTemplate::new(Config::global());
                 let mut default_headers = HeaderMap::new();
                 for (key, value) in headers {
+                    let expanded = template.expand(value)?;
+                    let mut header_value: HeaderValue = expanded.parse().map_err(|_| {
+                        ExtensionError::ConfigError(format!("invalid header value: {}", key))
+                    })?;
+                    header_value.set_sensitive(template.contains_secret(&expanded));
                     default_headers.insert(
                         HeaderName::try_from(key).map_err(|_| {
                             ExtensionError::ConfigError(format!("invalid header: {}", key))
                         })?,
-                        value.parse().map_err(|_| {
-                            ExtensionError::ConfigError(format!("invalid header value: {}", key))
-                        })?,
+                        header_value,
                     );
                 }
                 let client = reqwest::Client::builder()
@@ -350,7 +619,7 @@ impl ExtensionManager {
                 } else {
                     client_res?
                 };
-                Box::new(client)
+                client
             }
             ExtensionConfig::Stdio {
                 cmd,
@@ -360,25 +629,32 @@ impl ExtensionManager {
                 timeout,
                 ..
             } => {
+                // The stored config keeps its placeholders, so substituted secrets are never
+                // persisted or reported, just like values fetched for env_keys
+                let mut template = ExtensionTemplate::new(Config::global());
+                let cmd = template.expand(cmd)?;
+                let mut expanded_args = Vec::with_capacity(args.len());
+                for arg in args {
+                    expanded_args.push(template.expand(arg)?);
+                }
+                let args = expanded_args;
+                let envs = template.expand_values(envs.get_env())?;
+
                 let all_envs = merge_environments(envs, env_keys, &sanitized_name).await?;
-                let command = Command::new(cmd).configure(|command| {
-                    command.args(args).envs(all_envs);
+                let command = Command::new(&cmd).configure(|command| {
+                    command.args(&args).envs(all_envs);
                 });
 
                 // Check for malicious packages before launching the process
-                extension_malware_check::deny_if_malicious_cmd_args(cmd, args).await?;
+                extension_malware_check::deny_if_malicious_cmd_args(&cmd, &args).await?;
 
-                let client = child_process_client(command, timeout).await?;
-                Box::new(client)
+                let (client, process) = child_process_client(command, timeout)
+                    .await
+                    .map_err(|e| template.redact_error(e))?;
+                child = Some(process);
+                client
             }
-            ExtensionConfig::Builtin {
-                name,
-                display_name: _,
-                description: _,
-                timeout,
-                bundled: _,
-                available_tools: _,
-            } => {
+            ExtensionConfig::Builtin { name, timeout, .. } => {
                 let cmd = std::env::current_exe()
                     .expect("should find the current executable")
                     .to_str()
@@ -387,8 +663,9 @@ impl ExtensionManager {
                 let command = Command::new(cmd).configure(|command| {
                     command.arg("mcp").arg(name);
                 });
-                let client = child_process_client(command, timeout).await?;
-                Box::new(client)
+                let (client, process) = child_process_client(command, timeout).await?;
+                child = Some(process);
+                client
             }
             ExtensionConfig::InlinePython {
                 name,
@@ -412,26 +689,64 @@ impl ExtensionManager {
                     command.arg("python").arg(file_path.to_str().unwrap());
                 });
 
-                let client = child_process_client(command, timeout).await?;
+                let (client, process) = child_process_client(command, timeout).await?;
+                child = Some(process);
 
-                Box::new(client)
+                client
             }
             _ => unreachable!(),
         };
+        let mut client = client.with_limits(config.response_limits().resolve());
+        if let Some(keep_alive) = config.keep_alive() {
+            client = client.with_keep_alive(keep_alive);
+        }
+        let client: McpClientBox = Arc::new(client);
 
         let server_info = client.get_info().cloned();
-        self.add_client(
-            sanitized_name,
-            config,
-            Arc::new(Mutex::new(client)),
-            server_info,
-            temp_dir,
-        )
-        .await;
+        let extension = Extension::new(config, client, server_info, temp_dir, child);
+        self.extensions
+            .lock()
+            .await
+            .insert(sanitized_name, extension);
+        self.started.notify_waiters();
 
         Ok(())
     }
 
+    /// Wait until every extension `config` depends on is running, so it is never started
+    /// against a dependency whose client is not ready yet
+    async fn wait_for_dependencies(
+        &self,
+        config: &ExtensionConfig,
+        timeout: Duration,
+    ) -> ExtensionResult<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            // Registered before checking, so a start in between is not missed
+            let started = self.started.notified();
+            let missing: Vec<&str> = {
+                let extensions = self.extensions.lock().await;
+                config
+                    .depends_on()
+                    .iter()
+                    .filter(|dep| !extensions.contains_key(&normalize(dep.to_string())))
+                    .map(String::as_str)
+                    .collect()
+            };
+            if missing.is_empty() {
+                return Ok(());
+            }
+            if tokio::time::timeout_at(deadline, started).await.is_err() {
+                return Err(ExtensionError::SetupError(format!(
+                    "'{}' depends on {}, which did not start within {}s",
+                    config.name(),
+                    missing.join(", "),
+                    timeout.as_secs()
+                )));
+            }
+        }
+    }
+
     pub async fn add_client(
         &self,
         name: String,
@@ -443,7 +758,26 @@ impl ExtensionManager {
         self.extensions
             .lock()
             .await
-            .insert(name, Extension::new(config, client, info, temp_dir));
+            .insert(name, Extension::new(config, client, info, temp_dir, None));
+        self.started.notify_waiters();
+    }
+
+    /// Shut down every extension, waiting up to `timeout` for each to exit before killing it
+    /// along with any processes it started
+    pub async fn shutdown(&self, timeout: Duration) {
+        let extensions: Vec<Extension> = self
+            .extensions
+            .lock()
+            .await
+            .drain()
+            .map(|(_, extension)| extension)
+            .collect();
+        future::join_all(
+            extensions
+                .into_iter()
+                .map(|extension| extension.shutdown(timeout)),
+        )
+        .await;
     }
 
     /// Get extensions info
@@ -462,15 +796,33 @@ impl ExtensionManager {
             .collect()
     }
 
-    /// Get aggregated usage statistics
+    /// Get aggregated usage statistics for the tools called in this session
+    pub async fn get_usage_stats(&self) -> UsageStats {
+        let mut names: Vec<String> = self.extensions.lock().await.keys().cloned().collect();
+        names.sort();
+        self.usage.report(&names)
+    }
+
+    /// Forget the usage recorded so far, for when a new session starts
+    pub fn reset_usage_stats(&self) {
+        self.usage.reset();
+    }
+
     pub async fn remove_extension(&self, name: &str) -> ExtensionResult<()> {
         let sanitized_name = normalize(name.to_string());
-        self.extensions.lock().await.remove(&sanitized_name);
+        let extension = self.extensions.lock().await.remove(&sanitized_name);
+        self.tool_cache.invalidate_extension(&sanitized_name);
+        if let Some(extension) = extension {
+            extension.shutdown(DEFAULT_SHUTDOWN_TIMEOUT).await;
+        }
         Ok(())
     }
 
     pub async fn suggest_disable_extensions_prompt(&self) -> Value {
-        let enabled_extensions_count = self.extensions.lock().await.len();
+        let mut enabled_extensions: Vec<String> =
+            self.extensions.lock().await.keys().cloned().collect();
+        enabled_extensions.sort();
+        let enabled_extensions_count = enabled_extensions.len();
 
         let total_tools = self
             .get_prefixed_tools(None)
@@ -483,6 +835,20 @@ impl ExtensionManager {
         const MIN_TOOLS: usize = 50;
 
         if enabled_extensions_count > MIN_EXTENSIONS || total_tools > MIN_TOOLS {
+            let used = self.usage.used_extensions();
+            let unused: Vec<String> = enabled_extensions
+                .into_iter()
+                .filter(|name| !used.contains(name))
+                .collect();
+            let unused_note = if unused.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    "\n\nThese extensions have not been used in this session, \
+                    so they are good candidates to disable: {}.",
+                    unused.join(", ")
+                )
+            };
             Value::String(format!(
                 "The user currently has enabled {} extensions with a total of {} tools. \
                 Since this exceeds the recommended limits ({} extensions or {} tools), \
@@ -490,11 +856,12 @@ impl ExtensionManager {
                 Use the search_available_extensions tool to find extensions available to disable. \
                 You should only disable extensions found from the search_available_extensions tool. \
                 List all the extensions available to disable in the response. \
-                Explain that minimizing extensions helps with the recall of the correct tools to use.",
+                Explain that minimizing extensions helps with the recall of the correct tools to use.{}",
                 enabled_extensions_count,
                 total_tools,
                 MIN_EXTENSIONS,
                 MIN_TOOLS,
+                unused_note,
             ))
         } else {
             Value::String(String::new()) // Empty string if under limits
@@ -505,6 +872,24 @@ impl ExtensionManager {
         Ok(self.extensions.lock().await.keys().cloned().collect())
     }
 
+    /// Extensions whose keep-alive pings have been failing and likely need reconnecting
+    pub async fn degraded_extensions(&self) -> Vec<String> {
+        let clients: Vec<_> = self
+            .extensions
+            .lock()
+            .await
+            .iter()
+            .map(|(name, extension)| (name.clone(), extension.client.clone()))
+            .collect();
+        let mut degraded = Vec::new();
+        for (name, client) in clients {
+            if client.is_degraded() {
+                degraded.push(name);
+            }
+        }
+        degraded
+    }
+
     /// Get all tools from all clients with proper prefixing
     pub async fn get_prefixed_tools(
         &self,
@@ -531,8 +916,7 @@ impl ExtensionManager {
             let cancel_token = cancel_token.clone();
             task::spawn(async move {
                 let mut tools = Vec::new();
-                let client_guard = client.lock().await;
-                let mut client_tools = client_guard.list_tools(None, cancel_token).await?;
+                let mut client_tools = client.list_tools(None, cancel_token).await?;
 
                 loop {
                     for tool in client_tools.tools {
@@ -554,7 +938,7 @@ impl ExtensionManager {
                         break;
                     }
 
-                    client_tools = client_guard
+                    client_tools = client
                         .list_tools(client_tools.next_cursor, CancellationToken::default())
                         .await?;
                 }
@@ -576,6 +960,7 @@ impl ExtensionManager {
             }
         }
 
+        self.tool_cache.record_tools(&tools);
         Ok(tools)
     }
 
@@ -677,8 +1062,7 @@ impl ExtensionManager {
             .await
             .ok_or(ErrorData::new(ErrorCode::INVALID_PARAMS, error_msg, None))?;
 
-        let client_guard = client.lock().await;
-        let read_result = client_guard
+        let read_result = client
             .read_resource(uri, cancellation_token)
             .await
             .map_err(|_| {
@@ -717,8 +1101,7 @@ impl ExtensionManager {
                 )
             })?;
 
-        let client_guard = client.lock().await;
-        client_guard
+        client
             .list_resources(None, cancellation_token)
             .await
             .map_err(|e| {
@@ -840,17 +1223,44 @@ impl ExtensionManager {
             }
         }
 
+        let cacheable = self.tool_cache.is_cacheable(&tool_call.name);
+        if cacheable && !cancellation_token.is_cancelled() {
+            if let Some(content) = self.tool_cache.get(&tool_call.name, &tool_call.arguments) {
+                tracing::debug!("Using cached result for {}", tool_call.name);
+                return Ok(ToolCallResult::from(Ok(content)));
+            }
+        }
+
         let arguments = tool_call.arguments.clone();
         let client = client.clone();
-        let notifications_receiver = client.lock().await.subscribe().await;
+        let notifications_receiver = client.subscribe().await;
 
+        let usage = Arc::clone(&self.usage);
+        let tool_cache = Arc::clone(&self.tool_cache);
         let fut = async move {
-            let client_guard = client.lock().await;
-            client_guard
-                .call_tool(&tool_name, arguments, cancellation_token)
-                .await
-                .map(|call| call.content)
-                .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))
+            let started = Instant::now();
+            let result = client
+                .call_tool(&tool_name, arguments, cancellation_token.clone())
+                .await;
+            let success = matches!(&result, Ok(call) if call.is_error != Some(true));
+            usage.record(&client_name, &tool_name, started.elapsed(), success);
+            // A call cut short by cancellation may have returned a partial result
+            if cacheable && success && !cancellation_token.is_cancelled() {
+                if let Ok(call) = &result {
+                    tool_cache.insert(&tool_call.name, tool_call.arguments, call.content.clone());
+                }
+            }
+            result.map(|call| call.content).map_err(|e| {
+                let message = if client.is_degraded() {
+                    format!(
+                        "{} (the connection to extension '{}' appears to have dropped)",
+                        e, client_name
+                    )
+                } else {
+                    e.to_string()
+                };
+                ErrorData::new(ErrorCode::INTERNAL_ERROR, message, None)
+            })
         };
 
         Ok(ToolCallResult {
@@ -875,8 +1285,7 @@ impl ExtensionManager {
                 )
             })?;
 
-        let client_guard = client.lock().await;
-        client_guard
+        client
             .list_prompts(None, cancellation_token)
             .await
             .map_err(|e| {
@@ -886,7 +1295,7 @@ impl ExtensionManager {
                     None,
                 )
             })
-            .map(|lp| lp.prompts)
+            .map(|lp| lp.prompts.into_iter().map(with_argument_metadata).collect())
     }
 
     pub async fn list_prompts(
@@ -949,13 +1358,94 @@ impl ExtensionManager {
             .await
             .ok_or_else(|| anyhow::anyhow!("Extension {} not found", extension_name))?;
 
-        let client_guard = client.lock().await;
-        client_guard
+        client
             .get_prompt(name, arguments, cancellation_token)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to get prompt: {}", e))
     }
 
+    /// Get a prompt from an extension as messages to add to a conversation, after checking
+    /// that `arguments` has every argument the prompt requires
+    pub async fn render_prompt(
+        &self,
+        extension_name: &str,
+        name: &str,
+        arguments: Value,
+        cancellation_token: CancellationToken,
+    ) -> Result<Conversation> {
+        let prompts = self
+            .list_prompts_from_extension(extension_name, cancellation_token.clone())
+            .await
+            .map_err(|e| anyhow::anyhow!(e.message))?;
+        let Some(prompt) = prompts.iter().find(|p| p.name == name) else {
+            let available: Vec<&str> = prompts.iter().map(|p| p.name.as_str()).collect();
+            return Err(anyhow::anyhow!(
+                "Extension {} has no prompt '{}'. Available prompts: {}",
+                extension_name,
+                name,
+                if available.is_empty() {
+                    "none".to_string()
+                } else {
+                    available.join(", ")
+                }
+            ));
+        };
+
+        let arguments = match arguments {
+            Value::Null => Value::Object(Default::default()),
+            Value::Object(_) => arguments,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Arguments for prompt '{}' must be an object of name to value",
+                    name
+                ))
+            }
+        };
+        let missing: Vec<String> = prompt
+            .arguments
+            .iter()
+            .flatten()
+            .filter(|arg| arg.required == Some(true))
+            .filter(|arg| arguments.get(&arg.name).is_none_or(Value::is_null))
+            .map(|arg| match &arg.description {
+                Some(description) => format!("{} ({})", arg.name, description),
+                None => arg.name.clone(),
+            })
+            .collect();
+        if !missing.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Prompt '{}' is missing required arguments: {}",
+                name,
+                missing.join(", ")
+            ));
+        }
+
+        let result = self
+            .get_prompt(extension_name, name, arguments, cancellation_token)
+            .await?;
+        let mut messages = Vec::new();
+        for (i, prompt_message) in result.messages.into_iter().enumerate() {
+            let message = Message::from(prompt_message);
+            // Prompts go in as user and assistant turns taking turns, starting with the user
+            let expected_role = if i % 2 == 0 {
+                rmcp::model::Role::User
+            } else {
+                rmcp::model::Role::Assistant
+            };
+            if message.role != expected_role {
+                return Err(anyhow::anyhow!(
+                    "Prompt '{}' returned a {:?} message at position {}, expected {:?}",
+                    name,
+                    message.role,
+                    i,
+                    expected_role
+                ));
+            }
+            messages.push(message);
+        }
+        Ok(Conversation::new_unvalidated(messages))
+    }
+
     pub async fn search_available_extensions(&self) -> Result<Vec<Content>, ErrorData> {
         let mut output_parts = vec![];
 
@@ -1052,6 +1542,8 @@ mod tests {
     use rmcp::model::ListToolsResult;
     use rmcp::model::ReadResourceResult;
     use rmcp::model::ServerNotification;
+    use rmcp::model::ToolAnnotations;
+    use rmcp::model::{PromptMessage, PromptMessageRole};
     use serde_json::json;
     use tokio::sync::mpsc;
 
@@ -1075,8 +1567,10 @@ mod tests {
                 timeout: None,
                 bundled: None,
                 available_tools,
+                depends_on: Vec::new(),
+                response_limits: None,
             };
-            let extension = Extension::new(config, client, None, None);
+            let extension = Extension::new(config, client, None, None, None);
             self.extensions
                 .lock()
                 .await
@@ -1121,7 +1615,12 @@ mod tests {
                         name: "tool".into(),
                         description: Some("A basic tool".into()),
                         input_schema: Arc::new(json!({}).as_object().unwrap().clone()),
-                        annotations: None,
+                        annotations: Some(ToolAnnotations {
+                            read_only_hint: Some(true),
+                            idempotent_hint: Some(true),
+                            open_world_hint: Some(false),
+                            ..Default::default()
+                        }),
                         output_schema: None,
                     },
                     Tool {
@@ -1188,31 +1687,19 @@ mod tests {
 
         // Add some mock clients using the helper method
         extension_manager
-            .add_mock_extension(
-                "test_client".to_string(),
-                Arc::new(Mutex::new(Box::new(MockClient {}))),
-            )
+            .add_mock_extension("test_client".to_string(), Arc::new(MockClient {}))
             .await;
 
         extension_manager
-            .add_mock_extension(
-                "__client".to_string(),
-                Arc::new(Mutex::new(Box::new(MockClient {}))),
-            )
+            .add_mock_extension("__client".to_string(), Arc::new(MockClient {}))
             .await;
 
         extension_manager
-            .add_mock_extension(
-                "__cli__ent__".to_string(),
-                Arc::new(Mutex::new(Box::new(MockClient {}))),
-            )
+            .add_mock_extension("__cli__ent__".to_string(), Arc::new(MockClient {}))
             .await;
 
         extension_manager
-            .add_mock_extension(
-                "client 🚀".to_string(),
-                Arc::new(Mutex::new(Box::new(MockClient {}))),
-            )
+            .add_mock_extension("client 🚀".to_string(), Arc::new(MockClient {}))
             .await;
 
         // Test basic case
@@ -1248,24 +1735,15 @@ mod tests {
 
         // Add some mock clients using the helper method
         extension_manager
-            .add_mock_extension(
-                "test_client".to_string(),
-                Arc::new(Mutex::new(Box::new(MockClient {}))),
-            )
+            .add_mock_extension("test_client".to_string(), Arc::new(MockClient {}))
             .await;
 
         extension_manager
-            .add_mock_extension(
-                "__cli__ent__".to_string(),
-                Arc::new(Mutex::new(Box::new(MockClient {}))),
-            )
+            .add_mock_extension("__cli__ent__".to_string(), Arc::new(MockClient {}))
             .await;
 
         extension_manager
-            .add_mock_extension(
-                "client 🚀".to_string(),
-                Arc::new(Mutex::new(Box::new(MockClient {}))),
-            )
+            .add_mock_extension("client 🚀".to_string(), Arc::new(MockClient {}))
             .await;
 
         // verify a normal tool call
@@ -1359,6 +1837,136 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_usage_stats_and_unused_extension_prompt() {
+        let extension_manager = ExtensionManager::new();
+        for name in ["alpha", "beta", "gamma", "delta", "epsilon", "zeta"] {
+            extension_manager
+                .add_mock_extension(name.to_string(), Arc::new(MockClient {}))
+                .await;
+        }
+
+        for name in [
+            "alpha__tool",
+            "alpha__tool",
+            "alpha__missing",
+            "beta__available_tool",
+        ] {
+            let call = extension_manager
+                .dispatch_tool_call(
+                    ToolCall {
+                        name: name.to_string(),
+                        arguments: json!({}),
+                    },
+                    CancellationToken::default(),
+                )
+                .await
+                .unwrap();
+            let _ = call.result.await;
+        }
+
+        let stats = extension_manager.get_usage_stats().await;
+        assert_eq!(stats.extensions.len(), 6);
+        let alpha = &stats.extensions[0];
+        assert_eq!(alpha.name, "alpha");
+        assert_eq!(alpha.usage.invocations, 3);
+        assert_eq!(alpha.usage.failures, 1);
+        assert!(alpha.usage.last_used.is_some());
+        assert!(alpha.usage.p95_latency_ms.is_some());
+        let tools: Vec<(&str, u64, u64)> = alpha
+            .tools
+            .iter()
+            .map(|tool| {
+                (
+                    tool.name.as_str(),
+                    tool.usage.invocations,
+                    tool.usage.failures,
+                )
+            })
+            .collect();
+        assert_eq!(tools, vec![("tool", 2, 0), ("missing", 1, 1)]);
+        assert_eq!(stats.extensions[1].name, "beta");
+        assert_eq!(stats.extensions[1].usage.invocations, 1);
+        assert_eq!(stats.extensions[2].usage.invocations, 0);
+
+        let report = serde_json::to_value(&stats).unwrap();
+        assert_eq!(report["extensions"][0]["invocations"], 3);
+        assert_eq!(report["extensions"][0]["tools"][1]["failures"], 1);
+
+        let prompt = extension_manager.suggest_disable_extensions_prompt().await;
+        let prompt = prompt.as_str().unwrap();
+        assert!(prompt.contains("enabled 6 extensions"));
+        assert!(prompt.contains("good candidates to disable: delta, epsilon, gamma, zeta."));
+
+        extension_manager.reset_usage_stats();
+        let stats = extension_manager.get_usage_stats().await;
+        assert!(stats
+            .extensions
+            .iter()
+            .all(|extension| extension.usage.invocations == 0));
+        let prompt = extension_manager.suggest_disable_extensions_prompt().await;
+        assert!(prompt
+            .as_str()
+            .unwrap()
+            .contains("alpha, beta, delta, epsilon, gamma, zeta."));
+    }
+
+    #[tokio::test]
+    async fn test_cacheable_tool_results_are_reused() {
+        let extension_manager = ExtensionManager::new();
+        extension_manager
+            .add_mock_extension("alpha".to_string(), Arc::new(MockClient {}))
+            .await;
+        // Listing the tools picks up the cacheable annotation on "tool"
+        extension_manager.get_prefixed_tools(None).await.unwrap();
+
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+        for (name, arguments, token) in [
+            ("alpha__tool", json!({"x": 1}), CancellationToken::default()),
+            ("alpha__tool", json!({"x": 1}), CancellationToken::default()),
+            ("alpha__tool", json!({"x": 2}), CancellationToken::default()),
+            // A cancelled call is never answered from the cache
+            ("alpha__tool", json!({"x": 1}), cancelled.clone()),
+            (
+                "alpha__available_tool",
+                json!({}),
+                CancellationToken::default(),
+            ),
+            (
+                "alpha__available_tool",
+                json!({}),
+                CancellationToken::default(),
+            ),
+        ] {
+            let call = extension_manager
+                .dispatch_tool_call(
+                    ToolCall {
+                        name: name.to_string(),
+                        arguments,
+                    },
+                    token,
+                )
+                .await
+                .unwrap();
+            assert!(call.result.await.is_ok());
+        }
+
+        let stats = extension_manager.get_usage_stats().await;
+        let calls: Vec<(&str, u64)> = stats.extensions[0]
+            .tools
+            .iter()
+            .map(|tool| (tool.name.as_str(), tool.usage.invocations))
+            .collect();
+        assert_eq!(calls, vec![("tool", 3), ("available_tool", 2)]);
+
+        extension_manager.remove_extension("alpha").await.unwrap();
+        assert!(extension_manager
+            .tool_cache
+            .get("alpha__tool", &json!({"x": 1}))
+            .is_none());
+    }
+
     #[tokio::test]
     async fn test_tool_availability_filtering() {
         let extension_manager = ExtensionManager::new();
@@ -1369,7 +1977,7 @@ mod tests {
         extension_manager
             .add_mock_extension_with_tools(
                 "test_extension".to_string(),
-                Arc::new(Mutex::new(Box::new(MockClient {}))),
+                Arc::new(MockClient {}),
                 available_tools,
             )
             .await;
@@ -1394,7 +2002,7 @@ mod tests {
         extension_manager
             .add_mock_extension_with_tools(
                 "test_extension".to_string(),
-                Arc::new(Mutex::new(Box::new(MockClient {}))),
+                Arc::new(MockClient {}),
                 vec![], // Empty available_tools means all tools are available by default
             )
             .await;
@@ -1421,7 +2029,7 @@ mod tests {
         extension_manager
             .add_mock_extension_with_tools(
                 "test_extension".to_string(),
-                Arc::new(Mutex::new(Box::new(MockClient {}))),
+                Arc::new(MockClient {}),
                 available_tools,
             )
             .await;
@@ -1457,4 +2065,357 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[cfg(unix)]
+    fn process_running(pid: &str) -> bool {
+        let output = std::process::Command::new("ps")
+            .args(["-o", "stat=", "-p", pid])
+            .output()
+            .unwrap();
+        let stat = String::from_utf8_lossy(&output.stdout);
+        // Exited children of other processes may linger as zombies until reaped
+        !stat.trim().is_empty() && !stat.trim().starts_with('Z')
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_shutdown_reaps_child_processes() {
+        let dir = tempdir().unwrap();
+        let pid_file = dir.path().join("pids");
+        let script = dir.path().join("server.sh");
+        // Answers initialize, starts a grandchild, then ignores stdin closing
+        std::fs::write(
+            &script,
+            format!(
+                r#"read -r line
+id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+sleep 600 &
+echo "$$ $!" > '{}'
+printf '{{"jsonrpc":"2.0","id":%s,"result":{{"protocolVersion":"2025-03-26","capabilities":{{}},"serverInfo":{{"name":"fake","version":"0"}}}}}}\n' "$id"
+exec sleep 600
+"#,
+                pid_file.display()
+            ),
+        )
+        .unwrap();
+
+        let extension_manager = ExtensionManager::new();
+        extension_manager
+            .add_extension(ExtensionConfig::Stdio {
+                name: "fake".to_string(),
+                cmd: "sh".to_string(),
+                args: vec![script.to_string_lossy().to_string()],
+                envs: Default::default(),
+                env_keys: vec![],
+                timeout: Some(10),
+                description: None,
+                bundled: None,
+                available_tools: vec![],
+                depends_on: Vec::new(),
+                response_limits: None,
+            })
+            .await
+            .unwrap();
+
+        let pids = std::fs::read_to_string(&pid_file).unwrap();
+        let pids: Vec<&str> = pids.split_whitespace().collect();
+        assert_eq!(pids.len(), 2);
+        assert!(pids.iter().all(|pid| process_running(pid)));
+
+        extension_manager.shutdown(Duration::from_secs(1)).await;
+
+        assert!(extension_manager
+            .list_extensions()
+            .await
+            .unwrap()
+            .is_empty());
+        for pid in pids {
+            assert!(!process_running(pid), "process {} is still running", pid);
+        }
+    }
+
+    fn builtin_with_deps(name: &str, depends_on: &[&str]) -> ExtensionConfig {
+        ExtensionConfig::Builtin {
+            name: name.to_string(),
+            display_name: None,
+            description: None,
+            timeout: None,
+            bundled: None,
+            available_tools: vec![],
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            response_limits: None,
+        }
+    }
+
+    fn wave_names(plan: &StartupPlan) -> Vec<Vec<String>> {
+        plan.waves
+            .iter()
+            .map(|wave| {
+                let mut names: Vec<String> = wave.iter().map(|c| c.name()).collect();
+                names.sort();
+                names
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_startup_orders_by_dependencies() {
+        let plan = plan_startup(
+            vec![
+                builtin_with_deps("app", &["database", "Auth Proxy"]),
+                builtin_with_deps("auth proxy", &["database"]),
+                builtin_with_deps("database", &[]),
+                builtin_with_deps("notes", &[]),
+                builtin_with_deps("search", &["developer"]),
+            ],
+            &["developer".to_string()],
+        );
+
+        assert!(plan.rejected.is_empty(), "{:?}", plan.rejected);
+        assert_eq!(
+            wave_names(&plan),
+            vec![
+                vec!["database", "notes", "search"],
+                vec!["auth proxy"],
+                vec!["app"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_startup_rejects_cycles_and_unknown_dependencies() {
+        let plan = plan_startup(
+            vec![
+                builtin_with_deps("a", &["b"]),
+                builtin_with_deps("b", &["a"]),
+                builtin_with_deps("c", &["a"]),
+                builtin_with_deps("d", &["missing"]),
+                builtin_with_deps("e", &[]),
+            ],
+            &[],
+        );
+
+        assert_eq!(wave_names(&plan), vec![vec!["e"]]);
+        let rejected: HashMap<String, String> = plan
+            .rejected
+            .into_iter()
+            .map(|(name, e)| (name, e.to_string()))
+            .collect();
+        assert_eq!(rejected.len(), 4);
+        assert!(rejected["a"].contains("dependency cycle: a -> b -> a"));
+        assert!(rejected["b"].contains("dependency cycle: b -> a -> b"));
+        assert!(rejected["c"].contains("'c' depends on 'a', which cannot be started"));
+        assert!(rejected["d"].contains("'d' depends on 'missing', which is not enabled"));
+    }
+
+    #[tokio::test]
+    async fn test_extension_waits_for_its_dependencies() {
+        let extension_manager = Arc::new(ExtensionManager::new());
+        let dependent = builtin_with_deps("app", &["Database"]);
+
+        let waiting = {
+            let extension_manager = extension_manager.clone();
+            let dependent = dependent.clone();
+            tokio::spawn(async move {
+                extension_manager
+                    .wait_for_dependencies(&dependent, Duration::from_secs(10))
+                    .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        extension_manager
+            .add_client(
+                "database".to_string(),
+                builtin_with_deps("database", &[]),
+                Arc::new(MockClient {}),
+                None,
+                None,
+            )
+            .await;
+        waiting.await.unwrap().unwrap();
+
+        let err = extension_manager
+            .wait_for_dependencies(
+                &builtin_with_deps("other", &["cache"]),
+                Duration::from_millis(50),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("'other' depends on cache"));
+    }
+
+    /// Offers one prompt, `summarize`, taking a required `topic` and an optional `tone`
+    struct PromptClient {}
+
+    #[async_trait::async_trait]
+    impl McpClientTrait for PromptClient {
+        fn get_info(&self) -> Option<&InitializeResult> {
+            None
+        }
+
+        async fn list_resources(
+            &self,
+            _next_cursor: Option<String>,
+            _cancellation_token: CancellationToken,
+        ) -> Result<ListResourcesResult, Error> {
+            Err(Error::TransportClosed)
+        }
+
+        async fn read_resource(
+            &self,
+            _uri: &str,
+            _cancellation_token: CancellationToken,
+        ) -> Result<ReadResourceResult, Error> {
+            Err(Error::TransportClosed)
+        }
+
+        async fn list_tools(
+            &self,
+            _next_cursor: Option<String>,
+            _cancellation_token: CancellationToken,
+        ) -> Result<ListToolsResult, Error> {
+            Ok(ListToolsResult {
+                tools: vec![],
+                next_cursor: None,
+            })
+        }
+
+        async fn call_tool(
+            &self,
+            _name: &str,
+            _arguments: Value,
+            _cancellation_token: CancellationToken,
+        ) -> Result<CallToolResult, Error> {
+            Err(Error::TransportClosed)
+        }
+
+        async fn list_prompts(
+            &self,
+            _next_cursor: Option<String>,
+            _cancellation_token: CancellationToken,
+        ) -> Result<ListPromptsResult, Error> {
+            Ok(ListPromptsResult {
+                prompts: vec![Prompt::new(
+                    "summarize",
+                    Some("Summarize a topic"),
+                    Some(vec![
+                        PromptArgument {
+                            name: "topic".to_string(),
+                            description: Some("What to summarize".to_string()),
+                            required: Some(true),
+                        },
+                        PromptArgument {
+                            name: "tone".to_string(),
+                            description: None,
+                            required: None,
+                        },
+                    ]),
+                )],
+                next_cursor: None,
+            })
+        }
+
+        async fn get_prompt(
+            &self,
+            _name: &str,
+            arguments: Value,
+            _cancellation_token: CancellationToken,
+        ) -> Result<GetPromptResult, Error> {
+            let tone = arguments["tone"].as_str().unwrap_or("plain");
+            Ok(GetPromptResult {
+                description: None,
+                messages: vec![
+                    PromptMessage::new_text(
+                        PromptMessageRole::User,
+                        format!("Summarize {} in a {} tone", arguments["topic"], tone),
+                    ),
+                    PromptMessage::new_text(PromptMessageRole::Assistant, "Sure, here goes."),
+                ],
+            })
+        }
+
+        async fn subscribe(&self) -> mpsc::Receiver<ServerNotification> {
+            mpsc::channel(1).1
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prompts_list_argument_metadata() {
+        let extension_manager = ExtensionManager::new();
+        extension_manager
+            .add_mock_extension("notes".to_string(), Arc::new(PromptClient {}))
+            .await;
+
+        let prompts = extension_manager
+            .list_prompts_from_extension("notes", CancellationToken::default())
+            .await
+            .unwrap();
+        let arguments = prompts[0].arguments.as_ref().unwrap();
+        let metadata: Vec<_> = arguments
+            .iter()
+            .map(|arg| (arg.name.as_str(), arg.description.as_deref(), arg.required))
+            .collect();
+        assert_eq!(
+            metadata,
+            vec![
+                ("topic", Some("What to summarize"), Some(true)),
+                ("tone", None, Some(false)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_render_prompt() {
+        let extension_manager = ExtensionManager::new();
+        extension_manager
+            .add_mock_extension("notes".to_string(), Arc::new(PromptClient {}))
+            .await;
+        let render = |arguments: Value| {
+            extension_manager.render_prompt(
+                "notes",
+                "summarize",
+                arguments,
+                CancellationToken::default(),
+            )
+        };
+
+        let conversation = render(json!({"topic": "the release"})).await.unwrap();
+        let messages = conversation.messages();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, rmcp::model::Role::User);
+        assert_eq!(
+            messages[0].as_concat_text(),
+            "Summarize \"the release\" in a plain tone"
+        );
+        assert_eq!(messages[1].role, rmcp::model::Role::Assistant);
+
+        let conversation = render(json!({"topic": "bugs", "tone": "dry"}))
+            .await
+            .unwrap();
+        assert!(conversation.messages()[0]
+            .as_concat_text()
+            .ends_with("in a dry tone"));
+
+        // The optional argument can be left out, the required one can't
+        let err = render(json!({"tone": "dry"})).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Prompt 'summarize' is missing required arguments: topic (What to summarize)"
+        );
+        let err = render(Value::Null).await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("missing required arguments: topic"));
+
+        let err = extension_manager
+            .render_prompt("notes", "nope", json!({}), CancellationToken::default())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Extension notes has no prompt 'nope'. Available prompts: summarize"
+        );
+    }
 }
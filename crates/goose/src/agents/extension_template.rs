@@ -0,0 +1,238 @@
+//! Placeholder expansion for extension launch settings
+//!
+//! `${NAME}` is replaced with the environment variable `NAME` and `${config:key}` with the
+//! config value `key`, falling back to the secret store. `$$` is a literal `$`, and a `$` not
+//! followed by `{` or `$` is left as written.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::extension::{ExtensionError, ExtensionResult};
+use crate::config::{Config, ConfigError};
+
+const CONFIG_PREFIX: &str = "config:";
+const SECRET_PLACEHOLDER: &str = "[REDACTED]";
+
+/// Expands placeholders, remembering the values that came from the secret store so they can
+/// be kept out of anything reported back, as with `env_keys`
+pub struct ExtensionTemplate<'a> {
+    config: &'a Config,
+    secrets: Vec<String>,
+}
+
+#[allow(clippy::result_large_err)]
+impl<'a> ExtensionTemplate<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        Self {
+            config,
+            secrets: Vec::new(),
+        }
+    }
+
+    pub fn expand(&mut self, template: &str) -> ExtensionResult<String> {
+        let mut expanded = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('$') {
+            expanded.push_str(&rest[..start]);
+            rest = &rest[start..];
+            if let Some(after) = rest.strip_prefix("$$") {
+                expanded.push('$');
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("${") {
+                let end = after.find('}').ok_or_else(|| {
+                    ExtensionError::ConfigError(format!(
+                        "unterminated placeholder in '{}'",
+                        template
+                    ))
+                })?;
+                expanded.push_str(&self.resolve(&after[..end])?);
+                rest = &after[end + 1..];
+            } else {
+                expanded.push('$');
+                rest = &rest[1..];
+            }
+        }
+        expanded.push_str(rest);
+        Ok(expanded)
+    }
+
+    /// Expand every value of the map, leaving the keys as they are
+    pub fn expand_values(
+        &mut self,
+        values: HashMap<String, String>,
+    ) -> ExtensionResult<HashMap<String, String>> {
+        values
+            .into_iter()
+            .map(|(key, value)| Ok((key, self.expand(&value)?)))
+            .collect()
+    }
+
+    /// Whether `value` holds a secret substituted by this template
+    pub fn contains_secret(&self, value: &str) -> bool {
+        self.secrets.iter().any(|secret| value.contains(secret))
+    }
+
+    /// Mask substituted secrets, e.g. in stderr from a process that echoes its arguments
+    pub fn redact(&self, text: &str) -> String {
+        self.secrets.iter().fold(text.to_string(), |text, secret| {
+            text.replace(secret, SECRET_PLACEHOLDER)
+        })
+    }
+
+    pub fn redact_error(&self, error: ExtensionError) -> ExtensionError {
+        if self.secrets.is_empty() {
+            return error;
+        }
+        match error {
+            ExtensionError::ProcessExit(exit) => {
+                ExtensionError::ProcessExit(exit.map_stderr(|stderr| self.redact(stderr)))
+            }
+            error => error,
+        }
+    }
+
+    fn resolve(&mut self, name: &str) -> ExtensionResult<String> {
+        let unresolved = |what: &str| {
+            ExtensionError::ConfigError(format!("unresolved placeholder ${{{}}}: {}", name, what))
+        };
+
+        let Some(key) = name.strip_prefix(CONFIG_PREFIX) else {
+            let valid = name.chars().next().is_some_and(|c| !c.is_ascii_digit())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                return Err(unresolved("not a valid environment variable name"));
+            }
+            return std::env::var(name).map_err(|_| unresolved("environment variable is not set"));
+        };
+
+        if key.is_empty() {
+            return Err(unresolved("missing config key"));
+        }
+        let (value, secret) = match self.config.get_param::<Value>(key) {
+            Ok(value) => (value, false),
+            Err(ConfigError::NotFound(_)) => match self.config.get_secret::<Value>(key) {
+                Ok(value) => (value, true),
+                Err(ConfigError::NotFound(_)) => return Err(unresolved("config key not found")),
+                Err(e) => return Err(unresolved(&e.to_string())),
+            },
+            Err(e) => return Err(unresolved(&e.to_string())),
+        };
+
+        let value = match value {
+            Value::String(value) => value,
+            Value::Number(_) | Value::Bool(_) => value.to_string(),
+            _ => return Err(unresolved("config value is not a string, number or bool")),
+        };
+        if secret && !value.is_empty() {
+            self.secrets.push(value.clone());
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::NamedTempFile;
+
+    fn config() -> (Config, NamedTempFile, NamedTempFile) {
+        let config_file = NamedTempFile::new().unwrap();
+        let secrets_file = NamedTempFile::new().unwrap();
+        let config =
+            Config::new_with_file_secrets(config_file.path(), secrets_file.path()).unwrap();
+        (config, config_file, secrets_file)
+    }
+
+    fn error_message(result: ExtensionResult<String>) -> String {
+        match result {
+            Err(ExtensionError::ConfigError(message)) => message,
+            other => panic!("expected a config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_env_expansion() {
+        let (config, _config_file, _secrets_file) = config();
+        temp_env::with_var("GOOSE_TEMPLATE_TEST_ROOT", Some("/work/project"), || {
+            let mut template = ExtensionTemplate::new(&config);
+            assert_eq!(
+                template
+                    .expand("--root ${GOOSE_TEMPLATE_TEST_ROOT}/src")
+                    .unwrap(),
+                "--root /work/project/src"
+            );
+            // Bare $NAME isn't a placeholder
+            assert_eq!(
+                template.expand("$GOOSE_TEMPLATE_TEST_ROOT").unwrap(),
+                "$GOOSE_TEMPLATE_TEST_ROOT"
+            );
+            assert!(!template.contains_secret("/work/project"));
+        });
+    }
+
+    #[test]
+    fn test_config_expansion() {
+        let (config, _config_file, _secrets_file) = config();
+        config.set_param("template_test_port", json!(8080)).unwrap();
+        config
+            .set_secret("template_test_token", json!("tok-123456"))
+            .unwrap();
+
+        let mut template = ExtensionTemplate::new(&config);
+        assert_eq!(
+            template
+                .expand("--port=${config:template_test_port}")
+                .unwrap(),
+            "--port=8080"
+        );
+        let header = template
+            .expand("Bearer ${config:template_test_token}")
+            .unwrap();
+        assert_eq!(header, "Bearer tok-123456");
+
+        // Values from the secret store are tracked for redaction
+        assert!(template.contains_secret(&header));
+        assert_eq!(
+            template.redact("auth failed for tok-123456"),
+            "auth failed for [REDACTED]"
+        );
+
+        let envs = HashMap::from([(
+            "PORT".to_string(),
+            "${config:template_test_port}".to_string(),
+        )]);
+        assert_eq!(template.expand_values(envs).unwrap()["PORT"], "8080");
+    }
+
+    #[test]
+    fn test_missing_variables() {
+        let (config, _config_file, _secrets_file) = config();
+        let mut template = ExtensionTemplate::new(&config);
+
+        temp_env::with_var_unset("GOOSE_TEMPLATE_TEST_MISSING", || {
+            let message = error_message(template.expand("${GOOSE_TEMPLATE_TEST_MISSING}"));
+            assert!(message.contains("${GOOSE_TEMPLATE_TEST_MISSING}"));
+        });
+        let message = error_message(template.expand("${config:template_test_missing}"));
+        assert!(message.contains("${config:template_test_missing}"));
+        assert!(message.contains("not found"));
+
+        assert!(error_message(template.expand("${NOT-A-VAR}")).contains("valid"));
+        assert!(error_message(template.expand("--root ${HOME")).contains("unterminated"));
+    }
+
+    #[test]
+    fn test_escaping() {
+        let (config, _config_file, _secrets_file) = config();
+        let mut template = ExtensionTemplate::new(&config);
+
+        assert_eq!(template.expand("$${HOME}").unwrap(), "${HOME}");
+        assert_eq!(
+            template.expand("cost: $$5 or $5").unwrap(),
+            "cost: $5 or $5"
+        );
+        assert_eq!(template.expand("trailing $").unwrap(), "trailing $");
+    }
+}
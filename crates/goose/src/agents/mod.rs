@@ -1,8 +1,10 @@
 mod agent;
+pub mod audit_log;
 mod context;
 pub mod extension;
 pub mod extension_malware_check;
 pub mod extension_manager;
+pub mod extension_template;
 pub mod final_output_tool;
 mod large_response_handler;
 pub mod model_selector;
@@ -10,6 +12,7 @@ pub mod platform_tools;
 pub mod prompt_manager;
 pub mod recipe_tools;
 mod reply_parts;
+pub mod reply_stream;
 pub mod retry;
 mod router_tool_selector;
 mod router_tools;
@@ -20,15 +23,19 @@ pub mod subagent_execution_tool;
 pub mod subagent_handler;
 mod subagent_task_config;
 pub mod todo_tools;
+pub mod tool_cache;
 mod tool_execution;
 mod tool_route_manager;
 mod tool_router_index_manager;
+pub mod tool_usage;
 pub mod types;
 
-pub use agent::{Agent, AgentEvent};
+pub use agent::{Agent, AgentEvent, MAX_TURNS_REACHED_MESSAGE, PROVIDER_ERROR_PREFIX};
 pub use extension::ExtensionConfig;
 pub use extension_manager::ExtensionManager;
 pub use prompt_manager::PromptManager;
+pub use reply_stream::ReplyEvent;
 pub use subagent::{SubAgent, SubAgentProgress, SubAgentStatus};
 pub use subagent_task_config::TaskConfig;
+pub use tool_execution::runs_sequentially;
 pub use types::{FrontendTool, RetryConfig, SessionConfig, SuccessCheck};
@@ -0,0 +1,228 @@
+//! A finer grained view of the agent's reply, for UIs that render text as it arrives.
+//!
+//! [`Agent::reply`] yields whole [`Message`]s, and with a streaming provider each of them holds
+//! just the latest chunk of the response. [`Agent::reply_stream`] splits those into the events
+//! below, and keeps the chunks coalesced into complete messages the same way
+//! [`Conversation::push`] does, so callers don't have to.
+
+use anyhow::Result;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use rmcp::model::{Role, ServerNotification};
+use tokio_util::sync::CancellationToken;
+
+use crate::agents::types::SessionConfig;
+use crate::agents::{Agent, AgentEvent};
+use crate::conversation::message::{Message, MessageContent, ToolRequest, ToolResponse};
+use crate::conversation::Conversation;
+
+/// One step of a streamed reply
+#[derive(Clone, Debug)]
+pub enum ReplyEvent {
+    /// Assistant text to append to the message with `message_id`. Providers that stream send
+    /// a response as many of these, often a few tokens each.
+    Text {
+        message_id: Option<String>,
+        text: String,
+    },
+    /// Model reasoning to append to the message with `message_id`
+    Thinking {
+        message_id: Option<String>,
+        text: String,
+    },
+    /// The model asked for a tool call
+    ToolRequest(ToolRequest),
+    /// A notification sent by the extension while the tool call `request_id` runs, such as
+    /// progress or log output
+    ToolNotification {
+        request_id: String,
+        notification: ServerNotification,
+    },
+    /// A tool call finished, successfully or not
+    ToolResponse(ToolResponse),
+    /// Content with no finer grained event, such as a tool confirmation request, in a message
+    /// of its own
+    Message(Message),
+    /// The conversation was replaced, e.g. after it was compacted
+    HistoryReplaced(Vec<Message>),
+    /// The model answering changed
+    ModelChange { model: String, mode: String },
+    /// The reply is over. Holds the messages added during it with streamed chunks merged, as
+    /// they would be stored in the session.
+    Finished(Conversation),
+}
+
+/// Turns [`AgentEvent`]s into [`ReplyEvent`]s, merging streamed chunks as it goes
+#[derive(Default)]
+pub struct ReplyCoalescer {
+    messages: Conversation,
+}
+
+impl ReplyCoalescer {
+    pub fn push(&mut self, event: AgentEvent) -> Vec<ReplyEvent> {
+        match event {
+            AgentEvent::Message(message) => {
+                let events = Self::split(&message);
+                self.messages.push(message);
+                events
+            }
+            AgentEvent::McpNotification((request_id, notification)) => {
+                vec![ReplyEvent::ToolNotification {
+                    request_id,
+                    notification,
+                }]
+            }
+            AgentEvent::ModelChange { model, mode } => {
+                vec![ReplyEvent::ModelChange { model, mode }]
+            }
+            AgentEvent::HistoryReplaced(messages) => {
+                self.messages = Conversation::new_unvalidated(messages.clone());
+                vec![ReplyEvent::HistoryReplaced(messages)]
+            }
+        }
+    }
+
+    /// The messages seen so far, with streamed chunks merged
+    pub fn messages(&self) -> &Conversation {
+        &self.messages
+    }
+
+    pub fn finish(self) -> ReplyEvent {
+        ReplyEvent::Finished(self.messages)
+    }
+
+    fn split(message: &Message) -> Vec<ReplyEvent> {
+        let mut events = Vec::new();
+        let mut rest = Vec::new();
+        for content in &message.content {
+            match content {
+                MessageContent::Text(text) if message.role == Role::Assistant => {
+                    events.push(ReplyEvent::Text {
+                        message_id: message.id.clone(),
+                        text: text.text.clone(),
+                    });
+                }
+                MessageContent::Thinking(thinking) => events.push(ReplyEvent::Thinking {
+                    message_id: message.id.clone(),
+                    text: thinking.thinking.clone(),
+                }),
+                MessageContent::ToolRequest(request) => {
+                    events.push(ReplyEvent::ToolRequest(request.clone()))
+                }
+                MessageContent::ToolResponse(response) => {
+                    events.push(ReplyEvent::ToolResponse(response.clone()))
+                }
+                other => rest.push(other.clone()),
+            }
+        }
+        if !rest.is_empty() {
+            events.push(ReplyEvent::Message(Message {
+                content: rest,
+                ..message.clone()
+            }));
+        }
+        events
+    }
+}
+
+impl Agent {
+    /// Like [`Agent::reply`], but yields the reply as [`ReplyEvent`]s, ending with
+    /// [`ReplyEvent::Finished`] unless it fails
+    pub async fn reply_stream(
+        &self,
+        conversation: Conversation,
+        session: Option<SessionConfig>,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<BoxStream<'_, Result<ReplyEvent>>> {
+        let mut reply = self.reply(conversation, session, cancel_token).await?;
+        Ok(Box::pin(async_stream::try_stream! {
+            let mut coalescer = ReplyCoalescer::default();
+            while let Some(event) = reply.next().await {
+                for event in coalescer.push(event?) {
+                    yield event;
+                }
+            }
+            yield coalescer.finish();
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::{Content, LoggingLevel, LoggingMessageNotificationParam};
+    use serde_json::json;
+
+    fn chunk(id: &str, text: &str) -> AgentEvent {
+        AgentEvent::Message(Message::assistant().with_id(id).with_text(text))
+    }
+
+    #[test]
+    fn test_text_chunks_are_coalesced() {
+        let mut coalescer = ReplyCoalescer::default();
+        let mut texts = Vec::new();
+        for piece in ["Hel", "lo ", "world"] {
+            for event in coalescer.push(chunk("msg_1", piece)) {
+                match event {
+                    ReplyEvent::Text { message_id, text } => {
+                        assert_eq!(message_id.as_deref(), Some("msg_1"));
+                        texts.push(text);
+                    }
+                    other => panic!("unexpected event {:?}", other),
+                }
+            }
+        }
+        assert_eq!(texts, vec!["Hel", "lo ", "world"]);
+
+        let ReplyEvent::Finished(messages) = coalescer.finish() else {
+            panic!("expected the reply to finish");
+        };
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages.messages()[0].as_concat_text(), "Hello world");
+    }
+
+    #[test]
+    fn test_tool_calls_and_notifications() {
+        let mut coalescer = ReplyCoalescer::default();
+        let request = Message::assistant()
+            .with_id("msg_1")
+            .with_text("Listing files")
+            .with_tool_request(
+                "call_1",
+                Ok(mcp_core::ToolCall::new(
+                    "developer__shell",
+                    json!({"command": "ls"}),
+                )),
+            );
+        let events = coalescer.push(AgentEvent::Message(request));
+        assert!(matches!(events[0], ReplyEvent::Text { .. }));
+        assert!(matches!(&events[1], ReplyEvent::ToolRequest(r) if r.id == "call_1"));
+
+        let notification = ServerNotification::LoggingMessageNotification(
+            rmcp::model::Notification::new(LoggingMessageNotificationParam {
+                level: LoggingLevel::Info,
+                logger: None,
+                data: json!("running"),
+            }),
+        );
+        let events = coalescer.push(AgentEvent::McpNotification((
+            "call_1".to_string(),
+            notification,
+        )));
+        assert!(
+            matches!(&events[0], ReplyEvent::ToolNotification { request_id, .. } if request_id == "call_1")
+        );
+
+        let response = Message::user()
+            .with_id("msg_2")
+            .with_tool_response("call_1", Ok(vec![Content::text("a.rs")]));
+        let events = coalescer.push(AgentEvent::Message(response));
+        assert!(matches!(&events[0], ReplyEvent::ToolResponse(r) if r.id == "call_1"));
+
+        let events = coalescer.push(AgentEvent::Message(
+            Message::assistant().with_context_length_exceeded("too long"),
+        ));
+        assert!(matches!(&events[0], ReplyEvent::Message(m) if m.content.len() == 1));
+        assert_eq!(coalescer.messages().len(), 3);
+    }
+}
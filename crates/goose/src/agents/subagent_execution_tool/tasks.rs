@@ -91,6 +91,8 @@ async fn handle_inline_recipe_task(
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
+    // Each task runs as its own subtask session
+    task_config.id = task.id.clone();
     task_config.extensions = recipe.extensions.clone();
 
     let instruction = recipe
@@ -1,5 +1,11 @@
+use crate::agents::extension_manager::DEFAULT_SHUTDOWN_TIMEOUT;
 use crate::agents::subagent::SubAgent;
 use crate::agents::subagent_task_config::TaskConfig;
+use crate::execution::manager::{
+    ScheduleError, SessionScheduler, SubTaskResult, SubTaskResults, SubTaskStatus,
+};
+use crate::execution::SessionExecutionMode;
+use crate::providers::base::Usage;
 use anyhow::Result;
 use rmcp::model::{ErrorCode, ErrorData};
 
@@ -11,12 +17,102 @@ pub async fn run_complete_subagent_task(
     run_complete_subagent_task_with_options(text_instruction, task_config, false).await
 }
 
-/// Standalone function to run a complete subagent task with output options
+/// Standalone function to run a complete subagent task with output options. When the task
+/// has a parent session, its result is also published to [`SubTaskResults::global`] for the
+/// parent to collect.
 pub async fn run_complete_subagent_task_with_options(
     text_instruction: String,
     task_config: TaskConfig,
     return_last_only: bool,
 ) -> Result<String, anyhow::Error> {
+    let publisher = task_config
+        .parent_session_id
+        .clone()
+        .map(|parent| ResultPublisher::new(parent, task_config.id.clone()));
+    let result = run_subagent(text_instruction, task_config, return_last_only).await;
+    if let Some(publisher) = publisher {
+        match &result {
+            Ok(text) => publisher.publish(SubTaskStatus::Completed, text.clone()),
+            Err(e)
+                if matches!(
+                    e.downcast_ref::<ScheduleError>(),
+                    Some(ScheduleError::Cancelled(_))
+                ) =>
+            {
+                publisher.publish(SubTaskStatus::Cancelled, e.to_string())
+            }
+            Err(e) => publisher.publish(SubTaskStatus::Failed, e.to_string()),
+        }
+    }
+    result
+}
+
+/// Publishes a subagent's result to its parent session, as cancelled if the subagent is
+/// dropped before it finishes
+struct ResultPublisher {
+    parent_session: String,
+    session_id: String,
+    published: bool,
+}
+
+impl ResultPublisher {
+    fn new(parent_session: String, session_id: String) -> Self {
+        Self {
+            parent_session,
+            session_id,
+            published: false,
+        }
+    }
+
+    fn publish(mut self, status: SubTaskStatus, summary: String) {
+        self.send(status, summary);
+    }
+
+    fn send(&mut self, status: SubTaskStatus, summary: String) {
+        self.published = true;
+        SubTaskResults::global().publish(
+            &self.parent_session,
+            SubTaskResult {
+                session_id: self.session_id.clone(),
+                status,
+                summary,
+                resource_uris: Vec::new(),
+                usage: Usage::default(),
+            },
+        );
+    }
+}
+
+impl Drop for ResultPublisher {
+    fn drop(&mut self) {
+        if !self.published {
+            self.send(
+                SubTaskStatus::Cancelled,
+                "Subtask was stopped before it finished".to_string(),
+            );
+        }
+    }
+}
+
+async fn run_subagent(
+    text_instruction: String,
+    task_config: TaskConfig,
+    return_last_only: bool,
+) -> Result<String, anyhow::Error> {
+    // Wait for a slot under the parent session's subtask limit, and hold it until the subagent
+    // is done
+    let _permit = match &task_config.parent_session_id {
+        Some(parent) => Some(
+            SessionScheduler::global()
+                .acquire(
+                    task_config.id.clone(),
+                    SessionExecutionMode::task(parent.clone()),
+                )
+                .await?,
+        ),
+        None => None,
+    };
+
     // Create the subagent with the parent agent's provider
     let subagent = SubAgent::new(task_config.clone()).await.map_err(|e| {
         ErrorData::new(
@@ -26,10 +122,15 @@ pub async fn run_complete_subagent_task_with_options(
         )
     })?;
 
-    // Execute the subagent task
-    let messages = subagent
-        .reply_subagent(text_instruction, task_config)
-        .await?;
+    // Execute the subagent task, then stop the extensions it started whether or not it succeeded
+    let result = subagent.reply_subagent(text_instruction, task_config).await;
+    subagent
+        .extension_manager
+        .read()
+        .await
+        .shutdown(DEFAULT_SHUTDOWN_TIMEOUT)
+        .await;
+    let messages = result?;
 
     // Extract text content based on return_last_only flag
     let response_text = if return_last_only {
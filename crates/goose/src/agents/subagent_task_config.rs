@@ -14,6 +14,8 @@ pub const GOOSE_SUBAGENT_MAX_TURNS_ENV_VAR: &str = "GOOSE_SUBAGENT_MAX_TURNS";
 #[derive(Clone)]
 pub struct TaskConfig {
     pub id: String,
+    /// The session that spawned the task, whose subtask limit it counts against
+    pub parent_session_id: Option<String>,
     pub provider: Option<Arc<dyn Provider>>,
     pub max_turns: Option<usize>,
     pub extensions: Option<Vec<crate::agents::extension::ExtensionConfig>>,
@@ -23,6 +25,7 @@ impl fmt::Debug for TaskConfig {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("TaskConfig")
             .field("id", &self.id)
+            .field("parent_session_id", &self.parent_session_id)
             .field("provider", &"<dyn Provider>")
             .field("max_turns", &self.max_turns)
             .field("extensions", &self.extensions)
@@ -35,6 +38,7 @@ impl TaskConfig {
     pub fn new(provider: Option<Arc<dyn Provider>>) -> Self {
         Self {
             id: Uuid::new_v4().to_string(),
+            parent_session_id: None,
             provider,
             max_turns: Some(
                 env::var(GOOSE_SUBAGENT_MAX_TURNS_ENV_VAR)
@@ -46,6 +50,11 @@ impl TaskConfig {
         }
     }
 
+    pub fn with_parent_session(mut self, parent_session_id: Option<String>) -> Self {
+        self.parent_session_id = parent_session_id;
+        self
+    }
+
     /// Get a reference to the provider
     pub fn provider(&self) -> Option<&Arc<dyn Provider>> {
         self.provider.as_ref()
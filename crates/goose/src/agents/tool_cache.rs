@@ -0,0 +1,333 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rmcp::model::{Content, Tool};
+use serde_json::Value;
+
+use crate::config::Config;
+
+/// Per-tool overrides of the tools' own `cacheable` annotation, mapping prefixed names such as
+/// `lookup__find`, or `lookup__*` for every tool of an extension, to whether they are cached.
+/// An exact name wins over a pattern, and a longer pattern over a shorter one.
+pub const TOOL_CACHE_OVERRIDES_KEY: &str = "GOOSE_TOOL_CACHE_OVERRIDES";
+/// Seconds a cached result stays valid
+pub const TOOL_CACHE_TTL_KEY: &str = "GOOSE_TOOL_CACHE_TTL";
+/// Most results kept at once; the oldest are dropped first
+pub const TOOL_CACHE_MAX_ENTRIES_KEY: &str = "GOOSE_TOOL_CACHE_MAX_ENTRIES";
+
+const DEFAULT_TTL_SECS: u64 = 300;
+const DEFAULT_MAX_ENTRIES: usize = 256;
+
+/// Whether a tool opts in to caching. MCP tool annotations have no free-form fields, so a tool
+/// is `cacheable` when it is annotated read-only, idempotent and closed-world: its result then
+/// depends only on its arguments.
+pub fn is_cacheable_tool(tool: &Tool) -> bool {
+    tool.annotations.as_ref().is_some_and(|a| {
+        a.read_only_hint == Some(true)
+            && a.idempotent_hint == Some(true)
+            && a.open_world_hint == Some(false)
+    })
+}
+
+struct CacheEntry {
+    /// Kept to rule out hash collisions between different arguments
+    arguments: Value,
+    content: Vec<Content>,
+    stored_at: Instant,
+}
+
+/// Results of successful calls to tools marked cacheable, keyed by tool name and a hash of the
+/// arguments. Meant for pure tools such as formatters and lookups, which are often called
+/// again with the same arguments.
+pub struct ToolResultCache {
+    overrides: HashMap<String, bool>,
+    ttl: Duration,
+    max_entries: usize,
+    /// Prefixed names of the listed tools annotated as cacheable
+    annotated: Mutex<HashSet<String>>,
+    entries: Mutex<HashMap<(String, u64), CacheEntry>>,
+}
+
+impl Default for ToolResultCache {
+    fn default() -> Self {
+        Self::new(
+            HashMap::new(),
+            Duration::from_secs(DEFAULT_TTL_SECS),
+            DEFAULT_MAX_ENTRIES,
+        )
+    }
+}
+
+impl ToolResultCache {
+    pub fn new(overrides: HashMap<String, bool>, ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            overrides,
+            ttl,
+            max_entries,
+            annotated: Mutex::new(HashSet::new()),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_config() -> Self {
+        let config = Config::global();
+        Self::new(
+            config
+                .get_param::<HashMap<String, bool>>(TOOL_CACHE_OVERRIDES_KEY)
+                .unwrap_or_default(),
+            Duration::from_secs(
+                config
+                    .get_param(TOOL_CACHE_TTL_KEY)
+                    .unwrap_or(DEFAULT_TTL_SECS),
+            ),
+            config
+                .get_param(TOOL_CACHE_MAX_ENTRIES_KEY)
+                .unwrap_or(DEFAULT_MAX_ENTRIES),
+        )
+    }
+
+    /// Remember which of the listed (prefixed) tools are annotated as cacheable
+    pub fn record_tools(&self, tools: &[Tool]) {
+        let mut annotated = self.annotated.lock().unwrap();
+        for tool in tools {
+            if is_cacheable_tool(tool) {
+                annotated.insert(tool.name.to_string());
+            } else {
+                annotated.remove(&*tool.name);
+            }
+        }
+    }
+
+    pub fn is_cacheable(&self, tool_name: &str) -> bool {
+        if self.max_entries == 0 || self.ttl.is_zero() {
+            return false;
+        }
+        if let Some(cacheable) = self.overrides.get(tool_name) {
+            return *cacheable;
+        }
+        let pattern = self
+            .overrides
+            .iter()
+            .filter_map(|(pattern, cacheable)| {
+                let prefix = pattern.strip_suffix('*')?;
+                tool_name
+                    .starts_with(prefix)
+                    .then_some((prefix.len(), *cacheable))
+            })
+            .max_by_key(|(len, _)| *len);
+        match pattern {
+            Some((_, cacheable)) => cacheable,
+            None => self.annotated.lock().unwrap().contains(tool_name),
+        }
+    }
+
+    pub fn get(&self, tool_name: &str, arguments: &Value) -> Option<Vec<Content>> {
+        let key = (tool_name.to_string(), arguments_hash(arguments));
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get(&key)?;
+        if entry.stored_at.elapsed() >= self.ttl {
+            entries.remove(&key);
+            return None;
+        }
+        (entry.arguments == *arguments).then(|| entry.content.clone())
+    }
+
+    pub fn insert(&self, tool_name: &str, arguments: Value, content: Vec<Content>) {
+        let key = (tool_name.to_string(), arguments_hash(&arguments));
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, entry| entry.stored_at.elapsed() < self.ttl);
+            if entries.len() >= self.max_entries {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.stored_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(
+            key,
+            CacheEntry {
+                arguments,
+                content,
+                stored_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drop the cached results and annotations of one extension's tools
+    pub fn invalidate_extension(&self, extension_name: &str) {
+        let prefix = format!("{}__", extension_name);
+        self.annotated
+            .lock()
+            .unwrap()
+            .retain(|tool_name| !tool_name.starts_with(&prefix));
+        self.entries
+            .lock()
+            .unwrap()
+            .retain(|(tool_name, _), _| !tool_name.starts_with(&prefix));
+    }
+}
+
+/// Hash the arguments with object keys in sorted order, so `{"a":1,"b":2}` and `{"b":2,"a":1}`
+/// share an entry
+fn arguments_hash(arguments: &Value) -> u64 {
+    fn hash_value(value: &Value, hasher: &mut DefaultHasher) {
+        match value {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                for key in keys {
+                    key.hash(hasher);
+                    hash_value(&map[key], hasher);
+                }
+            }
+            Value::Array(items) => {
+                items.len().hash(hasher);
+                for item in items {
+                    hash_value(item, hasher);
+                }
+            }
+            other => other.to_string().hash(hasher),
+        }
+    }
+
+    let mut hasher = DefaultHasher::new();
+    hash_value(arguments, &mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::ToolAnnotations;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn cache(overrides: &[(&str, bool)], ttl: Duration, max_entries: usize) -> ToolResultCache {
+        ToolResultCache::new(
+            overrides
+                .iter()
+                .map(|(name, cacheable)| (name.to_string(), *cacheable))
+                .collect(),
+            ttl,
+            max_entries,
+        )
+    }
+
+    fn tool(name: &str, annotations: Option<ToolAnnotations>) -> Tool {
+        let mut tool = Tool::new(
+            name.to_string(),
+            "A tool",
+            Arc::new(json!({}).as_object().unwrap().clone()),
+        );
+        tool.annotations = annotations;
+        tool
+    }
+
+    fn pure() -> ToolAnnotations {
+        ToolAnnotations {
+            read_only_hint: Some(true),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tools_annotated_cacheable_are_cached() {
+        let cache = ToolResultCache::default();
+        cache.record_tools(&[
+            tool("lookup__find", Some(pure())),
+            tool(
+                "lookup__fetch",
+                Some(ToolAnnotations {
+                    open_world_hint: Some(true),
+                    ..pure()
+                }),
+            ),
+            tool("developer__shell", None),
+        ]);
+        assert!(cache.is_cacheable("lookup__find"));
+        assert!(!cache.is_cacheable("lookup__fetch"));
+        assert!(!cache.is_cacheable("developer__shell"));
+
+        // Relisting a tool without the annotation stops caching it
+        cache.record_tools(&[tool("lookup__find", None)]);
+        assert!(!cache.is_cacheable("lookup__find"));
+
+        cache.record_tools(&[tool("lookup__find", Some(pure()))]);
+        cache.invalidate_extension("lookup");
+        assert!(!cache.is_cacheable("lookup__find"));
+    }
+
+    #[test]
+    fn test_overrides_win_over_annotations() {
+        let cache = cache(
+            &[
+                ("format__*", true),
+                ("format__check", false),
+                ("lookup__*", false),
+                ("lookup__find*", true),
+            ],
+            Duration::from_secs(60),
+            10,
+        );
+        cache.record_tools(&[
+            tool("lookup__find", Some(pure())),
+            tool("lookup__list", Some(pure())),
+        ]);
+        assert!(cache.is_cacheable("format__rust"));
+        assert!(!cache.is_cacheable("format__check"));
+        assert!(cache.is_cacheable("lookup__find"));
+        assert!(!cache.is_cacheable("lookup__list"));
+
+        let disabled = cache(&[("format__*", true)], Duration::ZERO, 10);
+        assert!(!disabled.is_cacheable("format__rust"));
+    }
+
+    #[test]
+    fn test_hits_ignore_argument_key_order_and_expire() {
+        let cache = cache(&[], Duration::from_millis(50), 10);
+        cache.insert(
+            "lookup__find",
+            json!({"query": "goose", "limit": 3}),
+            vec![Content::text("found")],
+        );
+
+        let hit = cache
+            .get("lookup__find", &json!({"limit": 3, "query": "goose"}))
+            .unwrap();
+        assert_eq!(hit[0].as_text().unwrap().text, "found");
+        assert!(cache
+            .get("lookup__find", &json!({"query": "duck", "limit": 3}))
+            .is_none());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(cache
+            .get("lookup__find", &json!({"query": "goose", "limit": 3}))
+            .is_none());
+    }
+
+    #[test]
+    fn test_oldest_entry_is_evicted_and_extensions_invalidated() {
+        let cache = cache(&[], Duration::from_secs(60), 2);
+        for query in ["a", "b", "c"] {
+            cache.insert(
+                "lookup__find",
+                json!({ "query": query }),
+                vec![Content::text(query)],
+            );
+        }
+        assert!(cache.get("lookup__find", &json!({"query": "a"})).is_none());
+        assert!(cache.get("lookup__find", &json!({"query": "c"})).is_some());
+
+        cache.invalidate_extension("lookup");
+        assert!(cache.get("lookup__find", &json!({"query": "c"})).is_none());
+    }
+}
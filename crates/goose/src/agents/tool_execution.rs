@@ -1,16 +1,17 @@
+use std::collections::HashSet;
 use std::future::Future;
 use std::sync::Arc;
 
 use async_stream::try_stream;
 use futures::stream::{self, BoxStream};
 use futures::{Stream, StreamExt};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Semaphore};
 use tokio_util::sync::CancellationToken;
 
 use crate::config::permission::PermissionLevel;
 use crate::permission::Permission;
 use mcp_core::ToolResult;
-use rmcp::model::{Content, ServerNotification};
+use rmcp::model::{Content, ServerNotification, Tool};
 
 // ToolCallResult combines the result of a tool call with an optional notification stream that
 // can be used to receive notifications from the tool.
@@ -29,8 +30,108 @@ impl From<ToolResult<Vec<Content>>> for ToolCallResult {
 }
 
 use super::agent::{tool_stream, ToolStream};
+use super::audit_log::{AuditDecision, AuditEvent};
+use super::types::SessionConfig;
 use crate::agents::Agent;
-use crate::conversation::message::{Message, ToolRequest};
+use crate::conversation::message::{Message, MessageContent, ToolRequest};
+
+pub const DEFAULT_MAX_PARALLEL_TOOL_CALLS: usize = 8;
+
+/// Whether calls to `tool` must run one at a time. Tools without annotations run in parallel,
+/// as do tools annotated read-only or non-destructive; a tool that is annotated but leaves
+/// `destructive_hint` out is treated as destructive, as the MCP spec's default says.
+pub fn runs_sequentially(tool: &Tool) -> bool {
+    tool.annotations
+        .as_ref()
+        .is_some_and(|a| a.read_only_hint != Some(true) && a.destructive_hint != Some(false))
+}
+
+/// Bounds how many of the tool calls from one assistant turn run at the same time. Tools that
+/// [`runs_sequentially`] reports as destructive run one at a time, in the order they were
+/// requested, since those calls may depend on each other's side effects.
+#[derive(Clone)]
+pub(crate) struct ToolCallLimiter {
+    parallel: Arc<Semaphore>,
+    sequential: Arc<Mutex<()>>,
+    sequential_tools: Arc<HashSet<String>>,
+    cancel_token: Option<CancellationToken>,
+}
+
+impl ToolCallLimiter {
+    pub(crate) fn new(
+        max_parallel: usize,
+        tools: &[Tool],
+        cancel_token: Option<CancellationToken>,
+    ) -> Self {
+        let sequential_tools = tools
+            .iter()
+            .filter(|tool| runs_sequentially(tool))
+            .map(|tool| tool.name.to_string())
+            .collect();
+        Self {
+            parallel: Arc::new(Semaphore::new(max_parallel.max(1))),
+            sequential: Arc::new(Mutex::new(())),
+            sequential_tools: Arc::new(sequential_tools),
+            cancel_token,
+        }
+    }
+
+    /// A token for a single call, so cancelling one call leaves its siblings running while
+    /// cancelling the turn still stops them all
+    pub(crate) fn call_token(&self) -> Option<CancellationToken> {
+        self.cancel_token
+            .as_ref()
+            .map(CancellationToken::child_token)
+    }
+
+    /// Stream the outcome of a dispatched tool call, waiting for a free slot before it runs
+    pub(crate) fn stream(
+        &self,
+        tool_name: &str,
+        tool_result: ToolResult<ToolCallResult>,
+    ) -> ToolStream {
+        let result = match tool_result {
+            Ok(result) => result,
+            Err(e) => {
+                return tool_stream(Box::new(stream::empty()), futures::future::ready(Err(e)))
+            }
+        };
+
+        let parallel = self.parallel.clone();
+        let sequential = self
+            .sequential_tools
+            .contains(tool_name)
+            .then(|| self.sequential.clone());
+        let done = async move {
+            // Take the sequential lock first so queued sequential calls don't hold a slot
+            let _turn = match sequential {
+                Some(lock) => Some(lock.lock_owned().await),
+                None => None,
+            };
+            let _permit = parallel.acquire_owned().await;
+            result.result.await
+        };
+
+        tool_stream(
+            result
+                .notification_stream
+                .unwrap_or_else(|| Box::new(stream::empty())),
+            Box::pin(done),
+        )
+    }
+}
+
+/// Put the tool responses in `message` back in the order the tools were requested, since
+/// calls running in parallel finish in any order
+pub(crate) fn order_tool_responses(message: &mut Message, request_ids: &[String]) {
+    message.content.sort_by_key(|content| match content {
+        MessageContent::ToolResponse(response) => request_ids
+            .iter()
+            .position(|id| id == &response.id)
+            .unwrap_or(usize::MAX),
+        _ => usize::MAX,
+    });
+}
 
 pub const DECLINED_RESPONSE: &str = "The user has declined to run this tool. \
     DO NOT attempt to call this tool again. \
@@ -51,8 +152,9 @@ impl Agent {
         tool_requests: &'a [ToolRequest],
         tool_futures: Arc<Mutex<Vec<(String, ToolStream)>>>,
         message_tool_response: Arc<Mutex<Message>>,
-        cancellation_token: Option<CancellationToken>,
+        limiter: &'a ToolCallLimiter,
         inspection_results: &'a [crate::tool_inspection::InspectionResult],
+        session: &'a Option<SessionConfig>,
     ) -> BoxStream<'a, anyhow::Result<Message>> {
         try_stream! {
             for request in tool_requests.iter() {
@@ -79,20 +181,17 @@ impl Agent {
                     let mut rx = self.confirmation_rx.lock().await;
                     while let Some((req_id, confirmation)) = rx.recv().await {
                         if req_id == request.id {
-                            if confirmation.permission == Permission::AllowOnce || confirmation.permission == Permission::AlwaysAllow {
-                                let (req_id, tool_result) = self.dispatch_tool_call(tool_call.clone(), request.id.clone(), cancellation_token.clone(), &None).await;
+                            let approved = confirmation.permission == Permission::AllowOnce || confirmation.permission == Permission::AlwaysAllow;
+                            if let (Some(audit_logger), Some(session_config)) = (&self.audit_logger, session) {
+                                let decision = if approved { AuditDecision::UserApproved } else { AuditDecision::UserDeclined };
+                                audit_logger.record(&session_config.id, &request.id, &tool_call.name, AuditEvent::PermissionDecision { decision });
+                            }
+
+                            if approved {
+                                let (req_id, tool_result) = self.dispatch_tool_call_with_audit(tool_call.clone(), request.id.clone(), limiter.call_token(), &None, session).await;
                                 let mut futures = tool_futures.lock().await;
 
-                                futures.push((req_id, match tool_result {
-                                    Ok(result) => tool_stream(
-                                        result.notification_stream.unwrap_or_else(|| Box::new(stream::empty())),
-                                        result.result,
-                                    ),
-                                    Err(e) => tool_stream(
-                                        Box::new(stream::empty()),
-                                        futures::future::ready(Err(e)),
-                                    ),
-                                }));
+                                futures.push((req_id, limiter.stream(&tool_call.name, tool_result)));
 
                                 // Update the shared permission manager when user selects "Always Allow"
                                 if confirmation.permission == Permission::AlwaysAllow {
@@ -142,3 +241,32 @@ impl Agent {
         .boxed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{platform_tools, todo_tools};
+
+    #[test]
+    fn test_builtin_tools_follow_their_annotations() {
+        // Annotated read-only, or writing but not destructive
+        for tool in [
+            platform_tools::read_resource_tool(),
+            platform_tools::manage_extensions_tool(),
+            todo_tools::todo_read_tool(),
+        ] {
+            assert!(
+                !runs_sequentially(&tool),
+                "{} should run in parallel",
+                tool.name
+            );
+        }
+        // Annotated destructive
+        for tool in [
+            platform_tools::manage_schedule_tool(),
+            todo_tools::todo_write_tool(),
+        ] {
+            assert!(runs_sequentially(&tool), "{} should run alone", tool.name);
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Upper bounds, in milliseconds, of the latency histogram buckets. Slower calls land in a
+/// final overflow bucket.
+const LATENCY_BUCKETS_MS: [u64; 16] = [
+    1, 2, 5, 10, 20, 50, 100, 200, 500, 1_000, 2_000, 5_000, 10_000, 30_000, 60_000, 120_000,
+];
+const BUCKET_COUNT: usize = LATENCY_BUCKETS_MS.len() + 1;
+
+/// Counters for one tool, updated without taking a lock
+#[derive(Default)]
+struct ToolCounters {
+    invocations: AtomicU64,
+    failures: AtomicU64,
+    total_latency_ms: AtomicU64,
+    max_latency_ms: AtomicU64,
+    /// Unix milliseconds of the last call, or 0 when never called
+    last_used_ms: AtomicI64,
+    latency_buckets: [AtomicU64; BUCKET_COUNT],
+}
+
+impl ToolCounters {
+    fn record(&self, latency: Duration, success: bool, now: DateTime<Utc>) {
+        let latency_ms = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| latency_ms <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+
+        self.invocations.fetch_add(1, Ordering::Relaxed);
+        if !success {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+        self.total_latency_ms
+            .fetch_add(latency_ms, Ordering::Relaxed);
+        self.max_latency_ms.fetch_max(latency_ms, Ordering::Relaxed);
+        self.last_used_ms
+            .fetch_max(now.timestamp_millis(), Ordering::Relaxed);
+        self.latency_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> UsageSnapshot {
+        UsageSnapshot {
+            invocations: self.invocations.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            total_latency_ms: self.total_latency_ms.load(Ordering::Relaxed),
+            max_latency_ms: self.max_latency_ms.load(Ordering::Relaxed),
+            last_used_ms: self.last_used_ms.load(Ordering::Relaxed),
+            latency_buckets: std::array::from_fn(|i| {
+                self.latency_buckets[i].load(Ordering::Relaxed)
+            }),
+        }
+    }
+}
+
+/// A point-in-time copy of [`ToolCounters`], which can be summed across tools
+#[derive(Default)]
+struct UsageSnapshot {
+    invocations: u64,
+    failures: u64,
+    total_latency_ms: u64,
+    max_latency_ms: u64,
+    last_used_ms: i64,
+    latency_buckets: [u64; BUCKET_COUNT],
+}
+
+impl UsageSnapshot {
+    fn add(&mut self, other: &UsageSnapshot) {
+        self.invocations += other.invocations;
+        self.failures += other.failures;
+        self.total_latency_ms += other.total_latency_ms;
+        self.max_latency_ms = self.max_latency_ms.max(other.max_latency_ms);
+        self.last_used_ms = self.last_used_ms.max(other.last_used_ms);
+        for (total, count) in self.latency_buckets.iter_mut().zip(other.latency_buckets) {
+            *total += count;
+        }
+    }
+
+    /// The upper bound of the bucket holding the 95th percentile call, capped at the slowest
+    /// call seen
+    fn p95_latency_ms(&self) -> Option<u64> {
+        if self.invocations == 0 {
+            return None;
+        }
+        let target = (self.invocations * 95).div_ceil(100);
+        let mut seen = 0;
+        for (bucket, count) in self.latency_buckets.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                let bound = LATENCY_BUCKETS_MS
+                    .get(bucket)
+                    .copied()
+                    .unwrap_or(self.max_latency_ms);
+                return Some(bound.min(self.max_latency_ms));
+            }
+        }
+        Some(self.max_latency_ms)
+    }
+
+    fn last_used(&self) -> Option<DateTime<Utc>> {
+        if self.last_used_ms == 0 {
+            None
+        } else {
+            DateTime::from_timestamp_millis(self.last_used_ms)
+        }
+    }
+}
+
+/// How often and how well a tool or extension was used in this session
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageCounts {
+    pub invocations: u64,
+    pub failures: u64,
+    pub total_latency_ms: u64,
+    /// Estimated from a histogram, so it is the bucket bound the call fell under
+    pub p95_latency_ms: Option<u64>,
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+impl From<&UsageSnapshot> for UsageCounts {
+    fn from(snapshot: &UsageSnapshot) -> Self {
+        Self {
+            invocations: snapshot.invocations,
+            failures: snapshot.failures,
+            total_latency_ms: snapshot.total_latency_ms,
+            p95_latency_ms: snapshot.p95_latency_ms(),
+            last_used: snapshot.last_used(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolUsage {
+    pub name: String,
+    #[serde(flatten)]
+    pub usage: UsageCounts,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExtensionUsage {
+    pub name: String,
+    #[serde(flatten)]
+    pub usage: UsageCounts,
+    /// Tools that were called, most used first
+    pub tools: Vec<ToolUsage>,
+}
+
+/// Tool usage of every enabled extension, most used first
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageStats {
+    pub extensions: Vec<ExtensionUsage>,
+}
+
+/// Tool call counters per extension and tool, kept for the lifetime of a session
+#[derive(Default)]
+pub struct ToolUsageTracker {
+    counters: RwLock<HashMap<String, HashMap<String, Arc<ToolCounters>>>>,
+}
+
+impl ToolUsageTracker {
+    fn counters(&self, extension: &str, tool: &str) -> Arc<ToolCounters> {
+        if let Some(counters) = self
+            .counters
+            .read()
+            .unwrap()
+            .get(extension)
+            .and_then(|tools| tools.get(tool))
+        {
+            return Arc::clone(counters);
+        }
+        let mut extensions = self.counters.write().unwrap();
+        Arc::clone(
+            extensions
+                .entry(extension.to_string())
+                .or_default()
+                .entry(tool.to_string())
+                .or_default(),
+        )
+    }
+
+    pub fn record(&self, extension: &str, tool: &str, latency: Duration, success: bool) {
+        self.counters(extension, tool)
+            .record(latency, success, Utc::now());
+    }
+
+    pub fn reset(&self) {
+        self.counters.write().unwrap().clear();
+    }
+
+    /// Extensions that had at least one tool called
+    pub fn used_extensions(&self) -> HashSet<String> {
+        self.counters.read().unwrap().keys().cloned().collect()
+    }
+
+    /// The usage of `extensions`, which are listed even when none of their tools were called,
+    /// along with any other extension that has recorded calls
+    pub fn report(&self, extensions: &[String]) -> UsageStats {
+        let counters = self.counters.read().unwrap();
+        let mut names: Vec<&String> = extensions.iter().collect();
+        names.extend(counters.keys().filter(|name| !extensions.contains(name)));
+
+        let mut report: Vec<ExtensionUsage> = names
+            .into_iter()
+            .map(|name| {
+                let mut total = UsageSnapshot::default();
+                let mut tools: Vec<ToolUsage> = counters
+                    .get(name)
+                    .into_iter()
+                    .flatten()
+                    .map(|(tool, counters)| {
+                        let snapshot = counters.snapshot();
+                        total.add(&snapshot);
+                        ToolUsage {
+                            name: tool.clone(),
+                            usage: UsageCounts::from(&snapshot),
+                        }
+                    })
+                    .collect();
+                tools.sort_by(|a, b| {
+                    b.usage
+                        .invocations
+                        .cmp(&a.usage.invocations)
+                        .then_with(|| a.name.cmp(&b.name))
+                });
+                ExtensionUsage {
+                    name: name.clone(),
+                    usage: UsageCounts::from(&total),
+                    tools,
+                }
+            })
+            .collect();
+        report.sort_by(|a, b| {
+            b.usage
+                .invocations
+                .cmp(&a.usage.invocations)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        UsageStats { extensions: report }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_and_p95() {
+        let tracker = ToolUsageTracker::default();
+        for _ in 0..19 {
+            tracker.record("developer", "shell", Duration::from_millis(8), true);
+        }
+        tracker.record("developer", "shell", Duration::from_millis(4_000), false);
+        tracker.record("developer", "text_editor", Duration::from_millis(150), true);
+
+        let stats = tracker.report(&["memory".to_string(), "developer".to_string()]);
+        let names: Vec<&str> = stats.extensions.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["developer", "memory"]);
+
+        let developer = &stats.extensions[0];
+        assert_eq!(developer.usage.invocations, 21);
+        assert_eq!(developer.usage.failures, 1);
+        assert_eq!(developer.usage.total_latency_ms, 19 * 8 + 4_000 + 150);
+        assert_eq!(developer.tools[0].name, "shell");
+        // 19 of 20 calls took under 10ms, so the 95th percentile is still in that bucket
+        assert_eq!(developer.tools[0].usage.p95_latency_ms, Some(10));
+        assert_eq!(developer.tools[1].usage.p95_latency_ms, Some(150));
+        assert!(developer.usage.last_used.is_some());
+
+        let memory = &stats.extensions[1];
+        assert_eq!(memory.usage.invocations, 0);
+        assert_eq!(memory.usage.p95_latency_ms, None);
+        assert_eq!(memory.usage.last_used, None);
+        assert!(memory.tools.is_empty());
+
+        tracker.reset();
+        assert!(tracker.used_extensions().is_empty());
+    }
+}
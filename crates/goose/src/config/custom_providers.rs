@@ -4,11 +4,17 @@ use crate::providers::anthropic::AnthropicProvider;
 use crate::providers::base::ModelInfo;
 use crate::providers::ollama::OllamaProvider;
 use crate::providers::openai::OpenAiProvider;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use etcetera::{choose_app_strategy, AppStrategy};
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::path::Path;
+use std::time::Duration;
+
+/// Timeout applied to each request made while validating a custom provider
+const VALIDATION_TIMEOUT: Duration = Duration::from_secs(20);
 
 pub fn custom_providers_dir() -> std::path::PathBuf {
     choose_app_strategy(APP_STRATEGY.clone())
@@ -39,6 +45,23 @@ pub struct CustomProviderConfig {
     pub supports_streaming: Option<bool>,
 }
 
+/// Capabilities detected by probing an OpenAI-compatible endpoint
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProviderCapabilities {
+    pub streaming: bool,
+    pub tool_calls: bool,
+}
+
+/// Result of validating a custom provider against its live endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderValidation {
+    /// Models reported by `/models`, or the configured models if that endpoint is unavailable
+    pub models: Vec<String>,
+    /// Whether the server implements `/models`
+    pub models_endpoint: bool,
+    pub capabilities: ProviderCapabilities,
+}
+
 impl CustomProviderConfig {
     pub fn id(&self) -> &str {
         &self.name
@@ -108,6 +131,76 @@ impl CustomProviderConfig {
         Ok(provider_config)
     }
 
+    /// Check that the configured endpoint is reachable and speaks the OpenAI API, using the
+    /// API key stored under `api_key_env`
+    pub async fn validate(&self) -> Result<ProviderValidation> {
+        let api_key: String = Config::global()
+            .get_secret(&self.api_key_env)
+            .map_err(|_| anyhow!("Missing API key: {}", self.api_key_env))?;
+        self.validate_with_key(&api_key).await
+    }
+
+    /// Validate the endpoint with an explicit API key.
+    ///
+    /// Hits `/models` to discover the available models, falling back to a minimal
+    /// `/chat/completions` probe for servers that do not implement it, and then sends small
+    /// canary requests to detect streaming and tool call support. Tool calls count as
+    /// supported only when a forced call comes back as a `tool_calls` entry.
+    pub async fn validate_with_key(&self, api_key: &str) -> Result<ProviderValidation> {
+        if !matches!(self.engine, ProviderEngine::OpenAI) {
+            return Err(anyhow!(
+                "Endpoint validation is only supported for OpenAI compatible providers"
+            ));
+        }
+
+        let endpoint = OpenAiCompatEndpoint::new(&self.base_url, api_key, self.headers.as_ref())?;
+        let configured: Vec<String> = self.models.iter().map(|m| m.name.clone()).collect();
+
+        let (models, models_endpoint) = match endpoint.list_models().await {
+            Ok(models) => (models, true),
+            Err(EndpointError::NotFound) => (configured.clone(), false),
+            Err(e) => return Err(endpoint.describe(e)),
+        };
+
+        let probe_model = configured
+            .first()
+            .or_else(|| models.first())
+            .cloned()
+            .ok_or_else(|| {
+                anyhow!("No models configured and the server did not report any via /models")
+            })?;
+
+        // Without /models the basic chat probe is the only proof the endpoint works
+        endpoint
+            .chat_probe(&probe_model, ChatProbe::Basic)
+            .await
+            .map_err(|e| match e {
+                EndpointError::NotFound if !models_endpoint => anyhow!(
+                    "{} responded with 404 for both /models and /chat/completions. {}",
+                    endpoint.root,
+                    endpoint.path_hint()
+                ),
+                e => endpoint.describe(e),
+            })?;
+
+        let capabilities = ProviderCapabilities {
+            streaming: endpoint
+                .chat_probe(&probe_model, ChatProbe::Streaming)
+                .await
+                .is_ok(),
+            tool_calls: endpoint
+                .chat_probe(&probe_model, ChatProbe::Tools)
+                .await
+                .is_ok(),
+        };
+
+        Ok(ProviderValidation {
+            models,
+            models_endpoint,
+            capabilities,
+        })
+    }
+
     pub fn remove(id: &str) -> Result<()> {
         let config = Config::global();
         let api_key_name = Self::generate_api_key_name(id);
@@ -124,6 +217,281 @@ impl CustomProviderConfig {
     }
 }
 
+/// Fetch the model ids advertised by an OpenAI compatible server, for offering a picker
+/// while configuring a custom provider
+pub async fn discover_models(
+    base_url: &str,
+    api_key: &str,
+    headers: Option<&HashMap<String, String>>,
+) -> Result<Vec<String>> {
+    let endpoint = OpenAiCompatEndpoint::new(base_url, api_key, headers)?;
+    endpoint.list_models().await.map_err(|e| match e {
+        EndpointError::NotFound => anyhow!(
+            "{}/models was not found (404). {}",
+            endpoint.root,
+            endpoint.path_hint()
+        ),
+        e => endpoint.describe(e),
+    })
+}
+
+#[derive(Debug)]
+enum EndpointError {
+    NotFound,
+    Status(StatusCode, String),
+    Request(reqwest::Error),
+    InvalidResponse(String),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ChatProbe {
+    Basic,
+    Streaming,
+    Tools,
+}
+
+struct OpenAiCompatEndpoint {
+    client: Client,
+    /// API root without a trailing slash, e.g. `https://host/v1`
+    root: String,
+    scheme: String,
+    api_key: String,
+    headers: reqwest::header::HeaderMap,
+}
+
+impl OpenAiCompatEndpoint {
+    fn new(
+        base_url: &str,
+        api_key: &str,
+        headers: Option<&HashMap<String, String>>,
+    ) -> Result<Self> {
+        let url = url::Url::parse(base_url)
+            .map_err(|e| anyhow!("Invalid base URL '{}': {}", base_url, e))?;
+
+        // Accept either the API root or the full chat completions URL
+        let path = url.path().trim_end_matches('/');
+        let path = path.strip_suffix("/chat/completions").unwrap_or(path);
+        let path = if path.is_empty() { "/v1" } else { path };
+
+        let mut root = url.clone();
+        root.set_path(path);
+        root.set_query(None);
+
+        let mut header_map = reqwest::header::HeaderMap::new();
+        for (key, value) in headers.into_iter().flatten() {
+            let name = reqwest::header::HeaderName::from_bytes(key.as_bytes())?;
+            let value = reqwest::header::HeaderValue::from_str(value)?;
+            header_map.insert(name, value);
+        }
+
+        Ok(Self {
+            client: Client::builder().timeout(VALIDATION_TIMEOUT).build()?,
+            root: root.as_str().trim_end_matches('/').to_string(),
+            scheme: url.scheme().to_string(),
+            api_key: api_key.to_string(),
+            headers: header_map,
+        })
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, EndpointError> {
+        let response = self
+            .client
+            .get(format!("{}/models", self.root))
+            .bearer_auth(&self.api_key)
+            .headers(self.headers.clone())
+            .send()
+            .await
+            .map_err(EndpointError::Request)?;
+        let json: Value = Self::check_status(response)
+            .await?
+            .json()
+            .await
+            .map_err(|e| EndpointError::InvalidResponse(e.to_string()))?;
+
+        let data = json.get("data").and_then(|v| v.as_array()).ok_or_else(|| {
+            EndpointError::InvalidResponse("missing `data` array in /models response".into())
+        })?;
+        let mut models: Vec<String> = data
+            .iter()
+            .filter_map(|m| m.get("id").and_then(|v| v.as_str()).map(str::to_string))
+            .collect();
+        models.sort();
+        Ok(models)
+    }
+
+    async fn chat_probe(&self, model: &str, probe: ChatProbe) -> Result<(), EndpointError> {
+        let mut payload = json!({
+            "model": model,
+            "messages": [{"role": "user", "content": "ping"}],
+            "max_tokens": 1,
+        });
+        match probe {
+            ChatProbe::Basic => {}
+            ChatProbe::Streaming => payload["stream"] = json!(true),
+            ChatProbe::Tools => {
+                // Force a call to the canary tool; a server that accepts `tools` but never
+                // returns `tool_calls` cannot drive goose's extensions
+                payload["messages"] = json!([{"role": "user", "content": "Call the ping tool."}]);
+                payload["max_tokens"] = json!(64);
+                payload["tools"] = json!([{
+                    "type": "function",
+                    "function": {
+                        "name": "ping",
+                        "description": "Connectivity check",
+                        "parameters": {"type": "object", "properties": {}}
+                    }
+                }]);
+                payload["tool_choice"] = json!({"type": "function", "function": {"name": "ping"}});
+            }
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/chat/completions", self.root))
+            .bearer_auth(&self.api_key)
+            .headers(self.headers.clone())
+            .json(&payload)
+            .send()
+            .await
+            .map_err(EndpointError::Request)?;
+        let response = Self::check_status(response).await?;
+
+        match probe {
+            ChatProbe::Basic => {}
+            ChatProbe::Streaming => {
+                let is_event_stream = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .is_some_and(|v| v.starts_with("text/event-stream"));
+                if !is_event_stream {
+                    return Err(EndpointError::InvalidResponse(
+                        "server ignored `stream: true`".into(),
+                    ));
+                }
+            }
+            ChatProbe::Tools => {
+                let json: Value = response
+                    .json()
+                    .await
+                    .map_err(|e| EndpointError::InvalidResponse(e.to_string()))?;
+                let has_tool_calls = json
+                    .pointer("/choices/0/message/tool_calls")
+                    .and_then(|v| v.as_array())
+                    .is_some_and(|calls| !calls.is_empty());
+                if !has_tool_calls {
+                    return Err(EndpointError::InvalidResponse(
+                        "reply to a forced tool call has no `tool_calls`".into(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, EndpointError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        if status == StatusCode::NOT_FOUND {
+            return Err(EndpointError::NotFound);
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(EndpointError::Status(status, body))
+    }
+
+    fn path_hint(&self) -> String {
+        if self.root.ends_with("/v1") {
+            "Check that the base URL points at the OpenAI compatible API root.".to_string()
+        } else {
+            format!("Did you mean {}/v1?", self.root)
+        }
+    }
+
+    /// Turn an endpoint failure into an actionable error message
+    fn describe(&self, error: EndpointError) -> anyhow::Error {
+        match error {
+            EndpointError::NotFound => {
+                anyhow!("{} returned 404. {}", self.root, self.path_hint())
+            }
+            EndpointError::Status(status, body)
+                if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN =>
+            {
+                anyhow!(
+                    "{} rejected the API key ({}). Check the key and any required headers. {}",
+                    self.root,
+                    status,
+                    body
+                )
+            }
+            EndpointError::Status(status, body) => {
+                anyhow!("{} returned {}: {}", self.root, status, body)
+            }
+            EndpointError::InvalidResponse(msg) => anyhow!(
+                "{} does not look like an OpenAI compatible API: {}",
+                self.root,
+                msg
+            ),
+            EndpointError::Request(e) => self.describe_request_error(e),
+        }
+    }
+
+    fn describe_request_error(&self, error: reqwest::Error) -> anyhow::Error {
+        if error.is_timeout() {
+            return anyhow!(
+                "Timed out after {}s waiting for {}. Check that the server is running and the host and port are correct.",
+                VALIDATION_TIMEOUT.as_secs(),
+                self.root
+            );
+        }
+
+        let chain = error_chain(&error).to_lowercase();
+        let looks_like_tls = [
+            "tls",
+            "ssl",
+            "certificate",
+            "handshake",
+            "wrong version number",
+            "corrupt message",
+        ]
+        .iter()
+        .any(|needle| chain.contains(needle));
+
+        if looks_like_tls && self.scheme == "https" {
+            anyhow!(
+                "TLS handshake with {} failed ({}). If the server does not use TLS, did you mean http://?",
+                self.root,
+                chain
+            )
+        } else if self.scheme == "http" && (looks_like_tls || chain.contains("connection reset")) {
+            anyhow!(
+                "Connection to {} was dropped ({}). If the server expects TLS, did you mean https://?",
+                self.root,
+                chain
+            )
+        } else if error.is_connect() {
+            anyhow!(
+                "Could not connect to {} ({}). Check the host, port, and that the server is running.",
+                self.root,
+                chain
+            )
+        } else {
+            anyhow!("Request to {} failed: {}", self.root, chain)
+        }
+    }
+}
+
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut parts = vec![error.to_string()];
+    let mut source = error.source();
+    while let Some(e) = source {
+        parts.push(e.to_string());
+        source = e.source();
+    }
+    parts.join(": ")
+}
+
 pub fn load_custom_providers(dir: &Path) -> Result<Vec<CustomProviderConfig>> {
     if !dir.exists() {
         return Ok(Vec::new());
@@ -213,3 +581,199 @@ pub fn register_custom_providers(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn openai_config(base_url: String, models: &[&str]) -> CustomProviderConfig {
+        CustomProviderConfig {
+            name: "custom_test".to_string(),
+            engine: ProviderEngine::OpenAI,
+            display_name: "Test".to_string(),
+            description: None,
+            api_key_env: "CUSTOM_TEST_API_KEY".to_string(),
+            base_url,
+            models: models.iter().map(|m| ModelInfo::new(*m, 128000)).collect(),
+            headers: None,
+            timeout_seconds: None,
+            supports_streaming: None,
+        }
+    }
+
+    async fn mount_chat(server: &MockServer) {
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(json!({"tools": [{"type": "function"}]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{"message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {"name": "ping", "arguments": "{}"}
+                    }]
+                }}]
+            })))
+            .with_priority(1)
+            .mount(server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(json!({"stream": true})))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw("data: [DONE]\n\n", "text/event-stream"),
+            )
+            .with_priority(1)
+            .mount(server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{"message": {"role": "assistant", "content": "pong"}}]
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_discover_models_lists_sorted_ids() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{"id": "model-b"}, {"id": "model-a"}]
+            })))
+            .mount(&server)
+            .await;
+
+        let models = discover_models(&server.uri(), "key", None).await.unwrap();
+        assert_eq!(models, vec!["model-a", "model-b"]);
+
+        // The full chat completions URL resolves to the same API root
+        let url = format!("{}/v1/chat/completions", server.uri());
+        let models = discover_models(&url, "key", None).await.unwrap();
+        assert_eq!(models, vec!["model-a", "model-b"]);
+    }
+
+    #[tokio::test]
+    async fn test_validate_detects_capabilities() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{"id": "served-model"}]
+            })))
+            .mount(&server)
+            .await;
+        mount_chat(&server).await;
+
+        let config = openai_config(format!("{}/v1", server.uri()), &[]);
+        let validation = config.validate_with_key("key").await.unwrap();
+
+        assert!(validation.models_endpoint);
+        assert_eq!(validation.models, vec!["served-model"]);
+        assert_eq!(
+            validation.capabilities,
+            ProviderCapabilities {
+                streaming: true,
+                tool_calls: true
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_falls_back_when_models_is_404() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(json!({"tools": [{"type": "function"}]})))
+            .respond_with(ResponseTemplate::new(400).set_body_string("tools not supported"))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"choices": []})))
+            .mount(&server)
+            .await;
+
+        let config = openai_config(server.uri(), &["configured-model"]);
+        let validation = config.validate_with_key("key").await.unwrap();
+
+        assert!(!validation.models_endpoint);
+        assert_eq!(validation.models, vec!["configured-model"]);
+        assert!(!validation.capabilities.streaming);
+        assert!(!validation.capabilities.tool_calls);
+    }
+
+    #[tokio::test]
+    async fn test_tools_accepted_without_tool_calls_is_not_supported() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        // The server accepts `tools` but answers in plain text
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{"message": {"role": "assistant", "content": "pong"}}]
+            })))
+            .mount(&server)
+            .await;
+
+        let config = openai_config(server.uri(), &["configured-model"]);
+        let validation = config.validate_with_key("key").await.unwrap();
+
+        assert!(!validation.capabilities.tool_calls);
+    }
+
+    #[tokio::test]
+    async fn test_missing_v1_suggests_path() {
+        let server = MockServer::start().await;
+
+        let config = openai_config(format!("{}/api", server.uri()), &["m"]);
+        let err = config.validate_with_key("key").await.unwrap_err();
+        assert!(err.to_string().contains("Did you mean"), "{}", err);
+        assert!(err.to_string().contains("/api/v1"), "{}", err);
+
+        let err = discover_models(&format!("{}/api", server.uri()), "key", None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("404"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_key_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("bad key"))
+            .mount(&server)
+            .await;
+
+        let err = discover_models(&server.uri(), "wrong", None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("rejected the API key"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_https_against_plain_http_server_suggests_http() {
+        let server = MockServer::start().await;
+        let https_url = server.uri().replace("http://", "https://");
+
+        let err = discover_models(&https_url, "key", None).await.unwrap_err();
+        assert!(err.to_string().contains("did you mean http://"), "{}", err);
+    }
+}
@@ -1,8 +1,11 @@
 use super::base::Config;
+use crate::agents::extension::Envs;
+use crate::agents::extension_malware_check::deny_if_malicious_cmd_args;
 use crate::agents::ExtensionConfig;
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use utoipa::ToSchema;
 
 pub const DEFAULT_EXTENSION: &str = "developer";
@@ -10,6 +13,8 @@ pub const DEFAULT_EXTENSION_TIMEOUT: u64 = 300;
 pub const DEFAULT_EXTENSION_DESCRIPTION: &str = "";
 pub const DEFAULT_DISPLAY_NAME: &str = "Developer";
 const EXTENSIONS_CONFIG_KEY: &str = "extensions";
+/// Version of the document written by [`ExtensionConfigManager::export_bundle`]
+pub const EXTENSION_BUNDLE_VERSION: u32 = 1;
 
 #[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct ExtensionEntry {
@@ -18,6 +23,187 @@ pub struct ExtensionEntry {
     pub config: ExtensionConfig,
 }
 
+/// A shareable set of extension configs. Secret values are never included: env values are
+/// stripped down to their names in `env_keys`, and credential headers are dropped, so each
+/// user supplies their own secrets on import.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ExtensionBundle {
+    pub version: u32,
+    pub extensions: Vec<ExtensionEntry>,
+}
+
+/// What to do when an imported extension has the same key as a configured one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// Keep the configured extension and skip the imported one
+    Skip,
+    /// Replace the configured extension with the imported one
+    Overwrite,
+    /// Refuse the whole import
+    Fail,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct BundleImportSummary {
+    pub imported: Vec<String>,
+    /// Extensions left alone because one with the same key was already configured
+    pub skipped: Vec<String>,
+    /// Secrets that are still unset, as (extension, env key)
+    pub missing_secrets: Vec<(String, String)>,
+}
+
+fn is_yaml(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"))
+}
+
+fn is_credential_header(name: &str) -> bool {
+    let name = name.to_lowercase();
+    ["authorization", "cookie", "token", "key", "secret", "auth"]
+        .iter()
+        .any(|marker| name.contains(marker))
+}
+
+/// Move env values into `env_keys` and drop credential headers
+fn strip_secrets(config: ExtensionConfig) -> ExtensionConfig {
+    fn keys_of(envs: &Envs, env_keys: Vec<String>) -> Vec<String> {
+        let mut keys = env_keys;
+        let mut names: Vec<String> = envs.get_env().into_keys().collect();
+        names.sort();
+        for name in names {
+            if !keys.contains(&name) {
+                keys.push(name);
+            }
+        }
+        keys
+    }
+
+    match config {
+        ExtensionConfig::Stdio {
+            name,
+            cmd,
+            args,
+            envs,
+            env_keys,
+            timeout,
+            description,
+            bundled,
+            available_tools,
+            response_limits,
+            depends_on,
+        } => ExtensionConfig::Stdio {
+            name,
+            cmd,
+            args,
+            env_keys: keys_of(&envs, env_keys),
+            envs: Envs::default(),
+            timeout,
+            description,
+            bundled,
+            available_tools,
+            response_limits,
+            depends_on,
+        },
+        ExtensionConfig::Sse {
+            name,
+            uri,
+            envs,
+            env_keys,
+            description,
+            timeout,
+            bundled,
+            available_tools,
+            response_limits,
+            depends_on,
+            keep_alive_interval,
+        } => ExtensionConfig::Sse {
+            name,
+            uri,
+            env_keys: keys_of(&envs, env_keys),
+            envs: Envs::default(),
+            description,
+            timeout,
+            bundled,
+            available_tools,
+            response_limits,
+            depends_on,
+            keep_alive_interval,
+        },
+        ExtensionConfig::StreamableHttp {
+            name,
+            uri,
+            envs,
+            env_keys,
+            headers,
+            description,
+            timeout,
+            bundled,
+            available_tools,
+            response_limits,
+            depends_on,
+            keep_alive_interval,
+        } => ExtensionConfig::StreamableHttp {
+            name,
+            uri,
+            env_keys: keys_of(&envs, env_keys),
+            envs: Envs::default(),
+            headers: headers
+                .into_iter()
+                .filter(|(name, _)| !is_credential_header(name))
+                .collect(),
+            description,
+            timeout,
+            bundled,
+            available_tools,
+            response_limits,
+            depends_on,
+            keep_alive_interval,
+        },
+        other => other,
+    }
+}
+
+/// Problems that make an imported extension unusable
+fn validate_bundle_entry(entry: &ExtensionEntry) -> Result<()> {
+    let name = entry.config.name();
+    if name.trim().is_empty() {
+        bail!("extension name must not be empty");
+    }
+    match &entry.config {
+        ExtensionConfig::Stdio { cmd, envs, .. } => {
+            if cmd.trim().is_empty() {
+                bail!("extension '{}' has no command", name);
+            }
+            envs.validate()
+                .map_err(|e| anyhow!("extension '{}': {}", name, e))?;
+        }
+        ExtensionConfig::Sse { uri, envs, .. }
+        | ExtensionConfig::StreamableHttp { uri, envs, .. } => {
+            reqwest::Url::parse(uri)
+                .map_err(|e| anyhow!("extension '{}' has an invalid uri: {}", name, e))?;
+            envs.validate()
+                .map_err(|e| anyhow!("extension '{}': {}", name, e))?;
+        }
+        ExtensionConfig::Frontend { .. } => {
+            bail!(
+                "extension '{}' is provided by a frontend and can't be imported",
+                name
+            );
+        }
+        ExtensionConfig::Builtin { .. } | ExtensionConfig::InlinePython { .. } => {}
+    }
+    Ok(())
+}
+
+fn env_keys(config: &ExtensionConfig) -> (&[String], Option<&Envs>) {
+    match config {
+        ExtensionConfig::Stdio { env_keys, envs, .. }
+        | ExtensionConfig::Sse { env_keys, envs, .. }
+        | ExtensionConfig::StreamableHttp { env_keys, envs, .. } => (env_keys, Some(envs)),
+        _ => (&[], None),
+    }
+}
+
 pub fn name_to_key(name: &str) -> String {
     name.chars()
         .filter(|c| !c.is_whitespace())
@@ -28,21 +214,29 @@ pub fn name_to_key(name: &str) -> String {
 pub struct ExtensionConfigManager;
 
 impl ExtensionConfigManager {
-    fn get_extensions_map() -> Result<HashMap<String, ExtensionEntry>> {
-        let config = Config::global();
+    fn get_extensions_map(config: &Config) -> Result<HashMap<String, ExtensionEntry>> {
         Ok(config
             .get_param(EXTENSIONS_CONFIG_KEY)
             .unwrap_or_else(|_| HashMap::new()))
     }
 
-    fn save_extensions_map(extensions: HashMap<String, ExtensionEntry>) -> Result<()> {
-        let config = Config::global();
+    /// The extensions in the global config file alone, for changes that are saved back to it
+    fn get_global_extensions_map(config: &Config) -> Result<HashMap<String, ExtensionEntry>> {
+        Ok(config
+            .get_global_param(EXTENSIONS_CONFIG_KEY)
+            .unwrap_or_else(|_| HashMap::new()))
+    }
+
+    fn save_extensions_map(
+        config: &Config,
+        extensions: HashMap<String, ExtensionEntry>,
+    ) -> Result<()> {
         config.set_param(EXTENSIONS_CONFIG_KEY, serde_json::to_value(extensions)?)?;
         Ok(())
     }
 
     pub fn get_config_by_name(name: &str) -> Result<Option<ExtensionConfig>> {
-        let extensions = Self::get_extensions_map()?;
+        let extensions = Self::get_extensions_map(Config::global())?;
         Ok(extensions
             .values()
             .find(|entry| entry.config.name() == name)
@@ -50,39 +244,409 @@ impl ExtensionConfigManager {
     }
 
     pub fn set(entry: ExtensionEntry) -> Result<()> {
-        let mut extensions = Self::get_extensions_map()?;
+        Self::set_in(Config::global(), entry)
+    }
+
+    /// Like [`Self::set`], but against a specific config file
+    pub fn set_in(config: &Config, entry: ExtensionEntry) -> Result<()> {
+        let mut extensions = Self::get_global_extensions_map(config)?;
         let key = entry.config.key();
         extensions.insert(key, entry);
-        Self::save_extensions_map(extensions)
+        Self::save_extensions_map(config, extensions)
     }
 
     pub fn remove(key: &str) -> Result<()> {
-        let mut extensions = Self::get_extensions_map()?;
+        Self::remove_in(Config::global(), key)
+    }
+
+    /// Like [`Self::remove`], but against a specific config file
+    pub fn remove_in(config: &Config, key: &str) -> Result<()> {
+        let mut extensions = Self::get_global_extensions_map(config)?;
         extensions.remove(key);
-        Self::save_extensions_map(extensions)
+        Self::save_extensions_map(config, extensions)
     }
 
     pub fn set_enabled(key: &str, enabled: bool) -> Result<()> {
-        let mut extensions = Self::get_extensions_map()?;
+        Self::set_enabled_in(Config::global(), key, enabled)
+    }
+
+    /// Like [`Self::set_enabled`], but against a specific config file
+    pub fn set_enabled_in(config: &Config, key: &str, enabled: bool) -> Result<()> {
+        let mut extensions = Self::get_global_extensions_map(config)?;
         if let Some(entry) = extensions.get_mut(key) {
             entry.enabled = enabled;
-            Self::save_extensions_map(extensions)?;
+            Self::save_extensions_map(config, extensions)?;
         }
         Ok(())
     }
 
     pub fn get_all() -> Result<Vec<ExtensionEntry>> {
-        let extensions = Self::get_extensions_map()?;
+        Self::get_all_in(Config::global())
+    }
+
+    /// Like [`Self::get_all`], but against a specific config file
+    pub fn get_all_in(config: &Config) -> Result<Vec<ExtensionEntry>> {
+        let extensions = Self::get_extensions_map(config)?;
         Ok(extensions.into_values().collect())
     }
 
     pub fn get_all_names() -> Result<Vec<String>> {
-        let extensions = Self::get_extensions_map()?;
+        let extensions = Self::get_extensions_map(Config::global())?;
         Ok(extensions.keys().cloned().collect())
     }
 
+    pub fn export_bundle(names: &[String], path: &Path) -> Result<ExtensionBundle> {
+        Self::export_bundle_in(Config::global(), names, path)
+    }
+
+    /// Like [`Self::export_bundle`], but against a specific config file.
+    ///
+    /// Writes the named extensions (every extension when `names` is empty) to `path`, as YAML
+    /// for `.yaml`/`.yml` paths and JSON otherwise, with secrets stripped.
+    pub fn export_bundle_in(
+        config: &Config,
+        names: &[String],
+        path: &Path,
+    ) -> Result<ExtensionBundle> {
+        let extensions = Self::get_extensions_map(config)?;
+        let mut entries: Vec<ExtensionEntry> = if names.is_empty() {
+            extensions.into_values().collect()
+        } else {
+            names
+                .iter()
+                .map(|name| {
+                    extensions
+                        .get(&name_to_key(name))
+                        .cloned()
+                        .ok_or_else(|| anyhow!("No extension named '{}' is configured", name))
+                })
+                .collect::<Result<_>>()?
+        };
+        entries.sort_by_key(|entry| entry.config.key());
+
+        let bundle = ExtensionBundle {
+            version: EXTENSION_BUNDLE_VERSION,
+            extensions: entries
+                .into_iter()
+                .map(|entry| ExtensionEntry {
+                    enabled: entry.enabled,
+                    config: strip_secrets(entry.config),
+                })
+                .collect(),
+        };
+
+        let document = if is_yaml(path) {
+            serde_yaml::to_string(&bundle)?
+        } else {
+            serde_json::to_string_pretty(&bundle)?
+        };
+        std::fs::write(path, document)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(bundle)
+    }
+
+    pub async fn import_bundle(
+        path: &Path,
+        policy: OverwritePolicy,
+        prompt_secret: impl FnMut(&str, &str) -> Result<Option<String>>,
+    ) -> Result<BundleImportSummary> {
+        Self::import_bundle_in(Config::global(), path, policy, prompt_secret).await
+    }
+
+    /// Like [`Self::import_bundle`], but against a specific config file.
+    ///
+    /// Every entry is validated, checked against `policy` and, for stdio extensions, checked
+    /// for malicious packages before anything is written, so a bad bundle changes nothing.
+    /// `prompt_secret(extension, env_key)` is asked for each secret that isn't already stored;
+    /// returning `None` leaves it unset and reports it in the summary.
+    pub async fn import_bundle_in(
+        config: &Config,
+        path: &Path,
+        policy: OverwritePolicy,
+        mut prompt_secret: impl FnMut(&str, &str) -> Result<Option<String>>,
+    ) -> Result<BundleImportSummary> {
+        let document = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let bundle: ExtensionBundle = if is_yaml(path) {
+            serde_yaml::from_str(&document)?
+        } else {
+            serde_json::from_str(&document)?
+        };
+        if bundle.version != EXTENSION_BUNDLE_VERSION {
+            bail!(
+                "Unsupported extension bundle version {} (expected {})",
+                bundle.version,
+                EXTENSION_BUNDLE_VERSION
+            );
+        }
+
+        let mut extensions = Self::get_global_extensions_map(config)?;
+        let mut seen = HashSet::new();
+        let mut summary = BundleImportSummary::default();
+        let mut to_import = Vec::new();
+        for entry in bundle.extensions {
+            validate_bundle_entry(&entry)?;
+            let key = entry.config.key();
+            if !seen.insert(key.clone()) {
+                bail!(
+                    "Extension '{}' appears more than once in the bundle",
+                    entry.config.name()
+                );
+            }
+            if extensions.contains_key(&key) {
+                match policy {
+                    OverwritePolicy::Skip => {
+                        summary.skipped.push(entry.config.name());
+                        continue;
+                    }
+                    OverwritePolicy::Fail => {
+                        bail!("Extension '{}' is already configured", entry.config.name())
+                    }
+                    OverwritePolicy::Overwrite => {}
+                }
+            }
+            to_import.push(entry);
+        }
+
+        for entry in &to_import {
+            if let ExtensionConfig::Stdio { cmd, args, .. } = &entry.config {
+                deny_if_malicious_cmd_args(cmd, args).await?;
+            }
+        }
+
+        for entry in to_import {
+            let name = entry.config.name();
+            let (keys, envs) = env_keys(&entry.config);
+            let provided = envs.map(Envs::get_env).unwrap_or_default();
+            for key in keys {
+                if provided.contains_key(key) || config.get_secret::<String>(key).is_ok() {
+                    continue;
+                }
+                match prompt_secret(&name, key)? {
+                    Some(value) => config.set_secret(key, serde_json::Value::String(value))?,
+                    None => summary.missing_secrets.push((name.clone(), key.clone())),
+                }
+            }
+            extensions.insert(entry.config.key(), entry);
+            summary.imported.push(name);
+        }
+
+        Self::save_extensions_map(config, extensions)?;
+        Ok(summary)
+    }
+
     pub fn is_enabled(key: &str) -> Result<bool> {
-        let extensions = Self::get_extensions_map()?;
+        let extensions = Self::get_extensions_map(Config::global())?;
         Ok(extensions.get(key).map(|e| e.enabled).unwrap_or(false))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::{NamedTempFile, TempDir};
+
+    fn test_config() -> (Config, NamedTempFile, NamedTempFile) {
+        let config_file = NamedTempFile::new().unwrap();
+        let secrets_file = NamedTempFile::new().unwrap();
+        let config =
+            Config::new_with_file_secrets(config_file.path(), secrets_file.path()).unwrap();
+        (config, config_file, secrets_file)
+    }
+
+    fn stdio_extension() -> ExtensionEntry {
+        ExtensionEntry {
+            enabled: true,
+            config: ExtensionConfig::Stdio {
+                name: "GitHub Tools".to_string(),
+                cmd: "github-mcp-server".to_string(),
+                args: vec!["stdio".to_string()],
+                envs: Envs::new(HashMap::from([(
+                    "GITHUB_TOKEN".to_string(),
+                    "ghp_supersecret".to_string(),
+                )])),
+                env_keys: vec!["GITHUB_HOST".to_string()],
+                timeout: Some(120),
+                description: Some("GitHub issues and PRs".to_string()),
+                bundled: None,
+                available_tools: vec![],
+                depends_on: Vec::new(),
+                response_limits: None,
+            },
+        }
+    }
+
+    fn http_extension() -> ExtensionEntry {
+        ExtensionEntry {
+            enabled: false,
+            config: ExtensionConfig::StreamableHttp {
+                name: "docs".to_string(),
+                uri: "https://mcp.example.com/docs".to_string(),
+                envs: Envs::default(),
+                env_keys: vec![],
+                headers: HashMap::from([
+                    (
+                        "Authorization".to_string(),
+                        "Bearer sk-docs-secret".to_string(),
+                    ),
+                    ("X-Team".to_string(), "platform".to_string()),
+                ]),
+                description: None,
+                timeout: Some(60),
+                bundled: None,
+                available_tools: vec![],
+                depends_on: Vec::new(),
+                response_limits: None,
+                keep_alive_interval: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bundle_round_trip_strips_secrets() {
+        let (source, _c1, _s1) = test_config();
+        ExtensionConfigManager::set_in(&source, stdio_extension()).unwrap();
+        ExtensionConfigManager::set_in(&source, http_extension()).unwrap();
+
+        let dir = TempDir::new().unwrap();
+        for file_name in ["bundle.json", "bundle.yaml"] {
+            let path = dir.path().join(file_name);
+            ExtensionConfigManager::export_bundle_in(&source, &[], &path).unwrap();
+
+            let document = std::fs::read_to_string(&path).unwrap();
+            assert!(!document.contains("ghp_supersecret"));
+            assert!(!document.contains("sk-docs-secret"));
+            assert!(document.contains("GITHUB_TOKEN"));
+            assert!(document.contains("X-Team"));
+
+            let (target, _c2, _s2) = test_config();
+            target
+                .set_secret(
+                    "GITHUB_HOST",
+                    serde_json::Value::String("github.com".into()),
+                )
+                .unwrap();
+            let mut prompted = Vec::new();
+            let summary = ExtensionConfigManager::import_bundle_in(
+                &target,
+                &path,
+                OverwritePolicy::Fail,
+                |extension, key| {
+                    prompted.push((extension.to_string(), key.to_string()));
+                    Ok(Some("ghp_mine".to_string()))
+                },
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(summary.imported, vec!["docs", "GitHub Tools"]);
+            assert!(summary.missing_secrets.is_empty());
+            // Only the secret that wasn't stored yet is asked for
+            assert_eq!(
+                prompted,
+                vec![("GitHub Tools".to_string(), "GITHUB_TOKEN".to_string())]
+            );
+            assert_eq!(
+                target.get_secret::<String>("GITHUB_TOKEN").unwrap(),
+                "ghp_mine"
+            );
+
+            let imported = ExtensionConfigManager::get_extensions_map(&target).unwrap();
+            let ExtensionConfig::Stdio {
+                cmd,
+                envs,
+                env_keys,
+                timeout,
+                ..
+            } = &imported["githubtools"].config
+            else {
+                panic!("expected a stdio extension");
+            };
+            assert_eq!(cmd, "github-mcp-server");
+            assert!(envs.get_env().is_empty());
+            assert_eq!(env_keys, &vec!["GITHUB_HOST", "GITHUB_TOKEN"]);
+            assert_eq!(*timeout, Some(120));
+            assert!(!imported["docs"].enabled);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_respects_overwrite_policy() {
+        let (source, _c1, _s1) = test_config();
+        ExtensionConfigManager::set_in(&source, http_extension()).unwrap();
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bundle.json");
+        ExtensionConfigManager::export_bundle_in(&source, &["docs".to_string()], &path).unwrap();
+
+        let (target, _c2, _s2) = test_config();
+        let mut existing = http_extension();
+        existing.enabled = true;
+        ExtensionConfigManager::set_in(&target, existing).unwrap();
+
+        let err = ExtensionConfigManager::import_bundle_in(
+            &target,
+            &path,
+            OverwritePolicy::Fail,
+            |_, _| Ok(None),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("already configured"));
+
+        let summary = ExtensionConfigManager::import_bundle_in(
+            &target,
+            &path,
+            OverwritePolicy::Skip,
+            |_, _| Ok(None),
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.skipped, vec!["docs"]);
+        assert!(ExtensionConfigManager::get_extensions_map(&target).unwrap()["docs"].enabled);
+
+        let summary = ExtensionConfigManager::import_bundle_in(
+            &target,
+            &path,
+            OverwritePolicy::Overwrite,
+            |_, _| Ok(None),
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.imported, vec!["docs"]);
+        assert!(!ExtensionConfigManager::get_extensions_map(&target).unwrap()["docs"].enabled);
+    }
+
+    #[tokio::test]
+    async fn test_import_validates_before_writing() {
+        let (target, _c, _s) = test_config();
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bundle.json");
+        let mut invalid = http_extension();
+        if let ExtensionConfig::StreamableHttp { uri, .. } = &mut invalid.config {
+            *uri = "not a url".to_string();
+        }
+        let bundle = ExtensionBundle {
+            version: EXTENSION_BUNDLE_VERSION,
+            extensions: vec![stdio_extension(), invalid],
+        };
+        std::fs::write(&path, serde_json::to_string(&bundle).unwrap()).unwrap();
+
+        let err = ExtensionConfigManager::import_bundle_in(
+            &target,
+            &path,
+            OverwritePolicy::Fail,
+            |_, _| Ok(None),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid uri"));
+        assert!(ExtensionConfigManager::get_all_in(&target)
+            .unwrap()
+            .is_empty());
+
+        let err =
+            ExtensionConfigManager::export_bundle_in(&target, &["missing".to_string()], &path)
+                .unwrap_err();
+        assert!(err.to_string().contains("No extension named 'missing'"));
+    }
+}
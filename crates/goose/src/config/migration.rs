@@ -0,0 +1,144 @@
+//! Upgrades config files written by older versions of goose to the current layout
+//!
+//! The config file records the layout it was written with under `GOOSE_CONFIG_VERSION`. Files
+//! without one predate versioning and are treated as version 0. Each migration moves values
+//! from the layout before it to its own, and reports what it changed so that it can be logged.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub const CONFIG_VERSION_KEY: &str = "GOOSE_CONFIG_VERSION";
+pub const CONFIG_VERSION: u64 = 1;
+
+/// A step from the layout before `version` to `version`
+pub struct Migration {
+    pub version: u64,
+    pub apply: fn(&mut HashMap<String, Value>) -> Vec<String>,
+}
+
+/// Version 1 is the first versioned layout and matches the unversioned one. Add an entry here,
+/// and bump [`CONFIG_VERSION`], when a key is renamed or restructured.
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// Bring values read from a config file up to the current layout, removing the version
+/// field. Returns a description of each change, empty when nothing needed migrating.
+///
+/// Files written by a newer goose are left as they are, version included, so that saving them
+/// again does not mark them as an older layout.
+pub fn migrate_values(values: &mut HashMap<String, Value>) -> Vec<String> {
+    apply_migrations(values, MIGRATIONS)
+}
+
+fn apply_migrations(values: &mut HashMap<String, Value>, migrations: &[Migration]) -> Vec<String> {
+    let version = match values.get(CONFIG_VERSION_KEY) {
+        None => 0,
+        Some(value) => match value.as_u64() {
+            Some(version) => version,
+            None => {
+                tracing::warn!("Ignoring config version {}: expected an integer", value);
+                0
+            }
+        },
+    };
+    if version > CONFIG_VERSION {
+        tracing::warn!(
+            "Config was written with version {} but this goose only understands up to {}, \
+             loading it as is",
+            version,
+            CONFIG_VERSION
+        );
+        return Vec::new();
+    }
+    values.remove(CONFIG_VERSION_KEY);
+
+    migrations
+        .iter()
+        .filter(|migration| migration.version > version)
+        .flat_map(|migration| (migration.apply)(values))
+        .collect()
+}
+
+/// The version to record when saving `values`: the current one, unless they came from a file
+/// written by a newer goose
+pub fn version_to_save(values: &HashMap<String, Value>) -> u64 {
+    values
+        .get(CONFIG_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .map_or(CONFIG_VERSION, |version| version.max(CONFIG_VERSION))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn values(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn rename_model(values: &mut HashMap<String, Value>) -> Vec<String> {
+        match values.remove("MODEL") {
+            Some(model) => {
+                values.insert("GOOSE_MODEL".to_string(), model);
+                vec!["renamed 'MODEL' to 'GOOSE_MODEL'".to_string()]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    const TEST_MIGRATIONS: &[Migration] = &[Migration {
+        version: 1,
+        apply: rename_model,
+    }];
+
+    #[test]
+    fn test_older_configs_are_migrated() {
+        let mut config = values(json!({"MODEL": "gpt-4o", "GOOSE_MODE": "auto"}));
+        let changes = apply_migrations(&mut config, TEST_MIGRATIONS);
+        assert_eq!(
+            config,
+            values(json!({"GOOSE_MODEL": "gpt-4o", "GOOSE_MODE": "auto"}))
+        );
+        assert_eq!(changes, vec!["renamed 'MODEL' to 'GOOSE_MODEL'"]);
+
+        // Migrations already applied are skipped
+        let mut config = values(json!({"MODEL": "gpt-4o", CONFIG_VERSION_KEY: 1}));
+        assert!(apply_migrations(&mut config, TEST_MIGRATIONS).is_empty());
+        assert_eq!(config, values(json!({"MODEL": "gpt-4o"})));
+    }
+
+    #[test]
+    fn test_current_configs_are_untouched() {
+        let current = values(json!({
+            "GOOSE_PROVIDER": "openai",
+            "security": {"enabled": true},
+            // A user value under a generic name is not mistaken for the layout version
+            "version": 7,
+        }));
+
+        let mut unversioned = current.clone();
+        assert!(migrate_values(&mut unversioned).is_empty());
+        assert_eq!(unversioned, current);
+
+        // The version is taken out even when there is nothing to migrate
+        let mut versioned = current.clone();
+        versioned.insert(CONFIG_VERSION_KEY.to_string(), json!(CONFIG_VERSION));
+        assert!(migrate_values(&mut versioned).is_empty());
+        assert_eq!(versioned, current);
+        assert_eq!(version_to_save(&versioned), CONFIG_VERSION);
+    }
+
+    #[test]
+    fn test_newer_versions_are_kept() {
+        let mut config = values(json!({
+            CONFIG_VERSION_KEY: CONFIG_VERSION + 1,
+            "MODEL": "gpt-4o",
+        }));
+        assert!(apply_migrations(&mut config, TEST_MIGRATIONS).is_empty());
+        assert_eq!(
+            config,
+            values(json!({CONFIG_VERSION_KEY: CONFIG_VERSION + 1, "MODEL": "gpt-4o"}))
+        );
+        assert_eq!(version_to_save(&config), CONFIG_VERSION + 1);
+    }
+}
@@ -2,18 +2,27 @@ pub mod base;
 pub mod custom_providers;
 mod experiments;
 pub mod extensions;
+pub mod migration;
 pub mod permission;
+pub mod profiles;
+pub mod signup_anthropic;
+mod signup_common;
 pub mod signup_openrouter;
 pub mod signup_tetrate;
+pub mod validation;
 
 pub use crate::agents::ExtensionConfig;
-pub use base::{get_config_dir, Config, ConfigError, APP_STRATEGY};
+pub use base::{get_config_dir, Config, ConfigError, ConfigSource, APP_STRATEGY};
 pub use custom_providers::CustomProviderConfig;
 pub use experiments::ExperimentManager;
 pub use extensions::{ExtensionConfigManager, ExtensionEntry};
 pub use permission::PermissionManager;
+pub use profiles::ProviderProfile;
+pub use signup_anthropic::configure_anthropic;
+pub use signup_common::{validate_signup_model, AUTH_DEBUG_ENV};
 pub use signup_openrouter::configure_openrouter;
 pub use signup_tetrate::configure_tetrate;
+pub use validation::ConfigProblem;
 
 pub use extensions::DEFAULT_DISPLAY_NAME;
 pub use extensions::DEFAULT_EXTENSION;
@@ -1,7 +1,10 @@
 use super::APP_STRATEGY;
+use chrono::{DateTime, Utc};
 use etcetera::{choose_app_strategy, AppStrategy};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
 use std::fs;
 use std::path::{Path, PathBuf};
 use utoipa::ToSchema;
@@ -23,13 +26,137 @@ pub struct PermissionConfig {
     pub never_allow: Vec<String>,  // List of tools that are never allowed
 }
 
+/// Decision applied when a permission rule matches.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleDecision {
+    Allow,
+    Ask,
+    Deny,
+}
+
+impl From<RuleDecision> for PermissionLevel {
+    fn from(decision: RuleDecision) -> Self {
+        match decision {
+            RuleDecision::Allow => PermissionLevel::AlwaysAllow,
+            RuleDecision::Ask => PermissionLevel::AskBefore,
+            RuleDecision::Deny => PermissionLevel::NeverAllow,
+        }
+    }
+}
+
+/// A fine-grained permission rule, e.g. "allow developer__shell when the command starts with `git `".
+///
+/// `tool` is a glob over the full tool name (`*` and `?` wildcards), so `developer__*` covers
+/// every tool of an extension. When `argument_pattern` is set, the rule only matches if the
+/// regex matches at least one string value anywhere in the tool arguments.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, ToSchema)]
+pub struct PermissionRule {
+    pub tool: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub argument_pattern: Option<String>,
+    pub decision: RuleDecision,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl PermissionRule {
+    pub fn new(tool: impl Into<String>, decision: RuleDecision) -> Self {
+        Self {
+            tool: tool.into(),
+            argument_pattern: None,
+            decision,
+            expires_at: None,
+        }
+    }
+
+    pub fn with_argument_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.argument_pattern = Some(pattern.into());
+        self
+    }
+
+    pub fn with_expiry(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expiry| expiry <= now)
+    }
+
+    fn matches(&self, tool_name: &str, arguments: &Value) -> bool {
+        if !glob_matches(&self.tool, tool_name) {
+            return false;
+        }
+        match &self.argument_pattern {
+            None => true,
+            Some(pattern) => match Regex::new(pattern) {
+                Ok(re) => any_string_matches(arguments, &re),
+                Err(e) => {
+                    tracing::warn!(
+                        "Ignoring permission rule with invalid regex '{}': {}",
+                        pattern,
+                        e
+                    );
+                    false
+                }
+            },
+        }
+    }
+}
+
+/// Record of a rule deciding a tool call, kept for auditing.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PermissionRuleHit {
+    pub rule: PermissionRule,
+    pub tool_name: String,
+    pub at: DateTime<Utc>,
+}
+
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let mut regex = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).is_ok_and(|re| re.is_match(text))
+}
+
+fn any_string_matches(value: &Value, re: &Regex) -> bool {
+    match value {
+        Value::String(s) => re.is_match(s),
+        Value::Array(items) => items.iter().any(|v| any_string_matches(v, re)),
+        Value::Object(map) => map.values().any(|v| any_string_matches(v, re)),
+        _ => false,
+    }
+}
+
+/// On-disk layout of permission.yaml: permission categories plus the ordered rule list.
+#[derive(Debug, Deserialize, Serialize, Default)]
+struct PermissionFile {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    rules: Vec<PermissionRule>,
+    #[serde(flatten)]
+    permission_map: HashMap<String, PermissionConfig>,
+}
+
 /// PermissionManager manages permission configurations for various tools.
 #[derive(Debug, Clone)]
 pub struct PermissionManager {
     config_path: PathBuf, // Path to the permission configuration file
     permission_map: HashMap<String, PermissionConfig>, // Mapping of permission names to configurations
+    rules: Vec<PermissionRule>, // Ordered rules evaluated before the per-tool levels
+    rule_hits: VecDeque<PermissionRuleHit>, // Most recent rule decisions, for auditing
+    load_error: Option<String>, // Why permission.yaml could not be read; saving is refused while set
 }
 
+/// Number of rule decisions kept by `PermissionManager::rule_hits`
+const MAX_RULE_HITS: usize = 256;
+
 // Constants representing specific permission categories
 const USER_PERMISSION: &str = "user";
 const SMART_APPROVE_PERMISSION: &str = "smart_approve";
@@ -46,42 +173,75 @@ impl Default for PermissionManager {
         std::fs::create_dir_all(&config_dir).expect("Failed to create config directory");
         let config_path = config_dir.join("permission.yaml");
 
-        // Load the existing configuration file or create an empty map if the file doesn't exist
-        let permission_map = if config_path.exists() {
-            // Load the configuration file
-            let file_contents =
-                fs::read_to_string(&config_path).expect("Failed to read permission.yaml");
-            serde_yaml::from_str(&file_contents).unwrap_or_else(|_| HashMap::new())
+        Self::load(config_path)
+    }
+}
+
+impl PermissionManager {
+    fn load(config_path: PathBuf) -> Self {
+        // Load the existing configuration file or start empty if the file doesn't exist.
+        // A file that fails to parse is never overwritten, so a typo in one rule cannot
+        // wipe out the user's saved permissions.
+        let mut load_error = None;
+        let file = if config_path.exists() {
+            let parsed = fs::read_to_string(&config_path)
+                .map_err(|e| e.to_string())
+                .and_then(|contents| {
+                    serde_yaml::from_str::<PermissionFile>(&contents).map_err(|e| e.to_string())
+                });
+            match parsed {
+                Ok(file) => file,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to load {}: {}. Permissions and rules from it are not applied \
+                         and changes will not be saved until it is fixed.",
+                        config_path.display(),
+                        e
+                    );
+                    load_error = Some(e);
+                    PermissionFile::default()
+                }
+            }
         } else {
-            HashMap::new() // No config file, create an empty map
+            PermissionFile::default()
         };
 
         PermissionManager {
             config_path,
-            permission_map,
+            permission_map: file.permission_map,
+            rules: file.rules,
+            rule_hits: VecDeque::new(),
+            load_error,
         }
     }
-}
 
-impl PermissionManager {
+    fn save(&self) {
+        if let Some(error) = &self.load_error {
+            tracing::error!(
+                "Not saving permissions: {} could not be loaded ({})",
+                self.config_path.display(),
+                error
+            );
+            return;
+        }
+        let file = PermissionFile {
+            rules: self.rules.clone(),
+            permission_map: self.permission_map.clone(),
+        };
+        let yaml_content =
+            serde_yaml::to_string(&file).expect("Failed to serialize permission config");
+        fs::write(&self.config_path, yaml_content).expect("Failed to write to permission.yaml");
+    }
+
     /// Creates a new `PermissionManager` with a specified config path.
     pub fn new<P: AsRef<Path>>(config_path: P) -> Self {
-        let config_path = config_path.as_ref().to_path_buf();
-
-        // Load the existing configuration file or create an empty map if the file doesn't exist
-        let permission_map = if config_path.exists() {
-            // Load the configuration file
-            let file_contents =
-                fs::read_to_string(&config_path).expect("Failed to read permission.yaml");
-            serde_yaml::from_str(&file_contents).unwrap_or_else(|_| HashMap::new())
-        } else {
-            HashMap::new() // No config file, create an empty map
-        };
+        Self::load(config_path.as_ref().to_path_buf())
+    }
 
-        PermissionManager {
-            config_path,
-            permission_map,
-        }
+    /// Why the permission file could not be loaded, if it failed to parse. While set, changes
+    /// are kept in memory only.
+    pub fn load_error(&self) -> Option<&str> {
+        self.load_error.as_deref()
     }
 
     /// Returns a list of all the names (keys) in the permission map.
@@ -165,10 +325,73 @@ impl PermissionManager {
                 .push(principal_name.to_string()),
         }
 
-        // Serialize the updated permission map and write it back to the config file
-        let yaml_content = serde_yaml::to_string(&self.permission_map)
-            .expect("Failed to serialize permission config");
-        fs::write(&self.config_path, yaml_content).expect("Failed to write to permission.yaml");
+        // Serialize the updated permissions and write them back to the config file
+        self.save();
+    }
+
+    /// Returns the configured permission rules in evaluation order.
+    pub fn get_rules(&self) -> &[PermissionRule] {
+        &self.rules
+    }
+
+    /// Appends a rule (evaluated after existing rules) and persists it.
+    pub fn add_rule(&mut self, rule: PermissionRule) {
+        self.rules.push(rule);
+        self.save();
+    }
+
+    /// Inserts a rule at the front so it takes precedence, e.g. from an "always allow this" prompt.
+    pub fn prepend_rule(&mut self, rule: PermissionRule) {
+        self.rules.insert(0, rule);
+        self.save();
+    }
+
+    /// Removes the rule at `index`, returning it if it existed.
+    pub fn remove_rule(&mut self, index: usize) -> Option<PermissionRule> {
+        if index >= self.rules.len() {
+            return None;
+        }
+        let rule = self.rules.remove(index);
+        self.save();
+        Some(rule)
+    }
+
+    /// Evaluates rules in order and returns the decision of the first unexpired match.
+    /// Matches are recorded in `rule_hits`, keeping the latest `MAX_RULE_HITS`; expired rules
+    /// are skipped.
+    pub fn evaluate_rules(
+        &mut self,
+        tool_name: &str,
+        arguments: &Value,
+    ) -> Option<PermissionLevel> {
+        let now = Utc::now();
+        let rule = self
+            .rules
+            .iter()
+            .find(|rule| !rule.is_expired(now) && rule.matches(tool_name, arguments))?
+            .clone();
+
+        tracing::info!(
+            tool_name,
+            rule_tool = %rule.tool,
+            decision = ?rule.decision,
+            "Permission rule matched"
+        );
+        let level = rule.decision.into();
+        if self.rule_hits.len() == MAX_RULE_HITS {
+            self.rule_hits.pop_front();
+        }
+        self.rule_hits.push_back(PermissionRuleHit {
+            rule,
+            tool_name: tool_name.to_string(),
+            at: now,
+        });
+        Some(level)
+    }
+
+    /// The most recent rule matches recorded by `evaluate_rules`, oldest first.
+    pub fn rule_hits(&self) -> impl ExactSizeIterator<Item = &PermissionRuleHit> {
+        self.rule_hits.iter()
     }
 
     /// Removes all entries where the principal name starts with the given extension name.
@@ -185,9 +408,7 @@ impl PermissionManager {
                 .retain(|p| !p.starts_with(extension_name));
         }
 
-        let yaml_content = serde_yaml::to_string(&self.permission_map)
-            .expect("Failed to serialize permission config");
-        fs::write(&self.config_path, yaml_content).expect("Failed to write to permission.yaml");
+        self.save();
     }
 }
 
@@ -305,4 +526,151 @@ mod tests {
             .always_allow
             .contains(&"nonprefix__tool2".to_string()));
     }
+
+    #[test]
+    fn test_rules_evaluated_in_order() {
+        let mut manager = create_test_permission_manager();
+        manager.add_rule(
+            PermissionRule::new("developer__shell", RuleDecision::Allow)
+                .with_argument_pattern("^git "),
+        );
+        manager.add_rule(PermissionRule::new("developer__*", RuleDecision::Ask));
+
+        let git = serde_json::json!({"command": "git status"});
+        let rm = serde_json::json!({"command": "rm -rf target"});
+        assert_eq!(
+            manager.evaluate_rules("developer__shell", &git),
+            Some(PermissionLevel::AlwaysAllow)
+        );
+        assert_eq!(
+            manager.evaluate_rules("developer__shell", &rm),
+            Some(PermissionLevel::AskBefore)
+        );
+        assert_eq!(manager.evaluate_rules("memory__remember", &rm), None);
+
+        // A prepended rule wins over everything added before it
+        manager.prepend_rule(PermissionRule::new("*", RuleDecision::Deny));
+        assert_eq!(
+            manager.evaluate_rules("developer__shell", &git),
+            Some(PermissionLevel::NeverAllow)
+        );
+    }
+
+    #[test]
+    fn test_rule_argument_regex_matches_nested_strings() {
+        let mut manager = create_test_permission_manager();
+        manager.add_rule(
+            PermissionRule::new("*", RuleDecision::Deny).with_argument_pattern(r"~/\.ssh"),
+        );
+
+        let nested = serde_json::json!({"paths": ["README.md", {"target": "~/.ssh/id_rsa"}]});
+        assert_eq!(
+            manager.evaluate_rules("developer__text_editor", &nested),
+            Some(PermissionLevel::NeverAllow)
+        );
+        let prefix = serde_json::json!({"path": "~/.ssh_backup", "count": 2});
+        assert_eq!(
+            manager.evaluate_rules("developer__text_editor", &prefix),
+            Some(PermissionLevel::NeverAllow)
+        );
+        let clean = serde_json::json!({"path": "src/main.rs"});
+        assert_eq!(
+            manager.evaluate_rules("developer__text_editor", &clean),
+            None
+        );
+    }
+
+    #[test]
+    fn test_expired_rules_are_skipped() {
+        let mut manager = create_test_permission_manager();
+        manager.add_rule(
+            PermissionRule::new("tool", RuleDecision::Allow)
+                .with_expiry(Utc::now() - chrono::Duration::minutes(1)),
+        );
+        manager.add_rule(
+            PermissionRule::new("tool", RuleDecision::Ask)
+                .with_expiry(Utc::now() + chrono::Duration::hours(1)),
+        );
+
+        assert_eq!(
+            manager.evaluate_rules("tool", &serde_json::json!({})),
+            Some(PermissionLevel::AskBefore)
+        );
+    }
+
+    #[test]
+    fn test_rule_hits_are_recorded() {
+        let mut manager = create_test_permission_manager();
+        manager.add_rule(PermissionRule::new("a__*", RuleDecision::Allow));
+
+        manager.evaluate_rules("a__one", &serde_json::json!({}));
+        manager.evaluate_rules("b__two", &serde_json::json!({}));
+
+        let hits: Vec<_> = manager.rule_hits().collect();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].tool_name, "a__one");
+        assert_eq!(hits[0].rule.tool, "a__*");
+    }
+
+    #[test]
+    fn test_rule_hits_are_bounded() {
+        let mut manager = create_test_permission_manager();
+        manager.add_rule(PermissionRule::new("*", RuleDecision::Allow));
+
+        for i in 0..MAX_RULE_HITS + 10 {
+            manager.evaluate_rules(&format!("tool_{}", i), &serde_json::json!({}));
+        }
+
+        assert_eq!(manager.rule_hits().len(), MAX_RULE_HITS);
+        assert_eq!(manager.rule_hits().next().unwrap().tool_name, "tool_10");
+    }
+
+    #[test]
+    fn test_malformed_file_is_not_overwritten() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let contents = "user:\n  always_allow:\n  - tool1\n  ask_before: []\n  never_allow: []\n\
+                        rules:\n- tool: developer__shell\n  decision: sometimes\n";
+        std::fs::write(temp_file.path(), contents).unwrap();
+
+        let mut manager = PermissionManager::new(temp_file.path());
+        assert!(manager.load_error().is_some());
+
+        manager.update_user_permission("tool2", PermissionLevel::AlwaysAllow);
+        manager.add_rule(PermissionRule::new("*", RuleDecision::Deny));
+
+        // Changes apply in memory but the user's file is left for them to fix
+        assert_eq!(
+            manager.get_user_permission("tool2"),
+            Some(PermissionLevel::AlwaysAllow)
+        );
+        assert_eq!(std::fs::read_to_string(temp_file.path()).unwrap(), contents);
+    }
+
+    #[test]
+    fn test_rules_persist_alongside_permissions() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let expiry = Utc::now() + chrono::Duration::days(1);
+        {
+            let mut manager = PermissionManager::new(temp_file.path());
+            manager.update_user_permission("tool1", PermissionLevel::AlwaysAllow);
+            manager.add_rule(
+                PermissionRule::new("developer__shell", RuleDecision::Allow)
+                    .with_argument_pattern("^git ")
+                    .with_expiry(expiry),
+            );
+        }
+
+        let reloaded = PermissionManager::new(temp_file.path());
+        assert_eq!(
+            reloaded.get_user_permission("tool1"),
+            Some(PermissionLevel::AlwaysAllow)
+        );
+        assert_eq!(
+            reloaded.get_rules(),
+            &[PermissionRule::new("developer__shell", RuleDecision::Allow)
+                .with_argument_pattern("^git ")
+                .with_expiry(expiry)]
+        );
+        assert_eq!(reloaded.get_permission_names(), vec!["user".to_string()]);
+    }
 }
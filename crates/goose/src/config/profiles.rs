@@ -0,0 +1,266 @@
+use crate::config::base::{Config, ConfigError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Config key holding all named provider profiles
+pub const PROFILES_KEY: &str = "profiles";
+/// Config key recording which profile was last activated
+pub const ACTIVE_PROFILE_KEY: &str = "GOOSE_ACTIVE_PROFILE";
+
+/// A named provider/model/credential combination that can be swapped into the active slots.
+///
+/// Stored in the config file under `profiles.<name>`. The secret itself never lives in the
+/// config file; it is kept in the secret store under a profile-scoped key and copied into
+/// `secret_key` when the profile is activated.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProviderProfile {
+    pub provider: String,
+    pub model: String,
+    /// Name of the secret the provider reads, e.g. `OPENROUTER_API_KEY`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_key: Option<String>,
+}
+
+/// Keys the active profile overrides, and the profile field each one reads
+const PROFILE_FIELDS: [(&str, &str); 2] =
+    [("GOOSE_PROVIDER", "provider"), ("GOOSE_MODEL", "model")];
+
+/// The value the active profile in `values` sets for `key`, with the profile's name
+pub(crate) fn active_profile_value(
+    values: &HashMap<String, Value>,
+    key: &str,
+) -> Option<(String, Value)> {
+    let (_, field) = PROFILE_FIELDS.iter().find(|(name, _)| *name == key)?;
+    let profile = values.get(ACTIVE_PROFILE_KEY)?.as_str()?;
+    let value = values.get(PROFILES_KEY)?.get(profile)?.get(field)?.clone();
+    Some((profile.to_string(), value))
+}
+
+fn profile_secret_name(profile: &str, secret_key: &str) -> String {
+    format!("{}.{}.{}", PROFILES_KEY, profile, secret_key)
+}
+
+fn validate_profile_name(name: &str) -> Result<(), ConfigError> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ConfigError::InvalidValue(format!(
+            "profile name '{}' may only contain letters, digits, '-' and '_'",
+            name
+        )))
+    }
+}
+
+impl Config {
+    /// All saved provider profiles, keyed by name
+    pub fn list_profiles(&self) -> Result<BTreeMap<String, ProviderProfile>, ConfigError> {
+        match self.get_param(PROFILES_KEY) {
+            Ok(profiles) => Ok(profiles),
+            Err(ConfigError::NotFound(_)) => Ok(BTreeMap::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn get_profile(&self, name: &str) -> Result<ProviderProfile, ConfigError> {
+        self.list_profiles()?
+            .remove(name)
+            .ok_or_else(|| ConfigError::NotFound(format!("{}.{}", PROFILES_KEY, name)))
+    }
+
+    /// Save (or replace) a profile. When `secret` is given it is stored in the secret store
+    /// under a profile-scoped key, so several accounts for the same provider can coexist.
+    pub fn save_profile(
+        &self,
+        name: &str,
+        profile: ProviderProfile,
+        secret: Option<Value>,
+    ) -> Result<(), ConfigError> {
+        validate_profile_name(name)?;
+        if let Some(secret) = secret {
+            let secret_key = profile.secret_key.as_deref().ok_or_else(|| {
+                ConfigError::InvalidValue(format!(
+                    "profile '{}' has a secret but no secret_key to store it under",
+                    name
+                ))
+            })?;
+            self.set_secret(&profile_secret_name(name, secret_key), secret)?;
+        }
+
+        let mut profiles = self.list_profiles()?;
+        profiles.insert(name.to_string(), profile);
+        self.set_param(PROFILES_KEY, serde_json::to_value(profiles)?)
+    }
+
+    /// Capture the currently active provider, model and (optionally) secret as a profile
+    pub fn save_active_as_profile(
+        &self,
+        name: &str,
+        secret_key: Option<&str>,
+    ) -> Result<ProviderProfile, ConfigError> {
+        let profile = ProviderProfile {
+            provider: self.get_param("GOOSE_PROVIDER")?,
+            model: self.get_param("GOOSE_MODEL")?,
+            secret_key: secret_key.map(str::to_string),
+        };
+        let secret = match secret_key {
+            Some(key) => Some(self.get_secret::<Value>(key)?),
+            None => None,
+        };
+        self.save_profile(name, profile.clone(), secret)?;
+        Ok(profile)
+    }
+
+    /// Copy a profile's provider, model and secret into the active slots
+    pub fn activate_profile(&self, name: &str) -> Result<ProviderProfile, ConfigError> {
+        let profile = self.get_profile(name)?;
+
+        if let Some(secret_key) = &profile.secret_key {
+            match self.get_secret::<Value>(&profile_secret_name(name, secret_key)) {
+                Ok(secret) => self.set_secret(secret_key, secret)?,
+                // Profiles may rely on a secret configured outside the profile
+                Err(ConfigError::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.set_param("GOOSE_PROVIDER", Value::String(profile.provider.clone()))?;
+        self.set_param("GOOSE_MODEL", Value::String(profile.model.clone()))?;
+        self.set_param(ACTIVE_PROFILE_KEY, Value::String(name.to_string()))?;
+        Ok(profile)
+    }
+
+    /// Name of the profile most recently activated, if any
+    pub fn active_profile(&self) -> Option<String> {
+        self.get_param(ACTIVE_PROFILE_KEY).ok()
+    }
+
+    pub fn delete_profile(&self, name: &str) -> Result<(), ConfigError> {
+        let mut profiles = self.list_profiles()?;
+        let profile = profiles
+            .remove(name)
+            .ok_or_else(|| ConfigError::NotFound(format!("{}.{}", PROFILES_KEY, name)))?;
+
+        if let Some(secret_key) = &profile.secret_key {
+            self.delete_secret(&profile_secret_name(name, secret_key))?;
+        }
+        self.set_param(PROFILES_KEY, serde_json::to_value(profiles)?)?;
+
+        if self.active_profile().as_deref() == Some(name) {
+            self.delete(ACTIVE_PROFILE_KEY)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn test_config() -> (Config, NamedTempFile, NamedTempFile) {
+        let config_file = NamedTempFile::new().unwrap();
+        let secrets_file = NamedTempFile::new().unwrap();
+        let config =
+            Config::new_with_file_secrets(config_file.path(), secrets_file.path()).unwrap();
+        (config, config_file, secrets_file)
+    }
+
+    fn openrouter_profile(model: &str) -> ProviderProfile {
+        ProviderProfile {
+            provider: "openrouter".to_string(),
+            model: model.to_string(),
+            secret_key: Some("OPENROUTER_API_KEY".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_switch_between_profiles() -> Result<(), ConfigError> {
+        let (config, _c, _s) = test_config();
+        config.save_profile(
+            "work",
+            openrouter_profile("anthropic/claude-sonnet-4"),
+            Some(Value::String("work-key".into())),
+        )?;
+        config.save_profile(
+            "personal",
+            openrouter_profile("openai/gpt-4o"),
+            Some(Value::String("personal-key".into())),
+        )?;
+
+        let names: Vec<String> = config.list_profiles()?.into_keys().collect();
+        assert_eq!(names, vec!["personal", "work"]);
+
+        config.activate_profile("work")?;
+        assert_eq!(config.get_param::<String>("GOOSE_PROVIDER")?, "openrouter");
+        assert_eq!(
+            config.get_param::<String>("GOOSE_MODEL")?,
+            "anthropic/claude-sonnet-4"
+        );
+        assert_eq!(
+            config.get_secret::<String>("OPENROUTER_API_KEY")?,
+            "work-key"
+        );
+        assert_eq!(config.active_profile().as_deref(), Some("work"));
+
+        config.activate_profile("personal")?;
+        assert_eq!(config.get_param::<String>("GOOSE_MODEL")?, "openai/gpt-4o");
+        assert_eq!(
+            config.get_secret::<String>("OPENROUTER_API_KEY")?,
+            "personal-key"
+        );
+        assert_eq!(config.active_profile().as_deref(), Some("personal"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_secret_is_not_written_to_config_file() -> Result<(), ConfigError> {
+        let (config, config_file, _s) = test_config();
+        config.save_profile(
+            "work",
+            openrouter_profile("m"),
+            Some(Value::String("super-secret".into())),
+        )?;
+
+        let contents = std::fs::read_to_string(config_file.path())?;
+        assert!(contents.contains("openrouter"));
+        assert!(!contents.contains("super-secret"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_active_and_delete() -> Result<(), ConfigError> {
+        let (config, _c, _s) = test_config();
+        config.set_param("GOOSE_PROVIDER", Value::String("tetrate".into()))?;
+        config.set_param("GOOSE_MODEL", Value::String("claude".into()))?;
+        config.set_secret("TETRATE_API_KEY", Value::String("k".into()))?;
+
+        let profile = config.save_active_as_profile("router", Some("TETRATE_API_KEY"))?;
+        assert_eq!(profile.provider, "tetrate");
+        config.activate_profile("router")?;
+
+        config.delete_profile("router")?;
+        assert!(config.list_profiles()?.is_empty());
+        assert!(config.active_profile().is_none());
+        assert!(matches!(
+            config.activate_profile("router"),
+            Err(ConfigError::NotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_invalid_profile_names() {
+        let (config, _c, _s) = test_config();
+        for name in ["", "has.dot", "has space"] {
+            assert!(matches!(
+                config.save_profile(name, openrouter_profile("m"), None),
+                Err(ConfigError::InvalidValue(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Signup with an Anthropic API key.
+//!
+//! Anthropic doesn't hand out API keys through an OAuth or PKCE flow, so instead of waiting on
+//! a callback this opens the console page where keys are created, takes the key the user
+//! pastes, and checks it against the API before it is stored.
+
+#[cfg(test)]
+mod tests;
+
+use crate::config::signup_common::{redact, signup_model};
+use crate::config::Config;
+use crate::providers::anthropic::{ANTHROPIC_API_VERSION, ANTHROPIC_DEFAULT_MODEL};
+use anyhow::{anyhow, Result};
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+use std::time::Duration;
+
+const ANTHROPIC_KEYS_URL: &str = "https://console.anthropic.com/settings/keys";
+const ANTHROPIC_DEFAULT_HOST: &str = "https://api.anthropic.com";
+const ANTHROPIC_KEY_PREFIX: &str = "sk-ant-";
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub struct ApiKeyFlow {
+    host: String,
+}
+
+impl ApiKeyFlow {
+    /// Verify keys against `ANTHROPIC_HOST` when it is configured, or the public API
+    pub fn new() -> Result<Self> {
+        let host = Config::global()
+            .get_param("ANTHROPIC_HOST")
+            .unwrap_or_else(|_| ANTHROPIC_DEFAULT_HOST.to_string());
+        Ok(Self::with_host(host))
+    }
+
+    pub fn with_host(host: impl Into<String>) -> Self {
+        Self { host: host.into() }
+    }
+
+    pub fn get_auth_url(&self) -> String {
+        ANTHROPIC_KEYS_URL.to_string()
+    }
+
+    /// Trim a pasted key and reject anything that can't be an Anthropic API key
+    pub fn check_key_format(key: &str) -> Result<String> {
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(anyhow!("No API key was entered"));
+        }
+        if !key.starts_with(ANTHROPIC_KEY_PREFIX) || key.contains(char::is_whitespace) {
+            return Err(anyhow!(
+                "That doesn't look like an Anthropic API key, which starts with '{}'",
+                ANTHROPIC_KEY_PREFIX
+            ));
+        }
+        Ok(key.to_string())
+    }
+
+    /// Check that Anthropic accepts `key` by listing the models it can use
+    pub async fn verify_key(&self, key: &str) -> Result<()> {
+        tracing::info!(key = %redact(key), "Verifying Anthropic API key");
+        let response = Client::builder()
+            .timeout(VERIFY_TIMEOUT)
+            .build()?
+            .get(format!("{}/v1/models", self.host.trim_end_matches('/')))
+            .header("x-api-key", key)
+            .header("anthropic-version", ANTHROPIC_API_VERSION)
+            .send()
+            .await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(anyhow!(
+                "Anthropic rejected the API key, check that it was copied in full and is active"
+            )),
+            status => {
+                let error_text = response.text().await.unwrap_or_default();
+                tracing::warn!(%status, "API key verification failed");
+                Err(anyhow!(
+                    "Failed to verify API key: {} - {}",
+                    status,
+                    error_text
+                ))
+            }
+        }
+    }
+
+    /// Complete flow: open the console, read the key with `read_key`, and verify it.
+    /// Status lines are printed to stdout; use `complete_flow_with_status` to render them elsewhere.
+    pub async fn complete_flow<K>(&mut self, read_key: K) -> Result<String>
+    where
+        K: FnOnce() -> Result<String>,
+    {
+        self.complete_flow_with_status(|status| println!("{}", status), read_key)
+            .await
+    }
+
+    /// Complete flow, reporting user-facing status lines through `on_status`
+    pub async fn complete_flow_with_status<F, K>(
+        &mut self,
+        on_status: F,
+        read_key: K,
+    ) -> Result<String>
+    where
+        F: Fn(&str),
+        K: FnOnce() -> Result<String>,
+    {
+        let auth_url = self.get_auth_url();
+
+        on_status("Opening the Anthropic console to create an API key...");
+        if let Err(e) = webbrowser::open(&auth_url) {
+            tracing::warn!("Failed to open browser automatically: {}", e);
+            on_status(&format!("Please open this URL manually: {}", auth_url));
+        }
+
+        let api_key = Self::check_key_format(&read_key()?)?;
+
+        on_status("Checking the API key...");
+        self.verify_key(&api_key).await?;
+
+        Ok(api_key)
+    }
+}
+
+pub use self::ApiKeyFlow as AnthropicAuth;
+
+/// Store the key from signup and make anthropic the provider, with `model` or the default one
+pub fn configure_anthropic(config: &Config, api_key: String, model: Option<&str>) -> Result<()> {
+    config.set_secret("ANTHROPIC_API_KEY", Value::String(api_key))?;
+    config.set_param("GOOSE_PROVIDER", Value::String("anthropic".to_string()))?;
+    config.set_param(
+        "GOOSE_MODEL",
+        Value::String(signup_model(model, ANTHROPIC_DEFAULT_MODEL).to_string()),
+    )?;
+    Ok(())
+}
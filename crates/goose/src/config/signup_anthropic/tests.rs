@@ -0,0 +1,87 @@
+use super::*;
+use tempfile::TempDir;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const TEST_KEY: &str = "sk-ant-REDACTED";
+
+#[test]
+fn test_check_key_format() {
+    assert_eq!(
+        ApiKeyFlow::check_key_format(&format!("  {}\n", TEST_KEY)).unwrap(),
+        TEST_KEY
+    );
+    assert!(ApiKeyFlow::check_key_format("")
+        .unwrap_err()
+        .to_string()
+        .contains("No API key"));
+    for key in ["sk-or-v1-abc", "sk-ant-api03 with spaces"] {
+        assert!(ApiKeyFlow::check_key_format(key)
+            .unwrap_err()
+            .to_string()
+            .contains("starts with 'sk-ant-'"));
+    }
+}
+
+#[tokio::test]
+async fn test_verify_key() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .and(header("x-api-key", TEST_KEY))
+        .and(header("anthropic-version", ANTHROPIC_API_VERSION))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"data": []})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let flow = ApiKeyFlow::with_host(format!("{}/", server.uri()));
+    flow.verify_key(TEST_KEY).await.unwrap();
+
+    let err = flow.verify_key("sk-ant-revoked").await.unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("Anthropic rejected the API key"));
+}
+
+#[test]
+fn test_configure_anthropic() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = Config::new_with_file_secrets(
+        temp_dir.path().join("config.yaml"),
+        temp_dir.path().join("secrets.yaml"),
+    )
+    .unwrap();
+
+    // Environment variables take precedence over the stored values
+    let unset = [
+        ("ANTHROPIC_API_KEY", None::<&str>),
+        ("GOOSE_PROVIDER", None),
+        ("GOOSE_MODEL", None),
+    ];
+    temp_env::with_vars(unset, || {
+        configure_anthropic(&config, TEST_KEY.to_string(), None).unwrap();
+        assert_eq!(
+            config.get_secret::<String>("ANTHROPIC_API_KEY").unwrap(),
+            TEST_KEY
+        );
+        assert_eq!(
+            config.get_param::<String>("GOOSE_PROVIDER").unwrap(),
+            "anthropic"
+        );
+        assert_eq!(
+            config.get_param::<String>("GOOSE_MODEL").unwrap(),
+            ANTHROPIC_DEFAULT_MODEL
+        );
+
+        configure_anthropic(&config, TEST_KEY.to_string(), Some("claude-opus-4-0")).unwrap();
+        assert_eq!(
+            config.get_param::<String>("GOOSE_MODEL").unwrap(),
+            "claude-opus-4-0"
+        );
+    });
+}
@@ -0,0 +1,177 @@
+//! Helpers shared by the PKCE signup flows (OpenRouter, Tetrate).
+
+use crate::model::ModelConfig;
+use anyhow::{anyhow, Result};
+
+/// Environment variable that enables verbose (but still redacted) auth diagnostics
+pub const AUTH_DEBUG_ENV: &str = "GOOSE_AUTH_DEBUG";
+
+/// Number of characters kept visible at each end of a redacted value
+const REDACT_VISIBLE_CHARS: usize = 4;
+
+/// Whether verbose auth diagnostics were requested via `GOOSE_AUTH_DEBUG`
+pub(crate) fn auth_debug_enabled() -> bool {
+    std::env::var(AUTH_DEBUG_ENV)
+        .map(|v| !v.is_empty() && v != "0" && !v.eq_ignore_ascii_case("false"))
+        .unwrap_or(false)
+}
+
+/// Redact a secret so only the first and last four characters remain visible.
+/// Values too short to keep anything hidden are fully masked.
+pub(crate) fn redact(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= REDACT_VISIBLE_CHARS * 2 {
+        return "*".repeat(chars.len());
+    }
+    let head: String = chars[..REDACT_VISIBLE_CHARS].iter().collect();
+    let tail: String = chars[chars.len() - REDACT_VISIBLE_CHARS..].iter().collect();
+    format!("{}...{}", head, tail)
+}
+
+/// The model to configure at signup: the one asked for, or the provider's default when none was
+pub(crate) fn signup_model<'a>(requested: Option<&'a str>, default: &'a str) -> &'a str {
+    requested
+        .map(str::trim)
+        .filter(|model| !model.is_empty())
+        .unwrap_or(default)
+}
+
+/// Check that `model` is among the models offered, listing a few close names when it is not
+fn ensure_model_offered(provider_name: &str, model: &str, offered: &[String]) -> Result<()> {
+    if offered.iter().any(|m| m == model) {
+        return Ok(());
+    }
+    let needle = model.to_lowercase();
+    let similar: Vec<&str> = offered
+        .iter()
+        .filter(|m| {
+            let m = m.to_lowercase();
+            m.contains(&needle) || needle.contains(&m)
+        })
+        .take(5)
+        .map(String::as_str)
+        .collect();
+    if similar.is_empty() {
+        Err(anyhow!(
+            "{} does not offer model '{}'",
+            provider_name,
+            model
+        ))
+    } else {
+        Err(anyhow!(
+            "{} does not offer model '{}', did you mean: {}?",
+            provider_name,
+            model,
+            similar.join(", ")
+        ))
+    }
+}
+
+/// Check `model` against the models `provider_name` lists, using the credentials already stored
+/// by signup. Models are taken on trust when the provider can't list them.
+pub async fn validate_signup_model(provider_name: &str, model: &str) -> Result<()> {
+    let provider = crate::providers::create(provider_name, ModelConfig::new(model)?)?;
+    match provider.fetch_supported_models().await {
+        Ok(Some(offered)) => ensure_model_offered(provider_name, model, &offered),
+        Ok(None) => Ok(()),
+        Err(e) => {
+            tracing::warn!(
+                "Could not list {} models to check '{}': {}",
+                provider_name,
+                model,
+                e
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::fmt::MakeWriter;
+
+    /// A tracing writer that collects everything written into a shared buffer
+    #[derive(Clone, Default)]
+    pub struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl CapturedLogs {
+        pub fn contents(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+        }
+
+        /// Run `f` with a subscriber that records all events at DEBUG and above
+        pub fn capture<R>(&self, f: impl FnOnce() -> R) -> R {
+            let subscriber = tracing_subscriber::fmt()
+                .with_writer(self.clone())
+                .with_max_level(tracing::Level::DEBUG)
+                .with_ansi(false)
+                .finish();
+            tracing::subscriber::with_default(subscriber, f)
+        }
+    }
+
+    impl Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for CapturedLogs {
+        type Writer = CapturedLogs;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_keeps_first_and_last_four() {
+        assert_eq!(redact("abcdefghijklmnop"), "abcd...mnop");
+    }
+
+    #[test]
+    fn test_redact_masks_short_values() {
+        assert_eq!(redact("abcdefgh"), "********");
+        assert_eq!(redact(""), "");
+    }
+
+    #[test]
+    fn test_signup_model_falls_back_to_default() {
+        assert_eq!(signup_model(None, "default"), "default");
+        assert_eq!(signup_model(Some("  "), "default"), "default");
+        assert_eq!(signup_model(Some(" gpt-5 "), "default"), "gpt-5");
+    }
+
+    #[test]
+    fn test_ensure_model_offered() {
+        let offered: Vec<String> = [
+            "anthropic/claude-sonnet-4",
+            "openai/gpt-5",
+            "openai/gpt-5-mini",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        assert!(ensure_model_offered("openrouter", "openai/gpt-5", &offered).is_ok());
+
+        let err = ensure_model_offered("openrouter", "gpt-5", &offered).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "openrouter does not offer model 'gpt-5', did you mean: openai/gpt-5, openai/gpt-5-mini?"
+        );
+        let err = ensure_model_offered("openrouter", "llama", &offered).unwrap_err();
+        assert_eq!(err.to_string(), "openrouter does not offer model 'llama'");
+    }
+}
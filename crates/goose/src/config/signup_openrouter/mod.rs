@@ -3,6 +3,7 @@ pub mod server;
 #[cfg(test)]
 mod tests;
 
+use crate::config::signup_common::{auth_debug_enabled, redact, signup_model};
 use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use rand::{distributions::Alphanumeric, Rng};
@@ -81,7 +82,7 @@ impl PkceAuthFlow {
         // Start the server in a background task
         tokio::spawn(async move {
             if let Err(e) = server::run_callback_server(code_tx, shutdown_rx).await {
-                eprintln!("Server error: {}", e);
+                tracing::error!("Auth callback server error: {}", e);
             }
         });
 
@@ -102,10 +103,7 @@ impl PkceAuthFlow {
             code_challenge_method: "S256".to_string(),
         };
 
-        eprintln!("Exchanging code for API key...");
-        eprintln!("Code: {}", code);
-        eprintln!("Code verifier length: {}", self.code_verifier.len());
-        eprintln!("Code challenge: {}", self.code_challenge);
+        self.log_exchange_attempt(&code);
 
         let response = client
             .post(OPENROUTER_TOKEN_URL)
@@ -116,9 +114,10 @@ impl PkceAuthFlow {
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
-            eprintln!("Token exchange failed!");
-            eprintln!("Status: {}", status);
-            eprintln!("Error response: {}", error_text);
+            tracing::warn!(%status, "Token exchange failed");
+            if auth_debug_enabled() {
+                tracing::debug!(%status, error_response = %error_text, "Token exchange error details");
+            }
             return Err(anyhow!(
                 "Failed to exchange code: {} - {}",
                 status,
@@ -130,23 +129,47 @@ impl PkceAuthFlow {
         Ok(token_response.key)
     }
 
-    /// Complete flow: open browser, wait for callback, exchange code
+    /// Log a token exchange attempt without ever emitting the raw code or verifier
+    fn log_exchange_attempt(&self, code: &str) {
+        tracing::info!(code = %redact(code), "Exchanging authorization code for API key");
+        if auth_debug_enabled() {
+            tracing::debug!(
+                code_verifier_len = self.code_verifier.len(),
+                code_challenge = %redact(&self.code_challenge),
+                "PKCE exchange details"
+            );
+        }
+    }
+
+    /// Complete flow: open browser, wait for callback, exchange code.
+    /// Status lines are printed to stdout; use `complete_flow_with_status` to render them elsewhere.
     pub async fn complete_flow(&mut self) -> Result<String> {
+        self.complete_flow_with_status(|status| println!("{}", status))
+            .await
+    }
+
+    /// Complete flow, reporting user-facing status lines through `on_status`
+    pub async fn complete_flow_with_status<F>(&mut self, on_status: F) -> Result<String>
+    where
+        F: Fn(&str),
+    {
         let auth_url = self.get_auth_url();
 
-        println!("Opening browser for authentication...");
-        eprintln!("Auth URL: {}", auth_url);
+        on_status("Opening browser for authentication...");
+        if auth_debug_enabled() {
+            tracing::debug!(auth_url = %auth_url, "Auth URL");
+        }
 
         if let Err(e) = webbrowser::open(&auth_url) {
-            eprintln!("Failed to open browser automatically: {}", e);
-            println!("Please open this URL manually: {}", auth_url);
+            tracing::warn!("Failed to open browser automatically: {}", e);
+            on_status(&format!("Please open this URL manually: {}", auth_url));
         }
 
-        println!("Waiting for authentication callback...");
+        on_status("Waiting for authentication callback...");
         let code = self.start_server().await?;
 
-        println!("Authorization code received. Exchanging for API key...");
-        eprintln!("Received code: {}", code);
+        on_status("Authorization code received. Exchanging for API key...");
+        tracing::debug!(code = %redact(&code), "Received authorization code");
 
         let api_key = self.exchange_code(code).await?;
 
@@ -164,12 +187,13 @@ pub use self::PkceAuthFlow as OpenRouterAuth;
 use crate::config::Config;
 use serde_json::Value;
 
-pub fn configure_openrouter(config: &Config, api_key: String) -> Result<()> {
+/// Store the key from signup and make openrouter the provider, with `model` or the default one
+pub fn configure_openrouter(config: &Config, api_key: String, model: Option<&str>) -> Result<()> {
     config.set_secret("OPENROUTER_API_KEY", Value::String(api_key))?;
     config.set_param("GOOSE_PROVIDER", Value::String("openrouter".to_string()))?;
     config.set_param(
         "GOOSE_MODEL",
-        Value::String(OPENROUTER_DEFAULT_MODEL.to_string()),
+        Value::String(signup_model(model, OPENROUTER_DEFAULT_MODEL).to_string()),
     )?;
     Ok(())
 }
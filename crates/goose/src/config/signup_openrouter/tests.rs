@@ -1,3 +1,5 @@
+use crate::config::signup_common::test_support::CapturedLogs;
+use crate::config::signup_common::AUTH_DEBUG_ENV;
 use crate::config::signup_openrouter::PkceAuthFlow;
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use sha2::{Digest, Sha256};
@@ -63,3 +65,22 @@ fn test_pkce_verifier_length_bounds() {
     assert!(flow.code_verifier.len() >= 43);
     assert!(flow.code_verifier.len() <= 128);
 }
+
+#[test]
+fn test_exchange_logging_never_contains_full_code() {
+    let flow = PkceAuthFlow::new().unwrap();
+    let code = "authcode-0123456789-secret-value";
+
+    for debug in [None, Some("1")] {
+        let logs = CapturedLogs::default();
+        temp_env::with_var(AUTH_DEBUG_ENV, debug, || {
+            logs.capture(|| flow.log_exchange_attempt(code));
+        });
+
+        let output = logs.contents();
+        assert!(output.contains("auth...alue"));
+        assert!(!output.contains(code));
+        assert!(!output.contains(&flow.code_verifier));
+        assert!(!output.contains(&flow.code_challenge));
+    }
+}
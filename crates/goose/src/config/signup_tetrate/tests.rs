@@ -1,4 +1,6 @@
 use super::*;
+use crate::config::signup_common::test_support::CapturedLogs;
+use crate::config::signup_common::AUTH_DEBUG_ENV;
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use sha2::{Digest, Sha256};
 
@@ -69,7 +71,7 @@ fn test_configure_tetrate() {
 
     // Configure with a test API key
     let test_key = "test-api-key-123".to_string();
-    configure_tetrate(&config, test_key.clone()).unwrap();
+    configure_tetrate(&config, test_key.clone(), None).unwrap();
 
     // Verify the configuration was set correctly
     assert_eq!(
@@ -84,4 +86,27 @@ fn test_configure_tetrate() {
         config.get_param::<String>("GOOSE_MODEL").unwrap(),
         TETRATE_DEFAULT_MODEL.to_string()
     );
+
+    // A model picked at signup replaces the default
+    configure_tetrate(&config, test_key, Some("gpt-5")).unwrap();
+    assert_eq!(config.get_param::<String>("GOOSE_MODEL").unwrap(), "gpt-5");
+}
+
+#[test]
+fn test_exchange_logging_never_contains_full_code() {
+    let flow = PkceAuthFlow::new().unwrap();
+    let code = "authcode-0123456789-secret-value";
+
+    for debug in [None, Some("1")] {
+        let logs = CapturedLogs::default();
+        temp_env::with_var(AUTH_DEBUG_ENV, debug, || {
+            logs.capture(|| flow.log_exchange_attempt(code));
+        });
+
+        let output = logs.contents();
+        assert!(output.contains("auth...alue"));
+        assert!(!output.contains(code));
+        assert!(!output.contains(&flow.code_verifier));
+        assert!(!output.contains(&flow.code_challenge));
+    }
 }
@@ -0,0 +1,307 @@
+use crate::config::base::{Config, ConfigError};
+use crate::config::extensions::ExtensionEntry;
+use crate::config::profiles::{ProviderProfile, PROFILES_KEY};
+use crate::security::languages;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+const GOOSE_MODES: &[&str] = &["auto", "approve", "smart_approve", "chat"];
+
+/// A single problem found while validating the config file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigProblem {
+    /// Dotted path of the offending key, e.g. `security.enabled`
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+struct Problems(Vec<ConfigProblem>);
+
+impl Problems {
+    fn push(&mut self, key: impl Into<String>, message: impl Into<String>) {
+        self.0.push(ConfigProblem {
+            key: key.into(),
+            message: message.into(),
+        });
+    }
+
+    fn expect_string(&mut self, key: &str, value: &Value) -> Option<String> {
+        match value {
+            Value::String(s) if s.trim().is_empty() => {
+                self.push(key, "must not be empty");
+                None
+            }
+            Value::String(s) => Some(s.clone()),
+            other => {
+                self.push(key, format!("expected a string, found {}", describe(other)));
+                None
+            }
+        }
+    }
+
+    fn expect_bool(&mut self, key: &str, value: &Value) {
+        match value {
+            Value::Bool(_) => {}
+            Value::String(s) if matches!(s.to_lowercase().as_str(), "true" | "false") => {
+                self.push(
+                    key,
+                    format!(
+                        "expected a boolean, found the string \"{}\"; remove the quotes",
+                        s
+                    ),
+                );
+            }
+            other => self.push(
+                key,
+                format!("expected a boolean, found {}", describe(other)),
+            ),
+        }
+    }
+
+    fn expect_number_in(&mut self, key: &str, value: &Value, min: f64, max: f64) {
+        match value.as_f64() {
+            Some(n) if (min..=max).contains(&n) => {}
+            Some(n) => self.push(
+                key,
+                format!("must be between {} and {}, found {}", min, max, n),
+            ),
+            None => self.push(key, format!("expected a number, found {}", describe(value))),
+        }
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => format!("the boolean {}", b),
+        Value::Number(n) => format!("the number {}", n),
+        Value::String(s) => format!("the string \"{}\"", s),
+        Value::Array(_) => "a list".to_string(),
+        Value::Object(_) => "a mapping".to_string(),
+    }
+}
+
+impl Config {
+    /// Check the known keys in the config file against their expected shapes.
+    ///
+    /// Unknown keys are ignored. Environment overrides are not inspected since they are
+    /// parsed leniently at lookup time. Returns every problem found so they can all be
+    /// reported at startup rather than failing one at a time on first use.
+    pub fn validate(&self) -> Result<Vec<ConfigProblem>, ConfigError> {
+        let values = self.load_values()?;
+        Ok(validate_values(&values))
+    }
+}
+
+pub fn validate_values(values: &HashMap<String, Value>) -> Vec<ConfigProblem> {
+    let mut problems = Problems(Vec::new());
+
+    for key in ["GOOSE_PROVIDER", "GOOSE_MODEL"] {
+        if let Some(value) = values.get(key) {
+            problems.expect_string(key, value);
+        }
+    }
+
+    if let Some(value) = values.get("GOOSE_MODE") {
+        if let Some(mode) = problems.expect_string("GOOSE_MODE", value) {
+            if !GOOSE_MODES.contains(&mode.as_str()) {
+                problems.push(
+                    "GOOSE_MODE",
+                    format!(
+                        "unknown mode '{}', expected one of {}",
+                        mode,
+                        GOOSE_MODES.join(", ")
+                    ),
+                );
+            }
+        }
+    }
+
+    if let Some(value) = values.get("GOOSE_TEMPERATURE") {
+        problems.expect_number_in("GOOSE_TEMPERATURE", value, 0.0, 2.0);
+    }
+
+    if let Some(security) = values.get("security") {
+        validate_security(&mut problems, security);
+    }
+
+    if let Some(extensions) = values.get("extensions") {
+        validate_extensions(&mut problems, extensions);
+    }
+
+    if let Some(profiles) = values.get(PROFILES_KEY) {
+        validate_profiles(&mut problems, profiles);
+    }
+
+    problems.0
+}
+
+fn validate_security(problems: &mut Problems, security: &Value) {
+    let Some(map) = security.as_object() else {
+        problems.push(
+            "security",
+            format!("expected a mapping, found {}", describe(security)),
+        );
+        return;
+    };
+
+    if let Some(enabled) = map.get("enabled") {
+        problems.expect_bool("security.enabled", enabled);
+    }
+    if let Some(threshold) = map.get("threshold") {
+        problems.expect_number_in("security.threshold", threshold, 0.0, 1.0);
+    }
+    if let Some(block_above) = map.get("block_above") {
+        problems.expect_number_in("security.block_above", block_above, 0.0, 1.0);
+    }
+    if let Some(value) = map.get("languages") {
+        let Some(codes) = value.as_array() else {
+            problems.push(
+                "security.languages",
+                format!("expected a list, found {}", describe(value)),
+            );
+            return;
+        };
+        for code in codes {
+            let Some(code) = problems.expect_string("security.languages", code) else {
+                continue;
+            };
+            if languages::find(&code).is_none() {
+                problems.push(
+                    "security.languages",
+                    format!(
+                        "unknown language '{}', expected one of {}",
+                        code,
+                        languages::codes().join(", ")
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn validate_extensions(problems: &mut Problems, extensions: &Value) {
+    let Some(map) = extensions.as_object() else {
+        problems.push(
+            "extensions",
+            format!("expected a mapping, found {}", describe(extensions)),
+        );
+        return;
+    };
+
+    for (name, entry) in map {
+        let key = format!("extensions.{}", name);
+        if let Err(e) = serde_json::from_value::<ExtensionEntry>(entry.clone()) {
+            problems.push(key, format!("invalid extension entry: {}", e));
+        }
+    }
+}
+
+fn validate_profiles(problems: &mut Problems, profiles: &Value) {
+    let Some(map) = profiles.as_object() else {
+        problems.push(
+            PROFILES_KEY,
+            format!("expected a mapping, found {}", describe(profiles)),
+        );
+        return;
+    };
+
+    for (name, profile) in map {
+        if let Err(e) = serde_json::from_value::<ProviderProfile>(profile.clone()) {
+            problems.push(
+                format!("{}.{}", PROFILES_KEY, name),
+                format!("invalid profile: {}", e),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::NamedTempFile;
+
+    fn problems_for(yaml: &str) -> Vec<ConfigProblem> {
+        let config_file = NamedTempFile::new().unwrap();
+        let secrets_file = NamedTempFile::new().unwrap();
+        std::fs::write(config_file.path(), yaml).unwrap();
+        let config =
+            Config::new_with_file_secrets(config_file.path(), secrets_file.path()).unwrap();
+        config.validate().unwrap()
+    }
+
+    #[test]
+    fn test_valid_config_has_no_problems() {
+        let problems = problems_for(
+            r#"
+GOOSE_PROVIDER: openai
+GOOSE_MODEL: gpt-4o
+GOOSE_MODE: smart_approve
+security:
+  enabled: true
+  threshold: 0.8
+  languages: [en, es, zh]
+extensions:
+  developer:
+    enabled: true
+    type: builtin
+    name: developer
+    timeout: 300
+unrelated_key: [1, 2, 3]
+"#,
+        );
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+
+    #[test]
+    fn test_quoted_security_enabled_is_reported() {
+        let problems = problems_for("security:\n  enabled: \"true\"\n");
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].key, "security.enabled");
+        assert!(problems[0].message.contains("remove the quotes"));
+    }
+
+    #[test]
+    fn test_reports_every_problem() {
+        let values: HashMap<String, Value> = serde_json::from_value(json!({
+            "GOOSE_PROVIDER": 42,
+            "GOOSE_MODEL": "",
+            "GOOSE_MODE": "yolo",
+            "security": {"threshold": 3, "block_above": -1, "languages": ["en", "xx"]},
+            "extensions": {"broken": {"enabled": "yes"}},
+        }))
+        .unwrap();
+
+        let keys: Vec<String> = validate_values(&values)
+            .into_iter()
+            .map(|p| p.key)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "GOOSE_PROVIDER",
+                "GOOSE_MODEL",
+                "GOOSE_MODE",
+                "security.threshold",
+                "security.block_above",
+                "security.languages",
+                "extensions.broken"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_security_must_be_a_mapping() {
+        let problems = problems_for("security: on\n");
+        assert_eq!(problems[0].key, "security");
+    }
+}
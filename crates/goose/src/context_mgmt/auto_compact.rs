@@ -1,7 +1,7 @@
 use crate::conversation::message::Message;
 use crate::conversation::Conversation;
 use crate::{
-    agents::Agent, config::Config, context_mgmt::get_messages_token_counts_async,
+    agents::Agent, config::Config, context_mgmt::get_messages_token_counts,
     token_counter::create_async_token_counter,
 };
 use anyhow::Result;
@@ -74,7 +74,7 @@ pub async fn check_compaction_needed(
             let token_counter = create_async_token_counter()
                 .await
                 .map_err(|e| anyhow::anyhow!("Failed to create token counter: {}", e))?;
-            let token_counts = get_messages_token_counts_async(&token_counter, messages);
+            let token_counts = get_messages_token_counts(&token_counter, messages);
             (token_counts.iter().sum(), "estimated")
         }
     };
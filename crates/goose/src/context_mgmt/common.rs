@@ -5,7 +5,7 @@ use rmcp::model::Tool;
 use crate::conversation::message::Message;
 use crate::{
     providers::base::Provider,
-    token_counter::{AsyncTokenCounter, TokenCounter},
+    token_counter::{AsyncTokenCounter, CountTokens, TokenCounter},
 };
 
 const ESTIMATE_FACTOR: f32 = 0.7;
@@ -29,25 +29,14 @@ pub fn estimate_target_context_limit(provider: Arc<dyn Provider>) -> usize {
     }
 }
 
-pub fn get_messages_token_counts(token_counter: &TokenCounter, messages: &[Message]) -> Vec<usize> {
-    // Calculate current token count of each message, use count_chat_tokens to ensure we
-    // capture the full content of the message, include ToolRequests and ToolResponses
-    messages
-        .iter()
-        .map(|msg| token_counter.count_chat_tokens("", std::slice::from_ref(msg), &[]))
-        .collect()
-}
-
-/// Async version of get_messages_token_counts for better performance
-pub fn get_messages_token_counts_async(
-    token_counter: &AsyncTokenCounter,
+/// Tokens in each message, counting text, tool request arguments and tool responses
+pub fn get_messages_token_counts(
+    token_counter: &dyn CountTokens,
     messages: &[Message],
 ) -> Vec<usize> {
-    // Calculate current token count of each message, use count_chat_tokens to ensure we
-    // capture the full content of the message, include ToolRequests and ToolResponses
     messages
         .iter()
-        .map(|msg| token_counter.count_chat_tokens("", std::slice::from_ref(msg), &[]))
+        .map(|msg| token_counter.count_message_tokens(msg))
         .collect()
 }
 
@@ -90,7 +79,7 @@ pub fn get_token_counts_async(
     // Take into account the system prompt (includes goosehints), and our tools input
     let system_prompt_token_count = token_counter.count_tokens(system_prompt);
     let tools_token_count = token_counter.count_tokens_for_tools(tools.as_slice());
-    let messages_token_count = get_messages_token_counts_async(token_counter, messages);
+    let messages_token_count = get_messages_token_counts(token_counter, messages);
 
     ChatTokenCounts {
         system: system_prompt_token_count,
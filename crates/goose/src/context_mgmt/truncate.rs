@@ -1,5 +1,6 @@
 use crate::conversation::message::{Message, MessageContent};
 use crate::conversation::Conversation;
+use crate::token_counter::{CountTokens, HeuristicTokenCounter};
 use crate::utils::safe_truncate;
 use anyhow::{anyhow, Result};
 use rmcp::model::{RawContent, ResourceContents, Role};
@@ -22,12 +23,8 @@ fn handle_oversized_messages(
     let mut truncated_token_counts = Vec::new();
     let mut any_truncated = false;
 
-    // Create a basic token counter for re-estimating truncated content
-    // Note: This is a rough approximation since we don't have access to the actual tokenizer here
-    let estimate_tokens = |text: &str| -> usize {
-        // Rough approximation: 1 token per 4 characters for English text
-        (text.len() / 4).max(1)
-    };
+    // Re-estimate truncated content without a tokenizer, which isn't available here
+    let token_counter = HeuristicTokenCounter;
 
     for (i, (message, &original_tokens)) in messages.iter().zip(token_counts.iter()).enumerate() {
         if original_tokens > context_limit {
@@ -38,8 +35,7 @@ fn handle_oversized_messages(
 
             // Try to truncate the message content
             let truncated_message = truncate_message_content(message, MAX_TRUNCATED_CONTENT_SIZE)?;
-            let estimated_new_tokens =
-                estimate_message_tokens(&truncated_message, &estimate_tokens);
+            let estimated_new_tokens = token_counter.count_message_tokens(&truncated_message);
 
             if estimated_new_tokens > context_limit {
                 // Even truncated message is too large, skip it entirely
@@ -134,44 +130,6 @@ fn truncate_message_content(message: &Message, max_content_size: usize) -> Resul
     Ok(new_message)
 }
 
-/// Estimates token count for a message using a simple heuristic
-fn estimate_message_tokens(message: &Message, estimate_fn: &dyn Fn(&str) -> usize) -> usize {
-    let mut total_tokens = 10; // Base overhead for message structure
-
-    for content in &message.content {
-        match content {
-            MessageContent::Text(text_content) => {
-                total_tokens += estimate_fn(&text_content.text);
-            }
-            MessageContent::ToolResponse(tool_response) => {
-                if let Ok(ref result) = tool_response.tool_result {
-                    for content_item in result {
-                        match &content_item.raw {
-                            RawContent::Text(text_content) => {
-                                total_tokens += estimate_fn(&text_content.text);
-                            }
-                            RawContent::Resource(resource) => {
-                                match &resource.resource {
-                                    ResourceContents::TextResourceContents { text, .. } => {
-                                        total_tokens += estimate_fn(text);
-                                    }
-                                    _ => total_tokens += 5, // Small overhead for other resource types
-                                }
-                            }
-                            _ => {
-                                total_tokens += 5; // Small overhead for other content types
-                            }
-                        }
-                    }
-                }
-            }
-            _ => total_tokens += 5, // Small overhead for other content types
-        }
-    }
-
-    total_tokens
-}
-
 /// Truncates the messages to fit within the model's context window.
 /// Mutates the input messages and token counts in place.
 /// Returns an error if it's impossible to truncate the messages within the context limit.
@@ -680,11 +638,11 @@ mod tests {
     fn test_truncation_with_image_content() -> Result<()> {
         // Create a conversation with image content mixed in
         let messages = vec![
-            Message::user().with_image("base64_data", "image/png"), // 50 tokens
-            Message::assistant().with_text("I see the image"),      // 10 tokens
-            Message::user().with_text("Can you describe it?"),      // 10 tokens
-            Message::assistant().with_text("It shows..."),          // 20 tokens
-            Message::user().with_text("Thanks!"),                   // 5 tokens
+            Message::user().with_content(MessageContent::image("base64_data", "image/png")), // 50 tokens
+            Message::assistant().with_text("I see the image"), // 10 tokens
+            Message::user().with_text("Can you describe it?"), // 10 tokens
+            Message::assistant().with_text("It shows..."),     // 20 tokens
+            Message::user().with_text("Thanks!"),              // 5 tokens
         ];
         let token_counts = vec![50, 10, 10, 20, 5];
         let context_limit = 45; // Force truncation
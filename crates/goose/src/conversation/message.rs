@@ -1,13 +1,18 @@
+use anyhow::{anyhow, bail, Result};
+use base64::Engine;
 use chrono::Utc;
 use mcp_core::{ToolCall, ToolResult};
 use rmcp::model::{
-    AnnotateAble, Content, ImageContent, PromptMessage, PromptMessageContent, PromptMessageRole,
-    RawContent, RawImageContent, RawTextContent, ResourceContents, Role, TextContent,
+    AnnotateAble, Content, ImageContent, Meta, PromptMessage, PromptMessageContent,
+    PromptMessageRole, RawContent, RawImageContent, RawTextContent, ResourceContents, Role,
+    TextContent,
 };
 use serde::{Deserialize, Deserializer, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::collections::HashSet;
 use std::fmt;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
 use utoipa::ToSchema;
 
 use crate::conversation::tool_result_serde;
@@ -115,6 +120,130 @@ pub struct SummarizationRequested {
     pub msg: String,
 }
 
+/// A reference to a file the user attached. Only the path travels with the message; the model
+/// can read the file with its tools if it needs the contents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FileAttachment {
+    pub path: String,
+    pub description: String,
+    /// Size of the file in bytes when it was attached
+    pub size: u64,
+}
+
+impl FileAttachment {
+    /// How the attachment is shown to a model
+    pub fn to_prompt_text(&self) -> String {
+        let mut text = format!("[Attached file: {} ({} bytes)", self.path, self.size);
+        if !self.description.is_empty() {
+            text.push_str(&format!(" - {}", self.description));
+        }
+        text.push(']');
+        text
+    }
+}
+
+/// Largest image [`Message::with_image`] accepts
+pub const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Where the bytes of an attached image come from
+#[derive(Debug, Clone)]
+pub enum ImageSource {
+    Path(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+impl From<PathBuf> for ImageSource {
+    fn from(path: PathBuf) -> Self {
+        ImageSource::Path(path)
+    }
+}
+
+impl From<&Path> for ImageSource {
+    fn from(path: &Path) -> Self {
+        ImageSource::Path(path.to_path_buf())
+    }
+}
+
+impl From<Vec<u8>> for ImageSource {
+    fn from(bytes: Vec<u8>) -> Self {
+        ImageSource::Bytes(bytes)
+    }
+}
+
+impl From<&[u8]> for ImageSource {
+    fn from(bytes: &[u8]) -> Self {
+        ImageSource::Bytes(bytes.to_vec())
+    }
+}
+
+impl ImageSource {
+    fn name(&self) -> Option<String> {
+        match self {
+            ImageSource::Path(path) => path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned()),
+            ImageSource::Bytes(_) => None,
+        }
+    }
+
+    /// Load the bytes, checking the size first so an oversized file is never read
+    fn read(self, max_bytes: u64) -> Result<Vec<u8>> {
+        let size = match &self {
+            ImageSource::Path(path) => std::fs::metadata(path)
+                .map_err(|e| anyhow!("Cannot read image {}: {}", path.display(), e))?
+                .len(),
+            ImageSource::Bytes(bytes) => bytes.len() as u64,
+        };
+        if size > max_bytes {
+            bail!(
+                "Image is {} bytes, which is over the {} byte limit",
+                size,
+                max_bytes
+            );
+        }
+        match self {
+            ImageSource::Path(path) => std::fs::read(&path)
+                .map_err(|e| anyhow!("Cannot read image {}: {}", path.display(), e)),
+            ImageSource::Bytes(bytes) => Ok(bytes),
+        }
+    }
+}
+
+fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+/// Text that stands in for an image when the model cannot see images, e.g.
+/// `[image: chart.png, 640x480]`. The name and size come from the metadata recorded by
+/// [`Message::with_image`], falling back to the mime type and the image header.
+pub fn image_placeholder(image: &RawImageContent) -> String {
+    let meta = image.meta.as_ref();
+    let name = meta
+        .and_then(|meta| meta.get("name"))
+        .and_then(Value::as_str)
+        .unwrap_or(&image.mime_type)
+        .to_string();
+    let recorded = meta.and_then(|meta| {
+        let width = meta.get("width")?.as_u64()?;
+        let height = meta.get("height")?.as_u64()?;
+        Some((width, height))
+    });
+    let dimensions = recorded.or_else(|| {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&image.data)
+            .ok()?;
+        image_dimensions(&bytes).map(|(width, height)| (width as u64, height as u64))
+    });
+    match dimensions {
+        Some((width, height)) => format!("[image: {}, {}x{}]", name, width, height),
+        None => format!("[image: {}]", name),
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 /// Content passed inside a message, which can be both simple content and tool content
 #[serde(tag = "type", rename_all = "camelCase")]
@@ -129,6 +258,7 @@ pub enum MessageContent {
     RedactedThinking(RedactedThinkingContent),
     ContextLengthExceeded(ContextLengthExceeded),
     SummarizationRequested(SummarizationRequested),
+    Attachment(FileAttachment),
 }
 
 impl fmt::Display for MessageContent {
@@ -162,6 +292,7 @@ impl fmt::Display for MessageContent {
             MessageContent::SummarizationRequested(r) => {
                 write!(f, "[SummarizationRequested: {}]", r.msg)
             }
+            MessageContent::Attachment(a) => write!(f, "[Attachment: {}]", a.path),
         }
     }
 }
@@ -188,6 +319,47 @@ impl MessageContent {
         )
     }
 
+    /// Validate and encode an image. The image is read and base64 encoded only once it is
+    /// known to be within `max_bytes`; its name and dimensions are recorded in the content
+    /// metadata so it can be described to models without vision.
+    pub fn image_from(source: ImageSource, mime_type: &str, max_bytes: u64) -> Result<Self> {
+        if !mime_type.starts_with("image/") {
+            bail!("Unsupported image mime type '{}'", mime_type);
+        }
+        let name = source.name();
+        let bytes = source.read(max_bytes)?;
+        let (width, height) = image_dimensions(&bytes)
+            .ok_or_else(|| anyhow!("Not a readable {} image", mime_type))?;
+
+        let mut meta = Meta::new();
+        if let Some(name) = name {
+            meta.insert("name".to_string(), json!(name));
+        }
+        meta.insert("width".to_string(), json!(width));
+        meta.insert("height".to_string(), json!(height));
+
+        Ok(MessageContent::Image(
+            RawImageContent {
+                data: base64::engine::general_purpose::STANDARD.encode(&bytes),
+                mime_type: mime_type.to_string(),
+                meta: Some(meta),
+            }
+            .no_annotation(),
+        ))
+    }
+
+    pub fn attachment<S1: Into<String>, S2: Into<String>>(
+        path: S1,
+        description: S2,
+        size: u64,
+    ) -> Self {
+        MessageContent::Attachment(FileAttachment {
+            path: path.into(),
+            description: description.into(),
+            size,
+        })
+    }
+
     pub fn tool_request<S: Into<String>>(id: S, tool_call: ToolResult<ToolCall>) -> Self {
         MessageContent::ToolRequest(ToolRequest {
             id: id.into(),
@@ -298,6 +470,14 @@ impl MessageContent {
         }
     }
 
+    /// Get the attachment if this is an Attachment variant
+    pub fn as_attachment(&self) -> Option<&FileAttachment> {
+        match self {
+            MessageContent::Attachment(attachment) => Some(attachment),
+            _ => None,
+        }
+    }
+
     /// Get the thinking content if this is a ThinkingContent variant
     pub fn as_thinking(&self) -> Option<&ThinkingContent> {
         match self {
@@ -548,9 +728,30 @@ impl Message {
         ))
     }
 
-    /// Add image content to the message
-    pub fn with_image<S: Into<String>, T: Into<String>>(self, data: S, mime_type: T) -> Self {
-        self.with_content(MessageContent::image(data, mime_type))
+    /// Attach an image from a file or from raw bytes. Fails if the image is larger than
+    /// [`MAX_IMAGE_BYTES`] or cannot be decoded.
+    pub fn with_image<I: Into<ImageSource>>(self, image: I, mime_type: &str) -> Result<Self> {
+        let content = MessageContent::image_from(image.into(), mime_type, MAX_IMAGE_BYTES)?;
+        Ok(self.with_content(content))
+    }
+
+    /// Attach a reference to a file. The file must exist; its contents are not included.
+    pub fn with_attachment<P: AsRef<Path>, S: Into<String>>(
+        self,
+        path: P,
+        description: S,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let metadata = std::fs::metadata(path)
+            .map_err(|e| anyhow!("Cannot attach {}: {}", path.display(), e))?;
+        if !metadata.is_file() {
+            bail!("Cannot attach {}: not a file", path.display());
+        }
+        Ok(self.with_content(MessageContent::attachment(
+            path.to_string_lossy(),
+            description,
+            metadata.len(),
+        )))
     }
 
     /// Add a tool request to the message
@@ -726,8 +927,11 @@ impl Message {
 
 #[cfg(test)]
 mod tests {
-    use crate::conversation::message::{Message, MessageContent, MessageMetadata};
+    use crate::conversation::message::{
+        image_placeholder, ImageSource, Message, MessageContent, MessageMetadata,
+    };
     use crate::conversation::*;
+    use base64::Engine;
     use mcp_core::ToolCall;
     use rmcp::model::{
         AnnotateAble, PromptMessage, PromptMessageContent, PromptMessageRole, RawEmbeddedResource,
@@ -1222,4 +1426,116 @@ mod tests {
         assert!(metadata.user_visible);
         assert!(metadata.agent_visible);
     }
+
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        image::RgbImage::new(width, height)
+            .write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_with_image_from_path_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chart.png");
+        std::fs::write(&path, png_bytes(64, 48)).unwrap();
+
+        let message = Message::user()
+            .with_text("What does this show?")
+            .with_image(path.as_path(), "image/png")
+            .unwrap();
+
+        let json_str = serde_json::to_string(&message).unwrap();
+        let value: Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(value["content"][1]["type"], "image");
+        assert_eq!(value["content"][1]["mimeType"], "image/png");
+        assert_eq!(value["content"][1]["_meta"]["name"], "chart.png");
+
+        let round_tripped: Message = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(round_tripped, message);
+        let MessageContent::Image(image) = &round_tripped.content[1] else {
+            panic!("Expected image content");
+        };
+        assert_eq!(image_placeholder(&image.raw), "[image: chart.png, 64x48]");
+    }
+
+    #[test]
+    fn test_image_placeholder_without_metadata() {
+        let data = base64::engine::general_purpose::STANDARD.encode(png_bytes(3, 2));
+        let image = RawImageContent {
+            data,
+            mime_type: "image/png".to_string(),
+            meta: None,
+        };
+        assert_eq!(image_placeholder(&image), "[image: image/png, 3x2]");
+
+        let unreadable = RawImageContent {
+            data: "not an image".to_string(),
+            mime_type: "image/jpeg".to_string(),
+            meta: None,
+        };
+        assert_eq!(image_placeholder(&unreadable), "[image: image/jpeg]");
+    }
+
+    #[test]
+    fn test_image_size_limit_and_validation() {
+        let bytes = png_bytes(16, 16);
+        let err = MessageContent::image_from(
+            ImageSource::Bytes(bytes.clone()),
+            "image/png",
+            bytes.len() as u64 - 1,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("over the"));
+
+        assert!(MessageContent::image_from(
+            ImageSource::Bytes(bytes.clone()),
+            "image/png",
+            bytes.len() as u64
+        )
+        .is_ok());
+        assert!(Message::user()
+            .with_image(bytes.as_slice(), "text/plain")
+            .is_err());
+        assert!(Message::user()
+            .with_image(b"plain text".as_slice(), "image/png")
+            .is_err());
+    }
+
+    #[test]
+    fn test_with_attachment() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.csv");
+        std::fs::write(&path, "a,b\n1,2\n").unwrap();
+
+        let message = Message::user()
+            .with_attachment(&path, "quarterly numbers")
+            .unwrap();
+        let attachment = message.content[0].as_attachment().unwrap();
+        assert_eq!(attachment.size, 8);
+        assert_eq!(
+            attachment.to_prompt_text(),
+            format!(
+                "[Attached file: {} (8 bytes) - quarterly numbers]",
+                path.display()
+            )
+        );
+
+        let json_str = serde_json::to_string(&message).unwrap();
+        let value: Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(value["content"][0]["type"], "attachment");
+        let round_tripped: Message = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(round_tripped, message);
+
+        assert!(Message::user()
+            .with_attachment(dir.path(), "a directory")
+            .is_err());
+        assert!(Message::user()
+            .with_attachment(dir.path().join("missing.txt"), "")
+            .is_err());
+    }
 }
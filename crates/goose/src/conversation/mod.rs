@@ -1,5 +1,9 @@
-use crate::conversation::message::{Message, MessageContent};
-use rmcp::model::Role;
+use crate::conversation::message::{image_placeholder, Message, MessageContent};
+use crate::providers::formats::{anthropic, openai};
+use crate::providers::utils::ImageFormat;
+use crate::token_counter::CountTokens;
+use crate::tracing::redaction::Redactor;
+use rmcp::model::{Content, RawContent, ResourceContents, Role};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use thiserror::Error;
@@ -8,9 +12,37 @@ use utoipa::ToSchema;
 pub mod message;
 mod tool_result_serde;
 
+/// What [`Conversation::prune_tool_responses`] leaves in place of a tool's output
+pub const ELIDED_TOOL_OUTPUT: &str = "[output elided]";
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct Conversation(Vec<Message>);
 
+/// Tokens taken up by a conversation, split by the role that sent each message
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub user: usize,
+    pub assistant: usize,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> usize {
+        self.user + self.assistant
+    }
+
+    /// Dollar cost of these tokens, counting user tokens as input and assistant tokens as output
+    pub fn cost(&self, pricing: &TokenPricing) -> f64 {
+        self.user as f64 * pricing.input_cost + self.assistant as f64 * pricing.output_cost
+    }
+}
+
+/// Per-token prices in dollars, as supplied by the caller (e.g. from the provider's model info)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TokenPricing {
+    pub input_cost: f64,
+    pub output_cost: f64,
+}
+
 #[derive(Error, Debug)]
 #[error("invalid conversation: {reason}")]
 pub struct InvalidConversation {
@@ -91,6 +123,37 @@ impl Conversation {
         self.0.iter()
     }
 
+    /// The messages whose [`effective_role`] is `role`
+    pub fn with_effective_role(
+        &self,
+        role: EffectiveRole,
+    ) -> impl DoubleEndedIterator<Item = &Message> + '_ {
+        self.0
+            .iter()
+            .filter(move |message| effective_role(message) == role)
+    }
+
+    /// Messages from the user, leaving out the ones that only carry tool responses
+    pub fn user_messages(&self) -> impl DoubleEndedIterator<Item = &Message> + '_ {
+        self.with_effective_role(EffectiveRole::User)
+    }
+
+    pub fn assistant_messages(&self) -> impl DoubleEndedIterator<Item = &Message> + '_ {
+        self.with_effective_role(EffectiveRole::Assistant)
+    }
+
+    /// Messages carrying tool responses
+    pub fn tool_messages(&self) -> impl DoubleEndedIterator<Item = &Message> + '_ {
+        self.with_effective_role(EffectiveRole::Tool)
+    }
+
+    /// Messages made up only of text, from either role
+    pub fn text_only(&self) -> impl DoubleEndedIterator<Item = &Message> + '_ {
+        self.0
+            .iter()
+            .filter(|message| message.has_only_text_content())
+    }
+
     pub fn pop(&mut self) -> Option<Message> {
         self.0.pop()
     }
@@ -99,12 +162,87 @@ impl Conversation {
         self.0.truncate(len);
     }
 
+    /// How many tokens the conversation takes up as `counter` counts them, including tool
+    /// request arguments and tool responses
+    pub fn count_tokens(&self, counter: &dyn CountTokens) -> usize {
+        self.token_usage(counter).total()
+    }
+
+    /// [`Self::count_tokens`], split by the role of each message
+    pub fn token_usage(&self, counter: &dyn CountTokens) -> TokenUsage {
+        let mut usage = TokenUsage::default();
+        for message in &self.0 {
+            let tokens = counter.count_message_tokens(message);
+            match message.role {
+                Role::User => usage.user += tokens,
+                Role::Assistant => usage.assistant += tokens,
+            }
+        }
+        usage
+    }
+
+    /// Rough dollar cost of the conversation's tokens at `pricing`. This prices each message
+    /// once; providers bill earlier messages again as input on every turn, so treat it as a
+    /// lower bound on what the session cost.
+    pub fn estimated_cost(&self, pricing: &TokenPricing, counter: &dyn CountTokens) -> f64 {
+        self.token_usage(counter).cost(pricing)
+    }
+
     pub fn clear(&mut self) {
         self.0.clear();
     }
 
+    /// The messages in OpenAI's chat completions format, with tool requests as `tool_calls`
+    /// and each tool response as its own `tool` message
+    pub fn to_openai_messages(&self) -> Vec<serde_json::Value> {
+        openai::format_messages(&self.0, &ImageFormat::OpenAi)
+    }
+
+    /// The messages in Anthropic's messages format, with tool requests as `tool_use` blocks
+    /// and tool responses as `tool_result` blocks
+    pub fn to_anthropic_messages(&self) -> Vec<serde_json::Value> {
+        anthropic::format_messages(&self.0)
+    }
+
+    /// Replace the output of every successful tool response except the last `keep_last_n` with
+    /// a short placeholder, returning how many were pruned. The responses themselves stay, so
+    /// each tool request still has its answer.
+    pub fn prune_tool_responses(&mut self, keep_last_n: usize) -> usize {
+        let mut pruned = 0;
+        let responses = self
+            .0
+            .iter_mut()
+            .rev()
+            .flat_map(|message| message.content.iter_mut().rev())
+            .filter_map(|content| match content {
+                MessageContent::ToolResponse(response) => Some(response),
+                _ => None,
+            })
+            .skip(keep_last_n);
+        for response in responses {
+            if let Ok(contents) = &mut response.tool_result {
+                let elided = [Content::text(ELIDED_TOOL_OUTPUT)];
+                if contents.as_slice() != elided {
+                    *contents = elided.to_vec();
+                    pruned += 1;
+                }
+            }
+        }
+        pruned
+    }
+
+    /// Scrub secrets out of message text, tool arguments and text tool output, returning the
+    /// number of values replaced. Images and thinking signatures are left untouched.
+    pub fn redact(&mut self, redactor: &Redactor) -> usize {
+        self.0
+            .iter_mut()
+            .flat_map(|message| message.content.iter_mut())
+            .map(|content| redact_content(content, redactor))
+            .sum()
+    }
+
     fn validate(self) -> Result<Self, InvalidConversation> {
-        let (_messages, issues) = fix_messages(self.0.clone());
+        let (_messages, issues) = fix_messages(self.0.clone(), true);
         if !issues.is_empty() {
             let reason = issues.join("\n");
             Err(InvalidConversation {
@@ -117,6 +255,44 @@ impl Conversation {
     }
 }
 
+fn redact_content(content: &mut MessageContent, redactor: &Redactor) -> usize {
+    match content {
+        MessageContent::Text(text) => redactor.redact_str(&mut text.text),
+        MessageContent::Thinking(thinking) => redactor.redact_str(&mut thinking.thinking),
+        MessageContent::ToolRequest(request) => request
+            .tool_call
+            .as_mut()
+            .map_or(0, |call| redactor.redact_json(&mut call.arguments)),
+        MessageContent::FrontendToolRequest(request) => request
+            .tool_call
+            .as_mut()
+            .map_or(0, |call| redactor.redact_json(&mut call.arguments)),
+        MessageContent::ToolConfirmationRequest(request) => {
+            redactor.redact_json(&mut request.arguments)
+        }
+        MessageContent::ToolResponse(response) => {
+            response.tool_result.as_mut().map_or(0, |contents| {
+                contents
+                    .iter_mut()
+                    .map(|content| redact_tool_output(content, redactor))
+                    .sum()
+            })
+        }
+        _ => 0,
+    }
+}
+
+fn redact_tool_output(content: &mut Content, redactor: &Redactor) -> usize {
+    match &mut content.raw {
+        RawContent::Text(text) => redactor.redact_str(&mut text.text),
+        RawContent::Resource(resource) => match &mut resource.resource {
+            ResourceContents::TextResourceContents { text, .. } => redactor.redact_str(text),
+            ResourceContents::BlobResourceContents { .. } => 0,
+        },
+        _ => 0,
+    }
+}
+
 impl Default for Conversation {
     fn default() -> Self {
         Self::empty()
@@ -141,21 +317,32 @@ impl<'a> IntoIterator for &'a Conversation {
 }
 
 /// Fix a conversation that we're about to send to an LLM. So the last and first
-/// messages should always be from the user.
-pub fn fix_conversation(conversation: Conversation) -> (Conversation, Vec<String>) {
+/// messages should always be from the user. When the model cannot see images they are
+/// replaced with a short text description.
+pub fn fix_conversation(
+    conversation: Conversation,
+    supports_vision: bool,
+) -> (Conversation, Vec<String>) {
     let messages = conversation.messages().clone();
-    let (messages, issues) = fix_messages(messages);
+    let (messages, issues) = fix_messages(messages, supports_vision);
     (Conversation::new_unvalidated(messages), issues)
 }
 
-fn fix_messages(messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
+fn fix_messages(messages: Vec<Message>, supports_vision: bool) -> (Vec<Message>, Vec<String>) {
+    let (messages, images_downgraded) = if supports_vision {
+        (messages, Vec::new())
+    } else {
+        downgrade_images(messages)
+    };
+    let (messages, images_fixed) = fix_images(messages);
     let (messages_1, empty_removed) = remove_empty_messages(messages);
     let (messages_2, tool_calling_fixed) = fix_tool_calling(messages_1);
     let (messages_3, messages_merged) = merge_consecutive_messages(messages_2);
     let (messages_4, lead_trail_fixed) = fix_lead_trail(messages_3);
     let (messages_5, populated_if_empty) = populate_if_empty(messages_4);
 
-    let mut issues = Vec::new();
+    let mut issues = images_downgraded;
+    issues.extend(images_fixed);
     issues.extend(empty_removed);
     issues.extend(tool_calling_fixed);
     issues.extend(messages_merged);
@@ -165,6 +352,73 @@ fn fix_messages(messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
     (messages_5, issues)
 }
 
+fn downgrade_images(mut messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
+    let mut issues = Vec::new();
+    for message in &mut messages {
+        for content in &mut message.content {
+            match content {
+                MessageContent::Image(image) => {
+                    let placeholder = image_placeholder(&image.raw);
+                    issues.push(format!("Replaced image with {}", placeholder));
+                    *content = MessageContent::text(placeholder);
+                }
+                MessageContent::ToolResponse(response) => {
+                    let Ok(contents) = &mut response.tool_result else {
+                        continue;
+                    };
+                    for item in contents.iter_mut() {
+                        if let RawContent::Image(image) = &item.raw {
+                            let placeholder = image_placeholder(image);
+                            issues.push(format!(
+                                "Replaced image in tool response '{}' with {}",
+                                response.id, placeholder
+                            ));
+                            *item = Content::text(placeholder);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    (messages, issues)
+}
+
+/// Drop images that providers would reject outright, and turn images in assistant messages
+/// into text: only user messages and tool responses may carry images.
+fn fix_images(mut messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
+    let mut issues = Vec::new();
+    for message in &mut messages {
+        let role = message.role.clone();
+        message.content.retain_mut(|content| {
+            let MessageContent::Image(image) = content else {
+                return true;
+            };
+            if image.data.trim().is_empty() {
+                issues.push("Removed image with no data".to_string());
+                return false;
+            }
+            if !image.mime_type.starts_with("image/") {
+                issues.push(format!(
+                    "Removed image with unsupported type '{}'",
+                    image.mime_type
+                ));
+                return false;
+            }
+            if role == Role::Assistant {
+                let placeholder = image_placeholder(&image.raw);
+                issues.push(format!(
+                    "Replaced image in assistant message with {}",
+                    placeholder
+                ));
+                *content = MessageContent::text(placeholder);
+            }
+            true
+        });
+    }
+    (messages, issues)
+}
+
 fn remove_empty_messages(messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
     let mut issues = Vec::new();
     let filtered_messages = messages
@@ -294,21 +548,36 @@ fn merge_consecutive_messages(messages: Vec<Message>) -> (Vec<Message>, Vec<Stri
     (merged_messages, issues)
 }
 
-fn has_tool_response(message: &Message) -> bool {
-    message
-        .content
-        .iter()
-        .any(|content| matches!(content, MessageContent::ToolResponse(_)))
+/// Whether `message` carries a tool response, which makes it a tool message even though it
+/// is sent with the user role
+pub fn has_tool_response(message: &Message) -> bool {
+    message.is_tool_response()
 }
 
-fn effective_role(message: &Message) -> String {
-    if message.role == Role::User && has_tool_response(message) {
-        "tool".to_string()
-    } else {
-        match message.role {
-            Role::User => "user".to_string(),
-            Role::Assistant => "assistant".to_string(),
-        }
+/// Who a message really comes from: tool responses are sent as user messages, but providers
+/// treat them as a separate turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffectiveRole {
+    User,
+    Assistant,
+    Tool,
+}
+
+impl std::fmt::Display for EffectiveRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            EffectiveRole::User => "user",
+            EffectiveRole::Assistant => "assistant",
+            EffectiveRole::Tool => "tool",
+        })
+    }
+}
+
+pub fn effective_role(message: &Message) -> EffectiveRole {
+    match message.role {
+        Role::User if has_tool_response(message) => EffectiveRole::Tool,
+        Role::User => EffectiveRole::User,
+        Role::Assistant => EffectiveRole::Assistant,
     }
 }
 
@@ -378,20 +647,24 @@ pub fn debug_conversation_fix(
 
 #[cfg(test)]
 mod tests {
-    use crate::conversation::message::Message;
-    use crate::conversation::{debug_conversation_fix, fix_conversation, Conversation};
+    use crate::conversation::message::{Message, MessageContent};
+    use crate::conversation::{
+        debug_conversation_fix, effective_role, fix_conversation, has_tool_response, Conversation,
+        TokenPricing, TokenUsage, ELIDED_TOOL_OUTPUT,
+    };
     use mcp_core::tool::ToolCall;
-    use rmcp::model::Role;
+    use rmcp::model::{AnnotateAble, Content, ErrorCode, ErrorData, Meta, RawImageContent, Role};
     use serde_json::json;
 
     fn run_verify(messages: Vec<Message>) -> (Vec<Message>, Vec<String>) {
-        let (fixed, issues) = fix_conversation(Conversation::new_unvalidated(messages.clone()));
+        let (fixed, issues) =
+            fix_conversation(Conversation::new_unvalidated(messages.clone()), true);
 
         // Uncomment the following line to print the debug report
         // let report = debug_conversation_fix(&messages, &fixed, &issues);
         // print!("\n{}", report);
 
-        let (_fixed, issues_with_fixed) = fix_conversation(fixed.clone());
+        let (_fixed, issues_with_fixed) = fix_conversation(fixed.clone(), true);
         assert_eq!(
             issues_with_fixed.len(),
             0,
@@ -422,7 +695,7 @@ mod tests {
         for i in 1..=all_messages.len() {
             let messages = Conversation::new_unvalidated(all_messages[..i].to_vec());
             if messages.last().unwrap().role == Role::User {
-                let (fixed, issues) = fix_conversation(messages.clone());
+                let (fixed, issues) = fix_conversation(messages.clone(), true);
                 assert_eq!(
                     fixed.len(),
                     messages.len(),
@@ -527,7 +800,7 @@ mod tests {
             Message::user().with_text("thanks!"),
         ]);
 
-        let (fixed, issues) = fix_conversation(conversation);
+        let (fixed, issues) = fix_conversation(conversation, true);
 
         assert_eq!(fixed.len(), 5);
         assert_eq!(issues.len(), 2);
@@ -549,4 +822,354 @@ mod tests {
         let (_fixed, issues) = run_verify(messages);
         assert_eq!(issues.len(), 0);
     }
+
+    #[test]
+    fn test_filtered_message_views() {
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("Search for something"),
+            Message::assistant()
+                .with_text("I'll search for you")
+                .with_tool_request("search_1", Ok(ToolCall::new("search", json!({})))),
+            Message::user().with_tool_response("search_1", Ok(vec![])),
+            Message::assistant().with_text("Found it"),
+            Message::user().with_text("Thanks!"),
+        ]);
+        let texts = |messages: Vec<&Message>| -> Vec<String> {
+            messages.iter().map(|m| m.as_concat_text()).collect()
+        };
+
+        assert_eq!(
+            texts(conversation.user_messages().collect()),
+            vec!["Search for something", "Thanks!"]
+        );
+        assert_eq!(
+            texts(conversation.assistant_messages().collect()),
+            vec!["I'll search for you", "Found it"]
+        );
+        assert_eq!(conversation.tool_messages().count(), 1);
+        assert!(conversation.tool_messages().all(has_tool_response));
+        assert_eq!(
+            texts(conversation.text_only().collect()),
+            vec!["Search for something", "Found it", "Thanks!"]
+        );
+        assert_eq!(
+            conversation
+                .assistant_messages()
+                .next_back()
+                .map(|m| m.as_concat_text()),
+            Some("Found it".to_string())
+        );
+        assert_eq!(
+            conversation
+                .iter()
+                .map(|m| effective_role(m).to_string())
+                .collect::<Vec<_>>(),
+            vec!["user", "assistant", "tool", "assistant", "user"]
+        );
+    }
+
+    #[test]
+    fn test_images_downgraded_without_vision() {
+        let mut meta = Meta::new();
+        meta.insert("name".to_string(), json!("chart.png"));
+        meta.insert("width".to_string(), json!(640));
+        meta.insert("height".to_string(), json!(480));
+        let image = MessageContent::Image(
+            RawImageContent {
+                data: "aGVsbG8=".to_string(),
+                mime_type: "image/png".to_string(),
+                meta: Some(meta),
+            }
+            .no_annotation(),
+        );
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user()
+                .with_text("What is in this chart?")
+                .with_content(image),
+            Message::assistant()
+                .with_tool_request("shot_1", Ok(ToolCall::new("screenshot", json!({})))),
+            Message::user().with_tool_response(
+                "shot_1",
+                Ok(vec![Content::image("bm90IGFuIGltYWdl", "image/jpeg")]),
+            ),
+        ]);
+
+        let (with_vision, issues) = fix_conversation(conversation.clone(), true);
+        assert!(issues.is_empty());
+        assert_eq!(with_vision, conversation);
+
+        let (fixed, issues) = fix_conversation(conversation, false);
+        assert_eq!(
+            fixed.messages()[0].as_concat_text(),
+            "What is in this chart?\n[image: chart.png, 640x480]"
+        );
+        assert_eq!(
+            fixed.messages()[2].content[0].as_tool_response_text(),
+            Some("[image: image/jpeg]".to_string())
+        );
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0], "Replaced image with [image: chart.png, 640x480]");
+    }
+
+    #[test]
+    fn test_count_tokens_by_role() {
+        use crate::token_counter::{CountTokens, HeuristicTokenCounter};
+
+        let counter = HeuristicTokenCounter;
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("List the files here"),
+            Message::assistant().with_tool_request(
+                "call_1",
+                Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+            ),
+            Message::user().with_tool_response(
+                "call_1",
+                Ok(vec![Content::text("Cargo.toml\nREADME.md\nsrc")]),
+            ),
+            Message::assistant().with_text("There are three entries."),
+        ]);
+
+        let usage = conversation.token_usage(&counter);
+        // Each message adds 4 tokens of structure to its content
+        assert_eq!(usage.user, (4 + 4) + (4 + 7));
+        assert_eq!(usage.assistant, (4 + 14) + (4 + 5));
+        assert_eq!(conversation.count_tokens(&counter), usage.total());
+        assert_eq!(
+            usage.total(),
+            conversation
+                .iter()
+                .map(|message| counter.count_message_tokens(message))
+                .sum::<usize>()
+        );
+        assert_eq!(Conversation::empty().count_tokens(&counter), 0);
+    }
+
+    #[test]
+    fn test_prune_tool_responses() {
+        let shell = |id: &str| {
+            Message::assistant().with_tool_request(
+                id,
+                Ok(ToolCall::new(
+                    "developer__shell",
+                    json!({"command": "cat log"}),
+                )),
+            )
+        };
+        let mut conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("Check the logs"),
+            shell("call_1"),
+            Message::user().with_tool_response(
+                "call_1",
+                Ok(vec![Content::text("a very long log ".repeat(100))]),
+            ),
+            shell("call_2"),
+            Message::user().with_tool_response(
+                "call_2",
+                Err(ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    "no such file",
+                    None,
+                )),
+            ),
+            shell("call_3"),
+            Message::user().with_tool_response("call_3", Ok(vec![Content::text("line 1")])),
+            Message::assistant().with_text("The log has one line."),
+            Message::user().with_text("Thanks"),
+        ]);
+
+        assert_eq!(conversation.prune_tool_responses(1), 1);
+        let outputs: Vec<_> = conversation
+            .iter()
+            .flat_map(|message| message.content.iter())
+            .filter_map(|content| content.as_tool_response())
+            .map(|response| response.tool_result.clone())
+            .collect();
+        assert_eq!(outputs[0], Ok(vec![Content::text(ELIDED_TOOL_OUTPUT)]));
+        // Errors are short, so they are kept
+        assert!(outputs[1].is_err());
+        assert_eq!(outputs[2], Ok(vec![Content::text("line 1")]));
+
+        // Pruning again finds nothing new, and the pairing still validates
+        assert_eq!(conversation.prune_tool_responses(1), 0);
+        let (_fixed, issues) = run_verify(conversation.messages().clone());
+        assert!(issues.is_empty(), "{:?}", issues);
+
+        assert_eq!(conversation.prune_tool_responses(0), 1);
+    }
+
+    fn conversation_with_tool_calls() -> Conversation {
+        Conversation::new_unvalidated(vec![
+            Message::user().with_text("How many Rust files are in src?"),
+            Message::assistant()
+                .with_text("Let me count them.")
+                .with_tool_request(
+                    "call_1",
+                    Ok(ToolCall::new(
+                        "developer__shell",
+                        json!({"command": "find src -name '*.rs' | wc -l"}),
+                    )),
+                ),
+            Message::user().with_tool_response("call_1", Ok(vec![Content::text("42")])),
+            Message::assistant().with_tool_request(
+                "call_2",
+                Ok(ToolCall::new(
+                    "developer__shell",
+                    json!({"command": "ls tests"}),
+                )),
+            ),
+            Message::user().with_tool_response(
+                "call_2",
+                Err(ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    "ls: tests: No such file or directory",
+                    None,
+                )),
+            ),
+            Message::assistant().with_text("There are 42 Rust files and no tests directory."),
+        ])
+    }
+
+    // Regenerate with GOOSE_UPDATE_GOLDEN=1 after an intended change to a wire format
+    fn assert_golden(name: &str, actual: &[serde_json::Value]) {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/conversation/testdata")
+            .join(name);
+        let actual = serde_json::to_string_pretty(actual).unwrap() + "\n";
+        if std::env::var("GOOSE_UPDATE_GOLDEN").is_ok() {
+            std::fs::write(&path, &actual).unwrap();
+        }
+        let expected = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(actual, expected, "{} is out of date", path.display());
+    }
+
+    #[test]
+    fn test_openai_wire_format() {
+        assert_golden(
+            "tool_calls_openai.json",
+            &conversation_with_tool_calls().to_openai_messages(),
+        );
+    }
+
+    #[test]
+    fn test_anthropic_wire_format() {
+        assert_golden(
+            "tool_calls_anthropic.json",
+            &conversation_with_tool_calls().to_anthropic_messages(),
+        );
+    }
+
+    #[test]
+    fn test_estimated_cost() {
+        use crate::token_counter::CountTokens;
+
+        // Counts every message as 100 tokens so the expected cost is easy to work out
+        struct FixedCounter;
+        impl CountTokens for FixedCounter {
+            fn count_tokens(&self, _text: &str) -> usize {
+                0
+            }
+
+            fn count_message_tokens(&self, _message: &Message) -> usize {
+                100
+            }
+        }
+
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("Summarize this file"),
+            Message::assistant().with_text("It configures the build."),
+            Message::user().with_text("Thanks"),
+        ]);
+        let pricing = TokenPricing {
+            input_cost: 0.000003,
+            output_cost: 0.000015,
+        };
+
+        let usage = conversation.token_usage(&FixedCounter);
+        assert_eq!(
+            usage,
+            TokenUsage {
+                user: 200,
+                assistant: 100
+            }
+        );
+        let cost = conversation.estimated_cost(&pricing, &FixedCounter);
+        assert!((cost - (200.0 * 0.000003 + 100.0 * 0.000015)).abs() < 1e-12);
+        assert_eq!(cost, usage.cost(&pricing));
+        assert_eq!(
+            Conversation::empty().estimated_cost(&pricing, &FixedCounter),
+            0.0
+        );
+    }
+
+    fn image(data: &str, mime_type: &str) -> MessageContent {
+        MessageContent::image(data, mime_type)
+    }
+
+    #[test]
+    fn test_images_stay_with_their_message_when_merging() {
+        let messages = vec![
+            Message::user().with_text("Here is the failing page"),
+            Message::user()
+                .with_text("and the screenshot:")
+                .with_content(image("aGVsbG8=", "image/png")),
+            Message::assistant()
+                .with_tool_request("shot_1", Ok(ToolCall::new("screenshot", json!({})))),
+            Message::user().with_tool_response(
+                "shot_1",
+                Ok(vec![Content::image("bm90IGFuIGltYWdl", "image/jpeg")]),
+            ),
+            Message::user()
+                .with_content(image("d29ybGQ=", "image/png"))
+                .with_text("Compare with this one"),
+        ];
+
+        let (fixed, issues) = run_verify(messages);
+
+        assert_eq!(issues, vec!["Merged consecutive user messages"]);
+        assert_eq!(fixed.len(), 4);
+        // Both user messages keep their order, with the image after the text it follows
+        assert_eq!(fixed[0].content.len(), 3);
+        assert_eq!(fixed[0].content[2], image("aGVsbG8=", "image/png"));
+        // A text and image message is not folded into the tool response before it
+        assert!(fixed[2].content[0].as_tool_response().is_some());
+        assert_eq!(fixed[2].content.len(), 1);
+        assert_eq!(fixed[3].role, Role::User);
+        assert_eq!(fixed[3].content[0], image("d29ybGQ=", "image/png"));
+        assert_eq!(fixed[3].content[1].as_text(), Some("Compare with this one"));
+    }
+
+    #[test]
+    fn test_invalid_and_assistant_images_are_fixed() {
+        let messages = vec![
+            Message::user()
+                .with_text("Describe these")
+                .with_content(image("", "image/png"))
+                .with_content(image("aGVsbG8=", "application/pdf")),
+            Message::assistant()
+                .with_text("Here is a sketch:")
+                .with_content(image("aGVsbG8=", "image/png")),
+            Message::user().with_content(image("   ", "image/jpeg")),
+            Message::user().with_text("Thanks"),
+        ];
+
+        let (fixed, issues) = run_verify(messages);
+
+        assert_eq!(
+            issues,
+            vec![
+                "Removed image with no data",
+                "Removed image with unsupported type 'application/pdf'",
+                "Replaced image in assistant message with [image: image/png]",
+                "Removed image with no data",
+                "Removed empty message",
+            ]
+        );
+        assert_eq!(fixed.len(), 3);
+        assert_eq!(fixed[0].content.len(), 1);
+        assert_eq!(
+            fixed[1].as_concat_text(),
+            "Here is a sketch:\n[image: image/png]"
+        );
+        assert_eq!(fixed[2].as_concat_text(), "Thanks");
+    }
 }
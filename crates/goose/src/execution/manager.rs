@@ -1,23 +1,50 @@
 //! Agent lifecycle management with session isolation
 
+use super::session_log;
 use super::SessionExecutionMode;
 use crate::agents::Agent;
-use crate::config::APP_STRATEGY;
+use crate::config::{Config, APP_STRATEGY};
 use crate::model::ModelConfig;
+use crate::providers::base::Usage;
 use crate::providers::create;
 use crate::scheduler_factory::SchedulerFactory;
 use crate::scheduler_trait::SchedulerTrait;
+use crate::session::{Session, SessionManager};
+use crate::tracing::redaction::Redactor;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use etcetera::{choose_app_strategy, AppStrategy};
 use lru::LruCache;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::num::NonZeroUsize;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
+/// Config key (or env var) limiting how many background sessions run at once
+pub const MAX_BACKGROUND_SESSIONS_KEY: &str = "GOOSE_MAX_BACKGROUND_SESSIONS";
+/// Config key (or env var) limiting how many subtasks of a single parent session run at once
+pub const MAX_SUBTASKS_PER_PARENT_KEY: &str = "GOOSE_MAX_SUBTASKS_PER_PARENT";
+
+const DEFAULT_MAX_BACKGROUND_SESSIONS: usize = 4;
+const DEFAULT_MAX_SUBTASKS_PER_PARENT: usize = 5;
+
+static SESSION_SCHEDULER: Lazy<Arc<SessionScheduler>> =
+    Lazy::new(|| Arc::new(SessionScheduler::new(SessionLimits::from_config())));
+static SUBTASK_RESULTS: Lazy<Arc<SubTaskResults>> =
+    Lazy::new(|| Arc::new(SubTaskResults::default()));
+
 pub struct AgentManager {
     sessions: Arc<RwLock<LruCache<String, Arc<Agent>>>>,
     scheduler: Arc<dyn SchedulerTrait>,
+    session_scheduler: Arc<SessionScheduler>,
+    subtask_results: Arc<SubTaskResults>,
+    registry: Arc<SessionRegistry>,
     default_provider: Arc<RwLock<Option<Arc<dyn crate::providers::base::Provider>>>>,
 }
 
@@ -36,6 +63,9 @@ impl AgentManager {
         let manager = Self {
             sessions: Arc::new(RwLock::new(LruCache::new(capacity))),
             scheduler,
+            session_scheduler: SessionScheduler::global(),
+            subtask_results: SubTaskResults::global(),
+            registry: Arc::new(SessionRegistry::default()),
             default_provider: Arc::new(RwLock::new(None)),
         };
 
@@ -48,6 +78,31 @@ impl AgentManager {
         Ok(Arc::clone(&self.scheduler))
     }
 
+    /// Limits how many background sessions and subtasks run concurrently
+    pub fn session_scheduler(&self) -> Arc<SessionScheduler> {
+        Arc::clone(&self.session_scheduler)
+    }
+
+    /// Where subtasks publish their results for the parent session to collect
+    pub fn subtask_results(&self) -> Arc<SubTaskResults> {
+        Arc::clone(&self.subtask_results)
+    }
+
+    /// The sessions with a live agent, for lookup and cancellation by session id
+    pub fn session_registry(&self) -> Arc<SessionRegistry> {
+        Arc::clone(&self.registry)
+    }
+
+    /// Cancel a session and its subtasks, whether they are running or still queued for a
+    /// slot. Returns the ids of the running sessions that were signalled.
+    pub fn cancel_session(&self, session_id: &str) -> Result<Vec<String>> {
+        let cancelled = self.registry.cancel(session_id)?;
+        for id in &cancelled {
+            self.session_scheduler.cancel(id);
+        }
+        Ok(cancelled)
+    }
+
     pub async fn set_default_provider(&self, provider: Arc<dyn crate::providers::base::Provider>) {
         debug!("Setting default provider on AgentManager");
         *self.default_provider.write().await = Some(provider);
@@ -95,7 +150,19 @@ impl AgentManager {
             let mut sessions = self.sessions.write().await;
             if let Some(agent) = sessions.get(&session_id) {
                 debug!("Found existing agent for session {}", session_id);
-                return Ok(Arc::clone(agent));
+                let agent = Arc::clone(agent);
+                // A cancelled session that is used again starts over with a fresh token
+                let cancelled = self
+                    .registry
+                    .lookup(&session_id)
+                    .is_none_or(|handle| handle.state != SessionState::Running);
+                if cancelled {
+                    self.registry.unregister(&session_id);
+                    agent
+                        .set_session_cancellation(self.register_session(&session_id, &mode))
+                        .await;
+                }
+                return Ok(agent);
             }
 
             info!(
@@ -103,7 +170,12 @@ impl AgentManager {
                 session_id, mode
             );
             let agent = Arc::new(Agent::new());
-            sessions.put(session_id.clone(), Arc::clone(&agent));
+            if let Some((evicted, _)) = sessions.push(session_id.clone(), Arc::clone(&agent)) {
+                self.registry.unregister(&evicted);
+            }
+            agent
+                .set_session_cancellation(self.register_session(&session_id, &mode))
+                .await;
             agent
         };
 
@@ -131,11 +203,22 @@ impl AgentManager {
         Ok(agent)
     }
 
+    fn register_session(
+        &self,
+        session_id: &str,
+        mode: &SessionExecutionMode,
+    ) -> SessionCancellation {
+        let token = self.registry.register(session_id.to_string(), mode.clone());
+        SessionCancellation::new(session_id.to_string(), token, Arc::clone(&self.registry))
+    }
+
     pub async fn remove_session(&self, session_id: &str) -> Result<()> {
         let mut sessions = self.sessions.write().await;
         sessions
             .pop(session_id)
             .ok_or_else(|| anyhow::anyhow!("Session {} not found", session_id))?;
+        self.subtask_results.remove_parent(session_id);
+        self.registry.unregister(session_id);
         info!("Removed session {}", session_id);
         Ok(())
     }
@@ -147,4 +230,716 @@ impl AgentManager {
     pub async fn session_count(&self) -> usize {
         self.sessions.read().await.len()
     }
+
+    /// Write the session's ordered event log to `path` as JSONL, with secrets redacted.
+    /// Returns the number of events written.
+    pub async fn export_session(&self, session_id: &str, path: &Path) -> Result<usize> {
+        let session = SessionManager::get_session(session_id, true).await?;
+        let count = session_log::write_session_log(&session, path, &Redactor::for_export())?;
+        info!(
+            "Exported session {} ({} events) to {}",
+            session_id,
+            count,
+            path.display()
+        );
+        Ok(count)
+    }
+
+    /// Replay a log written by [`Self::export_session`] into a fresh session, so a reported
+    /// problem can be reproduced against the same conversation
+    pub async fn import_session(&self, path: &Path) -> Result<Session> {
+        let log = session_log::read_session_log(path)?;
+        let session = SessionManager::create_session(
+            log.working_dir,
+            format!("Replay of {}: {}", log.session_id, log.description),
+        )
+        .await?;
+        SessionManager::replace_conversation(&session.id, &log.conversation).await?;
+        info!(
+            "Imported session {} from {} as {}",
+            log.session_id,
+            path.display(),
+            session.id
+        );
+        SessionManager::get_session(&session.id, true).await
+    }
+}
+
+/// Concurrency limits applied by the [`SessionScheduler`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionLimits {
+    pub max_background_sessions: usize,
+    pub max_subtasks_per_parent: usize,
+}
+
+impl Default for SessionLimits {
+    fn default() -> Self {
+        Self {
+            max_background_sessions: DEFAULT_MAX_BACKGROUND_SESSIONS,
+            max_subtasks_per_parent: DEFAULT_MAX_SUBTASKS_PER_PARENT,
+        }
+    }
+}
+
+impl SessionLimits {
+    pub fn from_config() -> Self {
+        let config = Config::global();
+        let limit = |key: &str, default: usize| {
+            config
+                .get_param::<usize>(key)
+                .ok()
+                .filter(|limit| *limit > 0)
+                .unwrap_or(default)
+        };
+        Self {
+            max_background_sessions: limit(
+                MAX_BACKGROUND_SESSIONS_KEY,
+                DEFAULT_MAX_BACKGROUND_SESSIONS,
+            ),
+            max_subtasks_per_parent: limit(
+                MAX_SUBTASKS_PER_PARENT_KEY,
+                DEFAULT_MAX_SUBTASKS_PER_PARENT,
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionRunState {
+    /// Waiting for a slot; `position` counts the sessions ahead of it for the same slots
+    Queued {
+        position: usize,
+    },
+    Running,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("Session {0} was cancelled before it started")]
+    Cancelled(String),
+    #[error("Session {0} is already running or queued")]
+    AlreadyScheduled(String),
+}
+
+/// The pool of slots a session draws from
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Budget {
+    Interactive,
+    Background,
+    Parent(String),
+}
+
+impl Budget {
+    fn for_mode(mode: &SessionExecutionMode) -> Self {
+        match mode {
+            SessionExecutionMode::Interactive => Budget::Interactive,
+            SessionExecutionMode::Background => Budget::Background,
+            SessionExecutionMode::SubTask { parent_session } => {
+                Budget::Parent(parent_session.clone())
+            }
+        }
+    }
+}
+
+struct QueuedSession {
+    session_id: String,
+    mode: SessionExecutionMode,
+    budget: Budget,
+    admit: oneshot::Sender<Result<SessionPermit, ScheduleError>>,
+}
+
+#[derive(Default)]
+struct SchedulerState {
+    running: HashMap<String, (SessionExecutionMode, Budget)>,
+    queue: VecDeque<QueuedSession>,
+    /// Parents whose pending subtasks were cancelled; further subtasks are refused
+    cancelled_parents: HashSet<String>,
+}
+
+impl SchedulerState {
+    fn running_in(&self, budget: &Budget) -> usize {
+        self.running.values().filter(|(_, b)| b == budget).count()
+    }
+
+    fn is_scheduled(&self, session_id: &str) -> bool {
+        self.running.contains_key(session_id)
+            || self.queue.iter().any(|q| q.session_id == session_id)
+    }
+}
+
+/// Caps how many background sessions, and how many subtasks of each parent session, run at
+/// once. Sessions past a limit wait in a FIFO queue until a running session finishes.
+/// Interactive sessions are never limited.
+pub struct SessionScheduler {
+    limits: SessionLimits,
+    state: Mutex<SchedulerState>,
+}
+
+impl SessionScheduler {
+    pub fn new(limits: SessionLimits) -> Self {
+        Self {
+            limits,
+            state: Mutex::new(SchedulerState::default()),
+        }
+    }
+
+    /// The scheduler shared by every session in this process, which scheduled jobs and
+    /// subagents wait on before they start, with limits read from the config
+    pub fn global() -> Arc<Self> {
+        Arc::clone(&SESSION_SCHEDULER)
+    }
+
+    pub fn limits(&self) -> SessionLimits {
+        self.limits
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SchedulerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn limit(&self, budget: &Budget) -> usize {
+        match budget {
+            Budget::Interactive => usize::MAX,
+            Budget::Background => self.limits.max_background_sessions,
+            Budget::Parent(_) => self.limits.max_subtasks_per_parent,
+        }
+    }
+
+    fn start(
+        self: &Arc<Self>,
+        state: &mut SchedulerState,
+        session_id: String,
+        mode: SessionExecutionMode,
+        budget: Budget,
+    ) -> SessionPermit {
+        info!(session_id = %session_id, mode = %mode, "Session started");
+        state.running.insert(session_id.clone(), (mode, budget));
+        SessionPermit {
+            scheduler: Arc::clone(self),
+            session_id,
+        }
+    }
+
+    /// Wait for a slot for `session_id`. The session counts as running until the returned
+    /// permit is dropped. Subtasks count against their parent's budget.
+    pub async fn acquire(
+        self: &Arc<Self>,
+        session_id: impl Into<String>,
+        mode: SessionExecutionMode,
+    ) -> Result<SessionPermit, ScheduleError> {
+        let session_id = session_id.into();
+        let budget = Budget::for_mode(&mode);
+
+        let admitted = {
+            let mut state = self.lock();
+            if state.is_scheduled(&session_id) {
+                return Err(ScheduleError::AlreadyScheduled(session_id));
+            }
+            if let Budget::Parent(parent) = &budget {
+                if state.cancelled_parents.contains(parent) {
+                    return Err(ScheduleError::Cancelled(session_id));
+                }
+            }
+            // A session starting again is no longer cancelled
+            state.cancelled_parents.remove(&session_id);
+
+            let position = state.queue.iter().filter(|q| q.budget == budget).count();
+            if position == 0 && state.running_in(&budget) < self.limit(&budget) {
+                return Ok(self.start(&mut state, session_id, mode, budget));
+            }
+
+            info!(session_id = %session_id, mode = %mode, position, "Session queued");
+            let (admit, admitted) = oneshot::channel();
+            state.queue.push_back(QueuedSession {
+                session_id: session_id.clone(),
+                mode,
+                budget,
+                admit,
+            });
+            admitted
+        };
+
+        admitted
+            .await
+            .unwrap_or(Err(ScheduleError::Cancelled(session_id)))
+    }
+
+    /// Cancel `session_id` if it is queued, along with any queued subtasks it spawned.
+    /// Returns the number of queued sessions cancelled.
+    pub fn cancel(&self, session_id: &str) -> usize {
+        let cancelled: VecDeque<QueuedSession> = {
+            let mut state = self.lock();
+            state.cancelled_parents.insert(session_id.to_string());
+            let parent = Budget::Parent(session_id.to_string());
+            let (cancelled, kept) = std::mem::take(&mut state.queue)
+                .into_iter()
+                .partition(|q| q.session_id == session_id || q.budget == parent);
+            state.queue = kept;
+            cancelled
+        };
+
+        let count = cancelled.len();
+        for queued in cancelled {
+            info!(
+                session_id = %queued.session_id,
+                mode = %queued.mode,
+                "Queued session cancelled"
+            );
+            let _ = queued
+                .admit
+                .send(Err(ScheduleError::Cancelled(queued.session_id)));
+        }
+        count
+    }
+
+    pub fn state(&self, session_id: &str) -> Option<SessionRunState> {
+        let state = self.lock();
+        if state.running.contains_key(session_id) {
+            return Some(SessionRunState::Running);
+        }
+        let queued = state.queue.iter().find(|q| q.session_id == session_id)?;
+        let position = state
+            .queue
+            .iter()
+            .take_while(|q| q.session_id != session_id)
+            .filter(|q| q.budget == queued.budget)
+            .count();
+        Some(SessionRunState::Queued { position })
+    }
+
+    pub fn running_count(&self) -> usize {
+        self.lock().running.len()
+    }
+
+    pub fn queued_count(&self) -> usize {
+        self.lock().queue.len()
+    }
+
+    fn finish(self: &Arc<Self>, session_id: &str) {
+        // Permits the waiting side stopped listening for are dropped, and so released, only
+        // after the lock is let go
+        let mut undelivered = Vec::new();
+        {
+            let mut state = self.lock();
+            if let Some((mode, _)) = state.running.remove(session_id) {
+                info!(session_id = %session_id, mode = %mode, "Session finished");
+            }
+
+            let mut index = 0;
+            while index < state.queue.len() {
+                let budget = &state.queue[index].budget;
+                if state.running_in(budget) >= self.limit(budget) {
+                    index += 1;
+                    continue;
+                }
+                let Some(queued) = state.queue.remove(index) else {
+                    break;
+                };
+                let permit = self.start(&mut state, queued.session_id, queued.mode, queued.budget);
+                if let Err(Ok(permit)) = queued.admit.send(Ok(permit)) {
+                    undelivered.push(permit);
+                }
+            }
+        }
+        drop(undelivered);
+    }
+}
+
+/// A running slot held by a session; dropping it lets the next queued session start
+#[must_use]
+pub struct SessionPermit {
+    scheduler: Arc<SessionScheduler>,
+    session_id: String,
+}
+
+impl SessionPermit {
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+}
+
+impl std::fmt::Debug for SessionPermit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionPermit")
+            .field("session_id", &self.session_id)
+            .finish()
+    }
+}
+
+impl Drop for SessionPermit {
+    fn drop(&mut self) {
+        self.scheduler.finish(&self.session_id);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionState {
+    Running,
+    /// Cancellation was requested and the session has not stopped yet
+    Cancelling,
+    /// The session stopped after being cancelled and has not been unregistered yet
+    Cancelled,
+}
+
+/// A live session as known to the [`SessionRegistry`]
+#[derive(Debug, Clone)]
+pub struct SessionHandle {
+    pub session_id: String,
+    pub mode: SessionExecutionMode,
+    pub started_at: DateTime<Utc>,
+    pub state: SessionState,
+    /// Triggered when the session, or the session that spawned it, is cancelled
+    pub cancellation_token: CancellationToken,
+    /// The session that spawned this one, for subtasks
+    pub parent: Option<String>,
+}
+
+/// Links an agent to its session in the [`SessionRegistry`]: the token its replies stop on,
+/// and where to record that it stopped after being cancelled
+#[derive(Clone)]
+pub struct SessionCancellation {
+    session_id: String,
+    token: CancellationToken,
+    registry: Arc<SessionRegistry>,
+}
+
+impl SessionCancellation {
+    pub fn new(
+        session_id: String,
+        token: CancellationToken,
+        registry: Arc<SessionRegistry>,
+    ) -> Self {
+        Self {
+            session_id,
+            token,
+            registry,
+        }
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Start a reply that stops when the session is cancelled, or when `caller` is. Hold the
+    /// returned guard until the reply ends.
+    pub fn start_reply(
+        &self,
+        caller: Option<CancellationToken>,
+    ) -> (CancellationToken, ReplyGuard) {
+        let token = self.token.child_token();
+        if let Some(caller) = caller {
+            let reply = token.clone();
+            tokio::spawn(async move {
+                tokio::select! {
+                    _ = caller.cancelled() => reply.cancel(),
+                    _ = reply.cancelled() => {}
+                }
+            });
+        }
+        let guard = ReplyGuard {
+            cancellation: self.clone(),
+            token: token.clone(),
+        };
+        (token, guard)
+    }
+}
+
+/// Held while a reply runs. Dropping it records a cancelled session as stopped.
+pub struct ReplyGuard {
+    cancellation: SessionCancellation,
+    token: CancellationToken,
+}
+
+impl Drop for ReplyGuard {
+    fn drop(&mut self) {
+        // Also ends the task watching the caller's token
+        self.token.cancel();
+        let SessionCancellation {
+            session_id,
+            token,
+            registry,
+        } = &self.cancellation;
+        if token.is_cancelled() && registry.mark_cancelled(session_id) {
+            info!(session_id = %session_id, "Session stopped after being cancelled");
+        }
+    }
+}
+
+/// Resolves session ids to their running sessions, so a session can be looked up or
+/// cancelled by id. Cancelling a session also cancels every subtask it spawned, however
+/// deeply nested.
+#[derive(Default)]
+pub struct SessionRegistry {
+    sessions: Mutex<HashMap<String, SessionHandle>>,
+}
+
+impl SessionRegistry {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, SessionHandle>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Register a session when its agent starts, returning the token it should watch for
+    /// cancellation. A subtask registered under a parent that is already being cancelled
+    /// starts out cancelled.
+    pub fn register(&self, session_id: String, mode: SessionExecutionMode) -> CancellationToken {
+        let mut sessions = self.lock();
+        if let Some(existing) = sessions.get(&session_id) {
+            return existing.cancellation_token.clone();
+        }
+
+        let parent = match &mode {
+            SessionExecutionMode::SubTask { parent_session } => Some(parent_session.clone()),
+            _ => None,
+        };
+        let parent_cancelled = parent
+            .as_ref()
+            .and_then(|parent| sessions.get(parent))
+            .is_some_and(|parent| parent.state != SessionState::Running);
+
+        let cancellation_token = CancellationToken::new();
+        let state = if parent_cancelled {
+            cancellation_token.cancel();
+            SessionState::Cancelling
+        } else {
+            SessionState::Running
+        };
+        debug!(session_id = %session_id, mode = %mode, ?state, "Session registered");
+        sessions.insert(
+            session_id.clone(),
+            SessionHandle {
+                session_id,
+                mode,
+                started_at: Utc::now(),
+                state,
+                cancellation_token: cancellation_token.clone(),
+                parent,
+            },
+        );
+        cancellation_token
+    }
+
+    /// Remove a session when its agent goes away. Subtasks it spawned stay registered
+    /// until they unregister themselves.
+    pub fn unregister(&self, session_id: &str) -> Option<SessionHandle> {
+        let handle = self.lock().remove(session_id);
+        if handle.is_some() {
+            debug!(session_id = %session_id, "Session unregistered");
+        }
+        handle
+    }
+
+    pub fn lookup(&self, session_id: &str) -> Option<SessionHandle> {
+        self.lock().get(session_id).cloned()
+    }
+
+    /// Registered sessions matching `mode` and `state` when given, oldest first
+    pub fn list_sessions(
+        &self,
+        mode: Option<&SessionExecutionMode>,
+        state: Option<SessionState>,
+    ) -> Vec<SessionHandle> {
+        let mut handles: Vec<SessionHandle> = self
+            .lock()
+            .values()
+            .filter(|handle| mode.is_none_or(|mode| handle.mode == *mode))
+            .filter(|handle| state.is_none_or(|state| handle.state == state))
+            .cloned()
+            .collect();
+        handles.sort_by(|a, b| {
+            a.started_at
+                .cmp(&b.started_at)
+                .then_with(|| a.session_id.cmp(&b.session_id))
+        });
+        handles
+    }
+
+    /// Trigger the cancellation token of `session_id` and of every subtask beneath it.
+    /// Returns the ids of the sessions cancelled, starting with `session_id`.
+    pub fn cancel(&self, session_id: &str) -> Result<Vec<String>> {
+        let mut sessions = self.lock();
+        if !sessions.contains_key(session_id) {
+            anyhow::bail!("Session {} not found", session_id);
+        }
+
+        let mut cancelled = vec![session_id.to_string()];
+        let mut index = 0;
+        while index < cancelled.len() {
+            let parent = cancelled[index].clone();
+            let mut children: Vec<String> = sessions
+                .values()
+                .filter(|handle| handle.parent.as_deref() == Some(parent.as_str()))
+                .map(|handle| handle.session_id.clone())
+                .filter(|id| !cancelled.contains(id))
+                .collect();
+            children.sort();
+            cancelled.extend(children);
+            index += 1;
+        }
+
+        for id in &cancelled {
+            if let Some(handle) = sessions.get_mut(id) {
+                handle.cancellation_token.cancel();
+                if handle.state == SessionState::Running {
+                    handle.state = SessionState::Cancelling;
+                }
+                info!(session_id = %id, mode = %handle.mode, "Session cancelled");
+            }
+        }
+        Ok(cancelled)
+    }
+
+    /// Record that a cancelled session has stopped. Returns false if the session is not
+    /// registered or was not cancelled.
+    pub fn mark_cancelled(&self, session_id: &str) -> bool {
+        match self.lock().get_mut(session_id) {
+            Some(handle) if handle.state != SessionState::Running => {
+                handle.state = SessionState::Cancelled;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubTaskStatus {
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// What a subtask hands back to its parent session when it finishes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubTaskResult {
+    pub session_id: String,
+    pub status: SubTaskStatus,
+    pub summary: String,
+    /// URIs of resources the subtask produced, such as files it wrote
+    #[serde(default)]
+    pub resource_uris: Vec<String>,
+    #[serde(default)]
+    pub usage: Usage,
+}
+
+struct ResultChannel {
+    sender: mpsc::UnboundedSender<SubTaskResult>,
+    receiver: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<SubTaskResult>>>,
+}
+
+impl ResultChannel {
+    fn new() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            sender,
+            receiver: Arc::new(tokio::sync::Mutex::new(receiver)),
+        }
+    }
+}
+
+/// Per-parent channels carrying [`SubTaskResult`]s from subtasks back to the session that
+/// spawned them. Results are buffered here, independent of the subtask's agent, until the
+/// parent collects them, and are delivered in the order the subtasks completed.
+#[derive(Default)]
+pub struct SubTaskResults {
+    channels: Mutex<HashMap<String, ResultChannel>>,
+}
+
+impl SubTaskResults {
+    /// The results shared by every session in this process, where subagents publish when
+    /// they finish
+    pub fn global() -> Arc<Self> {
+        Arc::clone(&SUBTASK_RESULTS)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, ResultChannel>> {
+        self.channels.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn receiver(
+        &self,
+        parent_session: &str,
+    ) -> Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<SubTaskResult>>> {
+        let mut channels = self.lock();
+        let channel = channels
+            .entry(parent_session.to_string())
+            .or_insert_with(ResultChannel::new);
+        Arc::clone(&channel.receiver)
+    }
+
+    /// Publish a finished subtask's result to its parent session
+    pub fn publish(&self, parent_session: &str, result: SubTaskResult) {
+        info!(
+            parent_session = %parent_session,
+            session_id = %result.session_id,
+            status = ?result.status,
+            "Subtask result published"
+        );
+        let mut channels = self.lock();
+        let channel = channels
+            .entry(parent_session.to_string())
+            .or_insert_with(ResultChannel::new);
+        // The receiver lives in the same entry, so the channel cannot be closed here
+        let _ = channel.sender.send(result);
+    }
+
+    /// Take every result published for `parent_session` so far without waiting
+    pub async fn poll(&self, parent_session: &str) -> Vec<SubTaskResult> {
+        let receiver = self.receiver(parent_session);
+        let mut receiver = receiver.lock().await;
+        let mut results = Vec::new();
+        while let Ok(result) = receiver.try_recv() {
+            results.push(result);
+        }
+        results
+    }
+
+    /// Wait up to `timeout` for the next result published for `parent_session`
+    pub async fn next(&self, parent_session: &str, timeout: Duration) -> Option<SubTaskResult> {
+        let receiver = self.receiver(parent_session);
+        let mut receiver = receiver.lock().await;
+        tokio::time::timeout(timeout, receiver.recv())
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Wait up to `timeout` for `count` results, returning those collected in completion
+    /// order. Fewer than `count` are returned if the timeout passes first.
+    pub async fn collect(
+        &self,
+        parent_session: &str,
+        count: usize,
+        timeout: Duration,
+    ) -> Vec<SubTaskResult> {
+        let receiver = self.receiver(parent_session);
+        let mut receiver = receiver.lock().await;
+        let mut results = Vec::with_capacity(count);
+        let deadline = tokio::time::Instant::now() + timeout;
+        while results.len() < count {
+            match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                Ok(Some(result)) => results.push(result),
+                Ok(None) | Err(_) => break,
+            }
+        }
+        results
+    }
+
+    /// Drop any uncollected results for a parent session that has ended
+    pub fn remove_parent(&self, parent_session: &str) {
+        self.lock().remove(parent_session);
+    }
 }
@@ -0,0 +1,321 @@
+//! Session export as an ordered JSONL event log, and replay of that log into a new session
+//!
+//! The first line is a [`SessionEvent::Session`] header. Each message follows as a
+//! [`SessionEvent::Message`], preceded by a [`SessionEvent::ToolCall`] or
+//! [`SessionEvent::ToolResult`] line for every tool call and result it carries, so the log can
+//! be read for timings without parsing message content. Only the messages are needed to replay.
+
+use crate::conversation::message::{Message, MessageContent};
+use crate::conversation::Conversation;
+use crate::session::Session;
+use crate::tracing::redaction::Redactor;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the log format changes in a way older readers can't handle
+pub const SESSION_LOG_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SessionEvent {
+    Session {
+        version: u32,
+        session_id: String,
+        description: String,
+        working_dir: PathBuf,
+        created_at: String,
+        exported_at: String,
+        /// Number of values replaced by redaction
+        redactions: usize,
+    },
+    ToolCall {
+        message_index: usize,
+        id: String,
+        name: String,
+        created: i64,
+    },
+    ToolResult {
+        message_index: usize,
+        id: String,
+        is_error: bool,
+        created: i64,
+        /// Seconds between the call and its result
+        duration_secs: Option<i64>,
+    },
+    Message {
+        index: usize,
+        /// Seconds since the first message of the session
+        elapsed_secs: i64,
+        message: Message,
+    },
+}
+
+/// An exported session read back from disk
+#[derive(Debug, Clone)]
+pub struct SessionLog {
+    pub session_id: String,
+    pub description: String,
+    pub working_dir: PathBuf,
+    pub conversation: Conversation,
+}
+
+/// Build the event log for a session loaded with its messages, redacting the conversation
+pub fn session_events(session: &Session, redactor: &Redactor) -> Vec<SessionEvent> {
+    let mut conversation = session.conversation.clone().unwrap_or_default();
+    let redactions = conversation.redact(redactor);
+
+    let mut events = vec![SessionEvent::Session {
+        version: SESSION_LOG_VERSION,
+        session_id: session.id.clone(),
+        description: session.description.clone(),
+        working_dir: session.working_dir.clone(),
+        created_at: session.created_at.clone(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        redactions,
+    }];
+
+    let start = conversation.first().map(|m| m.created).unwrap_or_default();
+    let mut call_times: HashMap<String, i64> = HashMap::new();
+    for (index, message) in conversation.into_iter().enumerate() {
+        for content in &message.content {
+            match content {
+                MessageContent::ToolRequest(request) => {
+                    call_times.insert(request.id.clone(), message.created);
+                    events.push(SessionEvent::ToolCall {
+                        message_index: index,
+                        id: request.id.clone(),
+                        name: request
+                            .tool_call
+                            .as_ref()
+                            .map(|call| call.name.clone())
+                            .unwrap_or_default(),
+                        created: message.created,
+                    });
+                }
+                MessageContent::ToolResponse(response) => {
+                    events.push(SessionEvent::ToolResult {
+                        message_index: index,
+                        id: response.id.clone(),
+                        is_error: response.tool_result.is_err(),
+                        created: message.created,
+                        duration_secs: call_times
+                            .get(&response.id)
+                            .map(|called| message.created - called),
+                    });
+                }
+                _ => {}
+            }
+        }
+        events.push(SessionEvent::Message {
+            index,
+            elapsed_secs: message.created - start,
+            message,
+        });
+    }
+    events
+}
+
+/// Write the redacted event log for `session` to `path`, returning the number of events
+pub fn write_session_log(session: &Session, path: &Path, redactor: &Redactor) -> Result<usize> {
+    let events = session_events(session, redactor);
+    let file = File::create(path)
+        .with_context(|| format!("Failed to create session log {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+    for event in &events {
+        serde_json::to_writer(&mut writer, event)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(events.len())
+}
+
+/// Read a log written by [`write_session_log`], keeping the messages in their original order
+pub fn read_session_log(path: &Path) -> Result<SessionLog> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open session log {}", path.display()))?;
+
+    let mut log: Option<SessionLog> = None;
+    let mut messages = Vec::new();
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event: SessionEvent = serde_json::from_str(&line)
+            .with_context(|| format!("Invalid event on line {}", number + 1))?;
+        match event {
+            SessionEvent::Session {
+                version,
+                session_id,
+                description,
+                working_dir,
+                ..
+            } => {
+                if version > SESSION_LOG_VERSION {
+                    bail!(
+                        "Session log version {} is newer than the supported version {}",
+                        version,
+                        SESSION_LOG_VERSION
+                    );
+                }
+                log = Some(SessionLog {
+                    session_id,
+                    description,
+                    working_dir,
+                    conversation: Conversation::empty(),
+                });
+            }
+            SessionEvent::Message { message, .. } => messages.push(message),
+            SessionEvent::ToolCall { .. } | SessionEvent::ToolResult { .. } => {}
+        }
+    }
+
+    let mut log = log.ok_or_else(|| anyhow!("Session log {} has no header", path.display()))?;
+    log.conversation = Conversation::new_unvalidated(messages);
+    Ok(log)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::ToolCall;
+    use rmcp::model::Content;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn at(mut message: Message, created: i64) -> Message {
+        message.created = created;
+        message
+    }
+
+    fn session() -> Session {
+        let messages = vec![
+            at(
+                Message::user().with_text("deploy with sk-abcdefghijklmnopqrstuvwxyz123456"),
+                100,
+            ),
+            at(
+                Message::assistant().with_tool_request(
+                    "call_1",
+                    Ok(ToolCall::new(
+                        "developer__shell",
+                        json!({"command": "deploy", "api_key": "hunter2"}),
+                    )),
+                ),
+                102,
+            ),
+            at(
+                Message::user().with_tool_response("call_1", Ok(vec![Content::text("deployed")])),
+                105,
+            ),
+            at(Message::assistant().with_text("Done"), 106),
+        ];
+        Session {
+            id: "20261016_1".to_string(),
+            description: "Deploy".to_string(),
+            conversation: Some(Conversation::new_unvalidated(messages)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_events_are_ordered_and_timed() {
+        let events = session_events(&session(), &Redactor::new(Vec::new()));
+
+        let kinds: Vec<&str> = events
+            .iter()
+            .map(|event| match event {
+                SessionEvent::Session { .. } => "session",
+                SessionEvent::ToolCall { .. } => "tool_call",
+                SessionEvent::ToolResult { .. } => "tool_result",
+                SessionEvent::Message { .. } => "message",
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "session",
+                "message",
+                "tool_call",
+                "message",
+                "tool_result",
+                "message",
+                "message"
+            ]
+        );
+
+        assert!(matches!(
+            &events[0],
+            SessionEvent::Session { redactions: 2, .. }
+        ));
+        assert!(matches!(
+            &events[2],
+            SessionEvent::ToolCall { message_index: 1, name, created: 102, .. }
+                if name == "developer__shell"
+        ));
+        assert!(matches!(
+            &events[4],
+            SessionEvent::ToolResult {
+                message_index: 2,
+                is_error: false,
+                duration_secs: Some(3),
+                ..
+            }
+        ));
+        assert!(matches!(
+            &events[6],
+            SessionEvent::Message {
+                index: 3,
+                elapsed_secs: 6,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_round_trip_is_redacted() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let written = write_session_log(&session(), &path, &Redactor::new(Vec::new())).unwrap();
+        assert_eq!(written, 7);
+
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(raw.lines().count(), 7);
+        assert!(!raw.contains("sk-abcdefghijklmnopqrstuvwxyz123456"));
+        assert!(!raw.contains("hunter2"));
+
+        let log = read_session_log(&path).unwrap();
+        assert_eq!(log.session_id, "20261016_1");
+        assert_eq!(log.description, "Deploy");
+        let messages = log.conversation.messages();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(
+            messages[0].as_concat_text(),
+            "deploy with [REDACTED:openai_key]"
+        );
+        assert_eq!(messages[3].as_concat_text(), "Done");
+        assert_eq!(messages[2].created, 105);
+    }
+
+    #[test]
+    fn test_read_rejects_bad_logs() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("session.jsonl");
+
+        std::fs::write(&path, "").unwrap();
+        assert!(read_session_log(&path)
+            .unwrap_err()
+            .to_string()
+            .contains("has no header"));
+
+        std::fs::write(&path, "{\"event\":\"session\"}\n").unwrap();
+        assert!(read_session_log(&path)
+            .unwrap_err()
+            .to_string()
+            .contains("line 1"));
+    }
+}
@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use etcetera::{choose_app_strategy, AppStrategy};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::config::APP_STRATEGY;
 
@@ -40,6 +40,43 @@ pub fn get_log_directory(component: &str, use_date_subdir: bool) -> Result<PathB
     Ok(log_dir)
 }
 
+/// Removes the date subdirectories of a component's logs that are older than
+/// `retention_days`, returning how many were removed.
+pub fn prune_log_directories(component: &str, retention_days: u32) -> Result<usize> {
+    let component_dir = get_log_directory(component, false)?;
+    prune_dated_directories(
+        &component_dir,
+        retention_days,
+        chrono::Local::now().date_naive(),
+    )
+}
+
+fn prune_dated_directories(
+    dir: &Path,
+    retention_days: u32,
+    today: chrono::NaiveDate,
+) -> Result<usize> {
+    let cutoff = today - chrono::Duration::days(i64::from(retention_days));
+    let mut removed = 0;
+    for entry in fs::read_dir(dir).context("Failed to read log directory")? {
+        let entry = entry?;
+        let name = entry.file_name();
+        // Only touch directories that get_log_directory created
+        let Some(date) = name
+            .to_str()
+            .and_then(|name| chrono::NaiveDate::parse_from_str(name, "%Y-%m-%d").ok())
+        else {
+            continue;
+        };
+        if date < cutoff && entry.file_type()?.is_dir() {
+            fs::remove_dir_all(entry.path())
+                .with_context(|| format!("Failed to remove {}", entry.path().display()))?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,4 +188,26 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_prune_dated_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["2024-05-01", "2024-05-20", "2024-05-31", "not-a-date"] {
+            fs::create_dir(dir.path().join(name)).unwrap();
+        }
+        fs::write(dir.path().join("2024-04-01"), "a file, not a log directory").unwrap();
+
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 5, 31).unwrap();
+        assert_eq!(prune_dated_directories(dir.path(), 14, today).unwrap(), 1);
+
+        let mut remaining: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            vec!["2024-04-01", "2024-05-20", "2024-05-31", "not-a-date"]
+        );
+    }
 }
@@ -132,18 +132,47 @@ impl ToolInspector for PermissionInspector {
         _messages: &[Message],
     ) -> Result<Vec<InspectionResult>> {
         let mut results = Vec::new();
-        let permission_manager = self.permission_manager.lock().await;
+        let mut permission_manager = self.permission_manager.lock().await;
         let mode = self.mode.lock().await;
 
         for request in tool_requests {
             if let Ok(tool_call) = &request.tool_call {
                 let tool_name = &tool_call.name;
 
-                // Handle different modes
-                let action = if *mode == "chat" {
+                if *mode == "chat" {
                     // In chat mode, all tools are skipped (handled elsewhere)
                     continue;
-                } else if *mode == "auto" {
+                }
+
+                // Explicit rules take precedence over the mode's default policy
+                if let Some(level) =
+                    permission_manager.evaluate_rules(tool_name, &tool_call.arguments)
+                {
+                    let (action, reason) = match level {
+                        PermissionLevel::AlwaysAllow => {
+                            (InspectionAction::Allow, "Permission rule allows this call")
+                        }
+                        PermissionLevel::NeverAllow => {
+                            (InspectionAction::Deny, "Permission rule denies this call")
+                        }
+                        PermissionLevel::AskBefore => (
+                            InspectionAction::RequireApproval(None),
+                            "Permission rule requires approval for this call",
+                        ),
+                    };
+                    results.push(InspectionResult {
+                        tool_request_id: request.id.clone(),
+                        action,
+                        reason: reason.to_string(),
+                        confidence: 1.0,
+                        inspector_name: self.name().to_string(),
+                        finding_id: None,
+                    });
+                    continue;
+                }
+
+                // Handle different modes
+                let action = if *mode == "auto" {
                     // In auto mode, all tools are approved
                     InspectionAction::Allow
                 } else {
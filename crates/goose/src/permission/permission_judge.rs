@@ -185,7 +185,24 @@ pub async fn check_tool_permissions(
         if let Ok(tool_call) = request.tool_call.clone() {
             if mode == "chat" {
                 continue;
-            } else if mode == "auto" {
+            }
+
+            // 0. Explicit rules take precedence over the mode's default policy
+            if let Some(level) =
+                permission_manager.evaluate_rules(&tool_call.name, &tool_call.arguments)
+            {
+                if mode != "auto" && tool_call.name == PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME {
+                    extension_request_ids.push(request.id.clone());
+                }
+                match level {
+                    PermissionLevel::AlwaysAllow => approved.push(request.clone()),
+                    PermissionLevel::AskBefore => needs_approval.push(request.clone()),
+                    PermissionLevel::NeverAllow => denied.push(request.clone()),
+                }
+                continue;
+            }
+
+            if mode == "auto" {
                 approved.push(request.clone());
             } else {
                 if tool_call.name == PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME {
@@ -22,9 +22,9 @@ use crate::model::ModelConfig;
 use crate::providers::retry::ProviderRetry;
 use rmcp::model::Tool;
 
-const ANTHROPIC_DEFAULT_MODEL: &str = "claude-sonnet-4-0";
+pub const ANTHROPIC_DEFAULT_MODEL: &str = "claude-sonnet-4-0";
 const ANTHROPIC_DEFAULT_FAST_MODEL: &str = "claude-3-7-sonnet-latest";
-const ANTHROPIC_KNOWN_MODELS: &[&str] = &[
+pub const ANTHROPIC_KNOWN_MODELS: &[&str] = &[
     "claude-sonnet-4-0",
     "claude-sonnet-4-20250514",
     "claude-opus-4-0",
@@ -35,7 +35,7 @@ const ANTHROPIC_KNOWN_MODELS: &[&str] = &[
 ];
 
 const ANTHROPIC_DOC_URL: &str = "https://docs.anthropic.com/en/docs/about-claude/models";
-const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+pub const ANTHROPIC_API_VERSION: &str = "2023-06-01";
 
 #[derive(serde::Serialize)]
 pub struct AnthropicProvider {
@@ -407,6 +407,12 @@ pub trait Provider: Send + Sync {
         false
     }
 
+    /// Check if this provider's model can take images as input. Images are replaced with a
+    /// short text description before the conversation is sent to providers that cannot.
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
     /// Check if this provider supports cache control
     fn supports_cache_control(&self) -> bool {
         false
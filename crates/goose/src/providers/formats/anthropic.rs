@@ -110,6 +110,12 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
                     }));
                 }
                 MessageContent::Image(_) => continue, // Anthropic doesn't support image content yet
+                MessageContent::Attachment(attachment) => {
+                    content.push(json!({
+                        TYPE_FIELD: TEXT_TYPE,
+                        TEXT_TYPE: attachment.to_prompt_text()
+                    }));
+                }
                 MessageContent::FrontendToolRequest(tool_request) => {
                     if let Ok(tool_call) = &tool_request.tool_call {
                         content.push(json!({
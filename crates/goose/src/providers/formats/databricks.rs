@@ -216,6 +216,12 @@ fn format_messages(messages: &[Message], image_format: &ImageFormat) -> Vec<Data
                         }
                     }));
                 }
+                MessageContent::Attachment(attachment) => {
+                    content_array.push(json!({
+                        "type": "text",
+                        "text": attachment.to_prompt_text()
+                    }));
+                }
                 MessageContent::FrontendToolRequest(req) => {
                     // Frontend tool requests are converted to text messages
                     if let Ok(tool_call) = &req.tool_call {
@@ -206,6 +206,13 @@ pub fn format_messages(messages: &[Message], image_format: &ImageFormat) -> Vec<
                     // Handle direct image content
                     converted["content"] = json!([convert_image(image, image_format)]);
                 }
+                MessageContent::Attachment(attachment) => {
+                    let text = attachment.to_prompt_text();
+                    converted["content"] = match converted["content"].as_str() {
+                        Some(existing) => json!(format!("{}\n{}", existing, text)),
+                        None => json!(text),
+                    };
+                }
                 MessageContent::FrontendToolRequest(request) => match &request.tool_call {
                     Ok(tool_call) => {
                         let sanitized_name = sanitize_function_name(&tool_call.name);
@@ -59,6 +59,12 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
                 MessageContent::SummarizationRequested(_) => {
                     // Skip
                 }
+                MessageContent::Attachment(attachment) => {
+                    if !text_content.is_empty() {
+                        text_content.push('\n');
+                    }
+                    text_content.push_str(&attachment.to_prompt_text());
+                }
                 MessageContent::Thinking(_thinking) => {
                     // Skip thinking for now
                 }
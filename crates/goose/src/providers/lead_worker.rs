@@ -428,6 +428,11 @@ impl Provider for LeadWorkerProvider {
         }
     }
 
+    fn supports_vision(&self) -> bool {
+        // Either model may end up handling the next turn
+        self.lead_provider.supports_vision() && self.worker_provider.supports_vision()
+    }
+
     fn supports_embeddings(&self) -> bool {
         // Support embeddings if either provider supports them
         self.lead_provider.supports_embeddings() || self.worker_provider.supports_embeddings()
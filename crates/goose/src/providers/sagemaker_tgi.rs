@@ -279,6 +279,10 @@ impl Provider for SageMakerTgiProvider {
         self.model.clone()
     }
 
+    fn supports_vision(&self) -> bool {
+        false
+    }
+
     #[tracing::instrument(
         skip(self, model_config, system, messages, tools),
         fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
@@ -8,6 +8,7 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use etcetera::{choose_app_strategy, AppStrategy};
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 use tokio_cron_scheduler::{job::JobId, Job, JobScheduler as TokioJobScheduler};
@@ -17,6 +18,8 @@ use crate::agents::{Agent, SessionConfig};
 use crate::config::{self, Config};
 use crate::conversation::message::Message;
 use crate::conversation::Conversation;
+use crate::execution::manager::SessionScheduler;
+use crate::execution::SessionExecutionMode;
 use crate::providers::base::Provider as GooseProvider; // Alias to avoid conflict in test section
 use crate::providers::create;
 use crate::recipe::Recipe;
@@ -1099,6 +1102,15 @@ async fn run_scheduled_job_internal(
         }
     }?;
 
+    // Wait for a background slot, and hold it until the job's session is done
+    let _permit = SessionScheduler::global()
+        .acquire(job.id.clone(), SessionExecutionMode::scheduled())
+        .await
+        .map_err(|e| JobExecutionError {
+            job_id: job.id.clone(),
+            error: e.to_string(),
+        })?;
+
     let agent: Agent = Agent::new();
 
     let agent_provider: Arc<dyn GooseProvider>;
@@ -1142,14 +1154,12 @@ async fn run_scheduled_job_internal(
     }
 
     if let Some(ref recipe_extensions) = recipe.extensions {
-        for extension in recipe_extensions {
-            agent
-                .add_extension(extension.clone())
-                .await
-                .map_err(|e| JobExecutionError {
-                    job_id: job.id.clone(),
-                    error: format!("Failed to add extension '{}': {}", extension.name(), e),
-                })?;
+        let mut results = agent.add_extensions(recipe_extensions.clone());
+        while let Some((name, result)) = results.next().await {
+            result.map_err(|e| JobExecutionError {
+                job_id: job.id.clone(),
+                error: format!("Failed to add extension '{}': {}", name, e),
+            })?;
         }
     }
 
@@ -1223,8 +1233,6 @@ async fn run_scheduled_job_internal(
             .await
         {
             Ok(mut stream) => {
-                use futures::StreamExt;
-
                 while let Some(message_result) = stream.next().await {
                     tokio::task::yield_now().await;
 
@@ -0,0 +1,37 @@
+use super::LanguagePatterns;
+use crate::security::patterns::{RiskLevel, ThreatCategory, ThreatPattern};
+
+pub const PATTERNS: LanguagePatterns = LanguagePatterns {
+    code: "de",
+    name: "German",
+    patterns: &[
+        ThreatPattern {
+            name: "de_ignore_instructions",
+            pattern: r"\bignorier(e|en|t)?\s+(alle\s+)?(deine\s+|ihre\s+|die\s+)?(vorherigen|bisherigen|obigen|vorigen)\s+(anweisungen|instruktionen|regeln)",
+            description: "Instruction to ignore previous instructions (German)",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "de_forget_instructions",
+            pattern: r"\bvergiss\s+(alle\s+)?(deine|ihre|die)\s+(anweisungen|instruktionen|regeln)",
+            description: "Instruction to forget the assistant's instructions (German)",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "de_reveal_system_prompt",
+            pattern: r"\b(zeige?|verrate|gib)\s+(mir\s+)?(deinen|den|ihren)\s+(system-?prompt|systemprompt|systembefehl)",
+            description: "Request to reveal the system prompt (German)",
+            risk_level: RiskLevel::Medium,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "de_unrestricted_mode",
+            pattern: r"\bdu\s+bist\s+(jetzt|nun|ab\s+sofort)\s+.{0,30}(ohne\s+einschränkungen|uneingeschränkt|entwicklermodus)",
+            description: "Attempt to switch the assistant into an unrestricted mode (German)",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+    ],
+};
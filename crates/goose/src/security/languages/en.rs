@@ -0,0 +1,37 @@
+use super::LanguagePatterns;
+use crate::security::patterns::{RiskLevel, ThreatCategory, ThreatPattern};
+
+pub const PATTERNS: LanguagePatterns = LanguagePatterns {
+    code: "en",
+    name: "English",
+    patterns: &[
+        ThreatPattern {
+            name: "en_ignore_instructions",
+            pattern: r"\bignore\s+(all\s+)?(of\s+)?(the\s+|your\s+)?(previous|prior|above|earlier)\s+(instructions|prompts?|rules)",
+            description: "Instruction to ignore previous instructions",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "en_forget_instructions",
+            pattern: r"\b(forget|disregard)\s+(all\s+)?(of\s+)?(your|the|previous|prior)\s+(instructions|rules|guidelines)",
+            description: "Instruction to forget or disregard the assistant's instructions",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "en_reveal_system_prompt",
+            pattern: r"\b(reveal|show|print|repeat)\s+(me\s+)?(your|the)\s+(system\s+prompt|hidden\s+instructions|initial\s+instructions)",
+            description: "Request to reveal the system prompt",
+            risk_level: RiskLevel::Medium,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "en_unrestricted_mode",
+            pattern: r"\byou\s+are\s+now\s+.{0,30}(unrestricted|developer\s+mode|jailbroken|without\s+(any\s+)?restrictions)",
+            description: "Attempt to switch the assistant into an unrestricted mode",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+    ],
+};
@@ -0,0 +1,37 @@
+use super::LanguagePatterns;
+use crate::security::patterns::{RiskLevel, ThreatCategory, ThreatPattern};
+
+pub const PATTERNS: LanguagePatterns = LanguagePatterns {
+    code: "es",
+    name: "Spanish",
+    patterns: &[
+        ThreatPattern {
+            name: "es_ignore_instructions",
+            pattern: r"\bignor(a|e|ar|en)\s+(todas\s+)?(las\s+)?(tus\s+)?instrucciones\s+(anteriores|previas)",
+            description: "Instruction to ignore previous instructions (Spanish)",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "es_forget_instructions",
+            pattern: r"\bolvid(a|e|ar|en)\s+(todas\s+)?(tus|las|sus)\s+(instrucciones|reglas)",
+            description: "Instruction to forget the assistant's instructions (Spanish)",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "es_reveal_system_prompt",
+            pattern: r"\b(revela|muestra|imprime|repite)\s+(me\s+)?(tu|el|las|tus)\s+(prompt|mensaje|instrucciones)\s+(del\s+sistema|de\s+sistema|ocultas|iniciales)",
+            description: "Request to reveal the system prompt (Spanish)",
+            risk_level: RiskLevel::Medium,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "es_unrestricted_mode",
+            pattern: r"\b(ahora\s+eres|ahora\s+estás\s+en|actúa\s+como)\s+.{0,30}(sin\s+restricciones|sin\s+límites|modo\s+desarrollador)",
+            description: "Attempt to switch the assistant into an unrestricted mode (Spanish)",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+    ],
+};
@@ -0,0 +1,37 @@
+use super::LanguagePatterns;
+use crate::security::patterns::{RiskLevel, ThreatCategory, ThreatPattern};
+
+pub const PATTERNS: LanguagePatterns = LanguagePatterns {
+    code: "fr",
+    name: "French",
+    patterns: &[
+        ThreatPattern {
+            name: "fr_ignore_instructions",
+            pattern: r"\bignor(e|es|ez|er)\s+(toutes\s+)?(les\s+|tes\s+|vos\s+)?instructions\s+(précédentes|antérieures|ci-dessus)",
+            description: "Instruction to ignore previous instructions (French)",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "fr_forget_instructions",
+            pattern: r"\boubli(e|es|ez|er)\s+(toutes\s+)?(tes|vos|les)\s+(instructions|règles|consignes)",
+            description: "Instruction to forget the assistant's instructions (French)",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "fr_reveal_system_prompt",
+            pattern: r"\b(révèle|révélez|affiche|affichez|montre|montrez)[- ](moi\s+)?(ton|votre|le)\s+(prompt|message|invite)\s+(système|systeme)",
+            description: "Request to reveal the system prompt (French)",
+            risk_level: RiskLevel::Medium,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "fr_unrestricted_mode",
+            pattern: r"\b(tu\s+es|vous\s+êtes)\s+maintenant\s+.{0,30}(sans\s+restrictions?|sans\s+limites|mode\s+développeur)",
+            description: "Attempt to switch the assistant into an unrestricted mode (French)",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+    ],
+};
@@ -0,0 +1,43 @@
+//! Prompt injection phrasings, one pattern set per language.
+//!
+//! To add a language, create a file next to this one exposing a [`LanguagePatterns`] constant
+//! and list it in [`LANGUAGES`]. Pattern names must be unique across all sets, so prefix them
+//! with the language code.
+
+use crate::security::patterns::ThreatPattern;
+
+mod de;
+mod en;
+mod es;
+mod fr;
+mod zh;
+
+/// The injection patterns written in one language
+#[derive(Debug)]
+pub struct LanguagePatterns {
+    /// Code used to select the set in `security.languages`, e.g. `es`
+    pub code: &'static str,
+    pub name: &'static str,
+    pub patterns: &'static [ThreatPattern],
+}
+
+/// Every available language set; all of them are active unless `security.languages` says otherwise
+pub const LANGUAGES: &[LanguagePatterns] = &[
+    en::PATTERNS,
+    es::PATTERNS,
+    fr::PATTERNS,
+    de::PATTERNS,
+    zh::PATTERNS,
+];
+
+/// The language set with the given code
+pub fn find(code: &str) -> Option<&'static LanguagePatterns> {
+    LANGUAGES
+        .iter()
+        .find(|language| language.code.eq_ignore_ascii_case(code.trim()))
+}
+
+/// Codes of every available language set
+pub fn codes() -> Vec<&'static str> {
+    LANGUAGES.iter().map(|language| language.code).collect()
+}
@@ -0,0 +1,37 @@
+use super::LanguagePatterns;
+use crate::security::patterns::{RiskLevel, ThreatCategory, ThreatPattern};
+
+pub const PATTERNS: LanguagePatterns = LanguagePatterns {
+    code: "zh",
+    name: "Chinese",
+    patterns: &[
+        ThreatPattern {
+            name: "zh_ignore_instructions",
+            pattern: r"忽[略视]\s*(所有|全部)?\s*(之前|以上|前面|先前|上述)\s*的?\s*(所有|全部)?\s*(指令|指示|说明|提示|规则)",
+            description: "Instruction to ignore previous instructions (Chinese)",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "zh_forget_instructions",
+            pattern: r"忘[记掉]\s*(你的|所有|全部)?\s*的?\s*(指令|指示|设定|规则)",
+            description: "Instruction to forget the assistant's instructions (Chinese)",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "zh_reveal_system_prompt",
+            pattern: r"(显示|输出|告诉我|泄露|透露)\s*(你的)?\s*(系统提示词?|初始指令|隐藏指令)",
+            description: "Request to reveal the system prompt (Chinese)",
+            risk_level: RiskLevel::Medium,
+            category: ThreatCategory::PromptInjection,
+        },
+        ThreatPattern {
+            name: "zh_unrestricted_mode",
+            pattern: r"你现在(是|处于|进入).{0,10}(开发者模式|没有任何?限制|不受限制|无限制)",
+            description: "Attempt to switch the assistant into an unrestricted mode (Chinese)",
+            risk_level: RiskLevel::High,
+            category: ThreatCategory::PromptInjection,
+        },
+    ],
+};
@@ -1,30 +1,57 @@
+pub mod languages;
+pub mod path_policy;
 pub mod patterns;
 pub mod scanner;
 pub mod security_inspector;
+pub mod shell_checks;
 
 use crate::conversation::message::{Message, ToolRequest};
 use crate::permission::permission_judge::PermissionCheckResult;
 use anyhow::Result;
-use scanner::PromptInjectionScanner;
+use lru::LruCache;
+use mcp_core::tool::ToolCall;
+use path_policy::PathPolicy;
+use scanner::{EvidenceSpan, PromptInjectionScanner, ScanResult};
+use serde::{Deserialize, Serialize};
 use std::collections::{hash_map::DefaultHasher, HashSet};
 use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
+/// Number of distinct tool calls whose scan verdicts are kept
+const SCAN_CACHE_CAPACITY: usize = 256;
+
+/// Scan verdicts for one tool call, tagged with the finding kind prefix
+type Analyses = Vec<(&'static str, ScanResult)>;
+
 /// Simple security manager for the POC
 /// Focuses on tool call analysis with conversation context
 pub struct SecurityManager {
     scanner: Option<PromptInjectionScanner>,
+    path_policy: Option<PathPolicy>,
     flagged_findings: Arc<Mutex<HashSet<String>>>,
+    /// Verdicts keyed by the tool call content hash, so retried calls are not scanned again
+    scan_cache: Mutex<LruCache<u64, Arc<Analyses>>>,
+    scan_cache_hits: AtomicU64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityResult {
     pub is_malicious: bool,
     pub confidence: f32,
     pub explanation: String,
     pub should_ask_user: bool,
+    /// The finding is above `security.block_above`, so the tool call should be refused
+    /// without asking
+    #[serde(default)]
+    pub should_block: bool,
     pub finding_id: String,
     pub tool_request_id: String,
+    /// The pattern matches behind the finding, located in the tool arguments. Empty for
+    /// protected path findings.
+    #[serde(default)]
+    pub evidence: Vec<EvidenceSpan>,
 }
 
 impl SecurityManager {
@@ -32,18 +59,88 @@ impl SecurityManager {
         // Initialize scanner based on config
         let should_enable = Self::should_enable_security();
 
-        let scanner = if should_enable {
+        let (scanner, path_policy) = if should_enable {
             tracing::info!("Security scanner initialized and enabled");
-            Some(PromptInjectionScanner::new())
+            (
+                Some(PromptInjectionScanner::new()),
+                Some(PathPolicy::from_config()),
+            )
         } else {
             tracing::debug!("Security scanning disabled via configuration");
-            None
+            (None, None)
         };
 
+        Self::with_scanners(scanner, path_policy)
+    }
+
+    fn with_scanners(
+        scanner: Option<PromptInjectionScanner>,
+        path_policy: Option<PathPolicy>,
+    ) -> Self {
         Self {
             scanner,
+            path_policy,
             flagged_findings: Arc::new(Mutex::new(HashSet::new())),
+            scan_cache: Mutex::new(LruCache::new(
+                NonZeroUsize::new(SCAN_CACHE_CAPACITY).unwrap(),
+            )),
+            scan_cache_hits: AtomicU64::new(0),
+        }
+    }
+
+    /// Forget every flagged finding and cached verdict, so the next tool calls are scanned and
+    /// reported afresh
+    pub fn clear_findings(&self) {
+        self.flagged_findings.lock().unwrap().clear();
+        self.scan_cache.lock().unwrap().clear();
+    }
+
+    /// Forget one flagged finding so it is reported again the next time it is seen. Returns
+    /// whether the finding had been flagged.
+    pub fn unflag(&self, finding_id: &str) -> bool {
+        self.flagged_findings.lock().unwrap().remove(finding_id)
+    }
+
+    /// Number of tool calls answered from the scan cache
+    pub fn scan_cache_hits(&self) -> u64 {
+        self.scan_cache_hits.load(Ordering::Relaxed)
+    }
+
+    /// Scan a tool call with the injection scanner and the path policy, reusing the verdicts
+    /// from an identical earlier call
+    async fn scan_tool_call(
+        &self,
+        scanner: &PromptInjectionScanner,
+        tool_call: &ToolCall,
+    ) -> Result<Arc<Analyses>> {
+        let key = content_hash("", tool_call);
+        if let Some(cached) = self.scan_cache.lock().unwrap().get(&key) {
+            self.scan_cache_hits.fetch_add(1, Ordering::Relaxed);
+            tracing::debug!(
+                tool_name = %tool_call.name,
+                "🔁 Reusing cached security analysis for identical tool call"
+            );
+            return Ok(Arc::clone(cached));
+        }
+
+        // Analyze only the current tool call content, not the entire conversation history
+        // This prevents re-analyzing and re-flagging historical malicious content
+        let injection_result = scanner
+            .analyze_tool_call_with_context(tool_call, &[]) // Pass empty messages to avoid historical analysis
+            .await?;
+
+        // Protected-path hits are separate findings, so approving one doesn't hide the other
+        let mut analyses = vec![("", injection_result)];
+        if let Some(path_policy) = &self.path_policy {
+            analyses.push(("path:", path_policy.scan_tool_call(tool_call)));
         }
+
+        let analyses = Arc::new(analyses);
+        self.scan_cache
+            .lock()
+            .unwrap()
+            .put(key, Arc::clone(&analyses));
+        Ok(analyses)
     }
 
     /// Check if security should be enabled based on config
@@ -100,80 +197,81 @@ impl SecurityManager {
                     "🔍 Starting security analysis for current tool call"
                 );
 
-                // Analyze only the current tool call content, not the entire conversation history
-                // This prevents re-analyzing and re-flagging historical malicious content
-                let analysis_result = scanner
-                    .analyze_tool_call_with_context(tool_call, &[]) // Pass empty messages to avoid historical analysis
-                    .await?;
+                let analyses = self.scan_tool_call(scanner, tool_call).await?;
 
                 // Get threshold from config - only flag things above threshold
                 let config_threshold = scanner.get_threshold_from_config();
+                let block_threshold = scanner.get_block_threshold_from_config();
 
-                if analysis_result.is_malicious && analysis_result.confidence > config_threshold {
-                    // Generate a unique finding ID based on normalized tool call content
-                    // This ensures the same malicious content always gets the same finding ID
-                    // regardless of JSON formatting or tool request ID variations
-                    let normalized_content = format!(
-                        "{}:{}",
-                        tool_call.name,
-                        serde_json::to_string(&tool_call.arguments).unwrap_or_default()
-                    );
-                    let mut hasher = DefaultHasher::new();
-                    normalized_content.hash(&mut hasher);
-                    let content_hash = hasher.finish();
-                    let finding_id = format!("SEC-{:016x}", content_hash);
-
-                    // Check if we've already flagged this exact finding before
-                    let mut flagged_set = self.flagged_findings.lock().unwrap();
-                    if flagged_set.contains(&finding_id) {
-                        tracing::debug!(
+                for (finding_kind, analysis_result) in analyses.iter() {
+                    if analysis_result.is_malicious && analysis_result.confidence > config_threshold
+                    {
+                        // Generate a unique finding ID based on normalized tool call content
+                        // This ensures the same malicious content always gets the same finding ID
+                        // regardless of JSON formatting or tool request ID variations
+                        let finding_id =
+                            format!("SEC-{:016x}", content_hash(finding_kind, tool_call));
+
+                        // Check if we've already flagged this exact finding before
+                        let mut flagged_set = self.flagged_findings.lock().unwrap();
+                        if flagged_set.contains(&finding_id) {
+                            tracing::debug!(
+                                tool_name = %tool_call.name,
+                                tool_request_id = %tool_request.id,
+                                finding_id = %finding_id,
+                                "🔄 Skipping already flagged security finding - preventing re-flagging"
+                            );
+                            continue;
+                        }
+
+                        // Mark this finding as flagged
+                        flagged_set.insert(finding_id.clone());
+                        drop(flagged_set); // Release the lock
+
+                        let should_block = block_threshold
+                            .is_some_and(|block_above| analysis_result.confidence > block_above);
+
+                        tracing::warn!(
                             tool_name = %tool_call.name,
                             tool_request_id = %tool_request.id,
+                            confidence = analysis_result.confidence,
+                            explanation = %analysis_result.explanation,
                             finding_id = %finding_id,
-                            "🔄 Skipping already flagged security finding - preventing re-flagging"
+                            threshold = config_threshold,
+                            block_threshold = ?block_threshold,
+                            should_block,
+                            "🔒 Current tool call flagged as malicious after security analysis (above threshold)"
                         );
-                        continue;
-                    }
 
-                    // Mark this finding as flagged
-                    flagged_set.insert(finding_id.clone());
-                    drop(flagged_set); // Release the lock
-
-                    tracing::warn!(
-                        tool_name = %tool_call.name,
-                        tool_request_id = %tool_request.id,
-                        confidence = analysis_result.confidence,
-                        explanation = %analysis_result.explanation,
-                        finding_id = %finding_id,
-                        threshold = config_threshold,
-                        "🔒 Current tool call flagged as malicious after security analysis (above threshold)"
-                    );
-
-                    results.push(SecurityResult {
-                        is_malicious: analysis_result.is_malicious,
-                        confidence: analysis_result.confidence,
-                        explanation: analysis_result.explanation,
-                        should_ask_user: true, // Always ask user for threats above threshold
-                        finding_id,
-                        tool_request_id: tool_request.id.clone(),
-                    });
-                } else if analysis_result.is_malicious {
-                    tracing::warn!(
-                        tool_name = %tool_call.name,
-                        tool_request_id = %tool_request.id,
-                        confidence = analysis_result.confidence,
-                        explanation = %analysis_result.explanation,
-                        threshold = config_threshold,
-                        "🔒 Security finding below threshold - logged but not blocking execution"
-                    );
-                } else {
-                    tracing::debug!(
-                        tool_name = %tool_call.name,
-                        tool_request_id = %tool_request.id,
-                        confidence = analysis_result.confidence,
-                        explanation = %analysis_result.explanation,
-                        "✅ Current tool call passed security analysis"
-                    );
+                        results.push(SecurityResult {
+                            is_malicious: analysis_result.is_malicious,
+                            confidence: analysis_result.confidence,
+                            explanation: analysis_result.explanation.clone(),
+                            // Ask about threats above threshold, unless they are blocked outright
+                            should_ask_user: !should_block,
+                            should_block,
+                            finding_id,
+                            tool_request_id: tool_request.id.clone(),
+                            evidence: analysis_result.evidence.clone(),
+                        });
+                    } else if analysis_result.is_malicious {
+                        tracing::warn!(
+                            tool_name = %tool_call.name,
+                            tool_request_id = %tool_request.id,
+                            confidence = analysis_result.confidence,
+                            explanation = %analysis_result.explanation,
+                            threshold = config_threshold,
+                            "🔒 Security finding below threshold - logged but not blocking execution"
+                        );
+                    } else {
+                        tracing::debug!(
+                            tool_name = %tool_call.name,
+                            tool_request_id = %tool_request.id,
+                            confidence = analysis_result.confidence,
+                            explanation = %analysis_result.explanation,
+                            "✅ Current tool call passed security analysis"
+                        );
+                    }
                 }
             }
         }
@@ -212,8 +310,94 @@ impl SecurityManager {
     }
 }
 
+/// Hash of the normalized tool call content, prefixed with the finding kind
+fn content_hash(finding_kind: &str, tool_call: &ToolCall) -> u64 {
+    let normalized_content = format!(
+        "{}{}:{}",
+        finding_kind,
+        tool_call.name,
+        serde_json::to_string(&tool_call.arguments).unwrap_or_default()
+    );
+    let mut hasher = DefaultHasher::new();
+    normalized_content.hash(&mut hasher);
+    hasher.finish()
+}
+
 impl Default for SecurityManager {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool_request(id: &str, command: &str) -> ToolRequest {
+        ToolRequest {
+            id: id.to_string(),
+            tool_call: Ok(ToolCall {
+                name: "shell".to_string(),
+                arguments: json!({"command": command}),
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_identical_tool_call_hits_scan_cache() {
+        let manager = SecurityManager::with_scanners(Some(PromptInjectionScanner::new()), None);
+
+        let first = manager
+            .analyze_tool_requests(&[tool_request("req-1", "ls -la")], &[])
+            .await
+            .unwrap();
+        assert!(first.is_empty());
+        assert_eq!(manager.scan_cache_hits(), 0);
+
+        // A retry gets a new request id but the same content
+        let second = manager
+            .analyze_tool_requests(&[tool_request("req-2", "ls -la")], &[])
+            .await
+            .unwrap();
+        assert!(second.is_empty());
+        assert_eq!(manager.scan_cache_hits(), 1);
+
+        manager
+            .analyze_tool_requests(&[tool_request("req-3", "pwd")], &[])
+            .await
+            .unwrap();
+        assert_eq!(manager.scan_cache_hits(), 1);
+        assert_eq!(manager.scan_cache.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unflag_and_clear_findings() {
+        let manager = SecurityManager::with_scanners(Some(PromptInjectionScanner::new()), None);
+        let analyze = |id: &'static str| {
+            let manager = &manager;
+            async move {
+                manager
+                    .analyze_tool_requests(&[tool_request(id, "rm -rf /")], &[])
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let first = analyze("req-1").await;
+        assert_eq!(first.len(), 1);
+        let finding_id = first[0].finding_id.clone();
+        // Already flagged, so not reported again
+        assert!(analyze("req-2").await.is_empty());
+
+        assert!(manager.unflag(&finding_id));
+        assert!(!manager.unflag(&finding_id));
+        let again = analyze("req-3").await;
+        assert_eq!(again.len(), 1);
+        assert_eq!(again[0].finding_id, finding_id);
+
+        manager.clear_findings();
+        assert_eq!(manager.scan_cache.lock().unwrap().len(), 0);
+        assert_eq!(analyze("req-4").await.len(), 1);
+    }
+}
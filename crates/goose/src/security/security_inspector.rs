@@ -17,13 +17,21 @@ impl SecurityInspector {
         }
     }
 
+    /// The manager holding the flagged findings, e.g. to clear them after the security
+    /// config changes
+    pub fn security_manager(&self) -> &SecurityManager {
+        &self.security_manager
+    }
+
     /// Convert SecurityResult to InspectionResult
     fn convert_security_result(
         &self,
         security_result: &SecurityResult,
         tool_request_id: String,
     ) -> InspectionResult {
-        let action = if security_result.is_malicious && security_result.should_ask_user {
+        let action = if security_result.is_malicious && security_result.should_block {
+            InspectionAction::Deny
+        } else if security_result.is_malicious && security_result.should_ask_user {
             // High confidence threat - require user approval with warning
             InspectionAction::RequireApproval(Some(format!(
                 "🔒 Security Alert: This tool call has been flagged as potentially dangerous.\n\
@@ -147,6 +155,31 @@ mod tests {
         }
     }
 
+    fn security_result(should_block: bool) -> SecurityResult {
+        SecurityResult {
+            is_malicious: true,
+            confidence: 0.95,
+            explanation: "Detected 1 security threat".to_string(),
+            should_ask_user: !should_block,
+            should_block,
+            finding_id: "SEC-1".to_string(),
+            tool_request_id: "req".to_string(),
+            evidence: vec![],
+        }
+    }
+
+    #[test]
+    fn test_blocked_findings_are_denied() {
+        let inspector = SecurityInspector::new();
+
+        let blocked = inspector.convert_security_result(&security_result(true), "req".into());
+        assert_eq!(blocked.action, InspectionAction::Deny);
+        assert_eq!(blocked.reason, "Detected 1 security threat");
+
+        let asked = inspector.convert_security_result(&security_result(false), "req".into());
+        assert!(matches!(asked.action, InspectionAction::RequireApproval(_)));
+    }
+
     #[test]
     fn test_security_inspector_name() {
         let inspector = SecurityInspector::new();
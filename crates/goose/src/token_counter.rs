@@ -1,12 +1,13 @@
 use ahash::AHasher;
 use dashmap::DashMap;
-use rmcp::model::Tool;
+use mcp_core::{ToolCall, ToolResult};
+use rmcp::model::{RawContent, ResourceContents, Tool};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use tiktoken_rs::CoreBPE;
 use tokio::sync::OnceCell;
 
-use crate::conversation::message::Message;
+use crate::conversation::message::{Message, MessageContent};
 
 // Global tokenizer instance to avoid repeated initialization
 static TOKENIZER: OnceCell<Arc<CoreBPE>> = OnceCell::const_new();
@@ -14,6 +15,98 @@ static TOKENIZER: OnceCell<Arc<CoreBPE>> = OnceCell::const_new();
 // Cache size limits to prevent unbounded growth
 const MAX_TOKEN_CACHE_SIZE: usize = 10_000;
 
+/// Tokens of structure around every message: <|im_start|>ROLE<|im_sep|>MESSAGE<|im_end|>
+const TOKENS_PER_MESSAGE: usize = 4;
+/// Images are billed very differently by each provider, so they get a flat allowance
+const TOKENS_PER_IMAGE: usize = 5;
+
+/// Counts the tokens in text, so that sizes can be compared against a model's context limit.
+///
+/// [`HeuristicTokenCounter`] needs no tokenizer and is close for most text; the tiktoken-backed
+/// counters are exact for o200k models and a good approximation for other providers.
+pub trait CountTokens: Send + Sync {
+    fn count_tokens(&self, text: &str) -> usize;
+
+    /// Tokens in one message: its text, tool request names and arguments, and tool responses
+    fn count_message_tokens(&self, message: &Message) -> usize {
+        let tool_call_tokens = |id: &str, tool_call: &ToolResult<ToolCall>| match tool_call {
+            // Note: separators are tokenized with adjacent tokens, keep original for accuracy
+            Ok(call) => self.count_tokens(&format!("{}:{}:{}", id, call.name, call.arguments)),
+            Err(e) => self.count_tokens(&e.to_string()),
+        };
+
+        let mut tokens = TOKENS_PER_MESSAGE;
+        for content in &message.content {
+            tokens += match content {
+                MessageContent::Text(text) => self.count_tokens(&text.text),
+                MessageContent::Thinking(thinking) => self.count_tokens(&thinking.thinking),
+                MessageContent::ToolRequest(request) => {
+                    tool_call_tokens(&request.id, &request.tool_call)
+                }
+                MessageContent::FrontendToolRequest(request) => {
+                    tool_call_tokens(&request.id, &request.tool_call)
+                }
+                MessageContent::ToolResponse(response) => match &response.tool_result {
+                    Ok(contents) => contents
+                        .iter()
+                        .map(|content| match &content.raw {
+                            RawContent::Text(text) => self.count_tokens(&text.text),
+                            RawContent::Resource(resource) => match &resource.resource {
+                                ResourceContents::TextResourceContents { text, .. } => {
+                                    self.count_tokens(text)
+                                }
+                                _ => TOKENS_PER_IMAGE,
+                            },
+                            _ => TOKENS_PER_IMAGE,
+                        })
+                        .sum(),
+                    Err(e) => self.count_tokens(&e.to_string()),
+                },
+                MessageContent::Image(_) => TOKENS_PER_IMAGE,
+                _ => 0,
+            };
+        }
+        tokens
+    }
+}
+
+/// Estimates tokens from the shape of the text, for when no tokenizer is at hand.
+///
+/// BPE tokenizers like o200k encode most English words, with their leading space, as a single
+/// token and only split unusually long words. Runs of punctuation take about a token for every
+/// two characters, and scripts such as Chinese or Japanese about two tokens for every three.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicTokenCounter;
+
+impl CountTokens for HeuristicTokenCounter {
+    fn count_tokens(&self, text: &str) -> usize {
+        let mut tokens = 0;
+        let mut word: usize = 0;
+        let mut punctuation: usize = 0;
+        let mut other: usize = 0;
+        for c in text.chars() {
+            if c.is_ascii_alphanumeric() {
+                tokens += punctuation.div_ceil(2);
+                punctuation = 0;
+                word += 1;
+                continue;
+            }
+            tokens += word.div_ceil(10);
+            word = 0;
+            if c.is_ascii_punctuation() {
+                punctuation += 1;
+            } else {
+                tokens += punctuation.div_ceil(2);
+                punctuation = 0;
+                if !c.is_ascii() {
+                    other += 1;
+                }
+            }
+        }
+        tokens + word.div_ceil(10) + punctuation.div_ceil(2) + (other * 2).div_ceil(3)
+    }
+}
+
 /// Async token counter with caching capabilities
 pub struct AsyncTokenCounter {
     tokenizer: Arc<CoreBPE>,
@@ -339,6 +432,18 @@ impl TokenCounter {
     }
 }
 
+impl CountTokens for AsyncTokenCounter {
+    fn count_tokens(&self, text: &str) -> usize {
+        AsyncTokenCounter::count_tokens(self, text)
+    }
+}
+
+impl CountTokens for TokenCounter {
+    fn count_tokens(&self, text: &str) -> usize {
+        TokenCounter::count_tokens(self, text)
+    }
+}
+
 /// Get the global tokenizer instance (async version)
 /// Fixed encoding for all tokenization - using o200k_base for GPT-4o and o1 models
 async fn get_tokenizer() -> Result<Arc<CoreBPE>, String> {
@@ -693,4 +798,63 @@ mod tests {
             "Longer text should have more tokens"
         );
     }
+
+    const PROSE: &str = "The quick brown fox jumps over the lazy dog. Meanwhile, the project \
+        manager scheduled a meeting to discuss the quarterly roadmap and the budget for next year.";
+    const CODE: &str = "fn main() {\n    let numbers = vec![1, 2, 3, 4, 5];\n    let total: i32 = \
+        numbers.iter().map(|n| n * 2).sum();\n    println!(\"total = {}\", total);\n}\n";
+    const JSON: &str =
+        r#"{"path": "src/main.rs", "command": "view", "view_range": [1, 40], "recursive": false}"#;
+    const CJK: &str = "今日は天気がいいので、公園を散歩しました。明日は雨が降るそうです。";
+
+    #[test]
+    fn test_heuristic_counts_fixtures() {
+        let counter = HeuristicTokenCounter;
+        assert_eq!(counter.count_tokens(PROSE), 30);
+        assert_eq!(counter.count_tokens(CODE), 50);
+        assert_eq!(counter.count_tokens(JSON), 31);
+        assert_eq!(counter.count_tokens(CJK), 22);
+        assert_eq!(counter.count_tokens(""), 0);
+    }
+
+    #[test]
+    fn test_heuristic_agrees_with_tokenizer() {
+        let heuristic = HeuristicTokenCounter;
+        let exact = TokenCounter::new();
+        assert_eq!(exact.count_tokens(PROSE), 30);
+        assert_eq!(exact.count_tokens(CODE), 54);
+
+        for text in [PROSE, CODE, JSON, CJK] {
+            let estimate = heuristic.count_tokens(text) as f64;
+            let actual = exact.count_tokens(text) as f64;
+            assert!(
+                (estimate - actual).abs() <= actual * 0.2,
+                "estimated {} tokens but the tokenizer counts {} for {:?}",
+                estimate,
+                actual,
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_count_message_tokens() {
+        let counter = HeuristicTokenCounter;
+        let request = Message::assistant().with_tool_request(
+            "call_1",
+            Ok(mcp_core::tool::ToolCall::new(
+                "developer__shell",
+                serde_json::json!({"command": "ls"}),
+            )),
+        );
+        // "call_1:developer__shell:{"command":"ls"}" plus the message overhead
+        assert_eq!(counter.count_message_tokens(&request), 4 + 14);
+
+        let response = Message::user()
+            .with_tool_response("call_1", Ok(vec![rmcp::model::Content::text(PROSE)]));
+        assert_eq!(counter.count_message_tokens(&response), 4 + 30);
+
+        let text = Message::user().with_text(CJK);
+        assert_eq!(counter.count_message_tokens(&text), 4 + 22);
+    }
 }
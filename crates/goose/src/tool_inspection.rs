@@ -153,6 +153,24 @@ impl ToolInspectionManager {
         tracing::warn!("Permission inspector not found for permission manager update");
     }
 
+    /// Add a permission rule (e.g. from an "always allow this" approval prompt).
+    /// The rule is placed first so it overrides older, broader rules.
+    pub async fn add_permission_rule(&self, rule: crate::config::permission::PermissionRule) {
+        for inspector in &self.inspectors {
+            if inspector.name() == "permission" {
+                if let Some(permission_inspector) =
+                    inspector.as_any().downcast_ref::<PermissionInspector>()
+                {
+                    let mut permission_manager =
+                        permission_inspector.permission_manager.lock().await;
+                    permission_manager.prepend_rule(rule);
+                    return;
+                }
+            }
+        }
+        tracing::warn!("Permission inspector not found for permission rule update");
+    }
+
     /// Process inspection results using the permission inspector
     /// This delegates to the permission inspector's process_inspection_results method
     pub fn process_inspection_results_with_permission_inspector(
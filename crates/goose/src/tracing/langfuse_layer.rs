@@ -1,4 +1,5 @@
 use crate::tracing::observation_layer::{BatchManager, ObservationLayer, SpanTracker};
+use crate::tracing::telemetry_guard::TelemetryGuard;
 use chrono::Utc;
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
@@ -153,6 +154,11 @@ impl BatchManager for LangfuseBatchManager {
 }
 
 pub fn create_langfuse_observer() -> Option<ObservationLayer> {
+    create_langfuse_observer_with_guard().map(|(layer, _)| layer)
+}
+
+/// Like [`create_langfuse_observer`], also returning a guard that flushes the pending batch
+pub fn create_langfuse_observer_with_guard() -> Option<(ObservationLayer, TelemetryGuard)> {
     let public_key = env::var("LANGFUSE_PUBLIC_KEY")
         .or_else(|_| env::var("LANGFUSE_INIT_PROJECT_PUBLIC_KEY"))
         .unwrap_or_default(); // Use empty string if not found
@@ -176,10 +182,14 @@ pub fn create_langfuse_observer() -> Option<ObservationLayer> {
         LangfuseBatchManager::spawn_sender(batch_manager.clone());
     }
 
-    Some(ObservationLayer {
-        batch_manager,
-        span_tracker: Arc::new(Mutex::new(SpanTracker::new())),
-    })
+    let guard = TelemetryGuard::with_flusher(batch_manager.clone());
+    Some((
+        ObservationLayer {
+            batch_manager,
+            span_tracker: Arc::new(Mutex::new(SpanTracker::new())),
+        },
+        guard,
+    ))
 }
 
 #[cfg(test)]
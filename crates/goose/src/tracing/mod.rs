@@ -1,9 +1,18 @@
+pub mod file_layer;
 pub mod langfuse_layer;
 mod observation_layer;
 pub mod otlp_layer;
 pub mod rate_limiter;
+pub mod redaction;
+pub mod telemetry_guard;
+pub mod usage_metrics;
 
-pub use langfuse_layer::{create_langfuse_observer, LangfuseBatchManager};
+pub use file_layer::{
+    create_file_trace_filter, create_file_trace_layer, FileTraceLayer, RotatingFileWriter,
+};
+pub use langfuse_layer::{
+    create_langfuse_observer, create_langfuse_observer_with_guard, LangfuseBatchManager,
+};
 pub use observation_layer::{
     flatten_metadata, map_level, BatchManager, ObservationLayer, SpanData, SpanTracker,
 };
@@ -14,3 +23,8 @@ pub use otlp_layer::{
 pub use rate_limiter::{
     MetricData, RateLimitedTelemetrySender, SpanData as RateLimitedSpanData, TelemetryEvent,
 };
+pub use redaction::{RedactingSpanExporter, Redactor};
+pub use telemetry_guard::{TelemetryFlush, TelemetryGuard};
+pub use usage_metrics::{
+    record_usage, ModelPrice, PricingTable, UsageEvent, UsageMetrics, PRICING_CONFIG_KEY,
+};
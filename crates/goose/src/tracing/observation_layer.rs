@@ -1,3 +1,4 @@
+use crate::tracing::redaction::Redactor;
 use chrono::Utc;
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -263,7 +264,7 @@ where
             name: attrs.metadata().name().to_string(),
             start_time: Utc::now().to_rfc3339(),
             level: map_level(attrs.metadata().level()).to_owned(),
-            metadata: visitor.recorded_fields,
+            metadata: visitor.into_redacted_fields(),
             parent_span_id,
         };
 
@@ -281,7 +282,7 @@ where
         let span_id = span.into_u64();
         let mut visitor = JsonVisitor::new();
         values.record(&mut visitor);
-        let metadata = visitor.recorded_fields;
+        let metadata = visitor.into_redacted_fields();
 
         if !metadata.is_empty() {
             let layer = self.clone();
@@ -292,7 +293,7 @@ where
     fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
         let mut visitor = JsonVisitor::new();
         event.record(&mut visitor);
-        let metadata = visitor.recorded_fields;
+        let metadata = visitor.into_redacted_fields();
 
         if let Some(span_id) = ctx.lookup_current().map(|span| span.id().into_u64()) {
             let layer = self.clone();
@@ -302,17 +303,23 @@ where
 }
 
 #[derive(Debug)]
-struct JsonVisitor {
-    recorded_fields: serde_json::Map<String, Value>,
+pub(crate) struct JsonVisitor {
+    pub(crate) recorded_fields: serde_json::Map<String, Value>,
 }
 
 impl JsonVisitor {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             recorded_fields: serde_json::Map::new(),
         }
     }
 
+    /// The recorded fields with secrets scrubbed, ready to leave the process
+    pub(crate) fn into_redacted_fields(mut self) -> serde_json::Map<String, Value> {
+        Redactor::global().redact_map(&mut self.recorded_fields);
+        self.recorded_fields
+    }
+
     fn insert_value(&mut self, field: &Field, value: Value) {
         self.recorded_fields.insert(field.name().to_string(), value);
     }
@@ -329,6 +336,7 @@ macro_rules! record_field {
 impl Visit for JsonVisitor {
     record_field!(record_i64, i64);
     record_field!(record_u64, u64);
+    record_field!(record_f64, f64);
     record_field!(record_bool, bool);
     record_field!(record_str, &str);
 
@@ -340,9 +348,11 @@ impl Visit for JsonVisitor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tracing::redaction::REDACTION_COUNT_FIELD;
     use std::time::Duration;
     use tokio::sync::mpsc;
     use tracing::dispatcher;
+    use tracing_subscriber::layer::SubscriberExt;
 
     type Events = Arc<Mutex<Vec<(String, Value)>>>;
     struct TestFixture {
@@ -502,6 +512,37 @@ mod tests {
         assert_eq!(body["metadata"]["custom_field"], "custom value");
     }
 
+    #[tokio::test]
+    async fn test_span_fields_are_redacted() {
+        let (fixture, layer) = TestFixture::new().with_test_layer();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let span = tracing::info_span!(
+            target: "goose::providers",
+            "complete",
+            api_key = "plain-secret",
+            input = "use key sk-abcdefghijklmnopqrstuvwxyz0123",
+        );
+        span.in_scope(|| tracing::info!(target: "goose::providers", auth_token = "abc123"));
+        tokio::time::sleep(TEST_WAIT_DURATION).await;
+
+        let events = fixture.get_events().await;
+        let (_, create) = events
+            .iter()
+            .find(|(event_type, _)| event_type == "observation-create")
+            .unwrap();
+        assert_eq!(create["metadata"]["api_key"], "[REDACTED:api_key]");
+        assert_eq!(create["metadata"]["input"], "use key [REDACTED:openai_key]");
+        assert_eq!(create["metadata"][REDACTION_COUNT_FIELD], 2);
+
+        let (_, update) = events
+            .iter()
+            .find(|(event_type, _)| event_type == "span-update")
+            .unwrap();
+        assert_eq!(update["metadata"]["auth_token"], "[REDACTED:token]");
+    }
+
     #[test]
     fn test_flatten_metadata() {
         let _fixture = TestFixture::new();
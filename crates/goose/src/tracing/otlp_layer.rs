@@ -8,6 +8,9 @@ use tracing::{Level, Metadata};
 use tracing_opentelemetry::{MetricsLayer, OpenTelemetryLayer};
 use tracing_subscriber::filter::FilterFn;
 
+use crate::tracing::redaction::{RedactingSpanExporter, Redactor};
+use crate::tracing::telemetry_guard::TelemetryGuard;
+
 pub type OtlpTracingLayer =
     OpenTelemetryLayer<tracing_subscriber::Registry, opentelemetry_sdk::trace::Tracer>;
 pub type OtlpMetricsLayer = MetricsLayer<tracing_subscriber::Registry>;
@@ -53,7 +56,7 @@ impl OtlpConfig {
     }
 }
 
-pub fn init_otlp_tracing(config: &OtlpConfig) -> OtlpResult<()> {
+pub fn init_otlp_tracing(config: &OtlpConfig) -> OtlpResult<TelemetryGuard> {
     let resource = Resource::new(vec![
         KeyValue::new("service.name", "goose"),
         KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
@@ -65,6 +68,7 @@ pub fn init_otlp_tracing(config: &OtlpConfig) -> OtlpResult<()> {
         .with_endpoint(&config.endpoint)
         .with_timeout(config.timeout)
         .build()?;
+    let exporter = RedactingSpanExporter::new(exporter, Redactor::global().clone());
 
     let tracer_provider = trace::TracerProvider::builder()
         .with_batch_exporter(exporter, runtime::Tokio)
@@ -73,12 +77,12 @@ pub fn init_otlp_tracing(config: &OtlpConfig) -> OtlpResult<()> {
         .with_sampler(Sampler::AlwaysOn)
         .build();
 
-    global::set_tracer_provider(tracer_provider);
+    global::set_tracer_provider(tracer_provider.clone());
 
-    Ok(())
+    Ok(TelemetryGuard::with_flusher(tracer_provider))
 }
 
-pub fn init_otlp_metrics(config: &OtlpConfig) -> OtlpResult<()> {
+pub fn init_otlp_metrics(config: &OtlpConfig) -> OtlpResult<TelemetryGuard> {
     let resource = Resource::new(vec![
         KeyValue::new("service.name", "goose"),
         KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
@@ -100,12 +104,16 @@ pub fn init_otlp_metrics(config: &OtlpConfig) -> OtlpResult<()> {
         )
         .build();
 
-    global::set_meter_provider(meter_provider);
+    global::set_meter_provider(meter_provider.clone());
 
-    Ok(())
+    Ok(TelemetryGuard::with_flusher(meter_provider))
 }
 
 pub fn create_otlp_tracing_layer() -> OtlpResult<OtlpTracingLayer> {
+    Ok(build_otlp_tracing_layer()?.0)
+}
+
+fn build_otlp_tracing_layer() -> OtlpResult<(OtlpTracingLayer, trace::TracerProvider)> {
     let config = OtlpConfig::from_config().ok_or("OTEL_EXPORTER_OTLP_ENDPOINT not configured")?;
 
     let resource = Resource::new(vec![
@@ -119,6 +127,7 @@ pub fn create_otlp_tracing_layer() -> OtlpResult<OtlpTracingLayer> {
         .with_endpoint(&config.endpoint)
         .with_timeout(config.timeout)
         .build()?;
+    let exporter = RedactingSpanExporter::new(exporter, Redactor::global().clone());
 
     let tracer_provider = trace::TracerProvider::builder()
         .with_batch_exporter(exporter, runtime::Tokio)
@@ -131,10 +140,20 @@ pub fn create_otlp_tracing_layer() -> OtlpResult<OtlpTracingLayer> {
         .build();
 
     let tracer = tracer_provider.tracer("goose");
-    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+    Ok((
+        tracing_opentelemetry::layer().with_tracer(tracer),
+        tracer_provider,
+    ))
 }
 
 pub fn create_otlp_metrics_layer() -> OtlpResult<OtlpMetricsLayer> {
+    Ok(build_otlp_metrics_layer()?.0)
+}
+
+fn build_otlp_metrics_layer() -> OtlpResult<(
+    OtlpMetricsLayer,
+    opentelemetry_sdk::metrics::SdkMeterProvider,
+)> {
     let config = OtlpConfig::from_config().ok_or("OTEL_EXPORTER_OTLP_ENDPOINT not configured")?;
 
     let resource = Resource::new(vec![
@@ -160,13 +179,20 @@ pub fn create_otlp_metrics_layer() -> OtlpResult<OtlpMetricsLayer> {
 
     global::set_meter_provider(meter_provider.clone());
 
-    Ok(tracing_opentelemetry::MetricsLayer::new(meter_provider))
+    Ok((
+        tracing_opentelemetry::MetricsLayer::new(meter_provider.clone()),
+        meter_provider,
+    ))
 }
 
-pub fn init_otlp() -> OtlpResult<OtlpLayers> {
-    let tracing_layer = create_otlp_tracing_layer()?;
-    let metrics_layer = create_otlp_metrics_layer()?;
-    Ok((tracing_layer, metrics_layer))
+/// Create the OTLP tracing and metrics layers, along with a guard that flushes both
+pub fn init_otlp() -> OtlpResult<(OtlpLayers, TelemetryGuard)> {
+    let (tracing_layer, tracer_provider) = build_otlp_tracing_layer()?;
+    let (metrics_layer, meter_provider) = build_otlp_metrics_layer()?;
+
+    let mut guard = TelemetryGuard::with_flusher(tracer_provider);
+    guard.add(meter_provider);
+    Ok(((tracing_layer, metrics_layer), guard))
 }
 
 pub fn init_otlp_tracing_only() -> OtlpResult<OtlpTracingLayer> {
@@ -222,14 +248,10 @@ pub fn create_otlp_metrics_filter() -> FilterFn<impl Fn(&Metadata<'_>) -> bool>
 }
 
 /// Shutdown OTLP providers gracefully
+/// Shut down the global tracer provider. Flush pending spans and metrics through the
+/// [`TelemetryGuard`] returned by [`init_otlp`] first; this no longer waits for them.
 pub fn shutdown_otlp() {
-    // Shutdown the tracer provider and flush any pending spans
     global::shutdown_tracer_provider();
-
-    // Force flush of metrics by waiting a bit
-    // The meter provider doesn't have a direct shutdown method in the current SDK,
-    // but we can give it time to export any pending metrics
-    std::thread::sleep(std::time::Duration::from_millis(500));
 }
 
 #[cfg(test)]
@@ -3,6 +3,8 @@ use tokio::sync::mpsc;
 use tokio::time::sleep;
 use tracing::{info, warn};
 
+use crate::tracing::usage_metrics::{UsageEvent, UsageMetrics};
+
 pub struct RateLimitedTelemetrySender {
     sender: mpsc::UnboundedSender<TelemetryEvent>,
 }
@@ -11,6 +13,7 @@ pub struct RateLimitedTelemetrySender {
 pub enum TelemetryEvent {
     Span(SpanData),
     Metric(MetricData),
+    Usage(UsageEvent),
 }
 
 #[derive(Debug, Clone)]
@@ -29,6 +32,15 @@ pub struct MetricData {
 
 impl RateLimitedTelemetrySender {
     pub fn new(rate_limit_ms: u64) -> Self {
+        Self::spawn(rate_limit_ms, None)
+    }
+
+    /// A sender that also records usage events into `usage_metrics`
+    pub fn with_usage_metrics(rate_limit_ms: u64, usage_metrics: UsageMetrics) -> Self {
+        Self::spawn(rate_limit_ms, Some(usage_metrics))
+    }
+
+    fn spawn(rate_limit_ms: u64, usage_metrics: Option<UsageMetrics>) -> Self {
         let (sender, mut receiver) = mpsc::unbounded_channel::<TelemetryEvent>();
 
         tokio::spawn(async move {
@@ -54,6 +66,10 @@ impl RateLimitedTelemetrySender {
                     TelemetryEvent::Metric(metric_data) => {
                         Self::process_metric(metric_data).await;
                     }
+                    TelemetryEvent::Usage(usage) => match &usage_metrics {
+                        Some(usage_metrics) => usage_metrics.record(&usage),
+                        None => warn!("No usage metrics to record {:?} into", usage),
+                    },
                 }
 
                 last_send = Instant::now();
@@ -79,6 +95,13 @@ impl RateLimitedTelemetrySender {
         self.sender.send(TelemetryEvent::Metric(metric_data))
     }
 
+    pub fn send_usage(
+        &self,
+        usage: UsageEvent,
+    ) -> Result<(), mpsc::error::SendError<TelemetryEvent>> {
+        self.sender.send(TelemetryEvent::Usage(usage))
+    }
+
     async fn process_span(span_data: SpanData) {
         let span = tracing::info_span!("telemetry_span", name = %span_data.name);
         let _enter = span.enter();
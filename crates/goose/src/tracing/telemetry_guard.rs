@@ -0,0 +1,204 @@
+use async_trait::async_trait;
+use futures::future::join_all;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{oneshot, Mutex};
+
+use crate::tracing::langfuse_layer::LangfuseBatchManager;
+
+pub type FlushResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+/// Something holding buffered telemetry that can be pushed out on demand
+#[async_trait]
+pub trait TelemetryFlush: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn flush(&self) -> FlushResult;
+}
+
+/// Run a blocking flush on its own thread so a stalled exporter can be abandoned at exit
+/// without holding up the runtime's shutdown the way `spawn_blocking` would.
+async fn flush_on_thread<F>(flush: F) -> FlushResult
+where
+    F: FnOnce() -> FlushResult + Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(flush());
+    });
+    rx.await
+        .unwrap_or_else(|_| Err("telemetry flush thread panicked".into()))
+}
+
+#[async_trait]
+impl TelemetryFlush for opentelemetry_sdk::trace::TracerProvider {
+    fn name(&self) -> &'static str {
+        "otlp traces"
+    }
+
+    async fn flush(&self) -> FlushResult {
+        let provider = self.clone();
+        flush_on_thread(move || {
+            for result in provider.force_flush() {
+                result?;
+            }
+            Ok(())
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl TelemetryFlush for opentelemetry_sdk::metrics::SdkMeterProvider {
+    fn name(&self) -> &'static str {
+        "otlp metrics"
+    }
+
+    async fn flush(&self) -> FlushResult {
+        let provider = self.clone();
+        flush_on_thread(move || Ok(provider.force_flush()?)).await
+    }
+}
+
+#[async_trait]
+impl TelemetryFlush for Arc<Mutex<LangfuseBatchManager>> {
+    fn name(&self) -> &'static str {
+        "langfuse"
+    }
+
+    async fn flush(&self) -> FlushResult {
+        self.lock().await.send_async().await
+    }
+}
+
+/// Handle to every telemetry exporter set up at startup.
+///
+/// Call [`TelemetryGuard::flush_with_timeout`] before exiting so pending spans, metrics and
+/// Langfuse batches are exported without waiting longer than necessary.
+#[derive(Clone, Default)]
+pub struct TelemetryGuard {
+    flushers: Vec<Arc<dyn TelemetryFlush>>,
+}
+
+impl TelemetryGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_flusher(flusher: impl TelemetryFlush + 'static) -> Self {
+        let mut guard = Self::new();
+        guard.add(flusher);
+        guard
+    }
+
+    pub fn add(&mut self, flusher: impl TelemetryFlush + 'static) {
+        self.flushers.push(Arc::new(flusher));
+    }
+
+    /// Take over the exporters held by another guard
+    pub fn merge(&mut self, other: TelemetryGuard) {
+        self.flushers.extend(other.flushers);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flushers.is_empty()
+    }
+
+    /// Flush all exporters concurrently, giving up after `timeout`.
+    ///
+    /// Returns `true` if every exporter finished in time. Export failures are logged but
+    /// still count as finished.
+    pub async fn flush_with_timeout(&self, timeout: Duration) -> bool {
+        if self.flushers.is_empty() {
+            return true;
+        }
+
+        let flushes = self.flushers.iter().map(|flusher| async move {
+            if let Err(e) = flusher.flush().await {
+                tracing::warn!(
+                    exporter = flusher.name(),
+                    "Failed to flush telemetry: {}",
+                    e
+                );
+            }
+        });
+
+        match tokio::time::timeout(timeout, join_all(flushes)).await {
+            Ok(_) => true,
+            Err(_) => {
+                tracing::warn!(
+                    timeout_ms = timeout.as_millis() as u64,
+                    "Timed out flushing telemetry"
+                );
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::BoxFuture;
+    use opentelemetry::trace::{Tracer, TracerProvider as _};
+    use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+    use opentelemetry_sdk::runtime;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use std::time::Instant;
+
+    #[derive(Debug)]
+    struct TestExporter {
+        stall: bool,
+    }
+
+    impl SpanExporter for TestExporter {
+        fn export(&mut self, _batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+            if self.stall {
+                Box::pin(futures::future::pending())
+            } else {
+                Box::pin(futures::future::ready(Ok(())))
+            }
+        }
+    }
+
+    fn provider_with_pending_span(stall: bool) -> TracerProvider {
+        let provider = TracerProvider::builder()
+            .with_batch_exporter(TestExporter { stall }, runtime::Tokio)
+            .build();
+        provider.tracer("test").in_span("turn", |_| {});
+        provider
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_flush_completes_promptly_with_noop_exporter() {
+        let guard = TelemetryGuard::with_flusher(provider_with_pending_span(false));
+
+        let start = Instant::now();
+        assert!(guard.flush_with_timeout(Duration::from_secs(5)).await);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_flush_respects_timeout_when_exporter_stalls() {
+        let mut guard = TelemetryGuard::with_flusher(provider_with_pending_span(true));
+        guard.merge(TelemetryGuard::with_flusher(provider_with_pending_span(
+            false,
+        )));
+
+        let start = Instant::now();
+        assert!(!guard.flush_with_timeout(Duration::from_millis(200)).await);
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(200));
+        assert!(elapsed < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_empty_guard_flushes_immediately() {
+        assert!(TelemetryGuard::new().is_empty());
+        assert!(
+            TelemetryGuard::new()
+                .flush_with_timeout(Duration::ZERO)
+                .await
+        );
+    }
+}
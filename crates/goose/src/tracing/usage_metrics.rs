@@ -0,0 +1,363 @@
+//! Token usage and estimated cost of each provider response, as OpenTelemetry metrics
+//!
+//! Events are recorded through a [`RateLimitedTelemetrySender`], so they are exported by
+//! whichever meter provider [`init_otlp_metrics`](super::init_otlp_metrics) installed. The cost
+//! comes from the `GOOSE_METRICS_PRICING` config key, which maps provider and model names to
+//! prices in USD per million tokens:
+//!
+//! ```yaml
+//! GOOSE_METRICS_PRICING:
+//!   anthropic:
+//!     claude-sonnet-4-0: { input_per_million: 3.0, output_per_million: 15.0 }
+//! ```
+//!
+//! Responses from a model without a price are recorded with a cost of 0 and `price_unknown`
+//! set, so they can be told apart from free ones.
+
+use opentelemetry::metrics::{Counter, Histogram, Meter};
+use opentelemetry::{global, KeyValue};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::config::Config;
+use crate::tracing::rate_limiter::RateLimitedTelemetrySender;
+
+pub const PRICING_CONFIG_KEY: &str = "GOOSE_METRICS_PRICING";
+
+/// Prices of a model in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ModelPrice {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+impl ModelPrice {
+    pub fn cost(&self, input_tokens: u64, output_tokens: u64) -> f64 {
+        (input_tokens as f64 * self.input_per_million
+            + output_tokens as f64 * self.output_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// Model prices by provider, then model
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct PricingTable(HashMap<String, HashMap<String, ModelPrice>>);
+
+impl PricingTable {
+    /// Load the table from `GOOSE_METRICS_PRICING`, or an empty one if it isn't set or invalid
+    pub fn from_config(config: &Config) -> Self {
+        match config.get_param::<Self>(PRICING_CONFIG_KEY) {
+            Ok(table) => table,
+            Err(crate::config::ConfigError::NotFound(_)) => Self::default(),
+            Err(e) => {
+                tracing::warn!("Ignoring invalid {}: {}", PRICING_CONFIG_KEY, e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn with_price(mut self, provider: &str, model: &str, price: ModelPrice) -> Self {
+        self.0
+            .entry(provider.to_string())
+            .or_default()
+            .insert(model.to_string(), price);
+        self
+    }
+
+    pub fn price(&self, provider: &str, model: &str) -> Option<ModelPrice> {
+        self.0.get(provider)?.get(model).copied()
+    }
+}
+
+/// The token usage of one provider response
+#[derive(Debug, Clone)]
+pub struct UsageEvent {
+    pub provider: String,
+    pub model: String,
+    /// The goose mode of the session, e.g. `auto` or `approve`
+    pub mode: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// The instruments usage events are recorded into
+#[derive(Clone)]
+pub struct UsageMetrics {
+    input_tokens: Counter<u64>,
+    output_tokens: Counter<u64>,
+    estimated_cost: Counter<f64>,
+    input_tokens_per_response: Histogram<u64>,
+    output_tokens_per_response: Histogram<u64>,
+    estimated_cost_per_response: Histogram<f64>,
+    pricing: PricingTable,
+}
+
+impl UsageMetrics {
+    pub fn new(meter: &Meter, pricing: PricingTable) -> Self {
+        Self {
+            input_tokens: meter
+                .u64_counter("goose.input_tokens")
+                .with_description("Input tokens sent to the provider")
+                .with_unit("{token}")
+                .build(),
+            output_tokens: meter
+                .u64_counter("goose.output_tokens")
+                .with_description("Output tokens returned by the provider")
+                .with_unit("{token}")
+                .build(),
+            estimated_cost: meter
+                .f64_counter("goose.estimated_cost_usd")
+                .with_description("Estimated cost of provider responses")
+                .with_unit("USD")
+                .build(),
+            input_tokens_per_response: meter
+                .u64_histogram("goose.input_tokens.per_response")
+                .with_description("Input tokens of each provider response")
+                .with_unit("{token}")
+                .build(),
+            output_tokens_per_response: meter
+                .u64_histogram("goose.output_tokens.per_response")
+                .with_description("Output tokens of each provider response")
+                .with_unit("{token}")
+                .build(),
+            estimated_cost_per_response: meter
+                .f64_histogram("goose.estimated_cost_usd.per_response")
+                .with_description("Estimated cost of each provider response")
+                .with_unit("USD")
+                .build(),
+            pricing,
+        }
+    }
+
+    pub fn record(&self, event: &UsageEvent) {
+        let attributes = [
+            KeyValue::new("provider", event.provider.clone()),
+            KeyValue::new("model", event.model.clone()),
+            KeyValue::new("mode", event.mode.clone()),
+        ];
+        self.input_tokens.add(event.input_tokens, &attributes);
+        self.output_tokens.add(event.output_tokens, &attributes);
+        self.input_tokens_per_response
+            .record(event.input_tokens, &attributes);
+        self.output_tokens_per_response
+            .record(event.output_tokens, &attributes);
+
+        let price = self.pricing.price(&event.provider, &event.model);
+        let cost = price.map_or(0.0, |p| p.cost(event.input_tokens, event.output_tokens));
+        let mut cost_attributes = attributes.to_vec();
+        cost_attributes.push(KeyValue::new("price_unknown", price.is_none()));
+        self.estimated_cost.add(cost, &cost_attributes);
+        self.estimated_cost_per_response
+            .record(cost, &cost_attributes);
+    }
+}
+
+static USAGE_SENDER: OnceLock<RateLimitedTelemetrySender> = OnceLock::new();
+
+/// Record the usage of a provider response with the global meter provider. Must be called
+/// from within a tokio runtime, which the first call starts the recording task on.
+pub fn record_usage(event: UsageEvent) {
+    let sender = USAGE_SENDER.get_or_init(|| {
+        let metrics = UsageMetrics::new(
+            &global::meter("goose"),
+            PricingTable::from_config(Config::global()),
+        );
+        RateLimitedTelemetrySender::with_usage_metrics(400, metrics)
+    });
+    if let Err(e) = sender.send_usage(event) {
+        tracing::debug!("Dropped usage metrics: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::metrics::MeterProvider;
+    use opentelemetry::Value;
+    use opentelemetry_sdk::metrics::data::{self, ResourceMetrics};
+    use opentelemetry_sdk::metrics::reader::MetricReader;
+    use opentelemetry_sdk::metrics::{
+        InstrumentKind, ManualReader, MetricResult, Pipeline, SdkMeterProvider, Temporality,
+    };
+    use std::sync::{Arc, Weak};
+    use std::time::Duration;
+
+    /// A reader the test keeps a handle to after the meter provider takes ownership of it
+    #[derive(Clone, Debug)]
+    struct InMemoryReader(Arc<ManualReader>);
+
+    impl MetricReader for InMemoryReader {
+        fn register_pipeline(&self, pipeline: Weak<Pipeline>) {
+            self.0.register_pipeline(pipeline)
+        }
+
+        fn collect(&self, rm: &mut ResourceMetrics) -> MetricResult<()> {
+            self.0.collect(rm)
+        }
+
+        fn force_flush(&self) -> MetricResult<()> {
+            self.0.force_flush()
+        }
+
+        fn shutdown(&self) -> MetricResult<()> {
+            self.0.shutdown()
+        }
+
+        fn temporality(&self, kind: InstrumentKind) -> Temporality {
+            self.0.temporality(kind)
+        }
+    }
+
+    impl InMemoryReader {
+        fn sums(&self) -> HashMap<(String, String), f64> {
+            let mut rm = ResourceMetrics {
+                resource: Default::default(),
+                scope_metrics: vec![],
+            };
+            self.collect(&mut rm).unwrap();
+            let mut sums = HashMap::new();
+            for metric in rm.scope_metrics.iter().flat_map(|s| &s.metrics) {
+                let any = metric.data.as_any();
+                let points: Vec<(&[KeyValue], f64)> =
+                    if let Some(sum) = any.downcast_ref::<data::Sum<u64>>() {
+                        sum.data_points
+                            .iter()
+                            .map(|p| (p.attributes.as_slice(), p.value as f64))
+                            .collect()
+                    } else if let Some(sum) = any.downcast_ref::<data::Sum<f64>>() {
+                        sum.data_points
+                            .iter()
+                            .map(|p| (p.attributes.as_slice(), p.value))
+                            .collect()
+                    } else if let Some(histogram) = any.downcast_ref::<data::Histogram<u64>>() {
+                        histogram
+                            .data_points
+                            .iter()
+                            .map(|p| (p.attributes.as_slice(), p.sum as f64))
+                            .collect()
+                    } else if let Some(histogram) = any.downcast_ref::<data::Histogram<f64>>() {
+                        histogram
+                            .data_points
+                            .iter()
+                            .map(|p| (p.attributes.as_slice(), p.sum))
+                            .collect()
+                    } else {
+                        vec![]
+                    };
+                for (attributes, value) in points {
+                    sums.insert((metric.name.to_string(), label(attributes)), value);
+                }
+            }
+            sums
+        }
+    }
+
+    /// Attributes as `key=value` pairs, sorted
+    fn label(attributes: &[KeyValue]) -> String {
+        let mut pairs: Vec<String> = attributes
+            .iter()
+            .map(|kv| match &kv.value {
+                Value::String(s) => format!("{}={}", kv.key, s),
+                other => format!("{}={}", kv.key, other),
+            })
+            .collect();
+        pairs.sort();
+        pairs.join(",")
+    }
+
+    fn event(provider: &str, model: &str, input_tokens: u64, output_tokens: u64) -> UsageEvent {
+        UsageEvent {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            mode: "auto".to_string(),
+            input_tokens,
+            output_tokens,
+        }
+    }
+
+    #[test]
+    fn test_pricing_table_from_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = Config::new_with_file_secrets(
+            temp_dir.path().join("config.yaml"),
+            temp_dir.path().join("secrets.yaml"),
+        )
+        .unwrap();
+        assert_eq!(PricingTable::from_config(&config), PricingTable::default());
+
+        config
+            .set_param(
+                PRICING_CONFIG_KEY,
+                serde_json::json!({
+                    "openai": {"gpt-4o": {"input_per_million": 2.5, "output_per_million": 10.0}}
+                }),
+            )
+            .unwrap();
+        let table = PricingTable::from_config(&config);
+        let price = table.price("openai", "gpt-4o").unwrap();
+        assert_eq!(price.cost(1_000_000, 100_000), 3.5);
+        assert_eq!(table.price("openai", "gpt-4.1"), None);
+        assert_eq!(table.price("anthropic", "gpt-4o"), None);
+    }
+
+    #[tokio::test]
+    async fn test_usage_events_are_exported() {
+        let reader = InMemoryReader(Arc::new(ManualReader::builder().build()));
+        let provider = SdkMeterProvider::builder()
+            .with_reader(reader.clone())
+            .build();
+        let pricing = PricingTable::default().with_price(
+            "anthropic",
+            "claude-sonnet-4-0",
+            ModelPrice {
+                input_per_million: 3.0,
+                output_per_million: 15.0,
+            },
+        );
+        let metrics = UsageMetrics::new(&provider.meter("goose"), pricing);
+        let sender = RateLimitedTelemetrySender::with_usage_metrics(10, metrics);
+
+        sender
+            .send_usage(event("anthropic", "claude-sonnet-4-0", 1000, 200))
+            .unwrap();
+        sender
+            .send_usage(event("anthropic", "claude-sonnet-4-0", 3000, 800))
+            .unwrap();
+        sender
+            .send_usage(event("ollama", "qwen3", 500, 50))
+            .unwrap();
+
+        let priced = "mode=auto,model=claude-sonnet-4-0,provider=anthropic";
+        let priced_cost = format!("{},price_unknown=false", priced);
+        let unpriced = "mode=auto,model=qwen3,provider=ollama";
+        let unpriced_cost = format!("{},price_unknown=true", unpriced);
+        let key = |name: &str, attributes: &str| (name.to_string(), attributes.to_string());
+
+        // Events are recorded by the rate limited task, so wait for the last one to land
+        let mut sums = reader.sums();
+        for _ in 0..100 {
+            if sums.contains_key(&key("goose.input_tokens", unpriced)) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            sums = reader.sums();
+        }
+
+        assert_eq!(sums[&key("goose.input_tokens", priced)], 4000.0);
+        assert_eq!(sums[&key("goose.output_tokens", priced)], 1000.0);
+        assert_eq!(
+            sums[&key("goose.input_tokens.per_response", priced)],
+            4000.0
+        );
+        let cost = sums[&key("goose.estimated_cost_usd", &priced_cost)];
+        assert!((cost - 0.027).abs() < 1e-9, "{}", cost);
+        let cost = sums[&key("goose.estimated_cost_usd.per_response", &priced_cost)];
+        assert!((cost - 0.027).abs() < 1e-9, "{}", cost);
+
+        assert_eq!(sums[&key("goose.input_tokens", unpriced)], 500.0);
+        assert_eq!(sums[&key("goose.output_tokens", unpriced)], 50.0);
+        assert_eq!(sums[&key("goose.estimated_cost_usd", &unpriced_cost)], 0.0);
+    }
+}
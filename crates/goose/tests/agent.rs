@@ -1152,3 +1152,336 @@ mod max_turns_tests {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod parallel_tool_tests {
+    use super::*;
+    use async_trait::async_trait;
+    use goose::agents::extension::ExtensionConfig;
+    use goose::conversation::message::{Message, MessageContent};
+    use goose::conversation::Conversation;
+    use goose::model::ModelConfig;
+    use goose::providers::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
+    use goose::providers::errors::ProviderError;
+    use mcp_client::client::{Error, McpClientTrait};
+    use mcp_core::tool::ToolCall;
+    use rmcp::model::{
+        CallToolResult, Content, GetPromptResult, InitializeResult, ListPromptsResult,
+        ListResourcesResult, ListToolsResult, ReadResourceResult, ServerNotification, Tool,
+        ToolAnnotations,
+    };
+    use serde_json::{json, Value};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+    use tokio::sync::mpsc;
+    use tokio_util::sync::CancellationToken;
+
+    /// Asks for the given tool calls once, then finishes once their responses come back
+    struct ToolCallsProvider {
+        calls: Vec<(&'static str, &'static str, u64)>,
+    }
+
+    #[async_trait]
+    impl Provider for ToolCallsProvider {
+        async fn complete(
+            &self,
+            _system_prompt: &str,
+            messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            let answered = messages.last().is_some_and(|m| {
+                m.content
+                    .iter()
+                    .any(|c| matches!(c, MessageContent::ToolResponse(_)))
+            });
+            let message = if answered {
+                Message::assistant().with_text("Done.")
+            } else {
+                self.calls
+                    .iter()
+                    .fold(Message::assistant(), |message, (id, tool, delay_ms)| {
+                        message.with_tool_request(
+                            *id,
+                            Ok(ToolCall::new(
+                                *tool,
+                                json!({"label": id, "delay_ms": delay_ms}),
+                            )),
+                        )
+                    })
+            };
+            Ok((
+                message,
+                ProviderUsage::new("mock-model".to_string(), Usage::default()),
+            ))
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &ModelConfig,
+            system_prompt: &str,
+            messages: &[Message],
+            tools: &[Tool],
+        ) -> anyhow::Result<(Message, ProviderUsage), ProviderError> {
+            self.complete(system_prompt, messages, tools).await
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new("mock-model").unwrap()
+        }
+
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+    }
+
+    #[derive(Default)]
+    struct Concurrency {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    /// Tools that sleep for `delay_ms` before echoing their label, tracking how many run at once
+    struct DelayClient {
+        concurrency: Arc<Concurrency>,
+    }
+
+    fn tool(name: &str, annotations: Option<ToolAnnotations>) -> Tool {
+        let mut tool = Tool::new(
+            name.to_string(),
+            "Sleeps, then echoes its label",
+            Arc::new(
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "label": {"type": "string"},
+                        "delay_ms": {"type": "integer"}
+                    }
+                })
+                .as_object()
+                .unwrap()
+                .clone(),
+            ),
+        );
+        tool.annotations = annotations;
+        tool
+    }
+
+    #[async_trait]
+    impl McpClientTrait for DelayClient {
+        async fn list_resources(
+            &self,
+            _next_cursor: Option<String>,
+            _cancel_token: CancellationToken,
+        ) -> Result<ListResourcesResult, Error> {
+            Err(Error::TransportClosed)
+        }
+
+        async fn read_resource(
+            &self,
+            _uri: &str,
+            _cancel_token: CancellationToken,
+        ) -> Result<ReadResourceResult, Error> {
+            Err(Error::TransportClosed)
+        }
+
+        async fn list_tools(
+            &self,
+            _next_cursor: Option<String>,
+            _cancel_token: CancellationToken,
+        ) -> Result<ListToolsResult, Error> {
+            Ok(ListToolsResult {
+                tools: vec![
+                    tool(
+                        "sleep",
+                        Some(ToolAnnotations {
+                            read_only_hint: Some(true),
+                            ..Default::default()
+                        }),
+                    ),
+                    tool(
+                        "write",
+                        Some(ToolAnnotations {
+                            destructive_hint: Some(true),
+                            ..Default::default()
+                        }),
+                    ),
+                    tool("run", None),
+                ],
+                next_cursor: None,
+            })
+        }
+
+        async fn call_tool(
+            &self,
+            _name: &str,
+            arguments: Value,
+            _cancel_token: CancellationToken,
+        ) -> Result<CallToolResult, Error> {
+            let concurrency = &self.concurrency;
+            let running = concurrency.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            concurrency
+                .max_in_flight
+                .fetch_max(running, Ordering::SeqCst);
+            let delay = Duration::from_millis(arguments["delay_ms"].as_u64().unwrap());
+            tokio::time::sleep(delay).await;
+            concurrency.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(CallToolResult::success(vec![Content::text(
+                arguments["label"].as_str().unwrap(),
+            )]))
+        }
+
+        async fn list_prompts(
+            &self,
+            _next_cursor: Option<String>,
+            _cancel_token: CancellationToken,
+        ) -> Result<ListPromptsResult, Error> {
+            Err(Error::TransportClosed)
+        }
+
+        async fn get_prompt(
+            &self,
+            _name: &str,
+            _arguments: Value,
+            _cancel_token: CancellationToken,
+        ) -> Result<GetPromptResult, Error> {
+            Err(Error::TransportClosed)
+        }
+
+        async fn subscribe(&self) -> mpsc::Receiver<ServerNotification> {
+            mpsc::channel(1).1
+        }
+
+        fn get_info(&self) -> Option<&InitializeResult> {
+            None
+        }
+    }
+
+    /// Run one turn with the given tool calls, returning the tool response ids and texts, how
+    /// long the turn took, and the most tool calls that were running at once
+    async fn run_tool_calls(
+        calls: Vec<(&'static str, &'static str, u64)>,
+    ) -> Result<(Vec<(String, String)>, Duration, usize)> {
+        let agent = Agent::new();
+        agent
+            .update_provider(Arc::new(ToolCallsProvider { calls }))
+            .await?;
+        let concurrency = Arc::new(Concurrency::default());
+        agent
+            .extension_manager
+            .add_client(
+                "delay".to_string(),
+                ExtensionConfig::Builtin {
+                    name: "delay".to_string(),
+                    display_name: None,
+                    description: None,
+                    timeout: None,
+                    bundled: None,
+                    available_tools: vec![],
+                    depends_on: Vec::new(),
+                    response_limits: None,
+                },
+                Arc::new(DelayClient {
+                    concurrency: concurrency.clone(),
+                }),
+                None,
+                None,
+            )
+            .await;
+
+        let conversation = Conversation::new(vec![Message::user().with_text("Go")]).unwrap();
+        let started = Instant::now();
+        let reply_stream = agent.reply(conversation, None, None).await?;
+        tokio::pin!(reply_stream);
+
+        let mut responses = Vec::new();
+        while let Some(event) = reply_stream.next().await {
+            if let AgentEvent::Message(message) = event? {
+                for content in &message.content {
+                    match content {
+                        MessageContent::ToolConfirmationRequest(req) => {
+                            agent
+                                .handle_confirmation(
+                                    req.id.clone(),
+                                    goose::permission::PermissionConfirmation {
+                                        principal_type: goose::permission::permission_confirmation::PrincipalType::Tool,
+                                        permission: goose::permission::Permission::AllowOnce,
+                                    },
+                                )
+                                .await;
+                        }
+                        MessageContent::ToolResponse(response) => {
+                            let text = response.tool_result.as_ref().unwrap()[0]
+                                .as_text()
+                                .unwrap()
+                                .text
+                                .clone();
+                            responses.push((response.id.clone(), text));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        Ok((
+            responses,
+            started.elapsed(),
+            concurrency.max_in_flight.load(Ordering::SeqCst),
+        ))
+    }
+
+    fn expected(ids: &[&str]) -> Vec<(String, String)> {
+        ids.iter()
+            .map(|id| (id.to_string(), id.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_independent_tool_calls_run_in_parallel() -> Result<()> {
+        let (responses, elapsed, max_in_flight) = run_tool_calls(vec![
+            ("call_1", "delay__sleep", 600),
+            ("call_2", "delay__sleep", 200),
+            ("call_3", "delay__sleep", 400),
+        ])
+        .await?;
+
+        // Responses follow the request order, not the order the calls finished in
+        assert_eq!(responses, expected(&["call_1", "call_2", "call_3"]));
+        assert_eq!(max_in_flight, 3);
+        assert!(
+            elapsed < Duration::from_millis(1200),
+            "calls should overlap, took {:?}",
+            elapsed
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_destructive_tool_calls_run_one_at_a_time() -> Result<()> {
+        let (responses, elapsed, max_in_flight) = run_tool_calls(vec![
+            ("call_1", "delay__write", 150),
+            ("call_2", "delay__write", 150),
+            ("call_3", "delay__write", 150),
+        ])
+        .await?;
+
+        assert_eq!(responses, expected(&["call_1", "call_2", "call_3"]));
+        assert_eq!(max_in_flight, 1);
+        assert!(elapsed >= Duration::from_millis(450));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unannotated_tool_calls_run_in_parallel() -> Result<()> {
+        let (responses, _, max_in_flight) = run_tool_calls(vec![
+            ("call_1", "delay__run", 300),
+            ("call_2", "delay__run", 300),
+            ("call_3", "delay__sleep", 300),
+        ])
+        .await?;
+
+        assert_eq!(responses, expected(&["call_1", "call_2", "call_3"]));
+        assert_eq!(max_in_flight, 3);
+        Ok(())
+    }
+}
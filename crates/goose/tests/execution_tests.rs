@@ -1,8 +1,27 @@
 mod execution_tests {
-    use goose::execution::manager::AgentManager;
+    use async_trait::async_trait;
+    use futures::StreamExt;
+    use goose::agents::subagent_handler::run_complete_subagent_task;
+    use goose::agents::{Agent, TaskConfig};
+    use goose::conversation::message::Message;
+    use goose::conversation::Conversation;
+    use goose::execution::manager::{
+        AgentManager, ScheduleError, SessionLimits, SessionRegistry, SessionRunState,
+        SessionScheduler, SessionState, SubTaskResult, SubTaskStatus,
+    };
     use goose::execution::SessionExecutionMode;
+    use goose::model::ModelConfig;
+    use goose::providers::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
+    use goose::providers::errors::ProviderError;
+    use goose::recipe::Response;
+    use rmcp::model::Tool;
+    use serde_json::json;
     use serial_test::serial;
     use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::oneshot;
+    use tokio::task::JoinHandle;
+    use tokio_util::sync::CancellationToken;
 
     #[test]
     fn test_execution_mode_constructors() {
@@ -318,4 +337,613 @@ mod execution_tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
+
+    type StartOrder = Arc<std::sync::Mutex<Vec<String>>>;
+
+    /// A session that records when it starts and holds its slot until told to finish
+    struct MockSession {
+        finish: oneshot::Sender<()>,
+        handle: JoinHandle<Result<(), ScheduleError>>,
+    }
+
+    impl MockSession {
+        fn spawn(
+            scheduler: &Arc<SessionScheduler>,
+            session_id: &str,
+            mode: SessionExecutionMode,
+            started: &StartOrder,
+        ) -> Self {
+            let (finish, finished) = oneshot::channel();
+            let scheduler = Arc::clone(scheduler);
+            let started = Arc::clone(started);
+            let session_id = session_id.to_string();
+            let handle = tokio::spawn(async move {
+                let _permit = scheduler.acquire(session_id.clone(), mode).await?;
+                started.lock().unwrap().push(session_id);
+                let _ = finished.await;
+                Ok(())
+            });
+            Self { finish, handle }
+        }
+
+        async fn finish(self) -> Result<(), ScheduleError> {
+            let _ = self.finish.send(());
+            self.handle.await.unwrap()
+        }
+    }
+
+    async fn wait_for_state(
+        scheduler: &SessionScheduler,
+        session_id: &str,
+        expected: SessionRunState,
+    ) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while scheduler.state(session_id).as_ref() != Some(&expected) {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("{} never reached {:?}", session_id, expected));
+    }
+
+    fn scheduler(
+        max_background_sessions: usize,
+        max_subtasks_per_parent: usize,
+    ) -> Arc<SessionScheduler> {
+        Arc::new(SessionScheduler::new(SessionLimits {
+            max_background_sessions,
+            max_subtasks_per_parent,
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_queues_background_sessions_in_order() {
+        let scheduler = scheduler(2, 2);
+        let started = StartOrder::default();
+
+        let mut sessions = Vec::new();
+        for (index, id) in ["bg-1", "bg-2", "bg-3", "bg-4", "bg-5"].iter().enumerate() {
+            sessions.push(MockSession::spawn(
+                &scheduler,
+                id,
+                SessionExecutionMode::Background,
+                &started,
+            ));
+            let expected = match index {
+                0 | 1 => SessionRunState::Running,
+                n => SessionRunState::Queued { position: n - 2 },
+            };
+            wait_for_state(&scheduler, id, expected).await;
+        }
+
+        // Interactive sessions are never queued
+        let chat = MockSession::spawn(
+            &scheduler,
+            "chat",
+            SessionExecutionMode::Interactive,
+            &started,
+        );
+        wait_for_state(&scheduler, "chat", SessionRunState::Running).await;
+        assert_eq!(scheduler.running_count(), 3);
+        assert_eq!(scheduler.queued_count(), 3);
+
+        let mut sessions = sessions.into_iter();
+        sessions.next().unwrap().finish().await.unwrap();
+        wait_for_state(&scheduler, "bg-3", SessionRunState::Running).await;
+        wait_for_state(&scheduler, "bg-4", SessionRunState::Queued { position: 0 }).await;
+
+        for session in sessions {
+            session.finish().await.unwrap();
+        }
+        chat.finish().await.unwrap();
+
+        assert_eq!(
+            *started.lock().unwrap(),
+            vec!["bg-1", "bg-2", "chat", "bg-3", "bg-4", "bg-5"]
+        );
+        assert_eq!(scheduler.running_count(), 0);
+        assert_eq!(scheduler.queued_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_limits_subtasks_per_parent() {
+        let scheduler = scheduler(1, 1);
+        let started = StartOrder::default();
+
+        let a1 = MockSession::spawn(
+            &scheduler,
+            "a-1",
+            SessionExecutionMode::task("a".into()),
+            &started,
+        );
+        wait_for_state(&scheduler, "a-1", SessionRunState::Running).await;
+        let a2 = MockSession::spawn(
+            &scheduler,
+            "a-2",
+            SessionExecutionMode::task("a".into()),
+            &started,
+        );
+        wait_for_state(&scheduler, "a-2", SessionRunState::Queued { position: 0 }).await;
+
+        // Another parent's subtasks and background sessions draw from separate budgets
+        let b1 = MockSession::spawn(
+            &scheduler,
+            "b-1",
+            SessionExecutionMode::task("b".into()),
+            &started,
+        );
+        wait_for_state(&scheduler, "b-1", SessionRunState::Running).await;
+        let bg = MockSession::spawn(&scheduler, "bg", SessionExecutionMode::Background, &started);
+        wait_for_state(&scheduler, "bg", SessionRunState::Running).await;
+
+        assert_eq!(
+            scheduler
+                .acquire("a-1", SessionExecutionMode::task("a".into()))
+                .await
+                .unwrap_err(),
+            ScheduleError::AlreadyScheduled("a-1".into())
+        );
+
+        a1.finish().await.unwrap();
+        wait_for_state(&scheduler, "a-2", SessionRunState::Running).await;
+        for session in [a2, b1, bg] {
+            session.finish().await.unwrap();
+        }
+        assert_eq!(*started.lock().unwrap(), vec!["a-1", "b-1", "bg", "a-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_cancelling_parent_cancels_queued_subtasks() {
+        let scheduler = scheduler(1, 1);
+        let started = StartOrder::default();
+        let subtask = || SessionExecutionMode::task("parent".into());
+
+        let running = MockSession::spawn(&scheduler, "sub-1", subtask(), &started);
+        wait_for_state(&scheduler, "sub-1", SessionRunState::Running).await;
+        let queued: Vec<_> = ["sub-2", "sub-3"]
+            .iter()
+            .map(|id| MockSession::spawn(&scheduler, id, subtask(), &started))
+            .collect();
+        wait_for_state(&scheduler, "sub-3", SessionRunState::Queued { position: 1 }).await;
+        let other = MockSession::spawn(
+            &scheduler,
+            "other",
+            SessionExecutionMode::task("x".into()),
+            &started,
+        );
+        wait_for_state(&scheduler, "other", SessionRunState::Running).await;
+
+        assert_eq!(scheduler.cancel("parent"), 2);
+        for (session, id) in queued.into_iter().zip(["sub-2", "sub-3"]) {
+            assert_eq!(
+                session.finish().await.unwrap_err(),
+                ScheduleError::Cancelled(id.into())
+            );
+        }
+        assert_eq!(
+            scheduler.acquire("sub-4", subtask()).await.unwrap_err(),
+            ScheduleError::Cancelled("sub-4".into())
+        );
+
+        // Running subtasks keep their slot until they finish
+        assert_eq!(scheduler.state("sub-1"), Some(SessionRunState::Running));
+        running.finish().await.unwrap();
+        other.finish().await.unwrap();
+        assert_eq!(scheduler.running_count(), 0);
+        assert_eq!(*started.lock().unwrap(), vec!["sub-1", "other"]);
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_queued_session_does_not_hold_slot() {
+        let scheduler = scheduler(1, 1);
+        let started = StartOrder::default();
+
+        let first = MockSession::spawn(
+            &scheduler,
+            "bg-1",
+            SessionExecutionMode::Background,
+            &started,
+        );
+        wait_for_state(&scheduler, "bg-1", SessionRunState::Running).await;
+        let abandoned = MockSession::spawn(
+            &scheduler,
+            "bg-2",
+            SessionExecutionMode::Background,
+            &started,
+        );
+        wait_for_state(&scheduler, "bg-2", SessionRunState::Queued { position: 0 }).await;
+        abandoned.handle.abort();
+        let _ = abandoned.handle.await;
+
+        first.finish().await.unwrap();
+        let next = MockSession::spawn(
+            &scheduler,
+            "bg-3",
+            SessionExecutionMode::Background,
+            &started,
+        );
+        wait_for_state(&scheduler, "bg-3", SessionRunState::Running).await;
+        next.finish().await.unwrap();
+    }
+
+    fn subtask_result(session_id: &str, status: SubTaskStatus) -> SubTaskResult {
+        SubTaskResult {
+            session_id: session_id.to_string(),
+            status,
+            summary: format!("{} finished", session_id),
+            resource_uris: vec![format!("file:///tmp/{}.md", session_id)],
+            usage: Usage::new(Some(100), Some(20), Some(120)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subtask_results_reach_parent_in_completion_order() {
+        let manager = AgentManager::new(None).await.unwrap();
+        let results = manager.subtask_results();
+        let parent = "orchestrator".to_string();
+
+        let mut subtasks = Vec::new();
+        for (session_id, delay_ms, status) in [
+            ("subtask-slow", 150, SubTaskStatus::Completed),
+            ("subtask-fast", 10, SubTaskStatus::Failed),
+        ] {
+            let agent = manager
+                .get_or_create_agent(
+                    session_id.to_string(),
+                    SessionExecutionMode::task(parent.clone()),
+                )
+                .await
+                .unwrap();
+            let results = Arc::clone(&results);
+            let parent = parent.clone();
+            subtasks.push(tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                results.publish(&parent, subtask_result(session_id, status));
+                drop(agent);
+            }));
+        }
+        for subtask in subtasks {
+            subtask.await.unwrap();
+        }
+
+        // Results outlive the subtask agents
+        manager.remove_session("subtask-slow").await.unwrap();
+        manager.remove_session("subtask-fast").await.unwrap();
+
+        let collected = results.collect(&parent, 2, Duration::from_secs(5)).await;
+        let order: Vec<_> = collected
+            .iter()
+            .map(|r| (r.session_id.as_str(), r.status))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("subtask-fast", SubTaskStatus::Failed),
+                ("subtask-slow", SubTaskStatus::Completed)
+            ]
+        );
+        assert_eq!(
+            collected[0].resource_uris,
+            vec!["file:///tmp/subtask-fast.md"]
+        );
+        assert_eq!(collected[1].usage.total_tokens, Some(120));
+        assert!(results.poll(&parent).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subtask_results_poll_and_timeout() {
+        let manager = AgentManager::new(None).await.unwrap();
+        let results = manager.subtask_results();
+
+        assert!(results
+            .next("parent", Duration::from_millis(20))
+            .await
+            .is_none());
+
+        results.publish("parent", subtask_result("one", SubTaskStatus::Completed));
+        results.publish("other", subtask_result("two", SubTaskStatus::Completed));
+
+        // Only the parent's own results are returned, and a short collect gives up
+        let collected = results
+            .collect("parent", 2, Duration::from_millis(50))
+            .await;
+        assert_eq!(collected.len(), 1);
+        assert_eq!(collected[0].session_id, "one");
+
+        results.remove_parent("other");
+        assert!(results.poll("other").await.is_empty());
+    }
+
+    #[test]
+    fn test_cancelling_parent_cascades_to_subtasks() {
+        let registry = SessionRegistry::default();
+        let parent = registry.register("parent".into(), SessionExecutionMode::Background);
+        let child = registry.register("child".into(), SessionExecutionMode::task("parent".into()));
+        let grandchild = registry.register(
+            "grandchild".into(),
+            SessionExecutionMode::task("child".into()),
+        );
+        let other = registry.register("other".into(), SessionExecutionMode::chat());
+
+        let background = registry.list_sessions(Some(&SessionExecutionMode::Background), None);
+        assert_eq!(background.len(), 1);
+        assert_eq!(background[0].session_id, "parent");
+        assert_eq!(
+            registry.lookup("child").unwrap().parent.as_deref(),
+            Some("parent")
+        );
+
+        assert_eq!(
+            registry.cancel("parent").unwrap(),
+            vec!["parent", "child", "grandchild"]
+        );
+        assert!(parent.is_cancelled());
+        assert!(child.is_cancelled());
+        assert!(grandchild.is_cancelled());
+        assert!(!other.is_cancelled());
+        assert_eq!(
+            registry
+                .list_sessions(None, Some(SessionState::Cancelling))
+                .len(),
+            3
+        );
+        assert_eq!(
+            registry.lookup("other").unwrap().state,
+            SessionState::Running
+        );
+
+        // Subtasks spawned after the parent was cancelled never get to run
+        let late = registry.register("late".into(), SessionExecutionMode::task("parent".into()));
+        assert!(late.is_cancelled());
+
+        assert!(registry.mark_cancelled("child"));
+        assert!(!registry.mark_cancelled("other"));
+        assert_eq!(
+            registry.lookup("child").unwrap().state,
+            SessionState::Cancelled
+        );
+
+        for id in ["grandchild", "child", "late", "parent"] {
+            assert!(registry.unregister(id).is_some());
+        }
+        assert_eq!(registry.len(), 1);
+        assert!(registry.cancel("parent").is_err());
+        registry.unregister("other");
+        assert!(registry.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_agent_lifecycle_updates_registry() {
+        let manager = AgentManager::new(None).await.unwrap();
+        let registry = manager.session_registry();
+
+        manager
+            .get_or_create_agent("job".into(), SessionExecutionMode::scheduled())
+            .await
+            .unwrap();
+        manager
+            .get_or_create_agent("job-step".into(), SessionExecutionMode::task("job".into()))
+            .await
+            .unwrap();
+        assert_eq!(registry.len(), 2);
+
+        assert_eq!(
+            manager.cancel_session("job").unwrap(),
+            vec!["job", "job-step"]
+        );
+        let step = registry.lookup("job-step").unwrap();
+        assert!(step.cancellation_token.is_cancelled());
+        assert_eq!(step.state, SessionState::Cancelling);
+
+        manager.remove_session("job-step").await.unwrap();
+        manager.remove_session("job").await.unwrap();
+        assert!(registry.is_empty());
+        assert!(manager.cancel_session("job").is_err());
+    }
+
+    /// Answers every turn without calling the final output tool, so the agent keeps going
+    /// until it is stopped
+    #[derive(Clone)]
+    struct EndlessProvider {
+        model_config: ModelConfig,
+    }
+
+    #[async_trait]
+    impl Provider for EndlessProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            self.model_config.clone()
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok((
+                Message::assistant().with_text("Still working"),
+                ProviderUsage::new("mock".to_string(), Usage::default()),
+            ))
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &ModelConfig,
+            system: &str,
+            messages: &[Message],
+            tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            self.complete(system, messages, tools).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_session_stops_a_live_agent() {
+        let manager = AgentManager::new(None).await.unwrap();
+        let registry = manager.session_registry();
+        let agent = manager
+            .get_or_create_agent("live".into(), SessionExecutionMode::Background)
+            .await
+            .unwrap();
+        agent
+            .update_provider(Arc::new(EndlessProvider {
+                model_config: ModelConfig::new("test-model").unwrap(),
+            }))
+            .await
+            .unwrap();
+        agent
+            .add_final_output_tool(Response {
+                json_schema: Some(json!({"type": "object"})),
+            })
+            .await;
+
+        // The caller's own token is never cancelled; the session's is what stops the reply
+        let caller = CancellationToken::new();
+        let mut stream = agent
+            .reply(Conversation::empty(), None, Some(caller.clone()))
+            .await
+            .unwrap();
+        for _ in 0..3 {
+            stream.next().await.unwrap().unwrap();
+        }
+        assert_eq!(
+            registry.lookup("live").unwrap().state,
+            SessionState::Running
+        );
+
+        manager.cancel_session("live").unwrap();
+        let drained = tokio::time::timeout(Duration::from_secs(5), async {
+            while stream.next().await.is_some() {}
+        })
+        .await;
+        assert!(drained.is_ok(), "the reply kept running after cancellation");
+        drop(stream);
+        assert!(!caller.is_cancelled());
+        assert_eq!(
+            registry.lookup("live").unwrap().state,
+            SessionState::Cancelled
+        );
+
+        // Using the session again starts it over
+        manager
+            .get_or_create_agent("live".into(), SessionExecutionMode::Background)
+            .await
+            .unwrap();
+        let handle = registry.lookup("live").unwrap();
+        assert_eq!(handle.state, SessionState::Running);
+        assert!(!handle.cancellation_token.is_cancelled());
+
+        manager.remove_session("live").await.unwrap();
+        assert!(registry.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subagents_wait_for_a_slot_under_their_parent() {
+        let scheduler = SessionScheduler::global();
+        let parent = "subagent-parent".to_string();
+        let mut permits = Vec::new();
+        for index in 0..scheduler.limits().max_subtasks_per_parent {
+            permits.push(
+                scheduler
+                    .acquire(
+                        format!("sibling-{}", index),
+                        SessionExecutionMode::task(parent.clone()),
+                    )
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        let mut task_config = TaskConfig::new(None).with_parent_session(Some(parent.clone()));
+        task_config.extensions = Some(Vec::new());
+        let task_id = task_config.id.clone();
+        let subagent = tokio::spawn(run_complete_subagent_task(
+            "Summarize the repository".to_string(),
+            task_config,
+        ));
+        wait_for_state(
+            &scheduler,
+            &task_id,
+            SessionRunState::Queued { position: 0 },
+        )
+        .await;
+
+        permits.pop();
+        // Without a provider the subagent fails as soon as it starts, giving its slot back
+        let result = tokio::time::timeout(Duration::from_secs(5), subagent)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(result.is_err());
+        assert_eq!(scheduler.state(&task_id), None);
+    }
+
+    #[tokio::test]
+    async fn test_subagent_results_reach_the_parent_agent() {
+        let parent = "results-parent".to_string();
+        let parent_agent = Agent::new();
+
+        // Without a provider the subagent fails once it starts
+        let mut failing = TaskConfig::new(None).with_parent_session(Some(parent.clone()));
+        failing.extensions = Some(Vec::new());
+        let failing_id = failing.id.clone();
+        assert!(run_complete_subagent_task("Fail".to_string(), failing)
+            .await
+            .is_err());
+
+        // A subagent stopped before it finishes, here while waiting for a slot, still reports
+        let scheduler = SessionScheduler::global();
+        let mut permits = Vec::new();
+        for index in 0..scheduler.limits().max_subtasks_per_parent {
+            permits.push(
+                scheduler
+                    .acquire(
+                        format!("results-sibling-{}", index),
+                        SessionExecutionMode::task(parent.clone()),
+                    )
+                    .await
+                    .unwrap(),
+            );
+        }
+        let stopped = TaskConfig::new(None).with_parent_session(Some(parent.clone()));
+        let stopped_id = stopped.id.clone();
+        let stopping = tokio::spawn(run_complete_subagent_task("Stop".to_string(), stopped));
+        wait_for_state(
+            &scheduler,
+            &stopped_id,
+            SessionRunState::Queued { position: 0 },
+        )
+        .await;
+        stopping.abort();
+        assert!(stopping.await.unwrap_err().is_cancelled());
+        drop(permits);
+
+        // One started after its parent was cancelled never runs
+        scheduler.cancel(&parent);
+        let refused = TaskConfig::new(None).with_parent_session(Some(parent.clone()));
+        let refused_id = refused.id.clone();
+        assert!(run_complete_subagent_task("Refuse".to_string(), refused)
+            .await
+            .is_err());
+
+        let results = parent_agent.take_subtask_results(&parent).await;
+        let results: Vec<(String, SubTaskStatus)> = results
+            .into_iter()
+            .map(|result| (result.session_id, result.status))
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                (failing_id, SubTaskStatus::Failed),
+                (stopped_id, SubTaskStatus::Cancelled),
+                (refused_id, SubTaskStatus::Cancelled),
+            ]
+        );
+        assert!(parent_agent.take_subtask_results(&parent).await.is_empty());
+    }
 }
@@ -152,6 +152,8 @@ async fn test_replayed_session(
         timeout: Some(30),
         bundled: Some(false),
         available_tools: vec![],
+        depends_on: Vec::new(),
+        response_limits: None,
     };
 
     let extension_manager = ExtensionManager::new();
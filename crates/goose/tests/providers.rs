@@ -282,8 +282,10 @@ impl ProviderTester {
         .no_annotation();
 
         // Test 1: Direct image message
-        let message_with_image =
-            Message::user().with_image(image_content.data.clone(), image_content.mime_type.clone());
+        let message_with_image = Message::user().with_content(MessageContent::image(
+            image_content.data.clone(),
+            image_content.mime_type.clone(),
+        ));
 
         let result = self
             .provider
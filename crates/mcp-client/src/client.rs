@@ -1,3 +1,5 @@
+use crate::keep_alive::{Activity, KeepAlive};
+use crate::limits::ResponseLimits;
 use rmcp::{
     model::{
         CallToolRequest, CallToolRequestParam, CallToolResult, CancelledNotification,
@@ -5,7 +7,7 @@ use rmcp::{
         ClientRequest, GetPromptRequest, GetPromptRequestParam, GetPromptResult, Implementation,
         InitializeResult, ListPromptsRequest, ListPromptsResult, ListResourcesRequest,
         ListResourcesResult, ListToolsRequest, ListToolsResult, LoggingMessageNotification,
-        LoggingMessageNotificationMethod, PaginatedRequestParam, ProgressNotification,
+        LoggingMessageNotificationMethod, PaginatedRequestParam, PingRequest, ProgressNotification,
         ProgressNotificationMethod, ProtocolVersion, ReadResourceRequest, ReadResourceRequestParam,
         ReadResourceResult, RequestId, ServerNotification, ServerResult,
     },
@@ -70,6 +72,15 @@ pub trait McpClientTrait: Send + Sync {
     async fn subscribe(&self) -> mpsc::Receiver<ServerNotification>;
 
     fn get_info(&self) -> Option<&InitializeResult>;
+
+    /// Whether keep-alive pings have been failing, so the connection has likely dropped
+    fn is_degraded(&self) -> bool {
+        false
+    }
+
+    /// Close the connection, waiting up to `timeout` for the transport to shut down. MCP has
+    /// no shutdown request; servers are expected to exit once their transport closes.
+    async fn shutdown(&self, _timeout: Duration) {}
 }
 
 pub struct GooseClient {
@@ -143,6 +154,10 @@ pub struct McpClient {
     notification_subscribers: Arc<Mutex<Vec<mpsc::Sender<ServerNotification>>>>,
     server_info: Option<InitializeResult>,
     timeout: std::time::Duration,
+    limits: ResponseLimits,
+    activity: Arc<Activity>,
+    /// Stops the keep-alive task, if one was started
+    keep_alive: CancellationToken,
 }
 
 impl McpClient {
@@ -167,14 +182,38 @@ impl McpClient {
             notification_subscribers,
             server_info,
             timeout,
+            limits: ResponseLimits::default(),
+            activity: Arc::new(Activity::default()),
+            keep_alive: CancellationToken::new(),
         })
     }
 
+    /// Cap the size of tool results and resources read through this client
+    pub fn with_limits(mut self, limits: ResponseLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Ping the server whenever the connection has been idle for `keep_alive.interval`. Meant
+    /// for network transports, whose idle connections may be dropped along the way.
+    pub fn with_keep_alive(mut self, keep_alive: KeepAlive) -> Self {
+        let peer = self.client.get_mut().peer().clone();
+        tokio::spawn(keep_alive_loop(
+            peer,
+            Arc::clone(&self.activity),
+            keep_alive,
+            self.timeout,
+            self.keep_alive.clone(),
+        ));
+        self
+    }
+
     async fn send_request(
         &self,
         request: ClientRequest,
         cancel_token: CancellationToken,
     ) -> Result<ServerResult, Error> {
+        let _request = self.activity.start_request();
         let handle = self
             .client
             .lock()
@@ -182,7 +221,64 @@ impl McpClient {
             .send_cancellable_request(request, PeerRequestOptions::no_options())
             .await?;
 
-        await_response(handle, self.timeout, &cancel_token).await
+        let result = await_response(handle, self.timeout, &cancel_token).await;
+        if result.is_ok() {
+            self.activity.record_success();
+        }
+        result
+    }
+}
+
+impl Drop for McpClient {
+    fn drop(&mut self) {
+        self.keep_alive.cancel();
+    }
+}
+
+async fn keep_alive_loop(
+    peer: Peer<RoleClient>,
+    activity: Arc<Activity>,
+    keep_alive: KeepAlive,
+    timeout: Duration,
+    stop: CancellationToken,
+) {
+    loop {
+        tokio::select! {
+            _ = stop.cancelled() => return,
+            _ = tokio::time::sleep(keep_alive.interval) => {}
+        }
+        if !activity.is_idle_for(keep_alive.interval) {
+            continue;
+        }
+
+        let ping = ClientRequest::PingRequest(PingRequest {
+            method: Default::default(),
+            extensions: Default::default(),
+        });
+        let result = match peer
+            .send_cancellable_request(ping, PeerRequestOptions::no_options())
+            .await
+        {
+            Ok(handle) => await_response(handle, timeout, &stop).await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+        if stop.is_cancelled() {
+            return;
+        }
+
+        match result {
+            Ok(()) => activity.record_success(),
+            Err(e) => {
+                if activity.record_failure(keep_alive.max_failures) {
+                    tracing::warn!(
+                        error = %e,
+                        "MCP keep-alive pings keep failing, marking the connection degraded"
+                    );
+                } else {
+                    tracing::debug!(error = %e, "MCP keep-alive ping failed");
+                }
+            }
+        }
     }
 }
 
@@ -231,6 +327,10 @@ impl McpClientTrait for McpClient {
         self.server_info.as_ref()
     }
 
+    fn is_degraded(&self) -> bool {
+        self.activity.is_degraded()
+    }
+
     async fn list_resources(
         &self,
         cursor: Option<String>,
@@ -272,7 +372,9 @@ impl McpClientTrait for McpClient {
             .await?;
 
         match res {
-            ServerResult::ReadResourceResult(result) => Ok(result),
+            ServerResult::ReadResourceResult(result) => {
+                Ok(self.limits.limit_read_resource_result(uri, result))
+            }
             _ => Err(ServiceError::UnexpectedResponse),
         }
     }
@@ -324,7 +426,9 @@ impl McpClientTrait for McpClient {
             .await?;
 
         match res {
-            ServerResult::CallToolResult(result) => Ok(result),
+            ServerResult::CallToolResult(result) => {
+                Ok(self.limits.limit_call_tool_result(name, result))
+            }
             _ => Err(ServiceError::UnexpectedResponse),
         }
     }
@@ -386,4 +490,244 @@ impl McpClientTrait for McpClient {
         self.notification_subscribers.lock().await.push(tx);
         rx
     }
+
+    async fn shutdown(&self, timeout: Duration) {
+        self.keep_alive.cancel();
+        let client = self.client.lock().await;
+        client.cancellation_token().cancel();
+
+        // Cancelling closes the transport, which for a child process closes its stdin and
+        // waits for it to exit
+        let deadline = tokio::time::Instant::now() + timeout;
+        while !client.is_transport_closed() && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::RawContent;
+    use serde_json::json;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    /// Answers requests over an in-memory transport, calling `respond` for everything but
+    /// initialize and notifications
+    async fn mock_client(respond: fn(&str) -> Value, limits: ResponseLimits) -> McpClient {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            let (read, mut write) = tokio::io::split(server_io);
+            let mut lines = BufReader::new(read).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let request: Value = serde_json::from_str(&line).unwrap();
+                let (Some(id), Some(method)) = (request.get("id"), request["method"].as_str())
+                else {
+                    continue;
+                };
+                let result = match method {
+                    "initialize" => json!({
+                        "protocolVersion": "2025-03-26",
+                        "capabilities": {"tools": {}, "resources": {}},
+                        "serverInfo": {"name": "mock", "version": "0"}
+                    }),
+                    _ => respond(request["params"]["name"].as_str().unwrap_or(method)),
+                };
+                let response = json!({"jsonrpc": "2.0", "id": id, "result": result});
+                let mut message = serde_json::to_vec(&response).unwrap();
+                message.push(b'\n');
+                if write.write_all(&message).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        McpClient::connect(client_io, Duration::from_secs(5))
+            .await
+            .unwrap()
+            .with_limits(limits)
+    }
+
+    fn text_of(content: &RawContent) -> &str {
+        match content {
+            RawContent::Text(text) => &text.text,
+            other => panic!("expected text, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oversized_responses_are_truncated() {
+        let limits = ResponseLimits {
+            max_result_bytes: 1000,
+            max_content_items: 10,
+        };
+        let client = mock_client(
+            |name| match name {
+                "small" => json!({"content": [{"type": "text", "text": "hello"}]}),
+                "resources/read" => json!({"contents": [
+                    {"uri": "file:///dump.bin", "blob": "B".repeat(4000)}
+                ]}),
+                _ => json!({"content": [
+                    {"type": "text", "text": "x".repeat(5000)},
+                    {"type": "image", "data": "A".repeat(4000), "mimeType": "image/png"}
+                ]}),
+            },
+            limits,
+        )
+        .await;
+
+        let result = client
+            .call_tool("big", json!({}), CancellationToken::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            text_of(&result.content[0].raw),
+            format!(
+                "{}\n[truncated 4000 bytes, full content unavailable]",
+                "x".repeat(1000)
+            )
+        );
+        assert!(text_of(&result.content[1].raw).contains("4000 bytes omitted"));
+
+        let result = client
+            .call_tool("small", json!({}), CancellationToken::default())
+            .await
+            .unwrap();
+        assert_eq!(result.content.len(), 1);
+        assert_eq!(text_of(&result.content[0].raw), "hello");
+
+        let result = client
+            .read_resource("file:///dump.bin", CancellationToken::default())
+            .await
+            .unwrap();
+        match &result.contents[0] {
+            rmcp::model::ResourceContents::TextResourceContents { text, .. } => {
+                assert!(text.contains("4000 bytes omitted"))
+            }
+            other => panic!("expected a text stub, got {:?}", other),
+        }
+    }
+
+    #[derive(Default)]
+    struct PingCounter {
+        pings: std::sync::atomic::AtomicUsize,
+        fail: std::sync::atomic::AtomicBool,
+    }
+
+    impl PingCounter {
+        fn pings(&self) -> usize {
+            self.pings.load(std::sync::atomic::Ordering::SeqCst)
+        }
+
+        fn set_failing(&self, fail: bool) {
+            self.fail.store(fail, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    /// A mock server counting pings, answering them with an error while `fail` is set. The
+    /// `slow` tool takes 400ms to answer.
+    async fn ping_client(keep_alive: KeepAlive) -> (McpClient, Arc<PingCounter>) {
+        let counter = Arc::new(PingCounter::default());
+        let server_counter = Arc::clone(&counter);
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            let (read, mut write) = tokio::io::split(server_io);
+            let mut lines = BufReader::new(read).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let request: Value = serde_json::from_str(&line).unwrap();
+                let (Some(id), Some(method)) = (request.get("id"), request["method"].as_str())
+                else {
+                    continue;
+                };
+                let response = match method {
+                    "initialize" => json!({"jsonrpc": "2.0", "id": id, "result": {
+                        "protocolVersion": "2025-03-26",
+                        "capabilities": {"tools": {}},
+                        "serverInfo": {"name": "mock", "version": "0"}
+                    }}),
+                    "ping" => {
+                        server_counter
+                            .pings
+                            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        if server_counter
+                            .fail
+                            .load(std::sync::atomic::Ordering::SeqCst)
+                        {
+                            json!({"jsonrpc": "2.0", "id": id, "error": {
+                                "code": -32603, "message": "upstream unavailable"
+                            }})
+                        } else {
+                            json!({"jsonrpc": "2.0", "id": id, "result": {}})
+                        }
+                    }
+                    _ => {
+                        tokio::time::sleep(Duration::from_millis(400)).await;
+                        json!({"jsonrpc": "2.0", "id": id, "result": {
+                            "content": [{"type": "text", "text": "done"}]
+                        }})
+                    }
+                };
+                let mut message = serde_json::to_vec(&response).unwrap();
+                message.push(b'\n');
+                if write.write_all(&message).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let client = McpClient::connect(client_io, Duration::from_secs(5))
+            .await
+            .unwrap()
+            .with_keep_alive(keep_alive);
+        (client, counter)
+    }
+
+    #[tokio::test]
+    async fn test_keep_alive_pings_while_idle_and_stops_on_shutdown() {
+        let (client, counter) = ping_client(KeepAlive::new(Duration::from_millis(50))).await;
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(counter.pings() >= 3, "only {} pings", counter.pings());
+        assert!(!client.is_degraded());
+
+        client.shutdown(Duration::from_millis(200)).await;
+        let after_shutdown = counter.pings();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(counter.pings(), after_shutdown);
+    }
+
+    #[tokio::test]
+    async fn test_keep_alive_pauses_during_requests() {
+        let (client, counter) = ping_client(KeepAlive::new(Duration::from_millis(50))).await;
+        tokio::time::sleep(Duration::from_millis(120)).await;
+
+        let before = counter.pings();
+        let result = client
+            .call_tool("slow", json!({}), CancellationToken::default())
+            .await
+            .unwrap();
+        assert_eq!(text_of(&result.content[0].raw), "done");
+        // The server answers in order, so any ping sent during the call has been counted
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        // A ping already on its way when the call started may still land
+        assert!(counter.pings() <= before + 1);
+    }
+
+    #[tokio::test]
+    async fn test_failing_pings_mark_client_degraded() {
+        let (client, counter) = ping_client(KeepAlive {
+            interval: Duration::from_millis(30),
+            max_failures: 2,
+        })
+        .await;
+        counter.set_failing(true);
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert!(counter.pings() >= 2);
+        assert!(client.is_degraded());
+
+        counter.set_failing(false);
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(!client.is_degraded());
+    }
 }
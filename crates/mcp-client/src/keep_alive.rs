@@ -0,0 +1,95 @@
+//! Keep-alive pings for network transports, so that idle connections are not silently dropped
+//! by load balancers and proxies between goose and a remote extension
+//!
+//! Pings are only sent once the connection has been idle for a full interval, never while a
+//! request is in flight. After enough consecutive failures the client is marked degraded, and
+//! the next successful ping or request clears it again.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_MAX_FAILURES: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepAlive {
+    /// Idle time before a ping is sent, and the time between pings while idle
+    pub interval: Duration,
+    /// Consecutive failed pings after which the client is marked degraded
+    pub max_failures: u32,
+}
+
+impl KeepAlive {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            max_failures: DEFAULT_MAX_FAILURES,
+        }
+    }
+}
+
+/// Request activity of a client, shared with its keep-alive task
+#[derive(Debug)]
+pub(crate) struct Activity {
+    in_flight: AtomicUsize,
+    last_activity: Mutex<Instant>,
+    failures: AtomicU32,
+    degraded: AtomicBool,
+}
+
+impl Default for Activity {
+    fn default() -> Self {
+        Self {
+            in_flight: AtomicUsize::new(0),
+            last_activity: Mutex::new(Instant::now()),
+            failures: AtomicU32::new(0),
+            degraded: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Activity {
+    /// Track a request until the returned guard is dropped
+    pub(crate) fn start_request(&self) -> RequestGuard<'_> {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        RequestGuard { activity: self }
+    }
+
+    /// Whether the connection has been idle for at least `interval`
+    pub(crate) fn is_idle_for(&self, interval: Duration) -> bool {
+        self.in_flight.load(Ordering::SeqCst) == 0
+            && self.last_activity.lock().unwrap().elapsed() >= interval
+    }
+
+    pub(crate) fn record_success(&self) {
+        self.failures.store(0, Ordering::SeqCst);
+        if self.degraded.swap(false, Ordering::SeqCst) {
+            tracing::info!("MCP connection recovered");
+        }
+    }
+
+    /// Count a failed ping, returning whether this one marked the client degraded
+    pub(crate) fn record_failure(&self, max_failures: u32) -> bool {
+        let failures = self.failures.fetch_add(1, Ordering::SeqCst) + 1;
+        failures >= max_failures && !self.degraded.swap(true, Ordering::SeqCst)
+    }
+
+    pub(crate) fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::SeqCst)
+    }
+
+    fn touch(&self) {
+        *self.last_activity.lock().unwrap() = Instant::now();
+    }
+}
+
+pub(crate) struct RequestGuard<'a> {
+    activity: &'a Activity,
+}
+
+impl Drop for RequestGuard<'_> {
+    fn drop(&mut self) {
+        self.activity.touch();
+        self.activity.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
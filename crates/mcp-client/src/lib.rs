@@ -1,3 +1,7 @@
 pub mod client;
+pub mod keep_alive;
+pub mod limits;
 
 pub use client::{Error, McpClient, McpClientTrait};
+pub use keep_alive::KeepAlive;
+pub use limits::ResponseLimits;
@@ -0,0 +1,333 @@
+//! Caps on the size of tool results and resources, so that a misbehaving extension cannot
+//! flood the conversation with more than a provider or the UI can take
+//!
+//! Text past the byte budget is cut with a marker saying how much was dropped, and binary
+//! content that does not fit is replaced by a short description of what was there.
+
+use rmcp::model::{CallToolResult, Content, RawContent, ReadResourceResult, ResourceContents};
+
+pub const DEFAULT_MAX_RESULT_BYTES: usize = 1_000_000;
+pub const DEFAULT_MAX_CONTENT_ITEMS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseLimits {
+    /// Total bytes of text and binary data kept from a single response
+    pub max_result_bytes: usize,
+    /// Content items kept from a single response
+    pub max_content_items: usize,
+}
+
+impl Default for ResponseLimits {
+    fn default() -> Self {
+        Self {
+            max_result_bytes: DEFAULT_MAX_RESULT_BYTES,
+            max_content_items: DEFAULT_MAX_CONTENT_ITEMS,
+        }
+    }
+}
+
+impl ResponseLimits {
+    pub fn limit_call_tool_result(&self, tool: &str, mut result: CallToolResult) -> CallToolResult {
+        let mut budget = Budget::new(self.max_result_bytes);
+        let omitted = result.content.len().saturating_sub(self.max_content_items);
+        result.content.truncate(self.max_content_items);
+
+        for content in &mut result.content {
+            budget.limit_content(content);
+        }
+        if omitted > 0 {
+            result
+                .content
+                .push(Content::text(omitted_items_marker(omitted)));
+        }
+
+        // Structured content usually repeats the text, so it is held to the limit on its own
+        let structured_len = result
+            .structured_content
+            .as_ref()
+            .map_or(0, |value| value.to_string().len());
+        if structured_len > self.max_result_bytes {
+            result.structured_content = None;
+            budget.truncated += structured_len;
+        }
+
+        if budget.truncated > 0 || omitted > 0 {
+            tracing::warn!(
+                tool,
+                truncated_bytes = budget.truncated,
+                omitted_items = omitted,
+                "Tool result exceeded the response limits and was truncated"
+            );
+        }
+        result
+    }
+
+    pub fn limit_read_resource_result(
+        &self,
+        uri: &str,
+        mut result: ReadResourceResult,
+    ) -> ReadResourceResult {
+        let mut budget = Budget::new(self.max_result_bytes);
+        let omitted = result.contents.len().saturating_sub(self.max_content_items);
+        result.contents.truncate(self.max_content_items);
+
+        for contents in &mut result.contents {
+            budget.limit_resource(contents);
+        }
+        if omitted > 0 {
+            result.contents.push(ResourceContents::text(
+                omitted_items_marker(omitted),
+                uri.to_string(),
+            ));
+        }
+
+        if budget.truncated > 0 || omitted > 0 {
+            tracing::warn!(
+                uri,
+                truncated_bytes = budget.truncated,
+                omitted_items = omitted,
+                "Resource exceeded the response limits and was truncated"
+            );
+        }
+        result
+    }
+}
+
+fn omitted_items_marker(omitted: usize) -> String {
+    format!(
+        "[omitted {} more content items, full content unavailable]",
+        omitted
+    )
+}
+
+/// Bytes left to hand out across the contents of one response
+struct Budget {
+    limit: usize,
+    remaining: usize,
+    truncated: usize,
+}
+
+impl Budget {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            remaining: limit,
+            truncated: 0,
+        }
+    }
+
+    fn limit_content(&mut self, content: &mut Content) {
+        let stub = match &mut content.raw {
+            RawContent::Text(text) => {
+                self.limit_text(&mut text.text);
+                None
+            }
+            RawContent::Image(image) => self.limit_blob(&image.data, "image", &image.mime_type),
+            RawContent::Audio(audio) => {
+                self.limit_blob(&audio.raw.data, "audio", &audio.raw.mime_type)
+            }
+            RawContent::Resource(resource) => {
+                self.limit_resource(&mut resource.resource);
+                None
+            }
+            RawContent::ResourceLink(_) => None,
+        };
+        if let Some(stub) = stub {
+            content.raw = RawContent::text(stub);
+        }
+    }
+
+    fn limit_resource(&mut self, contents: &mut ResourceContents) {
+        let stub = match contents {
+            ResourceContents::TextResourceContents { text, .. } => {
+                self.limit_text(text);
+                None
+            }
+            ResourceContents::BlobResourceContents {
+                uri,
+                mime_type,
+                blob,
+                ..
+            } => self
+                .limit_blob(
+                    blob,
+                    "resource",
+                    mime_type.as_deref().unwrap_or("application/octet-stream"),
+                )
+                .map(|stub| (uri.clone(), stub)),
+        };
+        if let Some((uri, stub)) = stub {
+            *contents = ResourceContents::TextResourceContents {
+                uri,
+                mime_type: Some("text/plain".to_string()),
+                text: stub,
+                meta: None,
+            };
+        }
+    }
+
+    fn limit_text(&mut self, text: &mut String) {
+        if text.len() <= self.remaining {
+            self.remaining -= text.len();
+            return;
+        }
+
+        let mut end = self.remaining;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        let dropped = text.len() - end;
+        text.truncate(end);
+        text.push_str(&format!(
+            "\n[truncated {} bytes, full content unavailable]",
+            dropped
+        ));
+        self.truncated += dropped;
+        self.remaining -= end;
+    }
+
+    /// The stub to show in place of binary data that does not fit, if it doesn't
+    fn limit_blob(&mut self, data: &str, kind: &str, mime_type: &str) -> Option<String> {
+        if data.len() <= self.remaining {
+            self.remaining -= data.len();
+            return None;
+        }
+        self.truncated += data.len();
+        Some(format!(
+            "[{} content ({}) of {} bytes omitted: over the {} byte response limit]",
+            kind,
+            mime_type,
+            data.len(),
+            self.limit
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn text_of(content: &Content) -> &str {
+        match &content.raw {
+            RawContent::Text(text) => &text.text,
+            other => panic!("expected text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_text_is_truncated_across_contents() {
+        let limits = ResponseLimits {
+            max_result_bytes: 10,
+            max_content_items: 10,
+        };
+        let result = CallToolResult::success(vec![
+            Content::text("abcdef"),
+            Content::text("ghijklmnop"),
+            Content::text("qrs"),
+        ]);
+        let result = limits.limit_call_tool_result("read", result);
+
+        assert_eq!(text_of(&result.content[0]), "abcdef");
+        assert_eq!(
+            text_of(&result.content[1]),
+            "ghij\n[truncated 6 bytes, full content unavailable]"
+        );
+        assert_eq!(
+            text_of(&result.content[2]),
+            "\n[truncated 3 bytes, full content unavailable]"
+        );
+    }
+
+    #[test]
+    fn test_truncation_respects_char_boundaries() {
+        let limits = ResponseLimits {
+            max_result_bytes: 5,
+            max_content_items: 10,
+        };
+        // Each "é" is two bytes, so the cut lands before the third
+        let result = CallToolResult::success(vec![Content::text("ééé")]);
+        let result = limits.limit_call_tool_result("read", result);
+        assert_eq!(
+            text_of(&result.content[0]),
+            "éé\n[truncated 2 bytes, full content unavailable]"
+        );
+    }
+
+    #[test]
+    fn test_blobs_and_extra_items_are_replaced() {
+        let limits = ResponseLimits {
+            max_result_bytes: 16,
+            max_content_items: 2,
+        };
+        let mut result = CallToolResult::success(vec![
+            Content::image("A".repeat(64), "image/png"),
+            Content::text("ok"),
+            Content::text("dropped"),
+            Content::text("dropped"),
+        ]);
+        result.structured_content = Some(json!({"rows": "x".repeat(64)}));
+        let result = limits.limit_call_tool_result("screenshot", result);
+
+        assert_eq!(result.content.len(), 3);
+        assert_eq!(
+            text_of(&result.content[0]),
+            "[image content (image/png) of 64 bytes omitted: over the 16 byte response limit]"
+        );
+        assert_eq!(text_of(&result.content[1]), "ok");
+        assert_eq!(
+            text_of(&result.content[2]),
+            "[omitted 2 more content items, full content unavailable]"
+        );
+        assert_eq!(result.structured_content, None);
+    }
+
+    #[test]
+    fn test_resources_are_limited() {
+        let limits = ResponseLimits {
+            max_result_bytes: 8,
+            max_content_items: 10,
+        };
+        let result = ReadResourceResult {
+            contents: vec![
+                ResourceContents::BlobResourceContents {
+                    uri: "file:///big.bin".to_string(),
+                    mime_type: None,
+                    blob: "B".repeat(32),
+                    meta: None,
+                },
+                ResourceContents::text("0123456789", "file:///notes.txt"),
+            ],
+        };
+        let result = limits.limit_read_resource_result("file:///", result);
+
+        match &result.contents[0] {
+            ResourceContents::TextResourceContents { uri, text, .. } => {
+                assert_eq!(uri, "file:///big.bin");
+                assert!(text.contains("32 bytes omitted"));
+            }
+            other => panic!("expected a text stub, got {:?}", other),
+        }
+        match &result.contents[1] {
+            ResourceContents::TextResourceContents { text, .. } => assert_eq!(
+                text,
+                "01234567\n[truncated 2 bytes, full content unavailable]"
+            ),
+            other => panic!("expected text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_small_results_are_untouched() {
+        let limits = ResponseLimits::default();
+        let mut result = CallToolResult::success(vec![
+            Content::text("hello"),
+            Content::image("aGVsbG8=", "image/png"),
+        ]);
+        result.structured_content = Some(json!({"greeting": "hello"}));
+        assert_eq!(
+            limits.limit_call_tool_result("greet", result.clone()),
+            result
+        );
+    }
+}